@@ -0,0 +1,312 @@
+//! Static call graph between the subprograms of a unit
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use toc_hir::visitor::{self, HirVisitor};
+use toc_hir::{db, expr, stmt, symbol, ty, unit};
+use toc_span::Span;
+
+/// Calls between the subprograms of a unit, including the unit's main body
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    nodes: Vec<CallNode>,
+    calls: Vec<(NodeId, NodeId)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A caller or callee in a [`CallGraph`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallNode {
+    pub kind: CallNodeKind,
+    pub name: String,
+    /// Where the node is declared, or `None` for synthetic nodes
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallNodeKind {
+    /// Top level statements of the unit
+    MainBody,
+    /// A declared subprogram
+    Subprogram(symbol::DefId),
+    /// Synthetic node for calls that can't be statically resolved,
+    /// i.e. calls through subprogram variables
+    Indirect,
+}
+
+impl CallGraph {
+    /// All of the nodes in the graph, in the order they were discovered
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &CallNode)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (NodeId(idx), node))
+    }
+
+    pub fn node(&self, id: NodeId) -> &CallNode {
+        &self.nodes[id.0]
+    }
+
+    /// All of the calls in the graph, as `(caller, callee)` pairs
+    pub fn calls(&self) -> &[(NodeId, NodeId)] {
+        &self.calls
+    }
+
+    fn add_node(&mut self, node: CallNode) -> NodeId {
+        self.nodes.push(node);
+        NodeId(self.nodes.len() - 1)
+    }
+}
+
+/// Builds the call graph of a unit.
+///
+/// Only the calls that can be statically resolved are included, with calls
+/// through subprogram variables going to a single `<indirect>` node.
+/// Processes are included as subprograms, but starting one with `fork` isn't a call.
+pub fn call_graph(hir_db: &db::HirDb, unit_id: unit::UnitId) -> CallGraph {
+    let unit = hir_db.get_unit(unit_id);
+    let mut builder = GraphBuilder {
+        hir_db,
+        unit,
+        graph: CallGraph::default(),
+        subprograms: HashMap::new(),
+        indirect: None,
+    };
+
+    let main = builder.graph.add_node(CallNode {
+        kind: CallNodeKind::MainBody,
+        name: "<main>".to_string(),
+        span: Some(hir_db.get_span(unit_id.into())),
+    });
+
+    // All subprograms are discovered first, so that calls can refer to any of them
+    builder.collect_subprograms(&unit.stmts);
+    builder.add_calls(main, &unit.stmts);
+    builder.graph
+}
+
+struct GraphBuilder<'a> {
+    hir_db: &'a db::HirDb,
+    unit: &'a unit::Unit,
+    graph: CallGraph,
+    /// Declared subprograms, along with variables and parameters holding a subprogram
+    subprograms: HashMap<symbol::DefId, SubprogramRef>,
+    indirect: Option<NodeId>,
+}
+
+#[derive(Clone, Copy)]
+struct SubprogramRef {
+    /// Node of the declared subprogram, or `None` if it's called indirectly
+    node: Option<NodeId>,
+    /// If naming the subprogram calls it, which is the case for functions without
+    /// a parameter list
+    is_implicit_call: bool,
+}
+
+impl GraphBuilder<'_> {
+    fn collect_subprograms(&mut self, stmts: &[stmt::StmtId]) {
+        for stmt_id in stmts {
+            let (def_id, is_implicit_call, params, body) = match self.hir_db.get_stmt(*stmt_id) {
+                stmt::Stmt::Subprogram(decl) => (
+                    decl.def_id,
+                    decl.kind == ty::SubprogramKind::Function && decl.params.is_none(),
+                    decl.params.as_deref().unwrap_or_default(),
+                    &decl.stmts,
+                ),
+                stmt::Stmt::Process(decl) => {
+                    (decl.def_id, false, decl.params.as_slice(), &decl.stmts)
+                }
+                stmt::Stmt::ConstVar(decl) => {
+                    if let Some(ty) = decl.tail.type_spec() {
+                        self.collect_subprogram_vars(&decl.names, ty);
+                    }
+                    continue;
+                }
+                stmt::Stmt::Block(block) => {
+                    self.collect_subprograms(&block.stmts);
+                    continue;
+                }
+                stmt::Stmt::Module(module) => {
+                    self.collect_subprograms(&module.stmts);
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let node = self.graph.add_node(CallNode {
+                kind: CallNodeKind::Subprogram(def_id),
+                name: self.unit.symbol_table.get_symbol(def_id).name.clone(),
+                span: Some(self.unit.symbol_table.get_def_span(def_id)),
+            });
+            let subprogram = SubprogramRef {
+                node: Some(node),
+                is_implicit_call,
+            };
+            self.subprograms.insert(def_id, subprogram);
+
+            // Calls made before the body refer to the forward declaration
+            if let symbol::SymbolKind::Resolved(forward_def) =
+                self.unit.symbol_table.get_symbol(def_id).kind
+            {
+                self.subprograms.insert(forward_def, subprogram);
+            }
+
+            for param in params {
+                self.collect_subprogram_vars(&param.names, param.param_ty);
+            }
+
+            self.collect_subprograms(body);
+        }
+    }
+
+    /// Records `names` as being called indirectly, if they're declared with a subprogram type
+    fn collect_subprogram_vars(&mut self, names: &[symbol::DefId], ty: ty::TypeId) {
+        if let ty::Type::Subprogram(ty) = self.hir_db.get_type(ty) {
+            let is_implicit_call = ty.kind == ty::SubprogramKind::Function && ty.params.is_none();
+
+            self.subprograms.extend(names.iter().map(|def_id| {
+                (
+                    *def_id,
+                    SubprogramRef {
+                        node: None,
+                        is_implicit_call,
+                    },
+                )
+            }));
+        }
+    }
+
+    /// Adds the calls made by `caller` in `stmts`.
+    /// Calls in nested subprogram bodies belong to the nested subprogram instead.
+    fn add_calls(&mut self, caller: NodeId, stmts: &[stmt::StmtId]) {
+        for stmt_id in stmts {
+            match self.hir_db.get_stmt(*stmt_id) {
+                stmt::Stmt::Subprogram(stmt::Subprogram { def_id, stmts, .. })
+                | stmt::Stmt::Process(stmt::Process { def_id, stmts, .. }) => {
+                    if let Some(node) = self.subprograms[def_id].node {
+                        self.add_calls(node, stmts);
+                    }
+                }
+                stmt::Stmt::Block(block) => self.add_calls(caller, &block.stmts),
+                // Module bodies are run in place, so they're part of the caller
+                stmt::Stmt::Module(module) => self.add_calls(caller, &module.stmts),
+                _ => {
+                    let collector = CallCollector::default();
+                    visitor::walk_stmt(self.hir_db.clone(), *stmt_id, &collector);
+
+                    for call in collector.calls.into_inner() {
+                        if let Some(callee) = self.resolve_call(call) {
+                            self.add_call(caller, callee);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds the node of the subprogram called by `call`, if it is a call.
+    ///
+    /// Call expressions are also used for subscripts, so they're only counted as calls
+    /// if they're known to call a subprogram. Call statements always call something.
+    fn resolve_call(&mut self, call: Call) -> Option<NodeId> {
+        let subprogram = match call {
+            Call::Name(def_id) => self
+                .subprograms
+                .get(&def_id)
+                .filter(|subprogram| subprogram.is_implicit_call)
+                .copied()?,
+            Call::Explicit {
+                callee,
+                is_call_stmt,
+            } => {
+                let def_id = match self.hir_db.get_expr(callee) {
+                    expr::Expr::Name(expr::Name::Name(use_id)) => Some(use_id.as_def()),
+                    _ => None,
+                };
+
+                match def_id.and_then(|def_id| self.subprograms.get(&def_id)) {
+                    Some(subprogram) => *subprogram,
+                    None if is_call_stmt => SubprogramRef {
+                        node: None,
+                        is_implicit_call: false,
+                    },
+                    None => return None,
+                }
+            }
+        };
+
+        Some(match subprogram.node {
+            Some(node) => node,
+            None => self.indirect_node(),
+        })
+    }
+
+    fn indirect_node(&mut self) -> NodeId {
+        let graph = &mut self.graph;
+
+        *self.indirect.get_or_insert_with(|| {
+            graph.add_node(CallNode {
+                kind: CallNodeKind::Indirect,
+                name: "<indirect>".to_string(),
+                span: None,
+            })
+        })
+    }
+
+    fn add_call(&mut self, caller: NodeId, callee: NodeId) {
+        // Repeated calls are only recorded once
+        if !self.graph.calls.contains(&(caller, callee)) {
+            self.graph.calls.push((caller, callee));
+        }
+    }
+}
+
+/// Something in a statement which might call a subprogram
+enum Call {
+    /// Name which may implicitly call a subprogram
+    Name(symbol::DefId),
+    /// Call expression or statement
+    Explicit {
+        callee: expr::ExprId,
+        is_call_stmt: bool,
+    },
+}
+
+/// Collects the possible calls in a statement, in the order that they're walked
+#[derive(Default)]
+struct CallCollector {
+    calls: RefCell<Vec<Call>>,
+}
+
+impl HirVisitor for CallCollector {
+    fn visit_name(&self, _id: expr::ExprId, expr: &expr::Name) {
+        if let expr::Name::Name(use_id) = expr {
+            self.calls.borrow_mut().push(Call::Name(use_id.as_def()));
+        }
+    }
+
+    fn visit_call_stmt(&self, _id: stmt::StmtId, stmt: &stmt::Call) {
+        self.calls.borrow_mut().push(Call::Explicit {
+            callee: stmt.callee,
+            is_call_stmt: true,
+        });
+    }
+
+    fn visit_call(&self, _id: expr::ExprId, expr: &expr::Call) {
+        self.calls.borrow_mut().push(Call::Explicit {
+            callee: expr.lhs,
+            is_call_stmt: false,
+        });
+    }
+}
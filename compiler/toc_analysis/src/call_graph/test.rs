@@ -0,0 +1,144 @@
+//! Call graph tests
+use toc_hir::db;
+
+use super::{CallGraph, CallNodeKind};
+
+fn calls(graph: &CallGraph) -> Vec<(&str, &str)> {
+    graph
+        .calls()
+        .iter()
+        .map(|(caller, callee)| {
+            (
+                graph.node(*caller).name.as_str(),
+                graph.node(*callee).name.as_str(),
+            )
+        })
+        .collect()
+}
+
+fn build_graph(source: &str) -> CallGraph {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+    let hir_db = hir_db.finish();
+
+    super::call_graph(&hir_db, hir_res.id)
+}
+
+#[test]
+fn main_body_calls_procs() {
+    let graph = build_graph(
+        "
+        procedure a
+        end a
+        procedure b
+            a
+        end b
+        a
+        b
+        ",
+    );
+
+    let nodes = graph
+        .nodes()
+        .map(|(_, node)| node.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(nodes, vec!["<main>", "a", "b"]);
+    assert!(matches!(
+        graph.nodes().nth(1).unwrap().1.kind,
+        CallNodeKind::Subprogram(_)
+    ));
+
+    assert_eq!(
+        calls(&graph),
+        vec![("b", "a"), ("<main>", "a"), ("<main>", "b")]
+    );
+}
+
+#[test]
+fn nested_calls_belong_to_caller() {
+    let graph = build_graph(
+        "
+        function f : int
+        end f
+        procedure p (x : int)
+            begin
+                var a := f + f
+            end
+        end p
+        p(f)
+        p(1)
+        ",
+    );
+
+    // `f` is called just by naming it, since it doesn't have a parameter list
+    assert_eq!(
+        calls(&graph),
+        vec![("p", "f"), ("<main>", "f"), ("<main>", "p")]
+    );
+}
+
+#[test]
+fn recursive_call() {
+    let graph = build_graph("procedure a a end a");
+
+    assert_eq!(calls(&graph), vec![("a", "a")]);
+}
+
+#[test]
+fn calls_through_forward() {
+    let graph = build_graph(
+        "
+        forward procedure b
+        procedure a
+            b
+        end a
+        body procedure b
+            a
+        end b
+        b
+        ",
+    );
+
+    assert_eq!(calls(&graph), vec![("a", "b"), ("b", "a"), ("<main>", "b")]);
+}
+
+#[test]
+fn indirect_calls() {
+    let graph = build_graph(
+        "
+        var arr : array 1 .. 2 of int
+        var pv : procedure
+        procedure p (function f : int)
+            var a := f + arr(1)
+        end p
+        pv
+        ",
+    );
+
+    let nodes = graph
+        .nodes()
+        .map(|(_, node)| (node.name.as_str(), node.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(nodes.last(), Some(&("<indirect>", CallNodeKind::Indirect)));
+
+    // Subscripts aren't calls
+    assert_eq!(
+        calls(&graph),
+        vec![("p", "<indirect>"), ("<main>", "<indirect>")]
+    );
+}
+
+#[test]
+fn process_calls() {
+    let graph = build_graph(
+        "
+        procedure a end a
+        process p a end p
+        fork p
+        ",
+    );
+
+    // Forks aren't calls
+    assert_eq!(calls(&graph), vec![("p", "a")]);
+}
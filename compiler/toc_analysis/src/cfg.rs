@@ -0,0 +1,306 @@
+//! Control flow graph over the statements of a unit
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+
+use indexmap::IndexSet;
+use toc_hir::{db, expr, stmt, symbol, visitor::HirVisitor};
+
+/// Flow of control between statements.
+///
+/// Blocks are flattened out, with each block statement flowing into the first
+/// statement inside of it, and the last statement inside flowing out of the block.
+/// Statements with branches (`if` and `case`) flow into the first statement of
+/// each branch, and `loop` and `for` statements are the head of their loop, with
+/// the end of the body flowing back into the loop statement.
+#[derive(Debug)]
+pub(crate) struct Cfg {
+    /// Statements, in the order that they appear in the source
+    nodes: IndexSet<stmt::StmtId>,
+    /// Successors of each node, with `None` being the end of the unit or subprogram
+    succs: Vec<Vec<Option<NodeId>>>,
+    /// If each node can be reached from the start of the unit
+    reachable: Vec<bool>,
+    /// Nodes which flow past the end of the statement list
+    fallthrough: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct NodeId(usize);
+
+impl NodeId {
+    pub(crate) fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl Cfg {
+    /// Builds the graph for the top level statements of a unit, or the body of a subprogram
+    pub(crate) fn build(hir_db: &db::HirDb, stmts: &[stmt::StmtId]) -> Self {
+        let mut builder = CfgBuilder {
+            hir_db,
+            cfg: Cfg {
+                nodes: IndexSet::new(),
+                succs: vec![],
+                reachable: vec![],
+                fallthrough: vec![],
+            },
+            pending: vec![],
+            loop_exits: vec![],
+        };
+
+        builder.add_stmt_list(stmts);
+
+        let CfgBuilder {
+            mut cfg, pending, ..
+        } = builder;
+
+        for node in &pending {
+            cfg.succs[node.0].push(None);
+        }
+
+        cfg.fallthrough = pending;
+        cfg.reachable = cfg.find_reachable();
+        cfg
+    }
+
+    /// All of the nodes in the graph, in source order
+    pub(crate) fn nodes(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (NodeId, stmt::StmtId)> + ExactSizeIterator + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, stmt_id)| (NodeId(idx), *stmt_id))
+    }
+
+    /// Nodes that control can go to after `node`, where `None` is the end of the unit
+    /// or subprogram
+    pub(crate) fn successors(&self, node: NodeId) -> &[Option<NodeId>] {
+        &self.succs[node.0]
+    }
+
+    /// Checks if `stmt_id` can ever be run.
+    ///
+    /// Statements inside of unreachable blocks are also unreachable.
+    pub(crate) fn is_reachable(&self, stmt_id: stmt::StmtId) -> bool {
+        self.nodes
+            .get_index_of(&stmt_id)
+            .is_some_and(|idx| self.reachable[idx])
+    }
+
+    pub(crate) fn is_node_reachable(&self, node: NodeId) -> bool {
+        self.reachable[node.0]
+    }
+
+    /// Checks if control can run past the end of the statement list,
+    /// instead of leaving through a `return` or `result` statement
+    pub(crate) fn can_fall_through(&self) -> bool {
+        self.nodes.is_empty()
+            || self
+                .fallthrough
+                .iter()
+                .any(|node| self.is_node_reachable(*node))
+    }
+
+    fn find_reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.nodes.len()];
+        let mut worklist = vec![];
+
+        if !self.nodes.is_empty() {
+            worklist.push(NodeId(0));
+        }
+
+        while let Some(node) = worklist.pop() {
+            if std::mem::replace(&mut reachable[node.0], true) {
+                continue;
+            }
+
+            worklist.extend(self.succs[node.0].iter().flatten());
+        }
+
+        reachable
+    }
+}
+
+struct CfgBuilder<'a> {
+    hir_db: &'a db::HirDb,
+    cfg: Cfg,
+    /// Nodes which flow into the next node to be added
+    pending: Vec<NodeId>,
+    /// `exit` statements leaving each of the enclosing loops, innermost last
+    loop_exits: Vec<Vec<NodeId>>,
+}
+
+impl CfgBuilder<'_> {
+    fn add_stmt_list(&mut self, stmts: &[stmt::StmtId]) {
+        for stmt_id in stmts {
+            self.add_stmt(*stmt_id);
+        }
+    }
+
+    fn add_stmt(&mut self, stmt_id: stmt::StmtId) {
+        let (idx, _) = self.cfg.nodes.insert_full(stmt_id);
+        let node = NodeId(idx);
+        self.cfg.succs.push(vec![]);
+
+        for pred in self.pending.drain(..) {
+            self.cfg.succs[pred.0].push(Some(node));
+        }
+        self.pending.push(node);
+
+        match self.hir_db.get_stmt(stmt_id) {
+            stmt::Stmt::Block(block) => self.add_stmt_list(&block.stmts),
+            // Module bodies are run in place
+            stmt::Stmt::Module(module) => self.add_stmt_list(&module.stmts),
+            stmt::Stmt::Loop(stmt) => {
+                // Only left through `exit` statements
+                self.pending = self.add_loop_body(node, &stmt.stmts);
+            }
+            stmt::Stmt::For(stmt) => {
+                // Also left once the counter goes past the bounds
+                self.pending = self.add_loop_body(node, &stmt.stmts);
+                self.pending.push(node);
+            }
+            stmt::Stmt::Exit(stmt) => {
+                if let Some(exits) = self.loop_exits.last_mut() {
+                    exits.push(node);
+
+                    // Conditional exits can also keep going around the loop
+                    if stmt.when_condition.is_none() {
+                        self.pending.clear();
+                    }
+                }
+            }
+            stmt::Stmt::If(stmt) => {
+                self.add_stmt_list(&stmt.true_branch);
+                let mut after = std::mem::replace(&mut self.pending, vec![node]);
+
+                match &stmt.false_branch {
+                    stmt::FalseBranch::None => {}
+                    stmt::FalseBranch::ElseIf(elseif) => self.add_stmt(*elseif),
+                    stmt::FalseBranch::Else(stmts) => self.add_stmt_list(stmts),
+                }
+
+                after.append(&mut self.pending);
+                self.pending = after;
+            }
+            stmt::Stmt::Case(stmt) => {
+                let mut after = vec![];
+
+                for arm in &stmt.arms {
+                    self.pending = vec![node];
+                    self.add_stmt_list(&arm.stmts);
+                    after.append(&mut self.pending);
+                }
+
+                // Without a default arm, values not matching any arm skip the whole statement
+                if stmt.arms.iter().all(|arm| arm.selectors.is_some()) {
+                    after.push(node);
+                }
+
+                self.pending = after;
+            }
+            // Goes straight to the end of the subprogram
+            stmt::Stmt::Return(_) | stmt::Stmt::Result(_) => {
+                self.pending.clear();
+                self.cfg.succs[node.0].push(None);
+            }
+            // Never completes, so nothing comes after it
+            stmt::Stmt::Quit(_) => self.pending.clear(),
+            // Subprogram and process bodies are only run once called or forked,
+            // separately from the unit's flow
+            stmt::Stmt::Subprogram(_) | stmt::Stmt::Process(_) => {}
+            _ => {}
+        }
+    }
+
+    /// Adds the body of a loop headed by `head`, giving back the `exit` statements leaving it
+    fn add_loop_body(&mut self, head: NodeId, stmts: &[stmt::StmtId]) -> Vec<NodeId> {
+        self.loop_exits.push(vec![]);
+        self.add_stmt_list(stmts);
+
+        // Back to the start of the loop
+        for pred in self.pending.drain(..) {
+            self.cfg.succs[pred.0].push(Some(head));
+        }
+
+        self.loop_exits.pop().unwrap_or_default()
+    }
+}
+
+/// Walks over the parts of a statement which are run as part of its own node.
+///
+/// Statements nested inside of blocks, loops, and branches are left out, since they are
+/// separate nodes.
+pub(crate) fn walk_node(hir_db: &db::HirDb, stmt_id: stmt::StmtId, visitor: &dyn HirVisitor) {
+    let walk_expr = |expr_id| toc_hir::visitor::walk_expr(hir_db.clone(), expr_id, visitor);
+
+    match hir_db.get_stmt(stmt_id) {
+        stmt::Stmt::Block(_) | stmt::Stmt::Module(_) | stmt::Stmt::Loop(_) => {}
+        stmt::Stmt::For(stmt) => {
+            match stmt.bounds {
+                stmt::ForBounds::Implicit(ty) => {
+                    toc_hir::visitor::walk_type(hir_db.clone(), ty, visitor)
+                }
+                stmt::ForBounds::Full(start, end) => {
+                    walk_expr(start);
+                    walk_expr(end);
+                }
+            }
+
+            if let Some(step_by) = stmt.step_by {
+                walk_expr(step_by);
+            }
+        }
+        stmt::Stmt::If(stmt) => walk_expr(stmt.condition),
+        stmt::Stmt::Case(stmt) => {
+            walk_expr(stmt.discriminant);
+
+            for arm in &stmt.arms {
+                for selector in arm.selectors.iter().flatten() {
+                    walk_expr(*selector);
+                }
+            }
+        }
+        _ => toc_hir::visitor::walk_stmt(hir_db.clone(), stmt_id, visitor),
+    }
+}
+
+/// Gets the name expressions in the parts of a statement which are run as part of its
+/// own node, in the order that they are walked.
+pub(crate) fn names_in_node(
+    hir_db: &db::HirDb,
+    stmt_id: stmt::StmtId,
+) -> Vec<(expr::ExprId, symbol::DefId)> {
+    let names = NameCollector::default();
+    walk_node(hir_db, stmt_id, &names);
+    names.names.into_inner()
+}
+
+/// Gets all of the name expressions in a statement, in the order that they are walked.
+///
+/// Nested statements are also walked, so block statements give back the names of every
+/// statement inside of them.
+pub(crate) fn names_in_stmt(
+    hir_db: &db::HirDb,
+    stmt_id: stmt::StmtId,
+) -> Vec<(expr::ExprId, symbol::DefId)> {
+    let names = NameCollector::default();
+    toc_hir::visitor::walk_stmt(hir_db.clone(), stmt_id, &names);
+    names.names.into_inner()
+}
+
+#[derive(Default)]
+struct NameCollector {
+    names: RefCell<Vec<(expr::ExprId, symbol::DefId)>>,
+}
+
+impl HirVisitor for NameCollector {
+    fn visit_name(&self, id: expr::ExprId, expr: &expr::Name) {
+        if let expr::Name::Name(use_id) = expr {
+            self.names.borrow_mut().push((id, use_id.as_def()));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Control flow graph tests
+use toc_hir::db;
+
+use super::Cfg;
+
+/// Successors of each statement, by index, with `end` being the end of the unit
+fn successors(source: &str) -> Vec<String> {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+    let hir_db = hir_db.finish();
+
+    let unit = hir_db.get_unit(hir_res.id);
+    let cfg = Cfg::build(&hir_db, &unit.stmts);
+
+    cfg.nodes()
+        .map(|(node, _)| {
+            let succs = cfg
+                .successors(node)
+                .iter()
+                .map(|succ| match succ {
+                    Some(succ) => succ.index().to_string(),
+                    None => "end".to_string(),
+                })
+                .collect::<Vec<_>>();
+
+            format!("{} -> [{}]", node.index(), succs.join(", "))
+        })
+        .collect()
+}
+
+#[test]
+fn straight_line() {
+    assert_eq!(successors("put 1\nput 2"), vec!["0 -> [1]", "1 -> [end]"]);
+    assert!(successors("").is_empty());
+}
+
+#[test]
+fn blocks_flattened() {
+    assert_eq!(
+        successors("begin put 1 end\nput 2"),
+        vec!["0 -> [1]", "1 -> [2]", "2 -> [end]"]
+    );
+    // Empty blocks flow straight through
+    assert_eq!(
+        successors("begin end\nput 2"),
+        vec!["0 -> [1]", "1 -> [end]"]
+    );
+}
+
+#[test]
+fn module_bodies_flattened() {
+    assert_eq!(
+        successors("module m put 1 end m\nput 2"),
+        vec!["0 -> [1]", "1 -> [2]", "2 -> [end]"]
+    );
+}
+
+#[test]
+fn quit_goes_nowhere() {
+    assert_eq!(successors("quit\nput 1"), vec!["0 -> []", "1 -> [end]"]);
+    assert_eq!(
+        successors("begin quit end\nput 1"),
+        vec!["0 -> [1]", "1 -> []", "2 -> [end]"]
+    );
+}
+
+#[test]
+fn process_bodies_not_flattened() {
+    assert_eq!(
+        successors("process p put 1 end p\nput 2"),
+        vec!["0 -> [1]", "1 -> [end]"]
+    );
+}
+
+#[test]
+fn loop_flows_back() {
+    assert_eq!(
+        successors("loop put 1 end loop\nput 2"),
+        vec!["0 -> [1]", "1 -> [0]", "2 -> [end]"]
+    );
+    // Only left through an `exit`
+    assert_eq!(
+        successors("loop exit end loop\nput 2"),
+        vec!["0 -> [1]", "1 -> [2]", "2 -> [end]"]
+    );
+    assert_eq!(
+        successors("loop exit when true\nput 1 end loop\nput 2"),
+        vec!["0 -> [1]", "1 -> [2, 3]", "2 -> [0]", "3 -> [end]"]
+    );
+}
+
+#[test]
+fn for_flows_back() {
+    // Also left once the counter goes past the bounds
+    assert_eq!(
+        successors("for i : 1 .. 2 put 1 end for\nput 2"),
+        vec!["0 -> [1, 2]", "1 -> [0]", "2 -> [end]"]
+    );
+    assert_eq!(
+        successors("for i : 1 .. 2 exit end for\nput 2"),
+        vec!["0 -> [1, 2]", "1 -> [2]", "2 -> [end]"]
+    );
+}
+
+#[test]
+fn if_flows_into_branches() {
+    assert_eq!(
+        successors("if true then put 1 else put 2 end if\nput 3"),
+        vec!["0 -> [1, 2]", "1 -> [3]", "2 -> [3]", "3 -> [end]"]
+    );
+    // Without an else branch, a false condition skips the statement
+    assert_eq!(
+        successors("if true then put 1 end if\nput 3"),
+        vec!["0 -> [1, 2]", "1 -> [2]", "2 -> [end]"]
+    );
+    assert_eq!(
+        successors("if true then put 1 elsif false then put 2 end if"),
+        vec!["0 -> [1, 2]", "1 -> [end]", "2 -> [3, end]", "3 -> [end]"]
+    );
+}
+
+#[test]
+fn case_flows_into_arms() {
+    assert_eq!(
+        successors("case 1 of label 1 : put 1 label : put 2 end case"),
+        vec!["0 -> [1, 2]", "1 -> [end]", "2 -> [end]"]
+    );
+    // Without a default arm, unmatched values skip the statement
+    assert_eq!(
+        successors("case 1 of label 1 : put 1 end case"),
+        vec!["0 -> [1, end]", "1 -> [end]"]
+    );
+}
+
+#[test]
+fn return_goes_to_end() {
+    assert_eq!(
+        successors("return\nput 1"),
+        vec!["0 -> [end]", "1 -> [end]"]
+    );
+}
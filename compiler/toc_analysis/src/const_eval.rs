@@ -1,5 +1,6 @@
 //! Compile-time constant evaluation
 mod errors;
+mod fold;
 mod integer;
 mod ops;
 #[cfg(test)]
@@ -16,6 +17,7 @@ use indexmap::IndexMap;
 use toc_hir::{db, expr, symbol::GlobalDefId, unit};
 
 pub use errors::ConstError;
+pub use fold::fold_constants;
 pub use integer::ConstInt;
 pub use value::ConstValue;
 
@@ -326,9 +328,8 @@ impl InnerCtx {
                         }
                         expr::Literal::Real(v) => ConstValue::Real(*v),
                         expr::Literal::Boolean(v) => ConstValue::Bool(*v),
-                        expr::Literal::Char(_)
-                        | expr::Literal::CharSeq(_)
-                        | expr::Literal::String(_) => {
+                        expr::Literal::Char(v) => ConstValue::Char(*v),
+                        expr::Literal::CharSeq(_) | expr::Literal::String(_) => {
                             // Unsupported const value
                             return Err(ConstError::new(ErrorKind::UnsupportedValue, expr_span));
                         }
@@ -357,11 +358,9 @@ impl InnerCtx {
                     // May or may not reference a constant expression
                     match name {
                         expr::Name::Name(use_id) => {
-                            // Lookup var
-                            // ???: How to lookup identifiers imported from different units
-                            // - `Unit` should have an import table mapping local `UseId`s to
-                            //   `GlobalDefId`'s
-                            let global_def = use_id.as_def().into_global(unit_id);
+                            // Lookup var, looking through any imports
+                            let global_def =
+                                self.hir_db.get_unit(unit_id).resolve_def(use_id.as_def());
 
                             // Eval var
                             let value = self.eval_var(global_def).map_err(|err| {
@@ -387,6 +386,70 @@ impl InnerCtx {
                         }
                     }
                 }
+                expr::Expr::Init(_) => {
+                    // Aggregates don't have compile-time values yet
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::Builtin(_) => {
+                    // TODO: Evaluate builtins which only depend on types and constants
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::Nil(_) => {
+                    // Pointers don't have compile-time values
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::Field(_) => {
+                    // Records and unions don't have compile-time values yet
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::Deref(_) => {
+                    // Pointers only refer to values at runtime
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::NatCheat(_) => {
+                    // Reinterpreting values depends on their in-memory representation
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::Indirect(_) | expr::Expr::Bits(_) => {
+                    // Memory contents are only known at runtime
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::Call(_) => {
+                    // Arrays don't have compile-time values yet, and calls are
+                    // only done at runtime
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
+                expr::Expr::Substring(_) => {
+                    // Strings don't have compile-time values yet
+                    return Err(ConstError::new(
+                        ErrorKind::NoConstExpr(Default::default()),
+                        expr_span,
+                    ));
+                }
             }
         }
 
@@ -39,6 +39,24 @@ impl ConstError {
         &self.kind
     }
 
+    pub(crate) fn span(&self) -> Span {
+        self.span
+    }
+
+    /// If this error comes from performing an operation on compile-time values,
+    /// rather than from the operands not being evaluable at compile-time
+    pub(crate) fn is_computation_error(&self) -> bool {
+        matches!(
+            self.kind,
+            ErrorKind::IntOverflow
+                | ErrorKind::RealOverflow
+                | ErrorKind::DivByZero
+                | ErrorKind::RealDivByZero
+                | ErrorKind::NegativeIntExp
+                | ErrorKind::NegativeIntShift
+        )
+    }
+
     /// Reports the detailed version of the `ConstError` to the given reporter
     pub fn report_to(&self, reporter: &mut toc_reporting::MessageSink) {
         // Ignore already reported messages, or for missing expressions
@@ -47,7 +65,12 @@ impl ConstError {
         }
 
         // Report common message header
-        let msg = reporter.error_detailed(&format!("{}", self.kind), self.span);
+        let msg = if let ErrorKind::RealDivByZero = self.kind {
+            // Not fatal, since it still produces a value at runtime
+            reporter.warn_detailed(&format!("{}", self.kind), self.span)
+        } else {
+            reporter.error_detailed(&format!("{}", self.kind), self.span)
+        };
 
         // Report extra details
         match &self.kind {
@@ -68,6 +91,20 @@ impl ConstError {
                     self.span,
                 )
             }
+            ErrorKind::IntOverflow => {
+                // TODO: Use 64-bit bounds once 64-bit types are lowered
+                msg.with_info(
+                    &format!(
+                        "compile-time integers must be between {} and {}",
+                        i32::MIN,
+                        u32::MAX
+                    ),
+                    None,
+                )
+            }
+            ErrorKind::RealDivByZero => {
+                msg.with_info("this produces an infinite value at runtime", None)
+            }
             _ => msg,
         }
         .finish();
@@ -107,6 +144,9 @@ pub(super) enum ErrorKind {
     /// Division by zero
     #[error("division by zero in compile-time expression")]
     DivByZero,
+    /// Real division (`/`) by zero
+    #[error("division by zero in compile-time expression")]
+    RealDivByZero,
     /// Negative int exponent provided during power raising
     #[error("raising integer to a negative exponent")]
     NegativeIntExp,
@@ -0,0 +1,229 @@
+//! Folding of constant expressions into literals
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use toc_hir::{db, expr, stmt, ty, unit, visitor::HirVisitor};
+use toc_reporting::{MessageSink, ReportMessage, Stage};
+
+use crate::const_eval::{ConstError, ConstEvalCtx, ConstValue, RestrictType};
+
+/// Folds the constant operations in the unit into literals, keeping the spans
+/// of the original expressions.
+///
+/// Operations which overflow, or whose result doesn't fit in the declared type,
+/// are left unfolded and warned about. Results without a literal form
+/// (e.g. negative numbers) are also left unfolded, though their operands may still be folded.
+///
+/// ## Panics
+/// Panics if there are any other references to `hir_db`
+pub fn fold_constants(hir_db: &mut db::HirDb, unit_id: unit::UnitId) -> Vec<ReportMessage> {
+    let (folds, messages) = {
+        let const_eval = Arc::new(ConstEvalCtx::new(hir_db.clone()));
+
+        // Constants can come from any unit
+        for (_, node) in hir_db.nodes() {
+            if let db::HirNode::Unit(unit) = node {
+                super::collect_const_vars(hir_db.clone(), unit, const_eval.clone());
+            }
+        }
+
+        let visitor = FoldableVisitor {
+            foldable: Default::default(),
+            operands: Default::default(),
+            targets: Default::default(),
+        };
+        hir_db
+            .get_unit(unit_id)
+            .walk_nodes(hir_db.clone(), &visitor);
+
+        let mut folder = Folder {
+            hir_db: hir_db.clone(),
+            unit_id,
+            const_eval,
+            targets: visitor.targets.into_inner(),
+            folds: vec![],
+            reporter: MessageSink::new(Stage::Analyze),
+        };
+
+        // Only start from the outermost operations, so that nested operations are
+        // folded along with them
+        let operands = visitor.operands.into_inner();
+
+        for id in visitor.foldable.into_inner() {
+            if !operands.contains(&id) {
+                folder.fold_expr(id, true);
+            }
+        }
+
+        (folder.folds, folder.reporter.finish())
+    };
+
+    for (id, literal) in folds {
+        hir_db.replace_expr(id, expr::Expr::Literal(literal));
+    }
+
+    messages
+}
+
+/// Collects the operations that could be folded
+struct FoldableVisitor {
+    /// All operations, in postfix order
+    foldable: RefCell<Vec<expr::ExprId>>,
+    /// Operations which are operands of other operations
+    operands: RefCell<HashSet<expr::ExprId>>,
+    /// Declared types of initializer expressions
+    targets: RefCell<HashMap<expr::ExprId, ty::TypeId>>,
+}
+
+impl FoldableVisitor {
+    fn add_foldable(&self, id: expr::ExprId, operands: &[expr::ExprId]) {
+        self.foldable.borrow_mut().push(id);
+        self.operands.borrow_mut().extend(operands);
+    }
+}
+
+impl HirVisitor for FoldableVisitor {
+    fn visit_constvar(&self, _id: stmt::StmtId, decl: &stmt::ConstVar) {
+        if let (Some(ty), Some(init_expr)) = (decl.tail.type_spec(), decl.tail.init_expr()) {
+            self.targets.borrow_mut().insert(init_expr, ty);
+        }
+    }
+
+    fn visit_binary(&self, id: expr::ExprId, expr: &expr::Binary) {
+        self.add_foldable(id, &[expr.lhs, expr.rhs]);
+    }
+
+    fn visit_unary(&self, id: expr::ExprId, expr: &expr::Unary) {
+        self.add_foldable(id, &[expr.rhs]);
+    }
+
+    fn visit_paren(&self, id: expr::ExprId, expr: &expr::Paren) {
+        self.add_foldable(id, &[expr.expr]);
+    }
+}
+
+struct Folder {
+    hir_db: db::HirDb,
+    unit_id: unit::UnitId,
+    const_eval: Arc<ConstEvalCtx>,
+    targets: HashMap<expr::ExprId, ty::TypeId>,
+    /// Folded expressions, with the literals to replace them with
+    folds: Vec<(expr::ExprId, expr::Literal)>,
+    reporter: MessageSink,
+}
+
+impl Folder {
+    /// Tries to fold `id`, falling back to folding its operands
+    fn fold_expr(&mut self, id: expr::ExprId, is_root: bool) {
+        let (operands, op_span) = match self.hir_db.get_expr(id) {
+            expr::Expr::Binary(expr) => (vec![expr.lhs, expr.rhs], Some(expr.op.span())),
+            expr::Expr::Unary(expr) => (vec![expr.rhs], Some(expr.op.span())),
+            expr::Expr::Paren(expr) => (vec![expr.expr], None),
+            // Nothing to fold
+            _ => return,
+        };
+
+        // TODO: Allow 64-bit ops once 64-bit types are lowered
+        let const_expr = self
+            .const_eval
+            .defer_expr(self.unit_id, id, false, RestrictType::None);
+
+        match self.const_eval.eval_expr(const_expr) {
+            Ok(value) => {
+                let target = if is_root { self.targets.get(&id) } else { None };
+
+                if let Some(target) = target {
+                    if !fits_in(&value, self.hir_db.get_type(*target)) {
+                        self.report_too_large(id, &value, *target);
+                        return;
+                    }
+                }
+
+                if let Some(literal) = into_literal(value) {
+                    self.folds.push((id, literal));
+                    return;
+                }
+            }
+            Err(err) => {
+                // Only report errors at the operation they came from
+                if err.is_computation_error() && Some(err.span()) == op_span {
+                    self.report_not_folded(id, &err);
+                }
+            }
+        }
+
+        // Try to fold the operands instead
+        for operand in operands {
+            self.fold_expr(operand, false);
+        }
+    }
+
+    fn report_not_folded(&mut self, id: expr::ExprId, err: &ConstError) {
+        let span = self.hir_db.get_span(id.into());
+
+        self.reporter
+            .warn_detailed("constant expression is not folded", span)
+            .with_note(&format!("{}", err.kind()), err.span())
+            .finish();
+    }
+
+    fn report_too_large(&mut self, id: expr::ExprId, value: &ConstValue, target: ty::TypeId) {
+        let span = self.hir_db.get_span(id.into());
+        let target_span = self.hir_db.get_span(target.into());
+        let value = match value {
+            ConstValue::Integer(v) => v.into_i128(),
+            _ => unreachable!("only integers can be too large"),
+        };
+
+        self.reporter
+            .warn_detailed("constant expression is not folded", span)
+            .with_note(
+                &format!("`{}` does not fit in the declared type", value),
+                target_span,
+            )
+            .finish();
+    }
+}
+
+/// Checks if an integer `value` fits in the `target` type.
+/// Other values are always considered to fit.
+fn fits_in(value: &ConstValue, target: &ty::Type) -> bool {
+    let value = match value {
+        ConstValue::Integer(v) => v.into_i128(),
+        _ => return true,
+    };
+
+    let (min, max): (i128, i128) = match target {
+        ty::Type::Primitive(prim) => match prim {
+            ty::Primitive::Int1 => (i8::MIN.into(), i8::MAX.into()),
+            ty::Primitive::Int2 => (i16::MIN.into(), i16::MAX.into()),
+            ty::Primitive::Int | ty::Primitive::Int4 => (i32::MIN.into(), i32::MAX.into()),
+            ty::Primitive::Nat1 => (u8::MIN.into(), u8::MAX.into()),
+            ty::Primitive::Nat2 => (u16::MIN.into(), u16::MAX.into()),
+            ty::Primitive::Nat | ty::Primitive::Nat4 | ty::Primitive::AddressInt => {
+                (u32::MIN.into(), u32::MAX.into())
+            }
+            _ => return true,
+        },
+        _ => return true,
+    };
+
+    (min..=max).contains(&value)
+}
+
+/// Converts a value into the equivalent literal, if there is one
+fn into_literal(value: ConstValue) -> Option<expr::Literal> {
+    match value {
+        // Negative numbers are formed by negating a literal
+        ConstValue::Integer(v) if v.is_negative() => None,
+        ConstValue::Integer(v) => v.into_u64().map(expr::Literal::Integer),
+        ConstValue::Real(v) if v.is_sign_negative() => None,
+        ConstValue::Real(v) => Some(expr::Literal::Real(v)),
+        ConstValue::Bool(v) => Some(expr::Literal::Boolean(v)),
+        ConstValue::Char(v) => Some(expr::Literal::Char(v)),
+    }
+}
@@ -0,0 +1,116 @@
+use toc_hir::{db, expr, stmt};
+
+use crate::const_eval::fold_constants;
+
+#[track_caller]
+fn assert_fold(source: &str) {
+    insta::assert_snapshot!(insta::internals::AutoName, do_fold(source), source);
+}
+
+fn do_fold(source: &str) -> String {
+    let (mut hir_db, root_unit) = {
+        let parsed = toc_parser::parse(None, source);
+        let hir_db = db::HirBuilder::new();
+        let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+        let hir_db = hir_db.finish();
+
+        (hir_db, hir_res.id)
+    };
+
+    let messages = fold_constants(&mut hir_db, root_unit);
+
+    // Dump the initializers of each declaration
+    let mut s = String::new();
+
+    for stmt_id in &hir_db.get_unit(root_unit).stmts {
+        if let stmt::Stmt::ConstVar(decl) = hir_db.get_stmt(*stmt_id) {
+            if let Some(init_expr) = decl.tail.init_expr() {
+                let span = hir_db.get_span(init_expr.into());
+                s.push_str(&format!(
+                    "{:?}: {}\n",
+                    span.range,
+                    stringify_expr(&hir_db, init_expr)
+                ));
+            }
+        }
+    }
+
+    for msg in &messages {
+        s.push_str(&format!("{}\n", msg));
+    }
+
+    s
+}
+
+fn stringify_expr(hir_db: &db::HirDb, id: expr::ExprId) -> String {
+    match hir_db.get_expr(id) {
+        expr::Expr::Literal(literal) => format!("{:?}", literal),
+        expr::Expr::Binary(expr) => format!(
+            "({} {:?} {})",
+            stringify_expr(hir_db, expr.lhs),
+            expr.op.item(),
+            stringify_expr(hir_db, expr.rhs)
+        ),
+        expr::Expr::Unary(expr) => {
+            format!(
+                "({:?} {})",
+                expr.op.item(),
+                stringify_expr(hir_db, expr.rhs)
+            )
+        }
+        expr::Expr::Paren(expr) => format!("Paren {}", stringify_expr(hir_db, expr.expr)),
+        expr::Expr::Name(_) => "Name".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[test]
+fn fold_nested_ops() {
+    assert_fold("const a := 2 * 8 div 2");
+}
+
+#[test]
+fn fold_literal_kinds() {
+    assert_fold(
+        "const a := 1 / 2
+        const b := not false
+        const c := 1 < 2",
+    );
+}
+
+#[test]
+fn fold_through_const_names() {
+    assert_fold(
+        "const a := 2
+        var b := (a + 1) * 3",
+    );
+}
+
+#[test]
+fn fold_operands_of_unfoldable() {
+    // `b` isn't a compile-time value, but the parenthesized part is
+    assert_fold(
+        "var b := 1
+        var c := b + (1 + 1)",
+    );
+    // No literal for negative numbers, so only the inside is folded
+    assert_fold("const a := -(2 * 3)");
+}
+
+#[test]
+fn no_fold_overflow() {
+    assert_fold("const a := 16#FFFFFFFF + 1");
+    assert_fold("const a := 1 + 16#FFFFFFFF * 2");
+}
+
+#[test]
+fn no_fold_div_by_zero() {
+    assert_fold("const a := 1 div 0");
+}
+
+#[test]
+fn no_fold_too_large_for_target() {
+    assert_fold("var a : nat1 := 200 + 100");
+    assert_fold("var a : int1 := 100 + 27");
+    assert_fold("var a : int1 := -100 - 29");
+}
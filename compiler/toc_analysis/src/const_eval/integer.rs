@@ -119,6 +119,16 @@ impl ConstInt {
         }
     }
 
+    /// Converts the `ConstInt` into the corresponding `i128` value.
+    ///
+    /// All `ConstInt` values are representable as an `i128`.
+    pub fn into_i128(self) -> i128 {
+        match self.sign {
+            Sign::Positive => i128::from(self.magnitude),
+            Sign::Negative => -i128::from(self.magnitude),
+        }
+    }
+
     /// Converts the `ConstInt` into the corresponding `f64` value.
     pub fn into_f64(self) -> f64 {
         match self.sign {
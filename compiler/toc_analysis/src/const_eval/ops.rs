@@ -145,7 +145,9 @@ impl ConstOp {
                         let (lhs, rhs) = (lhs.cast_into_real()?, rhs.cast_into_real()?);
 
                         match lhs / rhs {
-                            _ if rhs == 0.0 => Err(ConstError::without_span(ErrorKind::DivByZero)),
+                            _ if rhs == 0.0 => {
+                                Err(ConstError::without_span(ErrorKind::RealDivByZero))
+                            }
                             v if v.is_infinite() => {
                                 Err(ConstError::without_span(ErrorKind::RealOverflow))
                             }
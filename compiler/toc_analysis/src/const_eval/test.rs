@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use toc_hir::db;
-use toc_reporting::{MessageSink, ReportMessage};
+use toc_reporting::{MessageSink, ReportMessage, Stage};
 use unindent::unindent;
 
 use crate::const_eval::ConstEvalCtx;
@@ -39,7 +39,7 @@ fn do_const_eval(source: &str) -> String {
     super::collect_const_vars(hir_db.clone(), unit, const_eval_ctx.clone());
 
     let mut results_str = String::new();
-    let mut reporter = MessageSink::new();
+    let mut reporter = MessageSink::new(Stage::Analyze);
 
     // Need access to the inner state of the ConstEvalCtx, which is behind a lock
     {
@@ -151,6 +151,8 @@ fn arithmetic_const_ops() {
         "2.0 ** 0"
         "-2.0 ** -4"
         "-2.0 ** -2"
+        "2 ** 3 ** 2     % 512"
+        "(2 ** 3) ** 2   % 64"
     ];
 }
 
@@ -511,6 +513,10 @@ fn restrict_assign_type() {
 fn unsupported_values() {
     assert_const_eval(r#"const a := "alphabet""#);
     assert_const_eval(r#"const a := 'fun times'"#);
+}
+
+#[test]
+fn char_values() {
     assert_const_eval(r#"const a := 'e'"#);
 }
 
@@ -12,6 +12,8 @@ pub enum ConstValue {
     Real(f64),
     /// Boolean value
     Bool(bool),
+    /// Character value
+    Char(char),
 }
 
 impl ConstValue {
@@ -38,6 +40,7 @@ impl ConstValue {
             ConstValue::Integer(_) => "integer value",
             ConstValue::Real(_) => "real value",
             ConstValue::Bool(_) => "boolean value",
+            ConstValue::Char(_) => "char value",
         }
     }
 
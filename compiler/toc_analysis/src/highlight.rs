@@ -0,0 +1,240 @@
+//! Semantic classification of source text, for syntax highlighting
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use toc_hir::{db, stmt, symbol, unit, visitor::HirVisitor};
+use toc_span::{FileId, Span, TextRange, TextSize};
+use toc_syntax::ast::{self, AstNode};
+use toc_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// A classified range of source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub class: SemanticClass,
+}
+
+/// Classification of a [`SemanticToken`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticClass {
+    Keyword,
+    Const,
+    Variable,
+    Parameter,
+    TypeName,
+    EnumVariant,
+    Subprogram,
+    Module,
+    StringEscape,
+    Comment,
+}
+
+/// Classifies the text of `file` for semantic highlighting.
+///
+/// `root` is the syntax tree of `file`, which must have been lowered into the unit.
+/// Names are classified by what they resolve to, so a reference to a `const`
+/// is classified as [`SemanticClass::Const`]. Undeclared names are left unclassified.
+///
+/// ## Returns
+/// The classified tokens, sorted by starting offset and without any overlaps.
+pub fn semantic_tokens(
+    hir_db: &db::HirDb,
+    unit_id: unit::UnitId,
+    file: Option<FileId>,
+    root: &SyntaxNode,
+) -> Vec<SemanticToken> {
+    let unit = hir_db.get_unit(unit_id);
+    let symbol_table = &unit.symbol_table;
+
+    let visitor = DeclVisitor {
+        decls: Default::default(),
+    };
+    unit.walk_nodes(hir_db.clone(), &visitor);
+    let decls = visitor.decls.into_inner();
+
+    let mut tokens = vec![];
+
+    // Names, from the resolved HIR
+    let def_class = |def_id: symbol::DefId| {
+        decls.get(&def_id).copied().or_else(|| {
+            // Also try the original forward declaration
+            match symbol_table.get_symbol(def_id).kind {
+                symbol::SymbolKind::Resolved(forward_def) => decls.get(&forward_def).copied(),
+                _ => None,
+            }
+        })
+    };
+    let names = symbol_table
+        .iter_defs()
+        .map(|(def_id, span, _)| (def_id, span))
+        .chain(
+            symbol_table
+                .iter_uses()
+                .map(|(use_id, span)| (use_id.as_def(), span)),
+        );
+
+    for (def_id, span) in names {
+        // Names spliced in from other files are classified along with those files
+        if span.file != file {
+            continue;
+        }
+
+        if let Some(class) = def_class(def_id) {
+            tokens.push(SemanticToken { span, class });
+        }
+    }
+
+    // Everything else, from the syntax tree
+    let mut syntax_tokens = vec![];
+
+    for node in root.descendants() {
+        classify_node(&node, &mut syntax_tokens);
+    }
+
+    for token in root
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+    {
+        classify_token(&token, &mut syntax_tokens);
+    }
+
+    tokens.extend(
+        syntax_tokens
+            .into_iter()
+            .map(|(range, class)| SemanticToken {
+                span: Span::new(file, range),
+                class,
+            }),
+    );
+
+    // Sort by position, keeping the HIR classification for names classified by both
+    tokens.sort_by_key(|token| token.span.range.start());
+
+    let mut covered_to = TextSize::from(0);
+    tokens.retain(|token| {
+        if token.span.range.start() < covered_to {
+            false
+        } else {
+            covered_to = token.span.range.end();
+            true
+        }
+    });
+
+    tokens
+}
+
+/// Classifies the names of declarations which aren't lowered into the HIR yet
+// TODO: Classify these from the HIR once subprograms, modules, enums, and parameters are lowered
+fn classify_node(node: &SyntaxNode, tokens: &mut Vec<(TextRange, SemanticClass)>) {
+    let mut push_name = |name: Option<ast::Name>, class| {
+        if let Some(name) = name {
+            tokens.push((name.syntax().text_range(), class));
+        }
+    };
+
+    match node.kind() {
+        SyntaxKind::ProcHeader => push_name(
+            ast::ProcHeader::cast(node.clone()).and_then(|n| n.name()),
+            SemanticClass::Subprogram,
+        ),
+        SyntaxKind::FcnHeader => push_name(
+            ast::FcnHeader::cast(node.clone()).and_then(|n| n.name()),
+            SemanticClass::Subprogram,
+        ),
+        SyntaxKind::ProcessDecl => push_name(
+            ast::ProcessDecl::cast(node.clone()).and_then(|n| n.name()),
+            SemanticClass::Subprogram,
+        ),
+        SyntaxKind::ModuleDecl => push_name(
+            ast::ModuleDecl::cast(node.clone()).and_then(|n| n.name()),
+            SemanticClass::Module,
+        ),
+        SyntaxKind::ClassDecl => push_name(
+            ast::ClassDecl::cast(node.clone()).and_then(|n| n.name()),
+            SemanticClass::Module,
+        ),
+        SyntaxKind::MonitorDecl => push_name(
+            ast::MonitorDecl::cast(node.clone()).and_then(|n| n.name()),
+            SemanticClass::Module,
+        ),
+        SyntaxKind::EnumType => {
+            // Fields are wrapped in a `NameList` by the parser
+            let names = node.children().find_map(ast::NameList::cast);
+
+            for name in names.iter().flat_map(|names| names.names()) {
+                push_name(Some(name), SemanticClass::EnumVariant);
+            }
+        }
+        SyntaxKind::ConstVarParam => {
+            let names = ast::ConstVarParam::cast(node.clone()).and_then(|n| n.param_names());
+
+            for name in names.iter().flat_map(|names| names.names()) {
+                push_name(Some(name), SemanticClass::Parameter);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Classifies keywords, comments, and character escapes
+fn classify_token(token: &SyntaxToken, tokens: &mut Vec<(TextRange, SemanticClass)>) {
+    let kind = token.kind();
+    let range = token.text_range();
+
+    if kind.is_keyword() {
+        tokens.push((range, SemanticClass::Keyword));
+    } else if kind == SyntaxKind::Comment {
+        tokens.push((range, SemanticClass::Comment));
+    } else if matches!(kind, SyntaxKind::CharLiteral | SyntaxKind::StringLiteral) {
+        for escape in toc_scanner::char_seq::escape_ranges(token.text()) {
+            let start = TextSize::from(escape.start as u32);
+            let end = TextSize::from(escape.end as u32);
+
+            tokens.push((
+                TextRange::new(start, end) + range.start(),
+                SemanticClass::StringEscape,
+            ));
+        }
+    }
+}
+
+/// Collects the classification of declared names
+struct DeclVisitor {
+    decls: RefCell<HashMap<symbol::DefId, SemanticClass>>,
+}
+
+impl HirVisitor for DeclVisitor {
+    fn visit_constvar(&self, _id: stmt::StmtId, decl: &stmt::ConstVar) {
+        let class = if decl.is_const {
+            SemanticClass::Const
+        } else {
+            SemanticClass::Variable
+        };
+
+        let mut decls = self.decls.borrow_mut();
+        decls.extend(decl.names.iter().map(|def_id| (*def_id, class)));
+    }
+
+    fn visit_bind(&self, _id: stmt::StmtId, decl: &stmt::Bind) {
+        let mut decls = self.decls.borrow_mut();
+
+        for binding in &decl.bindings {
+            let class = if binding.is_var {
+                SemanticClass::Variable
+            } else {
+                SemanticClass::Const
+            };
+
+            decls.insert(binding.def_id, class);
+        }
+    }
+
+    fn visit_type_decl(&self, _id: stmt::StmtId, decl: &stmt::Type) {
+        self.decls
+            .borrow_mut()
+            .insert(decl.def_id, SemanticClass::TypeName);
+    }
+}
@@ -0,0 +1,66 @@
+//! Semantic highlighting tests
+use toc_hir::db;
+use unindent::unindent;
+
+use crate::highlight::{semantic_tokens, SemanticToken};
+
+fn semantic_tokens_of(source: &str) -> Vec<SemanticToken> {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+    let hir_db = hir_db.finish();
+
+    semantic_tokens(&hir_db, hir_res.id, None, &parsed.syntax())
+}
+
+fn classify(source: &str) -> String {
+    semantic_tokens_of(source)
+        .into_iter()
+        .map(|token| {
+            let range = token.span.range;
+            let text = &source[range];
+            format!("{:?} {:?} `{}`", range, token.class, text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn classify_all_classes() {
+    let source = unindent(
+        r#"
+        % comment
+        const c := 1
+        var v : int := c
+        type t : int
+        type e : enum (red, green)
+        procedure p (a : int)
+        end p
+        module m
+        end m
+        var w : t := v
+        put "tab\tcaret^J", w
+        "#,
+    );
+
+    insta::assert_snapshot!(insta::internals::AutoName, classify(&source), &source);
+}
+
+#[test]
+fn tokens_are_sorted_and_disjoint() {
+    // Names inside of escapes and keywords inside of strings and comments
+    // shouldn't produce overlapping tokens
+    let source = unindent(
+        r#"
+        const c := "\x41 var"   % const c
+        var v : string := c
+        v := c + "^J" + v
+        "#,
+    );
+    let tokens = semantic_tokens_of(&source);
+    assert!(!tokens.is_empty());
+
+    for pair in tokens.windows(2) {
+        assert!(pair[0].span.range.end() <= pair[1].span.range.start());
+    }
+}
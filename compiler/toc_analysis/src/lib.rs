@@ -1,15 +1,28 @@
 //! General code analysis, including type checking and dead code reporting
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use toc_hir::{db, unit};
 use toc_reporting::ReportMessage;
 
 use crate::const_eval::ConstEvalCtx;
+pub mod call_graph;
+pub mod highlight;
+pub mod liveness;
+pub mod query;
 pub mod ty;
 
+mod cfg;
 mod const_eval;
+mod reachability;
+mod recursive_types;
 mod typeck;
+mod uninit;
+mod unused;
+
+pub use const_eval::fold_constants;
+pub use liveness::{liveness, LivenessResult};
 
 pub struct AnalyzeResult {
     messages: Vec<ReportMessage>,
@@ -21,16 +34,60 @@ impl AnalyzeResult {
     }
 }
 
-pub fn analyze_unit(hir_db: db::HirDb, unit_id: unit::UnitId) -> AnalyzeResult {
-    let unit = hir_db.get_unit(unit_id);
+/// Language dialect options, for the constructs that only some dialects accept
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Dialect {
+    /// If `fork` statements can start procedures as well as processes
+    pub fork_procedures: bool,
+}
+
+pub fn analyze_unit(hir_db: db::HirDb, unit_id: unit::UnitId, dialect: Dialect) -> AnalyzeResult {
+    analyze_units(hir_db, &[unit_id], dialect).pop().unwrap()
+}
 
+/// Analyzes all of the given units, giving back the results in the same order.
+///
+/// Units must come after any of the units that they import, so that the
+/// imported symbols can be resolved.
+pub fn analyze_units(
+    hir_db: db::HirDb,
+    unit_ids: &[unit::UnitId],
+    dialect: Dialect,
+) -> Vec<AnalyzeResult> {
+    // Constants can refer to constants from other units
     let const_eval_ctx = Arc::new(ConstEvalCtx::new(hir_db.clone()));
-    const_eval::collect_const_vars(hir_db.clone(), unit, const_eval_ctx.clone());
 
-    let (ty_ctx, messages) = typeck::typecheck_unit(hir_db.clone(), unit, const_eval_ctx.clone());
+    for unit_id in unit_ids {
+        let unit = hir_db.get_unit(*unit_id);
+        const_eval::collect_const_vars(hir_db.clone(), unit, const_eval_ctx.clone());
+    }
+
+    let mut ty_ctxs = HashMap::new();
+    let mut results = vec![];
+
+    for unit_id in unit_ids {
+        let unit = hir_db.get_unit(*unit_id);
+
+        let (ty_ctx, mut messages) = typeck::typecheck_unit(
+            hir_db.clone(),
+            unit,
+            const_eval_ctx.clone(),
+            &ty_ctxs,
+            dialect,
+        );
+        messages.extend(unused::check_unused_vars(hir_db.clone(), unit));
+        messages.extend(uninit::check_uninit_vars(hir_db.clone(), unit));
+        messages.extend(liveness::check_dead_stores(hir_db.clone(), unit));
+        messages.extend(reachability::check_unreachable(hir_db.clone(), unit));
+        messages.extend(recursive_types::check_recursive_types(hir_db.clone(), unit));
+
+        eprintln!("{}", ty::pretty_dump_typectx(&ty_ctx));
+
+        ty_ctxs.insert(*unit_id, ty_ctx);
+        results.push(AnalyzeResult { messages });
+    }
 
-    eprintln!("{}", ty::pretty_dump_typectx(&ty_ctx));
     eprintln!("{:#?}", const_eval_ctx);
 
-    AnalyzeResult { messages }
+    results
 }
@@ -0,0 +1,246 @@
+//! Liveness of the values stored into variables
+#[cfg(test)]
+mod test;
+
+use std::collections::HashSet;
+
+use toc_hir::{db, expr, stmt, symbol, unit};
+use toc_reporting::{MessageSink, ReportMessage, Stage};
+use toc_span::Span;
+
+use crate::cfg::{self, Cfg};
+
+/// Stores into the variables of a unit, and which of them are ever read
+#[derive(Debug, Default)]
+pub struct LivenessResult {
+    stores: Vec<Store>,
+    /// Variables which are read from anywhere in the unit
+    read_defs: HashSet<symbol::DefId>,
+}
+
+/// A value being stored into a variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Store {
+    /// Statement doing the store, either an assignment or a `var` declaration
+    pub stmt_id: stmt::StmtId,
+    pub def_id: symbol::DefId,
+    pub name: String,
+    /// Where the variable is stored into
+    pub span: Span,
+    /// If the stored value is read before the variable is overwritten or never read again
+    pub is_live: bool,
+}
+
+impl LivenessResult {
+    /// All of the stores in the unit, in source order
+    pub fn stores(&self) -> &[Store] {
+        &self.stores
+    }
+
+    /// Stores whose value is never read
+    pub fn dead_stores(&self) -> impl Iterator<Item = &Store> + '_ {
+        self.stores.iter().filter(|store| !store.is_live)
+    }
+
+    /// Checks if `def_id` is read from anywhere in the unit
+    pub fn is_ever_read(&self, def_id: symbol::DefId) -> bool {
+        self.read_defs.contains(&def_id)
+    }
+}
+
+/// Finds which of the values stored into variables are ever read.
+///
+/// Only stores into a whole variable are tracked, since writing into part of an
+/// array or record keeps the rest of the previous value around. Variables aliased
+/// by `bind` or accessed in a subprogram or process are never considered dead, since
+/// accesses through the new name or from the body aren't tracked, and exported
+/// variables are live until the end of the unit.
+pub fn liveness(hir_db: &db::HirDb, unit_id: unit::UnitId) -> LivenessResult {
+    let unit = hir_db.get_unit(unit_id);
+    let cfg = Cfg::build(hir_db, &unit.stmts);
+
+    // Gather up what each statement reads from and stores into
+    let mut escaped = HashSet::new();
+    let nodes = cfg
+        .nodes()
+        .map(|(node, stmt_id)| {
+            if !cfg.is_node_reachable(node) {
+                return NodeAccesses::default();
+            }
+
+            let accesses = NodeAccesses::collect(hir_db, unit, stmt_id);
+
+            match hir_db.get_stmt(stmt_id) {
+                stmt::Stmt::Bind(bind) => escaped.extend(
+                    bind.bindings
+                        .iter()
+                        .filter_map(|item| whole_var(hir_db, item.bind_to))
+                        .map(|(_, def_id)| def_id),
+                ),
+                // Subprograms and processes can run at any point after being
+                // called or forked
+                stmt::Stmt::Subprogram(_) | stmt::Stmt::Process(_) => escaped.extend(
+                    cfg::names_in_stmt(hir_db, stmt_id)
+                        .into_iter()
+                        .map(|(_, def_id)| def_id),
+                ),
+                _ => {}
+            }
+
+            accesses
+        })
+        .collect::<Vec<_>>();
+
+    let exit_live = unit
+        .exports
+        .iter()
+        .filter(|export| export.def_id.unit_id() == unit.id)
+        .map(|export| export.def_id.as_local())
+        .collect::<HashSet<_>>();
+
+    // Values flow forwards, so liveness flows backwards from the end of the unit
+    let mut live_in = vec![HashSet::new(); nodes.len()];
+    let live_out = |live_in: &[HashSet<symbol::DefId>], node| {
+        let mut live = HashSet::new();
+
+        for succ in cfg.successors(node) {
+            match succ {
+                Some(succ) => live.extend(live_in[succ.index()].iter().copied()),
+                None => live.extend(exit_live.iter().copied()),
+            }
+        }
+
+        live
+    };
+
+    loop {
+        let mut changed = false;
+
+        for (node, _) in cfg.nodes().rev() {
+            let accesses = &nodes[node.index()];
+            let mut live = live_out(&live_in, node);
+
+            for (def_id, _) in &accesses.stores {
+                live.remove(def_id);
+            }
+            live.extend(accesses.reads.iter().copied());
+
+            if live != live_in[node.index()] {
+                live_in[node.index()] = live;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let mut result = LivenessResult::default();
+
+    for (node, stmt_id) in cfg.nodes() {
+        let accesses = &nodes[node.index()];
+        let live = live_out(&live_in, node);
+
+        result.read_defs.extend(accesses.reads.iter().copied());
+        result
+            .stores
+            .extend(accesses.stores.iter().map(|(def_id, span)| Store {
+                stmt_id,
+                def_id: *def_id,
+                name: unit.symbol_table.get_symbol(*def_id).name.clone(),
+                span: *span,
+                is_live: live.contains(def_id) || escaped.contains(def_id),
+            }));
+    }
+
+    result
+}
+
+/// Reports values stored into variables that are never read.
+///
+/// Variables that are never read at all are left to the unused variable check,
+/// and names starting with `_` are exempt from this check.
+pub fn check_dead_stores(hir_db: db::HirDb, unit: &unit::Unit) -> Vec<ReportMessage> {
+    let result = liveness(&hir_db, unit.id);
+    let mut reporter = MessageSink::new(Stage::Analyze);
+
+    for store in result.dead_stores() {
+        if !result.is_ever_read(store.def_id) || store.name.starts_with('_') {
+            continue;
+        }
+
+        reporter
+            .warn_detailed(
+                &format!("value assigned to `{}` is never read", store.name),
+                store.span,
+            )
+            .with_info(
+                &format!(
+                    "`{}` is overwritten before the value is read, or is never read after this",
+                    store.name
+                ),
+                None,
+            )
+            .finish();
+    }
+
+    reporter.finish()
+}
+
+/// Variables read from and stored into by a single statement
+#[derive(Default)]
+struct NodeAccesses {
+    reads: HashSet<symbol::DefId>,
+    stores: Vec<(symbol::DefId, Span)>,
+}
+
+impl NodeAccesses {
+    fn collect(hir_db: &db::HirDb, unit: &unit::Unit, stmt_id: stmt::StmtId) -> Self {
+        let mut stores = vec![];
+        let mut store_name = None;
+
+        match hir_db.get_stmt(stmt_id) {
+            // Accesses in subprogram and process bodies are treated as escaping instead
+            stmt::Stmt::Subprogram(_) | stmt::Stmt::Process(_) => return Self::default(),
+            stmt::Stmt::ConstVar(decl) if !decl.is_const && decl.tail.init_expr().is_some() => {
+                stores.extend(
+                    decl.names
+                        .iter()
+                        .map(|def_id| (*def_id, unit.symbol_table.get_def_span(*def_id))),
+                );
+            }
+            stmt::Stmt::Assign(stmt) => {
+                if let Some((name_id, def_id)) = whole_var(hir_db, stmt.lhs) {
+                    stores.push((def_id, hir_db.get_span(stmt.lhs.into())));
+
+                    // Compound assignments also read from the left hand side
+                    if *stmt.op.item() == stmt::AssignOp::None {
+                        store_name = Some(name_id);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let reads = cfg::names_in_node(hir_db, stmt_id)
+            .into_iter()
+            .filter(|(expr_id, _)| Some(*expr_id) != store_name)
+            .map(|(_, def_id)| def_id)
+            .collect();
+
+        Self { reads, stores }
+    }
+}
+
+/// Finds the variable that the reference expression `id` refers to as a whole,
+/// along with the name expression referring to it
+fn whole_var(hir_db: &db::HirDb, mut id: expr::ExprId) -> Option<(expr::ExprId, symbol::DefId)> {
+    loop {
+        id = match hir_db.get_expr(id) {
+            expr::Expr::Name(expr::Name::Name(use_id)) => return Some((id, use_id.as_def())),
+            expr::Expr::Paren(expr) => expr.expr,
+            _ => return None,
+        };
+    }
+}
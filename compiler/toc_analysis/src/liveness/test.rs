@@ -0,0 +1,169 @@
+//! Liveness tests
+use toc_hir::{db, unit::UnitId};
+
+fn lower(source: &str) -> (db::HirDb, UnitId) {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+
+    (hir_db.finish(), hir_res.id)
+}
+
+/// Spans of the stores which are never read
+fn dead_stores(source: &str) -> Vec<String> {
+    let (hir_db, unit_id) = lower(source);
+
+    super::liveness(&hir_db, unit_id)
+        .dead_stores()
+        .map(|store| format!("`{}` at {:?}", store.name, store.span.range))
+        .collect()
+}
+
+fn dead_store_messages(source: &str) -> Vec<String> {
+    let (hir_db, unit_id) = lower(source);
+
+    let unit = hir_db.get_unit(unit_id);
+    super::check_dead_stores(hir_db.clone(), unit)
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect()
+}
+
+#[test]
+fn dead_store_before_reassignment() {
+    assert_eq!(
+        dead_stores("var a : int\na := 1\na := 2\nput a"),
+        vec!["`a` at 12..13"]
+    );
+    // Also for initializers
+    assert_eq!(
+        dead_stores("var a : int := 1\na := 2\nput a"),
+        vec!["`a` at 4..5"]
+    );
+}
+
+#[test]
+fn dead_store_at_end() {
+    assert_eq!(
+        dead_stores("var a : int\na := 1\nput a\na := 2"),
+        vec!["`a` at 25..26"]
+    );
+    // Nothing is read after the program stops
+    assert_eq!(
+        dead_stores("var a : int\na := 1\nquit\nput a"),
+        vec!["`a` at 12..13"]
+    );
+}
+
+#[test]
+fn store_read_in_nested_block() {
+    assert!(
+        dead_stores("var a : int\na := 1\nbegin begin put a end end\na := 2\nput a").is_empty()
+    );
+    assert_eq!(
+        dead_stores("var a : int\na := 1\nbegin begin a := 2 end end\nput a"),
+        vec!["`a` at 12..13"]
+    );
+}
+
+#[test]
+fn store_read_in_nested_if() {
+    assert!(dead_stores(
+        "var a : int\na := 1\nif true then if false then put a end if end if\na := 2\nput a"
+    )
+    .is_empty());
+    // Only read along some paths
+    assert!(dead_stores("var a : int\na := 1\nif true then a := 2 end if\nput a").is_empty());
+    assert!(
+        dead_stores("var a : int\na := 1\ncase 1 of label 1 : put a label : end case").is_empty()
+    );
+    // Overwritten along every path
+    assert_eq!(
+        dead_stores("var a : int\na := 1\nif true then a := 2 else a := 3 end if\nput a"),
+        vec!["`a` at 12..13"]
+    );
+}
+
+#[test]
+fn loop_carried_store() {
+    // Read on the next time around the loop
+    assert!(dead_stores("var a : int := 0\nloop put a\na := a + 1 end loop").is_empty());
+    assert!(dead_stores("var i : int := 0\nloop exit when i = 10\ni += 1 end loop").is_empty());
+    // Overwritten before going around again
+    assert_eq!(
+        dead_stores("var a : int\nloop a := 1\na := 2\nput a end loop"),
+        vec!["`a` at 17..18"]
+    );
+}
+
+#[test]
+fn store_read_by_itself() {
+    // Stored values are read before being overwritten
+    assert!(dead_stores("var a : int := 1\na := a + 1\nput a").is_empty());
+    assert!(dead_stores("var a : int := 1\na += 1\nput a").is_empty());
+    // Compound assignments are still stores
+    assert_eq!(
+        dead_stores("var a : int := 1\nput a\na += 1"),
+        vec!["`a` at 23..24"]
+    );
+}
+
+#[test]
+fn partial_stores_not_tracked() {
+    // Only part of the variable is overwritten, so the rest of the value is still around
+    assert!(
+        dead_stores("var a : array 1 .. 2 of int := init(1, 2)\na(1) := 3\nput a(2)").is_empty()
+    );
+}
+
+#[test]
+fn tag_not_a_whole_store() {
+    // Changing the tag keeps the union variable around, so the earlier store is still live
+    let source = "type u : union kind : 1 .. 2 of label 1: a : int label 2: b : int end union\n";
+    assert!(dead_stores(&format!(
+        "{}var v, w : u\nv := w\ntag v, 2\nv.b := 1\nput v.b",
+        source
+    ))
+    .is_empty());
+}
+
+#[test]
+fn bound_stores_always_live() {
+    assert!(dead_stores("var a : int := 1\nbind b to a\na := 2").is_empty());
+}
+
+#[test]
+fn process_accessed_stores_always_live() {
+    assert!(dead_stores("var a : int := 1\nprocess p put a end p\na := 2\nfork p").is_empty());
+}
+
+#[test]
+fn subprogram_accessed_stores_always_live() {
+    assert!(dead_stores("var a : int := 1\nprocedure p put a end p\na := 2\np").is_empty());
+}
+
+#[test]
+fn exported_stores_live_at_end() {
+    assert!(dead_stores("unit module m export a var a : int := 1 end m").is_empty());
+    // Only the initializer is overwritten
+    assert_eq!(
+        dead_stores("unit module m export a var a : int := 1 a := 2 end m"),
+        vec!["`a` at 27..28"]
+    );
+}
+
+#[test]
+fn unreachable_stores_ignored() {
+    assert!(dead_stores("var a : int := 1\nput a\nquit\na := 2").is_empty());
+}
+
+#[test]
+fn report_dead_store() {
+    assert_eq!(
+        dead_store_messages("var a : int\na := 1\na := 2\nput a"),
+        vec!["warn at 12..13: value assigned to `a` is never read\n| info: `a` is overwritten before the value is read, or is never read after this"]
+    );
+    // Left to the unused variable check
+    assert!(dead_store_messages("var a : int\na := 1").is_empty());
+    assert!(dead_store_messages("var _a : int\n_a := 1\n_a := 2\nput _a").is_empty());
+}
@@ -0,0 +1,365 @@
+//! Queries for navigating between symbol definitions and references
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use toc_hir::{db, expr, stmt, symbol, ty, unit, visitor::HirVisitor};
+use toc_span::{Span, TextSize};
+
+use crate::const_eval::ConstEvalCtx;
+
+/// A symbol definition, along with the span of the definition's name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefSpan {
+    pub def_id: symbol::DefId,
+    pub span: Span,
+}
+
+/// Finds the definition of the symbol at the given byte offset.
+///
+/// The offset can either be on a reference to a symbol, or on the name of the
+/// definition itself.
+///
+/// ## Returns
+/// The defining symbol and the span of its name, or `None` if there is no symbol
+/// at the offset or if the symbol is undeclared.
+pub fn find_definition(
+    hir_db: &db::HirDb,
+    unit_id: unit::UnitId,
+    offset: TextSize,
+) -> Option<DefSpan> {
+    let symbol_table = &hir_db.get_unit(unit_id).symbol_table;
+
+    let def_id = symbol_table
+        .iter_uses()
+        .find(|(_, span)| span.range.contains_inclusive(offset))
+        .map(|(use_id, _)| use_id.as_def())
+        .or_else(|| {
+            symbol_table
+                .iter_defs()
+                .find(|(_, span, _)| span.range.contains_inclusive(offset))
+                .map(|(def_id, _, _)| def_id)
+        })?;
+
+    // Undeclared symbols don't have a real definition
+    if let symbol::SymbolKind::Undeclared = symbol_table.get_symbol(def_id).kind {
+        return None;
+    }
+
+    Some(DefSpan {
+        def_id,
+        span: symbol_table.get_def_span(def_id),
+    })
+}
+
+/// A reference to a symbol definition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub span: Span,
+    /// If this is the definition's name itself, rather than a use of it
+    pub is_declaration: bool,
+}
+
+/// Finds all references to the given definition, in order of appearance.
+///
+/// If `include_declaration` is true, the span of the definition's name is also included.
+pub fn find_references(
+    hir_db: &db::HirDb,
+    unit_id: unit::UnitId,
+    def_id: symbol::DefId,
+    include_declaration: bool,
+) -> Vec<Reference> {
+    let symbol_table = &hir_db.get_unit(unit_id).symbol_table;
+
+    let decl = if include_declaration {
+        Some(Reference {
+            span: symbol_table.get_def_span(def_id),
+            is_declaration: true,
+        })
+    } else {
+        None
+    };
+
+    let mut references: Vec<_> = decl
+        .into_iter()
+        .chain(
+            symbol_table
+                .get_symbol(def_id)
+                .uses()
+                .map(|use_id| Reference {
+                    span: symbol_table.get_use_span(use_id),
+                    is_declaration: false,
+                }),
+        )
+        .collect();
+
+    references.sort_by_key(|reference| reference.span.range.start());
+    references.dedup_by_key(|reference| reference.span);
+    references
+}
+
+/// Finds the declaration of the record or union field at the given byte offset.
+///
+/// The offset can either be on a field access (`lhs.field`), or on the name of
+/// the field declaration itself. Fields are only resolved through declared types,
+/// so fields of variables with an inferred type aren't resolved.
+///
+/// ## Returns
+/// The span of the declared field's name, or `None` if there is no field at the
+/// offset or if the field can't be resolved.
+pub fn find_field_definition(
+    hir_db: &db::HirDb,
+    unit_id: unit::UnitId,
+    offset: TextSize,
+) -> Option<Span> {
+    let unit = hir_db.get_unit(unit_id);
+    let visitor = FieldVisitor::default();
+    unit.walk_nodes(hir_db.clone(), &visitor);
+
+    let FieldVisitor {
+        field_decls,
+        field_exprs,
+        typed_defs,
+    } = visitor;
+
+    // Declarations resolve to themselves
+    if let Some(span) = field_decls
+        .into_inner()
+        .into_iter()
+        .find(|span| span.range.contains_inclusive(offset))
+    {
+        return Some(span);
+    }
+
+    let field_expr =
+        field_exprs
+            .into_inner()
+            .into_iter()
+            .find(|id| match hir_db.get_expr(*id) {
+                expr::Expr::Field(expr) => expr.field.span().range.contains_inclusive(offset),
+                _ => false,
+            })?;
+
+    let resolver = FieldResolver {
+        hir_db,
+        symbol_table: &unit.symbol_table,
+        typed_defs: typed_defs.into_inner(),
+    };
+    let (span, _) = resolver.resolve_field(field_expr)?;
+    Some(span)
+}
+
+/// Finds the type of the innermost expression at the given byte offset, for
+/// showing on hover.
+///
+/// The unit (along with any units it imports) is typechecked to find the type.
+///
+/// ## Returns
+/// The type rendered the same way as it would be written in Turing, or `None` if
+/// there is no expression at the offset or if the expression's type is an error.
+pub fn hover_type(hir_db: &db::HirDb, unit_id: unit::UnitId, offset: TextSize) -> Option<String> {
+    let ty_ctx = typecheck_with_imports(hir_db, unit_id);
+
+    let (_, ty_ref) = ty_ctx
+        .iter_expr_types()
+        .map(|(id, ty_ref)| (hir_db.get_span(id.into()).range, ty_ref))
+        .filter(|(range, _)| range.contains_inclusive(offset))
+        .min_by_key(|(range, _)| range.len())?;
+
+    if crate::ty::rules::is_error(&ty_ref) {
+        return None;
+    }
+
+    Some(ty_ref.to_string())
+}
+
+/// Typechecks `unit_id` and everything that it imports, giving back the
+/// typing context of `unit_id`
+fn typecheck_with_imports(hir_db: &db::HirDb, unit_id: unit::UnitId) -> crate::ty::TyCtx {
+    // Imported units need to be checked before the units importing them
+    fn visit(
+        hir_db: &db::HirDb,
+        unit_id: unit::UnitId,
+        seen: &mut HashSet<unit::UnitId>,
+        order: &mut Vec<unit::UnitId>,
+    ) {
+        if !seen.insert(unit_id) {
+            return;
+        }
+
+        for imported in hir_db.unit_imports(unit_id) {
+            visit(hir_db, *imported, seen, order);
+        }
+
+        order.push(unit_id);
+    }
+
+    let mut order = vec![];
+    visit(hir_db, unit_id, &mut HashSet::new(), &mut order);
+
+    let const_eval = Arc::new(ConstEvalCtx::new(hir_db.clone()));
+
+    for unit_id in &order {
+        let unit = hir_db.get_unit(*unit_id);
+        crate::const_eval::collect_const_vars(hir_db.clone(), unit, const_eval.clone());
+    }
+
+    let mut ty_ctxs = HashMap::new();
+
+    for unit_id in order {
+        let unit = hir_db.get_unit(unit_id);
+        let (ty_ctx, _) = crate::typeck::typecheck_unit(
+            hir_db.clone(),
+            unit,
+            const_eval.clone(),
+            &ty_ctxs,
+            Default::default(),
+        );
+        ty_ctxs.insert(unit_id, ty_ctx);
+    }
+
+    ty_ctxs.remove(&unit_id).unwrap()
+}
+
+/// Collects the pieces needed to resolve field accesses
+#[derive(Default)]
+struct FieldVisitor {
+    /// Names of all declared fields, including union tags
+    field_decls: RefCell<Vec<Span>>,
+    /// All field access expressions
+    field_exprs: RefCell<Vec<expr::ExprId>>,
+    /// Symbols with an explicitly declared type
+    typed_defs: RefCell<HashMap<symbol::DefId, ty::TypeId>>,
+}
+
+impl FieldVisitor {
+    fn add_fields(&self, fields: &[ty::Field]) {
+        let names = fields.iter().flat_map(|field| &field.names);
+        self.field_decls
+            .borrow_mut()
+            .extend(names.map(|name| name.span()));
+    }
+}
+
+impl HirVisitor for FieldVisitor {
+    fn visit_constvar(&self, _id: stmt::StmtId, decl: &stmt::ConstVar) {
+        if let Some(ty) = decl.tail.type_spec() {
+            let mut typed_defs = self.typed_defs.borrow_mut();
+            typed_defs.extend(decl.names.iter().map(|def_id| (*def_id, ty)));
+        }
+    }
+
+    fn visit_type_decl(&self, _id: stmt::StmtId, decl: &stmt::Type) {
+        if let stmt::TypeDefKind::Alias(ty) = decl.type_def {
+            self.typed_defs.borrow_mut().insert(decl.def_id, ty);
+        }
+    }
+
+    fn visit_field(&self, id: expr::ExprId, _expr: &expr::Field) {
+        self.field_exprs.borrow_mut().push(id);
+    }
+
+    fn visit_record(&self, _id: ty::TypeId, ty: &ty::Record) {
+        self.add_fields(&ty.fields);
+    }
+
+    fn visit_union(&self, _id: ty::TypeId, ty: &ty::Union) {
+        self.field_decls.borrow_mut().push(ty.tag_name.span());
+
+        for variant in &ty.variants {
+            self.add_fields(&variant.fields);
+        }
+    }
+}
+
+struct FieldResolver<'a> {
+    hir_db: &'a db::HirDb,
+    symbol_table: &'a symbol::SymbolTable,
+    typed_defs: HashMap<symbol::DefId, ty::TypeId>,
+}
+
+impl FieldResolver<'_> {
+    /// Resolves a field access to the span of the declared field's name, and
+    /// the field's type
+    fn resolve_field(&self, id: expr::ExprId) -> Option<(Span, ty::TypeId)> {
+        let field = match self.hir_db.get_expr(id) {
+            expr::Expr::Field(field) => field,
+            _ => return None,
+        };
+        let field_name = field.field.item();
+
+        match self.hir_db.get_type(self.aggregate_ty(field.lhs)?) {
+            ty::Type::Record(record) => Self::find_field(&record.fields, field_name),
+            ty::Type::Union(union) if union.tag_name.item() == field_name => {
+                Some((union.tag_name.span(), union.tag_ty))
+            }
+            ty::Type::Union(union) => union
+                .variants
+                .iter()
+                .find_map(|variant| Self::find_field(&variant.fields, field_name)),
+            _ => None,
+        }
+    }
+
+    fn find_field(fields: &[ty::Field], name: &str) -> Option<(Span, ty::TypeId)> {
+        fields.iter().find_map(|field| {
+            field
+                .names
+                .iter()
+                .find(|field_name| field_name.item() == name)
+                .map(|field_name| (field_name.span(), field.ty))
+        })
+    }
+
+    /// Gets the declared type of an expression, looking through type aliases
+    fn aggregate_ty(&self, id: expr::ExprId) -> Option<ty::TypeId> {
+        let ty = match self.hir_db.get_expr(id) {
+            expr::Expr::Name(expr::Name::Name(use_id)) => *self.typed_defs.get(&use_id.as_def())?,
+            expr::Expr::Field(_) => self.resolve_field(id)?.1,
+            expr::Expr::Paren(expr) => return self.aggregate_ty(expr.expr),
+            expr::Expr::Indirect(expr) => expr.ty,
+            expr::Expr::Deref(expr) => match self.hir_db.get_type(self.aggregate_ty(expr.expr)?) {
+                ty::Type::Pointer(pointer) => pointer.to,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        self.unalias_ty(ty)
+    }
+
+    /// Follows type names to the type that they name
+    fn unalias_ty(&self, mut ty: ty::TypeId) -> Option<ty::TypeId> {
+        // Forward declarations can make alias cycles
+        let mut seen = HashSet::new();
+
+        while let ty::Type::Name(use_id) = self.hir_db.get_type(ty) {
+            let def_id = self.resolve_forward(use_id.as_def());
+
+            if !seen.insert(def_id) {
+                return None;
+            }
+
+            ty = *self.typed_defs.get(&def_id)?;
+        }
+
+        Some(ty)
+    }
+
+    /// Gets the declaration resolving a forward declaration, or the same definition
+    /// if it isn't a forward declaration
+    fn resolve_forward(&self, def_id: symbol::DefId) -> symbol::DefId {
+        self.symbol_table
+            .iter_defs()
+            .find_map(|(resolving_def, _, symbol)| match symbol.kind {
+                symbol::SymbolKind::Resolved(forward_def) if forward_def == def_id => {
+                    Some(resolving_def)
+                }
+                _ => None,
+            })
+            .unwrap_or(def_id)
+    }
+}
@@ -0,0 +1,331 @@
+//! Definition & reference query tests
+use toc_hir::{db, unit};
+use toc_span::{Span, TextRange, TextSize};
+use unindent::unindent;
+
+use crate::query::{
+    find_definition, find_field_definition, find_references, hover_type, Reference,
+};
+
+const SHADOWING: &str = r#"
+var a : int
+begin
+    var a : real
+    a := 1.0
+end
+a := a + 1
+"#;
+
+const FIELDS: &str = r#"
+type inner : record
+    c : int
+end record
+type outer : record
+    a, b : inner
+end record
+type choice : union kind : boolean of
+    label true: d : int
+end union
+var o : outer
+var u : choice
+var i := o
+o.b.c := 1
+u.d := u.kind
+i.a := o.a
+"#;
+
+fn lower_source(source: &str) -> (db::HirDb, unit::UnitId) {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+
+    (hir_db.finish(), hir_res.id)
+}
+
+/// Gets the range of the `nth` occurrence of `pat` in `source`, not including
+/// occurrences inside of other words
+fn nth_range(source: &str, pat: &str, nth: usize) -> TextRange {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let (start, _) = source
+        .match_indices(pat)
+        .filter(|(start, _)| {
+            let before = source[..*start].chars().next_back();
+            let after = source[start + pat.len()..].chars().next();
+
+            before.into_iter().chain(after).all(|c| !is_word_char(c))
+        })
+        .nth(nth)
+        .unwrap();
+    let start = TextSize::from(start as u32);
+    TextRange::at(start, TextSize::of(pat))
+}
+
+#[test]
+fn definition_of_reference() {
+    let source = unindent(SHADOWING);
+    let (hir_db, unit_id) = lower_source(&source);
+
+    // Outer reference resolves to the outer declaration
+    let use_range = nth_range(&source, "a :=", 1);
+    let def = find_definition(&hir_db, unit_id, use_range.start()).unwrap();
+    assert_eq!(def.span.range, nth_range(&source, "a", 0));
+
+    // Cursor at the end of the name still resolves
+    let def = find_definition(&hir_db, unit_id, use_range.start() + TextSize::of("a")).unwrap();
+    assert_eq!(def.span.range, nth_range(&source, "a", 0));
+}
+
+#[test]
+fn definition_of_shadowed_reference() {
+    let source = unindent(SHADOWING);
+    let (hir_db, unit_id) = lower_source(&source);
+
+    // Inner reference resolves to the inner declaration
+    let use_range = nth_range(&source, "a :=", 0);
+    let def = find_definition(&hir_db, unit_id, use_range.start()).unwrap();
+    assert_eq!(def.span.range, nth_range(&source, "a", 1));
+}
+
+#[test]
+fn definition_of_declaration() {
+    let source = unindent(SHADOWING);
+    let (hir_db, unit_id) = lower_source(&source);
+
+    // Declarations resolve to themselves
+    let decl_range = nth_range(&source, "a", 1);
+    let def = find_definition(&hir_db, unit_id, decl_range.start()).unwrap();
+    assert_eq!(def.span.range, decl_range);
+}
+
+#[test]
+fn definition_of_nothing() {
+    let source = unindent(SHADOWING);
+    let (hir_db, unit_id) = lower_source(&source);
+
+    // Not on any name
+    let range = nth_range(&source, "begin", 0);
+    assert_eq!(find_definition(&hir_db, unit_id, range.start()), None);
+}
+
+#[test]
+fn definition_of_undeclared() {
+    let source = "var a := b";
+    let (hir_db, unit_id) = lower_source(source);
+
+    let range = nth_range(source, "b", 0);
+    assert_eq!(find_definition(&hir_db, unit_id, range.start()), None);
+}
+
+#[test]
+fn references_stay_in_scope() {
+    let source = unindent(SHADOWING);
+    let (hir_db, unit_id) = lower_source(&source);
+
+    let outer = find_definition(&hir_db, unit_id, nth_range(&source, "a", 0).start()).unwrap();
+    let inner = find_definition(&hir_db, unit_id, nth_range(&source, "a", 1).start()).unwrap();
+    assert_ne!(outer.def_id, inner.def_id);
+
+    let spans = |def_id, include_declaration| {
+        find_references(&hir_db, unit_id, def_id, include_declaration)
+            .into_iter()
+            .map(|reference| reference.span.range)
+            .collect::<Vec<_>>()
+    };
+
+    // Outer references don't include the inner ones
+    assert_eq!(
+        spans(outer.def_id, true),
+        vec![
+            nth_range(&source, "a", 0),
+            nth_range(&source, "a", 3),
+            nth_range(&source, "a", 4),
+        ]
+    );
+    assert_eq!(
+        spans(outer.def_id, false),
+        vec![nth_range(&source, "a", 3), nth_range(&source, "a", 4)]
+    );
+
+    // Inner references don't include the outer ones
+    assert_eq!(
+        spans(inner.def_id, true),
+        vec![nth_range(&source, "a", 1), nth_range(&source, "a", 2)]
+    );
+    assert_eq!(spans(inner.def_id, false), vec![nth_range(&source, "a", 2)]);
+}
+
+#[test]
+fn definition_of_var_reference() {
+    let source = "var a : int\na := 1";
+    let (hir_db, unit_id) = lower_source(source);
+
+    let use_range = nth_range(source, "a", 1);
+    let def = find_definition(&hir_db, unit_id, use_range.start()).unwrap();
+    assert_eq!(def.span.range, nth_range(source, "a", 0));
+}
+
+#[test]
+fn references_of_var() {
+    let source = unindent(
+        r#"
+        var a : int
+        a := 1
+        put a
+        var b := a + 1
+        "#,
+    );
+    let (hir_db, unit_id) = lower_source(&source);
+
+    let def = find_definition(&hir_db, unit_id, nth_range(&source, "a", 0).start()).unwrap();
+    let reference = |nth, is_declaration| Reference {
+        span: Span::new(None, nth_range(&source, "a", nth)),
+        is_declaration,
+    };
+
+    assert_eq!(
+        find_references(&hir_db, unit_id, def.def_id, true),
+        vec![
+            reference(0, true),
+            reference(1, false),
+            reference(2, false),
+            reference(3, false),
+        ]
+    );
+}
+
+#[test]
+fn field_definition_of_record_field() {
+    let source = unindent(FIELDS);
+    let (hir_db, unit_id) = lower_source(&source);
+    let field_def = |range: TextRange| {
+        find_field_definition(&hir_db, unit_id, range.start()).map(|span| span.range)
+    };
+
+    // Through nested records
+    assert_eq!(
+        field_def(nth_range(&source, "b", 1)),
+        Some(nth_range(&source, "b", 0))
+    );
+    assert_eq!(
+        field_def(nth_range(&source, "c", 1)),
+        Some(nth_range(&source, "c", 0))
+    );
+    // Declarations resolve to themselves
+    assert_eq!(
+        field_def(nth_range(&source, "a", 0)),
+        Some(nth_range(&source, "a", 0))
+    );
+    assert_eq!(
+        field_def(nth_range(&source, "a", 2)),
+        Some(nth_range(&source, "a", 0))
+    );
+}
+
+#[test]
+fn field_definition_of_union_field() {
+    let source = unindent(FIELDS);
+    let (hir_db, unit_id) = lower_source(&source);
+    let field_def = |range: TextRange| {
+        find_field_definition(&hir_db, unit_id, range.start()).map(|span| span.range)
+    };
+
+    assert_eq!(
+        field_def(nth_range(&source, "d", 1)),
+        Some(nth_range(&source, "d", 0))
+    );
+    assert_eq!(
+        field_def(nth_range(&source, "kind", 1)),
+        Some(nth_range(&source, "kind", 0))
+    );
+}
+
+#[test]
+fn field_definition_through_pointer() {
+    let source = unindent(
+        r#"
+        type r : record b : int end record
+        var p : ^r
+        ^p.b := 1
+        "#,
+    );
+    let (hir_db, unit_id) = lower_source(&source);
+
+    let range = nth_range(&source, "b", 1);
+    assert_eq!(
+        find_field_definition(&hir_db, unit_id, range.start()).map(|span| span.range),
+        Some(nth_range(&source, "b", 0))
+    );
+}
+
+#[test]
+fn field_definition_of_inferred_type() {
+    let source = unindent(FIELDS);
+    let (hir_db, unit_id) = lower_source(&source);
+
+    // Fields of variables with an inferred type aren't resolved
+    let range = nth_range(&source, "a", 1);
+    assert_eq!(find_field_definition(&hir_db, unit_id, range.start()), None);
+    // Not on any field
+    let range = nth_range(&source, "var", 0);
+    assert_eq!(find_field_definition(&hir_db, unit_id, range.start()), None);
+}
+
+#[test]
+fn hover_literal() {
+    let source = "var a := 1.0";
+    let (hir_db, unit_id) = lower_source(source);
+
+    let range = nth_range(source, "1.0", 0);
+    assert_eq!(
+        hover_type(&hir_db, unit_id, range.start()).as_deref(),
+        Some("real")
+    );
+}
+
+#[test]
+fn hover_binary_expr() {
+    let source = "var a : nat1\nvar b := a + 2";
+    let (hir_db, unit_id) = lower_source(source);
+
+    // Innermost expression is the operand
+    let range = nth_range(source, "2", 0);
+    assert_eq!(
+        hover_type(&hir_db, unit_id, range.start()).as_deref(),
+        Some("{integer}")
+    );
+    // Between operands is the whole expression
+    let range = nth_range(source, "+", 0);
+    assert_eq!(
+        hover_type(&hir_db, unit_id, range.start()).as_deref(),
+        Some("nat")
+    );
+}
+
+#[test]
+fn hover_var_use() {
+    let source = "type t : record f : string(3) end record\nvar a : t\nvar i : int1\nput a.f, i";
+    let (hir_db, unit_id) = lower_source(source);
+
+    let range = nth_range(source, "f", 1);
+    assert_eq!(
+        hover_type(&hir_db, unit_id, range.start()).as_deref(),
+        Some("string(3)")
+    );
+    let range = nth_range(source, "i", 1);
+    assert_eq!(
+        hover_type(&hir_db, unit_id, range.start()).as_deref(),
+        Some("int1")
+    );
+}
+
+#[test]
+fn hover_between_exprs() {
+    let source = "var a := 1\n\nvar b := 2";
+    let (hir_db, unit_id) = lower_source(source);
+
+    // On the empty line
+    let offset = TextSize::of("var a := 1\n");
+    assert_eq!(hover_type(&hir_db, unit_id, offset), None);
+    let range = nth_range(source, "var", 1);
+    assert_eq!(hover_type(&hir_db, unit_id, range.start()), None);
+}
@@ -0,0 +1,123 @@
+//! Unreachable statement detection
+#[cfg(test)]
+mod test;
+
+use toc_hir::{db, stmt, ty, unit};
+use toc_reporting::{MessageSink, ReportMessage, Stage};
+
+use crate::cfg::Cfg;
+
+/// Reports statements which can never be reached, since they come after a
+/// statement that never completes (e.g. `quit`, `return`, or a `loop` without
+/// an `exit`).
+///
+/// `if` and `case` statements where every branch never completes also never complete.
+/// Only the first unreachable statement in each statement list is reported, and
+/// subprogram and process bodies are checked on their own.
+///
+/// Functions without a named result which can reach the end of their body are also
+/// reported, since they would finish without giving back a value.
+pub fn check_unreachable(hir_db: db::HirDb, unit: &unit::Unit) -> Vec<ReportMessage> {
+    let mut checker = ReachabilityCheck {
+        hir_db: &hir_db,
+        unit,
+        reporter: MessageSink::new(Stage::Analyze),
+    };
+
+    let cfg = Cfg::build(&hir_db, &unit.stmts);
+    checker.check_stmt_list(&cfg, &unit.stmts);
+    checker.reporter.finish()
+}
+
+struct ReachabilityCheck<'a> {
+    hir_db: &'a db::HirDb,
+    unit: &'a unit::Unit,
+    reporter: MessageSink,
+}
+
+impl ReachabilityCheck<'_> {
+    fn check_stmt_list(&mut self, cfg: &Cfg, stmts: &[stmt::StmtId]) {
+        let mut previous: Option<stmt::StmtId> = None;
+
+        for stmt_id in stmts {
+            if !cfg.is_reachable(*stmt_id) {
+                // The first statement in a list is only unreachable if the list's
+                // owner is also unreachable, which would've been reported instead
+                if let Some(diverge_id) = previous {
+                    let span = self.hir_db.get_span((*stmt_id).into());
+                    let diverge_span = self.hir_db.get_span(diverge_id.into());
+
+                    self.reporter
+                        .warn_detailed("unreachable statement", span)
+                        .with_note(
+                            "any code following this statement is unreachable",
+                            diverge_span,
+                        )
+                        .finish();
+                }
+
+                return;
+            }
+
+            match self.hir_db.get_stmt(*stmt_id) {
+                stmt::Stmt::Block(block) => self.check_stmt_list(cfg, &block.stmts),
+                stmt::Stmt::Module(module) => self.check_stmt_list(cfg, &module.stmts),
+                stmt::Stmt::For(stmt) => self.check_stmt_list(cfg, &stmt.stmts),
+                stmt::Stmt::Loop(stmt) => self.check_stmt_list(cfg, &stmt.stmts),
+                stmt::Stmt::If(stmt) => {
+                    self.check_stmt_list(cfg, &stmt.true_branch);
+
+                    match &stmt.false_branch {
+                        stmt::FalseBranch::None => {}
+                        stmt::FalseBranch::ElseIf(elseif) => self.check_stmt_list(cfg, &[*elseif]),
+                        stmt::FalseBranch::Else(stmts) => self.check_stmt_list(cfg, stmts),
+                    }
+                }
+                stmt::Stmt::Case(stmt) => {
+                    for arm in &stmt.arms {
+                        self.check_stmt_list(cfg, &arm.stmts);
+                    }
+                }
+                stmt::Stmt::Subprogram(decl) => self.check_subprogram(decl),
+                stmt::Stmt::Process(decl) => {
+                    self.check_body(&decl.stmts);
+                }
+                _ => {}
+            }
+
+            previous = Some(*stmt_id);
+        }
+    }
+
+    /// Checks the body of a subprogram or process, which has its own flow
+    fn check_body(&mut self, stmts: &[stmt::StmtId]) -> Cfg {
+        let cfg = Cfg::build(self.hir_db, stmts);
+        self.check_stmt_list(&cfg, stmts);
+        cfg
+    }
+
+    fn check_subprogram(&mut self, decl: &stmt::Subprogram) {
+        let cfg = self.check_body(&decl.stmts);
+
+        // Without a result name, running off the end of a function doesn't give back anything.
+        // Bodies which can only end by `quit` never need a `result`.
+        if decl.kind == ty::SubprogramKind::Function
+            && decl.result_name.is_none()
+            && cfg.can_fall_through()
+        {
+            let name = &self.unit.symbol_table.get_symbol(decl.def_id).name;
+            let span = self.unit.symbol_table.get_def_span(decl.def_id);
+
+            self.reporter
+                .warn_detailed("missing `result` statement", span)
+                .with_info(
+                    &format!(
+                        "`{}` can reach the end of its body without giving back a value",
+                        name
+                    ),
+                    None,
+                )
+                .finish();
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Unreachable statement tests
+use toc_hir::db;
+
+fn unreachable_messages(source: &str) -> Vec<String> {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+    let hir_db = hir_db.finish();
+
+    let unit = hir_db.get_unit(hir_res.id);
+    super::check_unreachable(hir_db.clone(), unit)
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect()
+}
+
+#[test]
+fn stmt_after_quit() {
+    assert_eq!(
+        unreachable_messages("quit\nput 1"),
+        vec!["warn at 5..10: unreachable statement\n| note for 0..4: any code following this statement is unreachable"]
+    );
+}
+
+#[test]
+fn only_first_unreachable_stmt() {
+    assert_eq!(
+        unreachable_messages("quit\nput 1\nput 2\nquit\nput 3").len(),
+        1
+    );
+}
+
+#[test]
+fn stmt_after_diverging_block() {
+    // The whole block diverges
+    assert_eq!(
+        unreachable_messages("begin put 1 quit end\nput 2"),
+        vec!["warn at 21..26: unreachable statement\n| note for 0..20: any code following this statement is unreachable"]
+    );
+    // Reported inside of the block too
+    assert_eq!(
+        unreachable_messages("begin quit put 1 end"),
+        vec!["warn at 11..16: unreachable statement\n| note for 6..10: any code following this statement is unreachable"]
+    );
+}
+
+#[test]
+fn no_unreachable_stmts() {
+    assert!(unreachable_messages("put 1\nput 2").is_empty());
+    assert!(unreachable_messages("put 1\nquit").is_empty());
+    assert!(unreachable_messages("begin put 1 end\nput 2").is_empty());
+}
+
+#[test]
+fn stmt_after_return() {
+    assert_eq!(
+        unreachable_messages("procedure p return put 1 end p"),
+        vec!["warn at 19..24: unreachable statement\n| note for 12..18: any code following this statement is unreachable"]
+    );
+    // Also leaves the main program
+    assert_eq!(unreachable_messages("return\nput 1").len(), 1);
+}
+
+#[test]
+fn stmt_after_result() {
+    assert_eq!(
+        unreachable_messages("function f : int result 1 put 1 end f"),
+        vec!["warn at 26..31: unreachable statement\n| note for 17..25: any code following this statement is unreachable"]
+    );
+}
+
+#[test]
+fn stmt_after_infinite_loop() {
+    assert_eq!(
+        unreachable_messages("loop end loop\nput 1"),
+        vec!["warn at 14..19: unreachable statement\n| note for 0..13: any code following this statement is unreachable"]
+    );
+    // Exits in nested loops only leave the nested loop
+    assert_eq!(
+        unreachable_messages("loop loop exit end loop end loop\nput 1").len(),
+        1
+    );
+}
+
+#[test]
+fn stmt_after_loop_with_exit() {
+    assert!(unreachable_messages("loop exit end loop\nput 1").is_empty());
+    assert!(unreachable_messages("loop exit when true end loop\nput 1").is_empty());
+    assert!(unreachable_messages("loop if true then exit end if end loop\nput 1").is_empty());
+}
+
+#[test]
+fn stmt_after_for_loop() {
+    assert!(unreachable_messages("for i : 1 .. 2 end for\nput 1").is_empty());
+    assert_eq!(
+        unreachable_messages("for i : 1 .. 2 quit put i end for").len(),
+        1
+    );
+}
+
+#[test]
+fn stmt_after_exit() {
+    assert_eq!(
+        unreachable_messages("loop exit put 1 end loop"),
+        vec!["warn at 10..15: unreachable statement\n| note for 5..9: any code following this statement is unreachable"]
+    );
+}
+
+#[test]
+fn stmt_after_diverging_if() {
+    assert_eq!(
+        unreachable_messages("if true then quit else return end if\nput 1"),
+        vec!["warn at 37..42: unreachable statement\n| note for 0..36: any code following this statement is unreachable"]
+    );
+    assert_eq!(
+        unreachable_messages("if true then quit elsif false then quit else quit end if\nput 1")
+            .len(),
+        1
+    );
+    // Not every branch diverges
+    assert!(unreachable_messages("if true then quit end if\nput 1").is_empty());
+    assert!(
+        unreachable_messages("if true then quit elsif false then quit end if\nput 1").is_empty()
+    );
+    assert!(unreachable_messages("if true then quit else put 2 end if\nput 1").is_empty());
+}
+
+#[test]
+fn stmt_after_diverging_case() {
+    assert_eq!(
+        unreachable_messages("case 1 of label 1 : quit label : return end case\nput 1"),
+        vec!["warn at 49..54: unreachable statement\n| note for 0..48: any code following this statement is unreachable"]
+    );
+    // Without a default arm, unmatched values go past the statement
+    assert!(unreachable_messages("case 1 of label 1 : quit end case\nput 1").is_empty());
+    // Not every arm diverges
+    assert!(unreachable_messages("case 1 of label 1 : quit label : end case\nput 1").is_empty());
+}
+
+#[test]
+fn unreachable_in_branches() {
+    assert_eq!(
+        unreachable_messages("if true then quit put 1 else quit put 2 end if").len(),
+        2
+    );
+    assert_eq!(
+        unreachable_messages("case 1 of label 1 : quit put 1 end case").len(),
+        1
+    );
+}
+
+#[test]
+fn missing_result() {
+    assert_eq!(
+        unreachable_messages("function f : int end f"),
+        vec!["warn at 9..10: missing `result` statement\n| info: `f` can reach the end of its body without giving back a value"]
+    );
+    // Not every branch gives back a value
+    assert_eq!(
+        unreachable_messages("function f : int if true then result 1 end if end f").len(),
+        1
+    );
+    assert_eq!(
+        unreachable_messages("function f : int loop exit when true result 1 end loop end f").len(),
+        1
+    );
+}
+
+#[test]
+fn no_missing_result() {
+    assert!(unreachable_messages("function f : int result 1 end f").is_empty());
+    assert!(unreachable_messages(
+        "function f : int if true then result 1 else result 2 end if end f"
+    )
+    .is_empty());
+    assert!(unreachable_messages("function f : int loop end loop end f").is_empty());
+    // Named results hold the value to give back
+    assert!(unreachable_messages("function f r : int end f").is_empty());
+    assert!(unreachable_messages("procedure p end p").is_empty());
+}
+
+#[test]
+fn quit_instead_of_result() {
+    // Quitting never finishes the function, so no result is needed
+    assert!(unreachable_messages("function f : int quit end f").is_empty());
+    assert!(
+        unreachable_messages("function f : int if true then result 1 else quit end if end f")
+            .is_empty()
+    );
+    // Results after a quit are never reached
+    assert_eq!(
+        unreachable_messages("function f : int quit result 1 end f"),
+        vec!["warn at 22..30: unreachable statement\n| note for 17..21: any code following this statement is unreachable"]
+    );
+}
@@ -0,0 +1,222 @@
+//! Detection of recursive types with an infinite size, and type aliases of themselves
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use indexmap::{IndexMap, IndexSet};
+use toc_hir::{db, stmt, symbol, ty, unit, visitor::HirVisitor};
+use toc_reporting::{MessageSink, ReportMessage, Stage};
+use toc_span::Span;
+
+/// Reports type declarations which contain themselves without going through
+/// any indirection (i.e. a pointer or a collection), giving them an infinite size.
+/// Type aliases which only refer back to themselves are reported at the
+/// declaration closing the cycle.
+///
+/// Type names are only declared after their definition, so such cycles can
+/// only be formed through forward declarations.
+pub fn check_recursive_types(hir_db: db::HirDb, unit: &unit::Unit) -> Vec<ReportMessage> {
+    let visitor = TypeDeclVisitor {
+        decls: Default::default(),
+    };
+    unit.walk_nodes(hir_db.clone(), &visitor);
+
+    let decls = visitor.decls.into_inner();
+    let resolved_forwards = decls
+        .keys()
+        .filter_map(|def_id| match unit.symbol_table.get_symbol(*def_id).kind {
+            symbol::SymbolKind::Resolved(forward_def) => Some((forward_def, *def_id)),
+            _ => None,
+        })
+        .collect();
+
+    let finder = CycleFinder {
+        hir_db,
+        decls: &decls,
+        resolved_forwards,
+    };
+    let mut reported = HashSet::new();
+    let mut reporter = MessageSink::new(Stage::Analyze);
+
+    for (def_id, ty) in &decls {
+        // Types in an already reported cycle would only report the same cycle again
+        if reported.contains(def_id) {
+            continue;
+        }
+
+        let mut path = CyclePath::default();
+
+        if !finder.find_cycle(*def_id, *ty, &mut path) {
+            continue;
+        }
+
+        if path.fields.is_empty() {
+            report_alias_cycle(unit, *def_id, &path, &mut reporter);
+        } else {
+            let name = &unit.symbol_table.get_symbol(*def_id).name;
+            let span = unit.symbol_table.get_def_span(*def_id);
+
+            let mut builder =
+                reporter.error_detailed(&format!("type `{}` has infinite size", name), span);
+
+            for field in &path.fields {
+                builder = builder.with_note("recursive without indirection", *field);
+            }
+
+            builder
+                .with_info(
+                    "use a pointer somewhere in the cycle to make the size finite",
+                    None,
+                )
+                .finish();
+        }
+
+        reported.insert(*def_id);
+        reported.extend(path.types);
+    }
+
+    reporter.finish()
+}
+
+/// Reports a cycle of type aliases which never go through another type.
+///
+/// The cycle is reported at the last declaration in it, since that's the
+/// declaration that closes the cycle.
+fn report_alias_cycle(
+    unit: &unit::Unit,
+    start: symbol::DefId,
+    path: &CyclePath,
+    reporter: &mut MessageSink,
+) {
+    let symbol_table = &unit.symbol_table;
+    let cycle: Vec<_> = std::iter::once(start)
+        .chain(path.types.iter().copied())
+        .collect();
+    let closing = *cycle
+        .iter()
+        .max_by_key(|def_id| symbol_table.get_def_span(**def_id).range.start())
+        .unwrap();
+
+    let name = &symbol_table.get_symbol(closing).name;
+    let mut builder = reporter.error_detailed(
+        &format!("type `{}` is an alias of itself", name),
+        symbol_table.get_def_span(closing),
+    );
+
+    for def_id in cycle.iter().filter(|def_id| **def_id != closing) {
+        let through = &symbol_table.get_symbol(*def_id).name;
+        builder = builder.with_note(
+            &format!("cycle goes through `{}`", through),
+            symbol_table.get_def_span(*def_id),
+        );
+    }
+
+    builder
+        .with_info("types can only refer to themselves through a pointer", None)
+        .finish();
+}
+
+/// Declarations of type aliases
+struct TypeDeclVisitor {
+    decls: RefCell<IndexMap<symbol::DefId, ty::TypeId>>,
+}
+
+impl HirVisitor for TypeDeclVisitor {
+    fn visit_type_decl(&self, _id: stmt::StmtId, decl: &stmt::Type) {
+        if let stmt::TypeDefKind::Alias(ty) = decl.type_def {
+            self.decls.borrow_mut().insert(decl.def_id, ty);
+        }
+    }
+}
+
+/// Fields (and array element types) and types passed through on the way back to the starting type
+#[derive(Default)]
+struct CyclePath {
+    fields: Vec<Span>,
+    types: IndexSet<symbol::DefId>,
+}
+
+struct CycleFinder<'a> {
+    hir_db: db::HirDb,
+    decls: &'a IndexMap<symbol::DefId, ty::TypeId>,
+    /// Forward declarations, mapped to their resolving declarations
+    resolved_forwards: HashMap<symbol::DefId, symbol::DefId>,
+}
+
+impl CycleFinder<'_> {
+    /// Checks if `ty` contains the type declared by `target` without any indirection,
+    /// recording the path taken to get back to it
+    fn find_cycle(&self, target: symbol::DefId, ty: ty::TypeId, path: &mut CyclePath) -> bool {
+        match self.hir_db.get_type(ty) {
+            ty::Type::Name(use_id) => {
+                let def_id = use_id.as_def();
+                let def_id = self.resolved_forwards.get(&def_id).unwrap_or(&def_id);
+
+                if *def_id == target {
+                    return true;
+                }
+
+                // Cycles not going through the target are found when starting from
+                // one of the types in that cycle
+                let aliased = match self.decls.get(def_id) {
+                    Some(aliased) if path.types.insert(*def_id) => *aliased,
+                    _ => return false,
+                };
+
+                if self.find_cycle(target, aliased, path) {
+                    return true;
+                }
+
+                path.types.pop();
+                false
+            }
+            ty::Type::Record(record) => self.find_in_fields(target, &record.fields, path),
+            ty::Type::Union(union) => union
+                .variants
+                .iter()
+                .any(|variant| self.find_in_fields(target, &variant.fields, path)),
+            ty::Type::Array(array) => {
+                // Elements are stored inline, like fields
+                path.fields.push(self.hir_db.get_span(ty.into()));
+
+                if self.find_cycle(target, array.elem_ty, path) {
+                    return true;
+                }
+
+                path.fields.pop();
+                false
+            }
+            // Pointers and collections are indirection, so they always have a finite size
+            ty::Type::Pointer(_) | ty::Type::Collection(_) => false,
+            // Sets only store index types, and subprograms are references to code
+            ty::Type::Set(_) | ty::Type::Subprogram(_) => false,
+            ty::Type::Missing
+            | ty::Type::Primitive(_)
+            | ty::Type::Range(_)
+            | ty::Type::Enum(_)
+            | ty::Type::Condition(_) => false,
+        }
+    }
+
+    fn find_in_fields(
+        &self,
+        target: symbol::DefId,
+        fields: &[ty::Field],
+        path: &mut CyclePath,
+    ) -> bool {
+        for field in fields {
+            // Field names are guaranteed to be non-empty
+            path.fields.push(field.names[0].span());
+
+            if self.find_cycle(target, field.ty, path) {
+                return true;
+            }
+
+            path.fields.pop();
+        }
+
+        false
+    }
+}
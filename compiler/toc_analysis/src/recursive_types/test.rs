@@ -0,0 +1,111 @@
+//! Recursive type tests
+use toc_hir::db;
+
+fn recursive_type_messages(source: &str) -> Vec<String> {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+    let hir_db = hir_db.finish();
+
+    let unit = hir_db.get_unit(hir_res.id);
+    super::check_recursive_types(hir_db.clone(), unit)
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect()
+}
+
+#[test]
+fn direct_recursion() {
+    assert_eq!(
+        recursive_type_messages(
+            "type node : forward\ntype node : record value : int next : node end record"
+        ),
+        vec![concat!(
+            "error at 25..29: type `node` has infinite size\n",
+            "| note for 51..55: recursive without indirection\n",
+            "| info: use a pointer somewhere in the cycle to make the size finite"
+        )]
+    );
+}
+
+#[test]
+fn mutual_recursion() {
+    // Only reported once for the whole cycle
+    assert_eq!(
+        recursive_type_messages(
+            r#"
+type b : forward
+type a : record b_f : b end record
+type b : union kind : boolean of label true: a_f : a end union
+"#
+        ),
+        vec![concat!(
+            "error at 23..24: type `a` has infinite size\n",
+            "| note for 34..37: recursive without indirection\n",
+            "| note for 98..101: recursive without indirection\n",
+            "| info: use a pointer somewhere in the cycle to make the size finite"
+        )]
+    );
+}
+
+#[test]
+fn recursion_through_array() {
+    // Array elements are stored inline, so they aren't indirection
+    assert_eq!(
+        recursive_type_messages("type node : forward\ntype node : array 1 .. 2 of node"),
+        vec![concat!(
+            "error at 25..29: type `node` has infinite size\n",
+            "| note for 32..52: recursive without indirection\n",
+            "| info: use a pointer somewhere in the cycle to make the size finite"
+        )]
+    );
+}
+
+#[test]
+fn recursion_through_indirection() {
+    assert!(recursive_type_messages(
+        "type node : forward\ntype node : record next : pointer to node end record"
+    )
+    .is_empty());
+    assert!(recursive_type_messages(
+        "type node : forward\ntype nodes : collection of node\ntype node : record next : pointer to nodes end record"
+    )
+    .is_empty());
+    // Not recursive at all
+    assert!(recursive_type_messages(
+        "type a : record f : int end record\ntype b : record a_f, a_g : a end record"
+    )
+    .is_empty());
+}
+
+#[test]
+fn alias_of_itself() {
+    assert_eq!(
+        recursive_type_messages("type a : forward\ntype a : a"),
+        vec![concat!(
+            "error at 22..23: type `a` is an alias of itself\n",
+            "| info: types can only refer to themselves through a pointer"
+        )]
+    );
+}
+
+#[test]
+fn alias_cycle() {
+    // Reported at the declaration closing the cycle
+    assert_eq!(
+        recursive_type_messages("type a : forward\ntype b : a\ntype a : b"),
+        vec![concat!(
+            "error at 33..34: type `a` is an alias of itself\n",
+            "| note for 22..23: cycle goes through `b`\n",
+            "| info: types can only refer to themselves through a pointer"
+        )]
+    );
+}
+
+#[test]
+fn alias_cycle_through_pointer() {
+    assert!(recursive_type_messages("type list : forward\ntype list : ^list").is_empty());
+    assert!(
+        recursive_type_messages("type a : forward\ntype b : pointer to a\ntype a : b").is_empty()
+    );
+}
@@ -1,14 +1,18 @@
 //! Representation of Turing types
 pub mod rules;
+#[cfg(test)]
+mod test;
 
+use std::convert::TryInto;
 use std::fmt::{self, Debug};
 use std::num::NonZeroU32;
 use std::ops::Deref;
 
 use indexmap::IndexMap;
+use toc_hir::ty::{ConditionKind, SubprogramKind};
 
 /// A type reference, for each unique type
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TyRef(internment::Intern<Type>);
 
 impl fmt::Debug for TyRef {
@@ -18,6 +22,12 @@ impl fmt::Debug for TyRef {
     }
 }
 
+impl fmt::Display for TyRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0.deref(), f)
+    }
+}
+
 impl Deref for TyRef {
     type Target = Type;
 
@@ -26,11 +36,25 @@ impl Deref for TyRef {
     }
 }
 
+/// How an operator overloaded between logical and bitwise operations
+/// (`and`, `or`, `xor`, and `not`) was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpKind {
+    /// Operates on booleans
+    Logical,
+    /// Operates on integers
+    Bitwise,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DefKind {
     Type(TyRef),
     Const(TyRef),
     Var(TyRef),
+    /// Processes aren't values, so they don't have a type
+    Process,
+    /// Modules aren't values either
+    Module,
     Error(TyRef),
 }
 
@@ -40,6 +64,12 @@ pub struct TyCtx {
     ty_table: IndexMap<toc_hir::ty::TypeId, TyRef>,
     // Store def id type here since it'll be needed for bytecode gen
     def_type: IndexMap<toc_hir::symbol::DefId, DefKind>,
+    // Resolved kinds of overloaded operators, also needed for bytecode gen
+    op_kinds: IndexMap<toc_hir::expr::ExprId, OpKind>,
+    // Types of every checked expression, for editor tooling
+    expr_types: IndexMap<toc_hir::expr::ExprId, TyRef>,
+    // If runtime checks apply to pointer derefs and subscripts, also needed for bytecode gen
+    checked_ops: IndexMap<toc_hir::expr::ExprId, toc_hir::stmt::Checkedness>,
 }
 
 impl TyCtx {
@@ -47,6 +77,9 @@ impl TyCtx {
         Self {
             ty_table: IndexMap::new(),
             def_type: IndexMap::new(),
+            op_kinds: IndexMap::new(),
+            expr_types: IndexMap::new(),
+            checked_ops: IndexMap::new(),
         }
     }
 
@@ -69,6 +102,43 @@ impl TyCtx {
     pub fn get_def_id_kind(&self, def_id: toc_hir::symbol::DefId) -> Option<DefKind> {
         self.def_type.get(&def_id).copied()
     }
+
+    pub fn map_op_kind(&mut self, expr_id: toc_hir::expr::ExprId, kind: OpKind) {
+        self.op_kinds.insert(expr_id, kind);
+    }
+
+    pub fn get_op_kind(&self, expr_id: toc_hir::expr::ExprId) -> Option<OpKind> {
+        self.op_kinds.get(&expr_id).copied()
+    }
+
+    pub fn map_expr_type(&mut self, expr_id: toc_hir::expr::ExprId, ty_ref: TyRef) {
+        self.expr_types.insert(expr_id, ty_ref);
+    }
+
+    pub fn get_expr_type(&self, expr_id: toc_hir::expr::ExprId) -> Option<TyRef> {
+        self.expr_types.get(&expr_id).copied()
+    }
+
+    pub fn map_checkedness(
+        &mut self,
+        expr_id: toc_hir::expr::ExprId,
+        checkedness: toc_hir::stmt::Checkedness,
+    ) {
+        self.checked_ops.insert(expr_id, checkedness);
+    }
+
+    /// Gets whether runtime checks apply to a pointer deref or subscript expression,
+    /// or `None` for any other expression
+    pub fn get_checkedness(
+        &self,
+        expr_id: toc_hir::expr::ExprId,
+    ) -> Option<toc_hir::stmt::Checkedness> {
+        self.checked_ops.get(&expr_id).copied()
+    }
+
+    pub fn iter_expr_types(&self) -> impl Iterator<Item = (toc_hir::expr::ExprId, TyRef)> + '_ {
+        self.expr_types.iter().map(|(id, ty)| (*id, *ty))
+    }
 }
 
 impl Default for TyCtx {
@@ -92,6 +162,29 @@ pub(crate) fn pretty_dump_typectx(ty_ctx: &TyCtx) -> String {
         s.push_str(&format!("    {:?} {:?}\n", k, v))
     }
 
+    // Operator kinds, only if there are any
+    if !ty_ctx.op_kinds.is_empty() {
+        s.push_str("op_kinds:\n");
+        for (k, v) in ty_ctx.op_kinds.iter() {
+            s.push_str(&format!("    {:?} {:?}\n", k, v))
+        }
+    }
+
+    // Ops without runtime checks, only if there are any
+    let unchecked_ops = ty_ctx
+        .checked_ops
+        .iter()
+        .filter(|(_, checkedness)| **checkedness == toc_hir::stmt::Checkedness::Unchecked)
+        .map(|(k, _)| k)
+        .collect::<Vec<_>>();
+
+    if !unchecked_ops.is_empty() {
+        s.push_str("unchecked_ops:\n");
+        for k in unchecked_ops {
+            s.push_str(&format!("    {:?}\n", k))
+        }
+    }
+
     s
 }
 
@@ -121,6 +214,326 @@ pub enum Type {
     CharN(SeqSize),
     /// Fixed-size string type
     StringN(SeqSize),
+    /// Record type
+    Record(Record),
+    /// Union type
+    Union(Union),
+    /// Range type, over integers, chars, or booleans
+    Range(Range),
+    /// Enum type
+    Enum(Enum),
+    /// Type exported as `opaque`, as seen from outside of the defining unit
+    Opaque(Opaque),
+    /// Pointer type
+    Pointer(Pointer),
+    /// Collection type
+    Collection(Collection),
+    /// Array type
+    Array(Array),
+    /// Set type, over an index type
+    Set(Set),
+    /// Procedure or function type
+    Subprogram(Subprogram),
+    /// Type of `nil`, optionally belonging to a specific collection or class.
+    /// Bare `nil` is assignable to any pointer.
+    Nil(Option<TyRef>),
+    /// Condition type, only usable with `wait` and `signal`
+    Condition(ConditionKind),
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Error => f.write_str("{error}"),
+            Type::Boolean => f.write_str("boolean"),
+            Type::Int(IntSize::Int1) => f.write_str("int1"),
+            Type::Int(IntSize::Int2) => f.write_str("int2"),
+            Type::Int(IntSize::Int4) => f.write_str("int4"),
+            Type::Int(IntSize::Int) => f.write_str("int"),
+            Type::Nat(NatSize::Nat1) => f.write_str("nat1"),
+            Type::Nat(NatSize::Nat2) => f.write_str("nat2"),
+            Type::Nat(NatSize::Nat4) => f.write_str("nat4"),
+            Type::Nat(NatSize::Nat) => f.write_str("nat"),
+            Type::Nat(NatSize::AddressInt) => f.write_str("addressint"),
+            Type::Real(RealSize::Real4) => f.write_str("real4"),
+            Type::Real(RealSize::Real8) => f.write_str("real8"),
+            Type::Real(RealSize::Real) => f.write_str("real"),
+            Type::Integer => f.write_str("{integer}"),
+            Type::Char => f.write_str("char"),
+            Type::String => f.write_str("string"),
+            Type::CharN(size) => write!(f, "char({})", size),
+            Type::StringN(size) => write!(f, "string({})", size),
+            // Aggregate types are shown by name, since their definitions can be
+            // arbitrarily large (or contain themselves through pointers)
+            Type::Record(Record {
+                name: Some(name), ..
+            })
+            | Type::Union(Union {
+                name: Some(name), ..
+            })
+            | Type::Collection(Collection {
+                name: Some(name), ..
+            })
+            | Type::Enum(Enum {
+                name: Some(name), ..
+            }) => f.write_str(name),
+            Type::Record(_) => f.write_str("record"),
+            Type::Union(_) => f.write_str("union"),
+            Type::Enum(_) => f.write_str("enum"),
+            Type::Range(range) => fmt_range(range, f),
+            Type::Opaque(opaque) => f.write_str(&opaque.name),
+            Type::Pointer(pointer) if pointer.is_checked => write!(f, "^{}", pointer.to),
+            Type::Pointer(pointer) => write!(f, "unchecked ^{}", pointer.to),
+            Type::Collection(collection) => write!(f, "collection of {}", collection.elem),
+            Type::Array(array) => {
+                if array.is_flexible {
+                    f.write_str("flexible ")?;
+                }
+
+                f.write_str("array ")?;
+                fmt_list(&array.ranges, f)?;
+                write!(f, " of {}", array.elem)
+            }
+            Type::Set(set) => write!(f, "set of {}", set.elem),
+            Type::Subprogram(subprogram) => {
+                f.write_str(match subprogram.kind {
+                    SubprogramKind::Procedure => "procedure",
+                    SubprogramKind::Function => "function",
+                })?;
+
+                if let Some(params) = &subprogram.params {
+                    f.write_str(" (")?;
+                    for (idx, param) in params.iter().enumerate() {
+                        if idx > 0 {
+                            f.write_str(", ")?;
+                        }
+                        if param.is_var {
+                            f.write_str("var ")?;
+                        }
+                        write!(f, "{}", param.ty)?;
+                    }
+                    f.write_str(")")?;
+                }
+
+                match subprogram.result {
+                    Some(result) => write!(f, " : {}", result),
+                    None => Ok(()),
+                }
+            }
+            Type::Nil(Some(collection)) => write!(f, "nil({})", collection),
+            Type::Nil(None) => f.write_str("nil"),
+            Type::Condition(kind) => f.write_str(match kind {
+                ConditionKind::Normal => "condition",
+                ConditionKind::Priority => "priority condition",
+                ConditionKind::Deferred => "deferred condition",
+                ConditionKind::Timeout => "timeout condition",
+            }),
+        }
+    }
+}
+
+/// Renders a range with its bounds, in the style of the range's base type
+fn fmt_range(range: &Range, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let fmt_bound = |f: &mut fmt::Formatter<'_>, ordinal: i128| match &*range.base {
+        Type::Boolean => write!(f, "{}", ordinal != 0),
+        Type::Char => match ordinal.try_into().ok().and_then(char::from_u32) {
+            Some(c) => write!(f, "'{}'", c.escape_default()),
+            None => write!(f, "chr({})", ordinal),
+        },
+        _ => write!(f, "{}", ordinal),
+    };
+
+    fmt_bound(f, range.start)?;
+    f.write_str(" .. ")?;
+
+    match range.size {
+        Some(size) => fmt_bound(f, range.start + i128::from(size) - 1),
+        None => f.write_str("*"),
+    }
+}
+
+/// Renders a comma separated list of types
+fn fmt_list(tys: &[TyRef], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (idx, ty) in tys.iter().enumerate() {
+        if idx > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", ty)?;
+    }
+
+    Ok(())
+}
+
+/// Record type, with all of the fields flattened out
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Record {
+    /// Defining type node.
+    /// Records are nominally typed, so this keeps structurally identical
+    /// records distinct.
+    pub def: toc_hir::ty::TypeId,
+    /// Declared name of the record, if it has one
+    pub name: Option<String>,
+    pub fields: Vec<Field>,
+}
+
+/// Union type, with all of the variant fields flattened out
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Union {
+    /// Defining type node.
+    /// Unions are nominally typed, so this keeps structurally identical
+    /// unions distinct.
+    pub def: toc_hir::ty::TypeId,
+    /// Declared name of the union, if it has one
+    pub name: Option<String>,
+    /// Union tag, typed as the tag's range type
+    pub tag: Field,
+    pub variants: Vec<Vec<Field>>,
+}
+
+/// Enum type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Enum {
+    /// Defining type node.
+    /// Enums are nominally typed, so this keeps structurally identical
+    /// enums distinct.
+    pub def: toc_hir::ty::TypeId,
+    /// Declared name of the enum, if it has one
+    pub name: Option<String>,
+    /// Names of the variants, in ordinal order
+    pub variants: Vec<String>,
+}
+
+/// Range type, with compile-time bounds
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Range {
+    /// Type of the range's values
+    pub base: TyRef,
+    /// Ordinal value of the start bound
+    pub start: i128,
+    /// Number of elements in the range, saturated at the size limit.
+    /// `None` if the range is unsized (i.e. has a `*` end bound)
+    pub size: Option<u32>,
+}
+
+/// Pointer type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Pointer {
+    /// Checked pointers are validated at runtime before being dereferenced
+    pub is_checked: bool,
+    /// Type being pointed to
+    pub to: TyRef,
+}
+
+/// Collection type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Collection {
+    /// Defining type node.
+    /// Collections are nominally typed, so this keeps collections of the
+    /// same element type distinct.
+    pub def: toc_hir::ty::TypeId,
+    /// Declared name of the collection, if it has one
+    pub name: Option<String>,
+    /// Type of the elements allocated in the collection
+    pub elem: TyRef,
+}
+
+/// Array type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Array {
+    /// Flexible arrays can be resized after being declared
+    pub is_flexible: bool,
+    /// Index types of each dimension
+    pub ranges: Vec<TyRef>,
+    /// Type of each element
+    pub elem: TyRef,
+}
+
+/// Set type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Set {
+    /// Index type of the elements in the set
+    pub elem: TyRef,
+}
+
+/// Procedure or function type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Subprogram {
+    pub kind: SubprogramKind,
+    /// Parameters, or `None` if the parameter list is omitted
+    pub params: Option<Vec<Param>>,
+    /// Result type, only present for functions
+    pub result: Option<TyRef>,
+}
+
+/// Parameter of a subprogram type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Param {
+    /// If arguments are passed by reference
+    pub is_var: bool,
+    pub ty: TyRef,
+}
+
+/// Named field of a record or union
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+    pub name: String,
+    pub ty: TyRef,
+}
+
+impl Type {
+    /// Gets the type used for operations on values of this type.
+    ///
+    /// Range types operate as their base type, and all other types are
+    /// left as-is.
+    pub fn to_base_type(&self) -> &Type {
+        match self {
+            Type::Range(range) => &range.base,
+            ty => ty,
+        }
+    }
+
+    /// Looks up a field by name, giving back the field's type.
+    ///
+    /// For unions, the tag is also accessible as a field, and fields from
+    /// all variants are accessible since the active variant can't be
+    /// determined statically.
+    pub fn lookup_field(&self, name: &str) -> Option<TyRef> {
+        fields_of_ty(self)
+            .find(|field| field.name == name)
+            .map(|field| field.ty)
+    }
+}
+
+/// Gets all of the accessible fields of a type, in declaration order.
+///
+/// Types without fields produce an empty list.
+pub fn fields_of(ty: TyRef) -> Vec<(String, TyRef)> {
+    fields_of_ty(&ty)
+        .map(|field| (field.name.clone(), field.ty))
+        .collect()
+}
+
+fn fields_of_ty(ty: &Type) -> Box<dyn Iterator<Item = &Field> + '_> {
+    match ty {
+        Type::Record(record) => Box::new(record.fields.iter()),
+        Type::Union(union) => {
+            Box::new(std::iter::once(&union.tag).chain(union.variants.iter().flatten()))
+        }
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+/// Opaque type, hiding the definition of an exported type
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Opaque {
+    /// Definition of the exported type.
+    /// Opaque types are nominally typed, so this keeps opaque types with the
+    /// same underlying type distinct.
+    pub def: toc_hir::symbol::GlobalDefId,
+    /// Name of the exported type
+    pub name: String,
+    /// Underlying type, only usable inside of the defining unit
+    pub base: TyRef,
 }
 
 /// Size variant of an Int
@@ -162,3 +575,12 @@ pub enum SeqSize {
     /// Fixed, compile-time size
     Fixed(NonZeroU32),
 }
+
+impl fmt::Display for SeqSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeqSize::Dynamic => f.write_str("*"),
+            SeqSize::Fixed(size) => write!(f, "{}", size),
+        }
+    }
+}
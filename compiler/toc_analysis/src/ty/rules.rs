@@ -1,4 +1,7 @@
 //! Rules for type interactions
+#[cfg(test)]
+mod test;
+
 use toc_hir::expr;
 use toc_reporting::MessageSink;
 use toc_span::Spanned;
@@ -7,9 +10,9 @@ use crate::ty::{SeqSize, TyRef, Type};
 
 /// Type for associated mismatch binary operand types
 pub struct MismatchedBinaryTypes {
-    _lhs: Spanned<TyRef>,
+    lhs: Spanned<TyRef>,
     op: Spanned<expr::BinaryOp>,
-    _rhs: Spanned<TyRef>,
+    rhs: Spanned<TyRef>,
     unsupported: bool,
 }
 
@@ -19,8 +22,13 @@ pub struct MismatchedUnaryTypes {
     _rhs: Spanned<TyRef>,
 }
 
+/// Checks if a value of type `source` can be assigned into a reference of type `target`.
+///
+/// All places where a value is implicitly converted into another type (assignments,
+/// initializers, and eventually argument passing) must go through this check.
+///
 /// Returns `Some(is_assignable)`, or `None` if either type is `ty::Error`
-pub fn is_ty_assignable_to(l_value_ty: TyRef, r_value_ty: TyRef) -> Option<bool> {
+pub fn is_assignable(target: TyRef, source: TyRef) -> Option<bool> {
     /// Maximum length of a `string`
     const MAX_STRING_LEN: u32 = 256;
 
@@ -64,12 +72,54 @@ pub fn is_ty_assignable_to(l_value_ty: TyRef, r_value_ty: TyRef) -> Option<bool>
     //
     // String(N) :=
     //   String(M) where N >= M
+    // | String(M) where N < M [runtime checked]
     // | Char(M) where N >= M and M < 256
     // | Char
     // | String [runtime checked]
     //
+    // Char(*) :=
+    //   Char
+    // | Char(_)
+    //
+    // String(*) :=
+    //   Char
+    // | Char(N) where N < 256
+    // | String
+    // | String(_)
+    //
+    // Char(*) and String(*) values follow the same rules as Char(N) and String
+    // values of an unknown size, respectively
+    //
+    // Record :=
+    //   Record [same type]
+    //
+    // Union :=
+    //   Union [same type]
+    //
+    // Range types follow the same rules as their base type,
+    // with values being bounds checked at runtime
+    //
+    // Opaque :=
+    //   Opaque [same type]
+    //
+    // Array :=
+    //   Array [same index and element types]
+    //
+    // Pointer :=
+    //   Pointer [same type]
+    // | nil
+    // | nil(C) where C is the pointed to collection or class
+    //
+    // Set :=
+    //   Set [same element type]
+    //
+    // Subprogram :=
+    //   Subprogram [same kind, parameter, and result types]
+    //
+    // Enum :=
+    //   Enum [same type]
 
-    let is_assignable = match (&*l_value_ty, &*r_value_ty) {
+    let is_assignable = match (target.to_base_type(), source.to_base_type()) {
         // Short-circuiting error types
         (Type::Error, _) | (_, Type::Error) => return None,
 
@@ -121,6 +171,46 @@ pub fn is_ty_assignable_to(l_value_ty: TyRef, r_value_ty: TyRef) -> Option<bool>
             n >= m && m.get() < MAX_STRING_LEN
         }
 
+        // Star-sized rules (only present for parameters):
+        // - Char(*) accepts Char and Char(N) of any size
+        // - String(*) accepts all the same types as String
+        (Type::CharN(SeqSize::Dynamic), Type::Char | Type::CharN(_)) => true,
+        (Type::StringN(SeqSize::Dynamic), Type::CharN(SeqSize::Fixed(n))) => {
+            n.get() < MAX_STRING_LEN
+        }
+        (Type::StringN(SeqSize::Dynamic), other) if is_charseq(other) => true,
+
+        // Star-sized values have a size which is only known at runtime:
+        // - Char(*) is assignable anywhere Char(N) could be, but checked at runtime
+        // - String(*) is assignable anywhere String is
+        (Type::Char | Type::CharN(_) | Type::StringN(_), Type::CharN(SeqSize::Dynamic)) => true,
+        (Type::String, Type::CharN(SeqSize::Dynamic)) => true,
+        (Type::Char | Type::CharN(_) | Type::String, Type::StringN(SeqSize::Dynamic)) => true,
+        (Type::StringN(SeqSize::Fixed(_)), Type::StringN(SeqSize::Dynamic)) => true,
+
+        // Records, unions, and enums are only assignable to the exact same type
+        (Type::Record(_), Type::Record(_))
+        | (Type::Union(_), Type::Union(_))
+        | (Type::Enum(_), Type::Enum(_)) => target == source,
+
+        // Opaque types are only assignable to the exact same type, and never
+        // to or from the underlying type
+        (Type::Opaque(_), Type::Opaque(_)) => target == source,
+
+        // Arrays are only assignable to arrays with the same index and element types
+        (Type::Array(_), Type::Array(_)) => target == source,
+
+        // Sets and subprograms are only assignable to the exact same type
+        (Type::Set(_), Type::Set(_)) | (Type::Subprogram(_), Type::Subprogram(_)) => {
+            target == source
+        }
+
+        // Pointers are only assignable to the exact same type, or from a
+        // `nil` belonging to the same collection or class
+        (Type::Pointer(_), Type::Pointer(_)) => target == source,
+        (Type::Pointer(_), Type::Nil(None)) => true,
+        (Type::Pointer(pointer), Type::Nil(Some(collection))) => pointer.to == *collection,
+
         // Not assignable otherwise
         _ => false,
     };
@@ -128,6 +218,96 @@ pub fn is_ty_assignable_to(l_value_ty: TyRef, r_value_ty: TyRef) -> Option<bool>
     Some(is_assignable)
 }
 
+/// Gets the inclusive range of values that can be stored in a sized integer type.
+///
+/// Returns `None` if the type isn't a sized integer type, or if the size is
+/// dependent on the target machine (i.e. `addressint`).
+pub fn integer_bounds(ty: &Type) -> Option<(i128, i128)> {
+    use crate::ty::{IntSize, NatSize};
+
+    let bounds = match ty {
+        Type::Int(IntSize::Int1) => (i8::MIN.into(), i8::MAX.into()),
+        Type::Int(IntSize::Int2) => (i16::MIN.into(), i16::MAX.into()),
+        Type::Int(IntSize::Int4) => (i32::MIN.into(), i32::MAX.into()),
+        // Smallest value is used as the uninitialized value marker
+        Type::Int(IntSize::Int) => (i128::from(i32::MIN) + 1, i32::MAX.into()),
+        Type::Nat(NatSize::Nat1) => (0, u8::MAX.into()),
+        Type::Nat(NatSize::Nat2) => (0, u16::MAX.into()),
+        Type::Nat(NatSize::Nat4) => (0, u32::MAX.into()),
+        // Largest value is used as the uninitialized value marker
+        Type::Nat(NatSize::Nat) => (0, i128::from(u32::MAX) - 1),
+        _ => return None,
+    };
+
+    Some(bounds)
+}
+
+/// Gets the maximum number of characters that can be stored in a char sequence type.
+///
+/// Returns `None` if the type isn't a char sequence, or if the size is only known at
+/// runtime (i.e. `char(*)` or `string(*)`).
+pub fn charseq_max_len(ty: &Type) -> Option<u32> {
+    /// Maximum number of characters in a `string`
+    const MAX_STRING_CHARS: u32 = 255;
+
+    let len = match ty.to_base_type() {
+        Type::Char => 1,
+        Type::String => MAX_STRING_CHARS,
+        Type::CharN(SeqSize::Fixed(n)) | Type::StringN(SeqSize::Fixed(n)) => n.get(),
+        _ => return None,
+    };
+
+    Some(len)
+}
+
+/// Checks if assigning a value of type `source` into the sized string type `target`
+/// could truncate the value, i.e. if `source` can hold more characters than `target`.
+///
+/// The actual length is only known at runtime, where it is checked.
+pub fn is_truncating(target: &Type, source: &Type) -> bool {
+    let target_len = match target.to_base_type() {
+        Type::StringN(SeqSize::Fixed(n)) => n.get(),
+        _ => return false,
+    };
+
+    match source.to_base_type() {
+        // Size is not known until runtime, so it may not fit
+        Type::StringN(SeqSize::Dynamic) => true,
+        source @ (Type::String | Type::StringN(_)) => {
+            charseq_max_len(source).is_some_and(|source_len| source_len > target_len)
+        }
+        _ => false,
+    }
+}
+
+/// Checks if `source` is a real type being converted into an integer type `target`,
+/// which always requires an explicit conversion.
+pub fn is_real_to_integer(target: &Type, source: &Type) -> bool {
+    is_integer(target.to_base_type()) && matches!(source.to_base_type(), Type::Real(_))
+}
+
+/// Checks if assigning a value of type `source` into `target` narrows the value,
+/// i.e. if `source` is an integer type stored in more bytes than `target`.
+///
+/// Conversions involving `{integer}` or target-dependent sizes are never
+/// considered narrowing, since those depend on the specific value.
+pub fn is_narrowing(target: &Type, source: &Type) -> bool {
+    use crate::ty::{IntSize, NatSize};
+
+    fn size_of(ty: &Type) -> Option<u8> {
+        let size = match ty.to_base_type() {
+            Type::Int(IntSize::Int1) | Type::Nat(NatSize::Nat1) => 1,
+            Type::Int(IntSize::Int2) | Type::Nat(NatSize::Nat2) => 2,
+            Type::Int(IntSize::Int4 | IntSize::Int) | Type::Nat(NatSize::Nat4 | NatSize::Nat) => 4,
+            _ => return None,
+        };
+
+        Some(size)
+    }
+
+    matches!((size_of(target), size_of(source)), (Some(target), Some(source)) if target < source)
+}
+
 pub fn is_number(ty: &Type) -> bool {
     matches!(
         ty,
@@ -143,6 +323,13 @@ pub fn is_nat(ty: &Type) -> bool {
     matches!(ty, Type::Integer | Type::Nat(_))
 }
 
+pub fn is_charseq(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::String | Type::StringN(_) | Type::Char | Type::CharN(_)
+    )
+}
+
 pub fn is_error(ty: &Type) -> bool {
     matches!(ty, Type::Error)
 }
@@ -165,6 +352,9 @@ pub fn check_binary_operands(
     // int - (int/nat) => int
     // (int/nat) - int => int
     // nat - nat => nat
+    //
+    // Integer-only arithmetic (`div`, `mod`, `rem`) follows the same rules,
+    // except that real operands are not allowed
     use crate::ty::{IntSize, NatSize, RealSize};
 
     fn check_arithmetic_operands(lhs_ty: &Type, rhs_ty: &Type) -> Option<Type> {
@@ -186,6 +376,14 @@ pub fn check_binary_operands(
         }
     }
 
+    fn check_integer_operands(lhs_ty: &Type, rhs_ty: &Type) -> Option<Type> {
+        match (lhs_ty, rhs_ty) {
+            // Only integers are allowed
+            (Type::Real(_), _) | (_, Type::Real(_)) => None,
+            (lhs, rhs) => check_arithmetic_operands(lhs, rhs),
+        }
+    }
+
     fn check_bitwise_operands(lhs_ty: &Type, rhs_ty: &Type) -> Option<Type> {
         match (lhs_ty, rhs_ty) {
             // Normal operands
@@ -195,15 +393,39 @@ pub fn check_binary_operands(
         }
     }
 
+    fn check_ordering_operands(lhs_ty: &Type, rhs_ty: &Type) -> bool {
+        match (lhs_ty, rhs_ty) {
+            // Numbers are ordered by value
+            (lhs, rhs) if is_number(lhs) && is_number(rhs) => true,
+            // Charseqs are ordered lexicographically
+            (lhs, rhs) if is_charseq(lhs) && is_charseq(rhs) => true,
+            // Enums are ordered by variant, and only against the same enum type
+            (Type::Enum(lhs), Type::Enum(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+
+    fn check_equality_operands(lhs_ty: &Type, rhs_ty: &Type) -> bool {
+        // TODO: Allow sets and pointers of the same type once they are lowered
+        match (lhs_ty, rhs_ty) {
+            // All ordered types can be compared for equality
+            (lhs, rhs) if check_ordering_operands(lhs, rhs) => true,
+            (Type::Boolean, Type::Boolean) => true,
+            // Opaque types can only be compared with the same type
+            (Type::Opaque(lhs), Type::Opaque(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+
     fn create_binary_type_error(
         lhs_ty_ref: Spanned<TyRef>,
         op: Spanned<expr::BinaryOp>,
         rhs_ty_ref: Spanned<TyRef>,
     ) -> Result<Type, MismatchedBinaryTypes> {
         Err(MismatchedBinaryTypes {
-            _lhs: lhs_ty_ref,
+            lhs: lhs_ty_ref,
             op,
-            _rhs: rhs_ty_ref,
+            rhs: rhs_ty_ref,
             unsupported: false,
         })
     }
@@ -214,14 +436,18 @@ pub fn check_binary_operands(
         rhs_ty_ref: Spanned<TyRef>,
     ) -> Result<Type, MismatchedBinaryTypes> {
         Err(MismatchedBinaryTypes {
-            _lhs: lhs_ty_ref,
+            lhs: lhs_ty_ref,
             op,
-            _rhs: rhs_ty_ref,
+            rhs: rhs_ty_ref,
             unsupported: true,
         })
     }
 
-    let (lhs_ty, rhs_ty) = (&**lhs_ty_ref.item(), &**rhs_ty_ref.item());
+    // Range types operate as their base types
+    let (lhs_ty, rhs_ty) = (
+        lhs_ty_ref.item().to_base_type(),
+        rhs_ty_ref.item().to_base_type(),
+    );
 
     // Short circuit for error types
     // Don't duplicate errors
@@ -233,13 +459,16 @@ pub fn check_binary_operands(
         // Arithmetic operators
         expr::BinaryOp::Add => {
             // Operations:
-            // x String concatenation (charseq, charseq => charseq)
+            // - String concatenation (charseq, charseq => string)
             // x Set union (set, set => set)
             // - Addition (number, number => number)
 
             if let Some(result_ty) = check_arithmetic_operands(&lhs_ty, &rhs_ty) {
                 // Addition
                 Ok(result_ty)
+            } else if is_charseq(lhs_ty) && is_charseq(rhs_ty) {
+                // String concatenation
+                Ok(Type::String)
             } else {
                 // Type error
                 create_binary_type_error(lhs_ty_ref, op, rhs_ty_ref)
@@ -271,16 +500,13 @@ pub fn check_binary_operands(
         }
         expr::BinaryOp::Div => {
             // Operations:
-            // - Integer division (number, number => integer)
-
-            match (&*lhs_ty, &*rhs_ty) {
-                // Pass through type inference
-                (Type::Integer, Type::Integer) => Ok(Type::Integer),
-                (operand, Type::Nat(_)) | (Type::Nat(_), operand) if is_nat(operand) => {
-                    Ok(Type::Nat(NatSize::Nat))
-                }
-                (lhs, rhs) if is_number(lhs) && is_number(rhs) => Ok(Type::Int(IntSize::Int)),
-                _ => create_binary_type_error(lhs_ty_ref, op, rhs_ty_ref),
+            // - Integer division (integer, integer => integer)
+
+            if let Some(result_ty) = check_integer_operands(lhs_ty, rhs_ty) {
+                // Integer division
+                Ok(result_ty)
+            } else {
+                create_binary_type_error(lhs_ty_ref, op, rhs_ty_ref)
             }
         }
         expr::BinaryOp::RealDiv => {
@@ -295,9 +521,9 @@ pub fn check_binary_operands(
         }
         expr::BinaryOp::Mod => {
             // Operations:
-            // - Modulo (number, number => number)
+            // - Modulo (integer, integer => integer)
 
-            if let Some(result_ty) = check_arithmetic_operands(&lhs_ty, &rhs_ty) {
+            if let Some(result_ty) = check_integer_operands(lhs_ty, rhs_ty) {
                 // Modulo
                 Ok(result_ty)
             } else {
@@ -306,9 +532,9 @@ pub fn check_binary_operands(
         }
         expr::BinaryOp::Rem => {
             // Operations:
-            // - Remainder (number, number => number)
+            // - Remainder (integer, integer => integer)
 
-            if let Some(result_ty) = check_arithmetic_operands(&lhs_ty, &rhs_ty) {
+            if let Some(result_ty) = check_integer_operands(lhs_ty, rhs_ty) {
                 // Remainder
                 Ok(result_ty)
             } else {
@@ -318,6 +544,8 @@ pub fn check_binary_operands(
         expr::BinaryOp::Exp => {
             // Operations:
             // - Exponentiation (number, number => number)
+            //   Follows the same rules as the other arithmetic operators, so
+            //   a real operand produces a real result
 
             if let Some(result_ty) = check_arithmetic_operands(&lhs_ty, &rhs_ty) {
                 // Exponentiation
@@ -409,12 +637,29 @@ pub fn check_binary_operands(
             }
         }
         // Comparison (a, b => boolean where a, b: Comparable)
-        expr::BinaryOp::Less => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
-        expr::BinaryOp::LessEq => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
-        expr::BinaryOp::Greater => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
-        expr::BinaryOp::GreaterEq => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
-        expr::BinaryOp::Equal => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
-        expr::BinaryOp::NotEqual => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
+        expr::BinaryOp::Less
+        | expr::BinaryOp::LessEq
+        | expr::BinaryOp::Greater
+        | expr::BinaryOp::GreaterEq => {
+            // Operations:
+            // - Ordering comparison (a, b => boolean where a, b: Ordered)
+
+            if check_ordering_operands(lhs_ty, rhs_ty) {
+                Ok(Type::Boolean)
+            } else {
+                create_binary_type_error(lhs_ty_ref, op, rhs_ty_ref)
+            }
+        }
+        expr::BinaryOp::Equal | expr::BinaryOp::NotEqual => {
+            // Operations:
+            // - Equality comparison (a, b => boolean where a, b: Equatable)
+
+            if check_equality_operands(lhs_ty, rhs_ty) {
+                Ok(Type::Boolean)
+            } else {
+                create_binary_type_error(lhs_ty_ref, op, rhs_ty_ref)
+            }
+        }
         // Set membership tests (set(a), a => boolean)
         expr::BinaryOp::In => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
         expr::BinaryOp::NotIn => create_unsupported_binary_op(lhs_ty_ref, op, rhs_ty_ref),
@@ -423,7 +668,10 @@ pub fn check_binary_operands(
 
 pub fn report_binary_typecheck_error(err: MismatchedBinaryTypes, reporter: &mut MessageSink) {
     let MismatchedBinaryTypes {
-        op, unsupported, ..
+        lhs,
+        op,
+        rhs,
+        unsupported,
     } = err;
     let op_name = match op.item() {
         expr::BinaryOp::Add => "addition",
@@ -455,7 +703,10 @@ pub fn report_binary_typecheck_error(err: MismatchedBinaryTypes, reporter: &mut
         return;
     }
 
-    let msg = reporter.error_detailed(&format!("incompatible types for {}", op_name), op.span());
+    let msg = reporter
+        .error_detailed(&format!("incompatible types for {}", op_name), op.span())
+        .with_note(&format!("this is of type `{}`", lhs.item()), lhs.span())
+        .with_note(&format!("this is of type `{}`", rhs.item()), rhs.span());
     let msg = match op.item() {
         // Arithmetic operators
         expr::BinaryOp::Add => {
@@ -464,11 +715,12 @@ pub fn report_binary_typecheck_error(err: MismatchedBinaryTypes, reporter: &mut
         expr::BinaryOp::Sub | expr::BinaryOp::Mul => {
             msg.with_info("operands must both be numbers or sets", None)
         }
-        expr::BinaryOp::Div
-        | expr::BinaryOp::RealDiv
-        | expr::BinaryOp::Mod
-        | expr::BinaryOp::Rem
-        | expr::BinaryOp::Exp => msg.with_info("operands must both be numbers", None),
+        expr::BinaryOp::RealDiv | expr::BinaryOp::Exp => {
+            msg.with_info("operands must both be numbers", None)
+        }
+        expr::BinaryOp::Div | expr::BinaryOp::Mod | expr::BinaryOp::Rem => {
+            msg.with_info("operands must both be integers", None)
+        }
         // Bitwise operators (integer, integer => nat)
         // + Logical operators (boolean, boolean => boolean)
         expr::BinaryOp::And | expr::BinaryOp::Or | expr::BinaryOp::Xor => {
@@ -481,12 +733,17 @@ pub fn report_binary_typecheck_error(err: MismatchedBinaryTypes, reporter: &mut
         // Pure logical operator
         expr::BinaryOp::Imply => msg.with_info("operands must both be booleans", None),
         // Comparison (a, b => boolean where a, b: Comparable)
-        expr::BinaryOp::Less => todo!(),
-        expr::BinaryOp::LessEq => todo!(),
-        expr::BinaryOp::Greater => todo!(),
-        expr::BinaryOp::GreaterEq => todo!(),
-        expr::BinaryOp::Equal => todo!(),
-        expr::BinaryOp::NotEqual => todo!(),
+        expr::BinaryOp::Less
+        | expr::BinaryOp::LessEq
+        | expr::BinaryOp::Greater
+        | expr::BinaryOp::GreaterEq => msg.with_info(
+            "operands must both be numbers, strings, or enums of the same type",
+            None,
+        ),
+        expr::BinaryOp::Equal | expr::BinaryOp::NotEqual => msg.with_info(
+            "operands must both be numbers, strings, booleans, or enums of the same type",
+            None,
+        ),
         // Set membership tests (set(a), a => boolean)
         expr::BinaryOp::In => todo!(),
         expr::BinaryOp::NotIn => todo!(),
@@ -510,7 +767,7 @@ pub fn check_unary_operands(
         })
     }
 
-    let rhs_ty = &**rhs_ty_ref.item();
+    let rhs_ty = rhs_ty_ref.item().to_base_type();
 
     // Short circuit for error types
     // Don't duplicate errors
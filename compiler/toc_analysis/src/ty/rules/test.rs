@@ -0,0 +1,298 @@
+//! Type rule tests
+use std::num::NonZeroU32;
+
+use crate::ty::{self, IntSize, NatSize, RealSize, SeqSize, TyCtx, TyRef, Type};
+
+use super::{integer_bounds, is_assignable, is_truncating};
+
+fn check_assignable(target: Type, source: Type) -> Option<bool> {
+    let mut ty_ctx = TyCtx::new();
+    let (target, source) = (ty_ctx.add_type(target), ty_ctx.add_type(source));
+
+    is_assignable(target, source)
+}
+
+fn fixed(size: u32) -> SeqSize {
+    SeqSize::Fixed(NonZeroU32::new(size).unwrap())
+}
+
+fn range_of(base: Type) -> Type {
+    let base = TyCtx::new().add_type(base);
+    Type::Range(ty::Range {
+        base,
+        start: 1,
+        size: Some(10),
+    })
+}
+
+#[test]
+fn assignable_numbers() {
+    let mut ty_ctx = TyCtx::new();
+    let mut intern =
+        |tys: Vec<Type>| -> Vec<TyRef> { tys.into_iter().map(|ty| ty_ctx.add_type(ty)).collect() };
+
+    let ints = intern(vec![
+        Type::Int(IntSize::Int1),
+        Type::Int(IntSize::Int2),
+        Type::Int(IntSize::Int4),
+        Type::Int(IntSize::Int),
+        Type::Nat(NatSize::Nat1),
+        Type::Nat(NatSize::Nat2),
+        Type::Nat(NatSize::Nat4),
+        Type::Nat(NatSize::Nat),
+        Type::Nat(NatSize::AddressInt),
+    ]);
+    let reals = intern(vec![
+        Type::Real(RealSize::Real4),
+        Type::Real(RealSize::Real8),
+        Type::Real(RealSize::Real),
+    ]);
+    let integer = intern(vec![Type::Integer]);
+
+    // int and nat are assignable to each other (range checked at runtime)
+    for &target in &ints {
+        for &source in ints.iter().chain(&integer) {
+            assert_eq!(
+                is_assignable(target, source),
+                Some(true),
+                "{} := {}",
+                target,
+                source
+            );
+        }
+    }
+
+    // int and nat widen into real
+    for &target in &reals {
+        for &source in ints.iter().chain(&integer).chain(&reals) {
+            assert_eq!(
+                is_assignable(target, source),
+                Some(true),
+                "{} := {}",
+                target,
+                source
+            );
+        }
+    }
+
+    // real never narrows into int or nat
+    for &target in &ints {
+        for &source in &reals {
+            assert_eq!(
+                is_assignable(target, source),
+                Some(false),
+                "{} := {}",
+                target,
+                source
+            );
+        }
+    }
+}
+
+#[test]
+fn assignable_char_seqs() {
+    // char accepts single characters
+    assert_eq!(check_assignable(Type::Char, Type::Char), Some(true));
+    assert_eq!(
+        check_assignable(Type::Char, Type::CharN(fixed(1))),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::Char, Type::StringN(fixed(1))),
+        Some(true)
+    );
+    assert_eq!(check_assignable(Type::Char, Type::String), Some(true));
+    assert_eq!(
+        check_assignable(Type::Char, Type::CharN(fixed(2))),
+        Some(false)
+    );
+
+    // char(n) only accepts the exact same length
+    assert_eq!(
+        check_assignable(Type::CharN(fixed(3)), Type::CharN(fixed(3))),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::CharN(fixed(3)), Type::CharN(fixed(2))),
+        Some(false)
+    );
+    assert_eq!(
+        check_assignable(Type::CharN(fixed(1)), Type::Char),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::CharN(fixed(2)), Type::Char),
+        Some(false)
+    );
+
+    // string accepts all char seqs, as long as they fit
+    assert_eq!(check_assignable(Type::String, Type::Char), Some(true));
+    assert_eq!(
+        check_assignable(Type::String, Type::CharN(fixed(255))),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::String, Type::CharN(fixed(256))),
+        Some(false)
+    );
+    assert_eq!(
+        check_assignable(Type::String, Type::StringN(fixed(20))),
+        Some(true)
+    );
+
+    // string(n) accepts string and char literals
+    assert_eq!(
+        check_assignable(Type::StringN(fixed(4)), Type::String),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::StringN(fixed(4)), Type::Char),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::StringN(fixed(4)), Type::CharN(fixed(4))),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::StringN(fixed(4)), Type::CharN(fixed(5))),
+        Some(false)
+    );
+}
+
+#[test]
+fn assignable_star_sized() {
+    // char(*) accepts char(n) of any size
+    assert_eq!(
+        check_assignable(Type::CharN(SeqSize::Dynamic), Type::Char),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::CharN(SeqSize::Dynamic), Type::CharN(fixed(300))),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::CharN(SeqSize::Dynamic), Type::String),
+        Some(false)
+    );
+
+    // string(*) accepts strings of any size
+    assert_eq!(
+        check_assignable(Type::StringN(SeqSize::Dynamic), Type::String),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::StringN(SeqSize::Dynamic), Type::StringN(fixed(4))),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::StringN(SeqSize::Dynamic), Type::CharN(fixed(255))),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::StringN(SeqSize::Dynamic), Type::CharN(fixed(256))),
+        Some(false)
+    );
+
+    // star-sized values can be assigned anywhere, but are checked at runtime
+    assert_eq!(
+        check_assignable(Type::StringN(fixed(4)), Type::StringN(SeqSize::Dynamic)),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(Type::CharN(fixed(4)), Type::CharN(SeqSize::Dynamic)),
+        Some(true)
+    );
+}
+
+#[test]
+fn truncating_strings() {
+    assert!(is_truncating(&Type::StringN(fixed(4)), &Type::String));
+    assert!(is_truncating(
+        &Type::StringN(fixed(4)),
+        &Type::StringN(fixed(5))
+    ));
+    assert!(is_truncating(
+        &Type::StringN(fixed(4)),
+        &Type::StringN(SeqSize::Dynamic)
+    ));
+    assert!(!is_truncating(
+        &Type::StringN(fixed(4)),
+        &Type::StringN(fixed(4))
+    ));
+    assert!(!is_truncating(&Type::StringN(fixed(4)), &Type::Char));
+    assert!(!is_truncating(&Type::String, &Type::StringN(fixed(5))));
+}
+
+#[test]
+fn assignable_ranges() {
+    // Ranges follow the rules of their base types
+    assert_eq!(
+        check_assignable(range_of(Type::Int(IntSize::Int)), Type::Integer),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(
+            Type::Real(RealSize::Real),
+            range_of(Type::Int(IntSize::Int))
+        ),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(range_of(Type::Char), Type::Char),
+        Some(true)
+    );
+    assert_eq!(
+        check_assignable(range_of(Type::Int(IntSize::Int)), Type::Char),
+        Some(false)
+    );
+}
+
+#[test]
+fn not_assignable() {
+    assert_eq!(
+        check_assignable(Type::Boolean, Type::Int(IntSize::Int)),
+        Some(false)
+    );
+    assert_eq!(
+        check_assignable(Type::Int(IntSize::Int), Type::Boolean),
+        Some(false)
+    );
+    assert_eq!(
+        check_assignable(Type::Int(IntSize::Int), Type::Char),
+        Some(false)
+    );
+    assert_eq!(
+        check_assignable(Type::String, Type::Int(IntSize::Int)),
+        Some(false)
+    );
+    assert_eq!(
+        check_assignable(Type::Real(RealSize::Real), Type::String),
+        Some(false)
+    );
+}
+
+#[test]
+fn assignable_error() {
+    // Errors are never reported as not assignable
+    assert_eq!(check_assignable(Type::Error, Type::Boolean), None);
+    assert_eq!(check_assignable(Type::Boolean, Type::Error), None);
+}
+
+#[test]
+fn sized_integer_bounds() {
+    assert_eq!(integer_bounds(&Type::Int(IntSize::Int1)), Some((-128, 127)));
+    assert_eq!(
+        integer_bounds(&Type::Int(IntSize::Int)),
+        Some((-2147483647, 2147483647))
+    );
+    assert_eq!(integer_bounds(&Type::Nat(NatSize::Nat2)), Some((0, 65535)));
+    assert_eq!(
+        integer_bounds(&Type::Nat(NatSize::Nat)),
+        Some((0, 4294967294))
+    );
+
+    // Not sized integers
+    assert_eq!(integer_bounds(&Type::Nat(NatSize::AddressInt)), None);
+    assert_eq!(integer_bounds(&Type::Integer), None);
+    assert_eq!(integer_bounds(&Type::Real(RealSize::Real)), None);
+}
@@ -0,0 +1,188 @@
+//! Type rendering tests
+use std::num::NonZeroU32;
+
+use toc_hir::db::HirBuilder;
+use toc_hir::ty::SubprogramKind;
+use toc_span::Span;
+
+use crate::ty::{self, IntSize, NatSize, SeqSize, TyCtx, TyRef, Type};
+
+fn hir_ty() -> toc_hir::ty::TypeId {
+    HirBuilder::new().add_type(toc_hir::ty::Type::Missing, Span::default())
+}
+
+fn fixed(size: u32) -> SeqSize {
+    SeqSize::Fixed(NonZeroU32::new(size).unwrap())
+}
+
+#[test]
+fn render_primitives() {
+    assert_eq!(Type::Int(IntSize::Int).to_string(), "int");
+    assert_eq!(Type::Nat(NatSize::AddressInt).to_string(), "addressint");
+    assert_eq!(Type::CharN(fixed(3)).to_string(), "char(3)");
+    assert_eq!(Type::StringN(SeqSize::Dynamic).to_string(), "string(*)");
+}
+
+fn range(ty_ctx: &mut TyCtx, base: Type, start: i128, size: Option<u32>) -> TyRef {
+    let base = ty_ctx.add_type(base);
+    ty_ctx.add_type(Type::Range(ty::Range { base, start, size }))
+}
+
+#[test]
+fn render_ranges() {
+    let mut ty_ctx = TyCtx::new();
+
+    let ints = range(&mut ty_ctx, Type::Int(IntSize::Int), 1, Some(3));
+    assert_eq!(ints.to_string(), "1 .. 3");
+    let negative = range(&mut ty_ctx, Type::Int(IntSize::Int), -2, Some(1));
+    assert_eq!(negative.to_string(), "-2 .. -2");
+    let unsized_range = range(&mut ty_ctx, Type::Int(IntSize::Int), 0, None);
+    assert_eq!(unsized_range.to_string(), "0 .. *");
+
+    // Bounds are shown as values of the base type
+    let chars = range(&mut ty_ctx, Type::Char, 'a' as i128, Some(26));
+    assert_eq!(chars.to_string(), "'a' .. 'z'");
+    let bools = range(&mut ty_ctx, Type::Boolean, 0, Some(2));
+    assert_eq!(bools.to_string(), "false .. true");
+}
+
+#[test]
+fn render_arrays() {
+    let mut ty_ctx = TyCtx::new();
+    let int = ty_ctx.add_type(Type::Int(IntSize::Int));
+    let char_ty = ty_ctx.add_type(Type::Char);
+    let ints = range(&mut ty_ctx, Type::Int(IntSize::Int), 1, Some(3));
+
+    let array = |is_flexible, ranges| {
+        Type::Array(ty::Array {
+            is_flexible,
+            ranges,
+            elem: int,
+        })
+    };
+
+    assert_eq!(array(false, vec![ints]).to_string(), "array 1 .. 3 of int");
+    assert_eq!(
+        array(true, vec![ints, char_ty]).to_string(),
+        "flexible array 1 .. 3, char of int"
+    );
+}
+
+#[test]
+fn render_sets() {
+    let mut ty_ctx = TyCtx::new();
+    let char_ty = ty_ctx.add_type(Type::Char);
+    let ints = range(&mut ty_ctx, Type::Int(IntSize::Int), 0, Some(10));
+
+    assert_eq!(
+        Type::Set(ty::Set { elem: char_ty }).to_string(),
+        "set of char"
+    );
+    assert_eq!(
+        Type::Set(ty::Set { elem: ints }).to_string(),
+        "set of 0 .. 9"
+    );
+}
+
+#[test]
+fn render_subprograms() {
+    let mut ty_ctx = TyCtx::new();
+    let int = ty_ctx.add_type(Type::Int(IntSize::Int));
+    let string = ty_ctx.add_type(Type::String);
+    let boolean = ty_ctx.add_type(Type::Boolean);
+
+    let param = |is_var, ty| ty::Param { is_var, ty };
+
+    let function = Type::Subprogram(ty::Subprogram {
+        kind: SubprogramKind::Function,
+        params: Some(vec![param(false, int), param(false, string)]),
+        result: Some(boolean),
+    });
+    assert_eq!(function.to_string(), "function (int, string) : boolean");
+
+    let procedure = Type::Subprogram(ty::Subprogram {
+        kind: SubprogramKind::Procedure,
+        params: Some(vec![param(true, int)]),
+        result: None,
+    });
+    assert_eq!(procedure.to_string(), "procedure (var int)");
+
+    // Parameter lists can be omitted entirely
+    let no_params = Type::Subprogram(ty::Subprogram {
+        kind: SubprogramKind::Function,
+        params: None,
+        result: Some(int),
+    });
+    assert_eq!(no_params.to_string(), "function : int");
+}
+
+#[test]
+fn render_pointers() {
+    let mut ty_ctx = TyCtx::new();
+    let to = ty_ctx.add_type(Type::String);
+
+    assert_eq!(
+        Type::Pointer(ty::Pointer {
+            is_checked: true,
+            to
+        })
+        .to_string(),
+        "^string"
+    );
+    assert_eq!(
+        Type::Pointer(ty::Pointer {
+            is_checked: false,
+            to
+        })
+        .to_string(),
+        "unchecked ^string"
+    );
+}
+
+#[test]
+fn render_named_aggregates() {
+    let mut ty_ctx = TyCtx::new();
+    let int = ty_ctx.add_type(Type::Int(IntSize::Int));
+
+    let record = |name: Option<&str>| {
+        Type::Record(ty::Record {
+            def: hir_ty(),
+            name: name.map(String::from),
+            fields: vec![ty::Field {
+                name: "f".to_string(),
+                ty: int,
+            }],
+        })
+    };
+
+    assert_eq!(record(Some("node")).to_string(), "node");
+    assert_eq!(record(None).to_string(), "record");
+
+    // Named types aren't expanded, even when they're inside of other types
+    let node = ty_ctx.add_type(record(Some("node")));
+    let pointer = ty_ctx.add_type(Type::Pointer(ty::Pointer {
+        is_checked: true,
+        to: node,
+    }));
+    assert_eq!(pointer.to_string(), "^node");
+
+    let collection = |name: Option<&str>| {
+        Type::Collection(ty::Collection {
+            def: hir_ty(),
+            name: name.map(String::from),
+            elem: node,
+        })
+    };
+    assert_eq!(collection(Some("nodes")).to_string(), "nodes");
+    assert_eq!(collection(None).to_string(), "collection of node");
+
+    let enum_ty = |name: Option<&str>| {
+        Type::Enum(ty::Enum {
+            def: hir_ty(),
+            name: name.map(String::from),
+            variants: vec!["a".to_string(), "b".to_string()],
+        })
+    };
+    assert_eq!(enum_ty(Some("colour")).to_string(), "colour");
+    assert_eq!(enum_ty(None).to_string(), "enum");
+}
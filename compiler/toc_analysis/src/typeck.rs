@@ -1,19 +1,22 @@
 //! Type checking
+mod builtin;
 #[cfg(test)]
 mod test;
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 
-use toc_hir::{db, expr, stmt, ty as hir_ty, unit};
-use toc_reporting::{MessageSink, ReportMessage};
-use toc_span::Spanned;
+use indexmap::IndexMap;
+use toc_hir::{db, expr, stmt, symbol, ty as hir_ty, unit};
+use toc_reporting::{edit_distance, MessageSink, ReportMessage};
+use toc_span::{Span, Spanned};
 
-use crate::const_eval::{ConstError, ConstEvalCtx, ConstInt, RestrictType};
+use crate::const_eval::{ConstError, ConstEvalCtx, ConstInt, ConstValue, RestrictType};
 use crate::ty::{self, DefKind, TyCtx, TyRef};
+use crate::Dialect;
 
 // ???: Can we build up a type ctx without doing type propagation?
 // Type propagation is inferring of types from inputs
@@ -32,12 +35,18 @@ use crate::ty::{self, DefKind, TyCtx, TyRef};
 // - Mutability in general falls under responsibility of assignability (so by extension, typeck is responsible)
 // - Export mutability matters for mutation outside of the local unit scope, normal const/var rules apply for local units
 
+/// Typechecks a unit.
+///
+/// `imported_tys` holds the typing contexts of already checked units, which
+/// are used to look up the types of any imported symbols.
 pub fn typecheck_unit(
     hir_db: db::HirDb,
     unit: &unit::Unit,
     const_eval: Arc<ConstEvalCtx>,
+    imported_tys: &HashMap<unit::UnitId, TyCtx>,
+    dialect: Dialect,
 ) -> (TyCtx, Vec<ReportMessage>) {
-    TypeCheck::check_unit(hir_db, unit, const_eval)
+    TypeCheck::check_unit(hir_db, unit, const_eval, imported_tys, dialect)
 }
 
 struct TypeCheck<'a> {
@@ -45,6 +54,7 @@ struct TypeCheck<'a> {
     hir_db: db::HirDb,
     unit: &'a unit::Unit,
     const_eval: Arc<ConstEvalCtx>,
+    dialect: Dialect,
 
     // Main mutable state
     state: RefCell<TypeCheckState>,
@@ -64,7 +74,64 @@ struct TypeCheck<'a> {
 struct TypeCheckState {
     ty_ctx: TyCtx,
     cached_expr_evals: HashMap<expr::ExprId, EvalKind>,
+    /// Declared names of record, union, and collection types, for use in diagnostics
+    type_names: HashMap<hir_ty::TypeId, symbol::DefId>,
     reporter: toc_reporting::MessageSink,
+    /// Checkedness of the statement currently being checked
+    checkedness: stmt::Checkedness,
+    /// Span of the `unchecked` statement starting the region of the statement currently being checked
+    unchecked_region: Option<Span>,
+    /// All `unchecked` regions seen so far, and if any runtime checks were skipped inside of them
+    unchecked_regions: IndexMap<Span, bool>,
+    /// Declarations whose type is named by a type exported as `opaque` from this unit,
+    /// along with the named type's definition
+    opaque_decls: Vec<(symbol::DefId, symbol::DefId)>,
+    /// Subprograms with parameter or result types named by a type exported as `opaque`
+    /// from this unit, along with the named type's definition for each parameter and the result
+    opaque_signatures: Vec<OpaqueSignature>,
+    /// Variants selected by `tag` statements for each union variable,
+    /// only tracked within the current straight-line list of statements
+    tagged_unions: HashMap<symbol::DefId, TaggedUnion>,
+    /// Kinds of the enclosing subprogram and process bodies, innermost last
+    bodies: Vec<BodyKind>,
+    /// Counters of `for` loops, for use in diagnostics
+    for_counters: HashSet<symbol::DefId>,
+    /// Nesting depth of the scope that the statement currently being checked is in
+    scope_depth: usize,
+    /// Scope depths of declared variables, for finding collections that
+    /// can be used after they go out of scope
+    var_depths: HashMap<symbol::DefId, usize>,
+    /// Enclosing subprogram declarations, innermost last
+    subprograms: Vec<symbol::DefId>,
+    /// Collection variables followed into by pointers in each subprogram body,
+    /// along with the span of the pointer
+    collection_derefs: HashMap<symbol::DefId, Vec<(symbol::DefId, Span)>>,
+}
+
+/// Opaque types named in a subprogram's signature
+struct OpaqueSignature {
+    def_id: symbol::DefId,
+    /// Opaque type named by each parameter, in the same order as in the subprogram type
+    params: Vec<Option<symbol::DefId>>,
+    /// Opaque type named by the result
+    result: Option<symbol::DefId>,
+}
+
+/// Kind of body that a statement is inside of
+#[derive(Debug, Clone, Copy)]
+enum BodyKind {
+    /// Function body, giving back a value of the given type
+    Function(TyRef),
+    Procedure,
+    Process,
+}
+
+/// Variant of a union variable selected by a `tag` statement
+#[derive(Debug, Clone, Copy)]
+struct TaggedUnion {
+    variant: usize,
+    /// Span of the `tag` statement
+    tag_span: Span,
 }
 
 impl<'a> TypeCheck<'a> {
@@ -72,11 +139,39 @@ impl<'a> TypeCheck<'a> {
         hir_db: db::HirDb,
         unit: &'a unit::Unit,
         const_eval: Arc<ConstEvalCtx>,
+        imported_tys: &HashMap<unit::UnitId, TyCtx>,
+        dialect: Dialect,
     ) -> (TyCtx, Vec<ReportMessage>) {
+        let mut ty_ctx = TyCtx::new();
+
+        // Imported symbols keep the types from their original definitions
+        for (def_id, original) in unit.symbol_table.iter_imports() {
+            let def_kind = imported_tys
+                .get(&original.unit_id())
+                .and_then(|ty_ctx| ty_ctx.get_def_id_kind(original.as_local()));
+
+            if let Some(def_kind) = def_kind {
+                ty_ctx.map_def_id(def_id, def_kind);
+            }
+        }
+
         let state = TypeCheckState {
-            ty_ctx: TyCtx::new(),
+            ty_ctx,
             cached_expr_evals: HashMap::new(),
-            reporter: toc_reporting::MessageSink::new(),
+            type_names: HashMap::new(),
+            reporter: toc_reporting::MessageSink::new(toc_reporting::Stage::Analyze),
+            checkedness: stmt::Checkedness::Checked,
+            unchecked_region: None,
+            unchecked_regions: IndexMap::new(),
+            opaque_decls: vec![],
+            opaque_signatures: vec![],
+            tagged_unions: HashMap::new(),
+            bodies: vec![],
+            for_counters: HashSet::new(),
+            scope_depth: 0,
+            var_depths: HashMap::new(),
+            subprograms: vec![],
+            collection_derefs: HashMap::new(),
         };
         let state = RefCell::new(state);
 
@@ -84,6 +179,7 @@ impl<'a> TypeCheck<'a> {
             hir_db,
             unit,
             const_eval,
+            dialect,
 
             state,
         };
@@ -93,6 +189,11 @@ impl<'a> TypeCheck<'a> {
             typeck.typeck_stmt(*stmt);
         }
 
+        // Opaque types are only opaque outside of the unit, so they're only hidden
+        // once the whole unit is checked
+        typeck.hide_opaque_types();
+        typeck.report_unused_unchecked();
+
         let state = typeck.state.into_inner();
 
         let TypeCheckState {
@@ -102,17 +203,174 @@ impl<'a> TypeCheck<'a> {
         (ty_ctx, reporter.finish())
     }
 
+    /// Replaces the types exported as `opaque` with opaque types, so that importing
+    /// units only see the opaque types
+    fn hide_opaque_types(&self) {
+        let mut state = self.state();
+        let mut opaque_tys = HashMap::new();
+
+        for export in &self.unit.exports {
+            if !export.is_opaque || export.def_id.unit_id() != self.unit.id {
+                continue;
+            }
+
+            let def_id = export.def_id.as_local();
+
+            // Only types can be opaque
+            if let Some(DefKind::Type(base)) = state.ty_ctx.get_def_id_kind(def_id) {
+                let opaque = state.ty_ctx.add_type(ty::Type::Opaque(ty::Opaque {
+                    def: export.def_id,
+                    name: self.unit.symbol_table.get_symbol(def_id).name.clone(),
+                    base,
+                }));
+
+                state.ty_ctx.map_def_id(def_id, DefKind::Type(opaque));
+                opaque_tys.insert(def_id, opaque);
+            }
+        }
+
+        // Declarations using the opaque types also see the opaque types
+        let opaque_decls = std::mem::take(&mut state.opaque_decls);
+
+        for (def_id, ty_def) in opaque_decls {
+            let opaque = match opaque_tys.get(&ty_def) {
+                Some(opaque) => *opaque,
+                None => continue,
+            };
+
+            let def_kind = match state.ty_ctx.get_def_id_kind(def_id) {
+                Some(DefKind::Const(_)) => DefKind::Const(opaque),
+                Some(DefKind::Var(_)) => DefKind::Var(opaque),
+                _ => continue,
+            };
+
+            state.ty_ctx.map_def_id(def_id, def_kind);
+        }
+
+        // Same with subprograms, so that opaque values can be passed back into the unit
+        let opaque_signatures = std::mem::take(&mut state.opaque_signatures);
+        let hide = |ty: TyRef, ty_def: Option<symbol::DefId>| {
+            ty_def
+                .and_then(|ty_def| opaque_tys.get(&ty_def).copied())
+                .unwrap_or(ty)
+        };
+
+        for signature in opaque_signatures {
+            let subprogram_ty = match state.ty_ctx.get_def_id_kind(signature.def_id) {
+                Some(DefKind::Const(ty)) => ty,
+                _ => continue,
+            };
+            let subprogram = match &*subprogram_ty {
+                ty::Type::Subprogram(subprogram) => subprogram,
+                _ => continue,
+            };
+
+            let params = subprogram.params.as_ref().map(|params| {
+                params
+                    .iter()
+                    .zip(&signature.params)
+                    .map(|(param, ty_def)| ty::Param {
+                        is_var: param.is_var,
+                        ty: hide(param.ty, *ty_def),
+                    })
+                    .collect()
+            });
+            let result = subprogram.result.map(|ty| hide(ty, signature.result));
+            let hidden_ty = state.ty_ctx.add_type(ty::Type::Subprogram(ty::Subprogram {
+                kind: subprogram.kind,
+                params,
+                result,
+            }));
+
+            state
+                .ty_ctx
+                .map_def_id(signature.def_id, DefKind::Const(hidden_ty));
+        }
+    }
+
+    /// Reports `unchecked` regions where no runtime checks would have been performed anyway
+    fn report_unused_unchecked(&self) {
+        let mut state = self.state();
+        let unused = state
+            .unchecked_regions
+            .iter()
+            .filter(|(_, skipped_checks)| !**skipped_checks)
+            .map(|(region, _)| *region)
+            .collect::<Vec<_>>();
+
+        for region in unused {
+            state
+                .reporter
+                .warn_detailed("`unchecked` statement has no effect", region)
+                .with_info(
+                    "none of the statements it applies to are checked at runtime",
+                    None,
+                )
+                .finish();
+        }
+    }
+
+    /// Records if runtime checks apply to a pointer deref or subscript expression
+    fn record_checkedness(&self, id: expr::ExprId) {
+        let checkedness = self.state().checkedness;
+        self.state().ty_ctx.map_checkedness(id, checkedness);
+
+        if checkedness == stmt::Checkedness::Unchecked {
+            self.skip_runtime_check();
+        }
+    }
+
+    /// Notes that a runtime check is skipped in the current `unchecked` region
+    fn skip_runtime_check(&self) {
+        let mut state = self.state();
+
+        if let Some(region) = state.unchecked_region {
+            state.unchecked_regions.insert(region, true);
+        }
+    }
+
     fn state(&self) -> std::cell::RefMut<TypeCheckState> {
         self.state.borrow_mut()
     }
 
     fn typeck_stmt(&self, id: stmt::StmtId) {
+        {
+            let mut state = self.state();
+            let region = self.hir_db.get_unchecked_region(id);
+
+            state.checkedness = self.hir_db.get_checkedness(id);
+            state.unchecked_region = region;
+
+            if let Some(region) = region {
+                state.unchecked_regions.entry(region).or_insert(false);
+            }
+        }
+
         match self.hir_db.get_stmt(id) {
             stmt::Stmt::ConstVar(decl) => self.typeck_constvar(decl),
+            stmt::Stmt::Type(decl) => self.typeck_type_decl(decl),
+            stmt::Stmt::Bind(decl) => self.typeck_bind(decl),
+            stmt::Stmt::Subprogram(decl) => self.typeck_subprogram(decl),
+            stmt::Stmt::Process(decl) => self.typeck_process(decl),
+            stmt::Stmt::Forward(decl) => self.typeck_forward(decl),
+            stmt::Stmt::Module(decl) => self.typeck_module(decl),
             stmt::Stmt::Assign(stmt) => self.typeck_assign(stmt),
             stmt::Stmt::Put(stmt) => self.typeck_put(stmt),
             stmt::Stmt::Get(stmt) => self.typeck_get(stmt),
+            stmt::Stmt::New(stmt) => self.typeck_alloc(stmt.collection, stmt.pointer, "new"),
+            stmt::Stmt::Free(stmt) => self.typeck_alloc(stmt.collection, stmt.pointer, "free"),
+            stmt::Stmt::For(stmt) => self.typeck_for(stmt),
+            stmt::Stmt::Loop(stmt) => self.typeck_nested_stmts(&stmt.stmts),
+            stmt::Stmt::Exit(stmt) => self.typeck_exit(stmt),
+            stmt::Stmt::If(stmt) => self.typeck_if(stmt),
+            stmt::Stmt::Case(stmt) => self.typeck_case(stmt),
+            stmt::Stmt::Quit(stmt) => self.typeck_quit(stmt),
             stmt::Stmt::Block(stmt) => self.typeck_block(stmt),
+            stmt::Stmt::Call(stmt) => self.typeck_call_stmt(stmt),
+            stmt::Stmt::Return(_) => {}
+            stmt::Stmt::Result(stmt) => self.typeck_result(id, stmt),
+            stmt::Stmt::Tag(stmt) => self.typeck_tag(id, stmt),
+            stmt::Stmt::Fork(stmt) => self.typeck_fork(stmt),
         }
     }
 
@@ -132,14 +390,28 @@ impl<'a> TypeCheck<'a> {
                 EvalKind::Error(err)
             }
             expr::Expr::Literal(expr) => self.typeck_literal(expr),
-            expr::Expr::Binary(expr) => self.typeck_binary(expr),
-            expr::Expr::Unary(expr) => self.typeck_unary(expr),
+            expr::Expr::Init(expr) => self.typeck_init(id, expr),
+            expr::Expr::Nil(expr) => self.typeck_nil(expr),
+            expr::Expr::Builtin(expr) => self.typeck_builtin(id, expr),
+            expr::Expr::Binary(expr) => self.typeck_binary(id, expr),
+            expr::Expr::Unary(expr) => self.typeck_unary(id, expr),
             expr::Expr::Paren(expr) => self.typeck_paren(expr),
             expr::Expr::Name(expr) => self.typeck_name(expr),
+            expr::Expr::Field(expr) => self.typeck_field(expr),
+            expr::Expr::Deref(expr) => self.typeck_deref(id, expr),
+            expr::Expr::NatCheat(expr) => self.typeck_nat_cheat(expr),
+            expr::Expr::Indirect(expr) => self.typeck_indirect(expr),
+            expr::Expr::Bits(expr) => self.typeck_bits(expr),
+            expr::Expr::Call(expr) => self.typeck_call(id, expr),
+            expr::Expr::Substring(expr) => self.typeck_substring(expr),
         };
 
-        // Cache the result
-        self.state().cached_expr_evals.insert(id, eval_kind);
+        // Cache the result, and remember the type for later queries
+        {
+            let mut state = self.state();
+            state.cached_expr_evals.insert(id, eval_kind);
+            state.ty_ctx.map_expr_type(id, eval_kind.as_ty_ref());
+        }
 
         eval_kind
     }
@@ -156,6 +428,22 @@ impl<'a> TypeCheck<'a> {
             // Missing => treat as an error type
             hir_ty::Type::Missing => ty::Type::Error,
             hir_ty::Type::Primitive(ty) => self.typeck_primitive(ty),
+            hir_ty::Type::Record(ty) => self.typeck_record(id, ty),
+            hir_ty::Type::Union(ty) => self.typeck_union(id, ty),
+            hir_ty::Type::Range(ty) => self.typeck_range(id, ty),
+            hir_ty::Type::Enum(ty) => self.typeck_enum(id, ty),
+            hir_ty::Type::Pointer(ty) => self.typeck_pointer(ty),
+            hir_ty::Type::Collection(ty) => self.typeck_collection(id, ty),
+            hir_ty::Type::Array(ty) => self.typeck_array(ty),
+            hir_ty::Type::Set(ty) => self.typeck_set(ty),
+            hir_ty::Type::Subprogram(ty) => self.typeck_subprogram_ty(ty),
+            hir_ty::Type::Condition(kind) => ty::Type::Condition(*kind),
+            hir_ty::Type::Name(use_id) => {
+                // Refers to an existing type, reuse it
+                let ty_ref = self.typeck_name_ty(*use_id);
+                self.state().ty_ctx.map_type(id, ty_ref);
+                return ty_ref;
+            }
         };
 
         // Add to ty_ctx cache
@@ -170,7 +458,7 @@ impl<'a> TypeCheck<'a> {
         Spanned::new(ty_ref, span)
     }
 
-    fn require_constvar_ref(&self, def_kind: DefKind) -> EvalKind {
+    fn require_constvar_ref(&self, use_id: symbol::UseId, def_kind: DefKind) -> EvalKind {
         match def_kind {
             DefKind::Const(ty) => EvalKind::ConstRef(ty),
             DefKind::Var(ty) => EvalKind::VarRef(ty),
@@ -178,6 +466,26 @@ impl<'a> TypeCheck<'a> {
             DefKind::Type(_) => {
                 // TODO: Report this error once type decls are lowered
 
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                EvalKind::Error(err)
+            }
+            DefKind::Process => {
+                self.report_process_use(use_id, |name| {
+                    format!("cannot use process `{}` as a value", name)
+                });
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                EvalKind::Error(err)
+            }
+            DefKind::Module => {
+                let name = &self.unit.symbol_table.get_symbol(use_id.as_def()).name;
+                let use_span = self.unit.symbol_table.get_use_span(use_id);
+
+                self.state().reporter.error(
+                    &format!("cannot use module `{}` as a value", name),
+                    use_span,
+                );
+
                 let err = self.state().ty_ctx.add_type(ty::Type::Error);
                 EvalKind::Error(err)
             }
@@ -187,8 +495,15 @@ impl<'a> TypeCheck<'a> {
     fn typeck_constvar(&self, decl: &stmt::ConstVar) {
         // extract type for declared identifiers
         // if both are present, then typecheck as assignment
-        let ty_ref = match &decl.tail {
+        let mut ty_ref = match &decl.tail {
             stmt::ConstVarTail::Both(ty_spec, _) | stmt::ConstVarTail::TypeSpec(ty_spec) => {
+                if let (hir_ty::Type::Collection(_), [def_id]) =
+                    (self.hir_db.get_type(*ty_spec), decl.names.as_slice())
+                {
+                    // Collections are named by the variable holding them
+                    self.state().type_names.insert(*ty_spec, *def_id);
+                }
+
                 // From type_spec
                 self.lower_type(*ty_spec)
             }
@@ -198,28 +513,62 @@ impl<'a> TypeCheck<'a> {
             }
         };
 
+        if let Some(ty_spec) = decl.tail.type_spec() {
+            let has_init = decl
+                .tail
+                .init_expr()
+                .is_some_and(|id| matches!(self.hir_db.get_expr(id), expr::Expr::Init(_)));
+
+            if !has_init && Self::is_implicit_size_array(&ty_ref) {
+                let span = self.hir_db.get_span(ty_spec.into());
+
+                self.state()
+                    .reporter
+                    .error_detailed("implicit size array requires an `init` initializer", span)
+                    .with_note("the size of this array comes from its initializer", span)
+                    .finish();
+            }
+        }
+
         if let stmt::ConstVarTail::Both(ty_spec, init_expr) = &decl.tail {
             let l_value_ty = self.lower_type(*ty_spec);
-            let r_value_ty = self.typeck_expr(*init_expr).as_ty_ref();
+            let r_value_ty = match self.hir_db.get_expr(*init_expr) {
+                expr::Expr::Init(init) => {
+                    // Aggregate initializers take on the declared type, and also give
+                    // implicit size arrays their size
+                    ty_ref = self.typeck_init_aggregate(*init_expr, init, l_value_ty);
+                    l_value_ty
+                }
+                _ => self.typeck_expr(*init_expr).as_ty_ref(),
+            };
+
+            let init_span = self.hir_db.get_span(init_expr.into());
 
-            if let Some(false) = ty::rules::is_ty_assignable_to(l_value_ty, r_value_ty) {
+            if let Some(false) = ty::rules::is_assignable(l_value_ty, r_value_ty) {
                 // Incompatible, report it
-                let init_span = self.hir_db.get_span(init_expr.into());
                 let spec_span = self.hir_db.get_span(ty_spec.into());
 
-                self.state()
-                    .reporter
-                    .error_detailed("mismatched types", init_span)
-                    .with_note(
-                        "initializer's type is incompatible with this type",
-                        spec_span,
-                    )
-                    .finish();
+                if ty::rules::is_real_to_integer(&l_value_ty, &r_value_ty) {
+                    self.report_real_to_integer(l_value_ty, r_value_ty, init_span);
+                } else if let Some(opaque) = self.opaque_conversion(l_value_ty, r_value_ty) {
+                    self.report_opaque(opaque, init_span);
+                } else {
+                    self.state()
+                        .reporter
+                        .error_detailed("mismatched types", init_span)
+                        .with_note(
+                            "initializer's type is incompatible with this type",
+                            spec_span,
+                        )
+                        .finish();
+                }
 
                 // Don't need to worry about ConstValue being anything,
                 // since that should be handled by const eval type restrictions
                 // However, there should still be an assert here
                 // TODO: Add assert ensuring there is no valid ConstValue
+            } else {
+                self.check_conversion(l_value_ty, r_value_ty, Some(*init_expr), init_span);
             }
         }
 
@@ -241,7 +590,271 @@ impl<'a> TypeCheck<'a> {
         };
 
         for def in &decl.names {
-            self.state().ty_ctx.map_def_id(*def, def_kind);
+            let mut state = self.state();
+            state.ty_ctx.map_def_id(*def, def_kind);
+
+            let depth = state.scope_depth;
+            state.var_depths.insert(*def, depth);
+        }
+
+        if let Some(ty_def) = decl.tail.type_spec().and_then(|ty| self.opaque_ty_def(ty)) {
+            let mut state = self.state();
+            let decls = decl.names.iter().map(|def| (*def, ty_def));
+            state.opaque_decls.extend(decls);
+        }
+    }
+
+    /// Gets the definition of the type named by `ty`, if the type is exported
+    /// from this unit as `opaque`
+    fn opaque_ty_def(&self, ty: hir_ty::TypeId) -> Option<symbol::DefId> {
+        let def_id = match self.hir_db.get_type(ty) {
+            hir_ty::Type::Name(use_id) => use_id.as_def(),
+            _ => return None,
+        };
+
+        let global_def = def_id.into_global(self.unit.id);
+        let is_opaque = self
+            .unit
+            .exports
+            .iter()
+            .any(|export| export.is_opaque && export.def_id == global_def);
+
+        is_opaque.then_some(def_id)
+    }
+
+    /// Records the opaque types named by the `params` and `result` types of a subprogram
+    fn record_opaque_signature(
+        &self,
+        def_id: symbol::DefId,
+        params: impl Iterator<Item = hir_ty::TypeId>,
+        result: Option<hir_ty::TypeId>,
+    ) {
+        let params = params.map(|ty| self.opaque_ty_def(ty)).collect::<Vec<_>>();
+        let result = result.and_then(|ty| self.opaque_ty_def(ty));
+
+        if result.is_some() || params.iter().any(Option::is_some) {
+            self.state().opaque_signatures.push(OpaqueSignature {
+                def_id,
+                params,
+                result,
+            });
+        }
+    }
+
+    fn typeck_type_decl(&self, decl: &stmt::Type) {
+        let ty_ref = match &decl.type_def {
+            stmt::TypeDefKind::Alias(ty) => {
+                if let hir_ty::Type::Record(_)
+                | hir_ty::Type::Union(_)
+                | hir_ty::Type::Enum(_)
+                | hir_ty::Type::Collection(_) = self.hir_db.get_type(*ty)
+                {
+                    // Remember the name for better diagnostics
+                    self.state().type_names.insert(*ty, decl.def_id);
+                }
+
+                self.lower_type(*ty)
+            }
+            stmt::TypeDefKind::Forward(forward_span) => {
+                if !self.is_resolved_forward(decl.def_id) {
+                    let name = &self.unit.symbol_table.get_symbol(decl.def_id).name;
+
+                    self.state()
+                        .reporter
+                        .error_detailed(
+                            &format!("forward type `{}` is never resolved", name),
+                            *forward_span,
+                        )
+                        .with_note(
+                            &format!("`{}` declared here", name),
+                            self.unit.symbol_table.get_def_span(decl.def_id),
+                        )
+                        .with_info(
+                            "forward types must be resolved by a later type declaration in the same scope",
+                            None,
+                        )
+                        .finish();
+                }
+
+                // TODO: Resolve forward types once recursive types can be represented
+                self.state().ty_ctx.add_type(ty::Type::Error)
+            }
+        };
+
+        self.state()
+            .ty_ctx
+            .map_def_id(decl.def_id, DefKind::Type(ty_ref));
+    }
+
+    fn typeck_bind(&self, decl: &stmt::Bind) {
+        for binding in &decl.bindings {
+            let bind_eval = self.typeck_expr(binding.bind_to);
+
+            // `var` binds can change the bound reference, so it must also be a `var`
+            if binding.is_var && bind_eval.as_mut_ty_ref().is_none() {
+                let bind_span = self.hir_db.get_span(binding.bind_to.into());
+
+                if let Some(def_id) = self.const_ref_def(binding.bind_to) {
+                    let name = &self.unit.symbol_table.get_symbol(def_id).name;
+                    let def_span = self.unit.symbol_table.get_def_span(def_id);
+
+                    self.state()
+                        .reporter
+                        .error_detailed(
+                            &format!("cannot bind `var` to const `{}`", name),
+                            bind_span,
+                        )
+                        .with_note(&format!("`{}` is declared as a const here", name), def_span)
+                        .finish();
+                } else {
+                    self.state()
+                        .reporter
+                        .error_detailed("cannot bind `var` to this expression", bind_span)
+                        .with_note(
+                            "this expression cannot be used as a variable reference",
+                            bind_span,
+                        )
+                        .finish();
+                }
+            }
+
+            // Binds are aliases, so they have the same type as the bound reference
+            let ty_ref = bind_eval.as_ty_ref();
+            let def_kind = match bind_eval {
+                EvalKind::Error(err) => DefKind::Error(err),
+                _ if binding.is_var => DefKind::Var(ty_ref),
+                _ => DefKind::Const(ty_ref),
+            };
+
+            self.state().ty_ctx.map_def_id(binding.def_id, def_kind);
+        }
+    }
+
+    fn typeck_process(&self, decl: &stmt::Process) {
+        self.state()
+            .ty_ctx
+            .map_def_id(decl.def_id, DefKind::Process);
+
+        if let Some(stack_size) = decl.stack_size {
+            let ty_ref = self.get_spanned_expr_ty_ref(stack_size);
+            self.check_integer_type(ty_ref);
+        }
+
+        self.typeck_params(&decl.params);
+        self.typeck_body(BodyKind::Process, &decl.stmts);
+    }
+
+    fn typeck_forward(&self, decl: &stmt::Forward) {
+        // Calls before the body is declared use the forward declaration
+        let ty_ref = self.lower_type(decl.ty);
+        self.state()
+            .ty_ctx
+            .map_def_id(decl.def_id, DefKind::Const(ty_ref));
+
+        if let hir_ty::Type::Subprogram(ty) = self.hir_db.get_type(decl.ty) {
+            let params = ty.params.iter().flatten().map(|param| param.param_ty);
+            self.record_opaque_signature(decl.def_id, params, ty.result);
+        }
+    }
+
+    fn typeck_module(&self, decl: &stmt::Module) {
+        self.state().ty_ctx.map_def_id(decl.def_id, DefKind::Module);
+
+        // Module variables live for as long as the enclosing scope does,
+        // so the body doesn't start a nested scope
+        self.state().tagged_unions.clear();
+
+        for stmt in &decl.stmts {
+            self.typeck_stmt(*stmt)
+        }
+
+        self.state().tagged_unions.clear();
+    }
+
+    fn typeck_subprogram(&self, decl: &stmt::Subprogram) {
+        let params = decl.params.as_ref().map(|params| {
+            params
+                .iter()
+                .flat_map(|param| {
+                    let ty = self.lower_type(param.param_ty);
+                    let is_var = param.is_var;
+
+                    param.names.iter().map(move |_| ty::Param { is_var, ty })
+                })
+                .collect()
+        });
+        let result = decl.result.map(|ty| self.lower_type(ty));
+        let subprogram_ty = self
+            .state()
+            .ty_ctx
+            .add_type(ty::Type::Subprogram(ty::Subprogram {
+                kind: decl.kind,
+                params,
+                result,
+            }));
+
+        // Bodies are called with the type from the forward declaration,
+        // with any differences from it reported during validation
+        let forward_kind = match self.unit.symbol_table.get_symbol(decl.def_id).kind {
+            symbol::SymbolKind::Resolved(forward_def) => {
+                self.state().ty_ctx.get_def_id_kind(forward_def)
+            }
+            _ => None,
+        };
+        let def_kind = match forward_kind {
+            Some(forward_kind) => forward_kind,
+            None => {
+                // Each name in a parameter group is a separate parameter
+                let params = decl
+                    .params
+                    .iter()
+                    .flatten()
+                    .flat_map(|param| param.names.iter().map(move |_| param.param_ty));
+                self.record_opaque_signature(decl.def_id, params, decl.result);
+
+                DefKind::Const(subprogram_ty)
+            }
+        };
+
+        // Mapped before the body, so that subprograms can call themselves
+        self.state().ty_ctx.map_def_id(decl.def_id, def_kind);
+
+        if let Some(params) = &decl.params {
+            self.typeck_params(params);
+        }
+
+        // The result name holds the value to give back, so it can be assigned to
+        if let (Some(name), Some(result)) = (decl.result_name, result) {
+            self.state().ty_ctx.map_def_id(name, DefKind::Var(result));
+        }
+        let body_kind = match result {
+            Some(result) => BodyKind::Function(result),
+            None => BodyKind::Procedure,
+        };
+        self.state().subprograms.push(decl.def_id);
+        self.typeck_body(body_kind, &decl.stmts);
+        self.state().subprograms.pop();
+    }
+
+    /// Checks the body of a subprogram or process
+    fn typeck_body(&self, kind: BodyKind, stmts: &[stmt::StmtId]) {
+        self.state().bodies.push(kind);
+        self.typeck_nested_stmts(stmts);
+        self.state().bodies.pop();
+    }
+
+    fn typeck_params(&self, params: &[stmt::Param]) {
+        for param in params {
+            let ty_ref = self.lower_type(param.param_ty);
+            let def_kind = if param.is_var {
+                DefKind::Var(ty_ref)
+            } else {
+                DefKind::Const(ty_ref)
+            };
+
+            for name in &param.names {
+                self.state().ty_ctx.map_def_id(*name, def_kind);
+            }
         }
     }
 
@@ -249,9 +862,18 @@ impl<'a> TypeCheck<'a> {
         let l_value_eval = self.typeck_expr(stmt.lhs);
         let r_value_eval = self.typeck_expr(stmt.rhs);
 
+        // Assigning a whole new union value also replaces the active variant
+        if let Some(def_id) = self.whole_var_def(stmt.lhs) {
+            self.state().tagged_unions.remove(&def_id);
+            self.check_escaping_collections(def_id, stmt.rhs);
+        }
+
         // Check if we can even assign into the l_value (i.e. is lhs mutable)
         let l_value_ty = if let Some(ty) = l_value_eval.as_mut_ty_ref() {
             ty
+        } else if let Some(def_id) = self.const_ref_def(stmt.lhs) {
+            self.report_const_assign(def_id, stmt.op.span());
+            return;
         } else {
             let l_value_span = self.hir_db.get_span(stmt.lhs.into());
 
@@ -289,66 +911,458 @@ impl<'a> TypeCheck<'a> {
 
         // Check if types are assignable
         // Leave error types as "always assignable"
-        let asn_able = ty::rules::is_ty_assignable_to(l_value_ty, r_value_ty);
+        let asn_able = ty::rules::is_assignable(l_value_ty, r_value_ty);
         if !asn_able.unwrap_or(true) {
-            // TODO: Report expected type vs found type
-            // - Requires type stringification/display impl
+            if ty::rules::is_real_to_integer(&l_value_ty, &r_value_ty) {
+                self.report_real_to_integer(l_value_ty, r_value_ty, stmt.op.span());
+            } else if let Some(opaque) = self.opaque_conversion(l_value_ty, r_value_ty) {
+                self.report_opaque(opaque, stmt.op.span());
+            } else {
+                // TODO: Report expected type vs found type
+                // - Requires type stringification/display impl
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", stmt.op.span())
+                    .finish();
+            }
+        } else if stmt.op.item().as_binary_op().is_none() {
+            let r_value_span = self.hir_db.get_span(stmt.rhs.into());
+            self.check_conversion(l_value_ty, r_value_ty, Some(stmt.rhs), r_value_span);
+        } else {
+            // Only the type of the combined value is known
+            self.check_conversion(l_value_ty, r_value_ty, None, stmt.op.span());
+        }
+    }
+
+    /// Checks if assigning a subprogram to `var_def` lets it be called after a
+    /// collection that it follows pointers into goes out of scope
+    fn check_escaping_collections(&self, var_def: symbol::DefId, value: expr::ExprId) {
+        let subprogram_def = match self.whole_var_def(value) {
+            Some(def_id) => def_id,
+            None => return,
+        };
+        let escaping = {
+            let state = self.state();
+            let (var_depth, derefs) = match (
+                state.var_depths.get(&var_def),
+                state.collection_derefs.get(&subprogram_def),
+            ) {
+                (Some(depth), Some(derefs)) => (*depth, derefs),
+                _ => return,
+            };
+
+            // The collection is visible from the subprogram, so it's declared
+            // in the same chain of scopes as the variable
+            derefs
+                .iter()
+                .filter(|(collection, _)| {
+                    matches!(state.var_depths.get(collection), Some(depth) if *depth > var_depth)
+                })
+                .copied()
+                .collect::<Vec<_>>()
+        };
+
+        let table = &self.unit.symbol_table;
+        let value_span = self.hir_db.get_span(value.into());
+
+        for (collection, deref_span) in escaping {
+            let collection_name = &table.get_symbol(collection).name;
+
             self.state()
                 .reporter
-                .error_detailed("mismatched types", stmt.op.span())
+                .warn_detailed(
+                    &format!(
+                        "pointer into `{}` may be followed after `{}` goes out of scope",
+                        collection_name, collection_name
+                    ),
+                    deref_span,
+                )
+                .with_note(
+                    &format!(
+                        "`{}` can be called through `{}` after this",
+                        table.get_symbol(subprogram_def).name,
+                        table.get_symbol(var_def).name
+                    ),
+                    value_span,
+                )
+                .with_note(
+                    &format!("`{}` declared here", collection_name),
+                    table.get_def_span(collection),
+                )
+                .with_info(
+                    "elements of a collection are freed once the collection goes out of scope",
+                    None,
+                )
                 .finish();
         }
     }
 
-    fn typeck_put(&self, stmt: &stmt::Put) {
-        if let Some(stream) = stmt.stream_num {
-            self.check_text_io_arg(stream);
+    /// Remembers that the current subprogram follows a pointer into `collection`
+    fn record_collection_deref(&self, collection: &ty::Collection, span: Span) {
+        let mut state = self.state();
+        let collection_def = match state.type_names.get(&collection.def) {
+            Some(def_id) => *def_id,
+            None => return,
+        };
+
+        if let Some(subprogram) = state.subprograms.last().copied() {
+            state
+                .collection_derefs
+                .entry(subprogram)
+                .or_default()
+                .push((collection_def, span));
         }
+    }
 
-        let items = stmt.items.iter().filter_map(|item| match item {
-            stmt::Skippable::Item(item) => Some(item),
+    /// Finds the `const` declaration or `for` loop counter that a reference
+    /// expression refers to, looking through any field accesses and subscripts
+    fn const_ref_def(&self, id: expr::ExprId) -> Option<symbol::DefId> {
+        match self.hir_db.get_expr(id) {
+            expr::Expr::Name(expr::Name::Name(use_id)) => {
+                let def_id = use_id.as_def();
+                let def_kind = self.state().ty_ctx.get_def_id_kind(def_id);
+
+                if let Some(DefKind::Const(_)) = def_kind {
+                    Some(def_id)
+                } else {
+                    None
+                }
+            }
+            expr::Expr::Field(field) => self.const_ref_def(field.lhs),
+            expr::Expr::Call(call) => self.const_ref_def(call.lhs),
+            expr::Expr::Bits(bits) => self.const_ref_def(bits.lhs),
             _ => None,
-        });
+        }
+    }
 
-        for item in items {
-            let put_type = self.check_text_io_item(item.expr);
-            if ty::rules::is_error(put_type.item()) {
-                continue;
+    fn report_const_assign(&self, def_id: symbol::DefId, span: Span) {
+        self.report_const_def(def_id, span, |name| format!("cannot assign to {}", name));
+    }
+
+    /// Reports a change to a `const` declaration or `for` loop counter,
+    /// with `make_msg` given the description of the changed name
+    fn report_const_def(
+        &self,
+        def_id: symbol::DefId,
+        span: Span,
+        make_msg: impl FnOnce(&str) -> String,
+    ) {
+        let name = &self.unit.symbol_table.get_symbol(def_id).name;
+        let def_span = self.unit.symbol_table.get_def_span(def_id);
+        let mut state = self.state();
+
+        let (desc, note) = if state.for_counters.contains(&def_id) {
+            (
+                format!("`for` loop counter `{}`", name),
+                format!("`{}` is declared as a `for` loop counter here", name),
+            )
+        } else {
+            (
+                format!("const `{}`", name),
+                format!("`{}` is declared as a const here", name),
+            )
+        };
+
+        state
+            .reporter
+            .error_detailed(&make_msg(&desc), span)
+            .with_note(&note, def_span)
+            .finish();
+    }
+
+    /// Checks an implicit conversion of a value of type `source_ty` into `target_ty`.
+    ///
+    /// Compile-time values that don't fit in the target type are reported as errors,
+    /// since they would always fail the runtime range check. Any other value of a
+    /// wider integer type is reported with a warning, since it may fail the check.
+    ///
+    /// String literals longer than a sized char sequence type are also reported as
+    /// errors, and any other string that could be longer than a sized string type is
+    /// reported with a warning.
+    ///
+    /// Nothing is reported inside of `unchecked` regions, since the runtime check
+    /// isn't performed there.
+    // TODO: Also skip the narrowing warning for `cheat` conversions once they are lowered
+    fn check_conversion(
+        &self,
+        target_ty: TyRef,
+        source_ty: TyRef,
+        value_expr: Option<expr::ExprId>,
+        span: Span,
+    ) {
+        if self.state().checkedness == stmt::Checkedness::Unchecked {
+            // Only conversions which would be checked at runtime make use of the region
+            if ty::rules::integer_bounds(&target_ty).is_some()
+                || ty::rules::charseq_max_len(&target_ty).is_some()
+            {
+                self.skip_runtime_check();
             }
 
-            // Only the following are allowed to have precision & exponent options:
-            // - Int
-            // - Nat
-            // - Real
-            if !ty::rules::is_number(put_type.item()) && !ty::rules::is_error(put_type.item()) {
-                if let Some(expr) = item.opts.precision() {
-                    let span = self.hir_db.get_span(expr.into());
+            return;
+        }
 
+        if let ty::Type::CharN(ty::SeqSize::Fixed(max_len))
+        | ty::Type::StringN(ty::SeqSize::Fixed(max_len)) = target_ty.to_base_type()
+        {
+            if let Some(len) = value_expr.and_then(|id| self.literal_string_len(id)) {
+                if len > max_len.get() as usize {
                     self.state()
                         .reporter
-                        .error_detailed("invalid put option", span)
-                        .with_note(
-                            "cannot specify fraction width for this type",
-                            put_type.span(),
-                        )
+                        .error_detailed(&format!("string is too long for `{}`", *target_ty), span)
+                        .with_note(&format!("this string has a length of {}", len), span)
                         .with_info(
-                            "fraction width can only be specified for numeric put types",
+                            &format!(
+                                "`{}` values can have at most {} character{}",
+                                *target_ty,
+                                max_len,
+                                if max_len.get() == 1 { "" } else { "s" }
+                            ),
                             None,
                         )
                         .finish();
                 }
 
-                if let Some(expr) = item.opts.exponent_width() {
-                    let span = self.hir_db.get_span(expr.into());
+                // Length is known, so there's nothing else to check
+                return;
+            }
+        }
 
-                    self.state()
-                        .reporter
-                        .error_detailed("invalid put option", span)
-                        .with_note(
-                            "cannot specify exponent width for this type",
-                            put_type.span(),
-                        )
-                        .with_info(
+        if ty::rules::is_truncating(&target_ty, &source_ty) {
+            let max_len = ty::rules::charseq_max_len(&target_ty).unwrap();
+
+            self.state()
+                .reporter
+                .warn_detailed(
+                    &format!(
+                        "implicit truncating conversion from `{}` to `{}`",
+                        *source_ty, *target_ty
+                    ),
+                    span,
+                )
+                .with_info(
+                    &format!(
+                        "strings longer than {} character{} will fail the runtime length check",
+                        max_len,
+                        if max_len == 1 { "" } else { "s" }
+                    ),
+                    None,
+                )
+                .finish();
+            return;
+        }
+
+        // TODO: Check against range type bounds once they are kept around
+        if let (Some((min, max)), Some(value_expr)) =
+            (ty::rules::integer_bounds(&target_ty), value_expr)
+        {
+            // Only compile-time values can be checked, so any evaluation errors are
+            // not relevant here
+            // TODO: Allow 64-bit ops once 64-bit types are lowered
+            let const_expr =
+                self.const_eval
+                    .defer_expr(self.unit.id, value_expr, false, RestrictType::Integer);
+
+            if let Ok(ConstValue::Integer(value)) = self.const_eval.eval_expr(const_expr) {
+                let value = value.into_i128();
+
+                let out_of_bounds = |(min, max)| value < min || value > max;
+                let op_span = self.folded_op_span(value_expr);
+                let source_bounds = match &*source_ty {
+                    ty::Type::Int(_) => ty::rules::integer_bounds(&source_ty),
+                    _ => None,
+                };
+
+                if op_span.is_some() && source_bounds.is_some_and(out_of_bounds) {
+                    // Already reported as an overflow in `check_folded_op`
+                } else if let (Some(op_span), true) = (op_span, out_of_bounds((min, max))) {
+                    // Untyped operations only overflow once the target type is known
+                    self.report_int_overflow(op_span, value, &target_ty, (min, max));
+                } else if value < min || value > max {
+                    // Untyped literals don't have a meaningful type to mention
+                    let value_note = if *source_ty == ty::Type::Integer {
+                        format!("this value is {}", value)
+                    } else {
+                        format!("this `{}` value is {}", *source_ty, value)
+                    };
+
+                    self.state()
+                        .reporter
+                        .error_detailed(
+                            &format!("value is out of range for `{}`", *target_ty),
+                            span,
+                        )
+                        .with_note(&value_note, span)
+                        .with_info(
+                            &format!(
+                                "`{}` values must be between {} and {}",
+                                *target_ty, min, max
+                            ),
+                            None,
+                        )
+                        .finish();
+                }
+
+                // Value is known, so there's nothing else to check
+                return;
+            }
+        }
+
+        if ty::rules::is_narrowing(&target_ty, &source_ty) {
+            let mut builder = self.state();
+            let builder = builder.reporter.warn_detailed(
+                &format!(
+                    "implicit narrowing conversion from `{}` to `{}`",
+                    *source_ty, *target_ty
+                ),
+                span,
+            );
+
+            let builder = match ty::rules::integer_bounds(target_ty.to_base_type()) {
+                Some((min, max)) => builder.with_info(
+                    &format!(
+                        "values outside of {} to {} will fail the runtime range check",
+                        min, max
+                    ),
+                    None,
+                ),
+                None => builder,
+            };
+
+            builder.finish();
+        }
+    }
+
+    /// Gets the length of a string literal, looking through any parentheses
+    fn literal_string_len(&self, id: expr::ExprId) -> Option<usize> {
+        match self.hir_db.get_expr(id) {
+            expr::Expr::Literal(expr::Literal::String(s)) => Some(s.chars().count()),
+            expr::Expr::Paren(paren) => self.literal_string_len(paren.expr),
+            _ => None,
+        }
+    }
+
+    /// Finds the opaque type in a conversion between an opaque type and its underlying type
+    fn opaque_conversion(&self, target_ty: TyRef, source_ty: TyRef) -> Option<TyRef> {
+        match (&*target_ty, &*source_ty) {
+            (ty::Type::Opaque(opaque), _)
+                if ty::rules::is_assignable(opaque.base, source_ty) == Some(true) =>
+            {
+                Some(target_ty)
+            }
+            (_, ty::Type::Opaque(opaque))
+                if ty::rules::is_assignable(target_ty, opaque.base) == Some(true) =>
+            {
+                Some(source_ty)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reports a use of the underlying type of an opaque type, from outside
+    /// of the defining unit
+    fn report_opaque(&self, opaque_ty: TyRef, span: Span) {
+        let opaque = match &*opaque_ty {
+            ty::Type::Opaque(opaque) => opaque,
+            _ => unreachable!("not an opaque type"),
+        };
+
+        let defining_unit = opaque.def.unit_id();
+        let message = match self.hir_db.unit_name(defining_unit) {
+            Some(unit_name) => format!(
+                "type `{}` is opaque outside module `{}`",
+                opaque.name, unit_name
+            ),
+            None => format!("type `{}` is opaque outside of its module", opaque.name),
+        };
+        let export_span = self
+            .hir_db
+            .get_unit(defining_unit)
+            .exports
+            .iter()
+            .find(|export| export.is_opaque && export.def_id == opaque.def)
+            .map(|export| export.span);
+
+        let mut state = self.state();
+        let builder = state.reporter.error_detailed(&message, span);
+
+        if let Some(export_span) = export_span {
+            builder
+                .with_note(
+                    &format!("`{}` is exported as opaque here", opaque.name),
+                    export_span,
+                )
+                .finish();
+        } else {
+            builder.finish();
+        }
+    }
+
+    fn report_real_to_integer(&self, target_ty: TyRef, source_ty: TyRef, span: Span) {
+        self.state()
+            .reporter
+            .error_detailed(
+                &format!(
+                    "cannot implicitly convert `{}` into `{}`",
+                    *source_ty, *target_ty
+                ),
+                span,
+            )
+            .with_info(
+                "use `round` or `floor` to convert a real value into an integer",
+                None,
+            )
+            .finish();
+    }
+
+    fn typeck_put(&self, stmt: &stmt::Put) {
+        if let Some(stream) = stmt.stream_num {
+            self.check_text_io_arg(stream);
+        }
+
+        let items = stmt.items.iter().filter_map(|item| match item {
+            stmt::Skippable::Item(item) => Some(item),
+            _ => None,
+        });
+
+        for item in items {
+            let put_type = self.check_text_io_item(item.expr);
+            if ty::rules::is_error(put_type.item()) {
+                continue;
+            }
+
+            // Only the following are allowed to have precision & exponent options:
+            // - Int
+            // - Nat
+            // - Real
+            if !ty::rules::is_number(put_type.item()) && !ty::rules::is_error(put_type.item()) {
+                if let Some(expr) = item.opts.precision() {
+                    let span = self.hir_db.get_span(expr.into());
+
+                    self.state()
+                        .reporter
+                        .error_detailed("invalid put option", span)
+                        .with_note(
+                            "cannot specify fraction width for this type",
+                            put_type.span(),
+                        )
+                        .with_info(
+                            "fraction width can only be specified for numeric put types",
+                            None,
+                        )
+                        .finish();
+                }
+
+                if let Some(expr) = item.opts.exponent_width() {
+                    let span = self.hir_db.get_span(expr.into());
+
+                    self.state()
+                        .reporter
+                        .error_detailed("invalid put option", span)
+                        .with_note(
+                            "cannot specify exponent width for this type",
+                            put_type.span(),
+                        )
+                        .with_info(
                             "exponent width can only be specified for numeric types",
                             None,
                         )
@@ -381,157 +1395,1847 @@ impl<'a> TypeCheck<'a> {
             _ => None,
         });
 
-        for item in items {
-            // Item expression must be a variable ref
-            self.check_text_io_item(item.expr);
-            let eval_kind = self.typeck_expr(item.expr);
+        for item in items {
+            // Item expression must be a variable ref
+            self.check_text_io_item(item.expr);
+            let eval_kind = self.typeck_expr(item.expr);
+
+            if let Some(def_id) = self.const_ref_def(item.expr) {
+                let get_item_span = self.hir_db.get_span(item.expr.into());
+                self.report_const_assign(def_id, get_item_span);
+            } else if !matches!(eval_kind, EvalKind::VarRef(_)) {
+                let get_item_span = self.hir_db.get_span(item.expr.into());
+
+                // TODO: Stringify item for more clarity on the error location
+                self.state()
+                    .reporter
+                    .error_detailed("cannot assign into get item expression", get_item_span)
+                    .with_note(
+                        "this expression cannot be used as a variable reference",
+                        get_item_span,
+                    )
+                    .finish();
+            }
+
+            if let stmt::GetWidth::Chars(expr) = item.width {
+                self.check_text_io_arg(expr);
+            }
+        }
+    }
+
+    fn check_text_io_arg(&self, id: expr::ExprId) {
+        let ty_ref = self.get_spanned_expr_ty_ref(id);
+
+        self.check_integer_type(ty_ref);
+    }
+
+    fn check_integer_type(&self, ty_ref: Spanned<TyRef>) {
+        let ty = ty_ref.item().to_base_type();
+
+        if !ty::rules::is_integer(ty) && !ty::rules::is_error(ty) {
+            // TODO: Stringify type for more clarity on the error
+            self.state()
+                .reporter
+                .error_detailed("mismatched types", ty_ref.span())
+                .with_note("expected integer type", ty_ref.span())
+                .finish();
+        }
+    }
+
+    fn check_boolean_type(&self, ty_ref: Spanned<TyRef>) {
+        let ty = ty_ref.item().to_base_type();
+
+        if !matches!(ty, ty::Type::Boolean) && !ty::rules::is_error(ty) {
+            self.state()
+                .reporter
+                .error_detailed("mismatched types", ty_ref.span())
+                .with_note("expected boolean type", ty_ref.span())
+                .finish();
+        }
+    }
+
+    fn check_text_io_item(&self, id: expr::ExprId) -> Spanned<TyRef> {
+        let ty_ref = self.get_spanned_expr_ty_ref(id);
+
+        // Must be a valid put/get type
+        // Can be one of the following:
+        // - Int
+        // - Nat
+        // - Real
+        // - Char
+        // - Char(N)
+        // - String
+        // - String(N)
+        // - Boolean
+        // - Enum
+        // - Range
+
+        match &*(*ty_ref.item()) {
+            ty::Type::Error
+            | ty::Type::Boolean
+            | ty::Type::Int(_)
+            | ty::Type::Nat(_)
+            | ty::Type::Real(_)
+            | ty::Type::Integer
+            | ty::Type::Char
+            | ty::Type::String
+            | ty::Type::CharN(_)
+            | ty::Type::StringN(_)
+            | ty::Type::Range(_)
+            | ty::Type::Enum(_) => ty_ref,
+            ty::Type::Opaque(_) => {
+                self.report_opaque(*ty_ref.item(), ty_ref.span());
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                Spanned::new(err, ty_ref.span())
+            }
+            ty::Type::Record(_) | ty::Type::Union(_) => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", ty_ref.span())
+                    .with_note(
+                        "records and unions cannot be used for text I/O",
+                        ty_ref.span(),
+                    )
+                    .with_info("put or get each field instead", None)
+                    .finish();
+
+                // Don't report any more errors for this item
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                Spanned::new(err, ty_ref.span())
+            }
+            ty::Type::Pointer(_) | ty::Type::Collection(_) | ty::Type::Nil(_) => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", ty_ref.span())
+                    .with_note(
+                        "pointers and collections cannot be used for text I/O",
+                        ty_ref.span(),
+                    )
+                    .finish();
+
+                // Don't report any more errors for this item
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                Spanned::new(err, ty_ref.span())
+            }
+            ty::Type::Array(_) => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", ty_ref.span())
+                    .with_note("arrays cannot be used for text I/O", ty_ref.span())
+                    .with_info("put or get each element instead", None)
+                    .finish();
+
+                // Don't report any more errors for this item
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                Spanned::new(err, ty_ref.span())
+            }
+            ty::Type::Set(_) | ty::Type::Subprogram(_) | ty::Type::Condition(_) => {
+                let what = match &**ty_ref.item() {
+                    ty::Type::Set(_) => "sets",
+                    ty::Type::Subprogram(_) => "subprograms",
+                    _ => "conditions",
+                };
+
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", ty_ref.span())
+                    .with_note(
+                        &format!("{} cannot be used for text I/O", what),
+                        ty_ref.span(),
+                    )
+                    .finish();
+
+                // Don't report any more errors for this item
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                Spanned::new(err, ty_ref.span())
+            }
+        }
+    }
+
+    fn typeck_quit(&self, stmt: &stmt::Quit) {
+        // Reason must be an integer exception code
+        if let Some(reason) = stmt.reason {
+            let ty_ref = self.get_spanned_expr_ty_ref(reason);
+
+            self.check_integer_type(ty_ref);
+        }
+    }
+
+    fn typeck_call_stmt(&self, stmt: &stmt::Call) {
+        if let Some(use_id) = self.process_use(stmt.callee) {
+            self.report_process_use(use_id, |name| format!("cannot call process `{}`", name));
+
+            for arg in &stmt.arguments {
+                self.typeck_expr(*arg);
+            }
+            return;
+        }
+
+        let callee_ty = self.typeck_expr(stmt.callee).as_ty_ref();
+
+        for arg in &stmt.arguments {
+            self.typeck_expr(*arg);
+        }
+
+        match &*callee_ty {
+            ty::Type::Error => {}
+            ty::Type::Subprogram(subprogram) => {
+                let span = self.hir_db.get_span(stmt.callee.into());
+                self.check_call_args(span, subprogram, &stmt.arguments);
+            }
+            _ => {
+                let span = self.hir_db.get_span(stmt.callee.into());
+
+                self.state()
+                    .reporter
+                    .error_detailed("cannot call this expression", span)
+                    .with_note(&format!("this is of type `{}`", callee_ty), span)
+                    .with_info("only procedures and functions can be called", None)
+                    .finish();
+            }
+        }
+    }
+
+    /// Checks the arguments of a call against the parameters of the called subprogram
+    fn check_call_args(&self, call_span: Span, callee: &ty::Subprogram, args: &[expr::ExprId]) {
+        let params = callee.params.as_deref().unwrap_or_default();
+
+        if args.len() != params.len() {
+            let plural = |count: usize| if count == 1 { "" } else { "s" };
+
+            self.state()
+                .reporter
+                .error_detailed("wrong number of arguments", call_span)
+                .with_note(
+                    &format!(
+                        "expected {} argument{}, found {}",
+                        params.len(),
+                        plural(params.len()),
+                        args.len()
+                    ),
+                    call_span,
+                )
+                .finish();
+        }
+
+        for (&arg_id, param) in args.iter().zip(params) {
+            let arg = self.get_spanned_expr_ty_ref(arg_id);
+            let arg_ty = *arg.item();
+
+            // `var` parameters can change the argument, so it must be a variable reference
+            if param.is_var && self.typeck_expr(arg_id).as_mut_ty_ref().is_none() {
+                self.report_immutable_var_arg(arg_id, arg.span());
+                continue;
+            }
+
+            // `var` parameters refer to the argument, so the types must be the same
+            let matches = if param.is_var {
+                arg_ty == param.ty || ty::rules::is_error(&arg_ty) || ty::rules::is_error(&param.ty)
+            } else {
+                ty::rules::is_assignable(param.ty, arg_ty) != Some(false)
+            };
+
+            if !matches {
+                let mut state = self.state();
+                let msg = state
+                    .reporter
+                    .error_detailed("mismatched types", arg.span())
+                    .with_note(&format!("this is of type `{}`", arg_ty), arg.span())
+                    .with_info(
+                        &format!("expected an argument of type `{}`", param.ty),
+                        None,
+                    );
+
+                if param.is_var {
+                    msg.with_info(
+                        "arguments to `var` parameters must have the same type",
+                        None,
+                    )
+                    .finish();
+                } else {
+                    msg.finish();
+                }
+            }
+        }
+    }
+
+    fn report_immutable_var_arg(&self, arg_id: expr::ExprId, span: Span) {
+        if ty::rules::is_error(&self.typeck_expr(arg_id).as_ty_ref()) {
+            return;
+        }
+
+        if let Some(def_id) = self.const_ref_def(arg_id) {
+            self.report_const_def(def_id, span, |name| {
+                format!("cannot pass {} to a `var` parameter", name)
+            });
+        } else {
+            self.state()
+                .reporter
+                .error_detailed("cannot pass this expression to a `var` parameter", span)
+                .with_note(
+                    "this expression cannot be used as a variable reference",
+                    span,
+                )
+                .finish();
+        }
+    }
+
+    fn typeck_tag(&self, id: stmt::StmtId, stmt: &stmt::Tag) {
+        let union_eval = self.typeck_expr(stmt.union_ref);
+        let union_span = self.hir_db.get_span(stmt.union_ref.into());
+        let tag_val_ty = self.get_spanned_expr_ty_ref(stmt.tag_val);
+
+        // Changing the tag also changes the union, so it must be a variable reference
+        let union_ty = if let Some(ty) = union_eval.as_mut_ty_ref() {
+            ty
+        } else if let Some(def_id) = self.const_ref_def(stmt.union_ref) {
+            self.report_const_assign(def_id, union_span);
+            return;
+        } else {
+            self.state()
+                .reporter
+                .error_detailed("cannot `tag` this expression", union_span)
+                .with_note(
+                    "this expression cannot be used as a variable reference",
+                    union_span,
+                )
+                .finish();
+            return;
+        };
+
+        let union = match &*union_ty {
+            ty::Type::Error => return,
+            ty::Type::Union(union) => union,
+            ty => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", union_span)
+                    .with_note(&format!("this is of type `{}`", ty), union_span)
+                    .with_info("`tag` can only be used with unions", None)
+                    .finish();
+                return;
+            }
+        };
+        let union_def_span = self.hir_db.get_span(union.def.into());
+
+        // Unions inside of records or arrays are changed along with the rest of the value
+        let def_id = if let Some(def_id) = self.whole_var_def(stmt.union_ref) {
+            def_id
+        } else {
+            self.state()
+                .reporter
+                .error_detailed("cannot `tag` this expression", union_span)
+                .with_note("this is not a union variable", union_span)
+                .with_note("union type declared here", union_def_span)
+                .with_info("only whole union variables can be tagged", None)
+                .finish();
+            return;
+        };
+
+        // Whatever variant was active before is replaced
+        self.state().tagged_unions.remove(&def_id);
+        let tag_ty = union.tag.ty;
+
+        if !ty::rules::is_assignable(tag_ty, *tag_val_ty.item()).unwrap_or(true) {
+            self.state()
+                .reporter
+                .error_detailed("mismatched types", tag_val_ty.span())
+                .with_note(
+                    &format!("this is of type `{}`", tag_val_ty.item()),
+                    tag_val_ty.span(),
+                )
+                .with_note("union type declared here", union_def_span)
+                .with_info(
+                    &format!("expected a value of the tag type `{}`", tag_ty),
+                    None,
+                )
+                .finish();
+            return;
+        }
+
+        // Only compile-time values can be checked, so any evaluation errors are
+        // not relevant here
+        let value = match self.eval_range_bound(stmt.tag_val) {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+
+        if let Some((min, max)) = self.union_tag_bounds(union.def) {
+            if self.state().checkedness == stmt::Checkedness::Unchecked {
+                // The tag range check is skipped
+                self.skip_runtime_check();
+            } else if value < min || value > max {
+                let show = |ordinal: i128| match tag_ty.to_base_type() {
+                    ty::Type::Char => {
+                        format!("{:?}", char::from_u32(ordinal as u32).unwrap_or('?'))
+                    }
+                    ty::Type::Boolean => (ordinal != 0).to_string(),
+                    _ => ordinal.to_string(),
+                };
+
+                self.state()
+                    .reporter
+                    .error_detailed(
+                        &format!("tag value is out of range for `{}`", union_ty),
+                        tag_val_ty.span(),
+                    )
+                    .with_note(&format!("this value is {}", show(value)), tag_val_ty.span())
+                    .with_note("union type declared here", union_def_span)
+                    .with_info(
+                        &format!("tags must be between {} and {}", show(min), show(max)),
+                        None,
+                    )
+                    .finish();
+                return;
+            }
+        }
+
+        if let Some(variant) = self.selected_variant(union.def, value) {
+            let tag_span = self.hir_db.get_span(id.into());
+
+            self.state()
+                .tagged_unions
+                .insert(def_id, TaggedUnion { variant, tag_span });
+        }
+    }
+
+    /// Finds the variant of a union selected by the tag value `value`,
+    /// falling back to the `label :` variant if no other variant is selected
+    fn selected_variant(&self, union_def: hir_ty::TypeId, value: i128) -> Option<usize> {
+        let union = match self.hir_db.get_type(union_def) {
+            hir_ty::Type::Union(union) => union,
+            _ => return None,
+        };
+
+        union
+            .variants
+            .iter()
+            .position(|variant| {
+                variant
+                    .selectors
+                    .iter()
+                    .any(|selector| self.eval_range_bound(*selector).ok() == Some(value))
+            })
+            .or_else(|| {
+                union
+                    .variants
+                    .iter()
+                    .position(|variant| variant.selectors.is_empty())
+            })
+    }
+
+    /// Warns about accessing a field of a union variable that isn't in the variant
+    /// selected by the last `tag` statement, since the access will always fail the
+    /// runtime tag check
+    fn check_tagged_field(&self, union: &ty::Union, expr: &expr::Field) {
+        let def_id = match self.whole_var_def(expr.lhs) {
+            Some(def_id) => def_id,
+            None => return,
+        };
+        let tagged = match self.state().tagged_unions.get(&def_id) {
+            Some(tagged) => *tagged,
+            None => return,
+        };
+
+        let field_name = expr.field.item();
+        let in_variant = union.variants[tagged.variant]
+            .iter()
+            .any(|field| field.name == *field_name);
+
+        if in_variant {
+            return;
+        } else if self.state().checkedness == stmt::Checkedness::Unchecked {
+            self.skip_runtime_check();
+            return;
+        }
+
+        let name = &self.unit.symbol_table.get_symbol(def_id).name;
+
+        self.state()
+            .reporter
+            .warn_detailed(
+                &format!(
+                    "field `{}` is not in the active variant of `{}`",
+                    field_name, name
+                ),
+                expr.field.span(),
+            )
+            .with_note(
+                &format!("`{}` is tagged with a different variant here", name),
+                tagged.tag_span,
+            )
+            .with_info("accessing this field will fail the runtime tag check", None)
+            .finish();
+    }
+
+    /// Finds the variable that the reference expression `id` refers to as a whole
+    fn whole_var_def(&self, id: expr::ExprId) -> Option<symbol::DefId> {
+        match self.hir_db.get_expr(id) {
+            expr::Expr::Name(expr::Name::Name(use_id)) => Some(use_id.as_def()),
+            expr::Expr::Paren(paren) => self.whole_var_def(paren.expr),
+            _ => None,
+        }
+    }
+
+    /// Gets the ordinal bounds of a union's tag, if the tag is a range type with known bounds.
+    ///
+    /// Tags of other types can't be given an out of range value, since their range
+    /// covers every value of the type.
+    // TODO: Also look through aliased range types once range type bounds are kept around
+    fn union_tag_bounds(&self, union_def: hir_ty::TypeId) -> Option<(i128, i128)> {
+        let tag_ty = match self.hir_db.get_type(union_def) {
+            hir_ty::Type::Union(union) => union.tag_ty,
+            _ => return None,
+        };
+
+        match self.hir_db.get_type(tag_ty) {
+            hir_ty::Type::Range(hir_ty::Range {
+                start,
+                end: hir_ty::RangeEnd::Expr(end),
+            }) => Some((
+                self.eval_range_bound(*start).ok()?,
+                self.eval_range_bound(*end).ok()?,
+            )),
+            _ => None,
+        }
+    }
+
+    fn typeck_fork(&self, stmt: &stmt::Fork) {
+        if self.process_use(stmt.process).is_none() {
+            let eval = self.typeck_expr(stmt.process);
+            let ty_ref = eval.as_ty_ref();
+            let span = self.hir_db.get_span(stmt.process.into());
+
+            match &*ty_ref {
+                ty::Type::Error => {}
+                ty::Type::Subprogram(subprogram)
+                    if self.dialect.fork_procedures
+                        && subprogram.kind == hir_ty::SubprogramKind::Procedure =>
+                {
+                    self.check_call_args(span, subprogram, &stmt.arguments);
+                }
+                _ => {
+                    let info = if self.dialect.fork_procedures {
+                        "only processes and procedures can be started with `fork`"
+                    } else {
+                        "only processes can be started with `fork`"
+                    };
+
+                    self.state()
+                        .reporter
+                        .error_detailed("cannot fork this expression", span)
+                        .with_note(&format!("this is of type `{}`", ty_ref), span)
+                        .with_info(info, None)
+                        .finish();
+                }
+            }
+        }
+
+        // TODO: Check the arguments against the process' parameters
+        for arg in &stmt.arguments {
+            self.typeck_expr(*arg);
+        }
+
+        if let Some(status) = stmt.status {
+            let ty_ref = self.get_spanned_expr_ty_ref(status);
+            self.check_boolean_type(ty_ref);
+        }
+
+        if let Some(stack_size) = stmt.stack_size {
+            let ty_ref = self.get_spanned_expr_ty_ref(stack_size);
+            self.check_integer_type(ty_ref);
+        }
+
+        if let Some(process_desc) = stmt.process_desc {
+            self.typeck_expr(process_desc);
+        }
+    }
+
+    /// Gets the use of a process, if `id` is a name expression referring to one
+    fn process_use(&self, id: expr::ExprId) -> Option<symbol::UseId> {
+        let use_id = match self.hir_db.get_expr(id) {
+            expr::Expr::Name(expr::Name::Name(use_id)) => *use_id,
+            _ => return None,
+        };
+
+        let def_kind = self.state().ty_ctx.get_def_id_kind(use_id.as_def());
+        (def_kind == Some(DefKind::Process)).then_some(use_id)
+    }
+
+    /// Reports a use of a process outside of a `fork` statement,
+    /// with `message` building the error message from the process name
+    fn report_process_use(&self, use_id: symbol::UseId, message: impl FnOnce(&str) -> String) {
+        let name = &self.unit.symbol_table.get_symbol(use_id.as_def()).name;
+        let use_span = self.unit.symbol_table.get_use_span(use_id);
+
+        self.state()
+            .reporter
+            .error_detailed(&message(name), use_span)
+            .with_note(
+                &format!("`{}` declared here", name),
+                self.unit.symbol_table.get_def_span(use_id.as_def()),
+            )
+            .with_info("processes can only be started with `fork`", None)
+            .finish();
+    }
+
+    fn typeck_block(&self, stmt: &stmt::Block) {
+        self.typeck_nested_stmts(&stmt.stmts);
+    }
+
+    fn typeck_for(&self, stmt: &stmt::For) {
+        let counter_ty = match stmt.bounds {
+            stmt::ForBounds::Implicit(bounds_ty) => {
+                let ty_ref = self.lower_type(bounds_ty);
+
+                // Must name a range or enum type, so that there's a start and end bound
+                if !matches!(
+                    &*ty_ref,
+                    ty::Type::Range(_) | ty::Type::Enum(_) | ty::Type::Error
+                ) {
+                    let span = self.hir_db.get_span(bounds_ty.into());
+
+                    self.state()
+                        .reporter
+                        .error_detailed("mismatched types", span)
+                        .with_note(&format!("this is of type `{}`", ty_ref), span)
+                        .with_info(
+                            "implicit `for` loop bounds must be a range or enum type",
+                            None,
+                        )
+                        .finish();
+                }
+
+                ty_ref
+            }
+            stmt::ForBounds::Full(start, end) => {
+                let start = self.get_spanned_expr_ty_ref(start);
+                let end = self.get_spanned_expr_ty_ref(end);
+                self.check_for_bounds(start, end)
+            }
+        };
+
+        if let Some(step_by) = stmt.step_by {
+            let ty_ref = self.get_spanned_expr_ty_ref(step_by);
+            self.check_integer_type(ty_ref);
+        }
+
+        if let Some(counter_def) = stmt.counter_def {
+            let mut state = self.state();
+            state
+                .ty_ctx
+                .map_def_id(counter_def, DefKind::Const(counter_ty));
+            state.for_counters.insert(counter_def);
+        }
+
+        self.typeck_nested_stmts(&stmt.stmts);
+    }
+
+    /// Checks that both `for` loop bounds are of the same discrete type,
+    /// giving back the type of the loop counter
+    fn check_for_bounds(&self, start: Spanned<TyRef>, end: Spanned<TyRef>) -> TyRef {
+        let (start_ty, end_ty) = (*start.item(), *end.item());
+
+        if ty::rules::is_error(&start_ty) || ty::rules::is_error(&end_ty) {
+            return self.state().ty_ctx.add_type(ty::Type::Error);
+        }
+
+        let (start_base, end_base) = (start_ty.to_base_type(), end_ty.to_base_type());
+        let is_same_discrete = match (start_base, end_base) {
+            (start, end) if ty::rules::is_integer(start) => ty::rules::is_integer(end),
+            (ty::Type::Char, ty::Type::Char) | (ty::Type::Boolean, ty::Type::Boolean) => true,
+            (ty::Type::Enum(start), ty::Type::Enum(end)) => start == end,
+            _ => false,
+        };
+
+        if !is_same_discrete {
+            self.state()
+                .reporter
+                .error_detailed("mismatched types", end.span())
+                .with_note(&format!("this is of type `{}`", end_ty), end.span())
+                .with_note(&format!("this is of type `{}`", start_ty), start.span())
+                .with_info(
+                    "`for` loop bounds must both be integers, `char`s, `boolean`s, or enums of the same type",
+                    None,
+                )
+                .finish();
+
+            return self.state().ty_ctx.add_type(ty::Type::Error);
+        }
+
+        // Bare integer literals infer to `int`
+        match (start_base, end_base) {
+            (ty::Type::Integer, ty::Type::Integer) => self
+                .state()
+                .ty_ctx
+                .add_type(ty::Type::Int(ty::IntSize::Int)),
+            (ty::Type::Integer, _) => end_ty,
+            _ => start_ty,
+        }
+    }
+
+    fn typeck_exit(&self, stmt: &stmt::Exit) {
+        if let Some(condition) = stmt.when_condition {
+            let ty_ref = self.get_spanned_expr_ty_ref(condition);
+            self.check_boolean_type(ty_ref);
+        }
+    }
+
+    fn typeck_if(&self, stmt: &stmt::If) {
+        let ty_ref = self.get_spanned_expr_ty_ref(stmt.condition);
+        self.check_boolean_type(ty_ref);
+
+        self.typeck_nested_stmts(&stmt.true_branch);
+
+        match &stmt.false_branch {
+            stmt::FalseBranch::None => {}
+            stmt::FalseBranch::ElseIf(elseif) => self.typeck_stmt(*elseif),
+            stmt::FalseBranch::Else(stmts) => self.typeck_nested_stmts(stmts),
+        }
+    }
+
+    fn typeck_case(&self, stmt: &stmt::Case) {
+        let discriminant = self.get_spanned_expr_ty_ref(stmt.discriminant);
+        let discriminant_ty = *discriminant.item();
+
+        // Must be a discrete type, so that each value can select an arm
+        let is_discrete = {
+            let ty = discriminant_ty.to_base_type();
+
+            ty::rules::is_integer(ty)
+                || matches!(ty, ty::Type::Char | ty::Type::Boolean | ty::Type::Enum(_))
+                || ty::rules::is_error(ty)
+        };
+
+        if !is_discrete {
+            self.state()
+                .reporter
+                .error_detailed("mismatched types", discriminant.span())
+                .with_note(
+                    &format!("this is of type `{}`", discriminant_ty),
+                    discriminant.span(),
+                )
+                .with_info(
+                    "`case` statements can only select on integer, `char`, `boolean`, or enum values",
+                    None,
+                )
+                .finish();
+        }
+
+        for arm in &stmt.arms {
+            for selector in arm.selectors.iter().flatten() {
+                let selector = self.get_spanned_expr_ty_ref(*selector);
+                let selector_ty = *selector.item();
+
+                if is_discrete
+                    && ty::rules::is_assignable(discriminant_ty, selector_ty) == Some(false)
+                {
+                    self.state()
+                        .reporter
+                        .error_detailed("mismatched types", selector.span())
+                        .with_note(
+                            &format!("this is of type `{}`", selector_ty),
+                            selector.span(),
+                        )
+                        .with_info(
+                            &format!("expected a selector of type `{}`", discriminant_ty),
+                            None,
+                        )
+                        .finish();
+                }
+            }
+
+            self.typeck_nested_stmts(&arm.stmts);
+        }
+    }
+
+    fn typeck_result(&self, id: stmt::StmtId, stmt: &stmt::Result) {
+        let value = self.get_spanned_expr_ty_ref(stmt.expr);
+        let value_ty = *value.item();
+        let body_kind = self.state().bodies.last().copied();
+
+        match body_kind {
+            Some(BodyKind::Function(result_ty)) => {
+                if ty::rules::is_assignable(result_ty, value_ty) == Some(false) {
+                    self.state()
+                        .reporter
+                        .error_detailed("mismatched types", value.span())
+                        .with_note(&format!("this is of type `{}`", value_ty), value.span())
+                        .with_info(&format!("expected a result of type `{}`", result_ty), None)
+                        .finish();
+                }
+            }
+            // Using `result` in a process is already reported during validation
+            Some(BodyKind::Process) => {}
+            _ => {
+                let span = self.hir_db.get_span(id.into());
+
+                self.state()
+                    .reporter
+                    .error_detailed("cannot use `result` here", span)
+                    .with_info("`result` statements are only allowed in functions", None)
+                    .finish();
+            }
+        }
+    }
+
+    /// Checks a nested list of statements, such as the body of a block.
+    ///
+    /// Variants selected by `tag` statements are only tracked within straight-line
+    /// code, so they are forgotten when going in and out of the list.
+    fn typeck_nested_stmts(&self, stmts: &[stmt::StmtId]) {
+        self.state().tagged_unions.clear();
+        self.state().scope_depth += 1;
+
+        for stmt in stmts {
+            self.typeck_stmt(*stmt)
+        }
+
+        self.state().scope_depth -= 1;
+        self.state().tagged_unions.clear();
+    }
+
+    fn typeck_literal(&self, expr: &toc_hir::expr::Literal) -> EvalKind {
+        let ty = match expr {
+            toc_hir::expr::Literal::Integer(_) => ty::Type::Integer,
+            toc_hir::expr::Literal::Real(_) => ty::Type::Real(ty::RealSize::Real),
+            toc_hir::expr::Literal::Char(_) => ty::Type::Char,
+            toc_hir::expr::Literal::CharSeq(s) => {
+                let len = s.chars().count();
+                let size = NonZeroU32::new(len.try_into().unwrap_or(u32::MAX)).unwrap();
+                let seq_size = ty::SeqSize::Fixed(size);
+                ty::Type::CharN(seq_size)
+            }
+            toc_hir::expr::Literal::String(_) => ty::Type::String,
+            toc_hir::expr::Literal::Boolean(_) => ty::Type::Boolean,
+        };
+
+        // Evaluates to a value
+        EvalKind::Value(self.state().ty_ctx.add_type(ty))
+    }
+
+    fn typeck_init(&self, id: expr::ExprId, expr: &expr::Init) -> EvalKind {
+        let span = self.hir_db.get_span(id.into());
+
+        self.state()
+            .reporter
+            .error_detailed("cannot use `init` here", span)
+            .with_info(
+                "`init` can only be used as the initial value of an `array`, `record`, or `set` declaration with an explicit type",
+                None,
+            )
+            .finish();
+
+        for value in &expr.exprs {
+            self.typeck_expr(*value);
+        }
+
+        let err = self.state().ty_ctx.add_type(ty::Type::Error);
+        EvalKind::Error(err)
+    }
+
+    /// Checks the values of an `init` initializer against the declared type
+    /// that it's initializing.
+    ///
+    /// Gives back the type of the initialized value, which is only different from
+    /// `target_ty` for implicit size arrays.
+    fn typeck_init_aggregate(
+        &self,
+        id: expr::ExprId,
+        init: &expr::Init,
+        target_ty: TyRef,
+    ) -> TyRef {
+        let target_ty = self
+            .size_implicit_array(target_ty, init.exprs.len())
+            .unwrap_or(target_ty);
+
+        // Initializers take on the type that they're initializing
+        {
+            let mut state = self.state();
+            state
+                .cached_expr_evals
+                .insert(id, EvalKind::Value(target_ty));
+            state.ty_ctx.map_expr_type(id, target_ty);
+        }
+
+        let (elem_tys, expected_count) = match &*target_ty {
+            ty::Type::Array(array) => {
+                // Flexible arrays can be resized, so any number of values is fine
+                let count = if array.is_flexible {
+                    None
+                } else {
+                    Self::array_elem_count(array)
+                };
+
+                (vec![array.elem; init.exprs.len()], count)
+            }
+            ty::Type::Record(record) => {
+                let fields = record.fields.iter().map(|field| field.ty).collect();
+                (fields, Some(record.fields.len()))
+            }
+            ty => {
+                if !matches!(ty, ty::Type::Error) {
+                    let span = self.hir_db.get_span(id.into());
+
+                    self.state()
+                        .reporter
+                        .error_detailed("mismatched types", span)
+                        .with_note(
+                            &format!("`init` can't initialize values of type `{}`", ty),
+                            span,
+                        )
+                        .with_info(
+                            "`init` can only initialize `array`, `record`, and `set` values",
+                            None,
+                        )
+                        .finish();
+                }
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                (vec![err; init.exprs.len()], None)
+            }
+        };
+
+        if let Some(expected) = expected_count.filter(|count| *count != init.exprs.len()) {
+            let span = self.hir_db.get_span(id.into());
+            let plural = |count: usize| if count == 1 { "" } else { "s" };
+
+            self.state()
+                .reporter
+                .error_detailed("wrong number of initializer values", span)
+                .with_note(
+                    &format!(
+                        "expected {} value{}, found {}",
+                        expected,
+                        plural(expected),
+                        init.exprs.len()
+                    ),
+                    span,
+                )
+                .finish();
+        }
+
+        for (idx, value) in init.exprs.iter().enumerate() {
+            match elem_tys.get(idx) {
+                Some(elem_ty) => self.typeck_init_value(*value, *elem_ty),
+                None => {
+                    // Extra values are already reported
+                    self.typeck_expr(*value);
+                }
+            }
+        }
+
+        target_ty
+    }
+
+    /// Checks a single value of an `init` initializer
+    fn typeck_init_value(&self, value: expr::ExprId, elem_ty: TyRef) {
+        if let expr::Expr::Init(init) = self.hir_db.get_expr(value) {
+            // Nested aggregates are initialized by nested `init`s
+            self.typeck_init_aggregate(value, init, elem_ty);
+            return;
+        }
+
+        let value_ty = self.get_spanned_expr_ty_ref(value);
+        let value_span = value_ty.span();
+        let value_ty = *value_ty.item();
+
+        if let Some(false) = ty::rules::is_assignable(elem_ty, value_ty) {
+            self.state()
+                .reporter
+                .error_detailed("mismatched types", value_span)
+                .with_note(&format!("this is of type `{}`", value_ty), value_span)
+                .with_info(&format!("expected a value of type `{}`", elem_ty), None)
+                .finish();
+        } else {
+            self.check_conversion(elem_ty, value_ty, Some(value), value_span);
+        }
+    }
+
+    /// Checks if `ty` is an array with an unsized (`*`) upper bound, which gets its size from an `init`
+    fn is_implicit_size_array(ty: &ty::Type) -> bool {
+        match ty {
+            ty::Type::Array(array) if !array.is_flexible => array
+                .ranges
+                .iter()
+                .any(|range| matches!(&**range, ty::Type::Range(ty::Range { size: None, .. }))),
+            _ => false,
+        }
+    }
+
+    /// Sizes an implicit size array (e.g. `array 1 .. * of int`) to have `count` elements.
+    ///
+    /// Only single dimension arrays can be sized this way.
+    fn size_implicit_array(&self, ty: TyRef, count: usize) -> Option<TyRef> {
+        let array = match &*ty {
+            ty::Type::Array(array) if Self::is_implicit_size_array(&ty) => array,
+            _ => return None,
+        };
+        let (base, start) = match array.ranges.as_slice() {
+            [range] => match &**range {
+                ty::Type::Range(range) => (range.base, range.start),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let size = count.try_into().ok();
+
+        let mut state = self.state();
+        let range = state
+            .ty_ctx
+            .add_type(ty::Type::Range(ty::Range { base, start, size }));
+
+        Some(state.ty_ctx.add_type(ty::Type::Array(ty::Array {
+            is_flexible: false,
+            ranges: vec![range],
+            elem: array.elem,
+        })))
+    }
+
+    /// Gets the number of elements in an array, if all of its index types have a known size
+    fn array_elem_count(array: &ty::Array) -> Option<usize> {
+        array.ranges.iter().try_fold(1usize, |count, range| {
+            let size = match &**range {
+                ty::Type::Range(range) => range.size? as usize,
+                ty::Type::Char => 256,
+                ty::Type::Boolean => 2,
+                _ => return None,
+            };
+
+            count.checked_mul(size)
+        })
+    }
+
+    fn typeck_nil(&self, expr: &expr::Nil) -> EvalKind {
+        let collection = if let Some(id) = expr.collection {
+            let ty_ref = self.lower_type(id);
+
+            match &*ty_ref {
+                // TODO: Also accept classes once they are lowered
+                ty::Type::Collection(_) => Some(ty_ref),
+                ty::Type::Error => return EvalKind::Error(ty_ref),
+                ty => {
+                    let span = self.hir_db.get_span(id.into());
+
+                    self.state()
+                        .reporter
+                        .error_detailed("mismatched types", span)
+                        .with_note(&format!("this is of type `{}`", ty), span)
+                        .with_info("`nil` can only belong to a collection or class", None)
+                        .finish();
+
+                    let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                    return EvalKind::Error(err);
+                }
+            }
+        } else {
+            None
+        };
+
+        EvalKind::Value(self.state().ty_ctx.add_type(ty::Type::Nil(collection)))
+    }
+
+    fn typeck_binary(&self, id: expr::ExprId, expr: &toc_hir::expr::Binary) -> EvalKind {
+        // TODO: do full binexpr typechecks
+        let ty = self.type_check_binary_op(expr.lhs, expr.op, expr.rhs);
+
+        if let expr::BinaryOp::And | expr::BinaryOp::Or | expr::BinaryOp::Xor = expr.op.item() {
+            self.record_op_kind(id, &ty);
+        }
+
+        self.check_folded_op(id, expr.op.span(), &ty, &[expr.lhs, expr.rhs]);
+
+        // Evaluates to a value
+        EvalKind::Value(self.state().ty_ctx.add_type(ty))
+    }
+
+    fn typeck_unary(&self, id: expr::ExprId, expr: &toc_hir::expr::Unary) -> EvalKind {
+        let ty = self.type_check_unary_op(expr.op, expr.rhs);
+
+        if let expr::UnaryOp::Not = expr.op.item() {
+            self.record_op_kind(id, &ty);
+        }
+
+        self.check_folded_op(id, expr.op.span(), &ty, &[expr.rhs]);
+
+        // Evaluates to a value
+        EvalKind::Value(self.state().ty_ctx.add_type(ty))
+    }
+
+    /// Reports any errors from folding the operation at compile-time, e.g. division by zero.
+    /// Results outside of the bounds of a sized `int` result type are reported as overflows.
+    /// Other result types aren't checked, since e.g. `nat` operations can produce
+    /// negative values.
+    ///
+    /// Errors from operands that can't be evaluated at compile-time are ignored, as are errors
+    /// from nested operations since those are reported at the nested operation.
+    fn check_folded_op(
+        &self,
+        id: expr::ExprId,
+        op_span: Span,
+        result_ty: &ty::Type,
+        operands: &[expr::ExprId],
+    ) {
+        match self.eval_folded_int(id) {
+            Err(err) => {
+                if err.is_computation_error() && err.span() == op_span {
+                    err.report_to(&mut self.state().reporter);
+                }
+            }
+            Ok(Some(value)) => {
+                let (min, max) = match (result_ty, ty::rules::integer_bounds(result_ty)) {
+                    (ty::Type::Int(_), Some(bounds)) => bounds,
+                    _ => return,
+                };
+                let in_bounds = |value: i128| (min..=max).contains(&value);
+
+                // Out of bounds operands are already reported at their own operation
+                let operands_in_bounds = operands.iter().all(|operand| {
+                    !matches!(self.eval_folded_int(*operand), Ok(Some(value)) if !in_bounds(value))
+                });
+
+                if !in_bounds(value) && operands_in_bounds {
+                    self.report_int_overflow(op_span, value, result_ty, (min, max));
+                }
+            }
+            Ok(None) => {}
+        }
+    }
+
+    /// Evaluates `id` at compile-time, giving the value if it's an integer
+    fn eval_folded_int(&self, id: expr::ExprId) -> Result<Option<i128>, ConstError> {
+        // TODO: Allow 64-bit ops once 64-bit types are lowered
+        let const_expr = self
+            .const_eval
+            .defer_expr(self.unit.id, id, false, RestrictType::None);
+
+        match self.const_eval.eval_expr(const_expr)? {
+            ConstValue::Integer(value) => Ok(Some(value.into_i128())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Reports that the operation at `op_span` produced a `value` outside of the `bounds` of `ty`
+    fn report_int_overflow(&self, op_span: Span, value: i128, ty: &ty::Type, bounds: (i128, i128)) {
+        let (min, max) = bounds;
+
+        self.state()
+            .reporter
+            .error_detailed("integer overflow in compile-time expression", op_span)
+            .with_note(&format!("the result is {}", value), op_span)
+            .with_info(
+                &format!("`{}` values must be between {} and {}", ty, min, max),
+                None,
+            )
+            .finish();
+    }
+
+    /// Finds the operator of a binary operation, looking through parentheses.
+    /// Unary operations aren't included, so that negative literals aren't treated as overflows.
+    fn folded_op_span(&self, id: expr::ExprId) -> Option<Span> {
+        match self.hir_db.get_expr(id) {
+            expr::Expr::Binary(expr) => Some(expr.op.span()),
+            expr::Expr::Paren(paren) => self.folded_op_span(paren.expr),
+            _ => None,
+        }
+    }
+
+    /// Records whether an overloaded logical/bitwise operator was resolved as
+    /// a logical or a bitwise operation, based on the result type
+    fn record_op_kind(&self, id: expr::ExprId, result_ty: &ty::Type) {
+        let kind = match result_ty {
+            ty::Type::Boolean => ty::OpKind::Logical,
+            ty::Type::Nat(_) => ty::OpKind::Bitwise,
+            // Not resolved
+            _ => return,
+        };
+
+        self.state().ty_ctx.map_op_kind(id, kind);
+    }
+
+    fn typeck_paren(&self, expr: &toc_hir::expr::Paren) -> EvalKind {
+        // Same eval kind as the inner
+        self.typeck_expr(expr.expr)
+    }
+
+    fn typeck_name(&self, expr: &toc_hir::expr::Name) -> EvalKind {
+        // If def-id, fetch type from def id map
+        // If self, then fetch type from provided class def id?
+        let (use_id, ty_ref) = match expr {
+            toc_hir::expr::Name::Name(use_id) => {
+                (use_id, self.state().ty_ctx.get_def_id_kind(use_id.as_def()))
+            }
+            toc_hir::expr::Name::Self_ => {
+                todo!()
+            }
+        };
+
+        let name_def = if let Some(ty_ref) = ty_ref {
+            ty_ref
+        } else {
+            self.undeclared_def_kind(*use_id)
+        };
+
+        let eval = self.require_constvar_ref(*use_id, name_def);
+
+        // Functions without a parameter list are called just by naming them
+        match &*eval.as_ty_ref() {
+            ty::Type::Subprogram(ty::Subprogram {
+                kind: hir_ty::SubprogramKind::Function,
+                params: None,
+                result: Some(result),
+            }) => EvalKind::Value(*result),
+            _ => eval,
+        }
+    }
+
+    fn typeck_field(&self, expr: &expr::Field) -> EvalKind {
+        if let Some(ty_ref) = self.subrange_name_ty(expr.lhs) {
+            if let ty::Type::Enum(enum_ty) = &*ty_ref {
+                return self.typeck_enum_variant(ty_ref, enum_ty, expr);
+            }
+        }
+
+        let lhs_eval = self.typeck_expr(expr.lhs);
+        let lhs_ty = lhs_eval.as_ty_ref();
+        let field_name = expr.field.item();
+
+        let (def, kind_name) = match &*lhs_ty {
+            // Don't report errors for error types
+            ty::Type::Error => return lhs_eval,
+            ty::Type::Union(union) if union.tag.name == *field_name => {
+                // Tag can only be changed through a `tag` statement,
+                // so it's never a var ref
+                return match lhs_eval {
+                    EvalKind::Error(_) => lhs_eval,
+                    _ => EvalKind::ConstRef(union.tag.ty),
+                };
+            }
+            ty::Type::Record(record) => (record.def, "record"),
+            ty::Type::Union(union) => (union.def, "union"),
+            ty::Type::Opaque(_) => {
+                self.report_opaque(lhs_ty, expr.field.span());
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+            _ => {
+                let lhs_span = self.hir_db.get_span(expr.lhs.into());
+
+                self.state()
+                    .reporter
+                    .error_detailed(
+                        &format!("cannot access field `{}`", field_name),
+                        expr.field.span(),
+                    )
+                    .with_note("this expression is not a record or union", lhs_span)
+                    .finish();
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+        };
+
+        if let Some(field_ty) = lhs_ty.lookup_field(field_name) {
+            if let ty::Type::Union(union) = &*lhs_ty {
+                self.check_tagged_field(union, expr);
+            }
+
+            return lhs_eval.with_ty_ref(field_ty);
+        }
+
+        // Not a field of the type
+        let def_span = self.hir_db.get_span(def.into());
+        let type_name = self
+            .state()
+            .type_names
+            .get(&def)
+            .map(|def_id| &self.unit.symbol_table.get_symbol(*def_id).name);
+        let message = if let Some(type_name) = type_name {
+            format!(
+                "no field `{}` in {} type `{}`",
+                field_name, kind_name, type_name
+            )
+        } else {
+            format!("no field `{}` in {} type", field_name, kind_name)
+        };
+
+        let suggestion = ty::fields_of(lhs_ty)
+            .into_iter()
+            .map(|(name, _)| (edit_distance(&name, field_name), name))
+            .filter(|(distance, _)| (1..=2).contains(distance))
+            .min_by_key(|(distance, _)| *distance);
+
+        let mut state = self.state();
+        let builder = state
+            .reporter
+            .error_detailed(&message, expr.field.span())
+            .with_note(&format!("{} type declared here", kind_name), def_span);
+
+        if let Some((_, suggestion)) = suggestion {
+            builder
+                .with_info(&format!("did you mean `{}`?", suggestion), None)
+                .finish();
+        } else {
+            builder.finish();
+        }
+        drop(state);
+
+        let err = self.state().ty_ctx.add_type(ty::Type::Error);
+        EvalKind::Error(err)
+    }
+
+    fn typeck_deref(&self, id: expr::ExprId, expr: &expr::Deref) -> EvalKind {
+        self.record_checkedness(id);
+
+        let pointer_ty = self.get_spanned_expr_ty_ref(expr.expr);
+        let pointer_span = pointer_ty.span();
+        let pointer_ty = *pointer_ty.item();
+
+        let to = match &*pointer_ty {
+            ty::Type::Error => return EvalKind::Error(pointer_ty),
+            ty::Type::Pointer(pointer) => pointer.to,
+            ty::Type::Opaque(_) => {
+                self.report_opaque(pointer_ty, pointer_span);
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+            ty => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", pointer_span)
+                    .with_note(&format!("this is of type `{}`", ty), pointer_span)
+                    .with_info("`^` can only be applied to pointers", None)
+                    .finish();
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+        };
+
+        // Following a pointer to a collection element is the same as `collection(pointer)`
+        let to = match &*to {
+            ty::Type::Collection(collection) => {
+                let span = self.hir_db.get_span(id.into());
+                self.record_collection_deref(collection, span);
+                collection.elem
+            }
+            _ => to,
+        };
+
+        // What's being pointed to can always be changed
+        EvalKind::VarRef(to)
+    }
+
+    fn typeck_nat_cheat(&self, expr: &expr::NatCheat) -> EvalKind {
+        use ty::{IntSize, NatSize, RealSize};
+
+        let operand_ty = self.get_spanned_expr_ty_ref(expr.expr);
+        let operand_span = operand_ty.span();
+        let operand_ty = *operand_ty.item();
+
+        // Reinterpreted as the natural number of the same size
+        let size = match operand_ty.to_base_type() {
+            ty::Type::Error => return EvalKind::Error(operand_ty),
+            ty::Type::Boolean
+            | ty::Type::Char
+            | ty::Type::Int(IntSize::Int1)
+            | ty::Type::Nat(NatSize::Nat1) => NatSize::Nat1,
+            ty::Type::Int(IntSize::Int2) | ty::Type::Nat(NatSize::Nat2) => NatSize::Nat2,
+            ty::Type::Integer
+            | ty::Type::Int(IntSize::Int4 | IntSize::Int)
+            | ty::Type::Nat(NatSize::Nat4 | NatSize::Nat | NatSize::AddressInt)
+            | ty::Type::Real(RealSize::Real4) => NatSize::Nat4,
+            ty::Type::Opaque(_) => {
+                // Would reveal the size of the underlying type
+                self.report_opaque(operand_ty, operand_span);
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+            ty => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", operand_span)
+                    .with_note(&format!("this is of type `{}`", ty), operand_span)
+                    .with_info(
+                        "`#` can only be applied to values that are 1, 2, or 4 bytes in size",
+                        None,
+                    )
+                    .finish();
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+        };
+
+        EvalKind::Value(self.state().ty_ctx.add_type(ty::Type::Nat(size)))
+    }
+
+    fn typeck_indirect(&self, expr: &expr::Indirect) -> EvalKind {
+        let addr_ty = self.get_spanned_expr_ty_ref(expr.addr);
+        let addr_span = addr_ty.span();
+        let addr_ty = *addr_ty.item();
+
+        match &*addr_ty {
+            // Address arithmetic produces `nat`s, so those are also accepted
+            ty::Type::Error | ty::Type::Integer | ty::Type::Nat(_) => {}
+            ty if ty::rules::is_integer(ty) => {
+                self.state()
+                    .reporter
+                    .warn_detailed("address should be an `addressint`", addr_span)
+                    .with_note(&format!("this is of type `{}`", ty), addr_span)
+                    .finish();
+            }
+            ty => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", addr_span)
+                    .with_note(&format!("this is of type `{}`", ty), addr_span)
+                    .with_info("addresses must be of type `addressint`", None)
+                    .finish();
+            }
+        }
+
+        // Memory at any address can be changed
+        EvalKind::VarRef(self.lower_type(expr.ty))
+    }
+
+    fn typeck_bits(&self, expr: &expr::Bits) -> EvalKind {
+        let lhs_eval = self.typeck_expr(expr.lhs);
+        let lhs_ty = lhs_eval.as_ty_ref();
+        let lhs_span = self.hir_db.get_span(expr.lhs.into());
+
+        match expr.bits {
+            expr::BitsSelector::Expr(bit) => {
+                if let Some(bits_ty) = self.subrange_name_ty(bit) {
+                    if !matches!(&*bits_ty, ty::Type::Range(_) | ty::Type::Error) {
+                        let span = self.hir_db.get_span(bit.into());
+
+                        self.state()
+                            .reporter
+                            .error_detailed("mismatched types", span)
+                            .with_note("expected a subrange type", span)
+                            .finish();
+                    }
+                } else {
+                    self.check_integer_type(self.get_spanned_expr_ty_ref(bit));
+                }
+            }
+            expr::BitsSelector::Range(start, end) => {
+                self.check_integer_type(self.get_spanned_expr_ty_ref(start));
+                self.check_integer_type(self.get_spanned_expr_ty_ref(end));
+            }
+        }
+
+        match lhs_eval {
+            EvalKind::Error(_) => lhs_eval,
+            EvalKind::Value(_) => {
+                self.state()
+                    .reporter
+                    .error_detailed("cannot take the bits of a value", lhs_span)
+                    .with_note("expected a reference to a `nat` variable", lhs_span)
+                    .finish();
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                EvalKind::Error(err)
+            }
+            _ if !matches!(lhs_ty.to_base_type(), ty::Type::Nat(_)) => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", lhs_span)
+                    .with_note(&format!("this is of type `{}`", *lhs_ty), lhs_span)
+                    .with_info("bits can only be taken of `nat` types", None)
+                    .finish();
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                EvalKind::Error(err)
+            }
+            // Assigning to the bits changes the original reference
+            _ => lhs_eval,
+        }
+    }
+
+    /// Checks an access to one of the variants of an enum type, e.g. `e.a`
+    fn typeck_enum_variant(
+        &self,
+        ty_ref: TyRef,
+        enum_ty: &ty::Enum,
+        expr: &expr::Field,
+    ) -> EvalKind {
+        let variant_name = expr.field.item();
+
+        if enum_ty.variants.contains(variant_name) {
+            return EvalKind::Value(ty_ref);
+        }
+
+        let def_span = self.hir_db.get_span(enum_ty.def.into());
+        let message = match &enum_ty.name {
+            Some(name) => format!("no variant `{}` in enum type `{}`", variant_name, name),
+            None => format!("no variant `{}` in enum type", variant_name),
+        };
+
+        self.state()
+            .reporter
+            .error_detailed(&message, expr.field.span())
+            .with_note("enum type declared here", def_span)
+            .finish();
+
+        let err = self.state().ty_ctx.add_type(ty::Type::Error);
+        EvalKind::Error(err)
+    }
+
+    /// Gets the type named by `id`, if it's a name expression referring to a type
+    fn subrange_name_ty(&self, id: expr::ExprId) -> Option<TyRef> {
+        let use_id = match self.hir_db.get_expr(id) {
+            expr::Expr::Name(expr::Name::Name(use_id)) => *use_id,
+            _ => return None,
+        };
+
+        match self.state().ty_ctx.get_def_id_kind(use_id.as_def()) {
+            Some(DefKind::Type(ty)) => Some(ty),
+            _ => None,
+        }
+    }
+
+    fn typeck_call(&self, id: expr::ExprId, expr: &expr::Call) -> EvalKind {
+        let lhs_eval = self.typeck_expr(expr.lhs);
+        let lhs_ty = lhs_eval.as_ty_ref();
+        let lhs_span = self.hir_db.get_span(expr.lhs.into());
+        let args = expr
+            .arguments
+            .iter()
+            .map(|arg| self.get_spanned_expr_ty_ref(*arg))
+            .collect::<Vec<_>>();
+
+        let array = match &*lhs_ty {
+            // Don't report errors for error types
+            ty::Type::Error => return lhs_eval,
+            ty::Type::Array(array) => array,
+            ty::Type::Subprogram(subprogram) => {
+                return self.typeck_subprogram_call(id, lhs_span, subprogram, &expr.arguments)
+            }
+            ty::Type::Collection(collection) => {
+                let span = self.hir_db.get_span(id.into());
+                self.record_checkedness(id);
+                self.record_collection_deref(collection, span);
+                return self.typeck_collection_subscript(id, lhs_eval, collection, &args);
+            }
+            ty::Type::Opaque(_) => {
+                self.report_opaque(lhs_ty, lhs_span);
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+            _ => {
+                self.state()
+                    .reporter
+                    .error_detailed("cannot subscript or call this expression", lhs_span)
+                    .with_note(&format!("this is of type `{}`", lhs_ty), lhs_span)
+                    .finish();
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                return EvalKind::Error(err);
+            }
+        };
+
+        self.record_checkedness(id);
+
+        if args.len() != array.ranges.len() {
+            let span = self.hir_db.get_span(id.into());
+            let plural = |count: usize| if count == 1 { "" } else { "s" };
+
+            self.state()
+                .reporter
+                .error_detailed("wrong number of subscripts", span)
+                .with_note(
+                    &format!(
+                        "expected {} subscript{}, found {}",
+                        array.ranges.len(),
+                        plural(array.ranges.len()),
+                        args.len()
+                    ),
+                    span,
+                )
+                .finish();
+        }
+
+        for (arg, range) in args.iter().zip(&array.ranges) {
+            if let Some(false) = ty::rules::is_assignable(*range, *arg.item()) {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", arg.span())
+                    .with_note(&format!("this is of type `{}`", arg.item()), arg.span())
+                    .with_info(&format!("expected a subscript of type `{}`", range), None)
+                    .finish();
+            }
+        }
+
+        // Elements can be changed if the array can be changed
+        lhs_eval.with_ty_ref(array.elem)
+    }
+
+    /// Checks a call to a subprogram inside of an expression, e.g. `f(1)`
+    fn typeck_subprogram_call(
+        &self,
+        id: expr::ExprId,
+        lhs_span: Span,
+        subprogram: &ty::Subprogram,
+        args: &[expr::ExprId],
+    ) -> EvalKind {
+        self.check_call_args(lhs_span, subprogram, args);
+
+        match subprogram.result {
+            Some(result) => EvalKind::Value(result),
+            None => {
+                let span = self.hir_db.get_span(id.into());
+
+                self.state()
+                    .reporter
+                    .error_detailed("cannot use a procedure call as a value", span)
+                    .with_note("procedures don't produce a value", lhs_span)
+                    .finish();
+
+                let err = self.state().ty_ctx.add_type(ty::Type::Error);
+                EvalKind::Error(err)
+            }
+        }
+    }
+
+    /// Checks a reference to a collection element, e.g. `c(p)`
+    fn typeck_collection_subscript(
+        &self,
+        id: expr::ExprId,
+        lhs_eval: EvalKind,
+        collection: &ty::Collection,
+        args: &[Spanned<TyRef>],
+    ) -> EvalKind {
+        let lhs_ty = lhs_eval.as_ty_ref();
+
+        if let [arg] = args {
+            let is_element_ptr = match &**arg.item() {
+                ty::Type::Error => true,
+                ty::Type::Pointer(pointer) => pointer.to == lhs_ty,
+                _ => false,
+            };
+
+            if !is_element_ptr {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", arg.span())
+                    .with_note(&format!("this is of type `{}`", arg.item()), arg.span())
+                    .with_info(&format!("expected a pointer to `{}`", lhs_ty), None)
+                    .finish();
+            }
+        } else {
+            let span = self.hir_db.get_span(id.into());
+
+            self.state()
+                .reporter
+                .error_detailed("wrong number of subscripts", span)
+                .with_note(&format!("expected 1 subscript, found {}", args.len()), span)
+                .finish();
+        }
+
+        // Elements can be changed if the collection can be changed
+        lhs_eval.with_ty_ref(collection.elem)
+    }
+
+    /// Checks the arguments of a `new` or `free` statement
+    fn typeck_alloc(&self, collection: Option<expr::ExprId>, pointer: expr::ExprId, keyword: &str) {
+        let collection = collection.map(|id| (id, self.typeck_expr(id).as_ty_ref()));
+        let pointer_eval = self.typeck_expr(pointer);
+        let pointer_span = self.hir_db.get_span(pointer.into());
+
+        // The pointer is changed to refer to the new element (or to `nil`)
+        let pointer_ty = if let Some(ty) = pointer_eval.as_mut_ty_ref() {
+            ty
+        } else if let Some(def_id) = self.const_ref_def(pointer) {
+            self.report_const_assign(def_id, pointer_span);
+            return;
+        } else {
+            self.state()
+                .reporter
+                .error_detailed(
+                    &format!("cannot `{}` this expression", keyword),
+                    pointer_span,
+                )
+                .with_note(
+                    "this expression cannot be used as a variable reference",
+                    pointer_span,
+                )
+                .finish();
+            return;
+        };
+
+        let pointee = match &*pointer_ty {
+            ty::Type::Error => return,
+            ty::Type::Pointer(pointer) => pointer.to,
+            ty::Type::Opaque(_) => {
+                self.report_opaque(pointer_ty, pointer_span);
+                return;
+            }
+            ty => {
+                self.state()
+                    .reporter
+                    .error_detailed("mismatched types", pointer_span)
+                    .with_note(&format!("this is of type `{}`", ty), pointer_span)
+                    .with_info(
+                        &format!("`{}` can only be used with pointers", keyword),
+                        None,
+                    )
+                    .finish();
+                return;
+            }
+        };
 
-            if !matches!(eval_kind, EvalKind::VarRef(_)) {
-                let get_item_span = self.hir_db.get_span(item.expr.into());
+        match (collection, &*pointee) {
+            (Some((id, collection_ty)), _) => {
+                let collection_span = self.hir_db.get_span(id.into());
+
+                match &*collection_ty {
+                    ty::Type::Error => return,
+                    ty::Type::Collection(_) => {}
+                    ty => {
+                        self.state()
+                            .reporter
+                            .error_detailed("mismatched types", collection_span)
+                            .with_note(&format!("this is of type `{}`", ty), collection_span)
+                            .with_info("expected a collection", None)
+                            .finish();
+                        return;
+                    }
+                }
 
-                // TODO: Stringify item for more clarity on the error location
+                if pointee != collection_ty && !ty::rules::is_error(&pointee) {
+                    self.state()
+                        .reporter
+                        .error_detailed("mismatched types", pointer_span)
+                        .with_note(&format!("this is of type `{}`", pointer_ty), pointer_span)
+                        .with_info(&format!("expected a pointer to `{}`", collection_ty), None)
+                        .finish();
+                }
+            }
+            (None, ty::Type::Collection(_)) => {
                 self.state()
                     .reporter
-                    .error_detailed("cannot assign into get item expression", get_item_span)
+                    .error_detailed(
+                        &format!("missing collection for `{}`", keyword),
+                        pointer_span,
+                    )
                     .with_note(
-                        "this expression cannot be used as a variable reference",
-                        get_item_span,
+                        &format!("this points into collection `{}`", pointee),
+                        pointer_span,
                     )
+                    .with_info(&format!("expected `{} collection, pointer`", keyword), None)
                     .finish();
             }
-
-            if let stmt::GetWidth::Chars(expr) = item.width {
-                self.check_text_io_arg(expr);
-            }
+            (None, _) => {}
         }
     }
 
-    fn check_text_io_arg(&self, id: expr::ExprId) {
-        let ty_ref = self.get_spanned_expr_ty_ref(id);
+    fn typeck_substring(&self, expr: &expr::Substring) -> EvalKind {
+        let lhs_ty = self.get_spanned_expr_ty_ref(expr.lhs);
 
-        self.check_integer_type(ty_ref);
-    }
+        for bound in &[expr.start, expr.end] {
+            match bound {
+                expr::SubstringBound::FromStart(bound)
+                | expr::SubstringBound::FromEnd(Some(bound)) => {
+                    let bound_ty = self.get_spanned_expr_ty_ref(*bound);
+                    self.check_integer_type(bound_ty);
+                }
+                expr::SubstringBound::FromEnd(None) => {}
+            }
+        }
 
-    fn check_integer_type(&self, ty_ref: Spanned<TyRef>) {
-        if !ty::rules::is_integer(ty_ref.item()) && !ty::rules::is_error(ty_ref.item()) {
-            // TODO: Stringify type for more clarity on the error
+        if ty::rules::is_error(lhs_ty.item()) {
+            return EvalKind::Error(*lhs_ty.item());
+        } else if !ty::rules::is_charseq(lhs_ty.item()) {
             self.state()
                 .reporter
-                .error_detailed("mismatched types", ty_ref.span())
-                .with_note("expected integer type", ty_ref.span())
+                .error_detailed("mismatched types", lhs_ty.span())
+                .with_note(
+                    "substrings can only be taken of string and character types",
+                    lhs_ty.span(),
+                )
                 .finish();
-        }
-    }
-
-    fn check_text_io_item(&self, id: expr::ExprId) -> Spanned<TyRef> {
-        let ty_ref = self.get_spanned_expr_ty_ref(id);
-
-        // Must be a valid put/get type
-        // Can be one of the following:
-        // - Int
-        // - Nat
-        // - Real
-        // - Char
-        // - Char(N)
-        // - String
-        // - String(N)
-        // - Boolean
-        // - Enum
 
-        // For now, all lowered types satisfy this condition
-        match &*(*ty_ref.item()) {
-            ty::Type::Error
-            | ty::Type::Boolean
-            | ty::Type::Int(_)
-            | ty::Type::Nat(_)
-            | ty::Type::Real(_)
-            | ty::Type::Integer
-            | ty::Type::Char
-            | ty::Type::String
-            | ty::Type::CharN(_)
-            | ty::Type::StringN(_) => {}
+            let err = self.state().ty_ctx.add_type(ty::Type::Error);
+            return EvalKind::Error(err);
         }
 
-        ty_ref
+        // Substrings are always strings
+        EvalKind::Value(self.state().ty_ctx.add_type(ty::Type::String))
     }
 
-    fn typeck_block(&self, stmt: &stmt::Block) {
-        for stmt in &stmt.stmts {
-            self.typeck_stmt(*stmt)
-        }
-    }
+    fn typeck_name_ty(&self, use_id: symbol::UseId) -> TyRef {
+        let symbol = self.unit.symbol_table.get_symbol(use_id.as_def());
 
-    fn typeck_literal(&self, expr: &toc_hir::expr::Literal) -> EvalKind {
-        let ty = match expr {
-            toc_hir::expr::Literal::Integer(_) => ty::Type::Integer,
-            toc_hir::expr::Literal::Real(_) => ty::Type::Real(ty::RealSize::Real),
-            toc_hir::expr::Literal::Char(_) => ty::Type::Char,
-            toc_hir::expr::Literal::CharSeq(s) => {
-                let size = NonZeroU32::new(s.len().try_into().unwrap_or(u32::MAX)).unwrap();
-                let seq_size = ty::SeqSize::Fixed(size);
-                ty::Type::CharN(seq_size)
-            }
-            toc_hir::expr::Literal::String(_) => ty::Type::String,
-            toc_hir::expr::Literal::Boolean(_) => ty::Type::Boolean,
-        };
+        if symbol.kind == symbol::SymbolKind::Forward && !self.is_resolved_forward(use_id.as_def())
+        {
+            let use_span = self.unit.symbol_table.get_use_span(use_id);
 
-        // Evaluates to a value
-        EvalKind::Value(self.state().ty_ctx.add_type(ty))
-    }
+            self.state()
+                .reporter
+                .error_detailed(
+                    &format!(
+                        "`{}` refers to a forward type that is never resolved",
+                        symbol.name
+                    ),
+                    use_span,
+                )
+                .with_note(
+                    &format!("`{}` declared here", symbol.name),
+                    self.unit.symbol_table.get_def_span(use_id.as_def()),
+                )
+                .finish();
+        }
 
-    fn typeck_binary(&self, expr: &toc_hir::expr::Binary) -> EvalKind {
-        // TODO: do full binexpr typechecks
-        let ty = self.type_check_binary_op(expr.lhs, expr.op, expr.rhs);
+        let def_kind = self.state().ty_ctx.get_def_id_kind(use_id.as_def());
+        let def_kind = if let Some(def_kind) = def_kind {
+            def_kind
+        } else {
+            self.undeclared_def_kind(use_id)
+        };
 
-        // Evaluates to a value
-        EvalKind::Value(self.state().ty_ctx.add_type(ty))
-    }
+        match def_kind {
+            DefKind::Type(ty) => ty,
+            DefKind::Error(err) => err,
+            // Collection variables also name their collection type, e.g. in `pointer to c`
+            DefKind::Const(ty) | DefKind::Var(ty) if matches!(*ty, ty::Type::Collection(_)) => ty,
+            DefKind::Const(_) | DefKind::Var(_) | DefKind::Process | DefKind::Module => {
+                let sym_name = &self.unit.symbol_table.get_symbol(use_id.as_def()).name;
+                let use_span = self.unit.symbol_table.get_use_span(use_id);
 
-    fn typeck_unary(&self, expr: &toc_hir::expr::Unary) -> EvalKind {
-        let ty = self.type_check_unary_op(expr.op, expr.rhs);
+                self.state()
+                    .reporter
+                    .error(&format!("`{}` is not a type", sym_name), use_span);
 
-        // Evaluates to a value
-        EvalKind::Value(self.state().ty_ctx.add_type(ty))
+                self.state().ty_ctx.add_type(ty::Type::Error)
+            }
+        }
     }
 
-    fn typeck_paren(&self, expr: &toc_hir::expr::Paren) -> EvalKind {
-        // Same eval kind as the inner
-        self.typeck_expr(expr.expr)
+    /// Checks if the forward declaration `def_id` is resolved by a later declaration
+    fn is_resolved_forward(&self, def_id: symbol::DefId) -> bool {
+        self.unit
+            .symbol_table
+            .iter_defs()
+            .any(|(_, _, symbol)| symbol.kind == symbol::SymbolKind::Resolved(def_id))
     }
 
-    fn typeck_name(&self, expr: &toc_hir::expr::Name) -> EvalKind {
-        // If def-id, fetch type from def id map
-        // If self, then fetch type from provided class def id?
-        let (use_id, ty_ref) = match expr {
-            toc_hir::expr::Name::Name(use_id) => {
-                (use_id, self.state().ty_ctx.get_def_id_kind(use_id.as_def()))
-            }
-            toc_hir::expr::Name::Self_ => {
-                todo!()
-            }
-        };
-
-        let name_def = if let Some(ty_ref) = ty_ref {
-            ty_ref
-        } else {
-            // Nab symbol info
-            let sym_name = &self.unit.symbol_table.get_symbol(use_id.as_def()).name;
-            let expr_range = self.unit.symbol_table.get_use_span(*use_id);
-
-            // Not declared, no type provided by any decls
-            self.state()
-                .reporter
-                .error(&format!("`{}` is not declared", sym_name), expr_range);
-
-            // Build error type
-            let err_ref = self.state().ty_ctx.add_type(ty::Type::Error);
-            let def_kind = DefKind::Error(err_ref);
-            self.state().ty_ctx.map_def_id(use_id.as_def(), def_kind);
-
-            def_kind
-        };
+    /// Gives an undeclared symbol an error type
+    ///
+    /// Undeclared symbols are already reported during lowering
+    fn undeclared_def_kind(&self, use_id: symbol::UseId) -> DefKind {
+        // Build error type
+        let err_ref = self.state().ty_ctx.add_type(ty::Type::Error);
+        let def_kind = DefKind::Error(err_ref);
+        self.state().ty_ctx.map_def_id(use_id.as_def(), def_kind);
 
-        self.require_constvar_ref(name_def)
+        def_kind
     }
 
     fn typeck_primitive(&self, ty: &hir_ty::Primitive) -> ty::Type {
@@ -583,6 +3287,246 @@ impl<'a> TypeCheck<'a> {
         }
     }
 
+    fn typeck_record(&self, id: hir_ty::TypeId, ty: &hir_ty::Record) -> ty::Type {
+        let fields = self.lower_fields(&ty.fields);
+
+        ty::Type::Record(ty::Record {
+            def: id,
+            name: self.type_name(id),
+            fields,
+        })
+    }
+
+    fn typeck_enum(&self, id: hir_ty::TypeId, ty: &hir_ty::Enum) -> ty::Type {
+        let variants = ty
+            .variants
+            .iter()
+            .map(|variant| variant.item().clone())
+            .collect();
+
+        ty::Type::Enum(ty::Enum {
+            def: id,
+            name: self.type_name(id),
+            variants,
+        })
+    }
+
+    fn typeck_union(&self, id: hir_ty::TypeId, ty: &hir_ty::Union) -> ty::Type {
+        let tag = ty::Field {
+            name: ty.tag_name.item().clone(),
+            ty: self.lower_type(ty.tag_ty),
+        };
+
+        let variants = ty
+            .variants
+            .iter()
+            .map(|variant| {
+                // TODO: Check that selectors are compile-time values of the tag type
+                for selector in &variant.selectors {
+                    self.typeck_expr(*selector);
+                }
+
+                self.lower_fields(&variant.fields)
+            })
+            .collect();
+
+        ty::Type::Union(ty::Union {
+            def: id,
+            name: self.type_name(id),
+            tag,
+            variants,
+        })
+    }
+
+    fn typeck_pointer(&self, ty: &hir_ty::Pointer) -> ty::Type {
+        let is_checked = ty.checkedness == stmt::Checkedness::Checked;
+        let to = self.lower_type(ty.to);
+
+        ty::Type::Pointer(ty::Pointer { is_checked, to })
+    }
+
+    fn typeck_collection(&self, id: hir_ty::TypeId, ty: &hir_ty::Collection) -> ty::Type {
+        let elem = self.lower_type(ty.elem);
+
+        ty::Type::Collection(ty::Collection {
+            def: id,
+            name: self.type_name(id),
+            elem,
+        })
+    }
+
+    fn typeck_array(&self, ty: &hir_ty::Array) -> ty::Type {
+        let ranges = ty
+            .ranges
+            .iter()
+            .map(|range| {
+                self.lower_index_type(
+                    *range,
+                    "invalid array index type",
+                    "array indices must be ranges, `char`, or `boolean`",
+                )
+            })
+            .collect();
+        let elem = self.lower_type(ty.elem_ty);
+
+        ty::Type::Array(ty::Array {
+            is_flexible: ty.is_flexible,
+            ranges,
+            elem,
+        })
+    }
+
+    fn typeck_set(&self, ty: &hir_ty::Set) -> ty::Type {
+        let elem = self.lower_index_type(
+            ty.elem,
+            "invalid set element type",
+            "set elements must be ranges, `char`, or `boolean`",
+        );
+
+        ty::Type::Set(ty::Set { elem })
+    }
+
+    /// Lowers a type that must be an index type (a range, `char`, or `boolean`)
+    fn lower_index_type(&self, id: hir_ty::TypeId, msg: &str, info: &str) -> TyRef {
+        let ty = self.lower_type(id);
+
+        if !matches!(
+            ty.to_base_type(),
+            ty::Type::Error | ty::Type::Char | ty::Type::Boolean
+        ) && !matches!(&*ty, ty::Type::Range(_))
+        {
+            let span = self.hir_db.get_span(id.into());
+
+            self.state()
+                .reporter
+                .error_detailed(msg, span)
+                .with_note(&format!("this is of type `{}`", ty), span)
+                .with_info(info, None)
+                .finish();
+        }
+
+        ty
+    }
+
+    fn typeck_subprogram_ty(&self, ty: &hir_ty::Subprogram) -> ty::Type {
+        let params = ty.params.as_ref().map(|params| {
+            params
+                .iter()
+                .map(|param| ty::Param {
+                    is_var: param.is_var,
+                    ty: self.lower_type(param.param_ty),
+                })
+                .collect()
+        });
+        let result = ty.result.map(|result| self.lower_type(result));
+
+        ty::Type::Subprogram(ty::Subprogram {
+            kind: ty.kind,
+            params,
+            result,
+        })
+    }
+
+    /// Gets the name that an aggregate type is declared with, if it has one
+    fn type_name(&self, id: hir_ty::TypeId) -> Option<String> {
+        let def_id = *self.state().type_names.get(&id)?;
+        Some(self.unit.symbol_table.get_symbol(def_id).name.clone())
+    }
+
+    fn typeck_range(&self, id: hir_ty::TypeId, ty: &hir_ty::Range) -> ty::Type {
+        let end = match ty.end {
+            hir_ty::RangeEnd::Unsized => None,
+            hir_ty::RangeEnd::Expr(expr) => Some(expr),
+        };
+
+        let start_ty = self.get_spanned_expr_ty_ref(ty.start);
+        let end_ty = end.map(|expr| self.get_spanned_expr_ty_ref(expr));
+
+        // Both bounds must be from the same class of index types
+        let base_ty = {
+            let start = start_ty.item().to_base_type();
+            let end = end_ty.as_ref().map_or(start, |ty| ty.item().to_base_type());
+
+            match (start, end) {
+                (ty::Type::Error, _) | (_, ty::Type::Error) => return ty::Type::Error,
+                (start, end) if ty::rules::is_integer(start) && ty::rules::is_integer(end) => {
+                    ty::Type::Int(ty::IntSize::Int)
+                }
+                (ty::Type::Char, ty::Type::Char) => ty::Type::Char,
+                (ty::Type::Boolean, ty::Type::Boolean) => ty::Type::Boolean,
+                (start, end) => {
+                    let is_index_ty = |ty: &ty::Type| {
+                        ty::rules::is_integer(ty)
+                            || matches!(ty, ty::Type::Char | ty::Type::Boolean)
+                    };
+                    let msg = if is_index_ty(start) && is_index_ty(end) {
+                        "mismatched range bound types"
+                    } else {
+                        "invalid range bound types"
+                    };
+
+                    let mut state = self.state();
+                    let msg = state
+                        .reporter
+                        .error_detailed(msg, start_ty.span())
+                        .with_note(
+                            &format!("this is of type `{}`", start_ty.item()),
+                            start_ty.span(),
+                        );
+                    let msg = if let Some(end_ty) = &end_ty {
+                        msg.with_note(
+                            &format!("this is of type `{}`", end_ty.item()),
+                            end_ty.span(),
+                        )
+                    } else {
+                        msg
+                    };
+                    msg.with_info(
+                        "range bounds must both be integers, characters, or booleans",
+                        None,
+                    )
+                    .finish();
+
+                    return ty::Type::Error;
+                }
+            }
+        };
+
+        let bounds = self.eval_range_bound(ty.start).and_then(|start| {
+            let size = match end {
+                Some(end) => Some(self.compute_range_size(id, start, end)?),
+                // Unsized ranges don't have a known size
+                None => None,
+            };
+
+            Ok((start, size))
+        });
+        let (start, size) = match bounds {
+            Ok(bounds) => bounds,
+            Err(err) => {
+                err.report_to(&mut self.state().reporter);
+                return ty::Type::Error;
+            }
+        };
+
+        let base = self.state().ty_ctx.add_type(base_ty);
+        ty::Type::Range(ty::Range { base, start, size })
+    }
+
+    fn lower_fields(&self, fields: &[hir_ty::Field]) -> Vec<ty::Field> {
+        fields
+            .iter()
+            .flat_map(|field| {
+                let ty = self.lower_type(field.ty);
+
+                field.names.iter().map(move |name| ty::Field {
+                    name: name.item().clone(),
+                    ty,
+                })
+            })
+            .collect()
+    }
+
     fn lower_seq_len(
         &self,
         seq_len: toc_hir::ty::SeqLength,
@@ -621,6 +3565,67 @@ impl<'a> TypeCheck<'a> {
         Ok(ty::SeqSize::Fixed(size))
     }
 
+    fn compute_range_size(
+        &self,
+        id: hir_ty::TypeId,
+        start: i128,
+        end: expr::ExprId,
+    ) -> Result<u32, RangeSizeError> {
+        let end = self.eval_range_bound(end)?;
+        let span = self.hir_db.get_span(id.into());
+
+        // Range is inclusive of the end bound
+        let size = (end - start) + 1;
+
+        if size.is_negative() {
+            Err(RangeSizeError::NegativeSize(Spanned::new(size, span)))
+        } else if size > i128::from(MAX_RANGE_SIZE) {
+            // Saturate at the size limit, so that later uses of the range don't also
+            // report errors
+            self.state()
+                .reporter
+                .error_detailed("range has too many elements", span)
+                .with_note(&format!("computed size is {}", size), span)
+                .with_info(
+                    &format!("ranges can have at most {} elements", MAX_RANGE_SIZE),
+                    None,
+                )
+                .finish();
+
+            Ok(MAX_RANGE_SIZE)
+        } else {
+            Ok(size.try_into().unwrap())
+        }
+    }
+
+    /// Evaluates a range bound into its ordinal value
+    fn eval_range_bound(&self, bound: expr::ExprId) -> Result<i128, RangeSizeError> {
+        // Never allow 64-bit ops (bounds are always within 32-bit values)
+        // Restrict to no type since the bound types are checked beforehand
+        let const_expr = self
+            .const_eval
+            .defer_expr(self.unit.id, bound, false, RestrictType::None);
+
+        // Always eagerly evaluate the expr
+        let value = self
+            .const_eval
+            .eval_expr(const_expr)
+            .map_err(RangeSizeError::ConstEval)?;
+
+        let ordinal = match value {
+            ConstValue::Char(v) => i128::from(u32::from(v)),
+            ConstValue::Bool(v) => i128::from(v),
+            v => {
+                let span = self.hir_db.get_span(bound.into());
+                v.into_int(span)
+                    .map_err(RangeSizeError::ConstEval)?
+                    .into_i128()
+            }
+        };
+
+        Ok(ordinal)
+    }
+
     fn type_check_binary_op(
         &self,
         lhs_id: expr::ExprId,
@@ -669,6 +3674,16 @@ impl EvalKind {
         }
     }
 
+    /// Keeps the same kind of evaluation, but with a different type
+    fn with_ty_ref(self, ty: TyRef) -> EvalKind {
+        match self {
+            EvalKind::VarRef(_) => EvalKind::VarRef(ty),
+            EvalKind::ConstRef(_) => EvalKind::ConstRef(ty),
+            EvalKind::Value(_) => EvalKind::Value(ty),
+            EvalKind::Error(_) => EvalKind::Error(ty),
+        }
+    }
+
     fn as_mut_ty_ref(self) -> Option<TyRef> {
         match self {
             EvalKind::VarRef(ty) => Some(ty),
@@ -678,6 +3693,33 @@ impl EvalKind {
     }
 }
 
+/// Maximum number of elements in a range.
+/// Element counts are stored as signed 32-bit integers in the interpreter.
+const MAX_RANGE_SIZE: u32 = i32::MAX as u32;
+
+enum RangeSizeError {
+    ConstEval(ConstError),
+    NegativeSize(Spanned<i128>),
+}
+
+impl RangeSizeError {
+    fn report_to(&self, reporter: &mut MessageSink) {
+        match self {
+            RangeSizeError::ConstEval(err) => err.report_to(reporter),
+            RangeSizeError::NegativeSize(size) => {
+                reporter
+                    .error_detailed("range has negative size", size.span())
+                    .with_note(&format!("computed size is {}", size.item()), size.span())
+                    .with_info(
+                        "the end bound must not be less than the start bound minus one",
+                        None,
+                    )
+                    .finish();
+            }
+        }
+    }
+}
+
 enum SeqLenError {
     ConstEval(ConstError),
     WrongSize(Spanned<ConstInt>, u32),
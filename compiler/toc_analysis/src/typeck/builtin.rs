@@ -0,0 +1,272 @@
+//! Type checking of builtin functions
+use toc_hir::expr::{self, BuiltinFn};
+use toc_span::Span;
+
+use super::{EvalKind, TypeCheck};
+use crate::ty::{self, TyRef};
+
+/// What a builtin function accepts for one of its arguments
+#[derive(Clone, Copy)]
+enum Param {
+    /// A reference to a variable or constant
+    Reference,
+    /// A type, or a reference to a variable or constant
+    TypeOrReference,
+    /// An array, or a range type
+    Bounded,
+    /// A `char` value
+    Char,
+    /// An integer value
+    Integer,
+    /// A string or character sequence value
+    String,
+    /// A string, character sequence, or array value
+    Sequence,
+    /// A real or integer value
+    Number,
+}
+
+/// Parameters accepted by each builtin function
+fn params_of(func: BuiltinFn) -> &'static [Param] {
+    match func {
+        BuiltinFn::Addr => &[Param::Reference],
+        BuiltinFn::SizeOf => &[Param::TypeOrReference],
+        BuiltinFn::Upper | BuiltinFn::Lower => &[Param::Bounded],
+        // TODO: Also accept enums once they are lowered
+        BuiltinFn::Ord => &[Param::Char],
+        BuiltinFn::Chr => &[Param::Integer],
+        BuiltinFn::Length => &[Param::Sequence],
+        BuiltinFn::Index => &[Param::String, Param::String],
+        BuiltinFn::Repeat => &[Param::String, Param::Integer],
+        BuiltinFn::Sqrt
+        | BuiltinFn::Sin
+        | BuiltinFn::Cos
+        | BuiltinFn::Exp
+        | BuiltinFn::Ln
+        | BuiltinFn::Round
+        | BuiltinFn::Floor
+        | BuiltinFn::Ceil
+        | BuiltinFn::Abs => &[Param::Number],
+        BuiltinFn::Max | BuiltinFn::Min => &[Param::Number, Param::Number],
+    }
+}
+
+impl TypeCheck<'_> {
+    pub(super) fn typeck_builtin(&self, id: expr::ExprId, expr: &expr::Builtin) -> EvalKind {
+        let func = *expr.func.item();
+        let params = params_of(func);
+
+        if expr.args.len() != params.len() {
+            let span = self.hir_db.get_span(id.into());
+            let plural = |count: usize| if count == 1 { "" } else { "s" };
+
+            self.state()
+                .reporter
+                .error_detailed("wrong number of arguments", span)
+                .with_note(
+                    &format!(
+                        "`{}` takes {} argument{}, found {}",
+                        func.name(),
+                        params.len(),
+                        plural(params.len()),
+                        expr.args.len()
+                    ),
+                    span,
+                )
+                .finish();
+        }
+
+        let args = expr
+            .args
+            .iter()
+            .zip(params)
+            .map(|(arg, param)| self.typeck_builtin_arg(func, *arg, *param))
+            .collect::<Vec<_>>();
+
+        // Still check any arguments past the expected ones
+        for arg in expr.args.iter().skip(params.len()) {
+            if let expr::BuiltinArg::Expr(arg) = arg {
+                self.typeck_expr(*arg);
+            }
+        }
+
+        let args = match args.into_iter().collect::<Option<Vec<_>>>() {
+            Some(args) if expr.args.len() == params.len() => args,
+            _ => return EvalKind::Error(self.state().ty_ctx.add_type(ty::Type::Error)),
+        };
+
+        let result_ty = match func {
+            BuiltinFn::Addr => ty::Type::Nat(ty::NatSize::AddressInt),
+            BuiltinFn::SizeOf | BuiltinFn::Ord | BuiltinFn::Length | BuiltinFn::Index => {
+                ty::Type::Int(ty::IntSize::Int)
+            }
+            BuiltinFn::Chr => ty::Type::Char,
+            BuiltinFn::Repeat => ty::Type::String,
+            BuiltinFn::Sqrt | BuiltinFn::Sin | BuiltinFn::Cos | BuiltinFn::Exp | BuiltinFn::Ln => {
+                ty::Type::Real(ty::RealSize::Real)
+            }
+            BuiltinFn::Round | BuiltinFn::Floor | BuiltinFn::Ceil => {
+                ty::Type::Int(ty::IntSize::Int)
+            }
+            // Already the index type
+            BuiltinFn::Upper | BuiltinFn::Lower => return EvalKind::Value(args[0]),
+            // Same type as the operand
+            BuiltinFn::Abs => return EvalKind::Value(args[0]),
+            BuiltinFn::Max | BuiltinFn::Min => {
+                match common_number_ty(args[0].to_base_type(), args[1].to_base_type()) {
+                    Some(ty) => ty,
+                    None => return EvalKind::Value(args[0]),
+                }
+            }
+        };
+
+        EvalKind::Value(self.state().ty_ctx.add_type(result_ty))
+    }
+
+    /// Checks an argument against what the builtin accepts, giving back the type
+    /// used for the result, or `None` if the argument isn't accepted
+    fn typeck_builtin_arg(
+        &self,
+        func: BuiltinFn,
+        arg: expr::BuiltinArg,
+        param: Param,
+    ) -> Option<TyRef> {
+        let (expr, span) = match arg {
+            expr::BuiltinArg::Expr(expr) => (expr, self.hir_db.get_span(expr.into())),
+            expr::BuiltinArg::Type(ty) => {
+                let span = self.hir_db.get_span(ty.into());
+                return self.typeck_builtin_ty_arg(func, self.lower_type(ty), span, param);
+            }
+        };
+
+        // Type names are lowered as expressions
+        if let Some(arg_ty) = self.subrange_name_ty(expr) {
+            return self.typeck_builtin_ty_arg(func, arg_ty, span, param);
+        }
+
+        let arg_eval = self.typeck_expr(expr);
+        let arg_ty = arg_eval.as_ty_ref();
+
+        match (param, arg_eval) {
+            (_, EvalKind::Error(_)) => None,
+            (Param::Reference, EvalKind::Value(_)) => {
+                self.state()
+                    .reporter
+                    .error_detailed("cannot take the address of a value", span)
+                    .with_note("expected a reference to a variable or constant", span)
+                    .finish();
+                None
+            }
+            (Param::TypeOrReference, EvalKind::Value(_)) => {
+                self.state()
+                    .reporter
+                    .error_detailed("cannot take the size of a value", span)
+                    .with_note(
+                        "expected a type, or a reference to a variable or constant",
+                        span,
+                    )
+                    .finish();
+                None
+            }
+            (Param::Reference | Param::TypeOrReference, _) => Some(arg_ty),
+            (Param::Bounded, _) => self.builtin_bound_ty(func, arg_ty, span),
+            (Param::Char, _) if matches!(arg_ty.to_base_type(), ty::Type::Char) => Some(arg_ty),
+            (Param::Integer, _) if ty::rules::is_integer(arg_ty.to_base_type()) => Some(arg_ty),
+            (Param::String, _) if ty::rules::is_charseq(&arg_ty) => Some(arg_ty),
+            (Param::Number, _) if ty::rules::is_number(arg_ty.to_base_type()) => Some(arg_ty),
+            (Param::Sequence, _)
+                if ty::rules::is_charseq(&arg_ty) || matches!(&*arg_ty, ty::Type::Array(_)) =>
+            {
+                Some(arg_ty)
+            }
+            (Param::Char, _) => {
+                self.report_builtin_mismatch(func, arg_ty, span, "a `char`");
+                None
+            }
+            (Param::Integer, _) => {
+                self.report_builtin_mismatch(func, arg_ty, span, "an integer");
+                None
+            }
+            (Param::String, _) => {
+                self.report_builtin_mismatch(func, arg_ty, span, "a string");
+                None
+            }
+            (Param::Sequence, _) => {
+                self.report_builtin_mismatch(func, arg_ty, span, "a string or an array");
+                None
+            }
+            (Param::Number, _) => {
+                self.report_builtin_mismatch(func, arg_ty, span, "a number");
+                None
+            }
+        }
+    }
+
+    fn typeck_builtin_ty_arg(
+        &self,
+        func: BuiltinFn,
+        arg_ty: TyRef,
+        span: Span,
+        param: Param,
+    ) -> Option<TyRef> {
+        match param {
+            _ if ty::rules::is_error(&arg_ty) => None,
+            Param::TypeOrReference => Some(arg_ty),
+            Param::Bounded => self.builtin_bound_ty(func, arg_ty, span),
+            Param::Reference
+            | Param::Char
+            | Param::Integer
+            | Param::String
+            | Param::Sequence
+            | Param::Number => {
+                self.state()
+                    .reporter
+                    .error_detailed("expected a value, found a type", span)
+                    .with_note(&format!("this is the type `{}`", arg_ty), span)
+                    .finish();
+                None
+            }
+        }
+    }
+
+    /// Gets the index type of an array, or the base type of a range type
+    fn builtin_bound_ty(&self, func: BuiltinFn, ty: TyRef, span: Span) -> Option<TyRef> {
+        let index_ty = match &*ty {
+            // TODO: Support picking the dimension of multi-dimensional arrays
+            ty::Type::Array(array) => *array.ranges.first()?,
+            ty::Type::Range(_) => ty,
+            _ => {
+                self.report_builtin_mismatch(func, ty, span, "an array or a range type");
+                return None;
+            }
+        };
+
+        match &*index_ty {
+            ty::Type::Range(range) => Some(range.base),
+            _ => Some(index_ty),
+        }
+    }
+
+    fn report_builtin_mismatch(&self, func: BuiltinFn, ty: TyRef, span: Span, expected: &str) {
+        self.state()
+            .reporter
+            .error_detailed("mismatched types", span)
+            .with_note(&format!("this is of type `{}`", ty), span)
+            .with_info(&format!("`{}` takes {}", func.name(), expected), None)
+            .finish();
+    }
+}
+
+/// Type of a result picked from two numbers, or `None` if both are already the same type.
+///
+/// Mixing in a real number always gives back a real number.
+fn common_number_ty(lhs: &ty::Type, rhs: &ty::Type) -> Option<ty::Type> {
+    use ty::Type;
+
+    match (lhs, rhs) {
+        _ if lhs == rhs => None,
+        (Type::Real(_), _) | (_, Type::Real(_)) => Some(Type::Real(ty::RealSize::Real)),
+        (Type::Int(_), _) | (_, Type::Int(_)) => Some(Type::Int(ty::IntSize::Int)),
+        _ => Some(Type::Nat(ty::NatSize::Nat)),
+    }
+}
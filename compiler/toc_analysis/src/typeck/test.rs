@@ -5,7 +5,7 @@ use toc_hir::db;
 use toc_reporting::ReportMessage;
 use unindent::unindent;
 
-use crate::{const_eval::ConstEvalCtx, ty::TyCtx};
+use crate::{const_eval::ConstEvalCtx, ty::TyCtx, Dialect};
 
 macro_rules! test_for_each_op {
     ($top_level_name:ident, $([$(($op:literal, $sub_name:ident)),+ $(,)?] => $source:literal),+ $(,)?) => {
@@ -36,10 +36,19 @@ macro_rules! test_named_group {
 
 #[track_caller]
 fn assert_typecheck(source: &str) {
-    insta::assert_snapshot!(insta::internals::AutoName, do_typecheck(source), source);
+    assert_typecheck_with_dialect(source, Default::default());
 }
 
-fn do_typecheck(source: &str) -> String {
+#[track_caller]
+fn assert_typecheck_with_dialect(source: &str, dialect: Dialect) {
+    insta::assert_snapshot!(
+        insta::internals::AutoName,
+        do_typecheck(source, dialect),
+        source
+    );
+}
+
+fn do_typecheck(source: &str, dialect: Dialect) -> String {
     let (hir_db, root_unit) = {
         let parsed = toc_parser::parse(None, &source);
         let hir_db = db::HirBuilder::new();
@@ -53,8 +62,13 @@ fn do_typecheck(source: &str) -> String {
     let unit = hir_db.get_unit(root_unit);
     let const_eval_ctx = Arc::new(ConstEvalCtx::new(hir_db.clone()));
     crate::const_eval::collect_const_vars(hir_db.clone(), unit, const_eval_ctx.clone());
-    let (ty_ctx, typeck_messages) =
-        crate::typeck::typecheck_unit(hir_db.clone(), unit, const_eval_ctx);
+    let (ty_ctx, typeck_messages) = crate::typeck::typecheck_unit(
+        hir_db.clone(),
+        unit,
+        const_eval_ctx,
+        &Default::default(),
+        dialect,
+    );
 
     stringify_typeck_results(&ty_ctx, &typeck_messages)
 }
@@ -101,6 +115,7 @@ fn bare_var_decl() {
 
 #[test]
 fn undeclared_symbol() {
+    // Reported during lowering, but should still be given an error type
     assert_typecheck("var a := b");
     // No cyclic deps
     assert_typecheck("var a := a");
@@ -137,10 +152,7 @@ test_for_each_op! { arithmetic_op,
         ("+", add),
         ("-", sub),
         ("*", mul),
-        ("div", idiv),
         ("/", rdiv),
-        ("mod", r#mod),
-        ("rem", rem),
         ("**", exp),
     ] => r#"
     % Compatibility with all variant of numbers
@@ -170,6 +182,119 @@ test_for_each_op! { arithmetic_op,
     "#
 }
 
+test_for_each_op! { comparison_op,
+    [
+        ("<", less),
+        (">", greater),
+        ("<=", less_eq),
+        (">=", greater_eq),
+        ("=", equal),
+        ("~=", not_equal),
+    ] => r#"
+    % Compatibility with all variant of numbers
+    var r : real
+    var i : int
+    var n : nat
+    var _rr : boolean := r {0} r
+    var _ri : boolean := r {0} i
+    var _ir : boolean := i {0} r
+    var _rn : boolean := r {0} n
+    var _nr : boolean := n {0} r
+    var _ii : boolean := i {0} i
+    var _in : boolean := i {0} n
+    var _ni : boolean := n {0} i
+    var _nn : boolean := n {0} n
+
+    % Compatibility with all variants of charseqs
+    var c : char
+    var c_sz : char(6)
+    var s : string
+    var s_sz : string(6)
+    var _cc : boolean := c {0} c
+    var _cs : boolean := c {0} s
+    var _sc : boolean := s {0} c
+    var _cs_sz : boolean := c_sz {0} s_sz
+    var _s_szc_sz : boolean := s_sz {0} c_sz
+    var _ss : boolean := s {0} s
+"#
+}
+
+test_for_each_op! { comparison_op_wrong_type,
+    [
+        ("<", less),
+        (">", greater),
+        ("<=", less_eq),
+        (">=", greater_eq),
+        ("=", equal),
+        ("~=", not_equal),
+    ] => r#"
+    % Numbers and charseqs are not comparable
+    var i : int
+    var s : string
+    var _is := i {0} s
+    var _si := s {0} i
+"#
+}
+
+test_for_each_op! { equality_op,
+    [
+        ("=", equal),
+        ("~=", not_equal),
+    ] => r#"
+    var b : boolean
+    var _bb : boolean := b {0} b
+"#
+}
+
+test_for_each_op! { equality_op_wrong_type,
+    [
+        ("<", less),
+        (">", greater),
+        ("<=", less_eq),
+        (">=", greater_eq),
+    ] => r#"
+    % Booleans are not ordered
+    var b : boolean
+    var _bb := b {0} b
+"#
+}
+
+test_for_each_op! { integer_arithmetic_op,
+    [
+        ("div", idiv),
+        ("mod", r#mod),
+        ("rem", rem),
+    ] => r#"
+    % Compatibility with all variant of integers
+    var i : int
+    var n : nat
+    var _ii := i {0} i
+    var _in := i {0} n
+    var _ni := n {0} i
+    var _nn := n {0} n
+"#
+}
+
+test_for_each_op! { integer_arithmetic_op_wrong_type,
+    [
+        ("div", idiv),
+        ("mod", r#mod),
+        ("rem", rem),
+    ] => r#"
+    % Real operands are not allowed
+    var r : real
+    var i : int
+    var n : nat
+    var _rr := r {0} r
+    var _ri := r {0} i
+    var _ir := i {0} r
+    var _rn := r {0} n
+    var _nr := n {0} r
+    var _r0 := 1 {0} r
+    var _r1 := r {0} 1
+"#
+}
+
 test_for_each_op! { arithmetic_op_wrong_type,
     [
         ("+", add),
@@ -305,10 +430,7 @@ test_for_each_op! { integer_inference,
         ("+", add),
         ("-", sub),
         ("*", mul),
-        ("div", idiv),
         ("/", rdiv),
-        ("mod", r#mod),
-        ("rem", rem),
         ("**", exp),
     ] => r#"
     % Inferred integer types should pass
@@ -342,6 +464,49 @@ test_for_each_op! { integer_inference,
 "#
 }
 
+// Test integer inference for integer-only operators
+test_for_each_op! { integer_inference_integer_op,
+    [
+        ("div", idiv),
+        ("mod", r#mod),
+        ("rem", rem),
+    ] => r#"
+    % Inferred integer types should pass
+    % Decl should be a concrete type
+    var a := 1 {0} 1
+    % Types of operands should make the type concrete
+    var i : int
+    var n : nat
+    var _i0 := 1 {0} i
+    var _i1 := i {0} 1
+    var _n0 := 1 {0} n
+    var _n1 := n {0} 1
+"#
+}
+
+test_named_group! { typeck_arithmetic,
+    [
+        mixed_real_result => r#"var _ := 1 + 1.0"#,
+        real_idiv => r#"var _ := 1.0 div 2"#,
+        real_exp => r#"
+        var r : real
+        var _r0 := 2 ** r
+        var _r1 := r ** 2
+        "#,
+        string_concat => r#"var _ := "a" + "b""#,
+        string_concat_charseqs => r#"
+        var c : char
+        var cn : char(3)
+        var sn : string(3)
+        var _cc := c + c
+        var _ccn := c + cn
+        var _cns := cn + "s"
+        var _ssn := "s" + sn
+        "#,
+        string_concat_wrong_type => r#"var _ := "a" + 1"#,
+    ]
+}
+
 test_named_group! { sized_char,
     [
         literal => r#"var _ : char(1)"#,
@@ -411,6 +576,10 @@ test_named_group! { typeck_assignment,
             const k : int := 3
             k := j
             "#,
+        lhs_const_compound_add => r#"
+            const k : int := 3
+            k += 1
+            "#,
         valid_compound_add => r#"
             var lhs : real
             var rhs : int
@@ -665,7 +834,6 @@ test_named_group! { assignability_into,
 test_named_group! { typeck_put_stmt,
     [
         normal_items => r#"
-        % TODO: Uncomment enum lines once enum types are lowered & checked
         var i : int
         var n : nat
         var r : real
@@ -673,7 +841,7 @@ test_named_group! { typeck_put_stmt,
         var cn : char(4)
         var s : string
         var sn : string(4)
-        %type en: enum(a, b) var ef : en
+        type en: enum(a, b) var ef : en
 
         put i : 0
         put n : 0
@@ -682,7 +850,7 @@ test_named_group! { typeck_put_stmt,
         put cn : 0
         put s : 0
         put sn : 0
-        %put ef : 0
+        put ef : 0
         "#,
         valid_extended_opts => r#"
         var i : int
@@ -694,18 +862,17 @@ test_named_group! { typeck_put_stmt,
         put r : 0 : 0 : 0
         "#,
         invalid_extended_opts => r#"
-        % TODO: Uncomment enum lines once enum types are lowered & checked
         var c : char
         var cn : char(4)
         var s : string
         var sn : string(4)
-        %type en: enum(a, b) var ef : en
+        type en: enum(a, b) var ef : en
 
         put c : 0 : 0 : 0
         put cn : 0 : 0 : 0
         put s : 0 : 0 : 0
         put sn : 0 : 0 : 0
-        %put ef : 0 : 0 : 0
+        put ef : 0 : 0 : 0
         "#,
         wrong_type_stream => r#"
         var s : real
@@ -723,14 +890,25 @@ test_named_group! { typeck_put_stmt,
         var e : real
         put 1 : 0 : 0 : e
         "#,
-        // TODO: Add test for non-put-able items once non-primitive types are lowered
+        wrong_type_record => r#"
+        type r : record a : int end record
+        var v : r
+        put v : 0 : 0
+        "#,
+        mixed_items => r#"
+        var stream : int
+        var w : 1 .. 10
+        var i : int
+        var r : real
+        var b : boolean
+        put : stream, i : w, skip, r : 0 : 2 : 3, "s" : w, b ..
+        "#,
     ]
 }
 
 test_named_group! { typeck_get_stmt,
     [
         normal_items => r#"
-        % TODO: Uncomment enum lines once enum types are lowered & checked
         var i : int
         var n : nat
         var r : real
@@ -738,7 +916,7 @@ test_named_group! { typeck_get_stmt,
         var cn : char(4)
         var s : string
         var sn : string(4)
-        %type en: enum(a, b) var ef : en
+        type en: enum(a, b) var ef : en
 
         get i : 0
         get n : 0
@@ -747,7 +925,7 @@ test_named_group! { typeck_get_stmt,
         get cn : 0
         get s : 0
         get sn : 0
-        %get ef : 0
+        get ef : 0
         "#,
         wrong_type_stream => r#"
         var s : real
@@ -769,6 +947,1476 @@ test_named_group! { typeck_get_stmt,
         wrong_ref_literal => r#"
         get 1
         "#,
-        // TODO: Add test for non-get-able items once non-primitive types are lowered
+        wrong_type_record => r#"
+        type r : record a : int end record
+        var v : r
+        get v
+        "#,
+        mixed_items => r#"
+        var stream : nat
+        var w : 1 .. 10
+        var i : int
+        var s : string
+        var cn : char(4)
+        get : stream, skip, i, s : *, cn : w
+        "#,
+    ]
+}
+
+test_named_group! { typeck_field_access,
+    [
+        record_field => r#"
+        type r : record a : int b, c : real end record
+        var v : r
+        var a : int := v.a
+        var c : real := v.c
+        v.b := 1.0
+        "#,
+        nested_record_chain => r#"
+        type inner : record x : char end record
+        type outer : record i : inner end record
+        var o : outer
+        var c : char := o.i.x
+        o.i.x := 'c'
+        "#,
+        const_record_field => r#"
+        type r : record a : int end record
+        var w : r
+        const v : r := w
+        v.a := 1
+        "#,
+        unknown_field => r#"
+        type r : record a : int end record
+        var v : r
+        var a := v.b
+        "#,
+        unknown_field_suggestion => r#"
+        type r : record count : int end record
+        var v : r
+        var a := v.cuont
+        "#,
+        unknown_field_anonymous => r#"
+        var v : record a : int end record
+        var a := v.b
+        "#,
+        non_record_field => r#"
+        var v : int
+        var a := v.b
+        "#,
+        not_a_type => r#"
+        var a : int
+        var b : a
+        "#,
+        union_tag_read => r#"
+        type u : union kind : boolean of
+            label true: i : int
+            label false: r : real
+        end union
+        var v : u
+        var k : boolean := v.kind
+        var i : int := v.i
+        var r : real := v.r
+        v.kind := false
+        "#,
+        unknown_union_field => r#"
+        type u : union kind : boolean of
+            label true: i : int
+        end union
+        var v : u
+        var k := v.knd
+        "#,
+        record_assignability => r#"
+        type r : record a : int end record
+        type s : record a : int end record
+        var v : r
+        var w : r
+        var x : s
+        v := w
+        v := x
+        "#,
+    ]
+}
+
+test_named_group! { typeck_range,
+    [
+        int_range => r#"
+        type r : 1 .. 10
+        var a : r := 5
+        var b : int := a + 1
+        "#,
+        folded_bounds => r#"
+        var a_range : (1 - 3 shl 5) .. (2 * 50 - 8 * 4)
+        "#,
+        char_range => r#"
+        type r : 'a' .. 'z'
+        var c : r := 'c'
+        "#,
+        boolean_range => r#"
+        type r : false .. true
+        var b : r := true
+        "#,
+        empty_range => r#"
+        type r : 1 .. 0
+        "#,
+        unsized_range => r#"
+        type r : 1 .. *
+        "#,
+        negative_size => r#"
+        type r : 5 .. 1
+        "#,
+        mixed_bound_types => r#"
+        type r : 1 .. 'c'
+        "#,
+        wrong_bound_types => r#"
+        type r : 1.0 .. 2.0
+        "#,
+        too_many_elements => r#"
+        type r : -2000000000 .. 2000000000
+        "#,
+        non_const_bounds => r#"
+        var a : int
+        type r : 1 .. a
+        "#,
+        wrong_assign_type => r#"
+        type r : 1 .. 10
+        var a : r := 'c'
+        "#,
+    ]
+}
+
+test_named_group! { typeck_set_ty,
+    [
+        range_elem => r#"type s : set of 1 .. 3"#,
+        char_elem => r#"type s : set of char"#,
+        boolean_elem => r#"type s : set of boolean"#,
+        wrong_elem_ty => r#"type s : set of int"#,
+        same_elem_assign => r#"
+        var a : set of 'a' .. 'c'
+        var b : set of 'a' .. 'c'
+        a := b
+        "#,
+        different_elem_assign => r#"
+        var a : set of 'a' .. 'c'
+        var b : set of 'a' .. 'd'
+        a := b
+        "#,
+    ]
+}
+
+test_named_group! { typeck_subprogram_ty,
+    [
+        function_ty => r#"type f : function (a : int, b : string) : boolean"#,
+        procedure_ty => r#"type p : procedure (var a : int)"#,
+        same_ty_assign => r#"
+        var a : procedure (var a : int)
+        var b : procedure (var b : int)
+        a := b
+        "#,
+        different_ty_assign => r#"
+        var a : function (a : int, b : string) : boolean
+        var b : procedure (a : int)
+        a := b
+        "#,
+        // Subprograms can't be printed
+        text_io => r#"
+        var f : function (a : int) : int
+        put f
+        "#,
+    ]
+}
+
+test_named_group! { typeck_comparison,
+    [
+        int_less => r#"var b : boolean := 1 < 2"#,
+        string_equal => r#"var b : boolean := "a" = "b""#,
+        int_string_equal => r#"var b := 1 = "x""#,
+        // Enum fields from different enum types are not comparable
+        different_enum_equal => r#"
+        type e1 : enum (a, b)
+        type e2 : enum (a, b)
+        var b := e1.a = e2.a
+        "#,
+        same_enum_compare => r#"
+        type e : enum (a, b)
+        var b : boolean := e.a < e.b
+        var c : boolean := e.a = e.b
+        "#,
+        range_compare => r#"
+        type r : 1 .. 10
+        var a : r
+        var b : boolean := a < 5
+        "#,
+    ]
+}
+
+test_named_group! { typeck_enum,
+    [
+        variant_access => r#"
+        type e : enum (a, b)
+        var v := e.b
+        "#,
+        no_variant => r#"
+        type e : enum (a, b)
+        var v := e.c
+        "#,
+        anonymous_no_variant => r#"
+        var v : enum (a, b)
+        var w := v.a
+        "#,
+        assign_same_enum => r#"
+        type e : enum (a, b)
+        type e_alias : e
+        var v : e := e.a
+        var w : e_alias := v
+        v := e.b
+        "#,
+        assign_different_enum => r#"
+        type e1 : enum (a, b)
+        type e2 : enum (a, b)
+        var v : e1 := e2.a
+        var w : enum (a, b) := e1.a
+        "#,
+        assign_enum_and_int => r#"
+        type e : enum (a, b)
+        var v : e := 0
+        var i : int := e.a
+        "#,
+        case_on_enum => r#"
+        type e : enum (a, b)
+        type f : enum (a, b)
+        var v : e
+        case v of
+        label e.a :
+        label f.b :
+        label 1 :
+        end case
+        "#,
+        for_over_enum => r#"
+        type e : enum (a, b, c)
+        type f : enum (a, b)
+        for i : e var v : e := i end for
+        for i : e.a .. e.b var v : e := i end for
+        for i : e.a .. f.b end for
+        "#,
+    ]
+}
+
+test_named_group! { typeck_logical_ops,
+    [
+        logical_and => r#"var a : boolean := true and false"#,
+        bitwise_and => r#"var a : nat := 5 and 3"#,
+        mixed_and => r#"var a := true and 3"#,
+        mixed_or => r#"var a := 3 or false"#,
+        mixed_xor => r#"var a := true xor 3"#,
+        logical_imply => r#"var a : boolean := true => false"#,
+        integer_imply => r#"var a := 5 => 3"#,
+        mixed_imply => r#"var a := true => 3"#,
+        chained_imply => r#"var a : boolean := true => false => true"#,
+        logical_not => r#"var a : boolean := not true"#,
+        bitwise_not => r#"var a : nat := ~ 5"#,
+        nested => r#"
+        var a : boolean := (1 and 2) = 3 or not false
+        "#,
+    ]
+}
+
+test_named_group! { typeck_value_range,
+    [
+        in_range => r#"var a : nat1 := 255"#,
+        out_of_range => r#"var a : nat1 := 256"#,
+        negative_nat => r#"var a : nat := -1"#,
+        int_min => r#"var a : int := -16#7FFFFFFF - 1"#,
+        assign_out_of_range => r#"
+        var a : int2
+        a := 40000
+        "#,
+        const_out_of_range => r#"
+        const c := 200
+        var a : int1 := c
+        "#,
+        // Not known at compile-time
+        runtime_value => r#"
+        var c := 200
+        var a : int1 := c
+        "#,
+    ]
+}
+
+test_named_group! { typeck_conversion,
+    [
+        const_overflow => r#"var n1 : nat1 := 500"#,
+        const_fits => r#"
+        const c : int := 100
+        var i1 : int1 := c
+        "#,
+        narrowing => r#"
+        var i : int
+        var i1 : int1 := i
+        i1 := i
+        "#,
+        narrowing_nat => r#"
+        var n4 : nat4
+        var n2 : nat2 := n4
+        "#,
+        narrowing_compound => r#"
+        var i : int
+        var i1 : int1
+        i1 += i
+        "#,
+        widening => r#"
+        var i1 : int1
+        var n2 : nat2
+        var i : int := i1
+        var n : nat := n2
+        "#,
+        same_size => r#"
+        var i : int
+        var n : nat := i
+        "#,
+        real_to_int => r#"
+        var r : real
+        var i : int := r
+        i := r
+        "#,
+        real_to_nat_compound => r#"
+        var r : real
+        var n : nat
+        n += r
+        "#,
+        int_to_real => r#"
+        var i : int
+        var r : real := i
+        "#,
+        unchecked_narrowing => r#"
+        unchecked
+        var i : int
+        var i1 : int1 := i
+        "#,
+    ]
+}
+
+test_named_group! { typeck_charseq_conversion,
+    [
+        const_too_long => r#"
+        var s : string(4) := "hello"
+        var c : char(4) := ("hello")
+        s := "hello"
+        "#,
+        const_fits => r#"
+        var s : string(5) := "hello"
+        s := "hi"
+        "#,
+        const_multibyte_fits => r#"
+        var s : string(3) := "añb"
+        var c : char(3) := 'ñ☃b'
+        var d := 'ñ☃b'
+        "#,
+        dynamic_truncation => r#"
+        var s : string
+        var s10 : string(10)
+        var s4 : string(4) := s
+        s4 := s10
+        s4 += s
+        "#,
+        dynamic_fits => r#"
+        var s4 : string(4)
+        var s10 : string(10) := s4
+        var s : string := s10
+        "#,
+        char_to_string => r#"
+        var c : char
+        var c4 : char(4)
+        var c5 : char(5)
+        var s4 : string(4) := c
+        s4 := c4
+        s4 := c5
+        "#,
+        unchecked_truncation => r#"
+        unchecked
+        var s : string
+        var s4 : string(4) := s
+        s4 := "hello"
+        "#,
+    ]
+}
+
+test_named_group! { typeck_checkedness,
+    [
+        unchecked_suppress => r#"
+        unchecked
+        var a : nat1 := 256
+        "#,
+        checked_after_unchecked => r#"
+        unchecked
+        var a : nat1 := 256
+        checked
+        var b : nat1 := 256
+        "#,
+        unchecked_nested_block => r#"
+        var a : nat1
+        unchecked
+        begin
+            a := 256
+        end
+        "#,
+        unchecked_block_end => r#"
+        var a : nat1
+        begin
+            unchecked
+            a := 256
+        end
+        a := 256
+        "#,
+        // Type mismatches are still reported
+        unchecked_mismatch => r#"
+        unchecked
+        var a : nat1 := "256"
+        "#,
+        unchecked_subscript => r#"
+        var a : array 1 .. 2 of int
+        var p : ^int
+        a(1) := 1
+        unchecked
+        a(2) := ^p
+        checked
+        a(1) := 2
+        "#,
+        unchecked_collection_subscript => r#"
+        var c : collection of int
+        var p : pointer to c
+        unchecked
+        c(p) := 1
+        "#,
+        unchecked_nested_subscript => r#"
+        var a : array 1 .. 2 of int
+        begin
+            unchecked
+            a(1) := 1
+        end
+        a(2) := 2
+        "#,
+        unchecked_unused => r#"
+        var b : boolean
+        unchecked
+        b := true
+        "#,
+        unchecked_unused_before_checked => r#"
+        var a : array 1 .. 2 of int
+        var b : boolean
+        unchecked
+        b := true
+        checked
+        a(1) := 1
+        unchecked
+        a(2) := 2
+        "#,
+    ]
+}
+
+test_named_group! { typeck_substring,
+    [
+        string_range => r#"
+        var s : string
+        var t := s(1 .. 3)
+        "#,
+        char_seq_range => r#"
+        var s : char(10)
+        var t : string := s(2 .. *)
+        "#,
+        relative_bounds => r#"
+        var s : string
+        var t := s(* - 2 .. * - 1)
+        "#,
+        to_end => r#"
+        var s : string
+        var t := s(2 .. *)
+        "#,
+        wrong_operand_type => r#"
+        var i : int
+        var t := i(1 .. 3)
+        "#,
+        wrong_bound_types => r#"
+        var s : string
+        var t := s(1.0 .. 'c')
+        "#,
+        not_a_var_ref => r#"
+        var s : string
+        s(1 .. 2) := "a"
+        "#,
+    ]
+}
+
+test_named_group! { typeck_indirect,
+    [
+        prim_ty => r#"
+        var i : int := int @ (16#A000)
+        int @ (16#A000) := 1
+        "#,
+        named_ty => r#"
+        type myRec : record a, b : int end record
+        var p : addressint
+        var i : int := myRec @ (p + 4).b
+        "#,
+        int_addr => r#"
+        var p : int
+        var i : int := int @ (p)
+        "#,
+        wrong_addr_type => r#"
+        var i : int := int @ (1.0)
+        "#,
+    ]
+}
+
+test_named_group! { typeck_bits,
+    [
+        bit_range => r#"
+        var x : nat
+        var y : nat := bits(x, 0 .. 3)
+        bits(x, 4) := 1
+        "#,
+        subrange_ty => r#"
+        type low : 0 .. 7
+        var x : nat2
+        var y := bits(x, low)
+        "#,
+        wrong_ref_type => r#"
+        var x : int
+        var y := bits(x, 0 .. 3)
+        "#,
+        not_a_ref => r#"
+        var y := bits(1 + 2, 0 .. 3)
+        "#,
+        wrong_bit_types => r#"
+        var x : nat
+        var y := bits(x, 0.0 .. 'c')
+        "#,
+        not_a_var_ref => r#"
+        const x : nat := 1
+        bits(x, 0) := 1
+        "#,
+    ]
+}
+
+test_named_group! { typeck_nil,
+    [
+        bare_nil => r#"
+        type c : collection of int
+        var p : pointer to c
+        var q : unchecked pointer to int
+        p := nil
+        q := nil
+        "#,
+        collection_nil => r#"
+        type c : collection of int
+        var p : pointer to c
+        p := nil(c)
+        "#,
+        other_collection_nil => r#"
+        type c : collection of int
+        type d : collection of int
+        var p : pointer to c
+        p := nil(d)
+        "#,
+        not_a_collection => r#"
+        type i : int
+        var p : pointer to int
+        p := nil(i)
+        "#,
+        not_a_pointer => r#"
+        var i : int := nil
+        "#,
+    ]
+}
+
+test_named_group! { typeck_collection,
+    [
+        elem_access => r#"
+        type rec : record name : string end record
+        var people : collection of rec
+        var p : pointer to people
+        new people, p
+        people(p).name := "a"
+        free people, p
+        "#,
+        collection_var_nil => r#"
+        var c : collection of int
+        var p : pointer to c := nil(c)
+        "#,
+        distinct_collections => r#"
+        var c : collection of int
+        var d : collection of int
+        var p : pointer to c
+        var q : pointer to d
+        p := q
+        "#,
+        forward_elem_resolved => r#"
+        var c : collection of forward n
+        type n : int
+        var p : pointer to c
+        new c, p
+        "#,
+        forward_elem_unresolved => r#"
+        var c : collection of forward n
+        "#,
+        wrong_subscript_ptr => r#"
+        var c : collection of int
+        var q : pointer to int
+        var i := c(q)
+        "#,
+        wrong_subscript_count => r#"
+        var c : collection of int
+        var p : pointer to c
+        var i := c(p, p)
+        "#,
+        deref_after_out_of_scope => r#"
+        var f : procedure
+        begin
+            var c : collection of int
+            var p : pointer to c
+            new c, p
+            procedure g
+                c(p) := 1
+                put ^p
+            end g
+            f := g
+        end
+        f
+        "#,
+        deref_in_scope => r#"
+        var c : collection of int
+        var p : pointer to c
+        var f : procedure
+        procedure g
+            c(p) := 1
+        end g
+        begin
+            var h : procedure
+            h := g
+            f := g
+        end
+        "#,
+    ]
+}
+
+test_named_group! { typeck_new_free,
+    [
+        pointer_to_type => r#"
+        var p : pointer to int
+        new p
+        free p
+        "#,
+        not_a_pointer => r#"
+        var c : collection of int
+        var i : int
+        new c, i
+        free c, i
+        "#,
+        not_a_collection => r#"
+        var i : int
+        var p : pointer to int
+        new i, p
+        free i, p
+        "#,
+        other_collection => r#"
+        var c : collection of int
+        var d : collection of int
+        var p : pointer to c
+        new d, p
+        "#,
+        missing_collection => r#"
+        var c : collection of int
+        var p : pointer to c
+        new p
+        free p
+        "#,
+        const_pointer => r#"
+        const p : pointer to int := nil
+        new p
+        "#,
+        not_a_reference => r#"
+        var c : collection of int
+        new c, nil(c)
+        "#,
+    ]
+}
+
+test_named_group! { typeck_init_expr,
+    [
+        sized_array => r#"
+        var a : array 1 .. 3 of int := init(1, 2, 3)
+        const b : array boolean, 1 .. 2 of real := init(1, 2.0, 3, 4)
+        "#,
+        wrong_count => r#"
+        var a : array 1 .. 3 of int := init(1, 2)
+        var b : array 1 .. 2 of int := init(1, 2, 3)
+        "#,
+        unsized_array => r#"
+        var a : array 1 .. * of int := init(1, 2, 3)
+        var b : flexible array 1 .. 2 of int := init(1, 2, 3)
+        "#,
+        wrong_elem_ty => r#"
+        var a : array 1 .. 3 of int := init(1, 'c', 3.0)
+        var b : array 1 .. 1 of nat1 := init(256)
+        "#,
+        record_fields => r#"
+        type r : record
+            a : int
+            b : string
+            c : array 1 .. 2 of char
+        end record
+        var ok : r := init(1, "b", init('c', 'd'))
+        var short : r := init(1, "b")
+        var bad : r := init("a", 2, init('c', 'd'))
+        "#,
+        not_aggregate => r#"
+        var a : int := init(1)
+        "#,
+        general_expr => r#"
+        var a := init(1, 2, 3)
+        var b : array 1 .. 1 of int
+        b := init(1)
+        "#,
+    ]
+}
+
+test_named_group! { typeck_implicit_size_array,
+    [
+        sized_from_init => r#"
+        var a : array 1 .. * of int := init(1, 2, 3)
+        "#,
+        missing_init => r#"
+        var a : array 1 .. * of int
+        var b : array 1 .. 3 of int
+        var c : array 1 .. * of int := b
+        "#,
+    ]
+}
+
+test_named_group! { typeck_builtin,
+    [
+        addr_result => r#"
+        var i : int
+        var a := addr(i)
+        "#,
+        addr_value => r#"
+        var a := addr(1)
+        "#,
+        sizeof_result => r#"
+        type t : int
+        var i : int
+        var a := sizeof(t)
+        var b := sizeof(i)
+        var c := sizeof(array 1 .. 2 of int)
+        "#,
+        sizeof_value => r#"
+        var a := sizeof(1 + 1)
+        "#,
+        bounds_result => r#"
+        type r : 'a' .. 'z'
+        var c : array r of int
+        var a := upper(c)
+        var b := lower(r)
+        "#,
+        bounds_not_bounded => r#"
+        var i : int
+        var a := upper(i)
+        "#,
+        ord_result => r#"
+        var a := ord('c')
+        "#,
+        ord_not_char => r#"
+        var a := ord(1.0)
+        "#,
+        chr_result => r#"
+        var a := chr(65)
+        "#,
+        chr_not_integer => r#"
+        type t : int
+        var a := chr('a')
+        var b := chr(t)
+        "#,
+        wrong_arg_count => r#"
+        var i : int
+        var a := chr()
+        var b := ord('a', 'b')
+        "#,
+        length_result => r#"
+        var s : string(4)
+        var c : array 1 .. 3 of real
+        var a := length("abc")
+        var b := length(s)
+        var d := length(c)
+        "#,
+        length_not_sequence => r#"
+        var a := length(1)
+        "#,
+        index_result => r#"
+        var s : string
+        var a := index(s, "b")
+        var b := index('abc', 'c')
+        "#,
+        index_not_string => r#"
+        var a := index(1, "b")
+        var b := index("abc", 2.0)
+        "#,
+        repeat_result => r#"
+        var a := repeat("ab", 3)
+        var b := repeat('c', 2)
+        "#,
+        repeat_not_integer => r#"
+        var a := repeat("ab", "c")
+        "#,
+        real_math_result => r#"
+        var a := sqrt(2)
+        var b := sin(1.0)
+        var c := cos(1.0)
+        var d := exp(1)
+        var e := ln(2.0)
+        "#,
+        rounding_result => r#"
+        var a := round(1.5)
+        var b := floor(1.5)
+        var c := ceil(1)
+        "#,
+        abs_result => r#"
+        var n : nat
+        var a := abs(-1.5)
+        var b := abs(n)
+        "#,
+        max_min_result => r#"
+        var i : int
+        var n : nat
+        var a := max(1, 2.0)
+        var b := min(i, n)
+        var c := max(1, 2)
+        "#,
+        math_not_number => r#"
+        var a := sqrt("4")
+        var b := round(true)
+        var c := max(1, 'c')
+        "#,
+        math_wrong_arg_count => r#"
+        var a := sqrt(1, 2)
+        var b := max(1)
+        "#,
+    ]
+}
+
+test_named_group! { typeck_deref,
+    [
+        pointer_operand => r#"
+        type t : record a : int end record
+        var p : ^int
+        var q : ^t
+        var a := ^p
+        var b : int := ^q.a
+        ^p := 1
+        ^q.a := 2
+        "#,
+        collection_pointer => r#"
+        var c : collection of int
+        var p : pointer to c
+        var a : int := ^p
+        ^p := 1
+        "#,
+        not_pointer => r#"
+        var i : int
+        var a := ^1
+        var b := ^i
+        "#,
+    ]
+}
+
+test_named_group! { typeck_nat_cheat,
+    [
+        sized_operands => r#"
+        var c : char
+        var i2 : int2
+        var r4 : real4
+        var a := #c
+        var b := #i2
+        var d := #r4
+        var e := #9 * 2
+        "#,
+        range_operand => r#"
+        type r : 1 .. 3
+        var v : r
+        var a := #v
+        "#,
+        unsized_operand => r#"
+        var r : real
+        var s : string
+        var a := #r
+        var b := #s
+        "#,
+    ]
+}
+
+test_named_group! { typeck_quit_stmt,
+    [
+        no_reason => "quit",
+        int_reason => "quit : 1",
+        nat_reason => r#"
+        var n : nat
+        quit > : n
+        "#,
+        wrong_reason_type => r#"quit < : "bad""#,
+    ]
+}
+
+test_named_group! { typeck_folded_op_errors,
+    [
+        int_div_by_zero => "const x := 1 div 0",
+        int_mod_by_zero => "const x := 1 mod 0",
+        int_rem_by_zero => "const x := 1 rem 0",
+        real_div_by_zero => "var x := 1.0 / 0",
+        int_overflow => "var x := 16#ffffffff + 1",
+        int_typed_overflow => "var y : int := 2147483647 + 1",
+        sized_int_overflow => r#"
+        const big : int := 2147483647
+        var y := big * 2 - 1
+        "#,
+        negative_int_exp => "const x := 2 ** -1",
+        nested_zero => "const x := 1 + 10 div (2 - 2)",
+        zero_from_const => r#"
+        const zero := 0
+        var x := 1 div zero
+        "#,
+        // Only reported once at the failing operation
+        propagated => r#"
+        const x := 1 div 0
+        const y := x + 1
+        "#,
+        non_const_operand => r#"
+        var zero := 0
+        var x := 1 div zero
+        "#,
+    ]
+}
+
+test_named_group! { typeck_forward_type,
+    [
+        resolved => r#"
+        type a : forward
+        type a : int
+        var b : a
+        "#,
+        unresolved => r#"
+        type a : forward
+        var b : a
+        "#,
+        // Resolving in an inner scope doesn't resolve the outer forward
+        resolved_in_inner_scope => r#"
+        type a : forward
+        begin
+            type a : int
+        end
+        "#,
+        duplicate_before_resolved => r#"
+        type a : forward
+        type a : forward
+        type a : int
+        "#,
+    ]
+}
+
+test_named_group! { typeck_bind,
+    [
+        to_array_elem => r#"
+        var arr : array 1 .. 3 of int
+        bind var a to arr(3)
+        a := 1
+        "#,
+        to_record_field => r#"
+        var r : record f : real end record
+        bind var a to r.f
+        a := 1.0
+        "#,
+        to_const => r#"
+        const c : int := 1
+        bind a to c
+        "#,
+        var_to_const => r#"
+        const c : int := 1
+        bind var a to c
+        "#,
+        var_to_const_elem => r#"
+        var v : array 1 .. 3 of int
+        const arr : array 1 .. 3 of int := v
+        bind var a to arr(1)
+        "#,
+        var_to_const_field => r#"
+        type rec : record f : int end record
+        var r : rec
+        const c : rec := r
+        bind var a to c.f
+        "#,
+        // Non-`var` binds can't be assigned to
+        assign_to_const_bind => r#"
+        var v : int
+        bind a to v
+        a := 1
+        "#,
+        to_undeclared => r#"
+        bind var a to b(1)
+        "#,
+    ]
+}
+
+test_named_group! { typeck_array_subscript,
+    [
+        elem_ty => r#"
+        var arr : array 1 .. 3, char of real
+        var r : real := arr(1, 'c')
+        "#,
+        wrong_count => r#"
+        var arr : array 1 .. 3 of int
+        var a := arr(1, 2)
+        var b := arr()
+        "#,
+        wrong_index_ty => r#"
+        var arr : array 1 .. 3 of int
+        var a := arr('c')
+        "#,
+        not_an_array => r#"
+        var i : int
+        var a := i(1)
+        "#,
+        const_elem => r#"
+        var v : array 1 .. 3 of int
+        const arr : array 1 .. 3 of int := v
+        arr(1) := 1
+        "#,
+        invalid_index_ty => r#"
+        var arr : array real of int
+        "#,
+        text_io => r#"
+        var arr : array 1 .. 3 of int
+        put arr
+        "#,
+    ]
+}
+
+test_named_group! { typeck_subprogram,
+    [
+        params => r#"
+        procedure p (var a : int, b : real)
+            a := 1
+            b := 1.0
+        end p
+        "#,
+        call_stmt => r#"
+        procedure p (a : int, b : real) end p
+        p(1, 2)
+        "#,
+        function_call => r#"
+        function f (a : int) : real end f
+        var r : real := f(1)
+        "#,
+        implicit_call => r#"
+        function f : int end f
+        var a : int := f + 1
+        "#,
+        recursive_call => r#"
+        procedure p (a : int)
+            p(a - 1)
+        end p
+        "#,
+        wrong_arg_count => r#"
+        procedure p (a : int) end p
+        procedure q end q
+        p(1, 2)
+        p
+        q(1)
+        "#,
+        wrong_arg_ty => r#"
+        procedure p (a : int, b : string) end p
+        p("a", 1)
+        "#,
+        var_arg_same_ty => r#"
+        procedure p (var a : int) end p
+        var n : nat
+        p(n)
+        "#,
+        const_var_arg => r#"
+        procedure p (var a : int) end p
+        const c : int := 1
+        p(c)
+        "#,
+        not_ref_var_arg => r#"
+        procedure p (var a : int) end p
+        function f (var a : int) : int end f
+        var n : int
+        p(1)
+        p(n + 1)
+        n := f(2)
+        "#,
+        procedure_as_value => r#"
+        procedure p (a : int) end p
+        var a := p(1)
+        "#,
+        not_a_subprogram => r#"
+        var a : int
+        a
+        "#,
+        named_result => r#"
+        function f (a : int) r : real
+            r := a
+            r += 1
+            var b : real := r
+            return
+        end f
+        "#,
+        named_result_wrong_ty => r#"
+        function f r : int
+            r := "a"
+        end f
+        "#,
+        call_through_var => r#"
+        var fv : function (a : int) : boolean
+        procedure pv (f : function (a : int) : boolean) end pv
+        var b : boolean := fv(1)
+        pv(fv)
+        "#,
+    ]
+}
+
+test_named_group! { typeck_forward_subprogram,
+    [
+        call_before_body => r#"
+        forward procedure p (a : int)
+        p(1)
+        p(1, 2)
+        body procedure p (a : int) end p
+        "#,
+        params_from_forward => r#"
+        forward procedure p (a : int)
+        body procedure p
+            var b : int := a
+        end p
+        "#,
+        result_from_forward => r#"
+        forward function f : int
+        body function f
+            result 'c'
+        end f
+        "#,
+        used_after_body => r#"
+        forward procedure p (a : int)
+        body procedure p (a, b : int) end p
+        p(1)
+        "#,
+    ]
+}
+
+test_named_group! { typeck_process,
+    [
+        params => r#"
+        process p (var a : int, b : real) : 8192
+            a := 1
+            b := 1.0
+        end p
+        "#,
+        wrong_stack_size => r#"process p : 1.0 end p"#,
+        direct_call => r#"
+        process p (a : int) end p
+        p(1)
+        p
+        "#,
+        as_value => r#"
+        process p end p
+        var a := p
+        "#,
+    ]
+}
+
+test_named_group! { typeck_module,
+    [
+        body => r#"
+        module m
+            var a : int := 1
+            procedure p
+                a := 2
+            end p
+        end m
+        "#,
+        not_a_value_or_type => r#"
+        module m end m
+        var a := m
+        var b : m
+        "#,
+    ]
+}
+
+test_named_group! { typeck_fork,
+    [
+        process => r#"
+        process p (a : int) end p
+        var s : boolean
+        var d : addressint
+        fork p(1)
+        fork p(1) : s, 8192, d
+        "#,
+        self_fork => "process p fork p end p",
+        not_a_process => r#"
+        var a : int
+        fork a
+        "#,
+        wrong_status_ty => r#"
+        process p end p
+        var s : int
+        fork p : s
+        "#,
+        wrong_stack_size => r#"
+        process p end p
+        var s : boolean
+        fork p : s, 'c'
+        "#,
+        procedure => r#"
+        procedure p (a : int) end p
+        fork p(1)
+        "#,
+    ]
+}
+
+#[test]
+fn fork_procedure_in_dialect() {
+    let dialect = Dialect {
+        fork_procedures: true,
+    };
+
+    assert_typecheck_with_dialect(
+        &unindent(
+            r#"
+            procedure p (a : int) end p
+            fork p(1)
+            "#,
+        ),
+        dialect,
+    );
+    assert_typecheck_with_dialect(
+        &unindent(
+            r#"
+            procedure p (a : int) end p
+            fork p(1, 2)
+            "#,
+        ),
+        dialect,
+    );
+    assert_typecheck_with_dialect(
+        &unindent(
+            r#"
+            function f : int result 1 end f
+            fork f
+            "#,
+        ),
+        dialect,
+    );
+}
+
+test_named_group! { typeck_tag,
+    [
+        valid_tag => r#"
+        type u : union kind : 1 .. 3 of
+        label 1: a : int
+        label 2, 3: b : real
+        end union
+        var v : u
+        var k : int
+        tag v, 2
+        tag v, k
+        "#,
+        boolean_tag => r#"
+        var v : union kind : boolean of label true: a : int label : end union
+        tag v, false
+        "#,
+        not_a_union => r#"
+        var i : int
+        tag i, 1
+        "#,
+        not_a_reference => "tag 1, 1",
+        const_union => r#"
+        type u : union kind : boolean of label true: a : int label : end union
+        var v : u
+        const c : u := v
+        tag c, true
+        "#,
+        wrong_tag_ty => r#"
+        var v : union kind : 1 .. 3 of label 1: a : int end union
+        tag v, true
+        "#,
+        out_of_range_tag => r#"
+        var v : union kind : 1 .. 3 of label 1: a : int end union
+        tag v, 4
+        tag v, 1 - 1
+        "#,
+        out_of_range_char_tag => r#"
+        var v : union kind : 'a' .. 'c' of label 'a': a : int end union
+        tag v, 'd'
+        "#,
+        unchecked_out_of_range_tag => r#"
+        unchecked
+        var v : union kind : 1 .. 3 of label 1: a : int end union
+        tag v, 4
+        "#,
+        record_tag => r#"
+        type r : record a : int end record
+        var v : r
+        tag v, 1
+        "#,
+        field_tag => r#"
+        type u : union kind : boolean of label true: a : int label : end union
+        var r : record f : u end record
+        var a : array 1 .. 2 of u
+        tag r.f, true
+        tag a(1), true
+        tag (a(1)), true
+        "#,
+        read_after_retag => r#"
+        type u : union kind : 1 .. 3 of
+        label 1: a : int
+        label 2: b : real
+        label : c : boolean
+        end union
+        var v : u
+        tag v, 1
+        v.a := 1
+        put v.b
+        tag v, 3
+        put v.c
+        put v.a, v.kind
+        tag (v), 2
+        put v.b
+        "#,
+        read_after_retag_forgotten => r#"
+        type u : union kind : 1 .. 3 of label 1: a : int label 2: b : real end union
+        var v, w : u
+        var k : int
+        % Only straight-line code is tracked
+        tag v, 1
+        begin put v.b end
+        put v.b
+        % Tags are forgotten after a runtime tag value or a new union value
+        tag v, 1
+        tag v, k
+        put v.b
+        tag v, 1
+        v := w
+        put v.b
+        "#,
+    ]
+}
+
+test_named_group! { typeck_if,
+    [
+        boolean_condition => r#"
+        if true then
+            var a : int
+        elsif 1 = 2 then
+        else
+        end if
+        "#,
+        wrong_condition_ty => r#"
+        if 1 then
+        elsif "a" then
+        end if
+        "#,
+    ]
+}
+
+test_named_group! { typeck_loop,
+    [
+        exit_when => r#"
+        loop
+            exit when true
+            exit
+        end loop
+        "#,
+        wrong_exit_when_ty => r#"
+        loop exit when 1 end loop
+        "#,
+    ]
+}
+
+test_named_group! { typeck_for,
+    [
+        counter_ty => r#"
+        var n : nat
+        type t : 'a' .. 'c'
+        for i : 1 .. 10 var a : int := i end for
+        for i : 1 .. n var a : nat := i end for
+        for decreasing i : 'a' .. 'z' by 2 var c : char := i end for
+        for i : false .. true var b : boolean := i end for
+        for i : t var c : char := i end for
+        "#,
+        wrong_bounds_ty => r#"
+        for i : 1 .. 'c' end for
+        for i : 1.0 .. 2.0 end for
+        for : "a" .. "b" end for
+        "#,
+        wrong_implicit_bounds_ty => r#"
+        type t : int
+        for i : t end for
+        "#,
+        wrong_step_ty => r#"
+        for i : 1 .. 2 by 1.0 end for
+        "#,
+        assign_to_counter => r#"
+        for i : 1 .. 2
+            i := 3
+        end for
+        "#,
+        counter_as_var_arg => r#"
+        procedure p (var a : int) end p
+        for i : 1 .. 2
+            p(i)
+        end for
+        "#,
+    ]
+}
+
+test_named_group! { typeck_case,
+    [
+        discrete_discriminant => r#"
+        var n : nat
+        case n of label 1, 2 : label 3 : label : end case
+        case 'c' of label 'a' : end case
+        case true of label false : end case
+        "#,
+        wrong_discriminant_ty => r#"
+        case "str" of label "a" : end case
+        case 1.0 of label 1 : end case
+        "#,
+        wrong_selector_ty => r#"
+        case 1 of label 'a', true : end case
+        "#,
+    ]
+}
+
+test_named_group! { typeck_result,
+    [
+        matching_ty => r#"
+        function f : real
+            result 1
+        end f
+        "#,
+        wrong_ty => r#"
+        function f : int
+            result "a"
+        end f
+        "#,
+        nested_in_stmts => r#"
+        function f : int
+            if true then result 1 end if
+            loop result 'c' end loop
+        end f
+        "#,
+        outside_of_function => r#"
+        procedure p result 1 end p
+        result 2
+        "#,
+        in_process => r#"
+        process p result 1 end p
+        "#,
     ]
 }
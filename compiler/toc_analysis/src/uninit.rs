@@ -0,0 +1,339 @@
+//! Uninitialized variable use detection
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use toc_hir::visitor::HirVisitor;
+use toc_hir::{db, expr, stmt, symbol, ty, unit};
+use toc_reporting::{MessageSink, ReportMessage, Stage};
+
+use crate::cfg::{self, Cfg};
+
+/// Reports reads of `var`s declared without an initial value, on paths where
+/// the variable hasn't been given a value yet.
+///
+/// After a branch, only variables initialized along every path are initialized,
+/// and passing a variable to a `var` parameter counts as initializing it.
+/// Writing into any part of an array or record counts as initializing all of it,
+/// and each variable is only reported once.
+pub fn check_uninit_vars(hir_db: db::HirDb, unit: &unit::Unit) -> Vec<ReportMessage> {
+    let mut checker = InitChecker {
+        hir_db: &hir_db,
+        unit,
+        var_params: collect_var_params(&hir_db, unit),
+        reported: HashSet::new(),
+        reporter: MessageSink::new(Stage::Analyze),
+    };
+
+    checker.check_body(&unit.stmts);
+    checker.reporter.finish()
+}
+
+struct InitChecker<'a> {
+    hir_db: &'a db::HirDb,
+    unit: &'a unit::Unit,
+    /// If each parameter is a `var` parameter, for everything that can be called
+    var_params: HashMap<symbol::DefId, Vec<bool>>,
+    /// Variables which have already been reported
+    reported: HashSet<symbol::DefId>,
+    reporter: MessageSink,
+}
+
+/// How a single statement affects which variables have a value
+#[derive(Default)]
+struct NodeEffects {
+    /// Names read by the statement, before anything is written
+    reads: Vec<(expr::ExprId, symbol::DefId)>,
+    /// Variables declared without an initial value
+    declared: Vec<symbol::DefId>,
+    /// Variables given a value
+    written: Vec<symbol::DefId>,
+}
+
+impl InitChecker<'_> {
+    /// Checks a list of statements with its own flow, i.e. the unit or a subprogram body.
+    ///
+    /// Only variables declared in the list are tracked, since anything from outside of
+    /// a subprogram could've been given a value before it was called.
+    fn check_body(&mut self, stmts: &[stmt::StmtId]) {
+        let cfg = Cfg::build(self.hir_db, stmts);
+        let nodes = cfg
+            .nodes()
+            .map(|(_, stmt_id)| self.node_effects(stmt_id))
+            .collect::<Vec<_>>();
+
+        // Variables which may not have a value yet when entering each node.
+        // Values flow forwards, so this flows along successors until nothing changes.
+        let mut uninit_in = vec![HashSet::new(); nodes.len()];
+
+        loop {
+            let mut changed = false;
+
+            for (node, _) in cfg.nodes() {
+                if !cfg.is_node_reachable(node) {
+                    continue;
+                }
+
+                let effects = &nodes[node.index()];
+                let mut uninit = uninit_in[node.index()].clone();
+
+                uninit.extend(effects.declared.iter().copied());
+                for def_id in &effects.written {
+                    uninit.remove(def_id);
+                }
+
+                for succ in cfg.successors(node).iter().flatten() {
+                    let succ_in: &mut HashSet<_> = &mut uninit_in[succ.index()];
+                    let old_len = succ_in.len();
+
+                    succ_in.extend(uninit.iter().copied());
+                    changed |= succ_in.len() != old_len;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        // Nothing in unreachable statements is run, so they can't read anything
+        for (node, stmt_id) in cfg.nodes() {
+            if !cfg.is_node_reachable(node) {
+                continue;
+            }
+
+            for (expr_id, def_id) in &nodes[node.index()].reads {
+                if uninit_in[node.index()].contains(def_id) {
+                    self.report_read(*expr_id, *def_id);
+                }
+            }
+
+            match self.hir_db.get_stmt(stmt_id) {
+                stmt::Stmt::Subprogram(decl) => self.check_body(&decl.stmts),
+                stmt::Stmt::Process(decl) => self.check_body(&decl.stmts),
+                _ => {}
+            }
+        }
+    }
+
+    fn node_effects(&self, stmt_id: stmt::StmtId) -> NodeEffects {
+        let stmt = self.hir_db.get_stmt(stmt_id);
+
+        let writes = match stmt {
+            // Bodies are checked on their own
+            stmt::Stmt::Subprogram(_) | stmt::Stmt::Process(_) => return NodeEffects::default(),
+            // Compound assignments also read from the left hand side
+            stmt::Stmt::Assign(stmt) if *stmt.op.item() == stmt::AssignOp::None => {
+                vec![stmt.lhs]
+            }
+            stmt::Stmt::Get(stmt) => stmt
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    stmt::Skippable::Item(item) => Some(item.expr),
+                    stmt::Skippable::Skip => None,
+                })
+                .collect(),
+            stmt::Stmt::New(stmt) => vec![stmt.pointer],
+            // Tagging selects the variant that later field writes go into
+            stmt::Stmt::Tag(stmt) => vec![stmt.union_ref],
+            // Writes through the new names aren't tracked, so binding counts as initializing
+            stmt::Stmt::Bind(stmt) => stmt.bindings.iter().map(|item| item.bind_to).collect(),
+            _ => vec![],
+        };
+
+        let accesses = AccessCollector {
+            hir_db: self.hir_db,
+            var_params: &self.var_params,
+            names: RefCell::default(),
+            var_args: RefCell::default(),
+        };
+        cfg::walk_node(self.hir_db, stmt_id, &accesses);
+
+        let written = writes
+            .into_iter()
+            .chain(accesses.var_args.into_inner())
+            .filter_map(|expr_id| self.written_var(expr_id))
+            .collect::<Vec<_>>();
+
+        // Everything else is read before anything is written to
+        let reads = accesses
+            .names
+            .into_inner()
+            .into_iter()
+            .filter(|(expr_id, _)| !written.iter().any(|(write_id, _)| write_id == expr_id))
+            .collect();
+
+        let declared = match stmt {
+            stmt::Stmt::ConstVar(decl) if self.needs_init(decl) => decl.names.clone(),
+            _ => vec![],
+        };
+
+        NodeEffects {
+            reads,
+            declared,
+            written: written.into_iter().map(|(_, def_id)| def_id).collect(),
+        }
+    }
+
+    /// Checks if the variables in `decl` start without a value
+    fn needs_init(&self, decl: &stmt::ConstVar) -> bool {
+        let ty_spec = match &decl.tail {
+            stmt::ConstVarTail::TypeSpec(ty_spec) if !decl.is_const => *ty_spec,
+            _ => return false,
+        };
+
+        // Collections and conditions are usable as soon as they're declared
+        !matches!(
+            self.hir_db.get_type(ty_spec),
+            ty::Type::Collection(_) | ty::Type::Condition(_)
+        )
+    }
+
+    /// Finds the variable being written into by the reference expression `id`,
+    /// along with the name expression referring to it
+    fn written_var(&self, mut id: expr::ExprId) -> Option<(expr::ExprId, symbol::DefId)> {
+        loop {
+            id = match self.hir_db.get_expr(id) {
+                expr::Expr::Name(expr::Name::Name(use_id)) => return Some((id, use_id.as_def())),
+                expr::Expr::Paren(expr) => expr.expr,
+                // Any element or field write initializes the whole array or record
+                expr::Expr::Field(expr) => expr.lhs,
+                expr::Expr::Call(expr) => expr.lhs,
+                _ => return None,
+            };
+        }
+    }
+
+    fn report_read(&mut self, expr_id: expr::ExprId, def_id: symbol::DefId) {
+        // Only reported once, since later reads would just repeat the same thing
+        if !self.reported.insert(def_id) {
+            return;
+        }
+
+        let name = &self.unit.symbol_table.get_symbol(def_id).name;
+        let span = self.hir_db.get_span(expr_id.into());
+
+        self.reporter
+            .warn_detailed(
+                &format!("`{}` may be used before being initialized", name),
+                span,
+            )
+            .with_info(
+                &format!("`{}` is declared here without an initial value", name),
+                self.unit.symbol_table.get_def_span(def_id),
+            )
+            .finish();
+    }
+}
+
+/// Collects the names read by a statement, along with the arguments passed to `var` parameters
+struct AccessCollector<'a> {
+    hir_db: &'a db::HirDb,
+    var_params: &'a HashMap<symbol::DefId, Vec<bool>>,
+    names: RefCell<Vec<(expr::ExprId, symbol::DefId)>>,
+    var_args: RefCell<Vec<expr::ExprId>>,
+}
+
+impl AccessCollector<'_> {
+    fn add_call(&self, callee: expr::ExprId, args: &[expr::ExprId]) {
+        let callee = match self.hir_db.get_expr(callee) {
+            expr::Expr::Name(expr::Name::Name(use_id)) => use_id.as_def(),
+            _ => return,
+        };
+
+        if let Some(params) = self.var_params.get(&callee) {
+            let var_args = args
+                .iter()
+                .zip(params)
+                .filter(|(_, is_var)| **is_var)
+                .map(|(arg, _)| *arg);
+
+            self.var_args.borrow_mut().extend(var_args);
+        }
+    }
+}
+
+impl HirVisitor for AccessCollector<'_> {
+    fn visit_name(&self, id: expr::ExprId, expr: &expr::Name) {
+        if let expr::Name::Name(use_id) = expr {
+            self.names.borrow_mut().push((id, use_id.as_def()));
+        }
+    }
+
+    fn visit_call(&self, _id: expr::ExprId, expr: &expr::Call) {
+        self.add_call(expr.lhs, &expr.arguments);
+    }
+
+    fn visit_call_stmt(&self, _id: stmt::StmtId, stmt: &stmt::Call) {
+        self.add_call(stmt.callee, &stmt.arguments);
+    }
+}
+
+/// Finds which parameters are `var` parameters, for every subprogram declaration and
+/// every variable or parameter with a subprogram type
+fn collect_var_params(hir_db: &db::HirDb, unit: &unit::Unit) -> HashMap<symbol::DefId, Vec<bool>> {
+    let collector = VarParamCollector {
+        hir_db,
+        var_params: RefCell::default(),
+    };
+
+    for stmt_id in &unit.stmts {
+        toc_hir::visitor::walk_stmt(hir_db.clone(), *stmt_id, &collector);
+    }
+
+    collector.var_params.into_inner()
+}
+
+struct VarParamCollector<'a> {
+    hir_db: &'a db::HirDb,
+    var_params: RefCell<HashMap<symbol::DefId, Vec<bool>>>,
+}
+
+impl VarParamCollector<'_> {
+    fn add_typed(&self, names: &[symbol::DefId], ty_id: ty::TypeId) {
+        if let ty::Type::Subprogram(ty) = self.hir_db.get_type(ty_id) {
+            let params = ty
+                .params
+                .iter()
+                .flatten()
+                .map(|param| param.is_var)
+                .collect::<Vec<_>>();
+
+            for name in names {
+                self.var_params.borrow_mut().insert(*name, params.clone());
+            }
+        }
+    }
+
+    fn add_params(&self, params: &[stmt::Param]) {
+        for param in params {
+            self.add_typed(&param.names, param.param_ty);
+        }
+    }
+}
+
+impl HirVisitor for VarParamCollector<'_> {
+    fn visit_constvar(&self, _id: stmt::StmtId, decl: &stmt::ConstVar) {
+        if let Some(ty_id) = decl.tail.type_spec() {
+            self.add_typed(&decl.names, ty_id);
+        }
+    }
+
+    fn visit_subprogram(&self, _id: stmt::StmtId, decl: &stmt::Subprogram) {
+        let params = decl.params.as_deref().unwrap_or_default();
+        let is_var = params
+            .iter()
+            .flat_map(|param| param.names.iter().map(move |_| param.is_var))
+            .collect();
+
+        self.var_params.borrow_mut().insert(decl.def_id, is_var);
+        self.add_params(params);
+    }
+
+    fn visit_process(&self, _id: stmt::StmtId, decl: &stmt::Process) {
+        self.add_params(&decl.params);
+    }
+}
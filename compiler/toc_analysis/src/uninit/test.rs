@@ -0,0 +1,172 @@
+//! Uninitialized variable use tests
+use toc_hir::db;
+
+fn uninit_messages(source: &str) -> Vec<String> {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+    let hir_db = hir_db.finish();
+
+    let unit = hir_db.get_unit(hir_res.id);
+    super::check_uninit_vars(hir_db.clone(), unit)
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect()
+}
+
+#[test]
+fn use_before_init() {
+    assert_eq!(
+        uninit_messages("var a : int\nput a"),
+        vec!["warn at 16..17: `a` may be used before being initialized\n| info for 4..5: `a` is declared here without an initial value"]
+    );
+}
+
+#[test]
+fn use_in_own_assignment() {
+    // Compound assignments read the variable first
+    assert_eq!(uninit_messages("var a : int\na += 1").len(), 1);
+    assert_eq!(uninit_messages("var a : int\na := a + 1").len(), 1);
+}
+
+#[test]
+fn use_inside_block() {
+    assert_eq!(uninit_messages("var a : int\nbegin put a end").len(), 1);
+    // Initialized inside of the block
+    assert!(uninit_messages("var a : int\nbegin a := 1 end\nput a").is_empty());
+}
+
+#[test]
+fn only_reported_once() {
+    assert_eq!(uninit_messages("var a : int\nput a\nput a\nput a").len(), 1);
+}
+
+#[test]
+fn no_use_after_diverging() {
+    assert!(uninit_messages("var a : int\nquit\nput a").is_empty());
+}
+
+#[test]
+fn initialized_vars() {
+    assert!(uninit_messages("var a : int := 1\nput a").is_empty());
+    assert!(uninit_messages("var a : int\na := 1\nput a").is_empty());
+    assert!(uninit_messages("var a : int\nget a\nput a").is_empty());
+    assert!(uninit_messages("const a : int := 1\nput a").is_empty());
+}
+
+#[test]
+fn aggregates_initialized_by_any_part() {
+    assert!(uninit_messages("var a : array 1 .. 2 of int\na(1) := 1\nput a(2)").is_empty());
+    assert!(uninit_messages("var a : record f : int end record\na.f := 1\nput a.f").is_empty());
+    // Indices are still reads
+    assert_eq!(
+        uninit_messages("var a : array 1 .. 2 of int\nvar i : int\na(i) := 1").len(),
+        1
+    );
+}
+
+#[test]
+fn pointers_initialized_by_new() {
+    assert!(uninit_messages("type t : int\nvar p : ^t\nnew p\nput p^").is_empty());
+}
+
+#[test]
+fn unions_initialized_by_tag() {
+    let source = "type u : union kind : 1 .. 2 of label 1: a : int label 2: b : int end union\n";
+    assert!(uninit_messages(&format!("{}var v : u\ntag v, 2\nput v.b", source)).is_empty());
+    // The tag value is still read
+    assert_eq!(
+        uninit_messages(&format!("{}var v : u\nvar k : int\ntag v, k", source)).len(),
+        1
+    );
+}
+
+#[test]
+fn init_in_only_one_if_branch() {
+    assert_eq!(
+        uninit_messages("var a : int\nif true then a := 1 end if\nput a"),
+        vec!["warn at 43..44: `a` may be used before being initialized\n| info for 4..5: `a` is declared here without an initial value"]
+    );
+    assert_eq!(
+        uninit_messages("var a : int\nif true then a := 1 elsif false then a := 2 end if\nput a")
+            .len(),
+        1
+    );
+    // Initialized in every branch
+    assert!(
+        uninit_messages("var a : int\nif true then a := 1 else a := 2 end if\nput a").is_empty()
+    );
+    // Branches that never finish don't need to initialize anything
+    assert!(uninit_messages("var a : int\nif true then a := 1 else quit end if\nput a").is_empty());
+}
+
+#[test]
+fn init_in_case_arms() {
+    assert_eq!(
+        uninit_messages("var a : int\ncase 1 of label 1 : a := 1 label : end case\nput a").len(),
+        1
+    );
+    // Without a default arm, unmatched values skip every arm
+    assert_eq!(
+        uninit_messages("var a : int\ncase 1 of label 1 : a := 1 end case\nput a").len(),
+        1
+    );
+    assert!(uninit_messages(
+        "var a : int\ncase 1 of label 1 : a := 1 label : a := 2 end case\nput a"
+    )
+    .is_empty());
+}
+
+#[test]
+fn init_in_loops() {
+    // Read before the first write
+    assert_eq!(
+        uninit_messages("var a : int\nloop put a\na := 1 end loop").len(),
+        1
+    );
+    // Can exit before the write
+    assert_eq!(
+        uninit_messages("var a : int\nloop exit when true\na := 1 end loop\nput a").len(),
+        1
+    );
+    assert!(uninit_messages("var a : int\nloop a := 1\nexit end loop\nput a").is_empty());
+    // Conditions aren't evaluated, so the first time around the loop could read `a`
+    assert_eq!(
+        uninit_messages(
+            "var a : int\nvar b := false\nloop if b then put a end if\na := 1\nb := true end loop"
+        )
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn init_via_var_param_call() {
+    assert!(
+        uninit_messages("procedure p (var a : int) end p\nvar n : int\np(n)\nput n").is_empty()
+    );
+    assert!(uninit_messages(
+        "function f (var a : int) : int result 1 end f\nvar n : int\nvar r := f(n)\nput n"
+    )
+    .is_empty());
+    // Through subprogram parameters
+    assert!(uninit_messages(
+        "procedure q (p : procedure (var a : int))\nvar n : int\np(n)\nput n\nend q"
+    )
+    .is_empty());
+    // Non-`var` parameters only read the argument
+    assert_eq!(
+        uninit_messages("procedure p (a : int) end p\nvar n : int\np(n)").len(),
+        1
+    );
+}
+
+#[test]
+fn use_in_subprogram_body() {
+    assert_eq!(
+        uninit_messages("procedure p\nvar a : int\nput a\nend p").len(),
+        1
+    );
+    // Outer variables could've been initialized before the call
+    assert!(uninit_messages("var a : int\nprocedure p\nput a\nend p\na := 1\np").is_empty());
+}
@@ -0,0 +1,120 @@
+//! Unused declaration detection
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use toc_hir::{db, expr, stmt, symbol, unit, visitor::HirVisitor};
+use toc_reporting::{MessageSink, ReportMessage, Stage};
+
+/// Reports local `var` and `const` declarations that are never read from.
+///
+/// Exported names and names starting with `_` are exempt from this check.
+pub fn check_unused_vars(hir_db: db::HirDb, unit: &unit::Unit) -> Vec<ReportMessage> {
+    // TODO: Exempt parameters once they are lowered
+    // TODO: Count reads through `var` parameters and address-of (`addr`) as uses
+    let visitor = UsageVisitor {
+        hir_db: hir_db.clone(),
+        usages: Default::default(),
+    };
+    unit.walk_nodes(hir_db, &visitor);
+
+    let Usages {
+        decls,
+        names,
+        writes,
+    } = visitor.usages.into_inner();
+
+    // Only names in a non-write position count as reads
+    let read_defs = names
+        .into_iter()
+        .filter(|(expr_id, _)| !writes.contains(expr_id))
+        .map(|(_, def_id)| def_id)
+        .collect::<HashSet<_>>();
+    let exported_defs = unit
+        .exports
+        .iter()
+        .filter(|export| export.def_id.unit_id() == unit.id)
+        .map(|export| export.def_id.as_local())
+        .collect::<HashSet<_>>();
+
+    let mut reporter = MessageSink::new(Stage::Analyze);
+
+    for (def_id, is_const) in decls {
+        let name = &unit.symbol_table.get_symbol(def_id).name;
+
+        if read_defs.contains(&def_id) || exported_defs.contains(&def_id) || name.starts_with('_') {
+            continue;
+        }
+
+        let kind = if is_const { "constant" } else { "variable" };
+        let span = unit.symbol_table.get_def_span(def_id);
+
+        reporter
+            .warn_detailed(&format!("unused {} `{}`", kind, name), span)
+            .with_info(
+                &format!("`{}` is never read, prefix it with `_` to allow this", name),
+                None,
+            )
+            .finish();
+    }
+
+    reporter.finish()
+}
+
+#[derive(Default)]
+struct Usages {
+    /// Declared `var` and `const` names, and if they are a `const`
+    decls: IndexMap<symbol::DefId, bool>,
+    /// All name expressions, and the symbol that they refer to
+    names: Vec<(expr::ExprId, symbol::DefId)>,
+    /// Name expressions that are only written to
+    writes: HashSet<expr::ExprId>,
+}
+
+struct UsageVisitor {
+    hir_db: db::HirDb,
+    usages: RefCell<Usages>,
+}
+
+impl UsageVisitor {
+    /// Marks the expression as a write if it's a bare name
+    fn mark_write(&self, id: expr::ExprId) {
+        if let expr::Expr::Name(expr::Name::Name(_)) = self.hir_db.get_expr(id) {
+            self.usages.borrow_mut().writes.insert(id);
+        }
+    }
+}
+
+impl HirVisitor for UsageVisitor {
+    fn visit_constvar(&self, _id: stmt::StmtId, decl: &stmt::ConstVar) {
+        let mut usages = self.usages.borrow_mut();
+
+        for def_id in &decl.names {
+            usages.decls.insert(*def_id, decl.is_const);
+        }
+    }
+
+    fn visit_assign(&self, _id: stmt::StmtId, stmt: &stmt::Assign) {
+        // Compound assignments also read from the left hand side
+        if *stmt.op.item() == stmt::AssignOp::None {
+            self.mark_write(stmt.lhs);
+        }
+    }
+
+    fn visit_get(&self, _id: stmt::StmtId, stmt: &stmt::Get) {
+        for item in &stmt.items {
+            if let stmt::Skippable::Item(item) = item {
+                self.mark_write(item.expr);
+            }
+        }
+    }
+
+    fn visit_name(&self, id: expr::ExprId, expr: &expr::Name) {
+        if let expr::Name::Name(use_id) = expr {
+            self.usages.borrow_mut().names.push((id, use_id.as_def()));
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Unused declaration tests
+use toc_hir::db;
+
+fn unused_messages(source: &str) -> Vec<String> {
+    let parsed = toc_parser::parse(None, source);
+    let hir_db = db::HirBuilder::new();
+    let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), None, parsed.syntax());
+    let hir_db = hir_db.finish();
+
+    let unit = hir_db.get_unit(hir_res.id);
+    super::check_unused_vars(hir_db.clone(), unit)
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect()
+}
+
+#[test]
+fn unused_local() {
+    assert_eq!(
+        unused_messages("var a : int"),
+        vec!["warn at 4..5: unused variable `a`\n| info: `a` is never read, prefix it with `_` to allow this"]
+    );
+    assert_eq!(
+        unused_messages("const c := 1"),
+        vec!["warn at 6..7: unused constant `c`\n| info: `c` is never read, prefix it with `_` to allow this"]
+    );
+}
+
+#[test]
+fn used_local() {
+    assert!(unused_messages("var a : int\nput a").is_empty());
+    assert!(unused_messages("const c := 1\nvar a : int := c\nput a").is_empty());
+    // Compound assignments also read
+    assert!(unused_messages("var a : int\na += 1").is_empty());
+    // Used in an inner scope
+    assert!(unused_messages("var a : int\nbegin put a end").is_empty());
+}
+
+#[test]
+fn underscore_unused_local() {
+    assert!(unused_messages("var _a : int").is_empty());
+    assert!(unused_messages("var _ : int").is_empty());
+}
+
+#[test]
+fn write_only_local() {
+    // Only writing to a variable doesn't count as a use
+    assert_eq!(unused_messages("var a : int\na := 1").len(), 1);
+    assert_eq!(unused_messages("var a : int\nget a").len(), 1);
+}
+
+#[test]
+fn unused_shadowed_local() {
+    // The outer `a` is never read
+    assert_eq!(
+        unused_messages("var a : int\nbegin var a : int put a end"),
+        vec!["warn at 4..5: unused variable `a`\n| info: `a` is never read, prefix it with `_` to allow this"]
+    );
+}
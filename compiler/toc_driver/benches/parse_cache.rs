@@ -0,0 +1,64 @@
+//! Compares parsing files from scratch against loading them from a warm parse cache
+//!
+//! Run with `cargo bench -p toc_driver --bench parse_cache`
+use std::time::{Duration, Instant};
+
+use toc_driver::cache::ParseCache;
+use toc_span::FileId;
+use toc_vfs::FileDb;
+
+const FILE_COUNT: usize = 20;
+const DECLS_PER_FILE: usize = 2000;
+const RUNS: usize = 5;
+
+fn synthetic_source(file: usize) -> String {
+    let mut source = String::new();
+
+    for decl in 0..DECLS_PER_FILE {
+        source.push_str(&format!(
+            "var v{0}_{1} : int := ({1} + {0}) * 2 - {1} div 3\nput v{0}_{1}, \"text\" ..\n",
+            file, decl
+        ));
+    }
+
+    source
+}
+
+fn best_time(file_db: &FileDb, files: &[FileId], cache: Option<&ParseCache>) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            let parsed = toc_driver::parse_files(file_db, files, false, cache);
+            let elapsed = start.elapsed();
+
+            assert_eq!(parsed.len(), files.len());
+            elapsed
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let file_db = FileDb::new();
+    let files = (0..FILE_COUNT)
+        .map(|file| file_db.add_file(&format!("file{}.t", file), &synthetic_source(file)))
+        .collect::<Vec<_>>();
+
+    let cache_dir = std::env::temp_dir().join(format!("toc_bench_cache_{}", std::process::id()));
+    let cache = ParseCache::new(&cache_dir);
+
+    let uncached = best_time(&file_db, &files, None);
+    // Fill up the cache before timing the cached runs
+    toc_driver::parse_files(&file_db, &files, false, Some(&cache));
+    let cached = best_time(&file_db, &files, Some(&cache));
+
+    std::fs::remove_dir_all(&cache_dir).expect("failed to remove the cache directory");
+
+    println!("parsing {} files (best of {} runs)", FILE_COUNT, RUNS);
+    println!("uncached:   {:?}", uncached);
+    println!("warm cache: {:?}", cached);
+    println!(
+        "speedup:    {:.2}x",
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
@@ -0,0 +1,56 @@
+//! Compares sequential and parallel parsing of many root files
+//!
+//! Run with `cargo bench -p toc_driver`
+use std::time::{Duration, Instant};
+
+use toc_span::FileId;
+use toc_vfs::FileDb;
+
+const FILE_COUNT: usize = 50;
+const DECLS_PER_FILE: usize = 2000;
+const RUNS: usize = 5;
+
+fn synthetic_source(file: usize) -> String {
+    let mut source = String::new();
+
+    for decl in 0..DECLS_PER_FILE {
+        source.push_str(&format!(
+            "var v{0}_{1} : int := ({1} + {0}) * 2 - {1} div 3\nput v{0}_{1}, \"text\" ..\n",
+            file, decl
+        ));
+    }
+
+    source
+}
+
+fn best_time(file_db: &FileDb, files: &[FileId], parallel: bool) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            let parsed = toc_driver::parse_files(file_db, files, parallel, None);
+            let elapsed = start.elapsed();
+
+            assert_eq!(parsed.len(), files.len());
+            elapsed
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let file_db = FileDb::new();
+    let files = (0..FILE_COUNT)
+        .map(|file| file_db.add_file(&format!("file{}.t", file), &synthetic_source(file)))
+        .collect::<Vec<_>>();
+
+    let sequential = best_time(&file_db, &files, false);
+    let parallel = best_time(&file_db, &files, true);
+
+    println!("parsing {} files (best of {} runs)", FILE_COUNT, RUNS);
+    println!("sequential: {:?}", sequential);
+    println!("parallel:   {:?}", parallel);
+    println!(
+        "speedup:    {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
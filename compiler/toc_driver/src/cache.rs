@@ -0,0 +1,553 @@
+//! On-disk cache of parsed files
+//!
+//! Each entry holds the green tree and parser messages of a source file, keyed
+//! by a hash of the source text. Entries are written in a small hand-rolled
+//! binary format:
+//!
+//! ```text
+//! header:   magic, format version, parser version, source hash, source length
+//! tree:     events (start node / token / finish node), ending with an end marker.
+//!           Tokens only store their length, since their text comes from the source.
+//! messages: message count, then each message
+//! ```
+//!
+//! All integers are little-endian, and strings are prefixed with their length.
+//! Loading an entry never panics: any entry that fails to decode, or whose
+//! tokens don't cover the source text, is treated as a cache miss.
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+
+use num_traits::FromPrimitive;
+use rowan::{GreenNodeBuilder, NodeOrToken};
+use toc_reporting::{AnnotateKind, MessageBuilder, MessageSink, Stage};
+use toc_span::{FileId, Span, TextRange, TextSize};
+use toc_syntax::SyntaxKind;
+
+/// Identifies cache entries, so that other files aren't mistaken for them
+const MAGIC: &[u8; 8] = b"TOCPARSE";
+
+/// Version of the entry format.
+/// Must be bumped whenever the format changes, so that old entries are ignored.
+const FORMAT_VERSION: u32 = 1;
+
+// Tree event tags
+const TAG_START_NODE: u8 = 0;
+const TAG_TOKEN: u8 = 1;
+const TAG_FINISH_NODE: u8 = 2;
+const TAG_END: u8 = 3;
+
+/// Cache of parsed files, stored inside of a directory
+#[derive(Debug, Clone)]
+pub struct ParseCache {
+    dir: PathBuf,
+    /// Entries are only used by the same parser version that stored them
+    parser_version: String,
+}
+
+impl ParseCache {
+    /// Creates a cache storing entries in `dir`.
+    /// The directory is created when the first entry is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            parser_version: parser_version(),
+        }
+    }
+
+    /// Pretends that entries are stored by a different parser version
+    #[cfg(test)]
+    fn with_parser_version(mut self, parser_version: &str) -> Self {
+        self.parser_version = parser_version.to_string();
+        self
+    }
+
+    /// Path of the entry for `source`
+    pub fn entry_path(&self, source: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.parse", content_hash(source)))
+    }
+
+    /// Loads the cached parse of `source`, with all spans pointing into `file`.
+    ///
+    /// Gives back `None` if there is no usable entry for `source`.
+    pub fn load(&self, file: Option<FileId>, source: &str) -> Option<toc_parser::ParseResult> {
+        let bytes = std::fs::read(self.entry_path(source)).ok()?;
+        decode_entry(&bytes, &self.parser_version, file, source)
+    }
+
+    /// Stores the parse of `source` into the cache
+    pub fn store(&self, source: &str, parsed: &toc_parser::ParseResult) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let bytes = encode_entry(&self.parser_version, source, parsed);
+        write_atomic(&self.entry_path(source), &bytes)
+    }
+}
+
+/// Writes to a temporary file first, so that concurrent readers never see
+/// a partially written entry
+fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let temp_path = path.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, path)
+}
+
+/// Version of the parser that produced an entry.
+///
+/// Includes a hash of all of the `SyntaxKind` names in order, since kinds are
+/// stored by number, and renumbering them would decode into the wrong kinds.
+fn parser_version() -> String {
+    let kinds = (0..)
+        .map_while(SyntaxKind::from_u16)
+        .map(|kind| format!("{:?};", kind))
+        .collect::<String>();
+
+    format!(
+        "{}-{:016x}",
+        toc_parser::PARSER_VERSION,
+        content_hash(&kinds)
+    )
+}
+
+/// 64-bit FNV-1a hash of the source text.
+///
+/// `std`'s hashers aren't guaranteed to be stable between releases, so they
+/// can't be used for keys that persist across runs.
+fn content_hash(source: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    source.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+fn encode_entry(parser_version: &str, source: &str, parsed: &toc_parser::ParseResult) -> Vec<u8> {
+    let mut out = Encoder::default();
+
+    out.bytes(MAGIC);
+    out.u32(FORMAT_VERSION);
+    out.str(parser_version);
+    out.u64(content_hash(source));
+    out.u64(source.len() as u64);
+
+    // Tree, as a flat list of events so that decoding doesn't need to recurse
+    for event in parsed.syntax().preorder_with_tokens() {
+        match event {
+            rowan::WalkEvent::Enter(NodeOrToken::Node(node)) => {
+                out.u8(TAG_START_NODE);
+                out.u16(rowan::SyntaxKind::from(node.kind()).0);
+            }
+            rowan::WalkEvent::Enter(NodeOrToken::Token(token)) => {
+                out.u8(TAG_TOKEN);
+                out.u16(rowan::SyntaxKind::from(token.kind()).0);
+                out.u32(token.text().len() as u32);
+            }
+            rowan::WalkEvent::Leave(NodeOrToken::Node(_)) => out.u8(TAG_FINISH_NODE),
+            rowan::WalkEvent::Leave(NodeOrToken::Token(_)) => {}
+        }
+    }
+    out.u8(TAG_END);
+
+    out.u32(parsed.messages().len() as u32);
+    for msg in parsed.messages() {
+        out.u8(stage_to_raw(msg.stage()));
+        out.u8(kind_to_raw(msg.kind()));
+        out.str(msg.message());
+        out.span(msg.span());
+
+        out.u32(msg.annotations().len() as u32);
+        for annotation in msg.annotations() {
+            out.u8(kind_to_raw(annotation.kind()));
+            out.str(annotation.message());
+            out.span(annotation.span());
+        }
+
+        out.u32(msg.footer().len() as u32);
+        for annotation in msg.footer() {
+            out.u8(kind_to_raw(annotation.kind()));
+            out.str(annotation.message());
+        }
+    }
+
+    out.0
+}
+
+fn decode_entry(
+    bytes: &[u8],
+    parser_version: &str,
+    file: Option<FileId>,
+    source: &str,
+) -> Option<toc_parser::ParseResult> {
+    let mut input = Decoder(bytes);
+
+    // Entries from other formats or parsers, or for other sources, are never used
+    let is_current = input.bytes(MAGIC.len())? == MAGIC
+        && input.u32()? == FORMAT_VERSION
+        && input.str()? == parser_version
+        && input.u64()? == content_hash(source)
+        && input.u64()? == source.len() as u64;
+
+    if !is_current {
+        return None;
+    }
+
+    // Tree
+    let mut builder = GreenNodeBuilder::new();
+    let mut depth = 0_usize;
+    let mut has_root = false;
+    let mut text_offset = 0_usize;
+
+    loop {
+        match input.u8()? {
+            TAG_START_NODE => {
+                // Only one root node is allowed
+                if depth == 0 && has_root {
+                    return None;
+                }
+
+                builder.start_node(input.kind()?);
+                depth += 1;
+                has_root = true;
+            }
+            TAG_TOKEN => {
+                // Tokens must be inside of a node
+                if depth == 0 {
+                    return None;
+                }
+
+                // Token text must line up with the source, which also catches
+                // any corruption that would split a char in half
+                let kind = input.kind()?;
+                let len = usize::try_from(input.u32()?).ok()?;
+                let text = source.get(text_offset..text_offset.checked_add(len)?)?;
+
+                builder.token(kind, text);
+                text_offset += len;
+            }
+            TAG_FINISH_NODE => {
+                depth = depth.checked_sub(1)?;
+                builder.finish_node();
+            }
+            TAG_END if depth == 0 && has_root => break,
+            _ => return None,
+        }
+    }
+
+    // The tree must be lossless, so anything else is a mismatched or corrupted entry
+    if text_offset != source.len() {
+        return None;
+    }
+
+    let node = builder.finish();
+
+    // Messages
+    let msg_count = input.u32()?;
+    let mut messages = vec![];
+
+    for _ in 0..msg_count {
+        let stage = stage_from_raw(input.u8()?)?;
+        let kind = annotate_kind_from_raw(input.u8()?)?;
+        let message = input.str()?;
+        let span = input.span(file, source)?;
+
+        // Fully decode the annotations before building the message, since
+        // builders can't be dropped without being finished
+        let mut annotations = vec![];
+
+        for _ in 0..input.u32()? {
+            let kind = annotate_kind_from_raw(input.u8()?)?;
+            let message = input.str()?;
+            annotations.push((kind, message, Some(input.span(file, source)?)));
+        }
+
+        for _ in 0..input.u32()? {
+            let kind = annotate_kind_from_raw(input.u8()?)?;
+            annotations.push((kind, input.str()?, None));
+        }
+
+        // Only notes and infos can be attached to messages
+        if annotations
+            .iter()
+            .any(|(kind, ..)| !matches!(kind, AnnotateKind::Note | AnnotateKind::Info))
+        {
+            return None;
+        }
+
+        let mut sink = MessageSink::new(stage);
+        let mut builder = MessageBuilder::new(&mut sink, kind, message, span);
+
+        for (kind, message, span) in annotations {
+            builder = match kind {
+                AnnotateKind::Note => builder.with_note(message, span),
+                _ => builder.with_info(message, span),
+            };
+        }
+
+        builder.finish();
+        messages.extend(sink.finish());
+    }
+
+    // Trailing data means that the entry wasn't written by this format
+    if !input.0.is_empty() {
+        return None;
+    }
+
+    Some(toc_parser::ParseResult::new(node, messages))
+}
+
+fn stage_to_raw(stage: Stage) -> u8 {
+    match stage {
+        Stage::Parse => 0,
+        Stage::Validate => 1,
+        Stage::LowerHir => 2,
+        Stage::Analyze => 3,
+    }
+}
+
+fn stage_from_raw(raw: u8) -> Option<Stage> {
+    Some(match raw {
+        0 => Stage::Parse,
+        1 => Stage::Validate,
+        2 => Stage::LowerHir,
+        3 => Stage::Analyze,
+        _ => return None,
+    })
+}
+
+fn kind_to_raw(kind: AnnotateKind) -> u8 {
+    match kind {
+        AnnotateKind::Note => 0,
+        AnnotateKind::Info => 1,
+        AnnotateKind::Warning => 2,
+        AnnotateKind::Error => 3,
+    }
+}
+
+fn annotate_kind_from_raw(raw: u8) -> Option<AnnotateKind> {
+    Some(match raw {
+        0 => AnnotateKind::Note,
+        1 => AnnotateKind::Info,
+        2 => AnnotateKind::Warning,
+        3 => AnnotateKind::Error,
+        _ => return None,
+    })
+}
+
+#[derive(Default)]
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn str(&mut self, text: &str) {
+        self.u32(text.len() as u32);
+        self.bytes(text.as_bytes());
+    }
+
+    /// Spans only record if they have a file, since cached parses only have
+    /// spans pointing into their own file
+    fn span(&mut self, span: Span) {
+        self.u8(span.file.is_some() as u8);
+        self.u32(span.range.start().into());
+        self.u32(span.range.end().into());
+    }
+}
+
+struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        <[u8; N]>::try_from(self.bytes(N)?).ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn str(&mut self) -> Option<&'a str> {
+        let len = usize::try_from(self.u32()?).ok()?;
+        std::str::from_utf8(self.bytes(len)?).ok()
+    }
+
+    fn kind(&mut self) -> Option<rowan::SyntaxKind> {
+        // Unknown kinds would panic when converted back into a `SyntaxKind`
+        SyntaxKind::from_u16(self.u16()?).map(rowan::SyntaxKind::from)
+    }
+
+    /// Decodes a span, which must lie within `source` so that reporting
+    /// the message doesn't go out of bounds
+    fn span(&mut self, file: Option<FileId>, source: &str) -> Option<Span> {
+        let file = match self.u8()? {
+            0 => None,
+            1 => file,
+            _ => return None,
+        };
+        let start = TextSize::from(self.u32()?);
+        let end = TextSize::from(self.u32()?);
+
+        if start > end || end > TextSize::of(source) {
+            return None;
+        }
+
+        if !source.is_char_boundary(start.into()) || !source.is_char_boundary(end.into()) {
+            return None;
+        }
+
+        Some(Span::new(file, TextRange::new(start, end)))
+    }
+}
+
+#[cfg(test)]
+fn temp_cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("toc_parse_cache_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn cache_round_trip() {
+    let dir = temp_cache_dir("round_trip");
+    let cache = ParseCache::new(&dir);
+    let file = FileId::new(std::num::NonZeroU32::new(1).unwrap());
+    let source = "var a : int := \nput \"ü\" ..\n";
+
+    let parsed = toc_parser::parse(Some(file), source);
+    assert!(!parsed.messages().is_empty());
+    assert!(cache.load(Some(file), source).is_none());
+
+    cache.store(source, &parsed).unwrap();
+    let loaded = cache.load(Some(file), source).unwrap();
+
+    assert_eq!(loaded.dump_tree(), parsed.dump_tree());
+    assert_eq!(
+        loaded
+            .messages()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        parsed
+            .messages()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+
+    // Other sources don't share the entry
+    assert!(cache.load(Some(file), "var a := 1").is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cache_other_parser_version() {
+    let dir = temp_cache_dir("parser_version");
+    let source = "var a := 1\nput a\n";
+    let parsed = toc_parser::parse(None, source);
+
+    let cache = ParseCache::new(&dir).with_parser_version("old");
+    cache.store(source, &parsed).unwrap();
+    assert!(cache.load(None, source).is_some());
+
+    // Entries from other parser versions are never used
+    let cache = ParseCache::new(&dir).with_parser_version("new");
+    assert!(cache.load(None, source).is_none());
+    assert!(ParseCache::new(&dir).load(None, source).is_none());
+
+    // The current version is owned by the parser
+    assert!(parser_version().starts_with(&format!("{}-", toc_parser::PARSER_VERSION)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cache_corrupted_entry() {
+    let dir = temp_cache_dir("corrupted");
+    let cache = ParseCache::new(&dir);
+    let source = "var a := 1\nput a\n";
+    let parsed = toc_parser::parse(None, source);
+
+    cache.store(source, &parsed).unwrap();
+    let entry_path = cache.entry_path(source);
+    let entry = std::fs::read(&entry_path).unwrap();
+
+    // Every truncation and single byte corruption of the entry must be rejected
+    // or still give back the same tree, and never panic
+    for len in 0..entry.len() {
+        std::fs::write(&entry_path, &entry[..len]).unwrap();
+        assert!(cache.load(None, source).is_none());
+    }
+
+    for idx in 0..entry.len() {
+        let mut corrupted = entry.clone();
+        corrupted[idx] ^= 0xA5;
+        std::fs::write(&entry_path, &corrupted).unwrap();
+
+        if let Some(loaded) = cache.load(None, source) {
+            assert_eq!(loaded.syntax().text(), source);
+        }
+    }
+
+    // Spans outside of the source or splitting a character are rejected,
+    // as well as truncated spans
+    let mut spans = Encoder::default();
+    spans.span(Span::new(None, TextRange::new(0.into(), 100.into())));
+    spans.span(Span::new(None, TextRange::new(0.into(), 1.into())));
+    spans.u8(0);
+    let mut input = Decoder(&spans.0);
+    assert!(input.span(None, source).is_none());
+    assert!(input.span(None, "ü").is_none());
+    assert!(input.span(None, source).is_none());
+
+    // Falls back to parsing through the driver
+    std::fs::write(&entry_path, b"not a cache entry").unwrap();
+    let file_db = toc_vfs::FileDb::new();
+    let root = file_db.add_file("a.t", source);
+    let parsed_file = crate::parse_file(&file_db, root, Some(&cache));
+    assert_eq!(parsed_file.parsed.dump_tree(), parsed.dump_tree());
+
+    // ... which replaces the bad entry
+    assert!(cache.load(None, source).is_some());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,293 @@
+//! Language features supported by this build of the compiler, for use by tooling
+//!
+//! [`FEATURES`] is the only place where support is recorded, and should be
+//! updated alongside `notes/TODO.md` whenever a feature moves through the
+//! compiler.
+
+/// Which group of syntax nodes a feature belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    Stmt,
+    Expr,
+    Type,
+}
+
+impl Family {
+    pub fn name(self) -> &'static str {
+        match self {
+            Family::Stmt => "stmt",
+            Family::Expr => "expr",
+            Family::Type => "type",
+        }
+    }
+}
+
+/// How far through the compiler a feature is supported.
+///
+/// Each level also implies all of the levels before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Support {
+    /// Parsed into a syntax tree, but reported as unsupported during lowering
+    Parsed,
+    /// Lowered into HIR, but not checked during analysis
+    Lowered,
+    /// Lowered into HIR and checked during analysis
+    Analyzed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Feature {
+    pub family: Family,
+    pub name: &'static str,
+    pub support: Support,
+}
+
+const fn feature(family: Family, name: &'static str, support: Support) -> Feature {
+    Feature {
+        family,
+        name,
+        support,
+    }
+}
+
+/// Every statement, expression, and type in the grammar, and how far each one is supported
+pub const FEATURES: &[Feature] = {
+    use Family::*;
+    use Support::*;
+
+    &[
+        feature(Stmt, "const_var", Analyzed),
+        feature(Stmt, "type", Analyzed),
+        feature(Stmt, "bind", Analyzed),
+        feature(Stmt, "procedure", Analyzed),
+        feature(Stmt, "function", Analyzed),
+        feature(Stmt, "process", Analyzed),
+        feature(Stmt, "external", Parsed),
+        feature(Stmt, "forward", Analyzed),
+        feature(Stmt, "deferred", Parsed),
+        feature(Stmt, "body", Analyzed),
+        feature(Stmt, "module", Analyzed),
+        feature(Stmt, "class", Parsed),
+        feature(Stmt, "monitor", Parsed),
+        feature(Stmt, "assign", Analyzed),
+        feature(Stmt, "open", Parsed),
+        feature(Stmt, "close", Parsed),
+        feature(Stmt, "put", Analyzed),
+        feature(Stmt, "get", Analyzed),
+        feature(Stmt, "read", Parsed),
+        feature(Stmt, "write", Parsed),
+        feature(Stmt, "seek", Parsed),
+        feature(Stmt, "tell", Parsed),
+        feature(Stmt, "for", Analyzed),
+        feature(Stmt, "loop", Analyzed),
+        feature(Stmt, "exit", Analyzed),
+        feature(Stmt, "if", Analyzed),
+        feature(Stmt, "case", Analyzed),
+        feature(Stmt, "block", Analyzed),
+        feature(Stmt, "invariant", Parsed),
+        feature(Stmt, "assert", Parsed),
+        feature(Stmt, "call", Analyzed),
+        feature(Stmt, "return", Analyzed),
+        feature(Stmt, "result", Analyzed),
+        feature(Stmt, "new", Analyzed),
+        feature(Stmt, "free", Analyzed),
+        feature(Stmt, "tag", Analyzed),
+        feature(Stmt, "fork", Analyzed),
+        feature(Stmt, "signal", Parsed),
+        feature(Stmt, "pause", Parsed),
+        feature(Stmt, "quit", Analyzed),
+        feature(Stmt, "break", Parsed),
+        feature(Stmt, "checkedness", Analyzed),
+        feature(Stmt, "pre", Parsed),
+        feature(Stmt, "init", Parsed),
+        feature(Stmt, "post", Parsed),
+        feature(Stmt, "handler", Parsed),
+        feature(Stmt, "inherit", Parsed),
+        feature(Stmt, "implement", Parsed),
+        feature(Stmt, "implement_by", Parsed),
+        feature(Stmt, "import", Parsed),
+        feature(Stmt, "export", Parsed),
+        feature(Stmt, "preprocessor", Parsed),
+        feature(Expr, "literal", Analyzed),
+        feature(Expr, "objectclass", Parsed),
+        feature(Expr, "init", Analyzed),
+        feature(Expr, "nil", Analyzed),
+        feature(Expr, "sizeof", Analyzed),
+        feature(Expr, "binary", Analyzed),
+        feature(Expr, "unary", Analyzed),
+        feature(Expr, "paren", Analyzed),
+        feature(Expr, "name", Analyzed),
+        feature(Expr, "self", Parsed),
+        feature(Expr, "field", Analyzed),
+        feature(Expr, "deref", Analyzed),
+        feature(Expr, "cheat", Parsed),
+        feature(Expr, "nat_cheat", Analyzed),
+        feature(Expr, "arrow", Parsed),
+        feature(Expr, "indirect", Analyzed),
+        feature(Expr, "bits", Analyzed),
+        feature(Expr, "call", Analyzed),
+        feature(Type, "primitive", Analyzed),
+        feature(Type, "name", Analyzed),
+        feature(Type, "range", Analyzed),
+        feature(Type, "enum", Analyzed),
+        feature(Type, "array", Analyzed),
+        feature(Type, "set", Analyzed),
+        feature(Type, "record", Analyzed),
+        feature(Type, "union", Analyzed),
+        feature(Type, "pointer", Analyzed),
+        feature(Type, "function", Analyzed),
+        feature(Type, "procedure", Analyzed),
+        feature(Type, "collection", Analyzed),
+        feature(Type, "condition", Analyzed),
+    ]
+};
+
+/// Kinds of output accepted by the `--dump` flag
+pub const DUMP_KINDS: &[&str] = &["callgraph", "liveness"];
+
+/// What this build of the compiler supports
+#[derive(Debug, Clone, Copy)]
+pub struct Capabilities {
+    pub version: &'static str,
+    pub features: &'static [Feature],
+    pub dump_kinds: &'static [&'static str],
+}
+
+impl Capabilities {
+    /// Looks up how far a feature is supported, or `None` if there's no such feature
+    pub fn support(&self, family: Family, name: &str) -> Option<Support> {
+        self.features
+            .iter()
+            .find(|feature| feature.family == family && feature.name == name)
+            .map(|feature| feature.support)
+    }
+
+    /// Formats the capabilities as a JSON object
+    pub fn to_json(&self) -> String {
+        use serde_json::json;
+
+        let features = self
+            .features
+            .iter()
+            .map(|feature| {
+                json!({
+                    "family": feature.family.name(),
+                    "name": feature.name,
+                    "parsed": feature.support >= Support::Parsed,
+                    "lowered": feature.support >= Support::Lowered,
+                    "analyzed": feature.support >= Support::Analyzed,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "version": self.version,
+            "features": features,
+            "dump_kinds": self.dump_kinds,
+        })
+        .to_string()
+    }
+}
+
+/// Gets the capabilities of this build of the compiler
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES,
+        dump_kinds: DUMP_KINDS,
+    }
+}
+
+#[cfg(test)]
+fn check_support(family: Family, name: &str, source: &str) {
+    let support = capabilities()
+        .support(family, name)
+        .unwrap_or_else(|| panic!("no feature `{}` for {}", name, family.name()));
+
+    let parsed = toc_parser::parse(None, source);
+    let parse_errors = parsed
+        .messages()
+        .iter()
+        .filter(|msg| msg.kind() == toc_reporting::AnnotateKind::Error)
+        .map(|msg| msg.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        parse_errors,
+        Vec::<String>::new(),
+        "`{}` failed to parse",
+        source
+    );
+
+    let hir_db = toc_hir::db::HirBuilder::new();
+    let lowered = toc_hir_lowering::lower_ast(hir_db, None, parsed.syntax());
+    let is_lowered = !lowered
+        .messages()
+        .iter()
+        .any(|msg| msg.message().starts_with("unsupported"));
+    assert_eq!(
+        is_lowered,
+        support >= Support::Lowered,
+        "`{}` is {:?}, but lowering disagrees",
+        source,
+        support
+    );
+}
+
+#[test]
+fn support_matches_stmts() {
+    check_support(Family::Stmt, "const_var", "var a : int");
+    check_support(Family::Stmt, "put", "put 1");
+    check_support(Family::Stmt, "quit", "quit");
+    check_support(Family::Stmt, "if", "if true then end if");
+    check_support(Family::Stmt, "loop", "loop exit end loop");
+    check_support(Family::Stmt, "case", "case 1 of label 1 : label : end case");
+    check_support(Family::Stmt, "result", "function f : int result 1 end f");
+    check_support(Family::Stmt, "for", "for i : 1 .. 2 end for");
+    check_support(Family::Stmt, "procedure", "procedure p end p");
+    check_support(Family::Stmt, "function", "function f : int end f");
+    check_support(Family::Stmt, "module", "module m end m");
+}
+
+#[test]
+fn support_matches_exprs() {
+    check_support(Family::Expr, "sizeof", "var i : int\nvar a := sizeof(i)");
+    check_support(Family::Expr, "deref", "var p : ^int\nvar a := ^p");
+    check_support(Family::Expr, "nil", "var p : ^int := nil");
+    check_support(Family::Expr, "self", "var a := self");
+    check_support(Family::Expr, "cheat", "var a := cheat(int, 1)");
+}
+
+#[test]
+fn support_matches_types() {
+    check_support(Family::Type, "record", "type t : record a : int end record");
+    check_support(Family::Type, "array", "var a : array 1 .. 2 of int");
+    check_support(Family::Type, "pointer", "var p : ^int");
+    check_support(Family::Type, "enum", "type t : enum(a, b)");
+    check_support(Family::Type, "set", "type t : set of boolean");
+    check_support(
+        Family::Type,
+        "function",
+        "type t : function (a : int) : int",
+    );
+    check_support(Family::Type, "procedure", "type t : procedure");
+}
+
+#[test]
+fn capabilities_as_json() {
+    let json: serde_json::Value = serde_json::from_str(&capabilities().to_json()).unwrap();
+    let record = json["features"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|feature| feature["family"] == "type" && feature["name"] == "record")
+        .unwrap();
+
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        json["dump_kinds"],
+        serde_json::json!(["callgraph", "liveness"])
+    );
+    assert_eq!(record["parsed"], true);
+    assert_eq!(record["lowered"], true);
+    assert_eq!(record["analyzed"], true);
+}
@@ -1,6 +1,21 @@
+pub mod cache;
+pub mod capabilities;
+pub mod timings;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Instant;
+
+use toc_hir::db;
+use toc_hir::unit::UnitId;
+use toc_reporting::{MessageSink, ReportMessage, Stage};
 use toc_span::{FileId, Span};
 use toc_syntax::ast::AstNode;
 use toc_syntax::{ast, match_ast, SyntaxNode};
+use toc_vfs::FileDb;
+
+pub use capabilities::capabilities;
+pub use timings::{StageTimer, TimedStage, Timings};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DependencyKind {
@@ -15,7 +30,11 @@ pub enum DependencyKind {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Dependency {
     pub kind: DependencyKind,
+    /// Name that the imported unit is referred to by, or `None` for includes
+    pub name: Option<String>,
     pub relative_path: String,
+    /// Span of the dependency item in the dependent file
+    pub span: Span,
 }
 
 pub fn gather_dependencies(
@@ -36,7 +55,7 @@ pub fn gather_dependencies(
         }
     }
 
-    let mut messages = toc_reporting::MessageSink::new();
+    let mut messages = MessageSink::new(Stage::Validate);
     let mut dependencies = vec![];
 
     let root = ast::Source::cast(syntax).unwrap();
@@ -84,42 +103,57 @@ pub fn gather_dependencies(
     }
 
     // Convert external items into paths
-    let external_deps = external_items
-        .into_iter()
-        .filter_map(|item| {
-            if let Some(path) = item.path() {
-                let (value, errors) = path.literal().unwrap();
-                let relative_path = if let toc_syntax::LiteralValue::String(path) = value {
-                    path
-                } else {
-                    // Always defined as a string literal value
-                    unreachable!()
-                };
+    let external_deps = external_items.into_iter().filter_map(|item| {
+        let span = Span::new(file, item.syntax().text_range());
+        let name = item
+            .name()
+            .and_then(|name| name.identifier_token())
+            .map(|ident| ident.text().to_string());
 
-                if let Some(errors) = errors {
-                    // Report errors
-                    let range = path.syntax().text_range();
+        let relative_path = if let Some(path) = item.path() {
+            let (value, errors) = path.literal().unwrap();
+            let relative_path = if let toc_syntax::LiteralValue::String(path) = value {
+                path
+            } else {
+                // Always defined as a string literal value
+                unreachable!()
+            };
 
-                    for (range, err) in errors.iter().map(|msg| msg.message_at(range)) {
-                        let span = Span::new(file, range);
-                        messages.error(&err.to_string(), span);
-                    }
+            if let Some(errors) = errors {
+                // Report errors, skipping over ones that the scanner already reported
+                let range = path.syntax().text_range();
 
-                    None
-                } else {
-                    // Use the path
-                    Some(relative_path)
+                for (range, err) in errors
+                    .iter()
+                    .filter(|msg| !msg.is_scanner_reported())
+                    .map(|msg| msg.message_at(range))
+                {
+                    let span = Span::new(file, range);
+                    messages.error(&err.to_string(), span);
                 }
-            } else {
-                item.name()
-                    .and_then(|name| name.identifier_token())
-                    .map(|ident| ident.text().to_string())
+
+                return None;
             }
-        })
-        .map(|relative_path| Dependency {
+
+            relative_path
+        } else {
+            name.clone()?
+        };
+
+        // Units imported only by path are named after the file
+        let name = name.or_else(|| {
+            Path::new(&relative_path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        });
+
+        Some(Dependency {
             kind: DependencyKind::Import,
+            name,
             relative_path,
-        });
+            span,
+        })
+    });
     dependencies.extend(external_deps);
 
     // Gather include stmts
@@ -135,10 +169,14 @@ pub fn gather_dependencies(
                 };
 
                 if let Some(errors) = errors {
-                    // Report errors
+                    // Report errors, skipping over ones that the scanner already reported
                     let range = path.syntax().text_range();
 
-                    for (range, err) in errors.iter().map(|msg| msg.message_at(range)) {
+                    for (range, err) in errors
+                        .iter()
+                        .filter(|msg| !msg.is_scanner_reported())
+                        .map(|msg| msg.message_at(range))
+                    {
                         let span = Span::new(file, range);
                         messages.error(&err.to_string(), span);
                     }
@@ -146,7 +184,9 @@ pub fn gather_dependencies(
                     // Add dependency
                     dependencies.push(Dependency {
                         kind: DependencyKind::Include,
+                        name: None,
                         relative_path,
+                        span: Span::new(file, include.syntax().text_range()),
                     });
                 }
             }
@@ -156,6 +196,644 @@ pub fn gather_dependencies(
     (dependencies, messages)
 }
 
+/// Results of parsing and validating a single root file
+pub struct ParsedFile {
+    pub file: FileId,
+    pub parsed: toc_parser::ParseResult,
+    pub dependencies: Vec<Dependency>,
+    pub dependency_messages: Vec<ReportMessage>,
+    pub validated: toc_validate::ValidateResult,
+}
+
+/// Parses and validates a single root file.
+///
+/// If a `cache` is given, a previously cached parse of the same source is used
+/// instead of parsing the file, and new parses are stored into the cache.
+pub fn parse_file(file_db: &FileDb, file: FileId, cache: Option<&cache::ParseCache>) -> ParsedFile {
+    let parsed = parse_unvalidated(file_db, file, cache);
+    let validated = parsed.validate();
+
+    parsed.into_validated(validated)
+}
+
+/// Parses and validates all of the given root files.
+///
+/// If `parallel` is true, the files are split up between multiple threads.
+/// Either way, results are given back in the same order as `files`.
+pub fn parse_files(
+    file_db: &FileDb,
+    files: &[FileId],
+    parallel: bool,
+    cache: Option<&cache::ParseCache>,
+) -> Vec<ParsedFile> {
+    map_files(files, parallel, |file| parse_file(file_db, *file, cache))
+}
+
+/// A parsed file which hasn't been validated yet, so that parsing and
+/// validation can be timed separately
+struct UnvalidatedFile {
+    file: FileId,
+    parsed: toc_parser::ParseResult,
+    dependencies: Vec<Dependency>,
+    dependency_messages: Vec<ReportMessage>,
+}
+
+impl UnvalidatedFile {
+    fn validate(&self) -> toc_validate::ValidateResult {
+        toc_validate::validate_ast(Some(self.file), self.parsed.syntax())
+    }
+
+    fn into_validated(self, validated: toc_validate::ValidateResult) -> ParsedFile {
+        ParsedFile {
+            file: self.file,
+            parsed: self.parsed,
+            dependencies: self.dependencies,
+            dependency_messages: self.dependency_messages,
+            validated,
+        }
+    }
+}
+
+fn parse_unvalidated(
+    file_db: &FileDb,
+    file: FileId,
+    cache: Option<&cache::ParseCache>,
+) -> UnvalidatedFile {
+    let info = file_db.get_file(file);
+    let parsed = match cache {
+        Some(cache) => cache.load(Some(file), &info.source).unwrap_or_else(|| {
+            let parsed = toc_parser::parse(Some(file), &info.source);
+
+            // Failing to cache the parse only makes the next run slower
+            let _ = cache.store(&info.source, &parsed);
+            parsed
+        }),
+        None => toc_parser::parse(Some(file), &info.source),
+    };
+    // TODO: Parse the gathered dependencies
+    let (dependencies, dependency_messages) = gather_dependencies(Some(file), parsed.syntax());
+
+    UnvalidatedFile {
+        file,
+        parsed,
+        dependencies,
+        dependency_messages: dependency_messages.finish(),
+    }
+}
+
+/// Maps each of `items` with `f`, splitting the items up between multiple
+/// threads if `parallel` is true.
+///
+/// Either way, results are given back in the same order as `items`.
+fn map_files<T: Sync, U: Send>(items: &[T], parallel: bool, f: impl Fn(&T) -> U + Sync) -> Vec<U> {
+    if !parallel || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let workers = std::thread::available_parallelism().map_or(1, |count| count.get());
+    let chunk_size = items.len() / workers + 1;
+    let f = &f;
+
+    crossbeam_utils::thread::scope(|scope| {
+        let workers = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move |_| chunk.iter().map(f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        // Join in spawn order to keep the results in input order
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("parser thread panicked"))
+            .collect()
+    })
+    .expect("parser thread panicked")
+}
+
+/// Results of compiling a set of root files
+pub struct CompileResult {
+    /// All of the parsed root files, followed by any imported files
+    pub parsed_files: Vec<ParsedFile>,
+    pub hir_db: db::HirDb,
+    pub lowered: Vec<toc_hir_lowering::HirLowerResult>,
+    pub analyzed: Vec<toc_analysis::AnalyzeResult>,
+    /// Messages from loading imported files
+    pub import_messages: Vec<ReportMessage>,
+    /// How long each stage of compiling took, from loading imported files up to analysis
+    pub timings: Timings,
+}
+
+impl CompileResult {
+    /// Ids of all of the compiled units, with imported units coming before
+    /// the units that import them
+    pub fn units(&self) -> impl Iterator<Item = UnitId> + '_ {
+        self.lowered.iter().map(|res| res.id)
+    }
+
+    /// All reported messages, ordered by file and then by position
+    pub fn messages(&self) -> Vec<&ReportMessage> {
+        let parse_msgs = self.parsed_files.iter().flat_map(|file| {
+            file.parsed
+                .messages()
+                .iter()
+                .chain(file.dependency_messages.iter())
+                .chain(file.validated.messages().iter())
+        });
+        let import_msgs = self.import_messages.iter();
+        let lower_msgs = self.lowered.iter().flat_map(|res| res.messages().iter());
+        let analyze_msgs = self.analyzed.iter().flat_map(|res| res.messages().iter());
+
+        let mut msgs = parse_msgs
+            .chain(import_msgs)
+            .chain(lower_msgs)
+            .chain(analyze_msgs)
+            .collect::<Vec<_>>();
+
+        // Stable sort, so messages at the same position keep their stage order
+        msgs.sort_by_key(|msg| (msg.span().file, msg.span().range.start()));
+        msgs
+    }
+}
+
+/// Compiles all of the given root files, along with any units that they import.
+///
+/// Each file is compiled into a separate unit, and files imported from multiple
+/// places are only compiled once.
+///
+/// Root files may be parsed in parallel (see `parse_files`), but imported files
+/// are parsed sequentially, and lowering and analysis are always done in a
+/// deterministic order. Parses are reused from and stored into `cache`, if given.
+/// Validation happens after all of the files are parsed, so that it can be timed
+/// separately, and is also split up between threads when `parallel` is true.
+/// `dialect` selects which dialect-specific constructs are accepted during analysis.
+/// Imported files with invalid UTF-8 are only reported if `allow_lossy` is false,
+/// in which case they are also left unparsed.
+pub fn compile_files(
+    file_db: &FileDb,
+    files: &[FileId],
+    parallel: bool,
+    cache: Option<&cache::ParseCache>,
+    dialect: toc_analysis::Dialect,
+    allow_lossy: bool,
+) -> CompileResult {
+    let mut timer = StageTimer::new();
+    let mut parsed_files = timer.time(TimedStage::Parse, || {
+        map_files(files, parallel, |file| {
+            parse_unvalidated(file_db, *file, cache)
+        })
+    });
+    let mut import_messages = MessageSink::new(Stage::LowerHir);
+
+    // Load & parse all of the imported files, with each file only parsed once
+    let mut file_indices = parsed_files
+        .iter()
+        .enumerate()
+        .map(|(index, parsed)| (parsed.file, index))
+        .collect::<HashMap<_, _>>();
+    let mut unit_imports = vec![];
+    // Files with invalid UTF-8 aren't parsed, since their replaced text doesn't
+    // line up with the original file
+    let mut unloadable_files = HashSet::new();
+
+    while unit_imports.len() < parsed_files.len() {
+        let parsed = &parsed_files[unit_imports.len()];
+        let importer_path = file_db.get_file(parsed.file).path.clone();
+        let dependencies = parsed.dependencies.clone();
+        let mut imports = vec![];
+
+        for dep in dependencies {
+            // Source from stdin isn't in any directory, so there's nothing to be relative to
+            if importer_path == STDIN_FILE_PATH {
+                let kind = match dep.kind {
+                    DependencyKind::Include => "includes",
+                    DependencyKind::Import => "imports",
+                };
+
+                import_messages.error(
+                    &format!("cannot resolve {} relative to {}", kind, STDIN_FILE_PATH),
+                    dep.span,
+                );
+                continue;
+            }
+
+            // TODO: Deal with include dependencies
+            if dep.kind != DependencyKind::Import {
+                continue;
+            }
+
+            let path = resolve_dependency_path(&importer_path, &dep.relative_path);
+            let file = match timer.time(TimedStage::VfsLoad, || {
+                load_dependency(file_db, &path, allow_lossy)
+            }) {
+                Ok((file, load_err)) => {
+                    if let Some(err) = load_err {
+                        let toc_vfs::LoadError::InvalidUtf8 { span, .. } = err;
+                        import_messages.error(&err.to_string(), span);
+                        unloadable_files.insert(file);
+                    }
+
+                    file
+                }
+                Err(err) => {
+                    import_messages.error(&format!("cannot load `{}`: {}", path, err), dep.span);
+                    continue;
+                }
+            };
+
+            if unloadable_files.contains(&file) {
+                continue;
+            }
+
+            let index = *file_indices.entry(file).or_insert_with(|| {
+                let parsed = timer.time(TimedStage::Parse, || {
+                    parse_unvalidated(file_db, file, cache)
+                });
+                parsed_files.push(parsed);
+                parsed_files.len() - 1
+            });
+
+            imports.push((index, dep.span));
+        }
+
+        unit_imports.push(imports);
+    }
+
+    let validated = timer.time(TimedStage::Validate, || {
+        map_files(&parsed_files, parallel, UnvalidatedFile::validate)
+    });
+    let parsed_files = parsed_files
+        .into_iter()
+        .zip(validated)
+        .map(|(parsed, validated)| parsed.into_validated(validated))
+        .collect::<Vec<_>>();
+
+    // Imported units are lowered before the units importing them
+    let mut order = UnitOrder {
+        file_db,
+        parsed_files: &parsed_files,
+        unit_imports: &unit_imports,
+        visited: vec![false; parsed_files.len()],
+        import_stack: vec![],
+        order: vec![],
+        messages: &mut import_messages,
+    };
+
+    for index in 0..parsed_files.len() {
+        order.visit(index);
+    }
+
+    let order = order.order;
+
+    // Lowering into the shared HIR database stays serialized
+    let hir_db = db::HirBuilder::new();
+    let mut lowered = vec![];
+    let mut lowered_indices = HashMap::new();
+    let lower_start = Instant::now();
+
+    for index in order {
+        let file = &parsed_files[index];
+
+        // Units from import cycles aren't lowered yet, and are skipped
+        let imports = unit_imports[index]
+            .iter()
+            .filter_map(|(imported, span)| {
+                let lowered_index = *lowered_indices.get(imported)?;
+
+                Some(toc_hir_lowering::UnitImport {
+                    unit: &lowered[lowered_index],
+                    span: *span,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let res = toc_hir_lowering::lower_unit(
+            hir_db.clone(),
+            Some(file.file),
+            file.parsed.syntax(),
+            &imports,
+        );
+
+        lowered_indices.insert(index, lowered.len());
+        lowered.push(res);
+    }
+
+    let hir_db = hir_db.finish();
+    timer.add(TimedStage::Lower, lower_start.elapsed());
+
+    let units = lowered.iter().map(|res| res.id).collect::<Vec<_>>();
+    let analyzed = timer.time(TimedStage::Analyze, || {
+        toc_analysis::analyze_units(hir_db.clone(), &units, dialect)
+    });
+
+    CompileResult {
+        parsed_files,
+        hir_db,
+        lowered,
+        analyzed,
+        import_messages: import_messages.finish(),
+        timings: timer.finish(),
+    }
+}
+
+/// Dumps the scanned tokens of `source`, one token per line.
+///
+/// Each line has the token kind, byte range, and escaped lexeme.
+/// Literal tokens are also followed by their decoded value.
+pub fn dump_tokens(source: &str) -> String {
+    use std::fmt::Write;
+    use toc_scanner::token::TokenKind;
+    use toc_syntax::{LiteralValue, SyntaxKind};
+
+    let mut dump = String::new();
+
+    for (kind, range) in toc_scanner::tokenize(source) {
+        let lexeme = &source[range.clone()];
+        write!(dump, "{:?}@{:?} {:?}", kind, range, lexeme).unwrap();
+
+        let literal_kind = match kind {
+            TokenKind::IntLiteral => Some(SyntaxKind::IntLiteral),
+            TokenKind::RadixLiteral => Some(SyntaxKind::RadixLiteral),
+            TokenKind::RealLiteral => Some(SyntaxKind::RealLiteral),
+            TokenKind::CharLiteral => Some(SyntaxKind::CharLiteral),
+            TokenKind::StringLiteral => Some(SyntaxKind::StringLiteral),
+            _ => None,
+        };
+        let literal = literal_kind.and_then(|kind| ast::LiteralExpr::parse_literal(kind, lexeme));
+
+        if let Some((value, errors)) = literal {
+            match value {
+                LiteralValue::Int(value) => write!(dump, " = {}", value),
+                LiteralValue::Real(value) => write!(dump, " = {:?}", value),
+                LiteralValue::Char(value) | LiteralValue::String(value) => {
+                    write!(dump, " = {:?}", value)
+                }
+                LiteralValue::Boolean(value) => write!(dump, " = {}", value),
+            }
+            .unwrap();
+
+            for err in errors.iter().flatten() {
+                write!(dump, " (error: {})", err).unwrap();
+            }
+        }
+
+        dump.push('\n');
+    }
+
+    dump
+}
+
+/// Gets the process exit code after reporting `messages`, which is non-zero
+/// if any errors were reported
+pub fn exit_code(messages: &[ReportMessage]) -> i32 {
+    let has_errors = messages
+        .iter()
+        .any(|msg| matches!(msg.kind(), toc_reporting::AnnotateKind::Error));
+
+    if has_errors {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Summarizes how many errors and warnings are in `messages`, e.g. `2 errors, 1 warning`
+pub fn message_summary(messages: &[ReportMessage]) -> String {
+    let count = |kind| messages.iter().filter(|msg| msg.kind() == kind).count();
+    let plural = |count: usize, what: &str| {
+        format!("{} {}{}", count, what, if count == 1 { "" } else { "s" })
+    };
+
+    format!(
+        "{}, {}",
+        plural(count(toc_reporting::AnnotateKind::Error), "error"),
+        plural(count(toc_reporting::AnnotateKind::Warning), "warning")
+    )
+}
+
+/// Renders the call graphs of the compiled units as a single Graphviz DOT graph.
+///
+/// Nodes are labeled with their names, along with the file and line of their
+/// declaration as found by `span_location`.
+pub fn dump_call_graphs(
+    graphs: &[toc_analysis::call_graph::CallGraph],
+    span_location: impl Fn(toc_span::Span) -> Option<toc_reporting::SpanLocation>,
+) -> String {
+    use std::fmt::Write;
+
+    fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    let mut dump = String::from("digraph calls {\n");
+
+    for (unit_idx, graph) in graphs.iter().enumerate() {
+        for (node_id, node) in graph.nodes() {
+            let mut label = escape(&node.name);
+
+            if let Some(location) = node.span.and_then(&span_location) {
+                write!(
+                    label,
+                    "\\n{}:{}",
+                    escape(&location.path),
+                    location.start.line
+                )
+                .unwrap();
+            }
+
+            writeln!(
+                dump,
+                "    u{}n{} [label=\"{}\"];",
+                unit_idx,
+                node_id.index(),
+                label
+            )
+            .unwrap();
+        }
+
+        for (caller, callee) in graph.calls() {
+            writeln!(
+                dump,
+                "    u{0}n{1} -> u{0}n{2};",
+                unit_idx,
+                caller.index(),
+                callee.index()
+            )
+            .unwrap();
+        }
+    }
+
+    dump.push_str("}\n");
+    dump
+}
+
+/// Renders the stores that are never read in the compiled units, one per line.
+///
+/// Each line has the location of the store as found by `span_location`, or
+/// `<unknown>` if there isn't one.
+pub fn dump_dead_stores(
+    results: &[toc_analysis::LivenessResult],
+    span_location: impl Fn(toc_span::Span) -> Option<toc_reporting::SpanLocation>,
+) -> String {
+    use std::fmt::Write;
+
+    let mut dump = String::new();
+
+    for store in results.iter().flat_map(|result| result.dead_stores()) {
+        match span_location(store.span) {
+            Some(location) => write!(
+                dump,
+                "{}:{}:{}",
+                location.path, location.start.line, location.start.column
+            ),
+            None => write!(dump, "<unknown>"),
+        }
+        .unwrap();
+
+        writeln!(dump, ": dead store to `{}`", store.name).unwrap();
+    }
+
+    dump
+}
+
+/// Path that reads the source from stdin, when given as a root file path
+pub const STDIN_PATH: &str = "-";
+
+/// Path that source read from stdin is registered under
+pub const STDIN_FILE_PATH: &str = "<stdin>";
+
+/// Loads the contents of a root file into `file_db`.
+///
+/// If `path` is [`STDIN_PATH`], the contents are read from `stdin` instead,
+/// and are registered under [`STDIN_FILE_PATH`].
+pub fn load_contents(
+    file_db: &FileDb,
+    path: &str,
+    mut stdin: impl std::io::Read,
+    allow_lossy: bool,
+) -> std::io::Result<(FileId, Option<toc_vfs::LoadError>)> {
+    let (path, contents) = if path == STDIN_PATH {
+        let mut contents = vec![];
+        stdin.read_to_end(&mut contents)?;
+
+        (STDIN_FILE_PATH, contents)
+    } else {
+        (path, std::fs::read(path)?)
+    };
+
+    Ok(file_db.add_file_bytes(path, &contents, allow_lossy))
+}
+
+/// Resolves the path of a dependency relative to the file that depends on it.
+///
+/// Paths without an extension refer to `.tu` unit files.
+fn resolve_dependency_path(file_path: &str, relative_path: &str) -> String {
+    let parent = Path::new(file_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let mut path = parent.join(relative_path);
+
+    if path.extension().is_none() {
+        path.set_extension("tu");
+    }
+
+    path.to_string_lossy().into_owned()
+}
+
+/// Loads a dependency from the given path, reusing the file if it has already
+/// been loaded. Invalid UTF-8 is handled the same way as in [`load_contents`].
+fn load_dependency(
+    file_db: &FileDb,
+    path: &str,
+    allow_lossy: bool,
+) -> std::io::Result<(FileId, Option<toc_vfs::LoadError>)> {
+    if let Some(file) = file_db.find_file(path) {
+        return Ok((file, None));
+    }
+
+    let contents = std::fs::read(path)?;
+    Ok(file_db.add_file_bytes(path, &contents, allow_lossy))
+}
+
+/// Orders units so that imported units come before the units importing them,
+/// reporting any import cycles along the way
+struct UnitOrder<'a> {
+    file_db: &'a FileDb,
+    parsed_files: &'a [ParsedFile],
+    unit_imports: &'a [Vec<(usize, Span)>],
+    visited: Vec<bool>,
+    /// Units currently being visited, along with the span of the import into the next unit
+    import_stack: Vec<(usize, Option<Span>)>,
+    order: Vec<usize>,
+    messages: &'a mut MessageSink,
+}
+
+impl UnitOrder<'_> {
+    fn visit(&mut self, index: usize) {
+        if self.visited[index] {
+            return;
+        }
+
+        self.visited[index] = true;
+        self.import_stack.push((index, None));
+
+        for (imported, span) in &self.unit_imports[index] {
+            self.import_stack.last_mut().unwrap().1 = Some(*span);
+
+            if let Some(cycle_start) = self
+                .import_stack
+                .iter()
+                .position(|(unit, _)| unit == imported)
+            {
+                self.report_cycle(cycle_start, *span);
+            } else {
+                self.visit(*imported);
+            }
+        }
+
+        self.import_stack.pop();
+        self.order.push(index);
+    }
+
+    fn report_cycle(&mut self, cycle_start: usize, span: Span) {
+        let path_of = |index: usize| {
+            let file = self.parsed_files[index].file;
+            self.file_db.get_file(file).path.clone()
+        };
+
+        // Each unit in the cycle imports the next one, wrapping back around to the start
+        let cycle = &self.import_stack[cycle_start..];
+        let notes = cycle
+            .iter()
+            .enumerate()
+            .filter_map(|(position, (index, import_span))| {
+                let next_index = cycle
+                    .get(position + 1)
+                    .map_or(cycle[0].0, |(next, _)| *next);
+                let note = format!("`{}` imports `{}`", path_of(*index), path_of(next_index));
+
+                Some((note, (*import_span)?))
+            })
+            .collect::<Vec<_>>();
+        let message = format!(
+            "import cycle between `{}` and `{}`",
+            path_of(cycle.last().unwrap().0),
+            path_of(cycle[0].0)
+        );
+
+        let mut builder = self.messages.error_detailed(&message, span);
+
+        for (note, import_span) in &notes {
+            builder = builder.with_note(note, *import_span);
+        }
+
+        builder.finish();
+    }
+}
+
+#[cfg(test)]
+fn dep_info(dep: &Dependency) -> (DependencyKind, Option<&str>, &str) {
+    (dep.kind, dep.name.as_deref(), &dep.relative_path)
+}
+
 #[test]
 fn gather_no_deps() {
     let parsed = toc_parser::parse(None, r#"moot"#);
@@ -177,54 +855,45 @@ fn gather_includes() {
 
     assert!(!dependencies.is_empty());
     assert_eq!(
-        dependencies[0],
-        Dependency {
-            kind: DependencyKind::Include,
-            relative_path: "\u{D2}\u{77}\u{D3}_whats_this".to_string()
-        }
+        dep_info(&dependencies[0]),
+        (
+            DependencyKind::Include,
+            None,
+            "\u{D2}\u{77}\u{D3}_whats_this"
+        )
     );
     assert_eq!(
-        dependencies[1],
-        Dependency {
-            kind: DependencyKind::Include,
-            relative_path: "me_time".to_string()
-        }
+        dep_info(&dependencies[1]),
+        (DependencyKind::Include, None, "me_time")
     );
     assert_eq!(dependencies.get(2), None);
 }
 
 #[test]
 fn gather_main_imports() {
-    let parsed = toc_parser::parse(
-        None,
-        r#"
+    let source = r#"
     import "a", name, and_ in "external_place"
-    "#,
-    );
+    "#;
+    let parsed = toc_parser::parse(None, source);
     let (dependencies, _messages) = gather_dependencies(None, parsed.syntax());
 
     assert!(!dependencies.is_empty());
     assert_eq!(
-        dependencies[0],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "a".to_string()
-        }
+        dep_info(&dependencies[0]),
+        (DependencyKind::Import, Some("a"), "a")
     );
     assert_eq!(
-        dependencies[1],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "name".to_string()
-        }
+        dep_info(&dependencies[1]),
+        (DependencyKind::Import, Some("name"), "name")
     );
     assert_eq!(
-        dependencies[2],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "external_place".to_string()
-        }
+        dep_info(&dependencies[2]),
+        (DependencyKind::Import, Some("and_"), "external_place")
     );
+
+    let span_text = |dep: &Dependency| &source[std::ops::Range::<usize>::from(dep.span.range)];
+    assert_eq!(span_text(&dependencies[0]), r#""a""#);
+    assert_eq!(span_text(&dependencies[2]), r#"and_ in "external_place""#);
 }
 
 #[test]
@@ -252,32 +921,20 @@ fn gather_deps_child_class() {
     let (dependencies, _messages) = gather_dependencies(None, parsed.syntax());
 
     assert_eq!(
-        dependencies[0],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "a".to_string()
-        }
+        dep_info(&dependencies[0]),
+        (DependencyKind::Import, Some("a"), "a")
     );
     assert_eq!(
-        dependencies[1],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "b".to_string()
-        }
+        dep_info(&dependencies[1]),
+        (DependencyKind::Import, Some("b"), "b")
     );
     assert_eq!(
-        dependencies[2],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "c".to_string()
-        }
+        dep_info(&dependencies[2]),
+        (DependencyKind::Import, Some("c"), "c")
     );
     assert_eq!(
-        dependencies[3],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "d".to_string()
-        }
+        dep_info(&dependencies[3]),
+        (DependencyKind::Import, Some("d"), "d")
     );
     assert_eq!(dependencies.get(4), None);
 }
@@ -295,32 +952,20 @@ fn gather_main_mixed_deps() {
 
     assert!(!dependencies.is_empty());
     assert_eq!(
-        dependencies[0],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "a".to_string()
-        }
+        dep_info(&dependencies[0]),
+        (DependencyKind::Import, Some("a"), "a")
     );
     assert_eq!(
-        dependencies[1],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "name".to_string()
-        }
+        dep_info(&dependencies[1]),
+        (DependencyKind::Import, Some("name"), "name")
     );
     assert_eq!(
-        dependencies[2],
-        Dependency {
-            kind: DependencyKind::Import,
-            relative_path: "external_place".to_string()
-        }
+        dep_info(&dependencies[2]),
+        (DependencyKind::Import, Some("and_"), "external_place")
     );
     assert_eq!(
-        dependencies[3],
-        Dependency {
-            kind: DependencyKind::Include,
-            relative_path: "bob".to_string()
-        }
+        dep_info(&dependencies[3]),
+        (DependencyKind::Include, None, "bob")
     );
 }
 
@@ -338,3 +983,638 @@ fn gather_bad_paths() {
     assert!(dependencies.is_empty(), "{:?}", dependencies);
     eprintln!("{:?}", messages.finish())
 }
+
+#[test]
+fn parallel_parse_matches_sequential() {
+    fn render(result: &CompileResult) -> Vec<String> {
+        result
+            .messages()
+            .iter()
+            .map(|msg| format!("{:?}: {}", msg.span().file, msg))
+            .collect()
+    }
+
+    let file_db = FileDb::new();
+    let files = (0..50)
+        .map(|i| {
+            // Parser, validator, lowering, and typeck errors in every file
+            let source = format!(
+                "var a{0} : int := {0}\nvar b{0} := a{0} +\nbegin import end\nput c{0}\na{0} := \"{0}\"\n",
+                i
+            );
+            file_db.add_file(&format!("file{}.t", i), &source)
+        })
+        .collect::<Vec<_>>();
+
+    let sequential = compile_files(&file_db, &files, false, None, Default::default(), false);
+    let parallel = compile_files(&file_db, &files, true, None, Default::default(), false);
+
+    assert!(!render(&sequential).is_empty());
+    assert_eq!(render(&sequential), render(&parallel));
+    assert_eq!(
+        sequential.units().collect::<Vec<_>>(),
+        parallel.units().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn resolve_dependency_paths() {
+    assert_eq!(resolve_dependency_path("main.t", "b"), "b.tu");
+    assert_eq!(
+        resolve_dependency_path("src/main.t", "lib/b"),
+        "src/lib/b.tu"
+    );
+    assert_eq!(resolve_dependency_path("src/main.t", "b.t"), "src/b.t");
+}
+
+#[test]
+fn compile_transitive_imports() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file(
+        "a.t",
+        "import b, c\nvar big : nat1 := j\nvar small : nat1 := k\nput big, small\n",
+    );
+    file_db.add_file(
+        "b.tu",
+        "unit module b import c export j const j := k + 1 end b\n",
+    );
+    file_db.add_file("c.tu", "unit module c export k const k := 255 end c\n");
+
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+    let messages = compiled
+        .messages()
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect::<Vec<_>>();
+
+    // `c` is imported twice, but is only lowered once
+    assert_eq!(compiled.units().count(), 3);
+
+    // `j` is evaluated through `b` and `c`
+    assert_eq!(
+        messages,
+        vec![
+            "error in file FileId(1) at 30..31: value is out of range for `nat1`\n\
+            | note in file FileId(1) for 30..31: this `int` value is 256\n\
+            | info: `nat1` values must be between 0 and 255"
+        ]
+    );
+
+    // `a` imports both `b` and `c`, and `b` imports `c`
+    let units = compiled.units().collect::<Vec<_>>();
+    let (c_unit, b_unit, a_unit) = (units[0], units[1], units[2]);
+    assert_eq!(compiled.hir_db.unit_imports(a_unit), &[b_unit, c_unit]);
+    assert_eq!(compiled.hir_db.unit_imports(b_unit), &[c_unit]);
+    assert_eq!(compiled.hir_db.unit_imports(c_unit), &[]);
+
+    // Both uses of `k` refer back to the declaration in `c`
+    let k_def = compiled.hir_db.get_unit(c_unit).exports[0].def_id;
+    for unit in [a_unit, b_unit] {
+        let unit = compiled.hir_db.get_unit(unit);
+        let (k_use, _) = unit
+            .symbol_table
+            .iter_uses()
+            .find(|(use_id, _)| unit.symbol_table.get_symbol(use_id.as_def()).name == "k")
+            .unwrap();
+
+        assert_eq!(unit.resolve_def(k_use.as_def()), k_def);
+    }
+}
+
+#[test]
+fn compile_opaque_exports() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file(
+        "a.t",
+        "import c\n\
+        var x : t := z\n\
+        var y : r := w\n\
+        put x = z, y.a\n\
+        x := 1\n\
+        var _n : int := x\n",
+    );
+    file_db.add_file(
+        "c.tu",
+        "unit module c export opaque t, opaque r, z, w\n\
+        type t : int\n\
+        type r : record a : int end record\n\
+        var z : t := 1\n\
+        var w : r\n\
+        z := z + 1\n\
+        w.a := z\n\
+        end c\n",
+    );
+
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+    let messages = compiled
+        .messages()
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect::<Vec<_>>();
+
+    // Opaque types can be assigned and compared for equality outside of `c`,
+    // but are only usable as their underlying types inside of `c`
+    assert_eq!(
+        messages,
+        vec![
+            "error in file FileId(1) at 52..53: type `r` is opaque outside module `c`\n\
+            | note in file FileId(2) for 31..39: `r` is exported as opaque here",
+            "error in file FileId(1) at 56..58: type `t` is opaque outside module `c`\n\
+            | note in file FileId(2) for 21..29: `t` is exported as opaque here",
+            "error in file FileId(1) at 77..78: type `t` is opaque outside module `c`\n\
+            | note in file FileId(2) for 21..29: `t` is exported as opaque here",
+        ]
+    );
+}
+
+#[test]
+fn compile_opaque_pointer() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file(
+        "a.t",
+        "import c\n\
+        var h : handle := make(1)\n\
+        put take(h), h = make(2)\n\
+        ^h := 2\n\
+        put #h, h(1)\n\
+        new h\n",
+    );
+    file_db.add_file(
+        "c.tu",
+        "unit module c export opaque handle, make, take\n\
+        type handle : ^int\n\
+        function make(v : int) : handle\n\
+            var p : handle\n\
+            new p\n\
+            ^p := v\n\
+            result p\n\
+        end make\n\
+        function take(p : handle) : int\n\
+            result ^p\n\
+        end take\n\
+        end c\n",
+    );
+
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+    let messages = compiled
+        .messages()
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect::<Vec<_>>();
+
+    // Opaque pointers can be passed back into `c`, but can't be followed,
+    // cheated, subscripted, or allocated outside of it
+    let opaque_error = |span| {
+        format!(
+            "error in file FileId(1) at {}: type `handle` is opaque outside module `c`\n\
+            | note in file FileId(2) for 21..34: `handle` is exported as opaque here",
+            span
+        )
+    };
+    assert_eq!(
+        messages,
+        vec![
+            opaque_error("61..62"),
+            opaque_error("73..74"),
+            opaque_error("76..77"),
+            opaque_error("85..86"),
+        ]
+    );
+}
+
+#[test]
+fn compile_alt_eq_decl() {
+    let compile_messages = |source: &str| {
+        let file_db = FileDb::new();
+        let a = file_db.add_file("a.t", source);
+        let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+
+        compiled
+            .messages()
+            .iter()
+            .map(|msg| msg.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Only warned about once, by the parser
+    assert_eq!(
+        compile_messages("const a : int = 1\nput a\n"),
+        vec!["warn in file FileId(1) at 14..15: ‘=’ found, assuming it to be ‘:=’"]
+    );
+    assert!(compile_messages("const a : int := 1\nput a\n").is_empty());
+}
+
+#[test]
+fn warnings_as_errors_exit_code() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file("a.t", "const a : int = 1\nput a\n");
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+    let mut msgs = toc_reporting::dedup_messages(compiled.messages());
+
+    // Only warnings, so compiling still succeeds
+    assert_eq!(exit_code(&msgs), 0);
+
+    toc_reporting::promote_warnings(&mut msgs);
+    assert_eq!(
+        msgs[0].to_string(),
+        "error in file FileId(1) at 14..15: ‘=’ found, assuming it to be ‘:=’"
+    );
+    assert_ne!(exit_code(&msgs), 0);
+}
+
+#[test]
+fn summarize_messages() {
+    let compile_summary = |source: &str| {
+        let file_db = FileDb::new();
+        let a = file_db.add_file("a.t", source);
+        let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+
+        message_summary(&toc_reporting::dedup_messages(compiled.messages()))
+    };
+
+    assert_eq!(
+        compile_summary("var a : int := 1\nput a\n"),
+        "0 errors, 0 warnings"
+    );
+    assert_eq!(
+        compile_summary("const a : int = 1\nput a\n"),
+        "0 errors, 1 warning"
+    );
+    assert_eq!(
+        compile_summary("var a : int := 1.0\nvar b : int := 1.0\nput a, b\n"),
+        "2 errors, 0 warnings"
+    );
+}
+
+#[test]
+fn compile_from_stdin() {
+    let file_db = FileDb::new();
+    let stdin: &[u8] = b"var a : int := 1.0\n";
+    let (file, load_err) = load_contents(&file_db, STDIN_PATH, stdin, false).unwrap();
+
+    assert_eq!(load_err, None);
+    assert_eq!(file_db.get_file(file).path, STDIN_FILE_PATH);
+
+    let compiled = compile_files(&file_db, &[file], false, None, Default::default(), false);
+    let msgs = toc_reporting::dedup_messages(compiled.messages());
+
+    // Diagnostics still point into the stdin source
+    assert!(!msgs.is_empty());
+    for msg in &msgs {
+        let origin = msg
+            .span()
+            .file
+            .map(|file| file_db.get_file(file).path.clone());
+        assert_eq!(origin.as_deref(), Some(STDIN_FILE_PATH));
+    }
+}
+
+#[test]
+fn compile_stdin_dependencies() {
+    let file_db = FileDb::new();
+    let stdin: &[u8] = b"import a\ninclude \"b.t\"\n";
+    let (file, _) = load_contents(&file_db, STDIN_PATH, stdin, false).unwrap();
+
+    let compiled = compile_files(&file_db, &[file], false, None, Default::default(), false);
+    let messages = compiled
+        .messages()
+        .iter()
+        .map(|msg| msg.message())
+        .filter(|msg| msg.starts_with("cannot resolve"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        vec![
+            "cannot resolve imports relative to <stdin>",
+            "cannot resolve includes relative to <stdin>",
+        ]
+    );
+}
+
+#[test]
+fn compile_stdin_with_files() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file("a.t", "put 1\n");
+    let stdin: &[u8] = b"put 2\n";
+    let (b, _) = load_contents(&file_db, STDIN_PATH, stdin, false).unwrap();
+
+    let compiled = compile_files(&file_db, &[a, b], false, None, Default::default(), false);
+
+    assert_eq!(compiled.units().count(), 2);
+    assert!(compiled.messages().is_empty());
+}
+
+#[test]
+fn dump_main_call_graph() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file("a.t", "put 1\n");
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+
+    let graphs = compiled
+        .units()
+        .map(|unit| toc_analysis::call_graph::call_graph(&compiled.hir_db, unit))
+        .collect::<Vec<_>>();
+    let dump = dump_call_graphs(&graphs, |_| {
+        Some(toc_reporting::SpanLocation {
+            path: "a.t".to_string(),
+            start: toc_reporting::LineColumn { line: 1, column: 1 },
+            end: toc_reporting::LineColumn { line: 1, column: 6 },
+        })
+    });
+
+    assert_eq!(
+        dump,
+        "digraph calls {\n    u0n0 [label=\"<main>\\na.t:1\"];\n}\n"
+    );
+}
+
+#[test]
+fn dump_proc_call_graph() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file("a.t", "procedure a end a\nprocedure b a end b\na\nb\n");
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+
+    let graphs = compiled
+        .units()
+        .map(|unit| toc_analysis::call_graph::call_graph(&compiled.hir_db, unit))
+        .collect::<Vec<_>>();
+    let dump = dump_call_graphs(&graphs, |_| None);
+
+    assert_eq!(
+        dump,
+        "digraph calls {\n    \
+         u0n0 [label=\"<main>\"];\n    \
+         u0n1 [label=\"a\"];\n    \
+         u0n2 [label=\"b\"];\n    \
+         u0n2 -> u0n1;\n    \
+         u0n0 -> u0n1;\n    \
+         u0n0 -> u0n2;\n\
+         }\n"
+    );
+}
+
+#[test]
+fn dump_unit_dead_stores() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file("a.t", "var a := 1\na := 2\nput a\n");
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+
+    let results = compiled
+        .units()
+        .map(|unit| toc_analysis::liveness(&compiled.hir_db, unit))
+        .collect::<Vec<_>>();
+    let dump = dump_dead_stores(&results, |span| {
+        let start = u32::from(span.range.start()) as usize + 1;
+
+        Some(toc_reporting::SpanLocation {
+            path: "a.t".to_string(),
+            start: toc_reporting::LineColumn {
+                line: 1,
+                column: start,
+            },
+            end: toc_reporting::LineColumn {
+                line: 1,
+                column: start + 1,
+            },
+        })
+    });
+
+    assert_eq!(dump, "a.t:1:5: dead store to `a`\n");
+}
+
+#[test]
+fn compile_import_cycle() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file("a.t", "import b\n");
+    file_db.add_file("b.tu", "unit module b import c end b\n");
+    file_db.add_file("c.tu", "unit module c import b end c\n");
+
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+    let messages = compiled
+        .messages()
+        .iter()
+        .map(|msg| msg.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(compiled.units().count(), 3);
+    assert_eq!(
+        messages,
+        vec![
+            "error in file FileId(3) at 21..22: import cycle between `c.tu` and `b.tu`\n\
+            | note in file FileId(2) for 21..22: `b.tu` imports `c.tu`\n\
+            | note in file FileId(3) for 21..22: `c.tu` imports `b.tu`"
+        ]
+    );
+}
+
+#[test]
+fn compile_lossy_import() {
+    let dir = std::env::temp_dir().join(format!("toc_lossy_import_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("b.tu"),
+        b"unit module b export k const k := \"\xFF\" + j end b\n",
+    )
+    .unwrap();
+    let root_path = dir.join("a.t").to_string_lossy().into_owned();
+
+    let compile = |allow_lossy| {
+        let file_db = FileDb::new();
+        let a = file_db.add_file(&root_path, "import b\n");
+        let compiled = compile_files(&file_db, &[a], false, None, Default::default(), allow_lossy);
+
+        compiled
+            .messages()
+            .iter()
+            .map(|msg| {
+                // Locate each message in the file's source text
+                let span = msg.span();
+                let source = &file_db.get_file(span.file.unwrap()).source;
+                let line_map = toc_span::LineMap::new(source);
+
+                (
+                    msg.message().to_string(),
+                    source[span.range].to_string(),
+                    line_map.line_col(u32::from(span.range.start()) as usize),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Invalid UTF-8 in imported files is reported like in root files,
+    // and the rest of the file isn't parsed...
+    assert_eq!(
+        compile(false),
+        vec![(
+            "file is not valid UTF-8 at byte 35".to_string(),
+            "\u{FFFD}".to_string(),
+            (0, 35)
+        )]
+    );
+    // ...unless lossy loading is allowed, where later spans are still in the same
+    // column as in the original file
+    assert_eq!(
+        compile(true),
+        vec![(
+            "undeclared identifier `j`".to_string(),
+            "j".to_string(),
+            (0, 40)
+        )]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn compile_missing_import() {
+    let file_db = FileDb::new();
+    let a = file_db.add_file("this/does/not/exist/a.t", "import b\n");
+
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+    let messages = compiled.messages();
+
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0]
+        .message()
+        .starts_with("cannot load `this/does/not/exist/b.tu`"));
+}
+
+#[test]
+fn dump_token_stream() {
+    let source = "% hello\nvar a := 16#fF /* ** */ + \"t^I\\\"\" + 1.5e1 + 2#3\n";
+
+    assert_eq!(
+        dump_tokens(source),
+        r#"Comment@0..7 "% hello"
+Whitespace@7..8 "\n"
+Var@8..11 "var"
+Whitespace@11..12 " "
+Identifier@12..13 "a"
+Whitespace@13..14 " "
+Assign@14..16 ":="
+Whitespace@16..17 " "
+RadixLiteral@17..22 "16#fF" = 255
+Whitespace@22..23 " "
+Comment@23..31 "/* ** */"
+Whitespace@31..32 " "
+Plus@32..33 "+"
+Whitespace@33..34 " "
+StringLiteral@34..41 "\"t^I\\\"\"" = "t\t\""
+Whitespace@41..42 " "
+Plus@42..43 "+"
+Whitespace@43..44 " "
+RealLiteral@44..49 "1.5e1" = 15.0
+Whitespace@49..50 " "
+Plus@50..51 "+"
+Whitespace@51..52 " "
+RadixLiteral@52..55 "2#3" = 0 (error: invalid digit for the specified base)
+Whitespace@55..56 "\n"
+"#
+    );
+}
+
+#[test]
+fn message_stages() {
+    let file_db = FileDb::new();
+    let source = "var register _r : int := 1\nvar _a := b\nvar _d : int := 1.0\nvar _e :=\n";
+    let a = file_db.add_file("a.t", source);
+
+    let compiled = compile_files(&file_db, &[a], false, None, Default::default(), false);
+    let mut messages = compiled
+        .messages()
+        .iter()
+        .map(|msg| (msg.span().range.start(), msg.stage(), msg.message()))
+        .collect::<Vec<_>>();
+    messages.sort_by_key(|(start, ..)| *start);
+    let messages = messages
+        .into_iter()
+        .map(|(_, stage, msg)| (stage, msg))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        vec![
+            (
+                Stage::Validate,
+                "‘register’ attribute is not allowed at module-like or program level"
+            ),
+            (Stage::LowerHir, "undeclared identifier `b`"),
+            (
+                Stage::Analyze,
+                "cannot implicitly convert `real` into `int`"
+            ),
+            (Stage::Parse, "expected expression after here"),
+        ]
+    );
+}
+
+#[test]
+fn stage_timings() {
+    use std::time::Duration;
+
+    let mut timer = StageTimer::new();
+    let file_db = FileDb::new();
+    let stdin: &[u8] = b"import b\nvar a : int := 1.0\n";
+    let (file, _) = timer.time(TimedStage::VfsLoad, || {
+        load_contents(&file_db, STDIN_PATH, stdin, false).unwrap()
+    });
+
+    let compiled = compile_files(&file_db, &[file], false, None, Default::default(), false);
+    timer.add_timings(&compiled.timings);
+    timer.time(TimedStage::Render, || {
+        toc_reporting::messages_to_json(&toc_reporting::dedup_messages(compiled.messages()), |_| {
+            None
+        })
+    });
+    let timings = timer.finish();
+
+    let table = timings.to_table();
+    for stage in TimedStage::ALL.iter() {
+        assert!(table.contains(stage.name()), "{}", table);
+    }
+    assert!(table.contains("total"), "{}", table);
+
+    // Stages don't overlap, and only a little bit of time is spent between them
+    let stage_sum = timings
+        .stages()
+        .map(|(_, duration)| duration)
+        .sum::<Duration>();
+    assert!(stage_sum <= timings.total());
+    assert!(timings.total() - stage_sum < Duration::from_millis(100));
+}
+
+#[test]
+fn export_hir_json() {
+    use serde_json::Value;
+
+    let file_db = FileDb::new();
+    let stdin: &[u8] = b"const a : int := 1\n";
+    let (file, _) = load_contents(&file_db, STDIN_PATH, stdin, false).unwrap();
+    let compiled = compile_files(&file_db, &[file], false, None, Default::default(), false);
+    let unit = compiled.units().next().unwrap();
+
+    let exported: Value =
+        serde_json::from_str(&toc_hir::export::to_json(&compiled.hir_db, unit)).unwrap();
+    assert_eq!(exported["format_version"], toc_hir::export::FORMAT_VERSION);
+
+    let nodes = exported["nodes"].as_array().unwrap();
+    let node = |id: &Value| &nodes[id.as_u64().unwrap() as usize];
+    let unit = &node(&exported["unit"])["node"]["Unit"];
+
+    let decl = node(&unit["stmts"][0]);
+    let const_var = &decl["node"]["Stmt"]["ConstVar"];
+    assert_eq!(const_var["is_const"], true);
+    assert_eq!(decl["span"]["file"], file.raw_id().get());
+    assert_eq!(decl["span"]["range"], serde_json::json!([0, 18]));
+
+    // Names are looked up in the symbol table, which is ordered by `DefId`
+    let def_id = const_var["names"][0].as_u64().unwrap() as usize;
+    let def = &unit["symbol_table"]["defs"][def_id];
+    assert_eq!(def["name"], "a");
+    assert_eq!(def["span"]["range"], serde_json::json!([6, 7]));
+
+    let ty = node(&const_var["tail"]["Both"][0]);
+    assert_eq!(ty["node"]["Type"]["Primitive"], "Int");
+    assert_eq!(ty["span"]["range"], serde_json::json!([10, 13]));
+}
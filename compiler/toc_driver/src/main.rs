@@ -2,134 +2,367 @@
 
 use std::collections::HashMap;
 use std::ops::Range;
-use std::{env, fs, io, sync::Arc};
+use std::{env, sync::Arc};
 
-use toc_hir::db;
-use toc_vfs::FileDb;
-
-fn load_contents(path: &str) -> io::Result<String> {
-    let contents = fs::read(path)?;
-    let contents = String::from_utf8_lossy(&contents).to_string();
-    Ok(contents)
-}
+use toc_driver::TimedStage;
+use toc_reporting::{MessageSink, Stage};
+use toc_vfs::{FileDb, LoadError};
 
 fn main() {
-    let path: String = env::args().nth(1).expect("Missing path to source file");
-    let contents = load_contents(&path).expect("Unable to load file");
-    let file_db = FileDb::new();
+    let mut allow_lossy = false;
+    let mut parallel = true;
+    let mut message_format = MessageFormat::Human;
+    let mut emit_tokens = false;
+    let mut emit_hir_json = false;
+    let mut dump_call_graph = false;
+    let mut dump_liveness = false;
+    let mut show_capabilities = false;
+    let mut check_only = false;
+    let mut show_timings = false;
+    let mut verbose_diagnostics = false;
+    let mut warnings_as_errors = false;
+    let mut dialect = toc_analysis::Dialect::default();
+    let mut tab_width = DEFAULT_TAB_WIDTH;
+    let mut cache = None;
+    let mut paths = vec![];
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Replace invalid UTF-8 sequences instead of reporting them
+            "--lossy-utf8" => allow_lossy = true,
+            // Parse all files on the main thread
+            "--sequential" => parallel = false,
+            // How to print diagnostics
+            "--message-format" => {
+                message_format = match args.next().as_deref() {
+                    Some("human") => MessageFormat::Human,
+                    Some("json") => MessageFormat::Json,
+                    _ => panic!("Expected `human` or `json` after `--message-format`"),
+                }
+            }
+            // Show which stage each message was reported from
+            "--verbose-diagnostics" => verbose_diagnostics = true,
+            // How many columns tabs are expanded to in rendered diagnostics
+            "--tab-width" => {
+                tab_width = match args.next().map(|width| width.parse()) {
+                    Some(Ok(width)) if width > 0 => width,
+                    _ => panic!("Expected a non-zero width after `--tab-width`"),
+                }
+            }
+            // Promote all warnings into errors
+            "--warnings-as-errors" => warnings_as_errors = true,
+            // Allow `fork` to start procedures, not just processes
+            "--fork-procedures" => dialect.fork_procedures = true,
+            // Where to cache parsed files between runs
+            "--cache-dir" => match args.next() {
+                Some(dir) => cache = Some(toc_driver::cache::ParseCache::new(dir)),
+                None => panic!("Expected a path after `--cache-dir`"),
+            },
+            // What to emit instead of compiling
+            "--emit" => match args.next().as_deref() {
+                Some("tokens") => emit_tokens = true,
+                Some("hir-json") => emit_hir_json = true,
+                _ => panic!("Expected `tokens` or `hir-json` after `--emit`"),
+            },
+            // What to dump instead of the usual output
+            "--dump" => match args.next().as_deref() {
+                Some("callgraph") => dump_call_graph = true,
+                Some("liveness") => dump_liveness = true,
+                _ => panic!("Expected `callgraph` or `liveness` after `--dump`"),
+            },
+            // Print the supported language features as JSON, for tooling
+            "--capabilities" => show_capabilities = true,
+            // Only report diagnostics, skipping everything after analysis
+            "--check" => check_only = true,
+            // Print how long each stage took
+            "--timings" => show_timings = true,
+            _ => paths.push(arg),
+        }
+    }
 
-    // Add the root path to the file db
-    let root_file = file_db.add_file(&path, &contents);
-    let hir_db = db::HirBuilder::new();
+    if check_only {
+        // Only the diagnostics are reported, so nothing else is emitted or dumped
+        emit_tokens = false;
+        emit_hir_json = false;
+        dump_call_graph = false;
+        dump_liveness = false;
+    }
 
-    // Parse root CST
-    let parsed = {
-        let info = file_db.get_file(root_file);
-        let parsed = toc_parser::parse(Some(root_file), &info.source);
-        let dependencies = toc_driver::gather_dependencies(Some(root_file), parsed.syntax());
-        // TODO: Gather dependencies from root CST, and parse them
+    if show_capabilities {
+        println!("{}", toc_driver::capabilities().to_json());
+        return;
+    }
 
-        println!("Parsed output: {}", parsed.dump_tree());
-        println!("Dependencies: {:#?}", dependencies);
+    assert!(!paths.is_empty(), "Missing path to source file");
+    assert!(
+        paths
+            .iter()
+            .filter(|path| *path == toc_driver::STDIN_PATH)
+            .count()
+            <= 1,
+        "`-` can only be given once, since stdin can only be read once"
+    );
+    let mut timer = toc_driver::StageTimer::new();
+    let file_db = FileDb::new();
+    let mut load_sink = MessageSink::new(Stage::Parse);
+
+    // Add the root paths to the file db
+    // Files with invalid UTF-8 are left out, since only the load error is reported for them
+    let root_files = timer.time(TimedStage::VfsLoad, || {
+        paths
+            .iter()
+            .filter_map(|path| {
+                // `-` reads the source from stdin
+                let (file, load_err) =
+                    toc_driver::load_contents(&file_db, path, std::io::stdin(), allow_lossy)
+                        .expect("Unable to load file");
+
+                if let Some(err) = load_err {
+                    let LoadError::InvalidUtf8 { span, .. } = err;
+                    load_sink.error(&err.to_string(), span);
+                    return None;
+                }
+
+                Some(file)
+            })
+            .collect::<Vec<_>>()
+    });
+    let load_msgs = load_sink.finish();
+
+    if emit_tokens {
+        // Only run the scanner
+        for file in root_files {
+            print!(
+                "{}",
+                toc_driver::dump_tokens(&file_db.get_file(file).source)
+            );
+        }
 
-        parsed
-    };
+        return;
+    }
 
     // TODO: Deal with include globs
+    let compiled = toc_driver::compile_files(
+        &file_db,
+        &root_files,
+        parallel,
+        cache.as_ref(),
+        dialect,
+        allow_lossy,
+    );
+    timer.add_timings(&compiled.timings);
+
+    if emit_hir_json {
+        // One JSON object per unit, each on its own line
+        for unit in compiled.units() {
+            println!("{}", toc_hir::export::to_json(&compiled.hir_db, unit));
+        }
 
-    let (validate_res, hir_res) = {
-        let validate_res = toc_validate::validate_ast(Some(root_file), parsed.syntax());
-        let hir_res = toc_hir_lowering::lower_ast(hir_db.clone(), Some(root_file), parsed.syntax());
+        return;
+    }
 
-        (validate_res, hir_res)
-    };
+    if dump_call_graph {
+        // Only the graph is printed, so that it can be piped into Graphviz
+        let span_mapper = SpanMapper::new(&file_db);
+        let graphs = compiled
+            .units()
+            .map(|unit| toc_analysis::call_graph::call_graph(&compiled.hir_db, unit))
+            .collect::<Vec<_>>();
 
-    let hir_db = hir_db.finish();
-    let root_unit = hir_db.get_unit(hir_res.id);
-    println!("{:#?}", root_unit);
+        print!(
+            "{}",
+            toc_driver::dump_call_graphs(&graphs, |span| span_mapper.span_location(span))
+        );
 
-    // TODO: resolve imports between units
+        return;
+    }
 
-    let analyze_res = toc_analysis::analyze_unit(hir_db.clone(), hir_res.id);
+    if dump_liveness {
+        let span_mapper = SpanMapper::new(&file_db);
+        let results = compiled
+            .units()
+            .map(|unit| toc_analysis::liveness(&compiled.hir_db, unit))
+            .collect::<Vec<_>>();
 
-    let mut msgs = parsed
-        .messages()
-        .iter()
-        .chain(validate_res.messages().iter())
-        .chain(hir_res.messages().iter())
-        .chain(analyze_res.messages().iter())
-        .collect::<Vec<_>>();
+        print!(
+            "{}",
+            toc_driver::dump_dead_stores(&results, |span| span_mapper.span_location(span))
+        );
 
-    // Sort by start order
-    msgs.sort_by_key(|msg| msg.span().range.start());
+        return;
+    }
 
-    let mut has_errors = false;
+    // Only the diagnostics are printed in JSON mode, so that the output can be parsed
+    if message_format == MessageFormat::Human && !check_only {
+        for file in &compiled.parsed_files {
+            println!("Parsed output: {}", file.parsed.dump_tree());
+            println!("Dependencies: {:#?}", file.dependencies);
+        }
 
-    let span_mapper = SpanMapper::new(&file_db);
+        for unit in compiled.units() {
+            println!("{:#?}", compiled.hir_db.get_unit(unit));
+        }
+    }
 
-    for msg in msgs {
-        has_errors |= matches!(msg.kind(), toc_reporting::AnnotateKind::Error);
-        let snippet = span_mapper.message_into_snippet(msg);
-        let display_list = annotate_snippets::display_list::DisplayList::from(snippet);
+    // Sort by file, then by start order, collapsing any repeated messages
+    let mut msgs = toc_reporting::dedup_messages(load_msgs.iter().chain(compiled.messages()));
 
-        println!("{}", display_list);
+    if warnings_as_errors {
+        toc_reporting::promote_warnings(&mut msgs);
     }
 
-    std::process::exit(if has_errors { -1 } else { 0 });
+    let rendered = timer.time(TimedStage::Render, || {
+        let span_mapper = SpanMapper::with_tab_width(&file_db, tab_width);
+
+        match message_format {
+            MessageFormat::Human => msgs
+                .iter()
+                .map(|msg| {
+                    let title = if verbose_diagnostics {
+                        format!("{} [{}]", msg.message(), msg.stage())
+                    } else {
+                        msg.message().to_string()
+                    };
+                    let snippet = span_mapper.message_into_snippet(msg, &title);
+                    let display_list = annotate_snippets::display_list::DisplayList::from(snippet);
+
+                    format!("{}\n", display_list)
+                })
+                .collect::<String>(),
+            MessageFormat::Json => {
+                toc_reporting::messages_to_json(&msgs, |span| span_mapper.span_location(span))
+            }
+        }
+    });
+
+    if check_only {
+        // Kept out of stdout, so that JSON output stays parseable
+        eprintln!("{}", toc_driver::message_summary(&msgs));
+    }
+
+    match message_format {
+        MessageFormat::Human => {
+            print!("{}", rendered);
+
+            if show_timings {
+                // Kept out of stdout, so that it doesn't get mixed in with the diagnostics
+                eprint!("{}", timer.finish().to_table());
+            }
+        }
+        // Timings are wrapped together with the messages, so that the output stays one JSON value
+        MessageFormat::Json if show_timings => println!(
+            "{{\"messages\":{},\"timings\":{}}}",
+            rendered,
+            timer.finish().to_json()
+        ),
+        MessageFormat::Json => println!("{}", rendered),
+    }
+
+    std::process::exit(toc_driver::exit_code(&msgs));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageFormat {
+    /// Annotated source snippets
+    Human,
+    /// A JSON array of messages, or an object with the messages and timings
+    /// if `--timings` is given
+    Json,
 }
 
+/// Number of columns between tab stops in rendered snippets
+const DEFAULT_TAB_WIDTH: usize = 4;
+
 struct SpanMapper {
-    files: HashMap<toc_span::FileId, (Arc<toc_vfs::FileInfo>, Vec<Range<usize>>)>,
+    files: HashMap<toc_span::FileId, (Arc<toc_vfs::FileInfo>, toc_span::LineMap)>,
+    /// Sources with tabs expanded, for files which have any tabs
+    expanded: HashMap<toc_span::FileId, ExpandedSource>,
 }
 
 impl SpanMapper {
     fn new(file_db: &toc_vfs::FileDb) -> Self {
+        Self::with_tab_width(file_db, DEFAULT_TAB_WIDTH)
+    }
+
+    /// Like [`SpanMapper::new`], but with tabs in rendered snippets going up to
+    /// the next multiple of `tab_width` columns
+    fn with_tab_width(file_db: &toc_vfs::FileDb, tab_width: usize) -> Self {
+        assert!(tab_width > 0, "tab width must be non-zero");
+
         let mut files = HashMap::new();
+        let mut expanded = HashMap::new();
 
         for file in file_db.files() {
             let info = file_db.get_file(file);
-            let line_ranges = Self::build_line_ranges(&info.source);
+            let line_map = toc_span::LineMap::new(&info.source);
 
-            files.insert(file, (info, line_ranges));
+            if info.source.contains('\t') {
+                expanded.insert(file, ExpandedSource::new(&info.source, tab_width));
+            }
+
+            files.insert(file, (info, line_map));
         }
 
-        Self { files }
+        Self { files, expanded }
     }
 
-    fn build_line_ranges(source: &str) -> Vec<Range<usize>> {
-        let mut line_ranges = vec![];
-        let mut line_start = 0;
-        let line_ends = source.char_indices().filter(|(_, c)| matches!(c, '\n'));
-
-        for (at_newline, _) in line_ends {
-            let line_end = at_newline + 1;
-            line_ranges.push(line_start..line_end);
-            line_start = line_end;
+    /// Gets the text of `file` as it is displayed in snippets
+    fn display_source(&self, file: toc_span::FileId) -> &str {
+        match self.expanded.get(&file) {
+            Some(expanded) => &expanded.text,
+            None => &self.files.get(&file).unwrap().0.source,
         }
+    }
 
-        // Use a line span covering the rest of the file
-        line_ranges.push(line_start..source.len());
-
-        line_ranges
+    /// Maps a byte index in the source of `file` into one in the displayed source
+    fn display_index(&self, file: toc_span::FileId, byte_idx: usize) -> usize {
+        match self.expanded.get(&file) {
+            Some(expanded) => expanded.offsets[byte_idx],
+            None => byte_idx,
+        }
     }
 
+    /// Finds the line containing `byte_idx`.
+    ///
+    /// Indices past the last line (e.g. at the end of the file) are clamped
+    /// to the last line.
     fn map_byte_index(
         &self,
         file: Option<toc_span::FileId>,
         byte_idx: usize,
     ) -> Option<(usize, Range<usize>)> {
-        self.files.get(file.as_ref()?).and_then(|(_, line_ranges)| {
-            line_ranges
-                .iter()
-                .enumerate()
-                .find(|(_line, range)| range.contains(&byte_idx))
-                .map(|(line, range)| (line, range.clone()))
+        let (_, line_map) = self.files.get(file.as_ref()?)?;
+        let (line, _) = line_map.line_col(byte_idx);
+
+        Some((line, line_map.line_range(line)))
+    }
+
+    /// Maps a span into its path and line/column location
+    fn span_location(&self, span: toc_span::Span) -> Option<toc_reporting::SpanLocation> {
+        let (info, line_map) = self.files.get(span.file.as_ref()?)?;
+
+        let line_column = |byte_idx: u32| {
+            let (line, column) = line_map.line_col(byte_idx as usize);
+
+            toc_reporting::LineColumn {
+                line: line + 1,
+                column: column + 1,
+            }
+        };
+
+        Some(toc_reporting::SpanLocation {
+            path: info.path.clone(),
+            start: line_column(span.range.start().into()),
+            end: line_column(span.range.end().into()),
         })
     }
 
+    /// Builds a snippet for rendering `msg`, with `title` as the main message text
     fn message_into_snippet<'a>(
         &'a self,
         msg: &'a toc_reporting::ReportMessage,
+        title: &'a str,
     ) -> annotate_snippets::snippet::Snippet<'a> {
         use annotate_snippets::{display_list::FormatOptions, snippet::*};
 
@@ -141,23 +374,19 @@ impl SpanMapper {
             source_slice: &'a str,
         }
 
-        let mut file_spans = vec![FileSpan {
-            span: msg.span(),
-            source_range: 0..0,
-            line_range: 0..0,
-            source_slice: "",
-        }];
+        let mut file_spans: Vec<FileSpan> = vec![];
 
-        // Merge spans together
-        for annotation in msg.annotations() {
-            let span = annotation.span();
-            let FileSpan {
-                span: last_span, ..
-            } = file_spans.last_mut().unwrap();
+        // Merge spans together, with one span per file
+        let all_spans = std::iter::once(msg.span())
+            .chain(msg.annotations().iter().map(|annotate| annotate.span()));
 
-            if span.file == last_span.file {
+        for span in all_spans {
+            if let Some(file_span) = file_spans
+                .iter_mut()
+                .find(|file_span| file_span.span.file == span.file)
+            {
                 // Merge spans
-                last_span.range = last_span.range.cover(span.range);
+                file_span.span.range = file_span.span.range.cover(span.range);
             } else {
                 // Add a new span
                 file_spans.push(FileSpan {
@@ -178,19 +407,20 @@ impl SpanMapper {
             let (start_line, start_range) = self
                 .map_byte_index(file_span.span.file, start as usize)
                 .unwrap();
-            let (end_line, end_range) = self
-                .map_byte_index(file_span.span.file, end as usize - 1)
-                .unwrap();
-
-            let source = &self
-                .files
-                .get(&file_span.span.file.unwrap())
-                .unwrap()
-                .0
-                .source;
-            file_span.source_range = start_range.start..end_range.end;
+            // Empty spans still need to be on a line
+            let last_byte = (end as usize).saturating_sub(1).max(start as usize);
+            let (end_line, end_range) =
+                self.map_byte_index(file_span.span.file, last_byte).unwrap();
+
+            // Slices are taken from the displayed source, so that tabs are expanded
+            let file = file_span.span.file.unwrap();
+            let source = self.display_source(file);
+            let source_range = self.display_index(file, start_range.start)
+                ..self.display_index(file, end_range.end);
+
+            file_span.source_slice = &source[source_range.clone()];
+            file_span.source_range = source_range;
             file_span.line_range = start_line..end_line;
-            file_span.source_slice = &source[start_range.start..end_range.end];
         }
 
         let file_spans = file_spans;
@@ -207,18 +437,19 @@ impl SpanMapper {
 
         fn span_into_annotation<'a, 'b>(
             annotate_type: AnnotationType,
-            span: toc_span::Span,
+            display_range: Range<usize>,
             label: &'a str,
             file_span: &'b FileSpan,
         ) -> SourceAnnotation<'a> {
             let FileSpan { source_range, .. } = file_span;
-            let (start, end) = (u32::from(span.range.start()), u32::from(span.range.end()));
+            let (start, end) = (display_range.start, display_range.end);
 
             let range_base = source_range.start;
-            let real_slice = (start as usize - range_base)..(end as usize - range_base);
+            let real_slice = (start - range_base)..(end - range_base);
 
             // Get the real start & end, in characters
-            // `annotate-snippets` requires that the range bounds are in characters, not byte indices
+            // `annotate-snippets` requires that the range bounds are in characters, not byte indices,
+            // and takes care of the display width of each character
             let real_start = file_span.source_slice[0..real_slice.start].chars().count();
             let real_end = real_start + file_span.source_slice[real_slice].chars().count();
 
@@ -229,21 +460,19 @@ impl SpanMapper {
             }
         }
 
-        let create_snippet = |file_span: &FileSpan| {
+        let create_snippet = |file_span: &FileSpan<'a>| {
             let FileSpan {
                 span,
-                source_range,
                 line_range,
+                source_slice,
                 ..
             } = file_span;
 
             let file = span.file.unwrap();
-            let source = &self.files.get(&file).unwrap().0.source;
-            let slice_text = &source[source_range.clone()];
             let can_fold = (line_range.end - line_range.start) > 10;
 
             Slice {
-                source: slice_text,
+                source: source_slice,
                 line_start: line_range.start + 1,
                 origin: Some(&self.files.get(&file).unwrap().0.path),
                 annotations: vec![],
@@ -251,44 +480,35 @@ impl SpanMapper {
             }
         };
 
-        let mut slices = vec![];
+        // One slice per file, in the same order as the file spans
+        let mut slices = file_spans.iter().map(create_snippet).collect::<Vec<_>>();
         let mut footer = vec![];
-        let mut report_spans = file_spans.iter().peekable();
 
-        // Insert the first slice
-        let mut current_file = msg.span().file;
+        // The primary span's label is part of the larger message
+        let all_annotations = std::iter::once((msg.kind(), msg.span(), "")).chain(
+            msg.annotations()
+                .iter()
+                .map(|annotate| (annotate.kind(), annotate.span(), annotate.message())),
+        );
 
-        {
-            let annotation = span_into_annotation(
-                annotate_kind_to_type(msg.kind()),
-                msg.span(),
-                "", // part of the larger message
-                report_spans.peek().unwrap(),
-            );
+        for (kind, span, label) in all_annotations {
+            // Always present, since every span was merged into a file span
+            let file_index = file_spans
+                .iter()
+                .position(|file_span| file_span.span.file == span.file)
+                .unwrap();
 
-            let mut slice = create_snippet(report_spans.peek().unwrap());
-            slice.annotations.push(annotation);
-            slices.push(slice);
-        }
+            let file = span.file.unwrap();
+            let display_range = self.display_index(file, span.range.start().into())
+                ..self.display_index(file, span.range.end().into());
 
-        for annotate in msg.annotations() {
             let annotation = span_into_annotation(
-                annotate_kind_to_type(annotate.kind()),
-                annotate.span(),
-                annotate.message(),
-                report_spans.peek().unwrap(),
+                annotate_kind_to_type(kind),
+                display_range,
+                label,
+                &file_spans[file_index],
             );
-
-            if current_file != annotate.span().file {
-                current_file = annotate.span().file;
-
-                let mut slice = create_snippet(report_spans.peek().unwrap());
-                slice.annotations.push(annotation);
-                slices.push(slice);
-            } else {
-                let slice = slices.last_mut().unwrap();
-                slice.annotations.push(annotation);
-            }
+            slices[file_index].annotations.push(annotation);
         }
 
         for annotate in msg.footer() {
@@ -299,9 +519,9 @@ impl SpanMapper {
             });
         }
 
-        let snippet = Snippet {
+        Snippet {
             title: Some(Annotation {
-                label: Some(msg.message()),
+                label: Some(title),
                 id: None,
                 annotation_type: annotate_kind_to_type(msg.kind()),
             }),
@@ -311,8 +531,260 @@ impl SpanMapper {
                 color: true,
                 ..Default::default()
             },
-        };
+        }
+    }
+}
+
+/// Source text with tabs expanded into spaces
+struct ExpandedSource {
+    text: String,
+    /// Maps byte indices in the original source into ones in `text`
+    offsets: Vec<usize>,
+}
 
-        snippet
+impl ExpandedSource {
+    fn new(source: &str, tab_width: usize) -> Self {
+        use unicode_width::UnicodeWidthChar;
+
+        let mut text = String::with_capacity(source.len());
+        let mut offsets = Vec::with_capacity(source.len() + 1);
+        let mut column = 0;
+
+        for ch in source.chars() {
+            offsets.extend(std::iter::repeat_n(text.len(), ch.len_utf8()));
+
+            match ch {
+                '\t' => {
+                    // Go up to the next tab stop
+                    let spaces = tab_width - column % tab_width;
+                    text.extend(std::iter::repeat_n(' ', spaces));
+                    column += spaces;
+                }
+                '\n' => {
+                    text.push(ch);
+                    column = 0;
+                }
+                _ => {
+                    // Wide characters take up more than one column
+                    text.push(ch);
+                    column += ch.width().unwrap_or(0);
+                }
+            }
+        }
+
+        offsets.push(text.len());
+        Self { text, offsets }
     }
 }
+
+#[cfg(test)]
+fn render_message(source: &str, span: std::ops::Range<u32>) -> String {
+    let file_db = FileDb::new();
+    let file = file_db.add_file("test.t", source);
+    let span_mapper = SpanMapper::new(&file_db);
+
+    let mut sink = MessageSink::new(Stage::Parse);
+    let range = toc_span::TextRange::new(span.start.into(), span.end.into());
+    sink.error("bad thing", toc_span::Span::new(Some(file), range));
+    let msgs = sink.finish();
+
+    // Without colors, so that columns are easier to check
+    let mut snippet = span_mapper.message_into_snippet(&msgs[0], msgs[0].message());
+    snippet.opt.color = false;
+    annotate_snippets::display_list::DisplayList::from(snippet).to_string()
+}
+
+/// Finds the columns of the underline in a rendered snippet, relative to the source text
+#[cfg(test)]
+fn underline_columns(rendered: &str) -> Range<usize> {
+    let lines: Vec<_> = rendered.lines().collect();
+    let underline = lines.iter().position(|line| line.contains('^')).unwrap();
+    // Source text starts after the gutter on the line above
+    let text_start = lines[underline - 1].find('|').unwrap() + 2;
+
+    let start = lines[underline].find('^').unwrap();
+    let end = lines[underline].rfind('^').unwrap() + 1;
+    (start - text_start)..(end - text_start)
+}
+
+#[test]
+fn span_at_end_of_file() {
+    let source = "var a := 1\nvar b :=";
+    let len = source.len() as u32;
+
+    assert!(render_message(source, len..len).contains("bad thing"));
+    assert!(render_message("var a\n", 6..6).contains("bad thing"));
+}
+
+#[test]
+fn render_tab_indented_line() {
+    let source = "begin\n\tvar a := 1\nend\n";
+    let rendered = render_message(source, 11..12);
+
+    // Tabs are expanded into spaces, and the caret is under `a`
+    assert!(rendered.contains("    var a := 1"), "{}", rendered);
+    assert!(!rendered.contains('\t'), "{}", rendered);
+    assert_eq!(underline_columns(&rendered), 8..9, "{}", rendered);
+}
+
+#[test]
+fn render_tab_after_wide_chars() {
+    // Tab stops are in display columns, so `変数` takes up the first 4 columns
+    let source = "変数\t:= 1\n";
+    let rendered = render_message(source, 7..9);
+
+    assert!(rendered.contains("変数    := 1"), "{}", rendered);
+    assert_eq!(underline_columns(&rendered), 8..10, "{}", rendered);
+}
+
+#[test]
+fn render_wide_identifier() {
+    let source = "var 変数 := 1\n";
+    let rendered = render_message(source, 4..10);
+
+    // Each character is two columns wide
+    assert_eq!(underline_columns(&rendered), 4..8, "{}", rendered);
+
+    // Carets after the identifier are also shifted over
+    let rendered = render_message(source, 11..13);
+    assert_eq!(underline_columns(&rendered), 9..11, "{}", rendered);
+}
+
+#[test]
+fn span_locations_with_tabs() {
+    // JSON locations are unaffected by tab expansion
+    let file_db = FileDb::new();
+    let file = file_db.add_file("a.t", "\tvar a\n");
+    let span_mapper = SpanMapper::with_tab_width(&file_db, 8);
+
+    let range = toc_span::TextRange::new(5.into(), 6.into());
+    let location = span_mapper
+        .span_location(toc_span::Span::new(Some(file), range))
+        .unwrap();
+    assert_eq!((location.start.line, location.start.column), (1, 6));
+}
+
+#[test]
+fn span_in_empty_file() {
+    assert!(render_message("", 0..0).contains("bad thing"));
+}
+
+#[test]
+fn annotations_across_files() {
+    let file_db = FileDb::new();
+    let file_a = file_db.add_file("a.t", "var a := 1\nvar b := 2\n");
+    let file_b = file_db.add_file("b.t", "var c := 3\n");
+    let span_mapper = SpanMapper::new(&file_db);
+
+    let span_at = |file, start: u32, end: u32| {
+        toc_span::Span::new(
+            Some(file),
+            toc_span::TextRange::new(start.into(), end.into()),
+        )
+    };
+
+    let mut sink = MessageSink::new(Stage::Parse);
+    sink.error_detailed("bad thing", span_at(file_a, 4, 5))
+        .with_note("in b", span_at(file_b, 4, 5))
+        .with_note("back in a", span_at(file_a, 15, 16))
+        .with_note("in b again", span_at(file_b, 9, 10))
+        .finish();
+    let msgs = sink.finish();
+
+    let snippet = span_mapper.message_into_snippet(&msgs[0], msgs[0].message());
+    let slices = snippet
+        .slices
+        .iter()
+        .map(|slice| {
+            let labels = slice
+                .annotations
+                .iter()
+                .map(|annotation| annotation.label)
+                .collect::<Vec<_>>();
+
+            (slice.origin.unwrap(), labels)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        slices,
+        vec![
+            ("a.t", vec!["", "back in a"]),
+            ("b.t", vec!["in b", "in b again"])
+        ]
+    );
+}
+
+#[test]
+fn span_locations() {
+    let file_db = FileDb::new();
+    let file = file_db.add_file("a.t", "var a := 1\nvar ü := 2\n");
+    let span_mapper = SpanMapper::new(&file_db);
+
+    let location = |start: u32, end: u32| {
+        let range = toc_span::TextRange::new(start.into(), end.into());
+        let location = span_mapper
+            .span_location(toc_span::Span::new(Some(file), range))
+            .unwrap();
+
+        (
+            location.path,
+            (location.start.line, location.start.column),
+            (location.end.line, location.end.column),
+        )
+    };
+
+    assert_eq!(location(4, 5), ("a.t".to_string(), (1, 5), (1, 6)));
+    // Columns are in chars, not bytes
+    assert_eq!(location(19, 20), ("a.t".to_string(), (2, 8), (2, 9)));
+    // End of the file
+    assert_eq!(location(23, 23), ("a.t".to_string(), (2, 12), (2, 12)));
+    assert_eq!(
+        span_mapper.span_location(toc_span::Span::new(None, Default::default())),
+        None
+    );
+}
+
+#[test]
+fn span_locations_bom_and_crlf() {
+    let file_db = FileDb::new();
+    let bom_file = file_db.add_file("bom.t", "\u{FEFF}var a := 1\n");
+    let crlf_file = file_db.add_file("crlf.t", "var a\r\nvar b\r\n");
+    let span_mapper = SpanMapper::new(&file_db);
+
+    let location = |file, start: u32, end: u32| {
+        let range = toc_span::TextRange::new(start.into(), end.into());
+        let location = span_mapper
+            .span_location(toc_span::Span::new(Some(file), range))
+            .unwrap();
+
+        (
+            (location.start.line, location.start.column),
+            (location.end.line, location.end.column),
+        )
+    };
+
+    // The BOM isn't part of the first column
+    assert_eq!(location(bom_file, 0, 3), ((1, 1), (1, 4)));
+    // CRLF line endings only take up one column
+    assert_eq!(location(crlf_file, 4, 6), ((1, 5), (1, 6)));
+    assert_eq!(location(crlf_file, 11, 12), ((2, 5), (2, 6)));
+    assert!(render_message("var a\r\nvar b\r\n", 11..12).contains("bad thing"));
+}
+
+#[test]
+fn render_stdin_origin() {
+    let file_db = FileDb::new();
+    let stdin: &[u8] = b"var a := 1\n";
+    let (file, _) =
+        toc_driver::load_contents(&file_db, toc_driver::STDIN_PATH, stdin, false).unwrap();
+    let span_mapper = SpanMapper::new(&file_db);
+
+    let mut sink = MessageSink::new(Stage::Parse);
+    let range = toc_span::TextRange::new(4.into(), 5.into());
+    sink.error("bad thing", toc_span::Span::new(Some(file), range));
+    let msgs = sink.finish();
+
+    let snippet = span_mapper.message_into_snippet(&msgs[0], msgs[0].message());
+    assert_eq!(snippet.slices[0].origin, Some("<stdin>"));
+}
@@ -0,0 +1,162 @@
+//! Wall clock timings for each stage of compilation
+
+use std::time::{Duration, Instant};
+
+/// A stage of compilation that is timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedStage {
+    /// Loading source files into the file db
+    VfsLoad,
+    Parse,
+    Validate,
+    Lower,
+    Analyze,
+    /// Rendering the reported messages
+    Render,
+}
+
+impl TimedStage {
+    /// Every stage, in the order that they are run
+    pub const ALL: [TimedStage; 6] = [
+        TimedStage::VfsLoad,
+        TimedStage::Parse,
+        TimedStage::Validate,
+        TimedStage::Lower,
+        TimedStage::Analyze,
+        TimedStage::Render,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TimedStage::VfsLoad => "vfs load",
+            TimedStage::Parse => "parse",
+            TimedStage::Validate => "validate",
+            TimedStage::Lower => "lower",
+            TimedStage::Analyze => "analyze",
+            TimedStage::Render => "render",
+        }
+    }
+}
+
+/// Records how long each stage takes, starting from when the timer is created.
+///
+/// Time spent in the same stage is added together, so a stage can be timed in
+/// multiple pieces (e.g. when loading and parsing imported files one at a time).
+#[derive(Debug)]
+pub struct StageTimer {
+    start: Instant,
+    stages: [Duration; TimedStage::ALL.len()],
+}
+
+impl StageTimer {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            stages: Default::default(),
+        }
+    }
+
+    /// Runs `f`, adding the time it took to `stage`
+    pub fn time<T>(&mut self, stage: TimedStage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let res = f();
+        self.add(stage, start.elapsed());
+        res
+    }
+
+    /// Adds `duration` to the time spent in `stage`
+    pub fn add(&mut self, stage: TimedStage, duration: Duration) {
+        self.stages[stage as usize] += duration;
+    }
+
+    /// Adds the stage times from `timings`, such as from [`crate::CompileResult::timings`].
+    ///
+    /// The total time of `timings` is ignored, since it is already covered by this timer.
+    pub fn add_timings(&mut self, timings: &Timings) {
+        for (stage, duration) in timings.stages() {
+            self.add(stage, duration);
+        }
+    }
+
+    /// Gets the stage times so far, with the total being the time since the timer was created
+    pub fn finish(&self) -> Timings {
+        Timings {
+            stages: self.stages,
+            total: self.start.elapsed(),
+        }
+    }
+}
+
+impl Default for StageTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How long each stage took, along with the total time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    stages: [Duration; TimedStage::ALL.len()],
+    total: Duration,
+}
+
+impl Timings {
+    /// Time spent in `stage`, which is zero if the stage was never run
+    pub fn stage(&self, stage: TimedStage) -> Duration {
+        self.stages[stage as usize]
+    }
+
+    /// Time spent in each stage, in the order that the stages are run
+    pub fn stages(&self) -> impl Iterator<Item = (TimedStage, Duration)> + '_ {
+        TimedStage::ALL
+            .iter()
+            .map(move |&stage| (stage, self.stage(stage)))
+    }
+
+    /// Total wall clock time, including any time spent outside of the stages
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Formats the timings as a table, with times in milliseconds
+    pub fn to_table(&self) -> String {
+        use std::fmt::Write;
+
+        let mut table = format!("{:<10} {:>12}\n", "stage", "time (ms)");
+        let rows = self
+            .stages()
+            .map(|(stage, duration)| (stage.name(), duration))
+            .chain(std::iter::once(("total", self.total)));
+
+        for (name, duration) in rows {
+            writeln!(table, "{:<10} {:>12.3}", name, as_millis(duration)).unwrap();
+        }
+
+        table
+    }
+
+    /// Formats the timings as a JSON object, with times in milliseconds
+    pub fn to_json(&self) -> String {
+        use serde_json::json;
+
+        let stages = self
+            .stages()
+            .map(|(stage, duration)| {
+                json!({
+                    "stage": stage.name(),
+                    "ms": as_millis(duration),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "stages": stages,
+            "total_ms": as_millis(self.total),
+        })
+        .to_string()
+    }
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,186 @@
+//! Opinionated formatter for Turing source
+//!
+//! Only the layout of lines is changed: statements are indented by how deeply they
+//! are nested inside of blocks and `case` arms, trailing whitespace is removed, and
+//! runs of blank lines are collapsed into one. Everything else, including comments
+//! and the spacing within each line, is printed back out as is.
+#[cfg(test)]
+mod test;
+
+use toc_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// Number of spaces in each level of indentation
+const INDENT_WIDTH: usize = 4;
+
+/// Formats a syntax tree, giving back the formatted source text.
+///
+/// Lines starting a statement or a part of a block (e.g. `else` or `end if`) are
+/// indented by how deeply nested they are, and any other lines keep their
+/// indentation relative to the line before them that was re-indented. Comments
+/// on their own line are indented along with the statements around them.
+pub fn format(root: &SyntaxNode) -> String {
+    let newline = if root.text().contains_char('\r') {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines = split_lines(root);
+    let mut out = String::new();
+    // How far the last re-indented line was moved over
+    let mut shift = 0;
+
+    for (idx, line) in lines.iter().enumerate() {
+        let first = match line.tokens.first() {
+            Some(first) => first,
+            // Blank lines are only kept in between other lines
+            None => continue,
+        };
+
+        if !out.is_empty() {
+            out.push_str(newline);
+
+            if line.blank_before {
+                out.push_str(newline);
+            }
+        }
+
+        let indent = if first.kind() == SyntaxKind::Comment {
+            comment_depth(&lines, idx) * INDENT_WIDTH
+        } else if starts_line_group(first) {
+            let indent = block_depth(first) * INDENT_WIDTH;
+            shift = indent as isize - line.indent as isize;
+            indent
+        } else {
+            (line.indent as isize + shift).max(0) as usize
+        };
+
+        out.extend(std::iter::repeat_n(' ', indent));
+
+        for token in &line.tokens {
+            out.push_str(token.text());
+        }
+    }
+
+    if !out.is_empty() {
+        out.push_str(newline);
+    }
+
+    out
+}
+
+/// A line of tokens from the original source
+struct Line {
+    /// Tokens on the line, without the indentation or trailing whitespace
+    tokens: Vec<SyntaxToken>,
+    /// Width of the original indentation, in columns
+    indent: usize,
+    /// If there are any blank lines right before this line
+    blank_before: bool,
+}
+
+fn split_lines(root: &SyntaxNode) -> Vec<Line> {
+    let new_line = |indent: &str, blank_before| Line {
+        tokens: vec![],
+        indent: indent_width(indent),
+        blank_before,
+    };
+
+    let mut lines = vec![];
+    let mut line = new_line("", false);
+
+    for token in root
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+    {
+        if token.kind() != SyntaxKind::Whitespace {
+            line.tokens.push(token);
+            continue;
+        }
+
+        match token.text().rfind('\n') {
+            Some(last_newline) => {
+                // Whitespace before the newline is trailing, and after is the next line's indentation
+                let blank_before = token.text().matches('\n').count() > 1;
+                let indent = &token.text()[last_newline + 1..];
+
+                lines.push(std::mem::replace(&mut line, new_line(indent, blank_before)));
+            }
+            // Indentation of the first line
+            None if line.tokens.is_empty() => line.indent = indent_width(token.text()),
+            None => line.tokens.push(token),
+        }
+    }
+
+    // Whitespace at the end of the last line is trailing
+    if line.tokens.last().map(|token| token.kind()) == Some(SyntaxKind::Whitespace) {
+        line.tokens.pop();
+    }
+
+    lines.push(line);
+    lines
+}
+
+fn indent_width(indent: &str) -> usize {
+    indent.chars().fold(0, |width, ch| match ch {
+        '\t' => (width / INDENT_WIDTH + 1) * INDENT_WIDTH,
+        _ => width + 1,
+    })
+}
+
+/// Checks if `token` starts a statement or a part of a block statement,
+/// which are always indented by how deeply nested they are
+fn starts_line_group(token: &SyntaxToken) -> bool {
+    token
+        .ancestors()
+        .take_while(|node| node.first_token().as_ref() == Some(token))
+        .any(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::EndGroup
+                    | SyntaxKind::ElseifStmt
+                    | SyntaxKind::ElseStmt
+                    | SyntaxKind::CaseArm
+            ) || node.parent().map(|parent| parent.kind()) == Some(SyntaxKind::StmtList)
+        })
+}
+
+/// Number of indentation levels that `token` is nested in, with each nested
+/// statement list and `case` arm being one level
+fn block_depth(token: &SyntaxToken) -> usize {
+    token
+        .ancestors()
+        .filter(|node| match node.kind() {
+            SyntaxKind::CaseArm => true,
+            // Top-level statements aren't indented
+            SyntaxKind::StmtList => {
+                node.parent().map(|parent| parent.kind()) != Some(SyntaxKind::Source)
+            }
+            _ => false,
+        })
+        .count()
+}
+
+/// Finds the indentation depth of a comment on its own line.
+///
+/// Comments are indented to the deeper of the code before or after them, so that
+/// comments at the end of a block stay inside of the block.
+fn comment_depth(lines: &[Line], idx: usize) -> usize {
+    let is_code = |token: &&SyntaxToken| {
+        !matches!(token.kind(), SyntaxKind::Comment | SyntaxKind::Whitespace)
+    };
+
+    let before = lines[..idx]
+        .iter()
+        .rev()
+        .find_map(|line| line.tokens.iter().rev().find(is_code));
+    let after = lines[idx..]
+        .iter()
+        .find_map(|line| line.tokens.iter().find(is_code));
+
+    before
+        .into_iter()
+        .chain(after)
+        .map(block_depth)
+        .max()
+        .unwrap_or(0)
+}
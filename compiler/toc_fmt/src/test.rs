@@ -0,0 +1,140 @@
+//! Formatter tests
+use expect_test::{expect, Expect};
+
+#[track_caller]
+fn check(source: &str, expected: Expect) {
+    let parsed = toc_parser::parse(None, source);
+    let formatted = crate::format(&parsed.syntax());
+    expected.assert_eq(&formatted);
+
+    // Formatting is idempotent
+    let parsed = toc_parser::parse(None, &formatted);
+    assert_eq!(crate::format(&parsed.syntax()), formatted);
+}
+
+#[test]
+fn format_empty() {
+    check("", expect![[""]]);
+    check("  \n\n\t", expect![[""]]);
+}
+
+#[test]
+fn format_if_else_chain() {
+    check(
+        "  if a then\nput 1\n      elsif b then\n\t\tput 2\n  put 3   \nelse    put 4\n        end if\n",
+        expect![[r#"
+            if a then
+                put 1
+            elsif b then
+                put 2
+                put 3
+            else    put 4
+            end if
+        "#]],
+    );
+}
+
+#[test]
+fn format_nested_loop() {
+    check(
+        "loop\nloop\nexit when a\n        put b\n end loop\n\n\n\n  exit\nend loop",
+        expect![[r#"
+            loop
+                loop
+                    exit when a
+                    put b
+                end loop
+
+                exit
+            end loop
+        "#]],
+    );
+}
+
+#[test]
+fn format_case_arms() {
+    check(
+        "case a of\nlabel 1:\nput 1\nlabel 2, 3: put 2\n put 3\n          label :\nend case\n",
+        expect![[r#"
+            case a of
+                label 1:
+                    put 1
+                label 2, 3: put 2
+                    put 3
+                label :
+            end case
+        "#]],
+    );
+}
+
+#[test]
+fn format_blocks() {
+    check(
+        "begin\nvar a := 1\n  begin\n      put a\nend\nend\nbegin put 1 end\n",
+        expect![[r#"
+            begin
+                var a := 1
+                begin
+                    put a
+                end
+            end
+            begin put 1 end
+        "#]],
+    );
+}
+
+#[test]
+fn format_comments() {
+    check(
+        "% leading\nbegin\n% inside\n      put 1 % trailing\n  /* before end */\nend\n   % after\nput /* inline */ 2\n",
+        expect![[r#"
+            % leading
+            begin
+                % inside
+                put 1 % trailing
+                /* before end */
+            end
+            % after
+            put /* inline */ 2
+        "#]],
+    );
+}
+
+#[test]
+fn format_continuation_lines() {
+    // Other lines keep their indentation relative to the start of their statement
+    check(
+        "begin\n        put 1,\n            2\nvar a : record\n  f : int\nend record\nend\n",
+        expect![[r#"
+            begin
+                put 1,
+                    2
+                var a : record
+                  f : int
+                end record
+            end
+        "#]],
+    );
+}
+
+#[test]
+fn format_blank_lines() {
+    check(
+        "\n\nput 1\n\n\n\nput 2\n\n\n",
+        expect![[r#"
+        put 1
+
+        put 2
+    "#]],
+    );
+}
+
+#[test]
+fn format_keeps_crlf() {
+    // Expect strings can't hold carriage returns, so compare directly
+    let parsed = toc_parser::parse(None, "begin\r\nput 1\r\n\r\n\r\nend\r\n");
+    assert_eq!(
+        crate::format(&parsed.syntax()),
+        "begin\r\n    put 1\r\n\r\nend\r\n"
+    );
+}
@@ -20,6 +20,13 @@ pub struct HirBuilder {
 struct Inner {
     arena: Arena<HirNode>,
     spans: IndexMap<Idx<HirNode>, Span>,
+    /// Statements inside of `unchecked` regions, along with the span of
+    /// the `unchecked` statement starting the region
+    unchecked_stmts: IndexMap<Idx<HirNode>, Span>,
+    /// Units imported by each unit
+    unit_imports: IndexMap<Idx<HirNode>, Vec<unit::UnitId>>,
+    /// Names of the units declared as `unit module`s
+    unit_names: IndexMap<Idx<HirNode>, String>,
 }
 
 impl HirBuilder {
@@ -53,6 +60,28 @@ impl HirBuilder {
         stmt::StmtId(HirId(idx))
     }
 
+    /// Marks a statement as being inside of the `unchecked` region started at `region`
+    pub fn mark_unchecked(&self, id: stmt::StmtId, region: Span) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.unchecked_stmts.insert(HirId::from(id).0, region);
+    }
+
+    /// Records that `unit` imports `imported`
+    pub fn add_unit_import(&self, unit: unit::UnitId, imported: unit::UnitId) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .unit_imports
+            .entry(HirId::from(unit).0)
+            .or_default()
+            .push(imported);
+    }
+
+    /// Records the name that `unit` is declared with
+    pub fn set_unit_name(&self, unit: unit::UnitId, name: String) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.unit_names.insert(HirId::from(unit).0, name);
+    }
+
     pub fn add_unit_with<F>(&self, make_unit: F, span: Span) -> unit::UnitId
     where
         F: FnOnce(unit::UnitId) -> unit::Unit,
@@ -119,12 +148,62 @@ impl HirDb {
         self.get_node(id.into()).as_unit().unwrap()
     }
 
+    /// Gets the checkedness of a statement
+    pub fn get_checkedness(&self, id: stmt::StmtId) -> stmt::Checkedness {
+        if self.inner.unchecked_stmts.contains_key(&HirId::from(id).0) {
+            stmt::Checkedness::Unchecked
+        } else {
+            stmt::Checkedness::Checked
+        }
+    }
+
+    /// Gets the span of the `unchecked` statement starting the region that
+    /// the statement is in, or `None` if the statement is checked
+    pub fn get_unchecked_region(&self, id: stmt::StmtId) -> Option<Span> {
+        self.inner.unchecked_stmts.get(&HirId::from(id).0).copied()
+    }
+
+    /// Iterates over all statements inside of `unchecked` regions
+    pub fn unchecked_stmts(&self) -> impl Iterator<Item = stmt::StmtId> + '_ {
+        self.inner
+            .unchecked_stmts
+            .keys()
+            .map(|idx| stmt::StmtId(HirId(*idx)))
+    }
+
+    /// Gets the units directly imported by `unit`
+    pub fn unit_imports(&self, unit: unit::UnitId) -> &[unit::UnitId] {
+        self.inner
+            .unit_imports
+            .get(&HirId::from(unit).0)
+            .map_or(&[], |imports| imports.as_slice())
+    }
+
+    /// Gets the name that `unit` is declared with, if it has one
+    pub fn unit_name(&self, unit: unit::UnitId) -> Option<&str> {
+        self.inner
+            .unit_names
+            .get(&HirId::from(unit).0)
+            .map(String::as_str)
+    }
+
     pub fn nodes(&self) -> impl Iterator<Item = (HirId, &HirNode)> {
         self.inner.arena.iter().map(|(id, node)| (HirId(id), node))
     }
+
+    /// Replaces the expression node at `id`, keeping the original span
+    ///
+    /// ## Panics
+    /// Panics if there are any other references to the database
+    pub fn replace_expr(&mut self, id: expr::ExprId, node: expr::Expr) {
+        let inner =
+            Arc::get_mut(&mut self.inner).expect("still existing references to the database");
+        inner.arena[HirId::from(id).0] = HirNode::Expr(node);
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(
     // Already stored inside of an arena, which absorbs the cost of a large variant
     clippy::large_enum_variant
@@ -174,3 +253,11 @@ impl HirNode {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct HirId(Idx<HirNode>);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for HirId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Serialized as the index of the node in the arena
+        serializer.serialize_u32(u32::from(self.0.into_raw()))
+    }
+}
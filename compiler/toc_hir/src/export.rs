@@ -0,0 +1,73 @@
+//! Exporting of the HIR for external tools
+//!
+//! The HIR is exported as JSON, so that it can be read from any language and
+//! doesn't depend on the byte order of the host. Arena ids (e.g. `ExprId` or
+//! `DefId`) are exported as plain integers, which index into the arrays that
+//! the nodes and symbols are exported in.
+//!
+//! Only available with the `serde` feature.
+
+use serde::Serialize;
+use toc_span::Span;
+
+use crate::db::{HirDb, HirNode};
+use crate::unit::UnitId;
+use crate::HirId;
+
+/// Version of the exported format.
+///
+/// Bumped whenever the layout of the exported HIR changes, so that consumers
+/// can detect when they need to be updated.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct ExportedHir<'a> {
+    format_version: u32,
+    /// Unit being exported
+    unit: UnitId,
+    /// Name that the unit is declared with, if it has one
+    unit_name: Option<&'a str>,
+    /// Units directly imported by the unit
+    imports: &'a [UnitId],
+    /// Every node in the database, indexed by `HirId`
+    nodes: Vec<ExportedNode<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedNode<'a> {
+    span: Span,
+    /// Span of the `unchecked` statement starting the region that a statement is in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unchecked_region: Option<Span>,
+    node: &'a HirNode,
+}
+
+/// Exports `unit` as a JSON object, with a top-level `format_version` field.
+///
+/// Ids are shared between all units in the database, so every node in `db`
+/// is exported, including ones from other units.
+pub fn to_json(db: &HirDb, unit: UnitId) -> String {
+    let nodes = db
+        .nodes()
+        .map(|(id, node)| ExportedNode {
+            span: db.get_span(id),
+            unchecked_region: unchecked_region(db, id, node),
+            node,
+        })
+        .collect();
+
+    let exported = ExportedHir {
+        format_version: FORMAT_VERSION,
+        unit,
+        unit_name: db.unit_name(unit),
+        imports: db.unit_imports(unit),
+        nodes,
+    };
+
+    serde_json::to_string(&exported).expect("HIR should always be serializable")
+}
+
+fn unchecked_region(db: &HirDb, id: HirId, node: &HirNode) -> Option<Span> {
+    node.as_stmt()?;
+    db.get_unchecked_region(crate::stmt::StmtId(id))
+}
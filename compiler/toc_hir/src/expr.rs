@@ -1,37 +1,50 @@
 //! Expression nodes
 use toc_span::Spanned;
 
-use crate::symbol;
+use crate::{symbol, ty};
 
 crate::hir_id_wrapper!(ExprId);
 
 /// Expressions
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Expr {
     /// Error expression, only used to represent invalid code
     Missing,
     /// Literal values
     Literal(Literal),
     //ObjClass(ObjClass),
-    //Init(Init),
-    //Nil(Nil),
-    //SizeOf(SizeOf),
+    /// Aggregate initializer (`init(exprs)`), only valid as a declaration's initializer
+    Init(Init),
+    /// Nil pointer (`nil` or `nil(collection)`)
+    Nil(Nil),
+    /// Call to a builtin function (e.g. `sizeof(ty)` or `upper(a)`)
+    Builtin(Builtin),
     Binary(Binary),
     Unary(Unary),
     Paren(Paren),
     /// `self` is a special case of a name expression
     Name(Name),
-    //Field(Field),
-    //Deref(Deref),
+    /// Field access (`lhs.field`)
+    Field(Field),
+    /// Pointer following (`^expr`)
+    Deref(Deref),
     //Cheat(Cheat),
-    //NatCheat(NatCheat),
+    /// Reinterpretation as a natural number of the same size (`#expr`)
+    NatCheat(NatCheat),
     //Arrow(Arrow),
-    //Indirect(Indirect),
-    //Bits(Bits),
-    //Call(Call),
+    /// Indirection through a memory address (`ty @ (addr)`)
+    Indirect(Indirect),
+    /// Bit extraction (`bits(lhs, bits)`)
+    Bits(Bits),
+    /// Array subscript, or call to a subprogram (`lhs(arguments)`)
+    Call(Call),
+    /// Substring of a character sequence (`lhs(start .. end)`)
+    Substring(Substring),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Literal {
     Integer(u64),
     Real(f64),
@@ -43,6 +56,7 @@ pub enum Literal {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Binary {
     pub lhs: ExprId,
     pub op: Spanned<BinaryOp>,
@@ -50,6 +64,7 @@ pub struct Binary {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum BinaryOp {
     /// Addition / Set Union / String Concatenation (`+`)
     Add,
@@ -98,12 +113,14 @@ pub enum BinaryOp {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Unary {
     pub op: Spanned<UnaryOp>,
     pub rhs: ExprId,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum UnaryOp {
     /// Binary/boolean negation operator (`not`)
     Not,
@@ -114,12 +131,152 @@ pub enum UnaryOp {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Builtin {
+    pub func: Spanned<BuiltinFn>,
+    pub args: Vec<BuiltinArg>,
+}
+
+/// Functions which are always available, unless shadowed by a declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BuiltinFn {
+    /// Address of a reference (`addr`)
+    Addr,
+    /// Size of a type or reference, in bytes (`sizeof`)
+    SizeOf,
+    /// Upper bound of an array or range (`upper`)
+    Upper,
+    /// Lower bound of an array or range (`lower`)
+    Lower,
+    /// Ordinal value of a character (`ord`)
+    Ord,
+    /// Character with an ordinal value (`chr`)
+    Chr,
+    /// Number of characters in a string, or elements in an array (`length`)
+    Length,
+    /// Position of a pattern in a string (`index`)
+    Index,
+    /// String repeated a number of times (`repeat`)
+    Repeat,
+    /// Square root of a number (`sqrt`)
+    Sqrt,
+    /// Sine of an angle in radians (`sin`)
+    Sin,
+    /// Cosine of an angle in radians (`cos`)
+    Cos,
+    /// Natural exponential of a number (`exp`)
+    Exp,
+    /// Natural logarithm of a number (`ln`)
+    Ln,
+    /// Number rounded to the nearest integer (`round`)
+    Round,
+    /// Largest integer not greater than a number (`floor`)
+    Floor,
+    /// Smallest integer not less than a number (`ceil`)
+    Ceil,
+    /// Absolute value of a number (`abs`)
+    Abs,
+    /// Larger of two numbers (`max`)
+    Max,
+    /// Smaller of two numbers (`min`)
+    Min,
+}
+
+impl BuiltinFn {
+    /// Looks up a builtin function called by name.
+    ///
+    /// `sizeof` is a keyword, so it can't be called by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "addr" => Self::Addr,
+            "upper" => Self::Upper,
+            "lower" => Self::Lower,
+            "ord" => Self::Ord,
+            "chr" => Self::Chr,
+            "length" => Self::Length,
+            "index" => Self::Index,
+            "repeat" => Self::Repeat,
+            "sqrt" => Self::Sqrt,
+            "sin" => Self::Sin,
+            "cos" => Self::Cos,
+            "exp" => Self::Exp,
+            "ln" => Self::Ln,
+            "round" => Self::Round,
+            "floor" => Self::Floor,
+            "ceil" => Self::Ceil,
+            "abs" => Self::Abs,
+            "max" => Self::Max,
+            "min" => Self::Min,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Addr => "addr",
+            Self::SizeOf => "sizeof",
+            Self::Upper => "upper",
+            Self::Lower => "lower",
+            Self::Ord => "ord",
+            Self::Chr => "chr",
+            Self::Length => "length",
+            Self::Index => "index",
+            Self::Repeat => "repeat",
+            Self::Sqrt => "sqrt",
+            Self::Sin => "sin",
+            Self::Cos => "cos",
+            Self::Exp => "exp",
+            Self::Ln => "ln",
+            Self::Round => "round",
+            Self::Floor => "floor",
+            Self::Ceil => "ceil",
+            Self::Abs => "abs",
+            Self::Max => "max",
+            Self::Min => "min",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BuiltinArg {
+    Expr(ExprId),
+    /// Structured type, which can't be written as an expression
+    Type(ty::TypeId),
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Deref {
+    /// Pointer to follow
+    pub expr: ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NatCheat {
+    pub expr: ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Paren {
     pub expr: ExprId,
 }
 
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Field {
+    /// Expression to access the field of
+    pub lhs: ExprId,
+    /// Name of the accessed field
+    pub field: Spanned<String>,
+}
+
 /// Name expression
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Name {
     /// Normal identifier reference
     Name(symbol::UseId),
@@ -127,3 +284,73 @@ pub enum Name {
     // TODO: Link a use-id to the appropriate class DefId
     Self_,
 }
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Call {
+    /// Expression being subscripted or called
+    pub lhs: ExprId,
+    pub arguments: Vec<ExprId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Substring {
+    /// Expression to take the substring of
+    pub lhs: ExprId,
+    /// Start of the substring, inclusive
+    pub start: SubstringBound,
+    /// End of the substring, inclusive
+    pub end: SubstringBound,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SubstringBound {
+    /// Bound relative to the start of the string (`expr`)
+    FromStart(ExprId),
+    /// Bound relative to the end of the string (`*` or `* - expr`),
+    /// where `*` is the length of the string
+    FromEnd(Option<ExprId>),
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Init {
+    /// Initial values, in element or field order
+    pub exprs: Vec<ExprId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Nil {
+    /// Collection or class that the nil pointer belongs to, if specified
+    pub collection: Option<ty::TypeId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Indirect {
+    /// Type of the value at the address
+    pub ty: ty::TypeId,
+    /// Address to take the value from
+    pub addr: ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Bits {
+    /// Expression to take the bits of
+    pub lhs: ExprId,
+    /// Bits to take
+    pub bits: BitsSelector,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BitsSelector {
+    /// Single bit, or a subrange type giving the bit range (`expr`)
+    Expr(ExprId),
+    /// Range of bits (`start .. end`), inclusive
+    Range(ExprId, ExprId),
+}
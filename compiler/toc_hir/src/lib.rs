@@ -11,6 +11,7 @@ pub(crate) mod internals {
     macro_rules! hir_id_wrapper {
         ($id:ident) => {
             #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize))]
             pub struct $id(pub(crate) $crate::db::HirId);
 
             impl From<$id> for $crate::db::HirId {
@@ -29,6 +30,8 @@ pub(crate) mod internals {
 }
 
 pub mod db;
+#[cfg(feature = "serde")]
+pub mod export;
 pub mod expr;
 pub mod stmt;
 pub mod symbol;
@@ -1,25 +1,31 @@
 //! Statement nodes
-use toc_span::Spanned;
+use toc_span::{Span, Spanned};
 
 use crate::{expr, symbol, ty};
 
 crate::hir_id_wrapper!(StmtId);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Stmt {
     /// Combined representation for `const` and `var` declarations
     /// (disambiguated by `is_const`)
     ConstVar(ConstVar),
-    // Type { .. },
-    // Bind { .. },
-    // Proc { .. },
-    // Fcn { .. },
-    // Process { .. },
+    /// Type declaration
+    Type(Type),
+    /// Bind declaration, aliasing references under new names
+    Bind(Bind),
+    /// Procedure or function declaration
+    Subprogram(Subprogram),
+    /// Process declaration, only started by `fork` statements
+    Process(Process),
     // External { .. },
-    // Forward { .. },
+    /// Forward subprogram declaration, resolved by a later `body` declaration
+    Forward(Forward),
     // Deferred { .. },
-    // Body { .. },
-    // Module { .. },
+    // `body` declarations are lowered as `Subprogram`s resolving their `Forward`
+    /// Module declaration, with the body run in place
+    Module(Module),
     // Class { .. },
     // Monitor { .. },
     /// Assignment statement
@@ -35,27 +41,40 @@ pub enum Stmt {
     // Write { .. },
     // Seek { .. },
     // Tell { .. },
-    // For { .. },
-    // Loop { .. },
-    // Exit { .. },
-    // If { .. },
-    // Case { .. },
+    /// For-loop statement, counting through a range of values
+    For(For),
+    /// Loop statement, repeating its body until exited
+    Loop(Loop),
+    /// Exit statement, leaving the innermost enclosing loop
+    Exit(Exit),
+    /// If statement
+    If(If),
+    /// Case statement, running the arm selected by the discriminant
+    Case(Case),
     /// Block statement (`begin ... end`)
     Block(Block),
     // Invariant { .. }
     // Assert { .. }
-    // Call { .. }
-    // Return { .. }
-    // Result { .. }
-    // New { .. }
-    // Free { .. }
-    // Tag { .. }
-    // Fork { .. }
+    /// Call statement, calling a subprogram without using its result
+    Call(Call),
+    /// Return statement, leaving the current subprogram or program
+    Return(Return),
+    /// Result statement, giving back a value from a function
+    Result(Result),
+    /// New statement, allocating an element for a pointer
+    New(New),
+    /// Free statement, deallocating the element a pointer refers to
+    Free(Free),
+    /// Tag statement, changing which variant of a union is active
+    Tag(Tag),
+    /// Fork statement, starting a new process
+    Fork(Fork),
     // Signal { .. }
     // Pause { .. }
-    // Quit { .. }
+    /// Quit statement
+    Quit(Quit),
     // Break { .. }
-    // Checkedness { .. }
+    // Checkedness is tracked as a statement attribute (see `db::HirDb::get_checkedness`)
     // Pre { .. }
     // Init { .. }
     // Post { .. }
@@ -70,6 +89,7 @@ pub enum Stmt {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ConstVar {
     pub is_register: bool,
     pub is_const: bool,
@@ -78,6 +98,7 @@ pub struct ConstVar {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ConstVarTail {
     /// Only the type spec is specified
     TypeSpec(ty::TypeId),
@@ -106,6 +127,95 @@ impl ConstVarTail {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Type {
+    pub def_id: symbol::DefId,
+    pub type_def: TypeDefKind,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TypeDefKind {
+    /// Normal type declaration
+    Alias(ty::TypeId),
+    /// Forward type declaration (`type x : forward`), with the span of the `forward` keyword
+    Forward(Span),
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Bind {
+    pub bindings: Vec<BindItem>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BindItem {
+    /// If the aliased reference can be changed through the new name
+    pub is_var: bool,
+    pub is_register: bool,
+    pub def_id: symbol::DefId,
+    /// Reference being aliased
+    pub bind_to: expr::ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Subprogram {
+    pub kind: ty::SubprogramKind,
+    pub def_id: symbol::DefId,
+    /// Parameters, or `None` if the parameter list is omitted
+    pub params: Option<Vec<Param>>,
+    /// Result type, only present for functions
+    pub result: Option<ty::TypeId>,
+    /// Name bound to the result value in the body,
+    /// only present for functions with a named result
+    pub result_name: Option<symbol::DefId>,
+    pub stmts: Vec<StmtId>,
+}
+
+/// Forward subprogram declaration.
+/// Only the type is kept, since the parameter names are declared by the `body`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Forward {
+    pub def_id: symbol::DefId,
+    /// Subprogram type given by the forward header
+    pub ty: ty::TypeId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Process {
+    pub def_id: symbol::DefId,
+    pub params: Vec<Param>,
+    /// Size of the stack given to each started process, in bytes.
+    /// If absent, the default stack size is used.
+    pub stack_size: Option<expr::ExprId>,
+    pub stmts: Vec<StmtId>,
+}
+
+/// Module declaration.
+/// Only the body is kept, since imports and exports aren't lowered yet.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Module {
+    pub def_id: symbol::DefId,
+    pub stmts: Vec<StmtId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Param {
+    /// If arguments are passed by reference, and can be changed through the parameter
+    pub is_var: bool,
+    pub is_register: bool,
+    pub names: Vec<symbol::DefId>,
+    pub param_ty: ty::TypeId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Assign {
     /// Left hand side of an assignment expression
     pub lhs: expr::ExprId,
@@ -116,6 +226,7 @@ pub struct Assign {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Put {
     /// Stream handle to put the text on.
     /// If absent, should be put on the `stdout` stream.
@@ -127,6 +238,7 @@ pub struct Put {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Get {
     /// Stream handle to get text from.
     /// If absent, should be fetched from the `stdin` stream.
@@ -136,11 +248,180 @@ pub struct Get {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct New {
+    /// Collection to allocate the element in, if the pointer points into one
+    pub collection: Option<expr::ExprId>,
+    /// Reference to the pointer being given the new element
+    pub pointer: expr::ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Free {
+    /// Collection that the element was allocated in, if the pointer points into one
+    pub collection: Option<expr::ExprId>,
+    /// Reference to the pointer being freed, which is set to `nil` afterwards
+    pub pointer: expr::ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Call {
+    /// Subprogram being called
+    pub callee: expr::ExprId,
+    pub arguments: Vec<expr::ExprId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Tag {
+    /// Reference to the union being changed
+    pub union_ref: expr::ExprId,
+    /// New value of the union's tag, which selects the active variant
+    pub tag_val: expr::ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Fork {
+    /// Process being started
+    pub process: expr::ExprId,
+    pub arguments: Vec<expr::ExprId>,
+    /// Reference to store if the process was successfully started
+    pub status: Option<expr::ExprId>,
+    /// Size of the stack given to the started process, overriding the one from the declaration
+    pub stack_size: Option<expr::ExprId>,
+    /// Reference to store the address of the started process' descriptor
+    pub process_desc: Option<expr::ExprId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Quit {
+    /// Where the quit is reported to have happened
+    pub cause: QuitCause,
+    /// Reason for quitting, as an integer exception code.
+    /// If absent, the default code is used.
+    pub reason: Option<expr::ExprId>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum QuitCause {
+    /// Quit happened at this statement (no marker)
+    Here,
+    /// Quit is blamed on the caller (`<`)
+    AtCaller,
+    /// Quit is passed through from an exception handler (`>`)
+    BubbleUp,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct For {
+    /// If the counter goes down from the end bound instead of up from the start bound
+    pub is_decreasing: bool,
+    /// Name of the loop counter, which can't be assigned to.
+    /// If absent, the loop doesn't have a named counter.
+    pub counter_def: Option<symbol::DefId>,
+    pub bounds: ForBounds,
+    /// Amount to change the counter by on each iteration.
+    /// If absent, the counter changes by 1.
+    pub step_by: Option<expr::ExprId>,
+    pub stmts: Vec<StmtId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ForBounds {
+    /// Bounds taken from all of the values of a range type (`for i : t`)
+    Implicit(ty::TypeId),
+    /// Explicit start and end bounds (`for i : 1 .. 10`)
+    Full(expr::ExprId, expr::ExprId),
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Loop {
+    pub stmts: Vec<StmtId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Exit {
+    /// Condition for leaving the loop.
+    /// If absent, the loop is always left.
+    pub when_condition: Option<expr::ExprId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct If {
+    pub condition: expr::ExprId,
+    /// Statements run if the condition is true
+    pub true_branch: Vec<StmtId>,
+    /// Where to go if the condition is false
+    pub false_branch: FalseBranch,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FalseBranch {
+    /// No `else` branch, so nothing is run
+    None,
+    /// `elsif` branch, represented as a nested `if` statement
+    ElseIf(StmtId),
+    /// `else` branch, with the statements to run
+    Else(Vec<StmtId>),
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Case {
+    /// Value used to select the arm to run
+    pub discriminant: expr::ExprId,
+    pub arms: Vec<CaseArm>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CaseArm {
+    /// Values which select this arm.
+    /// If absent, this is the default arm, selected by any other value.
+    pub selectors: Option<Vec<expr::ExprId>>,
+    pub stmts: Vec<StmtId>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Return;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Result {
+    /// Value given back from the function
+    pub expr: expr::ExprId,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Block {
     pub stmts: Vec<StmtId>,
 }
 
+/// If runtime checks are performed for a statement
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Checkedness {
+    /// Runtime checks are performed (the default)
+    Checked,
+    /// Runtime checks are skipped, inside of an `unchecked` region
+    Unchecked,
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum AssignOp {
     /// Plain assignment
     None,
@@ -201,6 +482,7 @@ impl AssignOp {
 /// A generic type representing anything skippable.
 /// Only used for text I/O statements (`PutStmt` & `GetStmt`).
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Skippable<T> {
     /// This I/O item is skipped.
     Skip,
@@ -210,6 +492,7 @@ pub enum Skippable<T> {
 
 /// A single put item, as part of a put statement.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PutItem {
     /// The expression to be put.
     pub expr: expr::ExprId,
@@ -223,6 +506,7 @@ pub struct PutItem {
 /// guaranteed to also be present (e.g. if `precision` was specified, then
 /// `width` is also guaranteed to be specified, but not `exponent_width`).
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum PutOpts {
     // /// `put` item with no args
     None,
@@ -277,6 +561,7 @@ impl PutOpts {
 
 /// A get item.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GetItem {
     /// The expression to put.
     /// Must be a reference expression.
@@ -287,6 +572,7 @@ pub struct GetItem {
 
 /// The fetch width of a get item.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum GetWidth {
     /// Fetch a space delimited portion of text.
     Token,
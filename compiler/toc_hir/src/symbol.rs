@@ -10,6 +10,7 @@ use crate::unit::UnitId;
 
 /// Definition of an identifier within a unit.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DefId(usize);
 
 impl DefId {
@@ -27,6 +28,7 @@ impl DefId {
 
 /// Use of an identifier within a unit.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct UseId(DefId, usize);
 
 impl UseId {
@@ -48,6 +50,7 @@ impl UseId {
 
 /// Definition of an identifier in a specific unit.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GlobalDefId(UnitId, DefId);
 
 impl GlobalDefId {
@@ -68,6 +71,7 @@ impl fmt::Debug for GlobalDefId {
 
 /// Use of an identifier in a specific unit
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GlobalUseId(UnitId, UseId);
 
 impl GlobalUseId {
@@ -113,6 +117,7 @@ impl Symbol {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SymbolKind {
     /// The symbol is undeclared at the point of definition.
     Undeclared,
@@ -133,6 +138,8 @@ pub struct SymbolTable {
     defs: IndexMap<DefId, Symbol>,
     def_spans: IndexMap<DefId, Span>,
     use_spans: IndexMap<UseId, Span>,
+    /// Symbols imported from other units, mapped to their original definitions
+    imports: IndexMap<DefId, GlobalDefId>,
     next_def: usize,
 }
 
@@ -142,6 +149,7 @@ impl SymbolTable {
             defs: IndexMap::new(),
             def_spans: IndexMap::new(),
             use_spans: IndexMap::new(),
+            imports: IndexMap::new(),
             next_def: 0,
         }
     }
@@ -179,6 +187,22 @@ impl SymbolTable {
         def_id
     }
 
+    /// Declares a symbol imported from another unit.
+    ///
+    /// ## Parameters
+    /// - `name`: The name of the imported symbol
+    /// - `span`: The text span of the import
+    /// - `original`: The definition being imported, in the exporting unit
+    ///
+    /// ## Returns
+    /// The `DefId` associated with the imported symbol in this unit
+    pub fn import_sym(&mut self, name: &str, span: Span, original: GlobalDefId) -> DefId {
+        let def_id = self.def_sym(name, span, SymbolKind::Declared, false);
+        self.imports.insert(def_id, original);
+
+        def_id
+    }
+
     /// Uses a given symbol.
     ///
     /// ## Returns
@@ -220,6 +244,63 @@ impl SymbolTable {
     pub fn iter_uses(&self) -> impl Iterator<Item = (UseId, Span)> + '_ {
         self.use_spans.iter().map(|(k, v)| (*k, *v))
     }
+
+    /// Gets the original definition of an imported symbol, or `None` if the
+    /// symbol isn't imported.
+    pub fn get_import(&self, def_id: DefId) -> Option<GlobalDefId> {
+        self.imports.get(&def_id).copied()
+    }
+
+    /// Iterates over all imported symbols, along with their original definitions
+    pub fn iter_imports(&self) -> impl Iterator<Item = (DefId, GlobalDefId)> + '_ {
+        self.imports.iter().map(|(k, v)| (*k, *v))
+    }
+}
+
+/// Serialized as a list of definitions, ordered by `DefId`, and a list of uses
+#[cfg(feature = "serde")]
+impl serde::Serialize for SymbolTable {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        #[derive(serde::Serialize)]
+        struct Def<'a> {
+            def_id: DefId,
+            name: &'a str,
+            kind: SymbolKind,
+            is_pervasive: bool,
+            span: Span,
+            /// Original definition, if the symbol is imported
+            imported_from: Option<GlobalDefId>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Use {
+            use_id: UseId,
+            span: Span,
+        }
+
+        let defs = self
+            .iter_defs()
+            .map(|(def_id, span, symbol)| Def {
+                def_id,
+                name: &symbol.name,
+                kind: symbol.kind,
+                is_pervasive: symbol.is_pervasive,
+                span,
+                imported_from: self.get_import(def_id),
+            })
+            .collect::<Vec<_>>();
+        let uses = self
+            .iter_uses()
+            .map(|(use_id, span)| Use { use_id, span })
+            .collect::<Vec<_>>();
+
+        let mut state = serializer.serialize_struct("SymbolTable", 2)?;
+        state.serialize_field("defs", &defs)?;
+        state.serialize_field("uses", &uses)?;
+        state.end()
+    }
 }
 
 impl Default for SymbolTable {
@@ -1,18 +1,44 @@
 //! Type related HIR nodes
 
-use crate::expr;
+use toc_span::Spanned;
+
+use crate::{expr, stmt, symbol};
 
 crate::hir_id_wrapper!(TypeId);
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Type {
     /// Error Type, only used to represent invalid code
     Missing,
     /// Primitive Type
     Primitive(Primitive),
+    /// Reference to a declared type
+    Name(symbol::UseId),
+    /// Record Type
+    Record(Record),
+    /// Union Type
+    Union(Union),
+    /// Range Type
+    Range(Range),
+    /// Enum Type
+    Enum(Enum),
+    /// Pointer Type
+    Pointer(Pointer),
+    /// Collection Type
+    Collection(Collection),
+    /// Array Type
+    Array(Array),
+    /// Set Type
+    Set(Set),
+    /// Procedure or Function Type
+    Subprogram(Subprogram),
+    /// Condition Type, for synchronizing processes in monitors
+    Condition(ConditionKind),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Primitive {
     Int,
     Int1,
@@ -34,7 +60,138 @@ pub enum Primitive {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SeqLength {
     Dynamic,
     Expr(expr::ExprId),
 }
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Record {
+    pub is_packed: bool,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Union {
+    pub is_packed: bool,
+    /// Name of the union's tag
+    pub tag_name: Spanned<String>,
+    /// Type of the union's tag
+    pub tag_ty: TypeId,
+    pub variants: Vec<UnionVariant>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UnionVariant {
+    /// Tag values which select this variant
+    pub selectors: Vec<expr::ExprId>,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Range {
+    pub start: expr::ExprId,
+    pub end: RangeEnd,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Enum {
+    /// Names of the enum's variants, in declaration order
+    pub variants: Vec<Spanned<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RangeEnd {
+    /// Unsized end bound (`*`), only accepted for parameters
+    Unsized,
+    Expr(expr::ExprId),
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Pointer {
+    /// Checked pointers are validated at runtime before being dereferenced
+    pub checkedness: stmt::Checkedness,
+    /// Type being pointed to
+    pub to: TypeId,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Collection {
+    /// Type of the elements allocated in the collection
+    pub elem: TypeId,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Array {
+    /// Flexible arrays can be resized after being declared
+    pub is_flexible: bool,
+    /// Index types of each dimension
+    pub ranges: Vec<TypeId>,
+    pub elem_ty: TypeId,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Set {
+    /// Index type of the elements in the set
+    pub elem: TypeId,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Subprogram {
+    pub kind: SubprogramKind,
+    /// Parameter types, or `None` if the parameter list is omitted
+    pub params: Option<Vec<Param>>,
+    /// Result type, only present for functions
+    pub result: Option<TypeId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SubprogramKind {
+    Procedure,
+    Function,
+}
+
+/// A parameter in a subprogram type.
+/// Parameter names are only documentation, and aren't kept.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Param {
+    /// If arguments are passed by reference, and can be changed through the parameter
+    pub is_var: bool,
+    pub is_register: bool,
+    pub param_ty: TypeId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ConditionKind {
+    /// Plain `condition`, woken up in first-in first-out order
+    Normal,
+    /// `priority condition`, woken up in order of the priority given to `wait`
+    Priority,
+    /// `deferred condition`, where `signal` doesn't immediately run the woken process
+    Deferred,
+    /// `timeout condition`, which is also signalled after the timeout given to `wait`
+    Timeout,
+}
+
+/// A group of fields sharing the same type
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Field {
+    pub names: Vec<Spanned<String>>,
+    pub ty: TypeId,
+}
@@ -1,6 +1,8 @@
 //! Code unit stuff
 
-use crate::symbol::SymbolTable;
+use toc_span::Span;
+
+use crate::symbol::{DefId, GlobalDefId, SymbolTable};
 use crate::visitor::HirVisitor;
 use crate::{db, stmt};
 
@@ -8,6 +10,7 @@ crate::hir_id_wrapper!(UnitId);
 
 /// Code Unit
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Unit {
     /// Id in the global `Database`
     pub id: UnitId,
@@ -15,12 +18,38 @@ pub struct Unit {
     pub stmts: Vec<stmt::StmtId>,
     /// Unit-local symbol table
     pub symbol_table: SymbolTable,
+    /// Items exported from the unit
+    pub exports: Vec<Export>,
+}
+
+/// An item exported from a unit
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Export {
+    /// Name that the item is exported as
+    pub name: String,
+    /// Original definition of the item, which may be in a different unit if
+    /// the item was imported
+    pub def_id: GlobalDefId,
+    /// If the item is exported as `opaque`, hiding the definition of a type
+    /// outside of the unit
+    pub is_opaque: bool,
+    /// Span of the item in the export list
+    pub span: Span,
 }
 
 impl Unit {
+    /// Walks over all of the nodes in the unit, in postfix order
     pub fn walk_nodes(&self, hir_db: db::HirDb, visitor: &dyn HirVisitor) {
-        let walker = crate::visitor::Walker::new(hir_db, self, visitor);
+        let walker = crate::visitor::Walker::new(hir_db, visitor);
+
+        walker.walk_unit(self);
+    }
 
-        walker.walk_nodes();
+    /// Gets the original definition of a symbol, looking through imports
+    pub fn resolve_def(&self, def_id: DefId) -> GlobalDefId {
+        self.symbol_table
+            .get_import(def_id)
+            .unwrap_or_else(|| def_id.into_global(self.id))
     }
 }
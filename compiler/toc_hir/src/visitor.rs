@@ -1,31 +1,39 @@
 //! HIR Tree visiting related structures
+#[cfg(test)]
+mod test;
 
-use crate::{db, expr, stmt, ty, unit};
+use crate::{db, expr, stmt, symbol, ty, unit};
+
+/// Walks over the statement `id` and all of its children, in postfix order
+pub fn walk_stmt(hir_db: db::HirDb, id: stmt::StmtId, visitor: &dyn HirVisitor) {
+    Walker::new(hir_db, visitor).walk_stmt(id);
+}
+
+/// Walks over the expression `id` and all of its children, in postfix order
+pub fn walk_expr(hir_db: db::HirDb, id: expr::ExprId, visitor: &dyn HirVisitor) {
+    Walker::new(hir_db, visitor).walk_expr(id);
+}
+
+/// Walks over the type `id` and all of its children, in postfix order
+pub fn walk_type(hir_db: db::HirDb, id: ty::TypeId, visitor: &dyn HirVisitor) {
+    Walker::new(hir_db, visitor).walk_type(id);
+}
 
 /// Walker for traversing the HIR tree, in postfix order
-pub(crate) struct Walker<'unit, 'visitor> {
+pub(crate) struct Walker<'visitor> {
     hir_db: db::HirDb,
-    unit: &'unit unit::Unit,
     visitor: &'visitor dyn HirVisitor,
 }
 
-impl<'unit, 'visitor> Walker<'unit, 'visitor> {
-    pub(crate) fn new(
-        hir_db: db::HirDb,
-        unit: &'unit unit::Unit,
-        visitor: &'visitor dyn HirVisitor,
-    ) -> Self {
-        Self {
-            hir_db,
-            unit,
-            visitor,
-        }
+impl<'visitor> Walker<'visitor> {
+    pub(crate) fn new(hir_db: db::HirDb, visitor: &'visitor dyn HirVisitor) -> Self {
+        Self { hir_db, visitor }
     }
 
-    pub(crate) fn walk_nodes(&self) {
-        self.visitor.visit_unit(self.unit);
+    pub(crate) fn walk_unit(&self, unit: &unit::Unit) {
+        self.visitor.visit_unit(unit);
 
-        for stmt in &self.unit.stmts {
+        for stmt in &unit.stmts {
             self.walk_stmt(*stmt)
         }
     }
@@ -35,10 +43,29 @@ impl<'unit, 'visitor> Walker<'unit, 'visitor> {
 
         match stmt {
             stmt::Stmt::ConstVar(decl) => self.walk_constvar(id, decl),
+            stmt::Stmt::Type(decl) => self.walk_type_decl(id, decl),
+            stmt::Stmt::Bind(decl) => self.walk_bind(id, decl),
+            stmt::Stmt::Subprogram(decl) => self.walk_subprogram(id, decl),
+            stmt::Stmt::Process(decl) => self.walk_process(id, decl),
+            stmt::Stmt::Forward(decl) => self.walk_forward(id, decl),
+            stmt::Stmt::Module(decl) => self.walk_module(id, decl),
             stmt::Stmt::Assign(stmt) => self.walk_assign(id, stmt),
             stmt::Stmt::Put(stmt) => self.walk_put(id, stmt),
             stmt::Stmt::Get(stmt) => self.walk_get(id, stmt),
+            stmt::Stmt::New(stmt) => self.walk_new(id, stmt),
+            stmt::Stmt::Free(stmt) => self.walk_free(id, stmt),
+            stmt::Stmt::For(stmt) => self.walk_for(id, stmt),
+            stmt::Stmt::Loop(stmt) => self.walk_loop(id, stmt),
+            stmt::Stmt::Exit(stmt) => self.walk_exit(id, stmt),
+            stmt::Stmt::If(stmt) => self.walk_if(id, stmt),
+            stmt::Stmt::Case(stmt) => self.walk_case(id, stmt),
+            stmt::Stmt::Quit(stmt) => self.walk_quit(id, stmt),
             stmt::Stmt::Block(stmt) => self.walk_block(id, stmt),
+            stmt::Stmt::Call(stmt) => self.walk_call_stmt(id, stmt),
+            stmt::Stmt::Return(stmt) => self.visitor.visit_return(id, stmt),
+            stmt::Stmt::Result(stmt) => self.walk_result(id, stmt),
+            stmt::Stmt::Tag(stmt) => self.walk_tag(id, stmt),
+            stmt::Stmt::Fork(stmt) => self.walk_fork(id, stmt),
         }
     }
 
@@ -54,6 +81,67 @@ impl<'unit, 'visitor> Walker<'unit, 'visitor> {
         self.visitor.visit_constvar(id, node);
     }
 
+    fn walk_type_decl(&self, id: stmt::StmtId, node: &stmt::Type) {
+        if let stmt::TypeDefKind::Alias(ty) = &node.type_def {
+            self.walk_type(*ty);
+        }
+
+        self.visitor.visit_type_decl(id, node);
+    }
+
+    fn walk_bind(&self, id: stmt::StmtId, node: &stmt::Bind) {
+        for binding in &node.bindings {
+            self.walk_expr(binding.bind_to);
+        }
+
+        self.visitor.visit_bind(id, node);
+    }
+
+    fn walk_subprogram(&self, id: stmt::StmtId, node: &stmt::Subprogram) {
+        for param in node.params.iter().flatten() {
+            self.walk_type(param.param_ty);
+        }
+
+        if let Some(ty) = node.result {
+            self.walk_type(ty);
+        }
+
+        for stmt in &node.stmts {
+            self.walk_stmt(*stmt)
+        }
+
+        self.visitor.visit_subprogram(id, node);
+    }
+
+    fn walk_process(&self, id: stmt::StmtId, node: &stmt::Process) {
+        for param in &node.params {
+            self.walk_type(param.param_ty);
+        }
+
+        if let Some(expr) = node.stack_size {
+            self.walk_expr(expr);
+        }
+
+        for stmt in &node.stmts {
+            self.walk_stmt(*stmt)
+        }
+
+        self.visitor.visit_process(id, node);
+    }
+
+    fn walk_forward(&self, id: stmt::StmtId, node: &stmt::Forward) {
+        self.walk_type(node.ty);
+        self.visitor.visit_forward(id, node);
+    }
+
+    fn walk_module(&self, id: stmt::StmtId, node: &stmt::Module) {
+        for stmt in &node.stmts {
+            self.walk_stmt(*stmt)
+        }
+
+        self.visitor.visit_module(id, node);
+    }
+
     fn walk_assign(&self, id: stmt::StmtId, node: &stmt::Assign) {
         self.walk_expr(node.lhs);
         self.walk_expr(node.rhs);
@@ -105,6 +193,106 @@ impl<'unit, 'visitor> Walker<'unit, 'visitor> {
         self.visitor.visit_get(id, node);
     }
 
+    fn walk_new(&self, id: stmt::StmtId, node: &stmt::New) {
+        if let Some(expr) = node.collection {
+            self.walk_expr(expr);
+        }
+
+        self.walk_expr(node.pointer);
+
+        self.visitor.visit_new(id, node);
+    }
+
+    fn walk_free(&self, id: stmt::StmtId, node: &stmt::Free) {
+        if let Some(expr) = node.collection {
+            self.walk_expr(expr);
+        }
+
+        self.walk_expr(node.pointer);
+
+        self.visitor.visit_free(id, node);
+    }
+
+    fn walk_for(&self, id: stmt::StmtId, node: &stmt::For) {
+        match node.bounds {
+            stmt::ForBounds::Implicit(ty) => self.walk_type(ty),
+            stmt::ForBounds::Full(start, end) => {
+                self.walk_expr(start);
+                self.walk_expr(end);
+            }
+        }
+
+        if let Some(expr) = node.step_by {
+            self.walk_expr(expr);
+        }
+
+        for stmt in &node.stmts {
+            self.walk_stmt(*stmt)
+        }
+
+        self.visitor.visit_for(id, node);
+    }
+
+    fn walk_loop(&self, id: stmt::StmtId, node: &stmt::Loop) {
+        for stmt in &node.stmts {
+            self.walk_stmt(*stmt)
+        }
+
+        self.visitor.visit_loop(id, node);
+    }
+
+    fn walk_exit(&self, id: stmt::StmtId, node: &stmt::Exit) {
+        if let Some(expr) = node.when_condition {
+            self.walk_expr(expr);
+        }
+
+        self.visitor.visit_exit(id, node);
+    }
+
+    fn walk_if(&self, id: stmt::StmtId, node: &stmt::If) {
+        self.walk_expr(node.condition);
+
+        for stmt in &node.true_branch {
+            self.walk_stmt(*stmt)
+        }
+
+        match &node.false_branch {
+            stmt::FalseBranch::None => {}
+            stmt::FalseBranch::ElseIf(stmt) => self.walk_stmt(*stmt),
+            stmt::FalseBranch::Else(stmts) => {
+                for stmt in stmts {
+                    self.walk_stmt(*stmt)
+                }
+            }
+        }
+
+        self.visitor.visit_if(id, node);
+    }
+
+    fn walk_case(&self, id: stmt::StmtId, node: &stmt::Case) {
+        self.walk_expr(node.discriminant);
+
+        for arm in &node.arms {
+            for expr in arm.selectors.iter().flatten() {
+                self.walk_expr(*expr);
+            }
+
+            for stmt in &arm.stmts {
+                self.walk_stmt(*stmt)
+            }
+        }
+
+        self.visitor.visit_case(id, node);
+    }
+
+    fn walk_quit(&self, id: stmt::StmtId, node: &stmt::Quit) {
+        if let Some(expr) = node.reason {
+            self.walk_expr(expr);
+        }
+
+        self.visitor.visit_quit(id, node);
+    }
+
     fn walk_block(&self, id: stmt::StmtId, node: &stmt::Block) {
         for stmt in &node.stmts {
             self.walk_stmt(*stmt)
@@ -113,16 +301,66 @@ impl<'unit, 'visitor> Walker<'unit, 'visitor> {
         self.visitor.visit_block(id, node);
     }
 
+    fn walk_call_stmt(&self, id: stmt::StmtId, node: &stmt::Call) {
+        self.walk_expr(node.callee);
+
+        for arg in &node.arguments {
+            self.walk_expr(*arg);
+        }
+
+        self.visitor.visit_call_stmt(id, node);
+    }
+
+    fn walk_result(&self, id: stmt::StmtId, node: &stmt::Result) {
+        self.walk_expr(node.expr);
+
+        self.visitor.visit_result(id, node);
+    }
+
+    fn walk_tag(&self, id: stmt::StmtId, node: &stmt::Tag) {
+        self.walk_expr(node.union_ref);
+        self.walk_expr(node.tag_val);
+
+        self.visitor.visit_tag(id, node);
+    }
+
+    fn walk_fork(&self, id: stmt::StmtId, node: &stmt::Fork) {
+        self.walk_expr(node.process);
+
+        for arg in &node.arguments {
+            self.walk_expr(*arg);
+        }
+
+        for expr in [node.status, node.stack_size, node.process_desc]
+            .iter()
+            .flatten()
+        {
+            self.walk_expr(*expr);
+        }
+
+        self.visitor.visit_fork(id, node);
+    }
+
     fn walk_expr(&self, id: expr::ExprId) {
         let node = self.hir_db.get_expr(id);
 
         match node {
             expr::Expr::Missing => {}
             expr::Expr::Literal(expr) => self.walk_literal(id, expr),
+            expr::Expr::Init(expr) => self.walk_init(id, expr),
+            expr::Expr::Nil(expr) => self.walk_nil(id, expr),
+            expr::Expr::Builtin(expr) => self.walk_builtin(id, expr),
             expr::Expr::Binary(expr) => self.walk_binary(id, expr),
             expr::Expr::Unary(expr) => self.walk_unary(id, expr),
             expr::Expr::Paren(expr) => self.walk_paren(id, expr),
             expr::Expr::Name(expr) => self.walk_name(id, expr),
+            expr::Expr::Field(expr) => self.walk_field(id, expr),
+            expr::Expr::Deref(expr) => self.walk_deref(id, expr),
+            expr::Expr::NatCheat(expr) => self.walk_nat_cheat(id, expr),
+            expr::Expr::Indirect(expr) => self.walk_indirect(id, expr),
+            expr::Expr::Bits(expr) => self.walk_bits(id, expr),
+            expr::Expr::Call(expr) => self.walk_call(id, expr),
+            expr::Expr::Substring(expr) => self.walk_substring(id, expr),
         }
     }
 
@@ -130,6 +368,33 @@ impl<'unit, 'visitor> Walker<'unit, 'visitor> {
         self.visitor.visit_literal(id, node);
     }
 
+    fn walk_init(&self, id: expr::ExprId, node: &expr::Init) {
+        for expr in &node.exprs {
+            self.walk_expr(*expr);
+        }
+
+        self.visitor.visit_init(id, node);
+    }
+
+    fn walk_nil(&self, id: expr::ExprId, node: &expr::Nil) {
+        if let Some(collection) = node.collection {
+            self.walk_type(collection);
+        }
+
+        self.visitor.visit_nil(id, node);
+    }
+
+    fn walk_builtin(&self, id: expr::ExprId, node: &expr::Builtin) {
+        for arg in &node.args {
+            match arg {
+                expr::BuiltinArg::Expr(expr) => self.walk_expr(*expr),
+                expr::BuiltinArg::Type(ty) => self.walk_type(*ty),
+            }
+        }
+
+        self.visitor.visit_builtin(id, node);
+    }
+
     fn walk_binary(&self, id: expr::ExprId, node: &expr::Binary) {
         self.walk_expr(node.lhs);
         self.walk_expr(node.rhs);
@@ -153,12 +418,86 @@ impl<'unit, 'visitor> Walker<'unit, 'visitor> {
         self.visitor.visit_name(id, node);
     }
 
+    fn walk_field(&self, id: expr::ExprId, node: &expr::Field) {
+        self.walk_expr(node.lhs);
+
+        self.visitor.visit_field(id, node);
+    }
+
+    fn walk_deref(&self, id: expr::ExprId, node: &expr::Deref) {
+        self.walk_expr(node.expr);
+
+        self.visitor.visit_deref(id, node);
+    }
+
+    fn walk_nat_cheat(&self, id: expr::ExprId, node: &expr::NatCheat) {
+        self.walk_expr(node.expr);
+
+        self.visitor.visit_nat_cheat(id, node);
+    }
+
+    fn walk_indirect(&self, id: expr::ExprId, node: &expr::Indirect) {
+        self.walk_type(node.ty);
+        self.walk_expr(node.addr);
+
+        self.visitor.visit_indirect(id, node);
+    }
+
+    fn walk_bits(&self, id: expr::ExprId, node: &expr::Bits) {
+        self.walk_expr(node.lhs);
+
+        match node.bits {
+            expr::BitsSelector::Expr(expr) => self.walk_expr(expr),
+            expr::BitsSelector::Range(start, end) => {
+                self.walk_expr(start);
+                self.walk_expr(end);
+            }
+        }
+
+        self.visitor.visit_bits(id, node);
+    }
+
+    fn walk_call(&self, id: expr::ExprId, node: &expr::Call) {
+        self.walk_expr(node.lhs);
+
+        for arg in &node.arguments {
+            self.walk_expr(*arg);
+        }
+
+        self.visitor.visit_call(id, node);
+    }
+
+    fn walk_substring(&self, id: expr::ExprId, node: &expr::Substring) {
+        self.walk_expr(node.lhs);
+
+        for bound in &[node.start, node.end] {
+            match bound {
+                expr::SubstringBound::FromStart(expr)
+                | expr::SubstringBound::FromEnd(Some(expr)) => self.walk_expr(*expr),
+                expr::SubstringBound::FromEnd(None) => {}
+            }
+        }
+
+        self.visitor.visit_substring(id, node);
+    }
+
     fn walk_type(&self, id: ty::TypeId) {
         let node = self.hir_db.get_type(id);
 
         match node {
             ty::Type::Missing => {}
             ty::Type::Primitive(ty) => self.walk_primitive(id, ty),
+            ty::Type::Name(ty) => self.walk_name_ty(id, ty),
+            ty::Type::Record(ty) => self.walk_record(id, ty),
+            ty::Type::Union(ty) => self.walk_union(id, ty),
+            ty::Type::Range(ty) => self.walk_range(id, ty),
+            ty::Type::Enum(ty) => self.walk_enum(id, ty),
+            ty::Type::Pointer(ty) => self.walk_pointer(id, ty),
+            ty::Type::Collection(ty) => self.walk_collection(id, ty),
+            ty::Type::Array(ty) => self.walk_array(id, ty),
+            ty::Type::Set(ty) => self.walk_set(id, ty),
+            ty::Type::Subprogram(ty) => self.walk_subprogram_ty(id, ty),
+            ty::Type::Condition(ty) => self.walk_condition(id, ty),
         }
     }
 
@@ -171,25 +510,155 @@ impl<'unit, 'visitor> Walker<'unit, 'visitor> {
 
         self.visitor.visit_primitive(id, node);
     }
+
+    fn walk_name_ty(&self, id: ty::TypeId, node: &symbol::UseId) {
+        self.visitor.visit_name_ty(id, node);
+    }
+
+    fn walk_record(&self, id: ty::TypeId, node: &ty::Record) {
+        for field in &node.fields {
+            self.walk_type(field.ty);
+        }
+
+        self.visitor.visit_record(id, node);
+    }
+
+    fn walk_union(&self, id: ty::TypeId, node: &ty::Union) {
+        self.walk_type(node.tag_ty);
+
+        for variant in &node.variants {
+            for expr in &variant.selectors {
+                self.walk_expr(*expr);
+            }
+
+            for field in &variant.fields {
+                self.walk_type(field.ty);
+            }
+        }
+
+        self.visitor.visit_union(id, node);
+    }
+
+    fn walk_range(&self, id: ty::TypeId, node: &ty::Range) {
+        self.walk_expr(node.start);
+
+        if let ty::RangeEnd::Expr(expr) = node.end {
+            self.walk_expr(expr);
+        }
+
+        self.visitor.visit_range(id, node);
+    }
+
+    fn walk_pointer(&self, id: ty::TypeId, node: &ty::Pointer) {
+        self.walk_type(node.to);
+
+        self.visitor.visit_pointer(id, node);
+    }
+
+    fn walk_collection(&self, id: ty::TypeId, node: &ty::Collection) {
+        self.walk_type(node.elem);
+
+        self.visitor.visit_collection(id, node);
+    }
+
+    fn walk_array(&self, id: ty::TypeId, node: &ty::Array) {
+        for range in &node.ranges {
+            self.walk_type(*range);
+        }
+
+        self.walk_type(node.elem_ty);
+
+        self.visitor.visit_array(id, node);
+    }
+
+    fn walk_set(&self, id: ty::TypeId, node: &ty::Set) {
+        self.walk_type(node.elem);
+
+        self.visitor.visit_set(id, node);
+    }
+
+    fn walk_subprogram_ty(&self, id: ty::TypeId, node: &ty::Subprogram) {
+        for param in node.params.iter().flatten() {
+            self.walk_type(param.param_ty);
+        }
+
+        if let Some(result) = node.result {
+            self.walk_type(result);
+        }
+
+        self.visitor.visit_subprogram_ty(id, node);
+    }
+
+    fn walk_enum(&self, id: ty::TypeId, node: &ty::Enum) {
+        self.visitor.visit_enum(id, node);
+    }
+
+    fn walk_condition(&self, id: ty::TypeId, node: &ty::ConditionKind) {
+        self.visitor.visit_condition(id, node);
+    }
 }
 
 /// Visitor over all nodes in the HIR tree, in postfix order
+///
+/// All methods do nothing by default, so only the nodes of interest need to be visited.
+/// Walking can start from a whole unit (see `unit::Unit::walk_nodes`), or from
+/// any node with [`walk_stmt`], [`walk_expr`], or [`walk_type`].
 #[allow(unused_variables)]
 pub trait HirVisitor {
     fn visit_unit(&self, unit: &unit::Unit) {}
     // Decls
     fn visit_constvar(&self, id: stmt::StmtId, decl: &stmt::ConstVar) {}
+    fn visit_type_decl(&self, id: stmt::StmtId, decl: &stmt::Type) {}
+    fn visit_bind(&self, id: stmt::StmtId, decl: &stmt::Bind) {}
+    fn visit_subprogram(&self, id: stmt::StmtId, decl: &stmt::Subprogram) {}
+    fn visit_process(&self, id: stmt::StmtId, decl: &stmt::Process) {}
+    fn visit_forward(&self, id: stmt::StmtId, decl: &stmt::Forward) {}
+    fn visit_module(&self, id: stmt::StmtId, decl: &stmt::Module) {}
     // Stmts
     fn visit_assign(&self, id: stmt::StmtId, stmt: &stmt::Assign) {}
     fn visit_put(&self, id: stmt::StmtId, stmt: &stmt::Put) {}
     fn visit_get(&self, id: stmt::StmtId, stmt: &stmt::Get) {}
+    fn visit_new(&self, id: stmt::StmtId, stmt: &stmt::New) {}
+    fn visit_free(&self, id: stmt::StmtId, stmt: &stmt::Free) {}
+    fn visit_for(&self, id: stmt::StmtId, stmt: &stmt::For) {}
+    fn visit_loop(&self, id: stmt::StmtId, stmt: &stmt::Loop) {}
+    fn visit_exit(&self, id: stmt::StmtId, stmt: &stmt::Exit) {}
+    fn visit_if(&self, id: stmt::StmtId, stmt: &stmt::If) {}
+    fn visit_case(&self, id: stmt::StmtId, stmt: &stmt::Case) {}
+    fn visit_quit(&self, id: stmt::StmtId, stmt: &stmt::Quit) {}
     fn visit_block(&self, id: stmt::StmtId, stmt: &stmt::Block) {}
+    fn visit_call_stmt(&self, id: stmt::StmtId, stmt: &stmt::Call) {}
+    fn visit_return(&self, id: stmt::StmtId, stmt: &stmt::Return) {}
+    fn visit_result(&self, id: stmt::StmtId, stmt: &stmt::Result) {}
+    fn visit_tag(&self, id: stmt::StmtId, stmt: &stmt::Tag) {}
+    fn visit_fork(&self, id: stmt::StmtId, stmt: &stmt::Fork) {}
     // Exprs
     fn visit_literal(&self, id: expr::ExprId, expr: &expr::Literal) {}
+    fn visit_init(&self, id: expr::ExprId, expr: &expr::Init) {}
+    fn visit_nil(&self, id: expr::ExprId, expr: &expr::Nil) {}
+    fn visit_builtin(&self, id: expr::ExprId, expr: &expr::Builtin) {}
     fn visit_binary(&self, id: expr::ExprId, expr: &expr::Binary) {}
     fn visit_unary(&self, id: expr::ExprId, expr: &expr::Unary) {}
     fn visit_paren(&self, id: expr::ExprId, expr: &expr::Paren) {}
     fn visit_name(&self, id: expr::ExprId, expr: &expr::Name) {}
+    fn visit_field(&self, id: expr::ExprId, expr: &expr::Field) {}
+    fn visit_deref(&self, id: expr::ExprId, expr: &expr::Deref) {}
+    fn visit_nat_cheat(&self, id: expr::ExprId, expr: &expr::NatCheat) {}
+    fn visit_indirect(&self, id: expr::ExprId, expr: &expr::Indirect) {}
+    fn visit_bits(&self, id: expr::ExprId, expr: &expr::Bits) {}
+    fn visit_call(&self, id: expr::ExprId, expr: &expr::Call) {}
+    fn visit_substring(&self, id: expr::ExprId, expr: &expr::Substring) {}
     // Types
     fn visit_primitive(&self, id: ty::TypeId, ty: &ty::Primitive) {}
+    fn visit_name_ty(&self, id: ty::TypeId, ty: &symbol::UseId) {}
+    fn visit_record(&self, id: ty::TypeId, ty: &ty::Record) {}
+    fn visit_union(&self, id: ty::TypeId, ty: &ty::Union) {}
+    fn visit_range(&self, id: ty::TypeId, ty: &ty::Range) {}
+    fn visit_enum(&self, id: ty::TypeId, ty: &ty::Enum) {}
+    fn visit_pointer(&self, id: ty::TypeId, ty: &ty::Pointer) {}
+    fn visit_collection(&self, id: ty::TypeId, ty: &ty::Collection) {}
+    fn visit_array(&self, id: ty::TypeId, ty: &ty::Array) {}
+    fn visit_set(&self, id: ty::TypeId, ty: &ty::Set) {}
+    fn visit_subprogram_ty(&self, id: ty::TypeId, ty: &ty::Subprogram) {}
+    fn visit_condition(&self, id: ty::TypeId, ty: &ty::ConditionKind) {}
 }
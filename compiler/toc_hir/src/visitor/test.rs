@@ -0,0 +1,187 @@
+use std::cell::{Cell, RefCell};
+
+use toc_span::{Span, Spanned};
+
+use crate::visitor::{self, HirVisitor};
+use crate::{db, expr, stmt, ty};
+
+/// Counts all of the visited expression nodes
+#[derive(Default)]
+struct ExprCounter {
+    count: Cell<usize>,
+}
+
+impl ExprCounter {
+    fn bump(&self) {
+        self.count.set(self.count.get() + 1);
+    }
+}
+
+impl HirVisitor for ExprCounter {
+    fn visit_literal(&self, _id: expr::ExprId, _expr: &expr::Literal) {
+        self.bump();
+    }
+
+    fn visit_binary(&self, _id: expr::ExprId, _expr: &expr::Binary) {
+        self.bump();
+    }
+
+    fn visit_unary(&self, _id: expr::ExprId, _expr: &expr::Unary) {
+        self.bump();
+    }
+
+    fn visit_paren(&self, _id: expr::ExprId, _expr: &expr::Paren) {
+        self.bump();
+    }
+
+    fn visit_name(&self, _id: expr::ExprId, _expr: &expr::Name) {
+        self.bump();
+    }
+
+    fn visit_field(&self, _id: expr::ExprId, _expr: &expr::Field) {
+        self.bump();
+    }
+
+    fn visit_substring(&self, _id: expr::ExprId, _expr: &expr::Substring) {
+        self.bump();
+    }
+}
+
+/// Records the order that nodes are visited in
+#[derive(Default)]
+struct OrderRecorder {
+    order: RefCell<Vec<&'static str>>,
+}
+
+impl HirVisitor for OrderRecorder {
+    fn visit_assign(&self, _id: stmt::StmtId, _stmt: &stmt::Assign) {
+        self.order.borrow_mut().push("assign");
+    }
+
+    fn visit_literal(&self, _id: expr::ExprId, _expr: &expr::Literal) {
+        self.order.borrow_mut().push("literal");
+    }
+
+    fn visit_binary(&self, _id: expr::ExprId, _expr: &expr::Binary) {
+        self.order.borrow_mut().push("binary");
+    }
+
+    fn visit_paren(&self, _id: expr::ExprId, _expr: &expr::Paren) {
+        self.order.borrow_mut().push("paren");
+    }
+
+    fn visit_field(&self, _id: expr::ExprId, _expr: &expr::Field) {
+        self.order.borrow_mut().push("field");
+    }
+}
+
+fn add_literal(hir_db: &db::HirBuilder, value: u64) -> expr::ExprId {
+    hir_db.add_expr(
+        expr::Expr::Literal(expr::Literal::Integer(value)),
+        Span::default(),
+    )
+}
+
+/// Builds `1 + (-2)`
+fn add_sample_expr(hir_db: &db::HirBuilder) -> expr::ExprId {
+    let lhs = add_literal(hir_db, 1);
+    let inner = add_literal(hir_db, 2);
+    let negate = hir_db.add_expr(
+        expr::Expr::Unary(expr::Unary {
+            op: Spanned::new(expr::UnaryOp::Negate, Span::default()),
+            rhs: inner,
+        }),
+        Span::default(),
+    );
+    let rhs = hir_db.add_expr(
+        expr::Expr::Paren(expr::Paren { expr: negate }),
+        Span::default(),
+    );
+
+    hir_db.add_expr(
+        expr::Expr::Binary(expr::Binary {
+            lhs,
+            op: Spanned::new(expr::BinaryOp::Add, Span::default()),
+            rhs,
+        }),
+        Span::default(),
+    )
+}
+
+#[test]
+fn count_expr_nodes() {
+    let hir_db = db::HirBuilder::new();
+    let root = add_sample_expr(&hir_db);
+    let hir_db = hir_db.finish();
+
+    let counter = ExprCounter::default();
+    visitor::walk_expr(hir_db, root, &counter);
+
+    assert_eq!(counter.count.get(), 5);
+}
+
+#[test]
+fn count_expr_nodes_in_type() {
+    // Range type `1 .. 1 + (-2)`
+    let hir_db = db::HirBuilder::new();
+    let start = add_literal(&hir_db, 1);
+    let end = add_sample_expr(&hir_db);
+    let root = hir_db.add_type(
+        ty::Type::Range(ty::Range {
+            start,
+            end: ty::RangeEnd::Expr(end),
+        }),
+        Span::default(),
+    );
+    let hir_db = hir_db.finish();
+
+    let counter = ExprCounter::default();
+    visitor::walk_type(hir_db, root, &counter);
+
+    assert_eq!(counter.count.get(), 6);
+}
+
+#[test]
+fn walk_in_postfix_order() {
+    // `(1).a += 2 + 3`
+    let hir_db = db::HirBuilder::new();
+    let inner = add_literal(&hir_db, 1);
+    let paren = hir_db.add_expr(
+        expr::Expr::Paren(expr::Paren { expr: inner }),
+        Span::default(),
+    );
+    let lhs = hir_db.add_expr(
+        expr::Expr::Field(expr::Field {
+            lhs: paren,
+            field: Spanned::new("a".to_string(), Span::default()),
+        }),
+        Span::default(),
+    );
+    let two = add_literal(&hir_db, 2);
+    let three = add_literal(&hir_db, 3);
+    let rhs = hir_db.add_expr(
+        expr::Expr::Binary(expr::Binary {
+            lhs: two,
+            op: Spanned::new(expr::BinaryOp::Add, Span::default()),
+            rhs: three,
+        }),
+        Span::default(),
+    );
+    let root = hir_db.add_stmt(
+        stmt::Stmt::Assign(stmt::Assign {
+            lhs,
+            op: Spanned::new(stmt::AssignOp::Add, Span::default()),
+            rhs,
+        }),
+        Span::default(),
+    );
+    let hir_db = hir_db.finish();
+
+    let recorder = OrderRecorder::default();
+    visitor::walk_stmt(hir_db, root, &recorder);
+
+    assert_eq!(
+        recorder.order.into_inner(),
+        vec!["literal", "paren", "field", "literal", "literal", "binary", "assign"]
+    );
+}
@@ -23,7 +23,7 @@ mod scopes;
 use toc_hir::db::HirBuilder;
 use toc_hir::unit;
 use toc_reporting::ReportMessage;
-use toc_span::FileId;
+use toc_span::{FileId, Span};
 use toc_syntax::{
     ast::{self, AstNode},
     SyntaxNode,
@@ -38,44 +38,103 @@ mod test;
 pub struct HirLowerResult {
     /// Id of the newly lowered unit
     pub id: unit::UnitId,
+    exports: Vec<unit::Export>,
     messages: Vec<ReportMessage>,
 }
 
 impl HirLowerResult {
+    /// Items exported from the unit, to be brought into the units importing it
+    pub fn exports(&self) -> &[unit::Export] {
+        &self.exports
+    }
+
     pub fn messages(&self) -> &[ReportMessage] {
         &self.messages
     }
 }
 
+/// A unit imported by the unit being lowered
+#[derive(Debug, Clone, Copy)]
+pub struct UnitImport<'a> {
+    /// Lowering results of the imported unit
+    pub unit: &'a HirLowerResult,
+    /// Span of the import in the importing unit
+    pub span: Span,
+}
+
 pub fn lower_ast(
     hir_db: HirBuilder,
     file: Option<FileId>,
     root_node: SyntaxNode,
 ) -> HirLowerResult {
-    let mut ctx = LoweringCtx::new(hir_db, file);
+    lower_unit(hir_db, file, root_node, &[])
+}
+
+/// Lowers a unit, bringing all items exported from the `imports` into the
+/// unit's root scope.
+///
+/// All of the imported units must already be lowered into `hir_db`.
+pub fn lower_unit(
+    hir_db: HirBuilder,
+    file: Option<FileId>,
+    root_node: SyntaxNode,
+    imports: &[UnitImport],
+) -> HirLowerResult {
     let root = ast::Source::cast(root_node).unwrap();
     let unit_span = toc_span::Span::new(file, root.syntax().text_range());
+    let mut ctx = LoweringCtx::new(hir_db, file, unit_span);
 
+    ctx.lower_imports(imports);
     let stmts = ctx.lower_root(root);
     let LoweringCtx {
         database: hir_db,
         messages,
         scopes,
+        exports: export_defs,
+        unit_name,
         ..
     } = ctx;
     let messages = messages.finish();
 
+    let mut exports = vec![];
     let unit = hir_db.add_unit_with(
-        move |id| {
+        |id| {
             let symbol_table = scopes.finish();
+
+            // Re-exported items refer back to the original definitions
+            exports = export_defs
+                .into_iter()
+                .map(|export| unit::Export {
+                    def_id: symbol_table
+                        .get_import(export.def_id)
+                        .unwrap_or_else(|| export.def_id.into_global(id)),
+                    name: export.name,
+                    is_opaque: export.is_opaque,
+                    span: export.span,
+                })
+                .collect::<Vec<_>>();
+
             unit::Unit {
                 id,
                 stmts,
                 symbol_table,
+                exports: exports.clone(),
             }
         },
         unit_span,
     );
 
-    HirLowerResult { id: unit, messages }
+    if let Some(name) = unit_name {
+        hir_db.set_unit_name(unit, name);
+    }
+
+    for import in imports {
+        hir_db.add_unit_import(unit, import.unit.id);
+    }
+
+    HirLowerResult {
+        id: unit,
+        exports,
+        messages,
+    }
 }
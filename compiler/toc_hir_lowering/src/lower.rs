@@ -6,44 +6,217 @@ mod expr;
 mod stmt;
 mod ty;
 
+use std::collections::{HashMap, HashSet};
+
 use toc_hir::db::{self};
 use toc_hir::stmt::StmtId;
-use toc_reporting::MessageSink;
-use toc_span::FileId;
-use toc_syntax::ast;
+use toc_hir::symbol;
+use toc_reporting::{MessageSink, Stage};
+use toc_span::{FileId, Span};
+use toc_syntax::ast::{self, AstNode};
 
-use crate::scopes;
+use crate::{scopes, UnitImport};
 
 pub(super) struct LoweringCtx {
     pub(super) file: Option<FileId>,
     pub(super) database: db::HirBuilder,
     pub(super) messages: MessageSink,
     pub(super) scopes: scopes::ScopeBuilder,
+    /// Span of the `unchecked` statement starting the current statement region,
+    /// or `None` if the region is checked
+    pub(super) unchecked_region: Option<Span>,
+    /// Symbols declared by type declarations
+    pub(super) type_defs: HashSet<symbol::DefId>,
+    /// Headers of forward subprogram declarations, for `body` declarations
+    /// that leave out the parameters or result type
+    pub(super) forward_headers: HashMap<symbol::DefId, ast::SubprogHeader>,
+    /// Items exported from the unit
+    pub(super) exports: Vec<ExportDef>,
+    /// Name of the unit, if it is a `unit module`
+    pub(super) unit_name: Option<String>,
+}
+
+/// An item exported from the unit, before re-exports are resolved
+pub(super) struct ExportDef {
+    /// Name that the item is exported as
+    pub(super) name: String,
+    pub(super) def_id: symbol::DefId,
+    pub(super) is_opaque: bool,
+    /// Span of the item in the export list
+    pub(super) span: Span,
+}
+
+/// Where a name is used, for narrowing down suggestions for undeclared names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum NameContext {
+    /// Used in an expression, so only non-type names are suggested
+    Expr,
+    /// Used as a type, so only type names are suggested
+    Type,
 }
 
 impl LoweringCtx {
-    pub(super) fn new(database: db::HirBuilder, file: Option<FileId>) -> Self {
+    /// Creates a new lowering context, with `bounds` being the span of the whole source
+    pub(super) fn new(database: db::HirBuilder, file: Option<FileId>, bounds: Span) -> Self {
         Self {
             file,
             database,
-            messages: MessageSink::new(),
+            messages: MessageSink::new(Stage::LowerHir).with_source_bounds(bounds),
             scopes: scopes::ScopeBuilder::new(),
+            unchecked_region: None,
+            type_defs: HashSet::new(),
+            forward_headers: HashMap::new(),
+            exports: vec![],
+            unit_name: None,
+        }
+    }
+
+    /// Brings the items exported from the imported units into the root scope
+    pub(super) fn lower_imports(&mut self, imports: &[UnitImport]) {
+        for import in imports {
+            for export in import.unit.exports() {
+                let name = export.name.as_str();
+
+                if let Some(prev_span) = self
+                    .scopes
+                    .find_redeclaration(name, symbol::SymbolKind::Declared)
+                {
+                    // Importing the same item through different units is fine
+                    if self.scopes.imported_def(name) != Some(export.def_id) {
+                        self.messages
+                            .error_detailed(
+                                &format!("`{}` is imported from multiple units", name),
+                                import.span,
+                            )
+                            .with_note(&format!("`{}` was first imported here", name), prev_span)
+                            .finish();
+                    }
+
+                    continue;
+                }
+
+                self.scopes.import_sym(name, import.span, export.def_id);
+            }
         }
     }
 
     pub(super) fn lower_root(&mut self, root: ast::Source) -> Vec<StmtId> {
-        // TODO: deal with root import statement (i.e. build up import info)
-        let _is_child_unit = root.unit_token().is_some();
-
-        let stmts = if let Some(stmts) = root.stmt_list() {
-            stmts
-                .stmts()
-                .filter_map(|stmt| self.lower_stmt(stmt))
-                .collect()
-        } else {
-            vec![]
-        };
+        let is_child_unit = root.unit_token().is_some();
+        let stmt_list = root.stmt_list();
+
+        if is_child_unit {
+            // The body of a `unit module` is lowered as the root of the unit
+            // TODO: Lower `class` and `monitor` units once those are lowered
+            let first_stmt = stmt_list.as_ref().and_then(|list| list.stmts().next());
+
+            if let Some(ast::Stmt::ModuleDecl(module)) = first_stmt {
+                self.unit_name = module
+                    .name()
+                    .and_then(|name| name.identifier_token())
+                    .map(|name| name.text().to_string());
 
-        stmts
+                let stmts = self.lower_stmt_list(module.stmt_list());
+                self.lower_exports(module.export_stmt());
+
+                return stmts;
+            }
+        }
+
+        self.lower_stmt_list(stmt_list)
+    }
+
+    fn lower_exports(&mut self, exports: Option<ast::ExportStmt>) {
+        // TODO: Handle the rest of the export attributes (`var`, `unqualified`, and `pervasive`)
+        for item in exports.iter().flat_map(|exports| exports.exports()) {
+            let is_opaque = item
+                .attrs()
+                .any(|attr| matches!(attr, ast::ExportAttr::OpaqueAttr(_)));
+            let item_span = Span::new(self.file, item.syntax().text_range());
+
+            if item.all_token().is_some() {
+                // Export everything declared in the unit
+                let mut all_defs = self
+                    .scopes
+                    .visible_defs()
+                    .filter(|(name, _)| self.scopes.imported_def(name).is_none())
+                    .map(|(name, def_id)| ExportDef {
+                        name: name.to_string(),
+                        def_id,
+                        is_opaque,
+                        span: item_span,
+                    })
+                    .collect::<Vec<_>>();
+                all_defs.sort_by_key(|export| export.def_id);
+
+                self.exports.extend(all_defs);
+                continue;
+            }
+
+            let name = match item.name().and_then(|name| name.identifier_token()) {
+                Some(name) => name,
+                None => continue,
+            };
+            let span = Span::new(self.file, name.text_range());
+            let name = name.text();
+
+            if self
+                .scopes
+                .visible_defs()
+                .any(|(visible, _)| visible == name)
+            {
+                let def_id = self.scopes.use_sym(name, span).as_def();
+                self.exports.push(ExportDef {
+                    name: name.to_string(),
+                    def_id,
+                    is_opaque,
+                    span: item_span,
+                });
+            } else {
+                self.report_undeclared(name, span, NameContext::Expr);
+            }
+        }
+    }
+
+    /// Uses a name in the current scope, reporting it if it isn't declared
+    pub(super) fn use_name(
+        &mut self,
+        name: &str,
+        span: Span,
+        context: NameContext,
+    ) -> symbol::UseId {
+        if !self.scopes.is_visible(name) {
+            self.report_undeclared(name, span, context);
+        }
+
+        self.scopes.use_sym(name, span)
+    }
+
+    fn report_undeclared(&mut self, name: &str, span: Span, context: NameContext) {
+        // Suggest the closest visible name of a compatible kind
+        let suggestion = self
+            .scopes
+            .visible_defs()
+            .filter(|(_, def_id)| {
+                let is_type = self.type_defs.contains(def_id);
+
+                match context {
+                    NameContext::Expr => !is_type,
+                    NameContext::Type => is_type,
+                }
+            })
+            .map(|(candidate, _)| (toc_reporting::edit_distance(candidate, name), candidate))
+            .filter(|(distance, _)| (1..=2).contains(distance))
+            .min();
+
+        let message = format!("undeclared identifier `{}`", name);
+        let builder = self.messages.error_detailed(&message, span);
+
+        if let Some((_, suggestion)) = suggestion {
+            builder
+                .with_info(&format!("did you mean `{}`?", suggestion), None)
+                .finish();
+        } else {
+            builder.finish();
+        }
     }
 }
@@ -29,22 +29,22 @@ impl super::LoweringCtx {
         let expr = match expr {
             ast::Expr::LiteralExpr(expr) => self.lower_literal_expr(expr),
             ast::Expr::ObjClassExpr(_) => self.unsupported_expr(span),
-            ast::Expr::InitExpr(_) => self.unsupported_expr(span),
-            ast::Expr::NilExpr(_) => self.unsupported_expr(span),
-            ast::Expr::SizeOfExpr(_) => self.unsupported_expr(span),
+            ast::Expr::InitExpr(expr) => self.lower_init_expr(expr),
+            ast::Expr::NilExpr(expr) => self.lower_nil_expr(expr),
+            ast::Expr::SizeOfExpr(expr) => self.lower_sizeof_expr(expr),
             ast::Expr::BinaryExpr(expr) => self.lower_binary_expr(expr),
             ast::Expr::UnaryExpr(expr) => self.lower_unary_expr(expr),
             ast::Expr::ParenExpr(expr) => self.lower_paren_expr(expr),
             ast::Expr::NameExpr(expr) => self.lower_name_expr(expr),
             ast::Expr::SelfExpr(_) => self.unsupported_expr(span),
-            ast::Expr::FieldExpr(_) => self.unsupported_expr(span),
-            ast::Expr::DerefExpr(_) => self.unsupported_expr(span),
+            ast::Expr::FieldExpr(expr) => self.lower_field_expr(expr),
+            ast::Expr::DerefExpr(expr) => self.lower_deref_expr(expr),
             ast::Expr::CheatExpr(_) => self.unsupported_expr(span),
-            ast::Expr::NatCheatExpr(_) => self.unsupported_expr(span),
+            ast::Expr::NatCheatExpr(expr) => self.lower_nat_cheat_expr(expr),
             ast::Expr::ArrowExpr(_) => self.unsupported_expr(span),
-            ast::Expr::IndirectExpr(_) => self.unsupported_expr(span),
-            ast::Expr::BitsExpr(_) => self.unsupported_expr(span),
-            ast::Expr::CallExpr(_) => self.unsupported_expr(span),
+            ast::Expr::IndirectExpr(expr) => self.lower_indirect_expr(expr),
+            ast::Expr::BitsExpr(expr) => self.lower_bits_expr(expr, span),
+            ast::Expr::CallExpr(expr) => self.lower_call_expr(expr, span),
         }
         .unwrap_or(expr::Expr::Missing);
 
@@ -62,9 +62,12 @@ impl super::LoweringCtx {
         if let Some(errs) = errs {
             let range = expr.syntax().text_range();
 
-            // Report errors
-            // TODO: Add note saying to escape the caret for `InvalidCaretEscape`
-            for (range, err) in errs.iter().map(|msg| msg.message_at(range)) {
+            // Report errors, skipping over ones that the scanner already reported
+            for (range, err) in errs
+                .iter()
+                .filter(|msg| !msg.is_scanner_reported())
+                .map(|msg| msg.message_at(range))
+            {
                 let span = Span::new(self.file, range);
 
                 self.messages.error(&err.to_string(), span);
@@ -84,6 +87,25 @@ impl super::LoweringCtx {
         Some(expr::Expr::Literal(value))
     }
 
+    fn lower_sizeof_expr(&mut self, expr: ast::SizeOfExpr) -> Option<expr::Expr> {
+        let sizeof_tok = expr.sizeof_token()?;
+        let func = Spanned::new(
+            expr::BuiltinFn::SizeOf,
+            Span::new(self.file, sizeof_tok.text_range()),
+        );
+        // Type names are parsed as expressions, and are only told apart during typeck
+        let arg = if let Some(ty) = expr.ty_size() {
+            expr::BuiltinArg::Type(self.lower_required_type(Some(ty)))
+        } else {
+            expr::BuiltinArg::Expr(self.lower_required_expr(expr.ref_size()))
+        };
+
+        Some(expr::Expr::Builtin(expr::Builtin {
+            func,
+            args: vec![arg],
+        }))
+    }
+
     fn lower_binary_expr(&mut self, expr: ast::BinaryExpr) -> Option<expr::Expr> {
         let op_span = toc_span::Span::new(self.file, expr.op_node()?.text_range());
         let op = syntax_to_hir_binary_op(expr.op_kind()?);
@@ -111,9 +133,171 @@ impl super::LoweringCtx {
     fn lower_name_expr(&mut self, expr: ast::NameExpr) -> Option<expr::Expr> {
         let name = expr.name()?.identifier_token()?;
         let span = Span::new(self.file, name.text_range());
-        let use_id = self.scopes.use_sym(name.text(), span);
+        let use_id = self.use_name(name.text(), span, super::NameContext::Expr);
         Some(expr::Expr::Name(expr::Name::Name(use_id)))
     }
+
+    fn lower_field_expr(&mut self, expr: ast::FieldExpr) -> Option<expr::Expr> {
+        let lhs = self.lower_required_expr(expr.expr());
+        let field = expr.name()?.identifier_token()?;
+        let field = Spanned::new(
+            field.text().to_string(),
+            Span::new(self.file, field.text_range()),
+        );
+
+        Some(expr::Expr::Field(expr::Field { lhs, field }))
+    }
+
+    fn lower_init_expr(&mut self, expr: ast::InitExpr) -> Option<expr::Expr> {
+        let exprs = expr
+            .expr_list()?
+            .exprs()
+            .map(|expr| self.lower_expr(expr))
+            .collect();
+
+        Some(expr::Expr::Init(expr::Init { exprs }))
+    }
+
+    fn lower_nil_expr(&mut self, expr: ast::NilExpr) -> Option<expr::Expr> {
+        let collection = expr.expr().map(|expr| self.lower_type_ref(expr));
+
+        Some(expr::Expr::Nil(expr::Nil { collection }))
+    }
+
+    fn lower_deref_expr(&mut self, expr: ast::DerefExpr) -> Option<expr::Expr> {
+        let expr = self.lower_required_expr(expr.expr());
+        Some(expr::Expr::Deref(expr::Deref { expr }))
+    }
+
+    fn lower_nat_cheat_expr(&mut self, expr: ast::NatCheatExpr) -> Option<expr::Expr> {
+        let expr = self.lower_required_expr(expr.expr());
+        Some(expr::Expr::NatCheat(expr::NatCheat { expr }))
+    }
+
+    fn lower_indirect_expr(&mut self, expr: ast::IndirectExpr) -> Option<expr::Expr> {
+        let ty = expr.indirect_ty().map(|ty| match ty {
+            ast::IndirectTy::PrimType(ty) => ast::Type::PrimType(ty),
+            ast::IndirectTy::NameType(ty) => ast::Type::NameType(ty),
+        });
+        let ty = self.lower_required_type(ty);
+        let addr = self.lower_required_expr(expr.expr());
+
+        Some(expr::Expr::Indirect(expr::Indirect { ty, addr }))
+    }
+
+    fn lower_bits_expr(&mut self, expr: ast::BitsExpr, span: Span) -> Option<expr::Expr> {
+        let param_list = expr.param_list()?;
+        let mut params = param_list.param().map(|param| param.param_kind());
+
+        let (lhs, bits) = match (params.next(), params.next(), params.next()) {
+            (Some(lhs), Some(bits), None) => (lhs, bits),
+            _ => {
+                self.messages
+                    .error("expected a reference and a bit number or bit range", span);
+                return None;
+            }
+        };
+
+        let lhs = match lhs {
+            Some(ast::ParamKind::Expr(lhs)) => self.lower_expr(lhs),
+            _ => self.lower_required_expr(None),
+        };
+        let bits = match bits {
+            Some(ast::ParamKind::RangeItem(range)) => {
+                let start = self.lower_bits_bound(range.start());
+                let end = self.lower_bits_bound(range.end());
+
+                expr::BitsSelector::Range(start, end)
+            }
+            Some(ast::ParamKind::Expr(bit)) => expr::BitsSelector::Expr(self.lower_expr(bit)),
+            _ => expr::BitsSelector::Expr(self.lower_required_expr(None)),
+        };
+
+        Some(expr::Expr::Bits(expr::Bits { lhs, bits }))
+    }
+
+    fn lower_bits_bound(&mut self, bound: Option<ast::RangeBound>) -> expr::ExprId {
+        match bound {
+            Some(ast::RangeBound::Expr(expr)) => self.lower_expr(expr),
+            Some(ast::RangeBound::RelativeBound(bound)) => {
+                // Only character sequences have an end to be relative to
+                let span = Span::new(self.file, bound.syntax().text_range());
+                self.messages
+                    .error("bit ranges cannot be relative to the end", span);
+
+                self.lower_required_expr(None)
+            }
+            None => self.lower_required_expr(None),
+        }
+    }
+
+    fn lower_call_expr(&mut self, expr: ast::CallExpr, span: Span) -> Option<expr::Expr> {
+        let param_list = expr.param_list()?;
+        let params = param_list
+            .param()
+            .filter_map(|param| param.param_kind())
+            .collect::<Vec<_>>();
+
+        // Substrings (`lhs(start .. end)`) have a single range as the argument
+        if let [ast::ParamKind::RangeItem(range)] = params.as_slice() {
+            let lhs = self.lower_required_expr(expr.expr());
+            let start = self.lower_substring_bound(range.start());
+            let end = self.lower_substring_bound(range.end());
+
+            return Some(expr::Expr::Substring(expr::Substring { lhs, start, end }));
+        }
+
+        // Subscripts and calls are only distinguished during typeck
+        // TODO: Lower `all` and range arguments for sets and char ranges
+        if !params
+            .iter()
+            .all(|param| matches!(param, ast::ParamKind::Expr(_)))
+        {
+            return self.unsupported_expr(span);
+        }
+
+        let arguments = params.into_iter().filter_map(|param| match param {
+            ast::ParamKind::Expr(arg) => Some(arg),
+            _ => None,
+        });
+
+        if let Some(func) = expr.expr().and_then(|lhs| self.builtin_fn(lhs)) {
+            let args = arguments
+                .map(|arg| expr::BuiltinArg::Expr(self.lower_expr(arg)))
+                .collect();
+
+            return Some(expr::Expr::Builtin(expr::Builtin { func, args }));
+        }
+
+        let lhs = self.lower_required_expr(expr.expr());
+        let arguments = arguments.map(|arg| self.lower_expr(arg)).collect();
+
+        Some(expr::Expr::Call(expr::Call { lhs, arguments }))
+    }
+
+    /// Gets the builtin function named by `lhs`, if it isn't shadowed by a declaration
+    fn builtin_fn(&mut self, lhs: ast::Expr) -> Option<Spanned<expr::BuiltinFn>> {
+        let name = match lhs {
+            ast::Expr::NameExpr(expr) => expr.name()?.identifier_token()?,
+            _ => return None,
+        };
+        let func = expr::BuiltinFn::from_name(name.text())
+            .filter(|_| !self.scopes.is_visible(name.text()))?;
+
+        Some(Spanned::new(func, Span::new(self.file, name.text_range())))
+    }
+
+    fn lower_substring_bound(&mut self, bound: Option<ast::RangeBound>) -> expr::SubstringBound {
+        match bound {
+            Some(ast::RangeBound::RelativeBound(bound)) => {
+                expr::SubstringBound::FromEnd(self.try_lower_expr(bound.expr()))
+            }
+            Some(ast::RangeBound::Expr(expr)) => {
+                expr::SubstringBound::FromStart(self.lower_expr(expr))
+            }
+            None => expr::SubstringBound::FromStart(self.lower_required_expr(None)),
+        }
+    }
 }
 
 fn syntax_to_hir_binary_op(op: toc_syntax::InfixOp) -> expr::BinaryOp {
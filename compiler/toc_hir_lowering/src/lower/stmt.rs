@@ -1,6 +1,8 @@
 //! Lowering into `Stmt` HIR nodes
+use std::collections::HashSet;
+
 use toc_hir::stmt::{Assign, ConstVar};
-use toc_hir::{stmt, symbol};
+use toc_hir::{stmt, symbol, ty};
 use toc_span::{Span, Spanned};
 use toc_syntax::ast::{self, AstNode};
 
@@ -10,16 +12,16 @@ impl super::LoweringCtx {
 
         let stmt = match stmt {
             ast::Stmt::ConstVarDecl(decl) => self.lower_constvar_decl(decl),
-            ast::Stmt::TypeDecl(_) => self.unsupported_stmt(span),
-            ast::Stmt::BindDecl(_) => self.unsupported_stmt(span),
-            ast::Stmt::ProcDecl(_) => self.unsupported_stmt(span),
-            ast::Stmt::FcnDecl(_) => self.unsupported_stmt(span),
-            ast::Stmt::ProcessDecl(_) => self.unsupported_stmt(span),
+            ast::Stmt::TypeDecl(decl) => self.lower_type_decl(decl),
+            ast::Stmt::BindDecl(decl) => self.lower_bind_decl(decl),
+            ast::Stmt::ProcDecl(decl) => self.lower_proc_decl(decl),
+            ast::Stmt::FcnDecl(decl) => self.lower_fcn_decl(decl),
+            ast::Stmt::ProcessDecl(decl) => self.lower_process_decl(decl),
             ast::Stmt::ExternalDecl(_) => self.unsupported_stmt(span),
-            ast::Stmt::ForwardDecl(_) => self.unsupported_stmt(span),
+            ast::Stmt::ForwardDecl(decl) => self.lower_forward_decl(decl),
             ast::Stmt::DeferredDecl(_) => self.unsupported_stmt(span),
-            ast::Stmt::BodyDecl(_) => self.unsupported_stmt(span),
-            ast::Stmt::ModuleDecl(_) => self.unsupported_stmt(span),
+            ast::Stmt::BodyDecl(decl) => self.lower_body_decl(decl),
+            ast::Stmt::ModuleDecl(decl) => self.lower_module_decl(decl),
             ast::Stmt::ClassDecl(_) => self.unsupported_stmt(span),
             ast::Stmt::MonitorDecl(_) => self.unsupported_stmt(span),
             ast::Stmt::AssignStmt(stmt) => self.lower_assign_stmt(stmt),
@@ -31,26 +33,27 @@ impl super::LoweringCtx {
             ast::Stmt::WriteStmt(_) => self.unsupported_stmt(span),
             ast::Stmt::SeekStmt(_) => self.unsupported_stmt(span),
             ast::Stmt::TellStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::ForStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::LoopStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::ExitStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::IfStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::CaseStmt(_) => self.unsupported_stmt(span),
+            ast::Stmt::ForStmt(stmt) => self.lower_for_stmt(stmt),
+            ast::Stmt::LoopStmt(stmt) => self.lower_loop_stmt(stmt),
+            ast::Stmt::ExitStmt(stmt) => self.lower_exit_stmt(stmt),
+            ast::Stmt::IfStmt(stmt) => self.lower_if_stmt(stmt),
+            ast::Stmt::CaseStmt(stmt) => self.lower_case_stmt(stmt),
             ast::Stmt::BlockStmt(stmt) => self.lower_block_stmt(stmt),
             ast::Stmt::InvariantStmt(_) => self.unsupported_stmt(span),
             ast::Stmt::AssertStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::CallStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::ReturnStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::ResultStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::NewStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::FreeStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::TagStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::ForkStmt(_) => self.unsupported_stmt(span),
+            ast::Stmt::CallStmt(stmt) => self.lower_call_stmt(stmt),
+            ast::Stmt::ReturnStmt(_) => Some(stmt::Stmt::Return(stmt::Return)),
+            ast::Stmt::ResultStmt(stmt) => self.lower_result_stmt(stmt),
+            ast::Stmt::NewStmt(stmt) => self.lower_new_stmt(stmt),
+            ast::Stmt::FreeStmt(stmt) => self.lower_free_stmt(stmt),
+            ast::Stmt::TagStmt(stmt) => self.lower_tag_stmt(stmt),
+            ast::Stmt::ForkStmt(stmt) => self.lower_fork_stmt(stmt),
             ast::Stmt::SignalStmt(_) => self.unsupported_stmt(span),
             ast::Stmt::PauseStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::QuitStmt(_) => self.unsupported_stmt(span),
+            ast::Stmt::QuitStmt(stmt) => self.lower_quit_stmt(stmt),
             ast::Stmt::BreakStmt(_) => self.unsupported_stmt(span),
-            ast::Stmt::CheckednessStmt(_) => self.unsupported_stmt(span),
+            // Handled by `lower_stmt_list`, since checkedness applies to a region of statements
+            ast::Stmt::CheckednessStmt(_) => None,
             ast::Stmt::PreStmt(_) => self.unsupported_stmt(span),
             ast::Stmt::InitStmt(_) => self.unsupported_stmt(span),
             ast::Stmt::PostStmt(_) => self.unsupported_stmt(span),
@@ -63,7 +66,115 @@ impl super::LoweringCtx {
             ast::Stmt::PreprocGlob(_) => self.unsupported_stmt(span),
         }?;
 
-        Some(self.database.add_stmt(stmt, span))
+        Some(self.add_stmt(stmt, span))
+    }
+
+    /// Adds a lowered statement, marking it as being in the current unchecked region
+    fn add_stmt(&mut self, stmt: stmt::Stmt, span: Span) -> stmt::StmtId {
+        let id = self.database.add_stmt(stmt, span);
+
+        if let Some(region) = self.unchecked_region {
+            self.database.mark_unchecked(id, region);
+        }
+
+        id
+    }
+
+    /// Lowers a list of statements, applying any checkedness changes to the
+    /// following statements in the list
+    pub(super) fn lower_stmt_list(
+        &mut self,
+        stmt_list: Option<ast::StmtList>,
+    ) -> Vec<stmt::StmtId> {
+        let stmt_list = match stmt_list {
+            Some(stmt_list) => stmt_list,
+            None => return vec![],
+        };
+
+        // Checkedness regions end at the end of the statement list
+        let outer_region = self.unchecked_region;
+        let mut lowered = vec![];
+        // Checkedness statement starting the current region, until there's a statement in it
+        let mut empty_region: Option<(Span, &str)> = None;
+
+        for stmt in stmt_list.stmts() {
+            if let ast::Stmt::CheckednessStmt(stmt) = stmt {
+                let started = self.lower_checkedness_stmt(stmt);
+
+                if let (Some((span, keyword)), Some((_, next))) = (empty_region, started) {
+                    self.report_no_effect(
+                        span,
+                        keyword,
+                        &format!(
+                            "it's immediately undone by the `{}` statement after it",
+                            next
+                        ),
+                    );
+                }
+
+                empty_region = started.or(empty_region);
+            } else {
+                empty_region = None;
+
+                if let Some(id) = self.lower_stmt(stmt) {
+                    lowered.push(id);
+                }
+            }
+        }
+
+        if let Some((span, keyword)) = empty_region {
+            self.report_no_effect(
+                span,
+                keyword,
+                "there are no statements after this in the block",
+            );
+        }
+
+        self.unchecked_region = outer_region;
+
+        lowered
+    }
+
+    /// Lowers a checkedness statement, giving back its span and keyword if it starts a new region
+    fn lower_checkedness_stmt(
+        &mut self,
+        stmt: ast::CheckednessStmt,
+    ) -> Option<(Span, &'static str)> {
+        let span = Span::new(self.file, stmt.syntax().text_range());
+        let (checkedness, keyword) = match stmt.checkedness()? {
+            checkedness if checkedness.unchecked_token().is_some() => {
+                (stmt::Checkedness::Unchecked, "unchecked")
+            }
+            _ => (stmt::Checkedness::Checked, "checked"),
+        };
+
+        let current = match self.unchecked_region {
+            Some(_) => stmt::Checkedness::Unchecked,
+            None => stmt::Checkedness::Checked,
+        };
+
+        if checkedness == current {
+            // Redundant statements don't start a new region
+            self.messages.info(
+                &format!("redundant `{}` statement, already {}", keyword, keyword),
+                span,
+            );
+            return None;
+        }
+
+        self.unchecked_region = match checkedness {
+            stmt::Checkedness::Unchecked => Some(span),
+            stmt::Checkedness::Checked => None,
+        };
+
+        Some((span, keyword))
+    }
+
+    fn report_no_effect(&mut self, span: Span, keyword: &str, info: &str) {
+        self.messages
+            .warn_detailed(&format!("`{}` statement has no effect", keyword), span)
+            .with_info(info, None)
+            .finish();
     }
 
     fn unsupported_stmt(&mut self, span: Span) -> Option<stmt::Stmt> {
@@ -98,6 +209,443 @@ impl super::LoweringCtx {
         }))
     }
 
+    fn lower_type_decl(&mut self, decl: ast::TypeDecl) -> Option<stmt::Stmt> {
+        let is_pervasive = decl.pervasive_attr().is_some();
+
+        let (type_def, kind) = if let Some(forward) = decl.forward_token() {
+            let span = Span::new(self.file, forward.text_range());
+            (
+                stmt::TypeDefKind::Forward(span),
+                symbol::SymbolKind::Forward,
+            )
+        } else {
+            let ty = self.lower_type(decl.named_ty()?)?;
+            (stmt::TypeDefKind::Alias(ty), symbol::SymbolKind::Declared)
+        };
+
+        // Declare name after the type to prevent def-use cycles
+        let name = decl.decl_name()?.identifier_token()?;
+        let span = Span::new(self.file, name.text_range());
+
+        let def_id = match (kind, self.find_forward_type(name.text())) {
+            // Link back to the forward declaration that this resolves
+            (symbol::SymbolKind::Declared, Some(forward_def)) => self.declare_name(
+                name.text(),
+                span,
+                symbol::SymbolKind::Resolved(forward_def),
+                is_pervasive,
+            ),
+            // Repeated forward declarations still refer to the first one, so that
+            // resolving the type resolves all of them
+            (symbol::SymbolKind::Forward, Some(forward_def)) => {
+                self.report_redeclaration(name.text(), span, kind);
+                forward_def
+            }
+            (kind, _) => self.declare_name(name.text(), span, kind, is_pervasive),
+        };
+
+        self.type_defs.insert(def_id);
+
+        Some(stmt::Stmt::Type(stmt::Type { def_id, type_def }))
+    }
+
+    fn lower_bind_decl(&mut self, decl: ast::BindDecl) -> Option<stmt::Stmt> {
+        let bindings = decl
+            .bindings()
+            .filter_map(|binding| {
+                let is_var = binding.as_var().is_some();
+                let is_register = binding.to_register().is_some();
+
+                let bind_to = binding.expr()?;
+                let is_reference = self.check_bind_target(&bind_to);
+                let bind_to = self.lower_expr(bind_to);
+
+                // Declare name after the target to prevent def-use cycles
+                let name = binding.bind_as()?.identifier_token()?;
+                let span = Span::new(self.file, name.text_range());
+                let def_id =
+                    self.declare_name(name.text(), span, symbol::SymbolKind::Declared, false);
+
+                // Invalid targets still declare the name, so that uses of it aren't
+                // also reported
+                is_reference.then_some(stmt::BindItem {
+                    is_var,
+                    is_register,
+                    def_id,
+                    bind_to,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if bindings.is_empty() {
+            // Either already reported, or captured by the parser
+            None
+        } else {
+            Some(stmt::Stmt::Bind(stmt::Bind { bindings }))
+        }
+    }
+
+    /// Checks that a bind target is a reference, reporting it if it isn't
+    fn check_bind_target(&mut self, expr: &ast::Expr) -> bool {
+        let is_reference = match expr {
+            ast::Expr::NameExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndirectExpr(_)
+            | ast::Expr::DerefExpr(_)
+            | ast::Expr::ArrowExpr(_) => true,
+            // Substrings are never references
+            ast::Expr::CallExpr(expr) => !expr.param_list().is_some_and(|params| {
+                params
+                    .param()
+                    .any(|param| matches!(param.param_kind(), Some(ast::ParamKind::RangeItem(_))))
+            }),
+            _ => false,
+        };
+
+        if !is_reference {
+            let span = Span::new(self.file, expr.syntax().text_range());
+
+            self.messages
+                .error_detailed("cannot bind to this expression", span)
+                .with_note("expected a reference to a variable or constant", span)
+                .finish();
+        }
+
+        is_reference
+    }
+
+    fn lower_proc_decl(&mut self, decl: ast::ProcDecl) -> Option<stmt::Stmt> {
+        let header = decl.proc_header()?;
+
+        self.lower_subprogram_decl(
+            ty::SubprogramKind::Procedure,
+            header.name()?,
+            header.pervasive_attr().is_some(),
+            header.params(),
+            None,
+            decl.stmt_list(),
+            symbol::SymbolKind::Declared,
+        )
+    }
+
+    fn lower_fcn_decl(&mut self, decl: ast::FcnDecl) -> Option<stmt::Stmt> {
+        let header = decl.fcn_header()?;
+
+        self.lower_subprogram_decl(
+            ty::SubprogramKind::Function,
+            header.name()?,
+            header.pervasive_attr().is_some(),
+            header.param_spec(),
+            Some(header.fcn_result()),
+            decl.stmt_list(),
+            symbol::SymbolKind::Declared,
+        )
+    }
+
+    fn lower_forward_decl(&mut self, decl: ast::ForwardDecl) -> Option<stmt::Stmt> {
+        // Import lists only restrict what the body can refer to, which isn't checked yet
+        let header = decl.subprog_header()?;
+        let (kind, name, is_pervasive, params, result) = match &header {
+            ast::SubprogHeader::ProcHeader(header) => (
+                ty::SubprogramKind::Procedure,
+                header.name(),
+                header.pervasive_attr().is_some(),
+                header.params(),
+                None,
+            ),
+            ast::SubprogHeader::FcnHeader(header) => (
+                ty::SubprogramKind::Function,
+                header.name(),
+                header.pervasive_attr().is_some(),
+                header.param_spec(),
+                Some(header.fcn_result()),
+            ),
+        };
+
+        // Parameter names are only declared by the body, so only the types are kept
+        let span = Span::new(self.file, header.syntax().text_range());
+        let params = params.map(|spec| self.lower_param_types(spec));
+        let result =
+            result.map(|result| self.lower_required_type(result.and_then(|result| result.ty())));
+        let ty = self.database.add_type(
+            ty::Type::Subprogram(ty::Subprogram {
+                kind,
+                params,
+                result,
+            }),
+            span,
+        );
+
+        let name = name?.identifier_token()?;
+        let span = Span::new(self.file, name.text_range());
+        let def_id =
+            self.declare_name(name.text(), span, symbol::SymbolKind::Forward, is_pervasive);
+        self.forward_headers.insert(def_id, header);
+
+        Some(stmt::Stmt::Forward(stmt::Forward { def_id, ty }))
+    }
+
+    fn lower_body_decl(&mut self, decl: ast::BodyDecl) -> Option<stmt::Stmt> {
+        let unsupported_parts = [
+            decl.pre_stmt().map(|node| node.syntax().text_range()),
+            decl.init_stmt().map(|node| node.syntax().text_range()),
+            decl.post_stmt().map(|node| node.syntax().text_range()),
+            decl.handler_stmt().map(|node| node.syntax().text_range()),
+        ];
+
+        for range in unsupported_parts.iter().flatten() {
+            self.unsupported_stmt(Span::new(self.file, *range));
+        }
+
+        let (name, is_pervasive, params, result) = match decl.body_kind()? {
+            ast::BodyKind::PlainHeader(header) => (
+                header.name(),
+                false,
+                header.param_spec(),
+                header.fcn_result(),
+            ),
+            ast::BodyKind::ProcHeader(header) => (
+                header.name(),
+                header.pervasive_attr().is_some(),
+                header.params(),
+                None,
+            ),
+            ast::BodyKind::FcnHeader(header) => (
+                header.name(),
+                header.pervasive_attr().is_some(),
+                header.param_spec(),
+                header.fcn_result(),
+            ),
+        };
+        let name = name?;
+        let forward = name
+            .identifier_token()
+            .and_then(|name| self.find_forward_subprogram(name.text()));
+
+        // Missing forward declarations are reported during validation
+        let (forward_def, header) = match forward {
+            Some((forward_def, header)) => (forward_def, header),
+            None => {
+                let (kind, result) = match decl.body_kind()? {
+                    ast::BodyKind::FcnHeader(_) => (ty::SubprogramKind::Function, Some(result)),
+                    _ if result.is_some() => (ty::SubprogramKind::Function, Some(result)),
+                    _ => (ty::SubprogramKind::Procedure, None),
+                };
+
+                return self.lower_subprogram_decl(
+                    kind,
+                    name,
+                    is_pervasive,
+                    params,
+                    result,
+                    decl.stmt_list(),
+                    symbol::SymbolKind::Declared,
+                );
+            }
+        };
+
+        // The forward declaration gives the parameters and result type if they're left out,
+        // with any differences reported during validation
+        let (kind, forward_pervasive, forward_params, forward_result) = match header {
+            ast::SubprogHeader::ProcHeader(header) => (
+                ty::SubprogramKind::Procedure,
+                header.pervasive_attr().is_some(),
+                header.params(),
+                None,
+            ),
+            ast::SubprogHeader::FcnHeader(header) => (
+                ty::SubprogramKind::Function,
+                header.pervasive_attr().is_some(),
+                header.param_spec(),
+                header.fcn_result(),
+            ),
+        };
+        let result = match kind {
+            ty::SubprogramKind::Procedure => None,
+            ty::SubprogramKind::Function => Some(result.or(forward_result)),
+        };
+
+        self.lower_subprogram_decl(
+            kind,
+            name,
+            is_pervasive || forward_pervasive,
+            params.or(forward_params),
+            result,
+            decl.stmt_list(),
+            symbol::SymbolKind::Resolved(forward_def),
+        )
+    }
+
+    /// Lowers a procedure or function declaration, declaring the name as `symbol_kind`.
+    /// `result` is only present for functions.
+    #[allow(clippy::too_many_arguments)]
+    fn lower_subprogram_decl(
+        &mut self,
+        kind: ty::SubprogramKind,
+        name: ast::Name,
+        is_pervasive: bool,
+        params: Option<ast::ParamSpec>,
+        result: Option<Option<ast::FcnResult>>,
+        stmt_list: Option<ast::StmtList>,
+        symbol_kind: symbol::SymbolKind,
+    ) -> Option<stmt::Stmt> {
+        // Declared before the body, so that subprograms can call themselves
+        let name = name.identifier_token()?;
+        let span = Span::new(self.file, name.text_range());
+        let def_id = self.declare_name(name.text(), span, symbol_kind, is_pervasive);
+
+        self.scopes.push_scope(false);
+
+        let params = params.map(|spec| self.lower_param_spec(spec));
+        let result = result.map(|result| self.lower_fcn_result(result));
+        let stmts = self.lower_stmt_list(stmt_list);
+
+        self.scopes.pop_scope();
+
+        let (result, result_name) = match result {
+            Some((ty, name)) => (Some(ty), name),
+            None => (None, None),
+        };
+
+        Some(stmt::Stmt::Subprogram(stmt::Subprogram {
+            kind,
+            def_id,
+            params,
+            result,
+            result_name,
+            stmts,
+        }))
+    }
+
+    /// Lowers the result type of a function, declaring the result name in the current scope
+    fn lower_fcn_result(
+        &mut self,
+        result: Option<ast::FcnResult>,
+    ) -> (ty::TypeId, Option<symbol::DefId>) {
+        let result_ty = self.lower_required_type(result.as_ref().and_then(|result| result.ty()));
+        let result_name = result
+            .and_then(|result| result.name())
+            .and_then(|name| name.identifier_token())
+            .map(|name| {
+                let span = Span::new(self.file, name.text_range());
+                self.declare_name(name.text(), span, symbol::SymbolKind::Declared, false)
+            });
+
+        (result_ty, result_name)
+    }
+
+    fn lower_process_decl(&mut self, decl: ast::ProcessDecl) -> Option<stmt::Stmt> {
+        let is_pervasive = decl.pervasive_attr().is_some();
+        let stack_size = self.try_lower_expr(decl.stack_size());
+
+        // Declared before the body, so that processes can fork themselves
+        let name = decl.name()?.identifier_token()?;
+        let span = Span::new(self.file, name.text_range());
+        let def_id = self.declare_name(
+            name.text(),
+            span,
+            symbol::SymbolKind::Declared,
+            is_pervasive,
+        );
+
+        self.scopes.push_scope(false);
+
+        let params = decl
+            .param_spec()
+            .map(|spec| self.lower_param_spec(spec))
+            .unwrap_or_default();
+        let stmts = self.lower_stmt_list(decl.stmt_list());
+
+        self.scopes.pop_scope();
+
+        Some(stmt::Stmt::Process(stmt::Process {
+            def_id,
+            params,
+            stack_size,
+            stmts,
+        }))
+    }
+
+    fn lower_module_decl(&mut self, decl: ast::ModuleDecl) -> Option<stmt::Stmt> {
+        let is_pervasive = decl.pervasive_attr().is_some();
+
+        // Declared before the body, so that the body can refer to the module
+        let name = decl.name()?.identifier_token()?;
+        let span = Span::new(self.file, name.text_range());
+        let def_id = self.declare_name(
+            name.text(),
+            span,
+            symbol::SymbolKind::Declared,
+            is_pervasive,
+        );
+
+        let unsupported_parts = [
+            decl.implement_stmt().map(|node| node.syntax().text_range()),
+            decl.implement_by_stmt()
+                .map(|node| node.syntax().text_range()),
+            decl.import_stmt().map(|node| node.syntax().text_range()),
+            decl.export_stmt().map(|node| node.syntax().text_range()),
+            decl.pre_stmt().map(|node| node.syntax().text_range()),
+            decl.post_stmt().map(|node| node.syntax().text_range()),
+        ];
+
+        for range in unsupported_parts.iter().flatten() {
+            self.unsupported_stmt(Span::new(self.file, *range));
+        }
+
+        // Modules are closed off, so only pervasive names from outside are visible
+        self.scopes.push_scope(true);
+        let stmts = self.lower_stmt_list(decl.stmt_list());
+        self.scopes.pop_scope();
+
+        Some(stmt::Stmt::Module(stmt::Module { def_id, stmts }))
+    }
+
+    /// Lowers the parameters of a subprogram, declaring the parameter names in the current scope
+    fn lower_param_spec(&mut self, spec: ast::ParamSpec) -> Vec<stmt::Param> {
+        spec.param_decl()
+            .filter_map(|param| {
+                let param = match param {
+                    ast::ParamDecl::ConstVarParam(param) => param,
+                    ast::ParamDecl::SubprogType(param) => return self.lower_subprog_param(param),
+                };
+
+                let is_var = param.pass_as_ref().is_some();
+                let is_register = param.bind_to_register().is_some();
+                let param_ty = self.lower_type(param.param_ty()?)?;
+                let names = self.lower_name_list(param.param_names(), false)?;
+
+                Some(stmt::Param {
+                    is_var,
+                    is_register,
+                    names,
+                    param_ty,
+                })
+            })
+            .collect()
+    }
+
+    /// Lowers a subprogram parameter, which is named by its subprogram type
+    fn lower_subprog_param(&mut self, param: ast::SubprogType) -> Option<stmt::Param> {
+        let span = Span::new(self.file, param.syntax().text_range());
+        let (name, ty) = match param {
+            ast::SubprogType::FcnType(ty) => (ty.name(), self.lower_fcn_type(ty)),
+            ast::SubprogType::ProcType(ty) => (ty.name(), self.lower_proc_type(ty)),
+        };
+        let param_ty = self.database.add_type(ty?, span);
+
+        let name = name?.identifier_token()?;
+        let name_span = Span::new(self.file, name.text_range());
+        let def_id = self.declare_name(name.text(), name_span, symbol::SymbolKind::Declared, false);
+
+        Some(stmt::Param {
+            is_var: false,
+            is_register: false,
+            names: vec![def_id],
+            param_ty,
+        })
+    }
+
     fn lower_assign_stmt(&mut self, stmt: ast::AssignStmt) -> Option<stmt::Stmt> {
         let op = {
             let asn_op = stmt.asn_op()?;
@@ -208,43 +756,318 @@ impl super::LoweringCtx {
         }
     }
 
+    fn lower_quit_stmt(&mut self, stmt: ast::QuitStmt) -> Option<stmt::Stmt> {
+        let cause = match stmt.reason() {
+            Some(cause) if cause.at_caller().is_some() => stmt::QuitCause::AtCaller,
+            Some(cause) if cause.bubble_up().is_some() => stmt::QuitCause::BubbleUp,
+            _ => stmt::QuitCause::Here,
+        };
+        let reason = self.try_lower_expr(stmt.quit_code());
+
+        Some(stmt::Stmt::Quit(stmt::Quit { cause, reason }))
+    }
+
+    fn lower_new_stmt(&mut self, stmt: ast::NewStmt) -> Option<stmt::Stmt> {
+        let (collection, pointer) = self.lower_alloc_args(stmt.expr_list()?, "new")?;
+
+        Some(stmt::Stmt::New(stmt::New {
+            collection,
+            pointer,
+        }))
+    }
+
+    fn lower_free_stmt(&mut self, stmt: ast::FreeStmt) -> Option<stmt::Stmt> {
+        let (collection, pointer) = self.lower_alloc_args(stmt.expr_list()?, "free")?;
+
+        Some(stmt::Stmt::Free(stmt::Free {
+            collection,
+            pointer,
+        }))
+    }
+
+    fn lower_tag_stmt(&mut self, stmt: ast::TagStmt) -> Option<stmt::Stmt> {
+        let union_ref = self.lower_expr(stmt.tag_ref()?);
+        let tag_val = self.lower_expr(stmt.tag_val()?);
+
+        Some(stmt::Stmt::Tag(stmt::Tag { union_ref, tag_val }))
+    }
+
+    /// Lowers the arguments of a `new` or `free` statement, which are either
+    /// just the pointer, or the collection followed by the pointer
+    fn lower_alloc_args(
+        &mut self,
+        args: ast::ExprList,
+        keyword: &str,
+    ) -> Option<(Option<toc_hir::expr::ExprId>, toc_hir::expr::ExprId)> {
+        let mut exprs = args.exprs().collect::<Vec<_>>();
+
+        if exprs.len() > 2 {
+            let extra = exprs
+                .drain(2..)
+                .map(|expr| expr.syntax().text_range())
+                .reduce(|all, range| all.cover(range))
+                .unwrap();
+            let span = Span::new(self.file, extra);
+
+            self.messages
+                .error_detailed(&format!("too many arguments to `{}`", keyword), span)
+                .with_info(
+                    &format!(
+                        "expected either `{0} pointer` or `{0} collection, pointer`",
+                        keyword
+                    ),
+                    None,
+                )
+                .finish();
+        }
+
+        let mut exprs = exprs.into_iter().map(|expr| self.lower_expr(expr));
+
+        match (exprs.next(), exprs.next()) {
+            (Some(collection), Some(pointer)) => Some((Some(collection), pointer)),
+            (Some(pointer), None) => Some((None, pointer)),
+            // Already reported by the parser
+            _ => None,
+        }
+    }
+
+    fn lower_call_stmt(&mut self, stmt: ast::CallStmt) -> Option<stmt::Stmt> {
+        let (callee, arguments) = self.lower_callee(stmt.expr()?);
+
+        Some(stmt::Stmt::Call(stmt::Call { callee, arguments }))
+    }
+
+    fn lower_fork_stmt(&mut self, stmt: ast::ForkStmt) -> Option<stmt::Stmt> {
+        let (process, arguments) = self.lower_callee(stmt.expr()?);
+        let status = self.try_lower_expr(stmt.fork_status().and_then(|status| status.expr()));
+        let stack_size = self.try_lower_expr(stmt.stack_size().and_then(|size| size.expr()));
+        let process_desc = self.try_lower_expr(stmt.process_desc().and_then(|desc| desc.expr()));
+
+        Some(stmt::Stmt::Fork(stmt::Fork {
+            process,
+            arguments,
+            status,
+            stack_size,
+            process_desc,
+        }))
+    }
+
+    /// Splits the target of a call or `fork` into the subprogram and the arguments to it
+    fn lower_callee(
+        &mut self,
+        expr: ast::Expr,
+    ) -> (toc_hir::expr::ExprId, Vec<toc_hir::expr::ExprId>) {
+        if let ast::Expr::CallExpr(call) = &expr {
+            let args = call
+                .param_list()
+                .map(|list| {
+                    list.param()
+                        .map(|param| match param.param_kind() {
+                            Some(ast::ParamKind::Expr(arg)) => Some(arg),
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()
+                })
+                .unwrap_or_default();
+
+            // Anything that isn't a plain argument list is left for expression lowering to deal with
+            if let (Some(lhs), Some(args)) = (call.expr(), args) {
+                let callee = self.lower_expr(lhs);
+                let arguments = args.into_iter().map(|arg| self.lower_expr(arg)).collect();
+
+                return (callee, arguments);
+            }
+        }
+
+        (self.lower_expr(expr), vec![])
+    }
+
     fn lower_block_stmt(&mut self, stmt: ast::BlockStmt) -> Option<stmt::Stmt> {
+        let stmts = self.lower_scoped_stmt_list(stmt.stmt_list());
+
+        Some(stmt::Stmt::Block(stmt::Block { stmts }))
+    }
+
+    fn lower_for_stmt(&mut self, stmt: ast::ForStmt) -> Option<stmt::Stmt> {
+        let is_decreasing = stmt.decreasing_token().is_some();
+        let bounds = match stmt.bounds() {
+            Some((start, Some(end))) => {
+                stmt::ForBounds::Full(self.lower_expr(start), self.lower_expr(end))
+            }
+            Some((bounds, None)) => stmt::ForBounds::Implicit(self.lower_type_ref(bounds)),
+            None => {
+                let start = self.lower_required_expr(None);
+                let end = self.lower_required_expr(None);
+                stmt::ForBounds::Full(start, end)
+            }
+        };
+        let step_by = self.try_lower_expr(stmt.steps().and_then(|step| step.expr()));
+
+        // The counter is only visible inside of the loop body
         self.scopes.push_scope(false);
 
-        let stmts = if let Some(stmts) = stmt.stmt_list() {
-            stmts
-                .stmts()
-                .filter_map(|stmt| self.lower_stmt(stmt))
-                .collect()
-        } else {
-            vec![]
+        let counter_def = stmt
+            .name()
+            .and_then(|name| name.identifier_token())
+            .map(|name| {
+                let span = Span::new(self.file, name.text_range());
+                self.declare_name(name.text(), span, symbol::SymbolKind::Declared, false)
+            });
+        let stmts = self.lower_stmt_list(stmt.stmt_list());
+
+        self.scopes.pop_scope();
+
+        Some(stmt::Stmt::For(stmt::For {
+            is_decreasing,
+            counter_def,
+            bounds,
+            step_by,
+            stmts,
+        }))
+    }
+
+    fn lower_loop_stmt(&mut self, stmt: ast::LoopStmt) -> Option<stmt::Stmt> {
+        let stmts = self.lower_scoped_stmt_list(stmt.stmt_list());
+
+        Some(stmt::Stmt::Loop(stmt::Loop { stmts }))
+    }
+
+    fn lower_exit_stmt(&mut self, stmt: ast::ExitStmt) -> Option<stmt::Stmt> {
+        let when_condition = self.try_lower_expr(stmt.condition());
+
+        Some(stmt::Stmt::Exit(stmt::Exit { when_condition }))
+    }
+
+    fn lower_if_stmt(&mut self, stmt: ast::IfStmt) -> Option<stmt::Stmt> {
+        self.lower_if_body(stmt.if_body()?)
+    }
+
+    /// Lowers the condition and branches of an `if` or `elsif`
+    fn lower_if_body(&mut self, body: ast::IfBody) -> Option<stmt::Stmt> {
+        let condition = self.lower_required_expr(body.condition());
+        let true_branch = self.lower_scoped_stmt_list(body.true_branch());
+
+        let false_branch = match body.false_branch() {
+            Some(ast::FalseBranch::ElseifStmt(stmt)) => {
+                let span = Span::new(self.file, stmt.syntax().text_range());
+
+                match stmt.if_body().and_then(|body| self.lower_if_body(body)) {
+                    Some(elseif) => stmt::FalseBranch::ElseIf(self.add_stmt(elseif, span)),
+                    None => stmt::FalseBranch::None,
+                }
+            }
+            Some(ast::FalseBranch::ElseStmt(stmt)) => {
+                stmt::FalseBranch::Else(self.lower_scoped_stmt_list(stmt.stmt_list()))
+            }
+            None => stmt::FalseBranch::None,
         };
 
+        Some(stmt::Stmt::If(stmt::If {
+            condition,
+            true_branch,
+            false_branch,
+        }))
+    }
+
+    fn lower_case_stmt(&mut self, stmt: ast::CaseStmt) -> Option<stmt::Stmt> {
+        let discriminant = self.lower_required_expr(stmt.expr());
+        let arms = stmt
+            .case_arm()
+            .map(|arm| {
+                let selectors = arm
+                    .select()
+                    .map(|select| select.exprs().map(|expr| self.lower_expr(expr)).collect());
+                let stmts = self.lower_scoped_stmt_list(arm.stmt_list());
+
+                stmt::CaseArm { selectors, stmts }
+            })
+            .collect();
+
+        Some(stmt::Stmt::Case(stmt::Case { discriminant, arms }))
+    }
+
+    fn lower_result_stmt(&mut self, stmt: ast::ResultStmt) -> Option<stmt::Stmt> {
+        let expr = self.lower_required_expr(stmt.expr());
+
+        Some(stmt::Stmt::Result(stmt::Result { expr }))
+    }
+
+    /// Lowers a list of statements inside of a new scope
+    fn lower_scoped_stmt_list(&mut self, stmt_list: Option<ast::StmtList>) -> Vec<stmt::StmtId> {
+        self.scopes.push_scope(false);
+
+        let stmts = self.lower_stmt_list(stmt_list);
+
         self.scopes.pop_scope();
 
-        Some(stmt::Stmt::Block(stmt::Block { stmts }))
+        stmts
+    }
+
+    /// Declares a name in the current scope, reporting any redeclarations
+    /// within the same scope
+    pub(super) fn declare_name(
+        &mut self,
+        name: &str,
+        span: Span,
+        kind: symbol::SymbolKind,
+        is_pervasive: bool,
+    ) -> symbol::DefId {
+        self.report_redeclaration(name, span, kind);
+        self.scopes.def_sym(name, span, kind, is_pervasive)
+    }
+
+    /// Finds an unresolved forward type declaration of `name` in the current scope
+    pub(super) fn find_forward_type(&self, name: &str) -> Option<symbol::DefId> {
+        self.scopes
+            .find_forward(name)
+            .filter(|def_id| self.type_defs.contains(def_id))
+    }
+
+    /// Finds an unresolved forward subprogram declaration of `name` in the current scope,
+    /// along with its header
+    fn find_forward_subprogram(&self, name: &str) -> Option<(symbol::DefId, ast::SubprogHeader)> {
+        let def_id = self.scopes.find_forward(name)?;
+        let header = self.forward_headers.get(&def_id)?;
+        let header = ast::SubprogHeader::cast(header.syntax().clone())?;
+        Some((def_id, header))
+    }
+
+    fn report_redeclaration(&mut self, name: &str, span: Span, kind: symbol::SymbolKind) {
+        if let Some(prev_span) = self.scopes.find_redeclaration(name, kind) {
+            self.messages
+                .error_detailed(
+                    &format!("`{}` is already declared in this scope", name),
+                    span,
+                )
+                .with_note(&format!("`{}` previously declared here", name), prev_span)
+                .finish();
+        }
     }
 
     /// Lowers a name list, holding up the invariant that it always contains
-    /// at least one identifier
+    /// at least one identifier.
+    ///
+    /// Names repeated in the same list are only declared once, since they are
+    /// already reported during validation.
     fn lower_name_list(
         &mut self,
         name_list: Option<ast::NameList>,
         is_pervasive: bool,
     ) -> Option<Vec<symbol::DefId>> {
+        let mut seen = HashSet::new();
         let names = name_list?
             .names()
-            .filter_map(|name| {
-                name.identifier_token().map(|token| {
-                    let span = Span::new(self.file, token.text_range());
+            .filter_map(|name| name.identifier_token())
+            .filter(|token| seen.insert(token.text().to_string()))
+            .map(|token| {
+                let span = Span::new(self.file, token.text_range());
 
-                    self.scopes.def_sym(
-                        token.text(),
-                        span,
-                        symbol::SymbolKind::Declared,
-                        is_pervasive,
-                    )
-                })
+                self.declare_name(
+                    token.text(),
+                    span,
+                    symbol::SymbolKind::Declared,
+                    is_pervasive,
+                )
             })
             .collect::<Vec<_>>();
 
@@ -1,6 +1,6 @@
 //! Lowering into `Type` HIR nodes
-use toc_hir::ty;
-use toc_span::Span;
+use toc_hir::{stmt, symbol, ty};
+use toc_span::{Span, Spanned};
 use toc_syntax::ast::{self, AstNode};
 
 impl super::LoweringCtx {
@@ -9,18 +9,18 @@ impl super::LoweringCtx {
 
         let ty = match ty {
             ast::Type::PrimType(ty) => self.lower_prim_type(ty),
-            ast::Type::NameType(_) => self.unsupported_ty(span),
-            ast::Type::RangeType(_) => self.unsupported_ty(span),
-            ast::Type::EnumType(_) => self.unsupported_ty(span),
-            ast::Type::ArrayType(_) => self.unsupported_ty(span),
-            ast::Type::SetType(_) => self.unsupported_ty(span),
-            ast::Type::RecordType(_) => self.unsupported_ty(span),
-            ast::Type::UnionType(_) => self.unsupported_ty(span),
-            ast::Type::PointerType(_) => self.unsupported_ty(span),
-            ast::Type::FcnType(_) => self.unsupported_ty(span),
-            ast::Type::ProcType(_) => self.unsupported_ty(span),
-            ast::Type::CollectionType(_) => self.unsupported_ty(span),
-            ast::Type::ConditionType(_) => self.unsupported_ty(span),
+            ast::Type::NameType(ty) => self.lower_name_type(ty),
+            ast::Type::RangeType(ty) => self.lower_range_type(ty),
+            ast::Type::EnumType(ty) => self.lower_enum_type(ty),
+            ast::Type::ArrayType(ty) => self.lower_array_type(ty),
+            ast::Type::SetType(ty) => self.lower_set_type(ty),
+            ast::Type::RecordType(ty) => self.lower_record_type(ty),
+            ast::Type::UnionType(ty) => self.lower_union_type(ty),
+            ast::Type::PointerType(ty) => self.lower_pointer_type(ty),
+            ast::Type::FcnType(ty) => self.lower_fcn_type(ty),
+            ast::Type::ProcType(ty) => self.lower_proc_type(ty),
+            ast::Type::CollectionType(ty) => self.lower_collection_type(ty),
+            ast::Type::ConditionType(ty) => self.lower_condition_type(ty),
         }?;
 
         Some(self.database.add_type(ty, span))
@@ -59,6 +59,270 @@ impl super::LoweringCtx {
         Some(ty::Type::Primitive(kind))
     }
 
+    fn lower_name_type(&mut self, ty: ast::NameType) -> Option<ty::Type> {
+        self.lower_type_name(ty.expr()?)
+    }
+
+    /// Lowers an expression naming a type, e.g. the collection in `nil(collection)`
+    pub(super) fn lower_type_ref(&mut self, expr: ast::Expr) -> ty::TypeId {
+        let span = Span::new(self.file, expr.syntax().text_range());
+        let ty = match expr {
+            ast::Expr::NameExpr(_) | ast::Expr::FieldExpr(_) => self.lower_type_name(expr),
+            _ => {
+                self.messages.error("expected a type name", span);
+                None
+            }
+        };
+        let ty = ty.unwrap_or(ty::Type::Missing);
+
+        self.database.add_type(ty, span)
+    }
+
+    fn lower_type_name(&mut self, expr: ast::Expr) -> Option<ty::Type> {
+        match expr {
+            ast::Expr::NameExpr(expr) => {
+                let name = expr.name()?.identifier_token()?;
+                let span = Span::new(self.file, name.text_range());
+                let use_id = self.use_name(name.text(), span, super::NameContext::Type);
+
+                Some(ty::Type::Name(use_id))
+            }
+            // TODO: Lower type paths once module exports are lowered
+            ast::Expr::FieldExpr(expr) => {
+                let span = Span::new(self.file, expr.syntax().text_range());
+                self.unsupported_ty(span)
+            }
+            // Only names are parsed as type names, except for the types of
+            // indirection expressions, which are checked during validation
+            _ => None,
+        }
+    }
+
+    fn lower_range_type(&mut self, ty: ast::RangeType) -> Option<ty::Type> {
+        let start = self.lower_required_expr(ty.begin());
+        let end = match ty.end() {
+            Some(ast::EndBound::UnsizedBound(_)) => ty::RangeEnd::Unsized,
+            Some(ast::EndBound::Expr(expr)) => ty::RangeEnd::Expr(self.lower_expr(expr)),
+            None => ty::RangeEnd::Expr(self.lower_required_expr(None)),
+        };
+
+        Some(ty::Type::Range(ty::Range { start, end }))
+    }
+
+    fn lower_pointer_type(&mut self, ty: ast::PointerType) -> Option<ty::Type> {
+        let checkedness = match ty.is_checked() {
+            Some(checked) if checked.unchecked_token().is_some() => stmt::Checkedness::Unchecked,
+            _ => stmt::Checkedness::Checked,
+        };
+        let to = self.lower_required_type(ty.to_ty());
+
+        Some(ty::Type::Pointer(ty::Pointer { checkedness, to }))
+    }
+
+    fn lower_collection_type(&mut self, ty: ast::CollectionType) -> Option<ty::Type> {
+        let elem = if ty.forward_token().is_some() {
+            self.lower_forward_elem(ty.name()?)?
+        } else {
+            self.lower_required_type(ty.elem_ty())
+        };
+
+        Some(ty::Type::Collection(ty::Collection { elem }))
+    }
+
+    /// Lowers the element type of `collection of forward name`, which acts as
+    /// a forward declaration of `name` that a later type declaration resolves
+    fn lower_forward_elem(&mut self, name: ast::Name) -> Option<ty::TypeId> {
+        let name = name.identifier_token()?;
+        let span = Span::new(self.file, name.text_range());
+
+        // Repeated uses of the same forward type refer to the same declaration
+        if self.find_forward_type(name.text()).is_none() {
+            let def_id = self.declare_name(name.text(), span, symbol::SymbolKind::Forward, false);
+            self.type_defs.insert(def_id);
+        }
+
+        let use_id = self.use_name(name.text(), span, super::NameContext::Type);
+        Some(self.database.add_type(ty::Type::Name(use_id), span))
+    }
+
+    fn lower_condition_type(&mut self, ty: ast::ConditionType) -> Option<ty::Type> {
+        let kind = match ty.condition_kind() {
+            Some(kind) if kind.priority_token().is_some() => ty::ConditionKind::Priority,
+            Some(kind) if kind.deferred_token().is_some() => ty::ConditionKind::Deferred,
+            Some(kind) if kind.timeout_token().is_some() => ty::ConditionKind::Timeout,
+            _ => ty::ConditionKind::Normal,
+        };
+
+        Some(ty::Type::Condition(kind))
+    }
+
+    fn lower_array_type(&mut self, ty: ast::ArrayType) -> Option<ty::Type> {
+        let is_flexible = ty.flexible_token().is_some();
+        let ranges = ty
+            .range_list()?
+            .ranges()
+            .map(|range| self.lower_required_type(Some(range)))
+            .collect();
+        let elem_ty = self.lower_required_type(ty.elem_ty());
+
+        Some(ty::Type::Array(ty::Array {
+            is_flexible,
+            ranges,
+            elem_ty,
+        }))
+    }
+
+    fn lower_set_type(&mut self, ty: ast::SetType) -> Option<ty::Type> {
+        let elem = self.lower_required_type(ty.elem_ty());
+
+        Some(ty::Type::Set(ty::Set { elem }))
+    }
+
+    pub(super) fn lower_fcn_type(&mut self, ty: ast::FcnType) -> Option<ty::Type> {
+        let params = ty.param_spec().map(|spec| self.lower_param_types(spec));
+        let result = Some(self.lower_required_type(ty.ty()));
+
+        Some(ty::Type::Subprogram(ty::Subprogram {
+            kind: ty::SubprogramKind::Function,
+            params,
+            result,
+        }))
+    }
+
+    pub(super) fn lower_proc_type(&mut self, ty: ast::ProcType) -> Option<ty::Type> {
+        let params = ty.param_spec().map(|spec| self.lower_param_types(spec));
+
+        Some(ty::Type::Subprogram(ty::Subprogram {
+            kind: ty::SubprogramKind::Procedure,
+            params,
+            result: None,
+        }))
+    }
+
+    /// Lowers the parameters of a subprogram type.
+    /// Each name in a parameter group becomes a separate parameter, but the names aren't declared.
+    pub(super) fn lower_param_types(&mut self, spec: ast::ParamSpec) -> Vec<ty::Param> {
+        spec.param_decl()
+            .flat_map(|param| {
+                let (is_var, is_register, count, param_ty) = match param {
+                    ast::ParamDecl::ConstVarParam(param) => {
+                        let count = param
+                            .param_names()
+                            .map_or(1, |names| names.names().count().max(1));
+                        (
+                            param.pass_as_ref().is_some(),
+                            param.bind_to_register().is_some(),
+                            count,
+                            self.lower_required_type(param.param_ty()),
+                        )
+                    }
+                    ast::ParamDecl::SubprogType(param) => {
+                        let span = Span::new(self.file, param.syntax().text_range());
+                        let ty = match param {
+                            ast::SubprogType::FcnType(ty) => self.lower_fcn_type(ty),
+                            ast::SubprogType::ProcType(ty) => self.lower_proc_type(ty),
+                        };
+                        let ty = ty.unwrap_or(ty::Type::Missing);
+                        (false, false, 1, self.database.add_type(ty, span))
+                    }
+                };
+
+                // Parameter types are shared between all of the names in the group
+                (0..count).map(move |_| ty::Param {
+                    is_var,
+                    is_register,
+                    param_ty,
+                })
+            })
+            .collect()
+    }
+
+    fn lower_enum_type(&mut self, ty: ast::EnumType) -> Option<ty::Type> {
+        let variants = ty
+            .fields()?
+            .names()
+            .filter_map(|name| {
+                let name = name.identifier_token()?;
+                let span = Span::new(self.file, name.text_range());
+                Some(Spanned::new(name.text().to_string(), span))
+            })
+            .collect();
+
+        Some(ty::Type::Enum(ty::Enum { variants }))
+    }
+
+    fn lower_record_type(&mut self, ty: ast::RecordType) -> Option<ty::Type> {
+        let is_packed = ty.packed_token().is_some();
+        let fields = self.lower_record_fields(ty.record_field());
+
+        Some(ty::Type::Record(ty::Record { is_packed, fields }))
+    }
+
+    fn lower_union_type(&mut self, ty: ast::UnionType) -> Option<ty::Type> {
+        let is_packed = ty.packed_token().is_some();
+
+        let tag_name = {
+            let name = ty.tag_name()?.identifier_token()?;
+            let span = Span::new(self.file, name.text_range());
+            Spanned::new(name.text().to_string(), span)
+        };
+        let tag_ty = self.lower_required_type(ty.range_ty());
+
+        let variants = ty
+            .union_variant()
+            .map(|variant| {
+                let selectors = variant
+                    .selectors()
+                    .map(|exprs| exprs.exprs().map(|expr| self.lower_expr(expr)).collect())
+                    .unwrap_or_default();
+                let fields = self.lower_record_fields(variant.record_field());
+
+                ty::UnionVariant { selectors, fields }
+            })
+            .collect();
+
+        Some(ty::Type::Union(ty::Union {
+            is_packed,
+            tag_name,
+            tag_ty,
+            variants,
+        }))
+    }
+
+    fn lower_record_fields(
+        &mut self,
+        fields: impl Iterator<Item = ast::RecordField>,
+    ) -> Vec<ty::Field> {
+        fields
+            .filter_map(|field| {
+                let names = field
+                    .field_names()?
+                    .names()
+                    .filter_map(|name| {
+                        let name = name.identifier_token()?;
+                        let span = Span::new(self.file, name.text_range());
+                        Some(Spanned::new(name.text().to_string(), span))
+                    })
+                    .collect::<Vec<_>>();
+                let ty = self.lower_required_type(field.field_ty());
+
+                // Invariant: Field names must contain at least one name
+                Some(ty::Field { names, ty }).filter(|field| !field.names.is_empty())
+            })
+            .collect()
+    }
+
+    /// Lowers a required type. If not present or invalid, constructs a `Type::Missing` node in-place
+    pub(super) fn lower_required_type(&mut self, ty: Option<ast::Type>) -> ty::TypeId {
+        if let Some(ty_id) = ty.and_then(|ty| self.lower_type(ty)) {
+            ty_id
+        } else {
+            // Allocate a generic span
+            self.database
+                .add_type(ty::Type::Missing, Default::default())
+        }
+    }
+
     fn lower_seq_length(&mut self, node: Option<ast::SeqLength>) -> ty::SeqLength {
         match node {
             Some(node) if node.star_token().is_some() => ty::SeqLength::Dynamic,
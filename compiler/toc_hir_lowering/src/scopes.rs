@@ -2,7 +2,7 @@
 #[cfg(test)]
 mod test;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use toc_hir::symbol::{self, SymbolTable};
 use toc_span::Span;
@@ -83,6 +83,98 @@ impl ScopeBuilder {
         )
     }
 
+    /// Declares a symbol imported from another unit in the current scope
+    pub fn import_sym(
+        &mut self,
+        name: &str,
+        span: Span,
+        original: symbol::GlobalDefId,
+    ) -> symbol::DefId {
+        let def_id = self.symbol_table.import_sym(name, span, original);
+        let scope = self.scopes.last_mut().unwrap();
+        scope.symbols.insert(name.to_string(), def_id);
+        def_id
+    }
+
+    /// Gets the original definition of `name` in the current scope, if it was
+    /// imported from another unit
+    pub fn imported_def(&self, name: &str) -> Option<symbol::GlobalDefId> {
+        let scope = self.scopes.last().unwrap();
+        let def_id = *scope.symbols.get(name)?;
+        self.symbol_table.get_import(def_id)
+    }
+
+    /// Finds a previous declaration of `name` in the current scope that a new
+    /// declaration of kind `kind` would conflict with, giving the span of the
+    /// previous declaration.
+    ///
+    /// Undeclared symbols never conflict, and a forward declaration may be
+    /// followed by its resolving declaration.
+    pub fn find_redeclaration(&self, name: &str, kind: symbol::SymbolKind) -> Option<Span> {
+        let scope = self.scopes.last().unwrap();
+        let prev_def = *scope.symbols.get(name)?;
+        let prev_kind = self.symbol_table.get_symbol(prev_def).kind;
+
+        let is_redeclared = match (prev_kind, kind) {
+            (symbol::SymbolKind::Undeclared, _) => false,
+            (symbol::SymbolKind::Forward, kind) => kind == symbol::SymbolKind::Forward,
+            _ => true,
+        };
+
+        if is_redeclared {
+            Some(self.symbol_table.get_def_span(prev_def))
+        } else {
+            None
+        }
+    }
+
+    /// Finds an unresolved forward declaration of `name` in the current scope
+    pub fn find_forward(&self, name: &str) -> Option<symbol::DefId> {
+        let scope = self.scopes.last().unwrap();
+        let def_id = *scope.symbols.get(name)?;
+
+        (self.symbol_table.get_symbol(def_id).kind == symbol::SymbolKind::Forward).then_some(def_id)
+    }
+
+    /// Checks if `name` refers to a symbol visible from the current scope.
+    ///
+    /// Undeclared symbols from earlier uses are also considered to be visible.
+    pub fn is_visible(&self, name: &str) -> bool {
+        self.lookup_def(name).is_some()
+    }
+
+    /// Iterates over all declared symbols visible from the current scope, with
+    /// shadowed symbols excluded.
+    pub fn visible_defs(&self) -> impl Iterator<Item = (&str, symbol::DefId)> + '_ {
+        let mut restrict_to_pervasive = false;
+        let mut seen = HashSet::new();
+        let mut defs = vec![];
+
+        for scope in self.scopes.iter().rev() {
+            for (name, def_id) in &scope.symbols {
+                let symbol = self.symbol_table.get_symbol(*def_id);
+
+                if restrict_to_pervasive && !symbol.is_pervasive {
+                    // Not visible across an import boundary
+                    continue;
+                }
+
+                if !seen.insert(name.as_str()) || symbol.kind == symbol::SymbolKind::Undeclared {
+                    // Either shadowed, or not a real declaration
+                    continue;
+                }
+
+                defs.push((name.as_str(), *def_id));
+            }
+
+            if scope.is_import_boundary {
+                restrict_to_pervasive = true;
+            }
+        }
+
+        defs.into_iter()
+    }
+
     pub fn use_sym(&mut self, name: &str, span: Span) -> symbol::UseId {
         let def_id = self.lookup_def(name).unwrap_or_else(|| {
             // Declare at the import boundary
@@ -27,6 +27,59 @@ fn test_ident_redeclare() {
     assert_ne!(initial_id, redeclare_id);
 }
 
+#[test]
+fn test_find_redeclaration() {
+    let mut scopes = ScopeBuilder::new();
+    assert_eq!(scopes.find_redeclaration("a", SymbolKind::Declared), None);
+
+    scopes.def_sym("a", Default::default(), SymbolKind::Declared, false);
+    assert!(scopes
+        .find_redeclaration("a", SymbolKind::Declared)
+        .is_some());
+
+    // Not a redeclaration in an inner scope
+    scopes.with_scope(false, |scopes| {
+        assert_eq!(scopes.find_redeclaration("a", SymbolKind::Declared), None);
+    });
+
+    // Undeclared symbols don't conflict
+    scopes.use_sym("b", Default::default());
+    assert_eq!(scopes.find_redeclaration("b", SymbolKind::Declared), None);
+
+    // Forward declarations only conflict with other forward declarations
+    scopes.def_sym("c", Default::default(), SymbolKind::Forward, false);
+    assert_eq!(scopes.find_redeclaration("c", SymbolKind::Declared), None);
+    assert!(scopes
+        .find_redeclaration("c", SymbolKind::Forward)
+        .is_some());
+}
+
+#[test]
+fn test_find_forward() {
+    let mut scopes = ScopeBuilder::new();
+
+    let forward_def = scopes.def_sym("a", Default::default(), SymbolKind::Forward, false);
+    assert_eq!(scopes.find_forward("a"), Some(forward_def));
+
+    // Only forward declarations in the current scope can be resolved
+    scopes.with_scope(false, |scopes| {
+        assert_eq!(scopes.find_forward("a"), None);
+    });
+
+    // Already resolved
+    scopes.def_sym(
+        "a",
+        Default::default(),
+        SymbolKind::Resolved(forward_def),
+        false,
+    );
+    assert_eq!(scopes.find_forward("a"), None);
+
+    // Not a forward declaration
+    scopes.def_sym("b", Default::default(), SymbolKind::Declared, false);
+    assert_eq!(scopes.find_forward("b"), None);
+}
+
 #[test]
 fn test_ident_declare_shadow() {
     // Identifier shadowing is not allow within inner scopes, but is detected later on
@@ -170,3 +223,42 @@ fn test_import_boundaries() {
         });
     });
 }
+
+#[test]
+fn test_pervasive_through_nested_boundaries() {
+    let mut scopes = ScopeBuilder::new();
+
+    let pervasive_ty = scopes.def_sym("t", Default::default(), SymbolKind::Declared, true);
+    let non_pervasive = scopes.def_sym("c", Default::default(), SymbolKind::Declared, false);
+
+    // Module body
+    scopes.with_scope(true, |scopes| {
+        let module_local = scopes.def_sym("m", Default::default(), SymbolKind::Declared, false);
+        assert_eq!(
+            scopes.use_sym("t", Default::default()).as_def(),
+            pervasive_ty
+        );
+
+        // Subprogram inside of the module
+        scopes.with_scope(true, |scopes| {
+            // Pervasive declarations pass through every boundary
+            assert_eq!(
+                scopes.use_sym("t", Default::default()).as_def(),
+                pervasive_ty
+            );
+
+            // Everything else stops at the closest one
+            let use_c = scopes.use_sym("c", Default::default());
+            let use_m = scopes.use_sym("m", Default::default());
+            assert_ne!(use_c.as_def(), non_pervasive);
+            assert_ne!(use_m.as_def(), module_local);
+            assert!(!scopes.visible_defs().any(|(name, _)| name == "c"));
+        });
+    });
+
+    // Undeclared uses inside of the boundaries don't leak out
+    assert_eq!(
+        scopes.use_sym("c", Default::default()).as_def(),
+        non_pervasive
+    );
+}
@@ -42,6 +42,21 @@ fn stringify_unit(db: &db::HirDb, unit: &unit::Unit) -> String {
         s.push_str(&format!("{:?}: {:?}\n", id, span));
     }
 
+    // Only show exports if there are any
+    if !unit.exports.is_empty() {
+        s.push_str("exports:\n");
+        for export in &unit.exports {
+            s.push_str(&format!("{:?}\n", export));
+        }
+    }
+
+    // Only show checkedness regions if there are any
+    let unchecked = db.unchecked_stmts().collect::<Vec<_>>();
+    if !unchecked.is_empty() {
+        s.push_str("unchecked stmts:\n");
+        s.push_str(&format!("{:?}\n", unchecked));
+    }
+
     s
 }
 
@@ -98,6 +113,15 @@ fn lower_var_def() {
     assert_lower("var a := a");
 }
 
+#[test]
+fn lower_alt_eq_decl() {
+    // `=` is lowered the same as `:=`, and is only warned about by the parser
+    for src in ["var a : int = 1", "const a : int = 1", "var a : int := 1"] {
+        let lowered = assert_lower(src);
+        assert!(lowered.hir_result.messages.is_empty());
+    }
+}
+
 #[test]
 fn lower_simple_assignment() {
     assert_lower("a := b");
@@ -122,6 +146,69 @@ fn lower_scoping_outer_use_inner_use() {
     assert_lower("q := j begin q := k end");
 }
 
+#[test]
+fn lower_scoping_redeclare() {
+    // redeclaring in the same scope should be reported
+    assert_lower("var a : int\nvar a : int");
+    // but not within the same name list, since that's reported during validation
+    // (only one of the names is declared)
+    assert_lower("var a, a : int");
+    // and for types
+    assert_lower("type a : int\ntype a : int");
+    // and for repeated forward declarations, which still refer to the first one
+    assert_lower("type a : forward\ntype a : forward\ntype a : int");
+}
+
+#[test]
+fn lower_scoping_shadow_no_redeclare() {
+    // shadowing in an inner scope is not a redeclaration
+    assert_lower("var a : int\nbegin var a : int end");
+    // declaring after an undeclared use is not a redeclaration
+    assert_lower("a := 1\nvar a : int");
+    // resolving a forward declaration is not a redeclaration
+    assert_lower("type a : forward\ntype a : int");
+}
+
+#[test]
+fn lower_undeclared_name() {
+    // base error, without any suggestions
+    assert_lower("var a := b");
+    // only reported on the first use
+    assert_lower("a := b\nb := a");
+    // near misses are suggested
+    assert_lower("var print := 1\nvar prompt := 2\nprnit := 3");
+    assert_lower("var count := 1\ncont := 2");
+    // too far away from anything to suggest
+    assert_lower("var count := 1\nthing := 2");
+    // names from outer scopes are also suggested
+    assert_lower("var value := 1\nbegin var other := 2 valeu := 3 end");
+}
+
+#[test]
+fn lower_undeclared_name_suggest_kind() {
+    // only type names are suggested in type position
+    assert_lower("var size := 1\ntype sizes : int\nvar a : sizs");
+    // only non-type names are suggested in expression position
+    assert_lower("type size_t : int\nvar sizet := 1\nvar a := size_\n");
+}
+
+#[test]
+fn lower_checkedness_regions() {
+    // statements after `unchecked` are unchecked until the next `checked`
+    assert_lower("var a : int\nunchecked\na := 1\na := 2\nchecked\na := 3");
+    // regions end at the end of a block, and nested blocks inherit the region
+    assert_lower("unchecked\nbegin a := 1 checked a := 2 end\na := 3\nbegin a := 4 end");
+    // no statements after `unchecked`
+    assert_lower("a := 1\nunchecked");
+    assert_lower("begin a := 1 unchecked end");
+    // redundant checkedness statements
+    assert_lower("checked\na := 1");
+    assert_lower("unchecked\nunchecked\na := 1");
+    // checkedness immediately changed again
+    assert_lower("unchecked\nchecked\na := 1");
+    assert_lower("unchecked\nchecked\nunchecked\na := 1");
+}
+
 #[test]
 fn lower_int_literal() {
     assert_lower("a := 01234560");
@@ -372,6 +459,172 @@ fn lower_paren_expr() {
     assert_lower("a := ()");
 }
 
+#[test]
+fn lower_field_expr() {
+    assert_lower("a := a.b");
+    // nested
+    assert_lower("a := a.b.c");
+    // missing field name
+    assert_lower("a := a.");
+}
+
+#[test]
+fn lower_substring_expr() {
+    assert_lower("a := s(1 .. 3)");
+    // relative bounds
+    assert_lower("a := s(* - 1 .. *)");
+    assert_lower("a := s(2 .. *)");
+    // missing end bound
+    assert_lower("a := s(1 .. )");
+    // not a substring
+    assert_lower("a := s(1, 2 .. 3)");
+}
+
+#[test]
+fn lower_nil_expr() {
+    assert_lower("a := nil");
+    assert_lower("type c : collection of int a := nil(c)");
+    // not a type name
+    assert_lower("a := nil(1 + 1)");
+}
+
+#[test]
+fn lower_pointer_type() {
+    assert_lower("type p : pointer to int");
+    assert_lower("type p : unchecked ^int");
+}
+
+#[test]
+fn lower_collection_type() {
+    assert_lower("type c : collection of int");
+    assert_lower("var c : collection of forward n type n : int");
+    // shares the same forward declaration
+    assert_lower("var c : collection of forward n var d : collection of forward n");
+    // already declared
+    assert_lower("type n : int var c : collection of forward n");
+}
+
+#[test]
+fn lower_new_stmt() {
+    assert_lower("new p");
+    assert_lower("new c, p");
+    // too many arguments
+    assert_lower("new c, p, a, b");
+}
+
+#[test]
+fn lower_free_stmt() {
+    assert_lower("free p");
+    assert_lower("free c, p");
+    // too many arguments
+    assert_lower("free c, p, a");
+}
+
+#[test]
+fn lower_tag_stmt() {
+    assert_lower("tag u, 1");
+    assert_lower("tag u.a(1), true");
+}
+
+#[test]
+fn lower_condition_type() {
+    assert_lower("var c : condition");
+    assert_lower("var c : priority condition");
+    assert_lower("var c : deferred condition");
+    assert_lower("var c : timeout condition");
+}
+
+#[test]
+fn lower_array_type() {
+    assert_lower("var a : array 1 .. 3 of int");
+    assert_lower("var a : flexible array 1 .. 3, char of int");
+    // missing element type
+    assert_lower("var a : array 1 .. 3 of");
+}
+
+#[test]
+fn lower_set_type() {
+    assert_lower("type s : set of 1 .. 3");
+    // missing element type
+    assert_lower("type s : set of");
+}
+
+#[test]
+fn lower_subprogram_type() {
+    assert_lower("type f : function (a : int, b, c : string) : boolean");
+    assert_lower("type p : procedure (var a : int, register b : int)");
+    // parameter lists can be omitted
+    assert_lower("type f : function : int");
+    assert_lower("type p : procedure");
+    // subprogram parameters
+    assert_lower("type p : procedure (function f : int)");
+}
+
+#[test]
+fn lower_call_expr() {
+    assert_lower("a := b(1)");
+    assert_lower("a := b(1, 2)(3)");
+    assert_lower("a := b()");
+}
+
+#[test]
+fn lower_bind_decl() {
+    assert_lower("var arr : array 1 .. 3 of int bind a to arr(3)");
+    assert_lower("var r : record f : int end record bind var a to r.f, b to r");
+    // not a reference
+    assert_lower("bind a to 1");
+    assert_lower("var s : string bind a to s(1 .. 2)");
+}
+
+#[test]
+fn lower_init_expr() {
+    assert_lower("var a : array 1 .. 3 of int := init(1, 2, 3)");
+    // missing value
+    assert_lower("var a : array 1 .. 3 of int := init(1, , 3)");
+}
+
+#[test]
+fn lower_deref_expr() {
+    assert_lower("a := ^b");
+    assert_lower("^a := ^b.c");
+    // missing operand
+    assert_lower("a := ^");
+}
+
+#[test]
+fn lower_builtin_expr() {
+    assert_lower("var a : int\nvar b := upper(a)");
+    assert_lower("var a := sizeof(array 1 .. 2 of int)\nvar b := sizeof(a)");
+    // shadowed by a declaration
+    assert_lower("var ord : int\nvar a := ord(1)");
+}
+
+#[test]
+fn lower_nat_cheat_expr() {
+    assert_lower("a := #b");
+    assert_lower("a := #9 * 2");
+    // missing operand
+    assert_lower("a := #");
+}
+
+#[test]
+fn lower_indirect_expr() {
+    assert_lower("a := int @ (16#A000)");
+    assert_lower("a := myRec @ (p + 4)");
+    // missing address
+    assert_lower("a := int @ ()");
+}
+
+#[test]
+fn lower_bits_expr() {
+    assert_lower("a := bits(x, 0 .. 3)");
+    assert_lower("a := bits(x, 1)");
+    // relative bounds aren't allowed
+    assert_lower("a := bits(x, 0 .. *)");
+    // missing bits
+    assert_lower("a := bits(x)");
+}
+
 #[test]
 fn lower_self_expr() {
     assert_lower("a := self");
@@ -382,6 +635,8 @@ fn lower_binary_expr() {
     assert_lower("a := a + a");
     // missing operand, should still be present
     assert_lower("a := () + ");
+    // lowest precedence, and left associative
+    assert_lower("a := a => a => a");
 }
 
 #[test]
@@ -391,6 +646,50 @@ fn lower_unary_expr() {
     assert_lower("a := +");
 }
 
+#[test]
+fn lower_type_decl() {
+    assert_lower("type a : int");
+    // forward declaration
+    assert_lower("type a : forward");
+    // type reference
+    assert_lower("type a : int type b : a");
+}
+
+#[test]
+fn lower_pervasive_attr() {
+    // `*` is the same as `pervasive`
+    assert_lower("var pervasive a : int const * b := 1 var c : int");
+    assert_lower("type pervasive t : int type * u : int type v : int");
+}
+
+#[test]
+fn lower_enum_type() {
+    assert_lower("type e : enum (a, b, c)");
+    // variants aren't declared in the enclosing scope
+    assert_lower("type e : enum (a) var v := a");
+}
+
+#[test]
+fn lower_record_type() {
+    assert_lower("type r : record a, b : int c : real end record");
+    assert_lower("type r : packed record a : int end record");
+}
+
+#[test]
+fn lower_union_type() {
+    assert_lower("type u : union kind : boolean of label true: a : int label : end union");
+}
+
+#[test]
+fn lower_range_type() {
+    assert_lower("type r : 1 .. 10");
+    assert_lower("type r : 'a' .. 'z'");
+    // Unsized end bound
+    assert_lower("type r : 1 .. *");
+    // Missing end bound
+    assert_lower("type r : 1 ..");
+}
+
 #[test]
 fn lower_prim_type() {
     let tys = vec![
@@ -446,3 +745,209 @@ fn lower_get_stmt() {
     // not a reference
     assert_lower("get a*a");
 }
+
+#[test]
+fn lower_quit_stmt() {
+    assert_lower("quit");
+    // with cause markers
+    assert_lower("quit <");
+    assert_lower("quit > : 50");
+    // missing reason expr
+    assert_lower("quit :");
+}
+
+#[test]
+fn lower_if_stmt() {
+    assert_lower("if true then put 1 elsif false then put 2 else put 3 end if");
+    // branches are separate scopes
+    assert_lower("if true then var a : int else a := 1 end if a := 2");
+    // missing condition
+    assert_lower("if then end if");
+}
+
+#[test]
+fn lower_for_stmt() {
+    assert_lower("for i : 1 .. 10 put i end for");
+    assert_lower("for decreasing i : 1 .. 10 by 2 end for");
+    // implicit bounds
+    assert_lower("type t : 1 .. 3 for i : t end for");
+    // counter and body are a separate scope
+    assert_lower("for i : 1 .. 2 var a : int end for a := i");
+    // no counter
+    assert_lower("for : 1 .. 2 end for");
+}
+
+#[test]
+fn lower_loop_stmt() {
+    assert_lower("loop exit when true exit end loop");
+    // body is a separate scope
+    assert_lower("loop var a : int end loop a := 1");
+}
+
+#[test]
+fn lower_case_stmt() {
+    assert_lower("case 1 of label 1, 2 : put 1 label : put 2 end case");
+    // arms are separate scopes
+    assert_lower("case 1 of label 1 : var a : int label : a := 1 end case");
+}
+
+#[test]
+fn lower_return_result_stmt() {
+    assert_lower("procedure p return end p");
+    assert_lower("function f : int result 1 end f");
+}
+
+#[test]
+fn lower_procedure_decl() {
+    assert_lower("procedure p end p");
+    // params are only visible in the body
+    assert_lower("procedure p (var a : int, b, c : boolean) a := 1 end p put a");
+    // can call itself
+    assert_lower("procedure p p end p");
+    // empty parameter list
+    assert_lower("procedure p () end p");
+}
+
+#[test]
+fn lower_function_decl() {
+    assert_lower("function f (a : int) : int end f");
+    // no parameter list
+    assert_lower("function f : int end f");
+    // missing result type
+    assert_lower("function f (a : int) : end f");
+    // named result is only visible in the body
+    assert_lower("function f (a : int) r : int r := a put r end f put r");
+}
+
+#[test]
+fn lower_module_decl() {
+    assert_lower("module m var a : int end m");
+    // only pervasive names from outside are visible in the body
+    assert_lower("var a : int var * b : int module m put a put b end m");
+    // ...including in subprograms inside of the module
+    assert_lower("var a : int var * b : int module m procedure p put a put b end p end m");
+    // the module is visible after the declaration, but names in the body aren't
+    assert_lower("module m var a : int end m put m put a");
+    // imports and exports aren't lowered yet
+    assert_lower("module m import a export b var b : int end m");
+}
+
+#[test]
+fn lower_forward_decl() {
+    assert_lower("forward procedure p (a : int) body procedure p (a : int) end p");
+    // bodies leaving out the parameters and result type take them from the forward declaration
+    assert_lower("forward function f (a : int) : int body function f result a end f");
+    // can be called before the body
+    assert_lower("forward procedure p p body procedure p end p");
+    // bodies without a forward declaration are still lowered
+    assert_lower("body procedure p end p");
+    // forward types aren't resolved by bodies
+    assert_lower("type t : forward body procedure t end t");
+}
+
+#[test]
+fn lower_process_decl() {
+    assert_lower("process p end p");
+    // with stack size
+    assert_lower("process p : 8192 end p");
+    // params are only visible in the body
+    assert_lower("process p (var a : int, b, c : boolean) : 8192 a := 1 end p put a");
+    // can fork itself
+    assert_lower("process p fork p end p");
+    // subprogram parameters are named by the subprogram type
+    assert_lower("process p (procedure q (a : int)) q(1) end p");
+}
+
+#[test]
+fn lower_call_stmt() {
+    assert_lower("p");
+    assert_lower("p(1, 2)");
+    assert_lower("a.b()");
+}
+
+#[test]
+fn lower_fork_stmt() {
+    assert_lower("fork p");
+    assert_lower("fork p(1, 2)");
+    // with status, stack size, and process descriptor
+    assert_lower("fork p : s, 8192, d");
+    assert_lower("fork p(1) : s");
+}
+
+#[test]
+fn lower_unit_module_exports() {
+    assert_lower(
+        "unit module m export a, b
+            const a := 1
+            var b : int
+            var c : int
+        end m",
+    );
+    // all items
+    assert_lower(
+        "unit module m export all
+            const a := 1
+            type b : int
+        end m",
+    );
+    // undeclared exports
+    assert_lower("unit module m export a, bb var b : int end m");
+    // not a unit, so nothing is exported
+    assert_lower("module m export a const a := 1 end m");
+    // opaque types
+    assert_lower("unit module m export opaque t, a type t : int const a : t := 1 end m");
+}
+
+#[test]
+fn lower_unit_imports() {
+    let hir_db = db::HirBuilder::new();
+    let lower_src = |src: &str, imports: &[crate::UnitImport]| {
+        let parsed = toc_parser::parse(None, src);
+        crate::lower_unit(hir_db.clone(), None, parsed.syntax(), imports)
+    };
+    let import_of = |unit| crate::UnitImport {
+        unit,
+        span: Default::default(),
+    };
+
+    let c = lower_src("unit module c export k const k := 1 end c", &[]);
+    // Re-exported items keep the original definition
+    let b = lower_src(
+        "unit module b export k, j const j := k end b",
+        &[import_of(&c)],
+    );
+    let a = lower_src("put j, k", &[import_of(&b), import_of(&c)]);
+    // Names clashing with a different item are reported
+    let clash = lower_src("unit module d export j const j := 2 end d", &[]);
+    let clashing = lower_src("put j", &[import_of(&b), import_of(&clash)]);
+
+    assert_eq!(b.exports()[0].def_id, c.exports()[0].def_id);
+    assert!(a.messages().is_empty());
+    assert_eq!(
+        clashing
+            .messages()
+            .iter()
+            .map(|msg| msg.message())
+            .collect::<Vec<_>>(),
+        vec!["`j` is imported from multiple units"]
+    );
+
+    let hir_db = hir_db.finish();
+    assert_eq!(hir_db.unit_imports(a.id), &[b.id, c.id]);
+
+    // All uses refer back to the original definitions
+    let unit = hir_db.get_unit(a.id);
+    let resolved = unit
+        .symbol_table
+        .iter_uses()
+        .map(|(use_id, _)| {
+            let name = &unit.symbol_table.get_symbol(use_id.as_def()).name;
+            (name.as_str(), unit.resolve_def(use_id.as_def()))
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        resolved,
+        vec![("j", b.exports()[1].def_id), ("k", c.exports()[0].def_id)]
+    );
+}
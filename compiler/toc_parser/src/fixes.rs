@@ -0,0 +1,217 @@
+//! Quick fixes for messages reported while parsing
+use toc_reporting::{AnnotateKind, ReportMessage, Stage};
+use toc_syntax::ast::edit::{make, SyntaxEditor, TextEdit};
+use toc_syntax::ast::AstNode;
+use toc_syntax::{SyntaxKind, SyntaxNode};
+
+/// A fix for a reported message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// Short description of what the fix does
+    pub label: String,
+    /// Edits to the source text applying the fix
+    pub edits: Vec<TextEdit>,
+}
+
+/// Gets all of the available fixes for `message`, which was reported while parsing
+/// the tree starting at `root`
+pub fn fixes_for(root: &SyntaxNode, message: &ReportMessage) -> Vec<Fix> {
+    if message.stage() != Stage::Parse || message.kind() != AnnotateKind::Warning {
+        return vec![];
+    }
+
+    let mut fixes = vec![];
+    fixes.extend(equ_as_assign(root, message));
+    fixes
+}
+
+/// Replaces a mistyped `=` with `:=` in assignments
+fn equ_as_assign(root: &SyntaxNode, message: &ReportMessage) -> Option<Fix> {
+    if message.message() != "‘=’ found, assuming it to be ‘:=’" {
+        return None;
+    }
+
+    let range = message.span().range;
+    let token = root.covering_element(range).into_token()?;
+    let asn_op = token.parent();
+
+    if token.kind() != SyntaxKind::Equ || asn_op.kind() != SyntaxKind::AsnOp {
+        return None;
+    }
+
+    let mut editor = SyntaxEditor::new(root.clone());
+    editor.replace(asn_op, make::assign_op().syntax().clone());
+
+    Some(Fix {
+        label: "replace ‘=’ with ‘:=’".to_string(),
+        edits: editor.finish().text_edits,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use toc_syntax::ast::edit::apply_edits;
+
+    use super::*;
+
+    #[track_caller]
+    fn apply_all_fixes(source: &str) -> String {
+        let parsed = crate::parse(None, source);
+        let root = parsed.syntax();
+
+        let edits: Vec<_> = parsed
+            .messages()
+            .iter()
+            .flat_map(|msg| fixes_for(&root, msg))
+            .flat_map(|fix| fix.edits)
+            .collect();
+
+        apply_edits(source, &edits)
+    }
+
+    #[test]
+    fn fix_equ_as_assign() {
+        let source = "var a : int\na = 1\n";
+        let parsed = crate::parse(None, source);
+        let root = parsed.syntax();
+
+        let fixes = fixes_for(&root, &parsed.messages()[0]);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].label, "replace ‘=’ with ‘:=’");
+        assert_eq!(
+            fixes[0].edits,
+            vec![TextEdit {
+                range: toc_span::TextRange::new(14.into(), 15.into()),
+                replacement: ":=".to_string(),
+            }]
+        );
+
+        // Fixed source doesn't have the warning anymore
+        let fixed = apply_edits(source, &fixes[0].edits);
+        assert_eq!(fixed, "var a : int\na := 1\n");
+
+        let reparsed = crate::parse(None, &fixed);
+        assert!(reparsed.messages().is_empty());
+    }
+
+    #[test]
+    fn fix_equ_as_assign_reparses_to_edited_tree() {
+        let source = "var a : int\nbegin\n    a = 1\nend\n";
+        let parsed = crate::parse(None, source);
+        let root = parsed.syntax();
+
+        // Edited tree is the same as the reparsed fixed source
+        let token = root
+            .descendants_with_tokens()
+            .find(|element| element.kind() == SyntaxKind::Equ)
+            .unwrap();
+        let mut editor = SyntaxEditor::new(root.clone());
+        editor.replace(token.parent().unwrap(), make::assign_op().syntax().clone());
+        let edited = editor.finish();
+
+        let fixed = apply_edits(source, &edited.text_edits);
+        let reparsed = crate::parse(None, &fixed);
+
+        assert_eq!(
+            format!("{:#?}", edited.syntax()),
+            format!("{:#?}", reparsed.syntax())
+        );
+        assert!(reparsed.messages().is_empty());
+    }
+
+    #[test]
+    fn fix_multiple_equ_as_assign() {
+        let source = "var a, b : int\na = 1\nb = a = 2\n";
+        let fixed = apply_all_fixes(source);
+
+        // Only the assignment operators are replaced, and not the comparison
+        assert_eq!(fixed, "var a, b : int\na := 1\nb := a = 2\n");
+        assert!(crate::parse(None, &fixed).messages().is_empty());
+    }
+
+    #[test]
+    fn no_fixes_for_other_messages() {
+        let source = "var a : int\na := \n";
+        let parsed = crate::parse(None, source);
+        let root = parsed.syntax();
+
+        assert!(!parsed.messages().is_empty());
+        assert!(parsed
+            .messages()
+            .iter()
+            .all(|msg| fixes_for(&root, msg).is_empty()));
+    }
+
+    #[test]
+    fn replace_end_group() {
+        let source = "if true then\nendif\n";
+        let parsed = crate::parse(None, source);
+        let root = parsed.syntax();
+
+        let end_group = root
+            .descendants()
+            .find(|node| node.kind() == SyntaxKind::EndGroup)
+            .unwrap();
+        let mut editor = SyntaxEditor::new(root.clone());
+        editor.replace(end_group, make::end_group("if").syntax().clone());
+        let edited = editor.finish();
+
+        let fixed = apply_edits(source, &edited.text_edits);
+        let reparsed = crate::parse(None, &fixed);
+
+        assert_eq!(fixed, "if true then\nend if\n");
+        assert_eq!(
+            format!("{:#?}", edited.syntax()),
+            format!("{:#?}", reparsed.syntax())
+        );
+        assert!(reparsed.messages().is_empty());
+    }
+
+    #[test]
+    fn insert_and_delete() {
+        let source = "var a : int\na := 1\n";
+        let parsed = crate::parse(None, source);
+        let root = parsed.syntax();
+
+        let stmts: Vec<_> = root
+            .descendants()
+            .filter(|node| {
+                matches!(
+                    node.kind(),
+                    SyntaxKind::ConstVarDecl | SyntaxKind::AssignStmt
+                )
+            })
+            .collect();
+        let mut editor = SyntaxEditor::new(root.clone());
+        editor.insert_after(stmts[0].clone(), make::whitespace(" "));
+        editor.insert_before(
+            stmts[1].clone(),
+            make::token(SyntaxKind::Comment, "% removed"),
+        );
+        editor.delete(stmts[1].clone());
+        let edited = editor.finish();
+
+        // Insertions around a deleted element are part of the same edit
+        assert_eq!(
+            edited.text_edits,
+            vec![
+                TextEdit {
+                    range: toc_span::TextRange::new(11.into(), 11.into()),
+                    replacement: " ".to_string(),
+                },
+                TextEdit {
+                    range: toc_span::TextRange::new(12.into(), 18.into()),
+                    replacement: "% removed".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            apply_edits(source, &edited.text_edits),
+            "var a : int \n% removed\n"
+        );
+        assert_eq!(
+            edited.syntax().text().to_string(),
+            "var a : int \n% removed\n"
+        );
+    }
+}
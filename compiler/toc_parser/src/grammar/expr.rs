@@ -120,7 +120,9 @@ fn expr_binding_power(p: &mut Parser, min_binding_power: u8) -> Option<Completed
     let mut lhs = lhs(p).or_else(|| prefix(p, only_primaries))?;
 
     loop {
-        if p.at(TokenKind::At) {
+        let is_indirect = p.at(TokenKind::At);
+
+        if is_indirect {
             lhs = indirect_expr_tail(p, lhs);
         }
 
@@ -129,7 +131,7 @@ fn expr_binding_power(p: &mut Parser, min_binding_power: u8) -> Option<Completed
         } else {
             // Not an infix operator, so let the caller decide the outcome
 
-            if p.at_hidden(TokenKind::At) {
+            if is_indirect && p.at_hidden(TokenKind::At) {
                 // can't chain indirect expr tails
                 // outcome has been decided
                 p.error_unexpected()
@@ -160,9 +162,10 @@ fn expr_binding_power(p: &mut Parser, min_binding_power: u8) -> Option<Completed
         let found_rhs = match op {
             InfixOp::Call => {
                 // call expr
+                // Could also be a subscript, so allow range items
                 let m = lhs.precede(p);
 
-                super::param_list(p);
+                super::param_list(p, true);
 
                 lhs = m.complete(p, SyntaxKind::CallExpr);
 
@@ -309,8 +312,8 @@ fn bits_expr(p: &mut Parser) -> Option<CompletedMarker> {
     p.bump();
 
     if p.at(TokenKind::LeftParen) {
-        // Eat a parameter list
-        super::param_list(p);
+        // Eat a parameter list, which can contain a bit range
+        super::param_list(p, true);
     } else {
         p.error_unexpected().report();
     }
@@ -326,7 +329,7 @@ fn objclass_expr(p: &mut Parser) -> Option<CompletedMarker> {
 
     if p.at(TokenKind::LeftParen) {
         // Eat a parameter list
-        super::param_list(p);
+        super::param_list(p, false);
     } else {
         p.error_unexpected().report();
     }
@@ -427,9 +430,9 @@ fn init_expr(p: &mut Parser) -> Option<CompletedMarker> {
     p.with_extra_recovery(&[TokenKind::RightParen, TokenKind::Comma], |p| {
         let m = p.start();
 
-        if let Some(..) = expr::expect_expr(p) {
+        if let Some(..) = init_elem(p) {
             while p.eat(TokenKind::Comma) && !p.at(TokenKind::RightParen) {
-                expr::expect_expr(p);
+                init_elem(p);
             }
 
             // Don't clog up expected tokens
@@ -447,6 +450,15 @@ fn init_expr(p: &mut Parser) -> Option<CompletedMarker> {
     Some(m.complete(p, SyntaxKind::InitExpr))
 }
 
+fn init_elem(p: &mut Parser) -> Option<CompletedMarker> {
+    // Nested 'init' groups initialize composite elements, and are whole elements by themselves
+    if p.at(TokenKind::Init) {
+        init_expr(p)
+    } else {
+        expr::expect_expr(p)
+    }
+}
+
 fn nil_expr(p: &mut Parser) -> Option<CompletedMarker> {
     // nil ( '(' Reference ')' )?
     debug_assert!(p.at(TokenKind::Nil));
@@ -469,6 +481,14 @@ fn indirect_expr_tail(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
 
     // Postfix indirection tail
     // Only allowed for primary exprs
+    let lhs = if lhs.kind() == SyntaxKind::PrimType {
+        lhs
+    } else {
+        // Enclose expr (potential name ref) inside NameType
+        // further checks are pushed down to AST validation
+        lhs.precede(p).complete(p, SyntaxKind::NameType)
+    };
+
     let m = lhs.precede(p);
     p.bump();
 
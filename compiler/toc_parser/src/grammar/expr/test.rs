@@ -1432,6 +1432,35 @@ fn recover_not_as_infix() {
     );
 }
 
+#[test]
+fn recover_not_as_infix_before_indirect() {
+    // Shouldn't be mistaken for a chained indirect expr tail
+    check(
+        "_:=1 not 2 @",
+        expect![[r#"
+            Source@0..12
+              StmtList@0..12
+                AssignStmt@0..10
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  LiteralExpr@3..4
+                    IntLiteral@3..4 "1"
+                  Whitespace@4..5 " "
+                  Error@5..10
+                    KwNot@5..8 "not"
+                    Whitespace@8..9 " "
+                    IntLiteral@9..10 "2"
+                Whitespace@10..11 " "
+                Error@11..12
+                  At@11..12 "@"
+            error at 9..10: expected ‘in’ or ‘=’, but found int literal
+            error at 11..12: expected statement, but found ‘@’"#]],
+    );
+}
+
 #[test]
 fn parse_simple_prefix() {
     check(
@@ -1562,6 +1591,103 @@ fn negation_over_arithmetic() {
     );
 }
 
+#[test]
+fn exp_over_negation() {
+    check(
+        "_:=-2**2",
+        expect![[r#"
+            Source@0..8
+              StmtList@0..8
+                AssignStmt@0..8
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  UnaryExpr@3..8
+                    Minus@3..4 "-"
+                    BinaryExpr@4..8
+                      LiteralExpr@4..5
+                        IntLiteral@4..5 "2"
+                      Exp@5..7 "**"
+                      LiteralExpr@7..8
+                        IntLiteral@7..8 "2""#]],
+    );
+}
+
+#[test]
+fn pound_over_exp() {
+    check(
+        "_:=#a**2",
+        expect![[r##"
+            Source@0..8
+              StmtList@0..8
+                AssignStmt@0..8
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  BinaryExpr@3..8
+                    NatCheatExpr@3..5
+                      Pound@3..4 "#"
+                      NameExpr@4..5
+                        Name@4..5
+                          Identifier@4..5 "a"
+                    Exp@5..7 "**"
+                    LiteralExpr@7..8
+                      IntLiteral@7..8 "2""##]],
+    );
+}
+
+#[test]
+fn pound_over_arithmetic() {
+    check(
+        "_:=#9*2",
+        expect![[r##"
+            Source@0..7
+              StmtList@0..7
+                AssignStmt@0..7
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  BinaryExpr@3..7
+                    NatCheatExpr@3..5
+                      Pound@3..4 "#"
+                      LiteralExpr@4..5
+                        IntLiteral@4..5 "9"
+                    Star@5..6 "*"
+                    LiteralExpr@6..7
+                      IntLiteral@6..7 "2""##]],
+    );
+}
+
+#[test]
+fn exp_with_negated_rhs() {
+    check(
+        "_:=2**-3",
+        expect![[r#"
+            Source@0..8
+              StmtList@0..8
+                AssignStmt@0..8
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  BinaryExpr@3..8
+                    LiteralExpr@3..4
+                      IntLiteral@3..4 "2"
+                    Exp@4..6 "**"
+                    UnaryExpr@6..8
+                      Minus@6..7 "-"
+                      LiteralExpr@7..8
+                        IntLiteral@7..8 "3""#]],
+    );
+}
+
 #[test]
 fn recover_infix_missing_rhs() {
     check(
@@ -2510,6 +2636,28 @@ fn parens_override_deref_binding() {
     );
 }
 
+#[test]
+fn deref_as_stmt_start() {
+    check(
+        "^p := 1",
+        expect![[r#"
+            Source@0..7
+              StmtList@0..7
+                AssignStmt@0..7
+                  DerefExpr@0..2
+                    Caret@0..1 "^"
+                    NameExpr@1..2
+                      Name@1..2
+                        Identifier@1..2 "p"
+                  Whitespace@2..3 " "
+                  AsnOp@3..5
+                    Assign@3..5 ":="
+                  Whitespace@5..6 " "
+                  LiteralExpr@6..7
+                    IntLiteral@6..7 "1""#]],
+    );
+}
+
 #[test]
 fn recover_deref_not_reference() {
     // here, this should be parsed as a valid expression, but checked to be an invalid one
@@ -2794,6 +2942,94 @@ fn recover_init_expr_just_comma() {
     )
 }
 
+#[test]
+fn parse_nested_init_expr() {
+    check(
+        "_:=init(1, init(2, 3), init(init(4)))",
+        expect![[r#"
+            Source@0..37
+              StmtList@0..37
+                AssignStmt@0..37
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  InitExpr@3..37
+                    KwInit@3..7 "init"
+                    LeftParen@7..8 "("
+                    ExprList@8..36
+                      LiteralExpr@8..9
+                        IntLiteral@8..9 "1"
+                      Comma@9..10 ","
+                      Whitespace@10..11 " "
+                      InitExpr@11..21
+                        KwInit@11..15 "init"
+                        LeftParen@15..16 "("
+                        ExprList@16..20
+                          LiteralExpr@16..17
+                            IntLiteral@16..17 "2"
+                          Comma@17..18 ","
+                          Whitespace@18..19 " "
+                          LiteralExpr@19..20
+                            IntLiteral@19..20 "3"
+                        RightParen@20..21 ")"
+                      Comma@21..22 ","
+                      Whitespace@22..23 " "
+                      InitExpr@23..36
+                        KwInit@23..27 "init"
+                        LeftParen@27..28 "("
+                        ExprList@28..35
+                          InitExpr@28..35
+                            KwInit@28..32 "init"
+                            LeftParen@32..33 "("
+                            ExprList@33..34
+                              LiteralExpr@33..34
+                                IntLiteral@33..34 "4"
+                            RightParen@34..35 ")"
+                        RightParen@35..36 ")"
+                    RightParen@36..37 ")""#]],
+    );
+}
+
+#[test]
+fn recover_nested_init_expr_in_binary_expr() {
+    // Nested `init` groups are whole elements
+    check(
+        "_:=init(init(1) + 2)",
+        expect![[r#"
+            Source@0..20
+              StmtList@0..20
+                AssignStmt@0..19
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  BinaryExpr@3..19
+                    InitExpr@3..15
+                      KwInit@3..7 "init"
+                      LeftParen@7..8 "("
+                      ExprList@8..15
+                        InitExpr@8..15
+                          KwInit@8..12 "init"
+                          LeftParen@12..13 "("
+                          ExprList@13..14
+                            LiteralExpr@13..14
+                              IntLiteral@13..14 "1"
+                          RightParen@14..15 ")"
+                    Whitespace@15..16 " "
+                    Plus@16..17 "+"
+                    Whitespace@17..18 " "
+                    LiteralExpr@18..19
+                      IntLiteral@18..19 "2"
+                Error@19..20
+                  RightParen@19..20 ")"
+            error at 16..17: expected ‘)’, but found ‘+’
+            error at 19..20: expected statement, but found ‘)’"#]],
+    );
+}
+
 #[test]
 fn parse_indirect_expr_ty() {
     check(
@@ -3268,9 +3504,10 @@ fn parse_indirect_expr_ty_ref() {
                   AsnOp@1..3
                     Assign@1..3 ":="
                   IndirectExpr@3..10
-                    NameExpr@3..4
-                      Name@3..4
-                        Identifier@3..4 "a"
+                    NameType@3..4
+                      NameExpr@3..4
+                        Name@3..4
+                          Identifier@3..4 "a"
                     Whitespace@4..5 " "
                     At@5..6 "@"
                     Whitespace@6..7 " "
@@ -3291,17 +3528,18 @@ fn parse_indirect_expr_ty_ref() {
                   AsnOp@1..3
                     Assign@1..3 ":="
                   IndirectExpr@3..14
-                    FieldExpr@3..8
-                      FieldExpr@3..6
-                        NameExpr@3..4
-                          Name@3..4
-                            Identifier@3..4 "a"
-                        Dot@4..5 "."
-                        Name@5..6
-                          Identifier@5..6 "b"
-                      Dot@6..7 "."
-                      Name@7..8
-                        Identifier@7..8 "c"
+                    NameType@3..8
+                      FieldExpr@3..8
+                        FieldExpr@3..6
+                          NameExpr@3..4
+                            Name@3..4
+                              Identifier@3..4 "a"
+                          Dot@4..5 "."
+                          Name@5..6
+                            Identifier@5..6 "b"
+                        Dot@6..7 "."
+                        Name@7..8
+                          Identifier@7..8 "c"
                     Whitespace@8..9 " "
                     At@9..10 "@"
                     Whitespace@10..11 " "
@@ -3328,9 +3566,10 @@ fn parse_indirect_expr_not_ty_ref_deref() {
                   DerefExpr@3..11
                     Caret@3..4 "^"
                     IndirectExpr@4..11
-                      NameExpr@4..5
-                        Name@4..5
-                          Identifier@4..5 "a"
+                      NameType@4..5
+                        NameExpr@4..5
+                          Name@4..5
+                            Identifier@4..5 "a"
                       Whitespace@5..6 " "
                       At@6..7 "@"
                       Whitespace@7..8 " "
@@ -3355,8 +3594,9 @@ fn parse_indirect_expr_not_ty_ref_literal() {
                   AsnOp@1..3
                     Assign@1..3 ":="
                   IndirectExpr@3..10
-                    LiteralExpr@3..4
-                      IntLiteral@3..4 "1"
+                    NameType@3..4
+                      LiteralExpr@3..4
+                        IntLiteral@3..4 "1"
                     Whitespace@4..5 " "
                     At@5..6 "@"
                     Whitespace@6..7 " "
@@ -3381,20 +3621,21 @@ fn parse_indirect_expr_not_ty_ref_parens() {
                   AsnOp@1..3
                     Assign@1..3 ":="
                   IndirectExpr@3..16
-                    ParenExpr@3..10
-                      LeftParen@3..4 "("
-                      FieldExpr@4..9
-                        FieldExpr@4..7
-                          NameExpr@4..5
-                            Name@4..5
-                              Identifier@4..5 "a"
-                          Dot@5..6 "."
-                          Name@6..7
-                            Identifier@6..7 "b"
-                        Dot@7..8 "."
-                        Name@8..9
-                          Identifier@8..9 "c"
-                      RightParen@9..10 ")"
+                    NameType@3..10
+                      ParenExpr@3..10
+                        LeftParen@3..4 "("
+                        FieldExpr@4..9
+                          FieldExpr@4..7
+                            NameExpr@4..5
+                              Name@4..5
+                                Identifier@4..5 "a"
+                            Dot@5..6 "."
+                            Name@6..7
+                              Identifier@6..7 "b"
+                          Dot@7..8 "."
+                          Name@8..9
+                            Identifier@8..9 "c"
+                        RightParen@9..10 ")"
                     Whitespace@10..11 " "
                     At@11..12 "@"
                     Whitespace@12..13 " "
@@ -3420,9 +3661,10 @@ fn recover_chained_indirect_tails() {
                   AsnOp@1..3
                     Assign@1..3 ":="
                   IndirectExpr@3..10
-                    NameExpr@3..4
-                      Name@3..4
-                        Identifier@3..4 "a"
+                    NameType@3..4
+                      NameExpr@3..4
+                        Name@3..4
+                          Identifier@3..4 "a"
                     Whitespace@4..5 " "
                     At@5..6 "@"
                     Whitespace@6..7 " "
@@ -3485,6 +3727,93 @@ fn recover_just_indirect_ty() {
     );
 }
 
+#[test]
+fn parse_indirect_expr_radix_addr() {
+    check(
+        "_:=int @ (16#A000)",
+        expect![[r#"
+            Source@0..18
+              StmtList@0..18
+                AssignStmt@0..18
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  IndirectExpr@3..18
+                    PrimType@3..6
+                      KwInt@3..6 "int"
+                    Whitespace@6..7 " "
+                    At@7..8 "@"
+                    Whitespace@8..9 " "
+                    LeftParen@9..10 "("
+                    LiteralExpr@10..17
+                      RadixLiteral@10..17 "16#A000"
+                    RightParen@17..18 ")""#]],
+    );
+}
+
+#[test]
+fn parse_indirect_expr_named_ty() {
+    check(
+        "_:=myRec @ (p + 4)",
+        expect![[r#"
+            Source@0..18
+              StmtList@0..18
+                AssignStmt@0..18
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  IndirectExpr@3..18
+                    NameType@3..8
+                      NameExpr@3..8
+                        Name@3..8
+                          Identifier@3..8 "myRec"
+                    Whitespace@8..9 " "
+                    At@9..10 "@"
+                    Whitespace@10..11 " "
+                    LeftParen@11..12 "("
+                    BinaryExpr@12..17
+                      NameExpr@12..13
+                        Name@12..13
+                          Identifier@12..13 "p"
+                      Whitespace@13..14 " "
+                      Plus@14..15 "+"
+                      Whitespace@15..16 " "
+                      LiteralExpr@16..17
+                        IntLiteral@16..17 "4"
+                    RightParen@17..18 ")""#]],
+    );
+}
+
+#[test]
+fn recover_indirect_expr_missing_left_paren() {
+    check(
+        "_:=int @ 16#A000)",
+        expect![[r#"
+            Source@0..17
+              StmtList@0..17
+                AssignStmt@0..17
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  IndirectExpr@3..17
+                    PrimType@3..6
+                      KwInt@3..6 "int"
+                    Whitespace@6..7 " "
+                    At@7..8 "@"
+                    Whitespace@8..9 " "
+                    LiteralExpr@9..16
+                      RadixLiteral@9..16 "16#A000"
+                    RightParen@16..17 ")"
+            error at 9..16: expected ‘(’, but found explicit int literal"#]],
+    );
+}
+
 #[test]
 fn parse_bits_expr() {
     check(
@@ -3541,6 +3870,134 @@ fn parse_bits_ref() {
     );
 }
 
+#[test]
+fn parse_substring_expr() {
+    check(
+        "a := s(1 .. 3)",
+        expect![[r#"
+        Source@0..14
+          StmtList@0..14
+            AssignStmt@0..14
+              NameExpr@0..1
+                Name@0..1
+                  Identifier@0..1 "a"
+              Whitespace@1..2 " "
+              AsnOp@2..4
+                Assign@2..4 ":="
+              Whitespace@4..5 " "
+              CallExpr@5..14
+                NameExpr@5..6
+                  Name@5..6
+                    Identifier@5..6 "s"
+                ParamList@6..14
+                  LeftParen@6..7 "("
+                  Param@7..13
+                    RangeItem@7..13
+                      LiteralExpr@7..8
+                        IntLiteral@7..8 "1"
+                      Whitespace@8..9 " "
+                      Range@9..11 ".."
+                      Whitespace@11..12 " "
+                      LiteralExpr@12..13
+                        IntLiteral@12..13 "3"
+                  RightParen@13..14 ")""#]],
+    );
+}
+
+#[test]
+fn parse_substring_relative_bounds() {
+    check(
+        "a := s(* - 1 .. *)",
+        expect![[r#"
+        Source@0..18
+          StmtList@0..18
+            AssignStmt@0..18
+              NameExpr@0..1
+                Name@0..1
+                  Identifier@0..1 "a"
+              Whitespace@1..2 " "
+              AsnOp@2..4
+                Assign@2..4 ":="
+              Whitespace@4..5 " "
+              CallExpr@5..18
+                NameExpr@5..6
+                  Name@5..6
+                    Identifier@5..6 "s"
+                ParamList@6..18
+                  LeftParen@6..7 "("
+                  Param@7..17
+                    RangeItem@7..17
+                      RelativeBound@7..12
+                        Star@7..8 "*"
+                        Whitespace@8..9 " "
+                        Minus@9..10 "-"
+                        Whitespace@10..11 " "
+                        LiteralExpr@11..12
+                          IntLiteral@11..12 "1"
+                      Whitespace@12..13 " "
+                      Range@13..15 ".."
+                      Whitespace@15..16 " "
+                      RelativeBound@16..17
+                        Star@16..17 "*"
+                  RightParen@17..18 ")""#]],
+    );
+}
+
+#[test]
+fn parse_substring_star_end() {
+    check(
+        "a := s(2 .. *)",
+        expect![[r#"
+            Source@0..14
+              StmtList@0..14
+                AssignStmt@0..14
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "a"
+                  Whitespace@1..2 " "
+                  AsnOp@2..4
+                    Assign@2..4 ":="
+                  Whitespace@4..5 " "
+                  CallExpr@5..14
+                    NameExpr@5..6
+                      Name@5..6
+                        Identifier@5..6 "s"
+                    ParamList@6..14
+                      LeftParen@6..7 "("
+                      Param@7..13
+                        RangeItem@7..13
+                          LiteralExpr@7..8
+                            IntLiteral@7..8 "2"
+                          Whitespace@8..9 " "
+                          Range@9..11 ".."
+                          Whitespace@11..12 " "
+                          RelativeBound@12..13
+                            Star@12..13 "*"
+                      RightParen@13..14 ")""#]],
+    );
+}
+
+#[test]
+fn recover_star_outside_of_subscript() {
+    check(
+        "x := *",
+        expect![[r#"
+            Source@0..6
+              StmtList@0..6
+                AssignStmt@0..6
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "x"
+                  Whitespace@1..2 " "
+                  AsnOp@2..4
+                    Assign@2..4 ":="
+                  Whitespace@4..5 " "
+                  Error@5..6
+                    Star@5..6 "*"
+            error at 5..6: expected expression, but found ‘*’"#]],
+    );
+}
+
 #[test]
 fn parse_bits_ref_range() {
     check(
@@ -3578,6 +4035,42 @@ fn parse_bits_ref_range() {
     );
 }
 
+#[test]
+fn parse_bits_expr_range() {
+    check(
+        "_:=bits(x, 0 .. 3)",
+        expect![[r#"
+            Source@0..18
+              StmtList@0..18
+                AssignStmt@0..18
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  BitsExpr@3..18
+                    KwBits@3..7 "bits"
+                    ParamList@7..18
+                      LeftParen@7..8 "("
+                      Param@8..10
+                        NameExpr@8..9
+                          Name@8..9
+                            Identifier@8..9 "x"
+                        Comma@9..10 ","
+                      Whitespace@10..11 " "
+                      Param@11..17
+                        RangeItem@11..17
+                          LiteralExpr@11..12
+                            IntLiteral@11..12 "0"
+                          Whitespace@12..13 " "
+                          Range@13..15 ".."
+                          Whitespace@15..16 " "
+                          LiteralExpr@16..17
+                            IntLiteral@16..17 "3"
+                      RightParen@17..18 ")""#]],
+    );
+}
+
 #[test]
 fn parse_bits_ref_relative_range() {
     // Reject during lowering
@@ -3767,6 +4260,63 @@ fn recover_just_objclass() {
     );
 }
 
+#[test]
+fn recover_objclass_relative_bound() {
+    // Range items are only allowed in subscripts
+    check(
+        "_:=objectclass(*)",
+        expect![[r#"
+            Source@0..17
+              StmtList@0..17
+                AssignStmt@0..17
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  ObjClassExpr@3..17
+                    KwObjectClass@3..14 "objectclass"
+                    ParamList@14..17
+                      LeftParen@14..15 "("
+                      Param@15..16
+                        Error@15..16
+                          Star@15..16 "*"
+                      RightParen@16..17 ")"
+            error at 15..16: expected expression, but found ‘*’"#]],
+    );
+}
+
+#[test]
+fn recover_objclass_range_item() {
+    check(
+        "_:=objectclass(a .. b)",
+        expect![[r#"
+            Source@0..22
+              StmtList@0..22
+                AssignStmt@0..16
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "_"
+                  AsnOp@1..3
+                    Assign@1..3 ":="
+                  ObjClassExpr@3..16
+                    KwObjectClass@3..14 "objectclass"
+                    ParamList@14..16
+                      LeftParen@14..15 "("
+                      Param@15..16
+                        NameExpr@15..16
+                          Name@15..16
+                            Identifier@15..16 "a"
+                Whitespace@16..17 " "
+                Error@17..22
+                  Range@17..19 ".."
+                  Whitespace@19..20 " "
+                  Identifier@20..21 "b"
+                  RightParen@21..22 ")"
+            error at 17..19: expected ‘,’ or ‘)’, but found ‘..’"#]],
+    );
+}
+
 #[test]
 fn parse_cheat_expr() {
     check(
@@ -4279,6 +4829,148 @@ fn recover_call_expr_relative_bound_missing_minus() {
     );
 }
 
+#[test]
+fn parse_substring_range() {
+    check(
+        "var sub := s(2 .. 5)",
+        expect![[r#"
+            Source@0..20
+              StmtList@0..20
+                ConstVarDecl@0..20
+                  KwVar@0..3 "var"
+                  Whitespace@3..4 " "
+                  NameList@4..7
+                    Name@4..7
+                      Identifier@4..7 "sub"
+                  Whitespace@7..8 " "
+                  Assign@8..10 ":="
+                  Whitespace@10..11 " "
+                  CallExpr@11..20
+                    NameExpr@11..12
+                      Name@11..12
+                        Identifier@11..12 "s"
+                    ParamList@12..20
+                      LeftParen@12..13 "("
+                      Param@13..19
+                        RangeItem@13..19
+                          LiteralExpr@13..14
+                            IntLiteral@13..14 "2"
+                          Whitespace@14..15 " "
+                          Range@15..17 ".."
+                          Whitespace@17..18 " "
+                          LiteralExpr@18..19
+                            IntLiteral@18..19 "5"
+                      RightParen@19..20 ")""#]],
+    );
+}
+
+#[test]
+fn parse_substring_unbounded_range() {
+    check(
+        "var sub := s(2 .. *)",
+        expect![[r#"
+            Source@0..20
+              StmtList@0..20
+                ConstVarDecl@0..20
+                  KwVar@0..3 "var"
+                  Whitespace@3..4 " "
+                  NameList@4..7
+                    Name@4..7
+                      Identifier@4..7 "sub"
+                  Whitespace@7..8 " "
+                  Assign@8..10 ":="
+                  Whitespace@10..11 " "
+                  CallExpr@11..20
+                    NameExpr@11..12
+                      Name@11..12
+                        Identifier@11..12 "s"
+                    ParamList@12..20
+                      LeftParen@12..13 "("
+                      Param@13..19
+                        RangeItem@13..19
+                          LiteralExpr@13..14
+                            IntLiteral@13..14 "2"
+                          Whitespace@14..15 " "
+                          Range@15..17 ".."
+                          Whitespace@17..18 " "
+                          RelativeBound@18..19
+                            Star@18..19 "*"
+                      RightParen@19..20 ")""#]],
+    );
+}
+
+#[test]
+fn parse_substring_relative_range() {
+    check(
+        "var sub := s(*-3 .. *)",
+        expect![[r#"
+            Source@0..22
+              StmtList@0..22
+                ConstVarDecl@0..22
+                  KwVar@0..3 "var"
+                  Whitespace@3..4 " "
+                  NameList@4..7
+                    Name@4..7
+                      Identifier@4..7 "sub"
+                  Whitespace@7..8 " "
+                  Assign@8..10 ":="
+                  Whitespace@10..11 " "
+                  CallExpr@11..22
+                    NameExpr@11..12
+                      Name@11..12
+                        Identifier@11..12 "s"
+                    ParamList@12..22
+                      LeftParen@12..13 "("
+                      Param@13..21
+                        RangeItem@13..21
+                          RelativeBound@13..16
+                            Star@13..14 "*"
+                            Minus@14..15 "-"
+                            LiteralExpr@15..16
+                              IntLiteral@15..16 "3"
+                          Whitespace@16..17 " "
+                          Range@17..19 ".."
+                          Whitespace@19..20 " "
+                          RelativeBound@20..21
+                            Star@20..21 "*"
+                      RightParen@21..22 ")""#]],
+    );
+}
+
+#[test]
+fn recover_substring_range_missing_end_bound() {
+    check(
+        "var sub := s(2 .. )",
+        expect![[r#"
+            Source@0..19
+              StmtList@0..19
+                ConstVarDecl@0..19
+                  KwVar@0..3 "var"
+                  Whitespace@3..4 " "
+                  NameList@4..7
+                    Name@4..7
+                      Identifier@4..7 "sub"
+                  Whitespace@7..8 " "
+                  Assign@8..10 ":="
+                  Whitespace@10..11 " "
+                  CallExpr@11..19
+                    NameExpr@11..12
+                      Name@11..12
+                        Identifier@11..12 "s"
+                    ParamList@12..19
+                      LeftParen@12..13 "("
+                      Param@13..17
+                        RangeItem@13..17
+                          LiteralExpr@13..14
+                            IntLiteral@13..14 "2"
+                          Whitespace@14..15 " "
+                          Range@15..17 ".."
+                      Whitespace@17..18 " "
+                      RightParen@18..19 ")"
+            error at 18..19: expected expression, but found ‘)’"#]],
+    );
+}
+
 #[test]
 fn parse_call_expr_all_item() {
     check(
@@ -4589,12 +5281,10 @@ fn recover_include_glob_expr() {
                     LiteralExpr@11..17
                       StringLiteral@11..17 "\"here\""
                 Whitespace@17..18 " "
-                Error@18..19
+                Error@18..21
                   Plus@18..19 "+"
-                Whitespace@19..20 " "
-                CallStmt@20..21
-                  LiteralExpr@20..21
-                    IntLiteral@20..21 "1"
+                  Whitespace@19..20 " "
+                  IntLiteral@20..21 "1"
             error at 3..10: expected expression, but found ‘include’
             error at 18..19: expected statement, but found ‘+’"#]],
     );
@@ -4615,13 +5305,10 @@ fn recover_include_glob_ref() {
                     LiteralExpr@8..14
                       StringLiteral@8..14 "\"here\""
                 Whitespace@14..15 " "
-                Error@15..17
+                Error@15..27
                   Arrow@15..17 "->"
-                Whitespace@17..18 " "
-                CallStmt@18..27
-                  NameExpr@18..27
-                    Name@18..27
-                      Identifier@18..27 "and_there"
+                  Whitespace@17..18 " "
+                  Identifier@18..27 "and_there"
             error at 15..17: expected statement, but found ‘->’"#]],
     );
 }
@@ -4975,3 +5662,156 @@ fn parse_not_eq_after_higher_precedence_op() {
                       IntLiteral@16..17 "0""#]],
     );
 }
+
+/// Checks the nesting of mixed operator chains against the precedence table
+/// from the old parser (`Precedence` in `toc_frontend::parser::expr`)
+mod precedence {
+    use rowan::NodeOrToken;
+    use toc_syntax::{ast, ast::AstNode, SyntaxKind, SyntaxNode};
+
+    /// Binary operators grouped by precedence, from lowest to highest.
+    /// All of them are left associative, except for `**`.
+    const BINARY_LEVELS: &[&[&str]] = &[
+        &["=>"],
+        &["or", "|"],
+        &["and", "&"],
+        &[
+            "<", ">", "=", "<=", ">=", "not=", "~=", "in", "not in", "~in",
+        ],
+        &["+", "-", "xor"],
+        &["*", "/", "div", "mod", "rem", "shl", "shr"],
+        &["**"],
+    ];
+    /// Lowest level that binds tighter than `not` (the comparison operators)
+    const NOT_OPERAND_LEVEL: usize = 3;
+    /// Lowest level that binds tighter than prefix `+` and `-` (only `**`)
+    const SIGN_OPERAND_LEVEL: usize = 6;
+
+    fn binary_ops() -> impl Iterator<Item = (usize, &'static str)> {
+        BINARY_LEVELS
+            .iter()
+            .enumerate()
+            .flat_map(|(level, ops)| ops.iter().map(move |op| (level, *op)))
+    }
+
+    /// Parses `expr`, giving back its nesting structure with every operator
+    /// expression wrapped in parentheses
+    #[track_caller]
+    fn nesting_of(expr: &str) -> String {
+        let res = crate::parse(None, &format!("_ := {}", expr));
+        assert!(
+            res.messages().is_empty(),
+            "`{}` has errors:\n{}",
+            expr,
+            res.debug_tree()
+        );
+
+        let rhs = res
+            .syntax()
+            .descendants()
+            .find(|node| node.kind() == SyntaxKind::AssignStmt)
+            .and_then(|stmt| stmt.children().filter(ast::Expr::can_cast).nth(1))
+            .unwrap();
+
+        render(&rhs)
+    }
+
+    fn render(node: &SyntaxNode) -> String {
+        if !matches!(node.kind(), SyntaxKind::BinaryExpr | SyntaxKind::UnaryExpr) {
+            return node.text().to_string();
+        }
+
+        let parts = node
+            .children_with_tokens()
+            .filter_map(|child| match child {
+                NodeOrToken::Node(node) if ast::Expr::can_cast(&node) => Some(render(&node)),
+                // Composite operators (e.g. `not in`) are wrapped up in their own node
+                NodeOrToken::Node(node) => Some(node.text().to_string()),
+                NodeOrToken::Token(token) if token.kind() == SyntaxKind::Whitespace => None,
+                NodeOrToken::Token(token) => Some(token.text().to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        format!("({})", parts.join(" "))
+    }
+
+    #[test]
+    fn mixed_operator_chains() {
+        assert_eq!(nesting_of("1 + 2 * 3 ** 4"), "(1 + (2 * (3 ** 4)))");
+        assert_eq!(nesting_of("1 ** 2 * 3 + 4"), "(((1 ** 2) * 3) + 4)");
+        assert_eq!(nesting_of("a and b or c"), "((a and b) or c)");
+        assert_eq!(nesting_of("a or b and c"), "(a or (b and c))");
+        assert_eq!(nesting_of("not a = b"), "(not (a = b))");
+        assert_eq!(nesting_of("not a and b"), "((not a) and b)");
+        assert_eq!(nesting_of("-a ** b"), "(- (a ** b))");
+        assert_eq!(nesting_of("-a * b"), "((- a) * b)");
+        assert_eq!(nesting_of("a < b = c"), "((a < b) = c)");
+        assert_eq!(nesting_of("a - b + c - d"), "(((a - b) + c) - d)");
+        assert_eq!(nesting_of("a ** b ** c"), "(a ** (b ** c))");
+    }
+
+    #[test]
+    fn imply_is_lowest() {
+        assert_eq!(nesting_of("a => b or c"), "(a => (b or c))");
+        assert_eq!(nesting_of("a or b => c"), "((a or b) => c)");
+        assert_eq!(nesting_of("not a => b"), "((not a) => b)");
+        assert_eq!(nesting_of("a => b => c"), "((a => b) => c)");
+
+        for (_, op) in binary_ops().filter(|(level, _)| *level > 0) {
+            assert_eq!(
+                nesting_of(&format!("a => b {} c", op)),
+                format!("(a => (b {} c))", op)
+            );
+            assert_eq!(
+                nesting_of(&format!("a {} b => c", op)),
+                format!("((a {} b) => c)", op)
+            );
+        }
+    }
+
+    #[test]
+    fn binary_operator_pairs() {
+        for (lhs_level, lhs_op) in binary_ops() {
+            for (rhs_level, rhs_op) in binary_ops() {
+                let source = format!("a {} b {} c", lhs_op, rhs_op);
+                let groups_left = match lhs_level.cmp(&rhs_level) {
+                    std::cmp::Ordering::Greater => true,
+                    std::cmp::Ordering::Less => false,
+                    // Only `**` is right associative
+                    std::cmp::Ordering::Equal => lhs_op != "**",
+                };
+
+                let expected = if groups_left {
+                    format!("((a {} b) {} c)", lhs_op, rhs_op)
+                } else {
+                    format!("(a {} (b {} c))", lhs_op, rhs_op)
+                };
+
+                assert_eq!(nesting_of(&source), expected, "for `{}`", source);
+            }
+        }
+    }
+
+    #[test]
+    fn prefix_operators() {
+        for (level, op) in binary_ops() {
+            let source = format!("not a {} b", op);
+            let expected = if level >= NOT_OPERAND_LEVEL {
+                format!("(not (a {} b))", op)
+            } else {
+                format!("((not a) {} b)", op)
+            };
+            assert_eq!(nesting_of(&source), expected, "for `{}`", source);
+
+            for sign in ["-", "+"] {
+                let source = format!("{}a {} b", sign, op);
+                let expected = if level >= SIGN_OPERAND_LEVEL {
+                    format!("({} (a {} b))", sign, op)
+                } else {
+                    format!("(({} a) {} b)", sign, op)
+                };
+                assert_eq!(nesting_of(&source), expected, "for `{}`", source);
+            }
+        }
+    }
+}
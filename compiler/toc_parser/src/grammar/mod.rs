@@ -257,16 +257,19 @@ pub(self) fn name_list(p: &mut Parser) -> Option<CompletedMarker> {
 }
 
 /// ParamList ( `'(' Param ( ',' Param )* ')'` )
-pub(self) fn param_list(p: &mut Parser) -> Option<CompletedMarker> {
+///
+/// Range items (e.g. `*`, `* - 1`, `1 .. *`) and `all` are only accepted
+/// if `allow_range_items` is true, since they're only valid in subscript position
+pub(self) fn param_list(p: &mut Parser, allow_range_items: bool) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::LeftParen));
 
     let m = p.start();
     p.bump();
 
     if !p.at(TokenKind::RightParen) {
-        if let Some((_, true)) = param(p) {
+        if let Some((_, true)) = param(p, allow_range_items) {
             loop {
-                match param(p) {
+                match param(p, allow_range_items) {
                     Some((_, true)) => {}      // parsed param, expecting more
                     Some((_, false)) => break, // parsed param, end of list
                     None => {
@@ -283,11 +286,15 @@ pub(self) fn param_list(p: &mut Parser) -> Option<CompletedMarker> {
     Some(m.complete(p, SyntaxKind::ParamList))
 }
 
-pub(self) fn param(p: &mut Parser) -> Option<(CompletedMarker, bool)> {
+pub(self) fn param(p: &mut Parser, allow_range_items: bool) -> Option<(CompletedMarker, bool)> {
     let m = p.start();
 
     p.with_extra_recovery(&[TokenKind::Comma, TokenKind::RightParen], |p| {
-        expr::expect_param_expr(p);
+        if allow_range_items {
+            expr::expect_param_expr(p);
+        } else {
+            expr::expect_expr(p);
+        }
     });
 
     // bump ',' onto param
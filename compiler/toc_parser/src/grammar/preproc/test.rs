@@ -1250,23 +1250,18 @@ mod expr {
                             Identifier@4..5 "A"
                         Whitespace@5..6 " "
                         PPTokenBody@6..16
-                          Error@6..9
+                          Error@6..16
                             KwNot@6..9 "not"
-                          Whitespace@9..10 " "
-                          CallStmt@10..11
-                            NameExpr@10..11
-                              Name@10..11
-                                Identifier@10..11 "B"
-                          Whitespace@11..12 " "
-                          Error@12..16
+                            Whitespace@9..10 " "
+                            Identifier@10..11 "B"
+                            Whitespace@11..12 " "
                             KwThen@12..16 "then"
                         Whitespace@16..17 " "
                         PPEndIf@17..24
                           PPKwEnd@17..21 "#end"
                           Whitespace@21..22 " "
                           KwIf@22..24 "if"
-                error at 6..9: expected ‘then’, but found ‘not’
-                error at 12..16: expected statement, but found ‘then’"##]],
+                error at 6..9: expected ‘then’, but found ‘not’"##]],
         )
     }
 
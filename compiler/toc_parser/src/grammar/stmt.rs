@@ -5,6 +5,7 @@ mod test;
 use expr::{expect_expr, expr_list};
 
 use super::*;
+use crate::parser::OpenedAt;
 
 pub(super) fn stmt(p: &mut Parser) -> Option<CompletedMarker> {
     match_token! {
@@ -60,8 +61,8 @@ pub(super) fn stmt(p: &mut Parser) -> Option<CompletedMarker> {
             TokenKind::Wait => wait_stmt(p),
             TokenKind::Quit => quit_stmt(p),
             TokenKind::Break => stmt_only_kw(p, TokenKind::Break, SyntaxKind::BreakStmt),
-            TokenKind::Checked => stmt_only_kw(p, TokenKind::Checked, SyntaxKind::CheckednessStmt),
-            TokenKind::Unchecked => stmt_only_kw(p, TokenKind::Unchecked, SyntaxKind::CheckednessStmt),
+            TokenKind::Checked => checkedness_stmt(p),
+            TokenKind::Unchecked => checkedness_stmt(p),
             TokenKind::Pre => stmt_with_expr(p, TokenKind::Pre, SyntaxKind::PreStmt),
             TokenKind::Init => init_stmt(p),
             TokenKind::Post => stmt_with_expr(p, TokenKind::Post, SyntaxKind::PostStmt),
@@ -100,9 +101,10 @@ pub(super) fn stmt(p: &mut Parser) -> Option<CompletedMarker> {
                 })
                 .or_else(|| preproc::stmt_preproc(p))
                 .or_else(|| {
-                    // report as expecting a statement
+                    // report as expecting a statement, skipping over the rest of the junk
                     p.error_unexpected()
                         .with_category(Expected::Statement)
+                        .skip_rest_of_line()
                         .report();
                     None
                 }),
@@ -125,6 +127,19 @@ fn stmt_with_expr(
     Some(m.complete(p, make_kind))
 }
 
+fn checkedness_stmt(p: &mut Parser) -> Option<CompletedMarker> {
+    debug_assert!(p.at(TokenKind::Checked) || p.at(TokenKind::Unchecked));
+
+    let m = p.start();
+    {
+        let m = p.start();
+        p.bump();
+        m.complete(p, SyntaxKind::Checkedness);
+    }
+
+    Some(m.complete(p, SyntaxKind::CheckednessStmt))
+}
+
 fn stmt_only_kw(
     p: &mut Parser,
     expect_at: TokenKind,
@@ -303,6 +318,7 @@ fn bind_item(p: &mut Parser) -> Option<CompletedMarker> {
 
 fn procedure_decl(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Procedure));
+    let opened_at = p.opened_at();
     let m = p.start();
 
     proc_header(p);
@@ -311,13 +327,14 @@ fn procedure_decl(p: &mut Parser) -> Option<CompletedMarker> {
         import_stmt(p);
     }
     subprog_body(p);
-    eat_end_group(p, TokenKind::Identifier, None);
+    eat_end_group(p, TokenKind::Identifier, None, opened_at);
 
     Some(m.complete(p, SyntaxKind::ProcDecl))
 }
 
 fn function_decl(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Function));
+    let opened_at = p.opened_at();
     let m = p.start();
 
     fcn_header(p, true);
@@ -326,7 +343,7 @@ fn function_decl(p: &mut Parser) -> Option<CompletedMarker> {
         import_stmt(p);
     }
     subprog_body(p);
-    eat_end_group(p, TokenKind::Identifier, None);
+    eat_end_group(p, TokenKind::Identifier, None, opened_at);
 
     Some(m.complete(p, SyntaxKind::FcnDecl))
 }
@@ -427,6 +444,7 @@ fn subprog_body(p: &mut Parser) {
 fn process_decl(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Process));
 
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -448,7 +466,7 @@ fn process_decl(p: &mut Parser) -> Option<CompletedMarker> {
         import_stmt(p);
     }
     subprog_body(p);
-    eat_end_group(p, TokenKind::Identifier, None);
+    eat_end_group(p, TokenKind::Identifier, None, opened_at);
 
     Some(m.complete(p, SyntaxKind::ProcessDecl))
 }
@@ -550,6 +568,7 @@ fn deferred_decl(p: &mut Parser) -> Option<CompletedMarker> {
 
 fn body_decl(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Body));
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -578,12 +597,13 @@ fn body_decl(p: &mut Parser) -> Option<CompletedMarker> {
 
     subprog_body(p);
 
-    eat_end_group(p, TokenKind::Identifier, None);
+    eat_end_group(p, TokenKind::Identifier, None, opened_at);
     Some(m.complete(p, SyntaxKind::BodyDecl))
 }
 
 fn module_decl(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Module));
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -592,12 +612,13 @@ fn module_decl(p: &mut Parser) -> Option<CompletedMarker> {
 
     module_body(p);
 
-    eat_end_group(p, TokenKind::Identifier, None);
+    eat_end_group(p, TokenKind::Identifier, None, opened_at);
     Some(m.complete(p, SyntaxKind::ModuleDecl))
 }
 
 fn class_decl(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Class));
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -609,12 +630,13 @@ fn class_decl(p: &mut Parser) -> Option<CompletedMarker> {
     }
     module_body(p);
 
-    eat_end_group(p, TokenKind::Identifier, None);
+    eat_end_group(p, TokenKind::Identifier, None, opened_at);
     Some(m.complete(p, SyntaxKind::ClassDecl))
 }
 
 fn monitor_decl(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Monitor));
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -629,7 +651,7 @@ fn monitor_decl(p: &mut Parser) -> Option<CompletedMarker> {
     }
     module_body(p);
 
-    eat_end_group(p, TokenKind::Identifier, None);
+    eat_end_group(p, TokenKind::Identifier, None, opened_at);
     Some(m.complete(
         p,
         if as_class {
@@ -870,10 +892,11 @@ fn get_item(p: &mut Parser) -> Option<CompletedMarker> {
         p.with_extra_recovery(&[TokenKind::Colon], |p| {
             expr::expect_expr(p);
         });
-
-        get_width(p);
     }
 
+    // `skip` can't have a width, but that's reported during validation
+    get_width(p);
+
     Some(m.complete(p, SyntaxKind::GetItem))
 }
 
@@ -1024,6 +1047,7 @@ fn stream_num(p: &mut Parser) -> Option<CompletedMarker> {
 fn for_stmt(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::For));
 
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -1065,19 +1089,20 @@ fn for_stmt(p: &mut Parser) -> Option<CompletedMarker> {
 
     self::stmt_list(p, None);
 
-    eat_end_group(p, TokenKind::For, Some(TokenKind::EndFor));
+    eat_end_group(p, TokenKind::For, Some(TokenKind::EndFor), opened_at);
     Some(m.complete(p, SyntaxKind::ForStmt))
 }
 
 fn loop_stmt(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Loop));
 
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
     self::stmt_list(p, None);
 
-    eat_end_group(p, TokenKind::Loop, Some(TokenKind::EndLoop));
+    eat_end_group(p, TokenKind::Loop, Some(TokenKind::EndLoop), opened_at);
     Some(m.complete(p, SyntaxKind::LoopStmt))
 }
 
@@ -1097,13 +1122,14 @@ fn exit_stmt(p: &mut Parser) -> Option<CompletedMarker> {
 fn if_stmt(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::If));
 
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump(); // bump `if`
 
     if_body(p);
 
     // Eat `end if` or `endif`
-    eat_end_group(p, TokenKind::If, Some(TokenKind::EndIf));
+    eat_end_group(p, TokenKind::If, Some(TokenKind::EndIf), opened_at);
 
     Some(m.complete(p, SyntaxKind::IfStmt))
 }
@@ -1146,6 +1172,7 @@ fn if_body(p: &mut Parser) -> Option<CompletedMarker> {
 fn elseif_stmt(p: &mut Parser, eat_tail: bool) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Elseif) || p.at(TokenKind::Elsif) || p.at(TokenKind::Elif));
 
+    let opened_at = p.opened_at();
     let m = p.start();
 
     if p.at(TokenKind::Elseif) || p.at_hidden(TokenKind::Elif) {
@@ -1160,7 +1187,7 @@ fn elseif_stmt(p: &mut Parser, eat_tail: bool) -> Option<CompletedMarker> {
 
     if eat_tail {
         // Eat `end if` or `endif`
-        eat_end_group(p, TokenKind::If, Some(TokenKind::EndIf));
+        eat_end_group(p, TokenKind::If, Some(TokenKind::EndIf), opened_at);
     }
 
     Some(m.complete(p, SyntaxKind::ElseifStmt))
@@ -1169,6 +1196,7 @@ fn elseif_stmt(p: &mut Parser, eat_tail: bool) -> Option<CompletedMarker> {
 fn else_stmt(p: &mut Parser, eat_tail: bool) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Else));
 
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -1176,7 +1204,7 @@ fn else_stmt(p: &mut Parser, eat_tail: bool) -> Option<CompletedMarker> {
 
     if eat_tail {
         // Eat `end if` or `endif`
-        eat_end_group(p, TokenKind::If, Some(TokenKind::EndIf));
+        eat_end_group(p, TokenKind::If, Some(TokenKind::EndIf), opened_at);
     }
 
     Some(m.complete(p, SyntaxKind::ElseStmt))
@@ -1185,6 +1213,7 @@ fn else_stmt(p: &mut Parser, eat_tail: bool) -> Option<CompletedMarker> {
 fn case_stmt(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Case));
 
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -1200,7 +1229,7 @@ fn case_stmt(p: &mut Parser) -> Option<CompletedMarker> {
         }
     }
 
-    eat_end_group(p, TokenKind::Case, Some(TokenKind::EndCase));
+    eat_end_group(p, TokenKind::Case, Some(TokenKind::EndCase), opened_at);
     Some(m.complete(p, SyntaxKind::CaseStmt))
 }
 
@@ -1220,8 +1249,10 @@ fn case_arm(p: &mut Parser) -> Option<CompletedMarker> {
 
     p.expect_punct(TokenKind::Colon);
 
-    // Nom on stmts
-    self::stmt_list(p, Some(&[TokenKind::Label]));
+    // Nom on stmts, without skipping over the next arm while recovering
+    p.with_extra_recovery(&[TokenKind::Label], |p| {
+        self::stmt_list(p, Some(&[TokenKind::Label]));
+    });
 
     Some(m.complete(p, SyntaxKind::CaseArm))
 }
@@ -1229,6 +1260,7 @@ fn case_arm(p: &mut Parser) -> Option<CompletedMarker> {
 fn block_stmt(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Begin));
 
+    let opened_at = p.opened_at();
     let m = p.start(); // BlockStmt
     p.bump();
 
@@ -1236,7 +1268,7 @@ fn block_stmt(p: &mut Parser) -> Option<CompletedMarker> {
 
     // end group
     let m_end = p.start();
-    p.expect(TokenKind::End);
+    p.expect_closing(TokenKind::End, opened_at);
     m_end.complete(p, SyntaxKind::EndGroup);
 
     Some(m.complete(p, SyntaxKind::BlockStmt))
@@ -1396,6 +1428,7 @@ fn handler_stmt(p: &mut Parser) -> Option<CompletedMarker> {
     // 'handler' '(' Name ')' StmtList 'end' 'handler'
     debug_assert!(p.at(TokenKind::Handler));
 
+    let opened_at = p.opened_at();
     let m = p.start();
     p.bump();
 
@@ -1407,7 +1440,7 @@ fn handler_stmt(p: &mut Parser) -> Option<CompletedMarker> {
 
     stmt_list(p, None);
 
-    eat_end_group(p, TokenKind::Handler, None);
+    eat_end_group(p, TokenKind::Handler, None, opened_at);
 
     Some(m.complete(p, SyntaxKind::HandlerStmt))
 }
@@ -1622,8 +1655,15 @@ fn stmt_list(p: &mut Parser, excluding: Option<&[TokenKind]>) -> Option<Complete
     Some(m.complete(p, SyntaxKind::StmtList))
 }
 
-/// Eats the end group with corresponding `tail` token, or eats the combined variant
-fn eat_end_group(p: &mut Parser, tail: TokenKind, combined: Option<TokenKind>) {
+/// Eats the end group with corresponding `tail` token, or eats the combined variant.
+///
+/// A missing or mismatched end group is reported along with where the construct was `opened_at`.
+fn eat_end_group(
+    p: &mut Parser,
+    tail: TokenKind,
+    combined: Option<TokenKind>,
+    opened_at: Option<OpenedAt>,
+) {
     let m = p.start();
 
     // combined isn't the canonical version, so don't show it to the world
@@ -1639,8 +1679,14 @@ fn eat_end_group(p: &mut Parser, tail: TokenKind, combined: Option<TokenKind>) {
         p.warn_alias(&format!("’end {}’", tail_text));
         p.bump();
     } else {
-        p.expect(TokenKind::End);
-        p.expect(tail);
+        p.expect_closing(TokenKind::End, opened_at);
+
+        if tail == TokenKind::Identifier {
+            // Only the construct's name is missing, which isn't about it being unclosed
+            p.expect(tail);
+        } else {
+            p.expect_closing(tail, opened_at);
+        }
     }
 
     m.complete(p, SyntaxKind::EndGroup);
@@ -61,7 +61,8 @@ fn parse_many_opt_semicolons() {
                 Semicolon@8..9 ";"
                 Semicolon@9..10 ";"
                 CheckednessStmt@10..17
-                  KwChecked@10..17 "checked"
+                  Checkedness@10..17
+                    KwChecked@10..17 "checked"
                 Semicolon@17..18 ";"
                 Semicolon@18..19 ";"
                 Semicolon@19..20 ";"
@@ -1033,22 +1034,14 @@ fn recover_not_weird_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..5
+                Error@2..12
                   KwNot@2..5 "not"
-                Error@5..6
                   Equ@5..6 "="
-                Error@6..7
                   Equ@6..7 "="
-                Error@7..10
                   KwNot@7..10 "not"
-                Whitespace@10..11 " "
-                CallStmt@11..12
-                  LiteralExpr@11..12
-                    IntLiteral@11..12 "1"
-            error at 2..5: expected statement, but found ‘not’
-            error at 5..6: expected statement, but found ‘=’
-            error at 6..7: expected statement, but found ‘=’
-            error at 7..10: expected statement, but found ‘not’"#]],
+                  Whitespace@10..11 " "
+                  IntLiteral@11..12 "1"
+            error at 2..5: expected statement, but found ‘not’"#]],
     );
     check(
         "a ~==~ 1",
@@ -1060,22 +1053,14 @@ fn recover_not_weird_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..3
+                Error@2..8
                   Tilde@2..3 "~"
-                Error@3..4
                   Equ@3..4 "="
-                Error@4..5
                   Equ@4..5 "="
-                Error@5..6
                   Tilde@5..6 "~"
-                Whitespace@6..7 " "
-                CallStmt@7..8
-                  LiteralExpr@7..8
-                    IntLiteral@7..8 "1"
-            error at 2..3: expected statement, but found ‘~’
-            error at 3..4: expected statement, but found ‘=’
-            error at 4..5: expected statement, but found ‘=’
-            error at 5..6: expected statement, but found ‘~’"#]],
+                  Whitespace@6..7 " "
+                  IntLiteral@7..8 "1"
+            error at 2..3: expected statement, but found ‘~’"#]],
     );
 }
 
@@ -1092,12 +1077,10 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..4
+                Error@2..6
                   LessEqu@2..4 "<="
-                Whitespace@4..5 " "
-                CallStmt@5..6
-                  LiteralExpr@5..6
-                    IntLiteral@5..6 "1"
+                  Whitespace@4..5 " "
+                  IntLiteral@5..6 "1"
             error at 2..4: expected statement, but found ‘<=’"#]],
     );
     check(
@@ -1110,16 +1093,12 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..4
+                Error@2..7
                   LessEqu@2..4 "<="
-                Error@4..5
                   Equ@4..5 "="
-                Whitespace@5..6 " "
-                CallStmt@6..7
-                  LiteralExpr@6..7
-                    IntLiteral@6..7 "1"
-            error at 2..4: expected statement, but found ‘<=’
-            error at 4..5: expected statement, but found ‘=’"#]],
+                  Whitespace@5..6 " "
+                  IntLiteral@6..7 "1"
+            error at 2..4: expected statement, but found ‘<=’"#]],
     );
     check(
         "a >= 1",
@@ -1131,12 +1110,10 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..4
+                Error@2..6
                   GreaterEqu@2..4 ">="
-                Whitespace@4..5 " "
-                CallStmt@5..6
-                  LiteralExpr@5..6
-                    IntLiteral@5..6 "1"
+                  Whitespace@4..5 " "
+                  IntLiteral@5..6 "1"
             error at 2..4: expected statement, but found ‘>=’"#]],
     );
     check(
@@ -1149,16 +1126,12 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..4
+                Error@2..7
                   GreaterEqu@2..4 ">="
-                Error@4..5
                   Equ@4..5 "="
-                Whitespace@5..6 " "
-                CallStmt@6..7
-                  LiteralExpr@6..7
-                    IntLiteral@6..7 "1"
-            error at 2..4: expected statement, but found ‘>=’
-            error at 4..5: expected statement, but found ‘=’"#]],
+                  Whitespace@5..6 " "
+                  IntLiteral@6..7 "1"
+            error at 2..4: expected statement, but found ‘>=’"#]],
     );
 
     // these are not compound ops in `toc`
@@ -1172,19 +1145,13 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..3
+                Error@2..7
                   Tilde@2..3 "~"
-                Error@3..4
                   Equ@3..4 "="
-                Error@4..5
                   Equ@4..5 "="
-                Whitespace@5..6 " "
-                CallStmt@6..7
-                  LiteralExpr@6..7
-                    IntLiteral@6..7 "1"
-            error at 2..3: expected statement, but found ‘~’
-            error at 3..4: expected statement, but found ‘=’
-            error at 4..5: expected statement, but found ‘=’"#]],
+                  Whitespace@5..6 " "
+                  IntLiteral@6..7 "1"
+            error at 2..3: expected statement, but found ‘~’"#]],
     );
     check(
         "a not== 1",
@@ -1196,19 +1163,13 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..5
+                Error@2..9
                   KwNot@2..5 "not"
-                Error@5..6
                   Equ@5..6 "="
-                Error@6..7
                   Equ@6..7 "="
-                Whitespace@7..8 " "
-                CallStmt@8..9
-                  LiteralExpr@8..9
-                    IntLiteral@8..9 "1"
-            error at 2..5: expected statement, but found ‘not’
-            error at 5..6: expected statement, but found ‘=’
-            error at 6..7: expected statement, but found ‘=’"#]],
+                  Whitespace@7..8 " "
+                  IntLiteral@8..9 "1"
+            error at 2..5: expected statement, but found ‘not’"#]],
     );
     check(
         "a not in= 1",
@@ -1220,20 +1181,14 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..5
+                Error@2..11
                   KwNot@2..5 "not"
-                Whitespace@5..6 " "
-                Error@6..8
+                  Whitespace@5..6 " "
                   KwIn@6..8 "in"
-                Error@8..9
                   Equ@8..9 "="
-                Whitespace@9..10 " "
-                CallStmt@10..11
-                  LiteralExpr@10..11
-                    IntLiteral@10..11 "1"
-            error at 2..5: expected statement, but found ‘not’
-            error at 6..8: expected statement, but found ‘in’
-            error at 8..9: expected statement, but found ‘=’"#]],
+                  Whitespace@9..10 " "
+                  IntLiteral@10..11 "1"
+            error at 2..5: expected statement, but found ‘not’"#]],
     );
     check(
         "a ~in= 1",
@@ -1245,19 +1200,13 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..3
+                Error@2..8
                   Tilde@2..3 "~"
-                Error@3..5
                   KwIn@3..5 "in"
-                Error@5..6
                   Equ@5..6 "="
-                Whitespace@6..7 " "
-                CallStmt@7..8
-                  LiteralExpr@7..8
-                    IntLiteral@7..8 "1"
-            error at 2..3: expected statement, but found ‘~’
-            error at 3..5: expected statement, but found ‘in’
-            error at 5..6: expected statement, but found ‘=’"#]],
+                  Whitespace@6..7 " "
+                  IntLiteral@7..8 "1"
+            error at 2..3: expected statement, but found ‘~’"#]],
     );
     check(
         "a in= 1",
@@ -1269,16 +1218,12 @@ fn recover_not_a_compound_asn_op() {
                     Name@0..1
                       Identifier@0..1 "a"
                 Whitespace@1..2 " "
-                Error@2..4
+                Error@2..7
                   KwIn@2..4 "in"
-                Error@4..5
                   Equ@4..5 "="
-                Whitespace@5..6 " "
-                CallStmt@6..7
-                  LiteralExpr@6..7
-                    IntLiteral@6..7 "1"
-            error at 2..4: expected statement, but found ‘in’
-            error at 4..5: expected statement, but found ‘=’"#]],
+                  Whitespace@5..6 " "
+                  IntLiteral@6..7 "1"
+            error at 2..4: expected statement, but found ‘in’"#]],
     );
     check(
         "a == 1",
@@ -1829,7 +1774,8 @@ fn recover_if_stmt_missing_end() {
                     KwThen@8..12 "then"
                     StmtList@12..12
                   EndGroup@12..12
-            error at 8..12: expected ‘else’, ‘elsif’ or ‘end’ after here"#]],
+            error at 8..12: expected ‘else’, ‘elsif’ or ‘end’ after here
+            | note for 0..2: the ‘if’ here is not closed"#]],
     );
 }
 
@@ -2040,7 +1986,8 @@ fn recover_if_stmt_multiple_elses() {
                             Whitespace@26..27 " "
                             KwIf@27..29 "if"
                   EndGroup@29..29
-            error at 27..29: expected ‘end’ after here"#]],
+            error at 27..29: expected ‘end’ after here
+            | note for 0..2: the ‘if’ here is not closed"#]],
     );
 }
 
@@ -2235,6 +2182,7 @@ fn recover_begin_missing_end_in_if() {
                   EndGroup@16..21
                     KwEndIf@16..21 "endif"
             error at 16..21: expected ‘end’, but found ‘endif’
+            | note for 10..15: the ‘begin’ here is not closed
             warn at 16..21: ‘endif’ found, assuming it to be ’end if’"#]],
     );
 }
@@ -2253,7 +2201,36 @@ fn recover_begin_with_endloop() {
                   EndGroup@6..6
                 Error@6..13
                   KwEndLoop@6..13 "endloop"
-            error at 6..13: expected ‘end’, but found ‘endloop’"#]],
+            error at 6..13: expected ‘end’, but found ‘endloop’
+            | note for 0..5: the ‘begin’ here is not closed"#]],
+    );
+}
+
+#[test]
+fn recover_mismatched_end_group_in_nested_constructs() {
+    check(
+        "begin loop endif end",
+        expect![[r#"
+            Source@0..20
+              StmtList@0..20
+                BlockStmt@0..11
+                  KwBegin@0..5 "begin"
+                  Whitespace@5..6 " "
+                  StmtList@6..11
+                    LoopStmt@6..11
+                      KwLoop@6..10 "loop"
+                      Whitespace@10..11 " "
+                      StmtList@11..11
+                      EndGroup@11..11
+                  EndGroup@11..11
+                Error@11..16
+                  KwEndIf@11..16 "endif"
+                Whitespace@16..17 " "
+                Error@17..20
+                  KwEnd@17..20 "end"
+            error at 11..16: expected ‘end’, but found ‘endif’
+            | note for 6..10: the ‘loop’ here is not closed
+            error at 17..20: expected statement, but found ‘end’"#]],
     );
 }
 
@@ -2271,7 +2248,8 @@ fn recover_begin_with_endfor() {
                   EndGroup@6..6
                 Error@6..12
                   KwEndFor@6..12 "endfor"
-            error at 6..12: expected ‘end’, but found ‘endfor’"#]],
+            error at 6..12: expected ‘end’, but found ‘endfor’
+            | note for 0..5: the ‘begin’ here is not closed"#]],
     );
 }
 
@@ -2289,7 +2267,8 @@ fn recover_begin_with_endcase() {
                   EndGroup@6..6
                 Error@6..13
                   KwEndCase@6..13 "endcase"
-            error at 6..13: expected ‘end’, but found ‘endcase’"#]],
+            error at 6..13: expected ‘end’, but found ‘endcase’
+            | note for 0..5: the ‘begin’ here is not closed"#]],
     );
 }
 
@@ -2651,7 +2630,8 @@ fn parse_checked() {
             Source@0..7
               StmtList@0..7
                 CheckednessStmt@0..7
-                  KwChecked@0..7 "checked""#]],
+                  Checkedness@0..7
+                    KwChecked@0..7 "checked""#]],
     );
 }
 
@@ -2672,7 +2652,8 @@ fn recover_on_checked() {
                   Assign@6..8 ":="
                 Whitespace@8..10 " \n"
                 CheckednessStmt@10..17
-                  KwChecked@10..17 "checked"
+                  Checkedness@10..17
+                    KwChecked@10..17 "checked"
             error at 10..17: expected expression, but found ‘checked’"#]],
     );
 }
@@ -2685,7 +2666,8 @@ fn parse_unchecked() {
             Source@0..9
               StmtList@0..9
                 CheckednessStmt@0..9
-                  KwUnchecked@0..9 "unchecked""#]],
+                  Checkedness@0..9
+                    KwUnchecked@0..9 "unchecked""#]],
     );
 }
 
@@ -2706,7 +2688,8 @@ fn recover_on_unchecked() {
                   Assign@6..8 ":="
                 Whitespace@8..10 " \n"
                 CheckednessStmt@10..19
-                  KwUnchecked@10..19 "unchecked"
+                  Checkedness@10..19
+                    KwUnchecked@10..19 "unchecked"
             error at 10..19: expected expression, but found ‘unchecked’"#]],
     );
 }
@@ -2816,7 +2799,8 @@ fn recover_loop_stmt_missing_tail_loop() {
                   StmtList@15..15
                   EndGroup@15..18
                     KwEnd@15..18 "end"
-            error at 9..14: expected ‘loop’, but found ‘begin’"#]],
+            error at 9..14: expected ‘loop’, but found ‘begin’
+            | note for 0..4: the ‘loop’ here is not closed"#]],
     );
 }
 
@@ -2838,7 +2822,8 @@ fn recover_just_loop() {
                       EndGroup@11..14
                         KwEnd@11..14 "end"
                   EndGroup@14..14
-            error at 11..14: expected ‘end’ after here"#]],
+            error at 11..14: expected ‘end’ after here
+            | note for 0..4: the ‘loop’ here is not closed"#]],
     );
 }
 
@@ -3691,6 +3676,53 @@ fn parse_bind_decl() {
     );
 }
 
+#[test]
+fn parse_bind_decl_to_subscript() {
+    check(
+        "bind a to arr(3), var b to rec.f",
+        expect![[r#"
+        Source@0..32
+          StmtList@0..32
+            BindDecl@0..32
+              KwBind@0..4 "bind"
+              Whitespace@4..5 " "
+              BindItem@5..16
+                Name@5..6
+                  Identifier@5..6 "a"
+                Whitespace@6..7 " "
+                KwTo@7..9 "to"
+                Whitespace@9..10 " "
+                CallExpr@10..16
+                  NameExpr@10..13
+                    Name@10..13
+                      Identifier@10..13 "arr"
+                  ParamList@13..16
+                    LeftParen@13..14 "("
+                    Param@14..15
+                      LiteralExpr@14..15
+                        IntLiteral@14..15 "3"
+                    RightParen@15..16 ")"
+              Comma@16..17 ","
+              Whitespace@17..18 " "
+              BindItem@18..32
+                VarAttr@18..21
+                  KwVar@18..21 "var"
+                Whitespace@21..22 " "
+                Name@22..23
+                  Identifier@22..23 "b"
+                Whitespace@23..24 " "
+                KwTo@24..26 "to"
+                Whitespace@26..27 " "
+                FieldExpr@27..32
+                  NameExpr@27..30
+                    Name@27..30
+                      Identifier@27..30 "rec"
+                  Dot@30..31 "."
+                  Name@31..32
+                    Identifier@31..32 "f""#]],
+    );
+}
+
 #[test]
 fn parse_bind_decl_many_bindings() {
     check(
@@ -5048,7 +5080,8 @@ fn recover_on_init() {
                     Whitespace@18..19 " "
                     LiteralExpr@19..20
                       IntLiteral@19..20 "1"
-            error at 9..13: expected ‘loop’, but found ‘init’"#]],
+            error at 9..13: expected ‘loop’, but found ‘init’
+            | note for 0..4: the ‘loop’ here is not closed"#]],
     );
 }
 
@@ -5198,7 +5231,8 @@ fn recover_handler_stmt_missing_tail() {
                   Whitespace@18..19 " "
                   EndGroup@19..22
                     KwEnd@19..22 "end"
-            error at 19..22: expected ‘handler’ after here"#]],
+            error at 19..22: expected ‘handler’ after here
+            | note for 0..7: the ‘handler’ here is not closed"#]],
     );
 }
 
@@ -5341,6 +5375,26 @@ fn parse_quit_stmt_all_opt() {
     );
 }
 
+#[test]
+fn parse_quit_stmt_opt_reason_gt_and_code() {
+    check(
+        "quit > : 50",
+        expect![[r#"
+            Source@0..11
+              StmtList@0..11
+                QuitStmt@0..11
+                  KwQuit@0..4 "quit"
+                  Whitespace@4..5 " "
+                  QuitCause@5..6
+                    Greater@5..6 ">"
+                  Whitespace@6..7 " "
+                  Colon@7..8 ":"
+                  Whitespace@8..9 " "
+                  LiteralExpr@9..11
+                    IntLiteral@9..11 "50""#]],
+    );
+}
+
 #[test]
 fn recover_quit_stmt_missing_code_expr() {
     check(
@@ -5357,6 +5411,36 @@ fn recover_quit_stmt_missing_code_expr() {
     );
 }
 
+#[test]
+fn recover_quit_stmt_cause_missing_code_expr() {
+    check(
+        "quit < :\nvar a := 1",
+        expect![[r#"
+            Source@0..19
+              StmtList@0..19
+                QuitStmt@0..8
+                  KwQuit@0..4 "quit"
+                  Whitespace@4..5 " "
+                  QuitCause@5..6
+                    Less@5..6 "<"
+                  Whitespace@6..7 " "
+                  Colon@7..8 ":"
+                Whitespace@8..9 "\n"
+                ConstVarDecl@9..19
+                  KwVar@9..12 "var"
+                  Whitespace@12..13 " "
+                  NameList@13..14
+                    Name@13..14
+                      Identifier@13..14 "a"
+                  Whitespace@14..15 " "
+                  Assign@15..17 ":="
+                  Whitespace@17..18 " "
+                  LiteralExpr@18..19
+                    IntLiteral@18..19 "1"
+            error at 9..12: expected expression, but found ‘var’"#]],
+    );
+}
+
 #[test]
 fn recover_on_quit() {
     check(
@@ -7140,6 +7224,7 @@ fn recover_module_decl_double_post() {
                     Name@27..28
                       Identifier@27..28 "a"
             error at 16..20: expected ‘end’, but found ‘post’
+            | note for 0..6: the ‘module’ here is not closed
             error at 23..26: expected statement, but found ‘end’"#]],
     );
 }
@@ -9121,7 +9206,8 @@ fn recover_on_include() {
                           StringLiteral@12..24 "\"still_here\""
                   EndGroup@24..24
             error at 4..11: expected identifier or ‘:’, but found ‘include’
-            error at 12..24: expected ‘end’ after here"#]],
+            error at 12..24: expected ‘end’ after here
+            | note for 0..3: the ‘for’ here is not closed"#]],
     )
 }
 
@@ -9146,13 +9232,11 @@ fn recover_many_units() {
               KwUnit@0..4 "unit"
               Whitespace@4..5 " "
               StmtList@5..14
-                Error@5..9
+                Error@5..14
                   KwUnit@5..9 "unit"
-                Whitespace@9..10 " "
-                Error@10..14
+                  Whitespace@9..10 " "
                   KwUnit@10..14 "unit"
-            error at 5..9: expected statement, but found ‘unit’
-            error at 10..14: expected statement, but found ‘unit’"#]],
+            error at 5..9: expected statement, but found ‘unit’"#]],
     );
 }
 
@@ -11559,6 +11643,28 @@ fn recover_get_stmt_missing_stream_expr() {
     );
 }
 
+#[test]
+fn parse_get_skip_with_width() {
+    // Rejected during validation
+    check(
+        r#"get skip : 4"#,
+        expect![[r#"
+            Source@0..12
+              StmtList@0..12
+                GetStmt@0..12
+                  KwGet@0..3 "get"
+                  Whitespace@3..4 " "
+                  GetItem@4..12
+                    KwSkip@4..8 "skip"
+                    Whitespace@8..9 " "
+                    GetWidth@9..12
+                      Colon@9..10 ":"
+                      Whitespace@10..11 " "
+                      LiteralExpr@11..12
+                        IntLiteral@11..12 "4""#]],
+    );
+}
+
 #[test]
 fn recover_just_get() {
     check(
@@ -12359,20 +12465,282 @@ fn recover_mixed_endings() {
     check(
         "loop begin endloop",
         expect![[r#"
-        Source@0..18
-          StmtList@0..18
-            LoopStmt@0..18
-              KwLoop@0..4 "loop"
-              Whitespace@4..5 " "
-              StmtList@5..11
-                BlockStmt@5..11
-                  KwBegin@5..10 "begin"
+            Source@0..18
+              StmtList@0..18
+                LoopStmt@0..18
+                  KwLoop@0..4 "loop"
+                  Whitespace@4..5 " "
+                  StmtList@5..11
+                    BlockStmt@5..11
+                      KwBegin@5..10 "begin"
+                      Whitespace@10..11 " "
+                      StmtList@11..11
+                      EndGroup@11..11
+                  EndGroup@11..18
+                    KwEndLoop@11..18 "endloop"
+            error at 11..18: expected ‘end’, but found ‘endloop’
+            | note for 5..10: the ‘begin’ here is not closed
+            warn at 11..18: ‘endloop’ found, assuming it to be ’end loop’"#]],
+    );
+}
+
+#[test]
+fn suggest_misspelled_procedure() {
+    check(
+        "procedur p\nend p",
+        expect![[r#"
+        Source@0..16
+          StmtList@0..16
+            CallStmt@0..8
+              NameExpr@0..8
+                Name@0..8
+                  Identifier@0..8 "procedur"
+            Whitespace@8..9 " "
+            CallStmt@9..10
+              NameExpr@9..10
+                Name@9..10
+                  Identifier@9..10 "p"
+            Whitespace@10..11 "\n"
+            Error@11..14
+              KwEnd@11..14 "end"
+            Whitespace@14..15 " "
+            CallStmt@15..16
+              NameExpr@15..16
+                Name@15..16
+                  Identifier@15..16 "p"
+        error at 11..14: expected statement, but found ‘end’
+        | info: did you mean ‘procedure’?"#]],
+    );
+}
+
+#[test]
+fn suggest_misspelled_function() {
+    check(
+        "funtion f : int\nend f",
+        expect![[r#"
+            Source@0..21
+              StmtList@0..21
+                CallStmt@0..7
+                  NameExpr@0..7
+                    Name@0..7
+                      Identifier@0..7 "funtion"
+                Whitespace@7..8 " "
+                CallStmt@8..9
+                  NameExpr@8..9
+                    Name@8..9
+                      Identifier@8..9 "f"
+                Whitespace@9..10 " "
+                Error@10..15
+                  Colon@10..11 ":"
+                  Whitespace@11..12 " "
+                  KwInt@12..15 "int"
+                Whitespace@15..16 "\n"
+                Error@16..19
+                  KwEnd@16..19 "end"
+                Whitespace@19..20 " "
+                CallStmt@20..21
+                  NameExpr@20..21
+                    Name@20..21
+                      Identifier@20..21 "f"
+            error at 10..11: expected statement, but found ‘:’
+            | info: did you mean ‘function’?
+            error at 16..19: expected statement, but found ‘end’"#]],
+    );
+}
+
+#[test]
+fn suggest_misspelled_elsif() {
+    check(
+        "if a then\nelsf b then\nend if",
+        expect![[r#"
+        Source@0..28
+          StmtList@0..28
+            IfStmt@0..28
+              KwIf@0..2 "if"
+              Whitespace@2..3 " "
+              IfBody@3..21
+                NameExpr@3..4
+                  Name@3..4
+                    Identifier@3..4 "a"
+                Whitespace@4..5 " "
+                KwThen@5..9 "then"
+                Whitespace@9..10 "\n"
+                StmtList@10..21
+                  CallStmt@10..14
+                    NameExpr@10..14
+                      Name@10..14
+                        Identifier@10..14 "elsf"
+                  Whitespace@14..15 " "
+                  CallStmt@15..16
+                    NameExpr@15..16
+                      Name@15..16
+                        Identifier@15..16 "b"
+                  Whitespace@16..17 " "
+                  Error@17..21
+                    KwThen@17..21 "then"
+              Whitespace@21..22 "\n"
+              EndGroup@22..28
+                KwEnd@22..25 "end"
+                Whitespace@25..26 " "
+                KwIf@26..28 "if"
+        error at 17..21: expected statement, but found ‘then’
+        | info: did you mean ‘else’ or ‘elsif’?"#]],
+    );
+}
+
+#[test]
+fn no_suggestion_for_keyword() {
+    check(
+        "proc p\nend p",
+        expect![[r#"
+        Source@0..12
+          StmtList@0..12
+            ProcDecl@0..12
+              ProcHeader@0..6
+                KwProcedure@0..4 "proc"
+                Whitespace@4..5 " "
+                Name@5..6
+                  Identifier@5..6 "p"
+              Whitespace@6..7 "\n"
+              StmtList@7..7
+              EndGroup@7..12
+                KwEnd@7..10 "end"
+                Whitespace@10..11 " "
+                Identifier@11..12 "p""#]],
+    );
+}
+
+#[test]
+fn no_suggestion_for_short_identifier() {
+    check(
+        "foo p\nend p",
+        expect![[r#"
+        Source@0..11
+          StmtList@0..11
+            CallStmt@0..3
+              NameExpr@0..3
+                Name@0..3
+                  Identifier@0..3 "foo"
+            Whitespace@3..4 " "
+            CallStmt@4..5
+              NameExpr@4..5
+                Name@4..5
+                  Identifier@4..5 "p"
+            Whitespace@5..6 "\n"
+            Error@6..9
+              KwEnd@6..9 "end"
+            Whitespace@9..10 " "
+            CallStmt@10..11
+              NameExpr@10..11
+                Name@10..11
+                  Identifier@10..11 "p"
+        error at 6..9: expected statement, but found ‘end’"#]],
+    );
+}
+
+#[test]
+fn recover_skip_junk_to_next_stmt() {
+    check(
+        "@@@ var a := 1",
+        expect![[r#"
+            Source@0..14
+              StmtList@0..14
+                Error@0..3
+                  At@0..1 "@"
+                  At@1..2 "@"
+                  At@2..3 "@"
+                Whitespace@3..4 " "
+                ConstVarDecl@4..14
+                  KwVar@4..7 "var"
+                  Whitespace@7..8 " "
+                  NameList@8..9
+                    Name@8..9
+                      Identifier@8..9 "a"
+                  Whitespace@9..10 " "
+                  Assign@10..12 ":="
+                  Whitespace@12..13 " "
+                  LiteralExpr@13..14
+                    IntLiteral@13..14 "1"
+            error at 0..1: expected statement, but found ‘@’"#]],
+    );
+}
+
+#[test]
+fn recover_skip_junk_to_next_line() {
+    check(
+        "a := 1 ) ) )\na := 2",
+        expect![[r#"
+            Source@0..19
+              StmtList@0..19
+                AssignStmt@0..6
+                  NameExpr@0..1
+                    Name@0..1
+                      Identifier@0..1 "a"
+                  Whitespace@1..2 " "
+                  AsnOp@2..4
+                    Assign@2..4 ":="
+                  Whitespace@4..5 " "
+                  LiteralExpr@5..6
+                    IntLiteral@5..6 "1"
+                Whitespace@6..7 " "
+                Error@7..12
+                  RightParen@7..8 ")"
+                  Whitespace@8..9 " "
+                  RightParen@9..10 ")"
                   Whitespace@10..11 " "
-                  StmtList@11..11
-                  EndGroup@11..11
-              EndGroup@11..18
-                KwEndLoop@11..18 "endloop"
-        error at 11..18: expected ‘end’, but found ‘endloop’
-        warn at 11..18: ‘endloop’ found, assuming it to be ’end loop’"#]],
+                  RightParen@11..12 ")"
+                Whitespace@12..13 "\n"
+                AssignStmt@13..19
+                  NameExpr@13..14
+                    Name@13..14
+                      Identifier@13..14 "a"
+                  Whitespace@14..15 " "
+                  AsnOp@15..17
+                    Assign@15..17 ":="
+                  Whitespace@17..18 " "
+                  LiteralExpr@18..19
+                    IntLiteral@18..19 "2"
+            error at 7..8: expected statement, but found ‘)’"#]],
+    );
+}
+
+#[test]
+fn recover_skip_junk_before_case_label() {
+    check(
+        "case a of label : ) ) label : end case",
+        expect![[r#"
+        Source@0..38
+          StmtList@0..38
+            CaseStmt@0..38
+              KwCase@0..4 "case"
+              Whitespace@4..5 " "
+              NameExpr@5..6
+                Name@5..6
+                  Identifier@5..6 "a"
+              Whitespace@6..7 " "
+              KwOf@7..9 "of"
+              Whitespace@9..10 " "
+              CaseArm@10..21
+                KwLabel@10..15 "label"
+                Whitespace@15..16 " "
+                Colon@16..17 ":"
+                Whitespace@17..18 " "
+                StmtList@18..21
+                  Error@18..21
+                    RightParen@18..19 ")"
+                    Whitespace@19..20 " "
+                    RightParen@20..21 ")"
+              Whitespace@21..22 " "
+              CaseArm@22..30
+                KwLabel@22..27 "label"
+                Whitespace@27..28 " "
+                Colon@28..29 ":"
+                Whitespace@29..30 " "
+                StmtList@30..30
+              EndGroup@30..38
+                KwEnd@30..33 "end"
+                Whitespace@33..34 " "
+                KwCase@34..38 "case"
+        error at 18..19: expected statement, but found ‘)’"#]],
     );
 }
@@ -222,7 +222,12 @@ fn pointer_type(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Unchecked) || p.at(TokenKind::Pointer));
 
     let m = p.start();
-    p.eat(TokenKind::Unchecked);
+
+    if p.at(TokenKind::Unchecked) {
+        let m = p.start();
+        p.bump();
+        m.complete(p, SyntaxKind::Checkedness);
+    }
 
     if !p.eat(TokenKind::Caret) {
         p.expect(TokenKind::Pointer);
@@ -289,8 +294,8 @@ pub(super) fn constvar_param(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Var) || p.at(TokenKind::Register) || p.at(TokenKind::Identifier));
 
     let m = p.start();
-    p.eat(TokenKind::Var);
-    p.eat(TokenKind::Register);
+    eat_attr(p, TokenKind::Var, SyntaxKind::VarAttr);
+    eat_attr(p, TokenKind::Register, SyntaxKind::RegisterAttr);
 
     p.with_extra_recovery(&[TokenKind::Colon], |p| {
         super::name_list(p);
@@ -299,13 +304,22 @@ pub(super) fn constvar_param(p: &mut Parser) -> Option<CompletedMarker> {
     p.expect_punct(TokenKind::Colon);
 
     // optional: `cheat`
-    p.eat(TokenKind::Cheat);
+    eat_attr(p, TokenKind::Cheat, SyntaxKind::CheatAttr);
 
     ty::ty(p);
 
     Some(m.complete(p, SyntaxKind::ConstVarParam))
 }
 
+/// Eats an optional attribute keyword, wrapping it inside of an attribute node
+fn eat_attr(p: &mut Parser, token: TokenKind, kind: SyntaxKind) {
+    if p.at(token) {
+        let m = p.start();
+        p.bump();
+        m.complete(p, kind);
+    }
+}
+
 fn record_type(p: &mut Parser) -> Option<CompletedMarker> {
     debug_assert!(p.at(TokenKind::Record));
 
@@ -772,7 +772,8 @@ fn parse_unchecked_pointer_type() {
                   Colon@7..8 ":"
                   Whitespace@8..9 " "
                   PointerType@9..40
-                    KwUnchecked@9..18 "unchecked"
+                    Checkedness@9..18
+                      KwUnchecked@9..18 "unchecked"
                     Whitespace@18..19 " "
                     KwPointer@19..26 "pointer"
                     Whitespace@26..27 " "
@@ -799,7 +800,8 @@ fn parse_unchecked_short_pointer_type() {
                   Colon@7..8 ":"
                   Whitespace@8..9 " "
                   PointerType@9..30
-                    KwUnchecked@9..18 "unchecked"
+                    Checkedness@9..18
+                      KwUnchecked@9..18 "unchecked"
                     Whitespace@18..19 " "
                     Caret@19..20 "^"
                     PrimType@20..30
@@ -869,7 +871,8 @@ fn recover_pointer_type_just_unchecked() {
                   Colon@7..8 ":"
                   Whitespace@8..9 " "
                   PointerType@9..18
-                    KwUnchecked@9..18 "unchecked"
+                    Checkedness@9..18
+                      KwUnchecked@9..18 "unchecked"
             error at 9..18: expected ‘^’ or ‘pointer’ after here"#]],
     );
 }
@@ -1888,14 +1891,11 @@ fn recover_proc_type_with_result_ty() {
                           KwInt@30..33 "int"
                       RightParen@33..34 ")"
                 Whitespace@34..35 " "
-                Error@35..36
+                Error@35..40
                   Colon@35..36 ":"
-                Whitespace@36..37 " "
-                Error@37..40
-                  PrimType@37..40
-                    KwInt@37..40 "int"
-            error at 35..36: expected statement, but found ‘:’
-            error at 37..40: expected ‘@’ after here"#]],
+                  Whitespace@36..37 " "
+                  KwInt@37..40 "int"
+            error at 35..36: expected statement, but found ‘:’"#]],
     );
 }
 
@@ -2116,9 +2116,11 @@ fn parse_proc_type_all_constvar_attrs() {
                     ParamSpec@22..50
                       LeftParen@22..23 "("
                       ConstVarParam@23..49
-                        KwVar@23..26 "var"
+                        VarAttr@23..26
+                          KwVar@23..26 "var"
                         Whitespace@26..27 " "
-                        KwRegister@27..35 "register"
+                        RegisterAttr@27..35
+                          KwRegister@27..35 "register"
                         Whitespace@35..36 " "
                         NameList@36..37
                           Name@36..37
@@ -2126,7 +2128,8 @@ fn parse_proc_type_all_constvar_attrs() {
                         Whitespace@37..38 " "
                         Colon@38..39 ":"
                         Whitespace@39..40 " "
-                        KwCheat@40..45 "cheat"
+                        CheatAttr@40..45
+                          KwCheat@40..45 "cheat"
                         Whitespace@45..46 " "
                         PrimType@46..49
                           KwInt@46..49 "int"
@@ -2158,9 +2161,11 @@ fn recover_proc_type_constvar_attrs_missing_name() {
                     ParamSpec@22..42
                       LeftParen@22..23 "("
                       ConstVarParam@23..41
-                        KwVar@23..26 "var"
+                        VarAttr@23..26
+                          KwVar@23..26 "var"
                         Whitespace@26..27 " "
-                        KwRegister@27..35 "register"
+                        RegisterAttr@27..35
+                          KwRegister@27..35 "register"
                         Whitespace@35..36 " "
                         NameList@36..36
                         Colon@36..37 ":"
@@ -2192,7 +2197,8 @@ fn recover_proc_type_constvar_attrs_missing_name() {
                     ParamSpec@22..33
                       LeftParen@22..23 "("
                       ConstVarParam@23..32
-                        KwVar@23..26 "var"
+                        VarAttr@23..26
+                          KwVar@23..26 "var"
                         Whitespace@26..27 " "
                         NameList@27..27
                         Colon@27..28 ":"
@@ -2224,7 +2230,8 @@ fn recover_proc_type_constvar_attrs_missing_name() {
                     ParamSpec@22..38
                       LeftParen@22..23 "("
                       ConstVarParam@23..37
-                        KwRegister@23..31 "register"
+                        RegisterAttr@23..31
+                          KwRegister@23..31 "register"
                         Whitespace@31..32 " "
                         NameList@32..32
                         Colon@32..33 ":"
@@ -1,25 +1,35 @@
 //! Parser for building the initial Concrete Syntax Tree
 mod event;
+pub mod fixes;
 mod grammar;
 mod parser;
 mod sink;
 mod source;
+#[cfg(test)]
+mod typed_nodes;
 
 use source::Source;
 use toc_reporting::ReportMessage;
 use toc_scanner::Scanner;
-use toc_span::FileId;
+use toc_span::{FileId, Span, TextRange, TextSize};
 use toc_syntax::SyntaxNode;
 
 use rowan::GreenNode;
 
 use crate::sink::Sink;
 
+/// Version of the trees produced by the parser.
+///
+/// Must be bumped whenever the grammar changes the shape of the produced trees,
+/// so that trees stored by older parsers (e.g. in parse caches) aren't reused.
+pub const PARSER_VERSION: u32 = 1;
+
 pub fn parse(file: Option<FileId>, source: &str) -> ParseResult {
     let (tokens, scanner_msgs) = Scanner::new(file, source).collect_all();
 
+    let bounds = Span::new(file, TextRange::up_to(TextSize::of(source)));
     let source = Source::new(&tokens);
-    let parser = parser::Parser::new(file, source);
+    let parser = parser::Parser::new(file, source, bounds);
     let (events, mut parser_msgs) = parser.parse();
     parser_msgs.dedup_shared_ranges();
     let sink = Sink::new(&tokens, events, vec![scanner_msgs, parser_msgs]);
@@ -33,6 +43,11 @@ pub struct ParseResult {
 }
 
 impl ParseResult {
+    /// Creates a parse result from an already built tree, such as one loaded from a cache
+    pub fn new(node: GreenNode, messages: Vec<ReportMessage>) -> Self {
+        Self { node, messages }
+    }
+
     pub fn syntax(&self) -> SyntaxNode {
         SyntaxNode::new_root(self.node.clone())
     }
@@ -71,6 +86,7 @@ impl ParseResult {
 pub(crate) fn check(source: &str, expected: expect_test::Expect) {
     let res = parse(None, source);
     expected.assert_eq(&res.debug_tree());
+    typed_nodes::assert_typed_nodes(&res.syntax());
 }
 
 // Updating tests? Set `UPDATE_EXPECT=1` before running `cargo test`
@@ -134,4 +150,58 @@ mod test {
                   LiteralExpr@9..11
                     RealLiteral@9..11 "1e""#]]);
     }
+
+    #[test]
+    #[rustfmt::skip]
+    fn parse_control_chars_as_trivia() {
+        check("var a := 1\0\0\nvar b := 2", expect![[r#"
+            Source@0..23
+              StmtList@0..23
+                ConstVarDecl@0..10
+                  KwVar@0..3 "var"
+                  Whitespace@3..4 " "
+                  NameList@4..5
+                    Name@4..5
+                      Identifier@4..5 "a"
+                  Whitespace@5..6 " "
+                  Assign@6..8 ":="
+                  Whitespace@8..9 " "
+                  LiteralExpr@9..10
+                    IntLiteral@9..10 "1"
+                Error@10..12 "\0\0"
+                Whitespace@12..13 "\n"
+                ConstVarDecl@13..23
+                  KwVar@13..16 "var"
+                  Whitespace@16..17 " "
+                  NameList@17..18
+                    Name@17..18
+                      Identifier@17..18 "b"
+                  Whitespace@18..19 " "
+                  Assign@19..21 ":="
+                  Whitespace@21..22 " "
+                  LiteralExpr@22..23
+                    IntLiteral@22..23 "2"
+            error at 10..12: source contains invalid control characters (U+0000)"#]]);
+    }
+
+    #[test]
+    fn reprint_round_trip() {
+        let sources = [
+            "",
+            "   \t\n   ",
+            "% line comment\nvar a := 1 % trailing\n",
+            "/* block /* nested */ comment */ put a..\n",
+            "var a : int\r\na := 1\r\n",
+            "\u{FEFF}var \u{5909}\u{6570} := \"\\t\\u{1F600}\"",
+            // Parse errors still keep all of the source around
+            "var a := 1\0\0\nvar b := 2",
+            "begin put end end if loop",
+            "type u : union kind : 1 .. 3 of\n  label 1: a : int\nend union\ntag u, 4",
+        ];
+
+        for source in sources.iter() {
+            let res = parse(None, source);
+            assert_eq!(toc_syntax::reprint(res.syntax().green()), *source);
+        }
+    }
 }
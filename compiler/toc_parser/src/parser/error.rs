@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use toc_scanner::token::TokenKind;
+use toc_scanner::token::{TokenKind, KEYWORDS};
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum ParseMessage {
@@ -77,6 +77,62 @@ impl fmt::Display for Expected {
     }
 }
 
+/// Largest edit distance for an identifier to still be considered a misspelled keyword
+const MAX_KEYWORD_DISTANCE: usize = 2;
+
+/// Identifiers shorter than this are close to too many keywords for suggestions to be useful
+const MIN_SUGGESTION_LEN: usize = 4;
+
+/// Keywords which are only accepted as alternate spellings of other keywords
+const ALIAS_KEYWORDS: &[TokenKind] = &[
+    TokenKind::Elif,
+    TokenKind::Elseif,
+    TokenKind::EndCase,
+    TokenKind::EndFor,
+    TokenKind::EndIf,
+    TokenKind::EndLoop,
+];
+
+/// Finds the keywords that `ident` is most likely a misspelling of
+pub(crate) fn similar_keywords(ident: &str) -> Vec<TokenKind> {
+    if ident.chars().count() < MIN_SUGGESTION_LEN {
+        return vec![];
+    }
+
+    let mut closest = MAX_KEYWORD_DISTANCE + 1;
+    let mut similar = vec![];
+
+    for (keyword, kind) in KEYWORDS {
+        if ALIAS_KEYWORDS.contains(kind) {
+            continue;
+        }
+
+        let distance = toc_reporting::transposed_edit_distance(ident, keyword);
+
+        if distance < closest {
+            closest = distance;
+            similar.clear();
+        }
+
+        if distance == closest {
+            similar.push(*kind);
+        }
+    }
+
+    similar
+}
+
+/// Formats a suggestion for the given keywords
+pub(crate) fn keyword_suggestion(keywords: &[TokenKind]) -> String {
+    let keywords = keywords.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    match keywords.split_last() {
+        Some((last, [])) => format!("did you mean {}?", last),
+        Some((last, rest)) => format!("did you mean {} or {}?", rest.join(", "), last),
+        None => unreachable!("no keywords to suggest"),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -169,4 +225,30 @@ mod test {
             expect![[r#"expected expression, but found ‘var’"#]],
         )
     }
+
+    #[test]
+    fn similar_keyword_suggestions() {
+        assert_eq!(similar_keywords("procedur"), vec![TokenKind::Procedure]);
+        assert_eq!(similar_keywords("funtion"), vec![TokenKind::Function]);
+        assert_eq!(
+            similar_keywords("elsf"),
+            vec![TokenKind::Else, TokenKind::Elsif]
+        );
+        // Too short to be suggested
+        assert_eq!(similar_keywords("fnc"), vec![]);
+        // Too far from any keyword
+        assert_eq!(similar_keywords("identifier"), vec![]);
+    }
+
+    #[test]
+    fn format_keyword_suggestion() {
+        assert_eq!(
+            keyword_suggestion(&[TokenKind::Procedure]),
+            "did you mean ‘procedure’?"
+        );
+        assert_eq!(
+            keyword_suggestion(&[TokenKind::Else, TokenKind::Elsif]),
+            "did you mean ‘else’ or ‘elsif’?"
+        );
+    }
 }
@@ -46,7 +46,10 @@ impl Marker {
         // Push corresponding finish node
         parser.events.push(Event::FinishNode);
 
-        CompletedMarker { pos: self.pos }
+        CompletedMarker {
+            pos: self.pos,
+            kind,
+        }
     }
 
     /// Abandons the marker, disposing of the past node
@@ -76,6 +79,7 @@ impl Marker {
 /// Used to wrap a marker inside of another node
 pub(crate) struct CompletedMarker {
     pos: usize,
+    kind: SyntaxKind,
 }
 
 impl CompletedMarker {
@@ -87,4 +91,9 @@ impl CompletedMarker {
 
         parser.start().with_child(self.pos)
     }
+
+    /// The kind of the finished node
+    pub(crate) fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
 }
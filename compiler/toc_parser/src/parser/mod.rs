@@ -4,8 +4,8 @@ pub(crate) mod marker;
 
 use drop_bomb::DropBomb;
 pub(crate) use error::Expected;
-use toc_reporting::MessageSink;
-use toc_span::{FileId, Span};
+use toc_reporting::{MessageSink, Stage};
+use toc_span::{FileId, Span, TextRange};
 
 use crate::event::Event;
 use crate::grammar;
@@ -19,6 +19,9 @@ use std::rc::Rc;
 use toc_scanner::token::TokenKind;
 use toc_syntax::SyntaxKind;
 
+/// How many identifiers before an unexpected token are checked for misspelled keywords
+const MAX_SUGGESTION_LOOKBACK: usize = 2;
+
 const STMT_START_RECOVERY_SET: &[TokenKind] = &[
     // Preprocessor //
     TokenKind::PreprocIf,
@@ -110,12 +113,13 @@ pub(crate) struct Parser<'t, 'src> {
 }
 
 impl<'t, 'src> Parser<'t, 'src> {
-    pub(crate) fn new(file: Option<FileId>, source: Source<'t, 'src>) -> Self {
+    /// Creates a new parser, with `bounds` being the span of the whole source
+    pub(crate) fn new(file: Option<FileId>, source: Source<'t, 'src>, bounds: Span) -> Self {
         Self {
             file,
             source,
             events: vec![],
-            msg_sink: MessageSink::new(),
+            msg_sink: MessageSink::new(Stage::Parse).with_source_bounds(bounds),
             expected_kinds: vec![],
             extra_recovery: Rc::new(RefCell::new(vec![])),
         }
@@ -192,6 +196,29 @@ impl<'t, 'src> Parser<'t, 'src> {
         }
     }
 
+    /// Expects the next token to be a part of the end group of a construct, otherwise
+    /// reports an error pointing back to where the construct was `opened_at`
+    ///
+    /// # Returns
+    /// Returns `true` if the expected token was found
+    pub(crate) fn expect_closing(&mut self, kind: TokenKind, opened_at: Option<OpenedAt>) -> bool {
+        if !self.eat(kind) {
+            self.error_unexpected().with_opened_at(opened_at).report();
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Remembers where the construct starting at the next token is opened,
+    /// for use in [`expect_closing`](Self::expect_closing)
+    pub(crate) fn opened_at(&mut self) -> Option<OpenedAt> {
+        self.source.peek_token().map(|token| OpenedAt {
+            kind: token.kind,
+            range: token.range,
+        })
+    }
+
     /// Expects the next token to be of `kind`, otherwise reports an error.
     ///
     /// Does not consume the token if it was not expected.
@@ -251,6 +278,12 @@ impl<'t, 'src> Parser<'t, 'src> {
         self.peek().is_none()
     }
 
+    /// Checks if the current token is in any of the recovery sets
+    fn at_recovery_set(&mut self) -> bool {
+        self.at_set(STMT_START_RECOVERY_SET) || self.at_set(&self.extra_recovery.clone().borrow())
+        // just cloning the Rc & reborrowing the contents
+    }
+
     /// Checks if the current token is in the given set
     fn at_set(&mut self, set: &[TokenKind]) -> bool {
         self.peek().map_or(false, |k| set.contains(&k))
@@ -287,12 +320,22 @@ enum EatBehaviour {
     Recovery,
     /// Will never eat a token
     Never,
+    /// Will eat tokens up to the start of the next line, if not in any recovery sets
+    RestOfLine,
+}
+
+/// Where a construct closed by an end group is opened
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OpenedAt {
+    kind: TokenKind,
+    range: TextRange,
 }
 
 pub(crate) struct UnexpectedBuilder<'p, 't, 's> {
     p: &'p mut Parser<'t, 's>,
     category: Option<Expected>,
     marker: Option<Marker>,
+    opened_at: Option<OpenedAt>,
     eat_behaviour: EatBehaviour,
     bomb: DropBomb,
 }
@@ -303,6 +346,7 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
             p,
             category: None,
             marker: None,
+            opened_at: None,
             eat_behaviour: EatBehaviour::Recovery,
             bomb: DropBomb::new("missing call to `report`"),
         }
@@ -320,6 +364,12 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
         self
     }
 
+    /// Points back to where the construct being closed was opened
+    pub(crate) fn with_opened_at(mut self, opened_at: Option<OpenedAt>) -> Self {
+        self.opened_at = opened_at;
+        self
+    }
+
     /// Will not eat a token when building the error node
     pub(crate) fn dont_eat(mut self) -> Self {
         self.eat_behaviour = EatBehaviour::Never;
@@ -333,6 +383,13 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
         self
     }
 
+    /// Will eat the rest of the tokens on the current line when building the error node,
+    /// stopping early at anything in the recovery sets
+    pub(crate) fn skip_rest_of_line(mut self) -> Self {
+        self.eat_behaviour = EatBehaviour::RestOfLine;
+        self
+    }
+
     /// Reports the error
     pub(crate) fn report(mut self) {
         self.bomb.defuse();
@@ -343,6 +400,7 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
             Some(token) => (Some(token.kind), token.range),
             None => (None, self.p.source.last_token_range().unwrap()), // Last token always exists in a non-empty file
         };
+        let suggestion = self.keyword_suggestion();
 
         // push error
         debug_assert!(
@@ -350,9 +408,10 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
             "Extra call to `error_unexpected`"
         );
 
-        let span = Span::new(self.p.file, range);
+        let file = self.p.file;
+        let span = Span::new(file, range);
 
-        self.p.msg_sink.error(
+        let mut builder = self.p.msg_sink.error_detailed(
             &format!(
                 "{}",
                 ParseMessage::UnexpectedToken {
@@ -364,11 +423,23 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
             span,
         );
 
+        if let Some(opened_at) = self.opened_at {
+            builder = builder.with_note(
+                &format!("the {} here is not closed", opened_at.kind),
+                Span::new(file, opened_at.range),
+            );
+        }
+
+        if let Some(suggestion) = suggestion {
+            builder = builder.with_info(&suggestion, None);
+        }
+
+        builder.finish();
+
         // If the cursor is part of the recovery set (and if we're set to respect recovery sets),
         // error node does not need to be built
-        let should_eat = matches!(self.eat_behaviour, EatBehaviour::Forced)
-            || !(self.p.at_set(&STMT_START_RECOVERY_SET)
-                || self.p.at_set(&self.p.extra_recovery.clone().borrow())); // just cloning the Rc & reborrowing the contents
+        let should_eat =
+            matches!(self.eat_behaviour, EatBehaviour::Forced) || !self.p.at_recovery_set();
 
         // Never build a marker if we're at the end of the file, or behaviour is set to never eat
         if !matches!(self.eat_behaviour, EatBehaviour::Never) && !self.p.at_end() && should_eat {
@@ -379,6 +450,16 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
 
             self.p.bump();
 
+            if matches!(self.eat_behaviour, EatBehaviour::RestOfLine) {
+                // Lump the rest of the junk into the same error node
+                while !self.p.at_end()
+                    && !self.p.source.at_line_start()
+                    && !self.p.at_recovery_set()
+                {
+                    self.p.bump();
+                }
+            }
+
             m.complete(self.p, SyntaxKind::Error);
         } else if let Some(marker) = self.marker {
             // Always complete the provided marker, since there's
@@ -386,4 +467,32 @@ impl<'p, 't, 's> UnexpectedBuilder<'p, 't, 's> {
             marker.complete(self.p, SyntaxKind::Error);
         }
     }
+
+    /// Suggests a keyword if the unexpected token, or the identifiers right before it,
+    /// look like a misspelled statement or type keyword
+    fn keyword_suggestion(&mut self) -> Option<String> {
+        // Only suggest where a keyword could've started something
+        if !matches!(self.category, Some(Expected::Statement | Expected::Type)) {
+            return None;
+        }
+
+        let current = self
+            .p
+            .source
+            .peek_token()
+            .filter(|token| token.kind == TokenKind::Identifier)
+            .map(|token| token.lexeme.to_string());
+        let preceding = self
+            .p
+            .source
+            .preceding_identifiers()
+            .map(|token| token.lexeme.to_string());
+
+        current
+            .into_iter()
+            .chain(preceding.take(MAX_SUGGESTION_LOOKBACK))
+            .map(|ident| error::similar_keywords(&ident))
+            .find(|keywords| !keywords.is_empty())
+            .map(|keywords| error::keyword_suggestion(&keywords))
+    }
 }
@@ -287,7 +287,7 @@ impl From<TokenWrapper> for SyntaxKind {
             TokenKind::Whitespace => SyntaxKind::Whitespace,
             TokenKind::Comment => SyntaxKind::Comment,
             TokenKind::Error => SyntaxKind::Error,
-            TokenKind::NumberLiteral(_) => unreachable!(), // always converted out
+            TokenKind::NumberLiteral(_) | TokenKind::ControlChars => unreachable!(), // always converted out
             TokenKind::PreprocIf => SyntaxKind::PPKwIf,
             TokenKind::PreprocElseIf => SyntaxKind::PPKwElseif,
             TokenKind::PreprocElsIf => SyntaxKind::PPKwElsif,
@@ -32,6 +32,32 @@ impl<'t, 'src> Source<'t, 'src> {
         self.token_at(self.cursor)
     }
 
+    /// Iterates over the identifiers right before the cursor, starting from the closest one.
+    /// Stops at the first token that isn't an identifier.
+    pub(crate) fn preceding_identifiers(&self) -> impl Iterator<Item = &Token<'src>> {
+        self.tokens[..self.cursor]
+            .iter()
+            .rev()
+            .filter(|tok| !tok.kind.is_trivia())
+            .take_while(|tok| tok.kind == TokenKind::Identifier)
+    }
+
+    /// Checks if the next token is the first one on its line, i.e. there's a line break
+    /// in the trivia between the next token and the one before it
+    pub(crate) fn at_line_start(&mut self) -> bool {
+        self.skip_trivia();
+
+        let preceding = &self.tokens[..self.cursor];
+        let has_line_break = preceding
+            .iter()
+            .rev()
+            .take_while(|tok| tok.kind.is_trivia())
+            .any(|tok| tok.lexeme.contains('\n'));
+
+        // Start of the file also counts as the start of a line
+        has_line_break || preceding.iter().all(|tok| tok.kind.is_trivia())
+    }
+
     pub(crate) fn last_token_range(&self) -> Option<TokenRange> {
         self.tokens.last().map(|Token { range, .. }| *range)
     }
@@ -68,6 +94,7 @@ mod test {
                 kind: *kind,
                 lexeme: "",
                 range: TokenRange::new(0.into(), 0.into()),
+                value: None,
             })
             .collect()
     }
@@ -95,4 +122,59 @@ mod test {
         assert_eq!(to_kind(source.next_token()), Some(TokenKind::IntLiteral));
         assert_eq!(to_kind(source.next_token()), None);
     }
+
+    #[test]
+    fn preceding_identifiers() {
+        let tokens = make_tokens(&[
+            TokenKind::Var,
+            TokenKind::Whitespace,
+            TokenKind::Identifier,
+            TokenKind::Whitespace,
+            TokenKind::Identifier,
+            TokenKind::Whitespace,
+            TokenKind::Assign,
+        ]);
+        let mut source = Source::new(&tokens);
+
+        assert_eq!(source.preceding_identifiers().count(), 0);
+
+        source.next_token();
+        source.next_token();
+        source.next_token();
+        assert_eq!(to_kind(source.peek_token()), Some(TokenKind::Assign));
+        assert_eq!(source.preceding_identifiers().count(), 2);
+
+        source.next_token();
+        assert_eq!(source.preceding_identifiers().count(), 0);
+    }
+
+    #[test]
+    fn at_line_start() {
+        let tokens: Vec<_> = [
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Identifier, "a"),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Identifier, "b"),
+            (TokenKind::Comment, "% c"),
+            (TokenKind::Whitespace, "\n"),
+            (TokenKind::Identifier, "d"),
+        ]
+        .iter()
+        .map(|(kind, lexeme)| Token {
+            kind: *kind,
+            lexeme,
+            range: TokenRange::new(0.into(), 0.into()),
+            value: None,
+        })
+        .collect();
+        let mut source = Source::new(&tokens);
+
+        assert!(source.at_line_start());
+        source.next_token();
+        assert!(!source.at_line_start());
+        source.next_token();
+        assert!(source.at_line_start());
+        source.next_token();
+        assert!(!source.at_line_start());
+    }
 }
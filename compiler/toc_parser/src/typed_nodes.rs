@@ -0,0 +1,225 @@
+//! Checks that every node produced by the parser has a typed AST node
+use num_traits::FromPrimitive;
+use rowan::GreenNodeBuilder;
+use toc_syntax::ast::{self, AstNode};
+use toc_syntax::{SyntaxKind, SyntaxNode};
+
+/// Node kinds which are declared, but never produced by the parser
+const UNPRODUCED_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::SubprogBody,
+    SyntaxKind::ModuleBody,
+    SyntaxKind::CompoundOp,
+    SyntaxKind::RefExpr,
+    SyntaxKind::RangeSpec,
+    SyntaxKind::SubprogParam,
+];
+
+/// If `node` can be cast into its corresponding typed AST node
+fn casts_to_typed_node(node: &SyntaxNode) -> bool {
+    match node.kind() {
+        SyntaxKind::NotIn => ast::NotIn::can_cast(node),
+        SyntaxKind::NotEq => ast::NotEq::can_cast(node),
+        SyntaxKind::Name => ast::Name::can_cast(node),
+        SyntaxKind::NameList => ast::NameList::can_cast(node),
+        SyntaxKind::UnqualifiedAttr => ast::UnqualifiedAttr::can_cast(node),
+        SyntaxKind::PervasiveAttr => ast::PervasiveAttr::can_cast(node),
+        SyntaxKind::RegisterAttr => ast::RegisterAttr::can_cast(node),
+        SyntaxKind::ConstAttr => ast::ConstAttr::can_cast(node),
+        SyntaxKind::VarAttr => ast::VarAttr::can_cast(node),
+        SyntaxKind::CheatAttr => ast::CheatAttr::can_cast(node),
+        SyntaxKind::ForwardAttr => ast::ForwardAttr::can_cast(node),
+        SyntaxKind::OpaqueAttr => ast::OpaqueAttr::can_cast(node),
+        SyntaxKind::Source => ast::Source::can_cast(node),
+        SyntaxKind::StmtList => ast::StmtList::can_cast(node),
+        SyntaxKind::ImportStmt => ast::ImportStmt::can_cast(node),
+        SyntaxKind::PreprocGlob => ast::PreprocGlob::can_cast(node),
+        SyntaxKind::PPInclude => ast::PPInclude::can_cast(node),
+        SyntaxKind::PPIf => ast::PPIf::can_cast(node),
+        SyntaxKind::PPTokenBody => ast::PPTokenBody::can_cast(node),
+        SyntaxKind::PPElseif => ast::PPElseif::can_cast(node),
+        SyntaxKind::PPElse => ast::PPElse::can_cast(node),
+        SyntaxKind::PPEndIf => ast::PPEndIf::can_cast(node),
+        SyntaxKind::PPParenExpr => ast::PPParenExpr::can_cast(node),
+        SyntaxKind::PPBinaryExpr => ast::PPBinaryExpr::can_cast(node),
+        SyntaxKind::PPUnaryExpr => ast::PPUnaryExpr::can_cast(node),
+        SyntaxKind::PPNameExpr => ast::PPNameExpr::can_cast(node),
+        SyntaxKind::ConstVarDecl => ast::ConstVarDecl::can_cast(node),
+        SyntaxKind::TypeDecl => ast::TypeDecl::can_cast(node),
+        SyntaxKind::BindDecl => ast::BindDecl::can_cast(node),
+        SyntaxKind::ProcDecl => ast::ProcDecl::can_cast(node),
+        SyntaxKind::FcnDecl => ast::FcnDecl::can_cast(node),
+        SyntaxKind::ProcessDecl => ast::ProcessDecl::can_cast(node),
+        SyntaxKind::ExternalDecl => ast::ExternalDecl::can_cast(node),
+        SyntaxKind::ExternalVar => ast::ExternalVar::can_cast(node),
+        SyntaxKind::ForwardDecl => ast::ForwardDecl::can_cast(node),
+        SyntaxKind::DeferredDecl => ast::DeferredDecl::can_cast(node),
+        SyntaxKind::ModuleDecl => ast::ModuleDecl::can_cast(node),
+        SyntaxKind::ClassDecl => ast::ClassDecl::can_cast(node),
+        SyntaxKind::MonitorDecl => ast::MonitorDecl::can_cast(node),
+        SyntaxKind::AssignStmt => ast::AssignStmt::can_cast(node),
+        SyntaxKind::AsnOp => ast::AsnOp::can_cast(node),
+        SyntaxKind::OpenStmt => ast::OpenStmt::can_cast(node),
+        SyntaxKind::CloseStmt => ast::CloseStmt::can_cast(node),
+        SyntaxKind::PutStmt => ast::PutStmt::can_cast(node),
+        SyntaxKind::GetStmt => ast::GetStmt::can_cast(node),
+        SyntaxKind::ReadStmt => ast::ReadStmt::can_cast(node),
+        SyntaxKind::WriteStmt => ast::WriteStmt::can_cast(node),
+        SyntaxKind::SeekStmt => ast::SeekStmt::can_cast(node),
+        SyntaxKind::TellStmt => ast::TellStmt::can_cast(node),
+        SyntaxKind::ForStmt => ast::ForStmt::can_cast(node),
+        SyntaxKind::LoopStmt => ast::LoopStmt::can_cast(node),
+        SyntaxKind::ExitStmt => ast::ExitStmt::can_cast(node),
+        SyntaxKind::IfStmt => ast::IfStmt::can_cast(node),
+        SyntaxKind::CaseStmt => ast::CaseStmt::can_cast(node),
+        SyntaxKind::InvariantStmt => ast::InvariantStmt::can_cast(node),
+        SyntaxKind::AssertStmt => ast::AssertStmt::can_cast(node),
+        SyntaxKind::CallStmt => ast::CallStmt::can_cast(node),
+        SyntaxKind::ReturnStmt => ast::ReturnStmt::can_cast(node),
+        SyntaxKind::ResultStmt => ast::ResultStmt::can_cast(node),
+        SyntaxKind::NewStmt => ast::NewStmt::can_cast(node),
+        SyntaxKind::FreeStmt => ast::FreeStmt::can_cast(node),
+        SyntaxKind::TagStmt => ast::TagStmt::can_cast(node),
+        SyntaxKind::ForkStmt => ast::ForkStmt::can_cast(node),
+        SyntaxKind::SignalStmt => ast::SignalStmt::can_cast(node),
+        SyntaxKind::PauseStmt => ast::PauseStmt::can_cast(node),
+        SyntaxKind::QuitStmt => ast::QuitStmt::can_cast(node),
+        SyntaxKind::BreakStmt => ast::BreakStmt::can_cast(node),
+        SyntaxKind::CheckednessStmt => ast::CheckednessStmt::can_cast(node),
+        SyntaxKind::BlockStmt => ast::BlockStmt::can_cast(node),
+        SyntaxKind::BindItem => ast::BindItem::can_cast(node),
+        SyntaxKind::ProcHeader => ast::ProcHeader::can_cast(node),
+        SyntaxKind::DeviceSpec => ast::DeviceSpec::can_cast(node),
+        SyntaxKind::ParamSpec => ast::ParamSpec::can_cast(node),
+        SyntaxKind::FcnHeader => ast::FcnHeader::can_cast(node),
+        SyntaxKind::FcnResult => ast::FcnResult::can_cast(node),
+        SyntaxKind::PreStmt => ast::PreStmt::can_cast(node),
+        SyntaxKind::InitStmt => ast::InitStmt::can_cast(node),
+        SyntaxKind::PostStmt => ast::PostStmt::can_cast(node),
+        SyntaxKind::HandlerStmt => ast::HandlerStmt::can_cast(node),
+        SyntaxKind::InitVar => ast::InitVar::can_cast(node),
+        SyntaxKind::BodyDecl => ast::BodyDecl::can_cast(node),
+        SyntaxKind::PlainHeader => ast::PlainHeader::can_cast(node),
+        SyntaxKind::InheritStmt => ast::InheritStmt::can_cast(node),
+        SyntaxKind::ImplementStmt => ast::ImplementStmt::can_cast(node),
+        SyntaxKind::ImplementByStmt => ast::ImplementByStmt::can_cast(node),
+        SyntaxKind::ExportStmt => ast::ExportStmt::can_cast(node),
+        SyntaxKind::ImportList => ast::ImportList::can_cast(node),
+        SyntaxKind::ImportItem => ast::ImportItem::can_cast(node),
+        SyntaxKind::ExternalItem => ast::ExternalItem::can_cast(node),
+        SyntaxKind::ExportItem => ast::ExportItem::can_cast(node),
+        SyntaxKind::OldOpen => ast::OldOpen::can_cast(node),
+        SyntaxKind::NewOpen => ast::NewOpen::can_cast(node),
+        SyntaxKind::OpenPath => ast::OpenPath::can_cast(node),
+        SyntaxKind::OpenMode => ast::OpenMode::can_cast(node),
+        SyntaxKind::IoCap => ast::IoCap::can_cast(node),
+        SyntaxKind::OldClose => ast::OldClose::can_cast(node),
+        SyntaxKind::NewClose => ast::NewClose::can_cast(node),
+        SyntaxKind::StreamNum => ast::StreamNum::can_cast(node),
+        SyntaxKind::PutItem => ast::PutItem::can_cast(node),
+        SyntaxKind::PutOpt => ast::PutOpt::can_cast(node),
+        SyntaxKind::GetItem => ast::GetItem::can_cast(node),
+        SyntaxKind::GetWidth => ast::GetWidth::can_cast(node),
+        SyntaxKind::BinaryIO => ast::BinaryIO::can_cast(node),
+        SyntaxKind::BinaryItem => ast::BinaryItem::can_cast(node),
+        SyntaxKind::RequestSize => ast::RequestSize::can_cast(node),
+        SyntaxKind::ActualSize => ast::ActualSize::can_cast(node),
+        SyntaxKind::ForBounds => ast::ForBounds::can_cast(node),
+        SyntaxKind::StepBy => ast::StepBy::can_cast(node),
+        SyntaxKind::IfBody => ast::IfBody::can_cast(node),
+        SyntaxKind::ElseStmt => ast::ElseStmt::can_cast(node),
+        SyntaxKind::ElseifStmt => ast::ElseifStmt::can_cast(node),
+        SyntaxKind::CaseArm => ast::CaseArm::can_cast(node),
+        SyntaxKind::ExprList => ast::ExprList::can_cast(node),
+        SyntaxKind::ParamList => ast::ParamList::can_cast(node),
+        SyntaxKind::WaitStmt => ast::WaitStmt::can_cast(node),
+        SyntaxKind::ForkStatus => ast::ForkStatus::can_cast(node),
+        SyntaxKind::StackSize => ast::StackSize::can_cast(node),
+        SyntaxKind::ProcessDesc => ast::ProcessDesc::can_cast(node),
+        SyntaxKind::QuitCause => ast::QuitCause::can_cast(node),
+        SyntaxKind::Checkedness => ast::Checkedness::can_cast(node),
+        SyntaxKind::LiteralExpr => ast::LiteralExpr::can_cast(node),
+        SyntaxKind::CheatExpr => ast::CheatExpr::can_cast(node),
+        SyntaxKind::ObjClassExpr => ast::ObjClassExpr::can_cast(node),
+        SyntaxKind::InitExpr => ast::InitExpr::can_cast(node),
+        SyntaxKind::NilExpr => ast::NilExpr::can_cast(node),
+        SyntaxKind::SizeOfExpr => ast::SizeOfExpr::can_cast(node),
+        SyntaxKind::BinaryExpr => ast::BinaryExpr::can_cast(node),
+        SyntaxKind::UnaryExpr => ast::UnaryExpr::can_cast(node),
+        SyntaxKind::ParenExpr => ast::ParenExpr::can_cast(node),
+        SyntaxKind::NameExpr => ast::NameExpr::can_cast(node),
+        SyntaxKind::SelfExpr => ast::SelfExpr::can_cast(node),
+        SyntaxKind::FieldExpr => ast::FieldExpr::can_cast(node),
+        SyntaxKind::DerefExpr => ast::DerefExpr::can_cast(node),
+        SyntaxKind::NatCheatExpr => ast::NatCheatExpr::can_cast(node),
+        SyntaxKind::ArrowExpr => ast::ArrowExpr::can_cast(node),
+        SyntaxKind::IndirectExpr => ast::IndirectExpr::can_cast(node),
+        SyntaxKind::BitsExpr => ast::BitsExpr::can_cast(node),
+        SyntaxKind::CallExpr => ast::CallExpr::can_cast(node),
+        SyntaxKind::SizeSpec => ast::SizeSpec::can_cast(node),
+        SyntaxKind::PrimType => ast::PrimType::can_cast(node),
+        SyntaxKind::NameType => ast::NameType::can_cast(node),
+        SyntaxKind::Param => ast::Param::can_cast(node),
+        SyntaxKind::AllItem => ast::AllItem::can_cast(node),
+        SyntaxKind::RangeItem => ast::RangeItem::can_cast(node),
+        SyntaxKind::RelativeBound => ast::RelativeBound::can_cast(node),
+        SyntaxKind::RangeType => ast::RangeType::can_cast(node),
+        SyntaxKind::EnumType => ast::EnumType::can_cast(node),
+        SyntaxKind::ArrayType => ast::ArrayType::can_cast(node),
+        SyntaxKind::SetType => ast::SetType::can_cast(node),
+        SyntaxKind::RecordType => ast::RecordType::can_cast(node),
+        SyntaxKind::UnionType => ast::UnionType::can_cast(node),
+        SyntaxKind::PointerType => ast::PointerType::can_cast(node),
+        SyntaxKind::CollectionType => ast::CollectionType::can_cast(node),
+        SyntaxKind::ConditionType => ast::ConditionType::can_cast(node),
+        SyntaxKind::SizedCharType => ast::SizedCharType::can_cast(node),
+        SyntaxKind::SizedStringType => ast::SizedStringType::can_cast(node),
+        SyntaxKind::SeqLength => ast::SeqLength::can_cast(node),
+        SyntaxKind::RecordField => ast::RecordField::can_cast(node),
+        SyntaxKind::UnionVariant => ast::UnionVariant::can_cast(node),
+        SyntaxKind::FcnType => ast::FcnType::can_cast(node),
+        SyntaxKind::ProcType => ast::ProcType::can_cast(node),
+        SyntaxKind::ConstVarParam => ast::ConstVarParam::can_cast(node),
+        SyntaxKind::ConditionKind => ast::ConditionKind::can_cast(node),
+        SyntaxKind::RangeList => ast::RangeList::can_cast(node),
+        SyntaxKind::EndGroup => ast::EndGroup::can_cast(node),
+        SyntaxKind::UnsizedBound => ast::UnsizedBound::can_cast(node),
+        // Recovery nodes don't have a fixed shape
+        SyntaxKind::Error => true,
+        _ => false,
+    }
+}
+
+/// Asserts that every node in the tree can be cast into a typed AST node
+#[track_caller]
+pub(crate) fn assert_typed_nodes(root: &SyntaxNode) {
+    for node in root.descendants() {
+        assert!(
+            casts_to_typed_node(&node),
+            "{:?} does not have a corresponding typed AST node",
+            node.kind()
+        );
+    }
+}
+
+#[test]
+fn all_node_kinds_have_typed_nodes() {
+    let first = SyntaxKind::Name as u16;
+    let last = SyntaxKind::UnsizedBound as u16;
+
+    for kind in (first..=last).filter_map(SyntaxKind::from_u16) {
+        if UNPRODUCED_KINDS.contains(&kind) {
+            continue;
+        }
+
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(kind.into());
+        builder.finish_node();
+        let node = SyntaxNode::new_root(builder.finish());
+
+        assert!(
+            casts_to_typed_node(&node),
+            "{:?} does not have a corresponding typed AST node",
+            kind
+        );
+    }
+}
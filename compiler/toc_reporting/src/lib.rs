@@ -28,9 +28,34 @@ impl fmt::Display for AnnotateKind {
     }
 }
 
+/// Compilation stage that a message was reported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Scanning and parsing source text
+    Parse,
+    /// Validating the parsed syntax tree
+    Validate,
+    /// Lowering into the HIR
+    LowerHir,
+    /// Analyzing the HIR, including type checking
+    Analyze,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stage::Parse => "parse",
+            Stage::Validate => "validate",
+            Stage::LowerHir => "lower",
+            Stage::Analyze => "analyze",
+        })
+    }
+}
+
 /// A reported message
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ReportMessage {
+    stage: Stage,
     header: SourceAnnotation,
     annotations: Vec<SourceAnnotation>,
     footer: Vec<Annotation>,
@@ -52,6 +77,11 @@ impl ReportMessage {
         self.header.span
     }
 
+    /// Gets the compilation stage the message was reported from
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
     /// Gets any associated annotations
     pub fn annotations(&self) -> &[SourceAnnotation] {
         &self.annotations
@@ -61,6 +91,16 @@ impl ReportMessage {
     pub fn footer(&self) -> &[Annotation] {
         &self.footer
     }
+
+    /// Checks if the message and all of its annotations lie inside of `bounds`.
+    ///
+    /// Spans from other files aren't checked, since they're from a different source.
+    pub fn is_within(&self, bounds: Span) -> bool {
+        std::iter::once(&self.header)
+            .chain(&self.annotations)
+            .filter(|annotation| annotation.span.file == bounds.file)
+            .all(|annotation| bounds.range.contains_range(annotation.span.range))
+    }
 }
 
 impl fmt::Display for ReportMessage {
@@ -81,7 +121,7 @@ impl fmt::Display for ReportMessage {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SourceAnnotation {
     annotation: Annotation,
     span: Span,
@@ -128,7 +168,7 @@ impl fmt::Display for SourceAnnotation {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Annotation {
     kind: AnnotateKind,
     msg: String,
@@ -153,19 +193,42 @@ impl fmt::Display for Annotation {
 }
 
 /// Sink for message reports
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct MessageSink {
+    stage: Stage,
     messages: Vec<ReportMessage>,
+    /// Span of the whole source the messages are reported in, if known
+    bounds: Option<Span>,
 }
 
 impl MessageSink {
-    pub fn new() -> Self {
-        Self { messages: vec![] }
+    /// Creates a new sink for messages reported from `stage`
+    pub fn new(stage: Stage) -> Self {
+        Self {
+            stage,
+            messages: vec![],
+            bounds: None,
+        }
     }
 
-    /// Merges an existing set of messages into a new MessageSink
-    pub fn with_messages(messages: Vec<ReportMessage>) -> Self {
-        Self { messages }
+    /// Requires all reported messages to lie inside of `bounds`, the span of the whole source.
+    ///
+    /// Only checked in debug builds, so that messages pointing outside of the
+    /// source are caught by tests instead of breaking message rendering.
+    pub fn with_source_bounds(mut self, bounds: Span) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Merges an existing set of messages into a new MessageSink.
+    ///
+    /// Existing messages keep their original stage.
+    pub fn with_messages(stage: Stage, messages: Vec<ReportMessage>) -> Self {
+        Self {
+            stage,
+            messages,
+            bounds: None,
+        }
     }
 
     /// Reports an error message
@@ -188,6 +251,11 @@ impl MessageSink {
         self.report_detailed(AnnotateKind::Warning, message, span)
     }
 
+    /// Reports an info message
+    pub fn info(&mut self, message: &str, span: Span) {
+        self.report(AnnotateKind::Info, message, span)
+    }
+
     /// Reports a message
     ///
     /// Does not add any annotations to the message
@@ -294,15 +362,231 @@ impl<'a> MessageBuilder<'a> {
         // Defuse bomb now
         drop_bomb.defuse();
 
-        reporter.messages.push(ReportMessage {
+        let msg = ReportMessage {
+            stage: reporter.stage,
             header: SourceAnnotation {
                 annotation: Annotation { kind, msg: message },
                 span,
             },
             annotations,
             footer,
+        };
+
+        if let Some(bounds) = reporter.bounds {
+            debug_assert!(
+                msg.is_within(bounds),
+                "message is outside of the source bounds {:?}: {}",
+                bounds.range,
+                msg
+            );
+        }
+
+        reporter.messages.push(msg);
+    }
+}
+
+/// Promotes all warning messages into errors, for when warnings are treated as errors.
+///
+/// Only the kind of the message itself is changed, so any attached annotations
+/// keep their original kinds.
+pub fn promote_warnings(messages: &mut [ReportMessage]) {
+    for msg in messages {
+        let kind = &mut msg.header.annotation.kind;
+
+        if *kind == AnnotateKind::Warning {
+            *kind = AnnotateKind::Error;
+        }
+    }
+}
+
+/// Sorts messages by file and then by position, and collapses identical messages into one.
+///
+/// Messages are identical if they have the same kind, span, and message text.
+/// Collapsed messages note how many times they were reported.
+pub fn dedup_messages<'a>(
+    messages: impl IntoIterator<Item = &'a ReportMessage>,
+) -> Vec<ReportMessage> {
+    let sort_key = |msg: &ReportMessage| (msg.span().file, msg.span().range.start());
+
+    let mut messages = messages.into_iter().collect::<Vec<_>>();
+    messages.sort_by_key(|msg| sort_key(msg));
+
+    // Identical messages start at the same position, so only the messages
+    // starting at the current position need to be checked
+    let mut deduped: Vec<(&ReportMessage, usize)> = vec![];
+    let mut group_start = 0;
+
+    for msg in messages {
+        match deduped.last() {
+            Some((last, _)) if sort_key(last) == sort_key(msg) => {}
+            _ => group_start = deduped.len(),
+        }
+
+        let existing = deduped[group_start..].iter_mut().find(|(other, _)| {
+            other.kind() == msg.kind()
+                && other.span() == msg.span()
+                && other.message() == msg.message()
         });
+
+        match existing {
+            Some((_, count)) => *count += 1,
+            None => deduped.push((msg, 1)),
+        }
     }
+
+    deduped
+        .into_iter()
+        .map(|(msg, count)| {
+            let mut msg = msg.clone();
+
+            if count > 1 {
+                let text = &mut msg.header.annotation.msg;
+                *text = format!("{} (repeated {} times)", text, count);
+            }
+
+            msg
+        })
+        .collect()
+}
+
+/// Location of a span inside of a file, as used in machine-readable output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanLocation {
+    /// Path to the file containing the span
+    pub path: String,
+    /// 1-based line and column of the start of the span
+    pub start: LineColumn,
+    /// 1-based line and column of the end of the span
+    pub end: LineColumn,
+}
+
+/// A 1-based line and column position.
+///
+/// Columns are counted in characters from the start of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Serializes messages into a JSON array, for consumption by other tools.
+///
+/// `locate` maps a span into the path and line/column location of the span,
+/// or `None` if the span isn't located in a known file.
+/// Spans without a location still include the byte range, but leave the
+/// path and line/column fields as `null`.
+pub fn messages_to_json(
+    messages: &[ReportMessage],
+    locate: impl Fn(Span) -> Option<SpanLocation>,
+) -> String {
+    use serde_json::{json, Value};
+
+    let span_to_json = |span: Span| {
+        let location = locate(span);
+        let (path, start, end) = match &location {
+            Some(location) => (
+                Value::from(location.path.as_str()),
+                json!({ "line": location.start.line, "column": location.start.column }),
+                json!({ "line": location.end.line, "column": location.end.column }),
+            ),
+            None => (Value::Null, Value::Null, Value::Null),
+        };
+
+        json!({
+            "file": path,
+            "byte_start": u32::from(span.range.start()),
+            "byte_end": u32::from(span.range.end()),
+            "start": start,
+            "end": end,
+        })
+    };
+
+    let messages = messages
+        .iter()
+        .map(|msg| {
+            let annotations = msg
+                .annotations()
+                .iter()
+                .map(|annotation| {
+                    json!({
+                        "kind": annotation.kind().to_string(),
+                        "message": annotation.message(),
+                        "span": span_to_json(annotation.span()),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let footer = msg
+                .footer()
+                .iter()
+                .map(|annotation| {
+                    json!({
+                        "kind": annotation.kind().to_string(),
+                        "message": annotation.message(),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            json!({
+                "kind": msg.kind().to_string(),
+                "message": msg.message(),
+                "stage": msg.stage().to_string(),
+                "span": span_to_json(msg.span()),
+                "annotations": annotations,
+                "footer": footer,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Value::Array(messages).to_string()
+}
+
+/// Levenshtein distance between two names, used for suggesting similar names
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev_row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitute = prev_row[j] + usize::from(a_char != *b_char);
+            row[j + 1] = substitute.min(prev_row[j + 1] + 1).min(row[j] + 1);
+        }
+
+        prev_row = row;
+    }
+
+    prev_row[b.len()]
+}
+
+/// Damerau-Levenshtein distance between two words, where swapping two adjacent
+/// characters also counts as a single edit.
+///
+/// Each substring is only edited once (i.e. it's the optimal string alignment distance),
+/// which is close enough for suggesting fixes to typos.
+pub fn transposed_edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+
+    for (i, a_char) in a.iter().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        let prev_row = &rows[i];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitute = prev_row[j] + usize::from(a_char != b_char);
+            row[j + 1] = substitute.min(prev_row[j + 1] + 1).min(row[j] + 1);
+
+            if i > 0 && j > 0 && *a_char == b[j - 1] && a[i - 1] == *b_char {
+                row[j + 1] = row[j + 1].min(rows[i - 1][j - 1] + 1);
+            }
+        }
+
+        rows.push(row);
+    }
+
+    rows[a.len()][b.len()]
 }
 
 #[cfg(test)]
@@ -313,7 +597,7 @@ mod tests {
 
     #[test]
     fn report_message() {
-        let mut sink = MessageSink::new();
+        let mut sink = MessageSink::new(Stage::Parse);
         sink.report(
             AnnotateKind::Error,
             "an error message",
@@ -337,4 +621,165 @@ mod tests {
             ("a warning message", TextRange::new(3.into(), 5.into()))
         );
     }
+
+    #[test]
+    fn promote_warnings_to_errors() {
+        let span = Span::new(None, TextRange::new(0.into(), 1.into()));
+        let mut sink = MessageSink::new(Stage::Analyze);
+
+        sink.warn_detailed("unused variable", span)
+            .with_note("declared here", span)
+            .finish();
+        sink.info("just some info", span);
+
+        let mut msgs = sink.finish();
+        promote_warnings(&mut msgs);
+
+        assert_eq!(msgs[0].kind(), AnnotateKind::Error);
+        assert_eq!(msgs[0].annotations()[0].kind(), AnnotateKind::Note);
+        assert_eq!(msgs[1].kind(), AnnotateKind::Info);
+    }
+
+    #[test]
+    fn dedup_identical_messages() {
+        let span = |start: u32, end: u32| Span::new(None, TextRange::new(start.into(), end.into()));
+        let mut sink = MessageSink::new(Stage::Parse);
+
+        sink.error("expected expression", span(4, 5));
+        sink.warn("unused variable", span(0, 1));
+        sink.error("expected expression", span(4, 5));
+        // Same position, but different messages
+        sink.error("unexpected token", span(4, 5));
+        sink.warn("expected expression", span(4, 5));
+        sink.error("expected expression", span(4, 6));
+        sink.error("expected expression", span(4, 5));
+
+        let msgs = sink.finish();
+        let deduped = dedup_messages(&msgs)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            deduped,
+            vec![
+                "warn at 0..1: unused variable",
+                "error at 4..5: expected expression (repeated 3 times)",
+                "error at 4..5: unexpected token",
+                "warn at 4..5: expected expression",
+                "error at 4..6: expected expression",
+            ]
+        );
+    }
+
+    #[test]
+    fn json_single_error() {
+        let mut sink = MessageSink::new(Stage::Parse);
+        sink.error_detailed(
+            "mismatched types",
+            Span::new(None, TextRange::new(9.into(), 13.into())),
+        )
+        .with_info("types must match", None)
+        .finish();
+        let msgs = sink.finish();
+
+        let json = messages_to_json(&msgs, |span| {
+            Some(SpanLocation {
+                path: "src/main.t".to_string(),
+                start: LineColumn {
+                    line: 2,
+                    column: u32::from(span.range.start()) as usize - 3,
+                },
+                end: LineColumn {
+                    line: 2,
+                    column: u32::from(span.range.end()) as usize - 3,
+                },
+            })
+        });
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!([{
+                "kind": "error",
+                "message": "mismatched types",
+                "stage": "parse",
+                "span": {
+                    "file": "src/main.t",
+                    "byte_start": 9,
+                    "byte_end": 13,
+                    "start": { "line": 2, "column": 6 },
+                    "end": { "line": 2, "column": 10 },
+                },
+                "annotations": [],
+                "footer": [{ "kind": "info", "message": "types must match" }],
+            }])
+        );
+    }
+
+    #[test]
+    fn json_unknown_location() {
+        let mut sink = MessageSink::new(Stage::Parse);
+        sink.warn(
+            "unused variable",
+            Span::new(None, TextRange::new(0.into(), 1.into())),
+        );
+        let msgs = sink.finish();
+
+        let json = messages_to_json(&msgs, |_| None);
+
+        assert_eq!(
+            json,
+            r#"[{"annotations":[],"footer":[],"kind":"warn","message":"unused variable","span":{"byte_end":1,"byte_start":0,"end":null,"file":null,"start":null},"stage":"parse"}]"#
+        );
+    }
+
+    #[test]
+    fn message_within_bounds() {
+        let bounds = Span::new(None, TextRange::new(0.into(), 4.into()));
+        let mut sink = MessageSink::new(Stage::Parse).with_source_bounds(bounds);
+
+        sink.error_detailed(
+            "inside",
+            Span::new(None, TextRange::new(1.into(), 4.into())),
+        )
+        .with_note("at the end", Span::new(None, TextRange::empty(4.into())))
+        .finish();
+
+        let msgs = sink.finish();
+        assert!(msgs[0].is_within(bounds));
+        assert!(!msgs[0].is_within(Span::new(None, TextRange::new(0.into(), 3.into()))));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "message is outside of the source bounds")]
+    fn message_outside_bounds() {
+        let bounds = Span::new(None, TextRange::new(0.into(), 4.into()));
+        let mut sink = MessageSink::new(Stage::Parse).with_source_bounds(bounds);
+
+        sink.error(
+            "past the end",
+            Span::new(None, TextRange::new(3.into(), 5.into())),
+        );
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("print", "print"), 0);
+        assert_eq!(edit_distance("prnit", "print"), 2);
+        assert_eq!(edit_distance("pint", "print"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn transposed_edit_distances() {
+        assert_eq!(transposed_edit_distance("print", "print"), 0);
+        assert_eq!(transposed_edit_distance("prnit", "print"), 1);
+        assert_eq!(transposed_edit_distance("pint", "print"), 1);
+        assert_eq!(transposed_edit_distance("", "abc"), 3);
+        assert_eq!(transposed_edit_distance("kitten", "sitting"), 3);
+        // Substrings aren't edited more than once
+        assert_eq!(transposed_edit_distance("ca", "abc"), 3);
+    }
 }
@@ -0,0 +1,370 @@
+//! Char sequence (string and char literal) handling
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::{char, iter};
+
+/// Errors encountered while unescaping a char sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CharSeqError {
+    #[error("octal character value is greater than \\377 (decimal 255)")]
+    InvalidOctalChar,
+    #[error("unicode codepoint value is greater than U+10FFFF")]
+    InvalidUnicodeChar,
+    #[error("surrogate chars are not allowed in char sequences")]
+    SurrogateChar,
+    #[error("missing hex digits after here")]
+    MissingHexDigits,
+    #[error("unknown backslash escape")]
+    InvalidSlashEscape,
+    #[error("unknown caret escape")]
+    InvalidCaretEscape,
+    #[error("no characters in literal")]
+    EmptySequence,
+}
+
+/// Extracts the text of a char sequence literal, applying any character escapes.
+///
+/// `text` is the full literal text, including the delimiters.
+/// Any ranges in the error list are relative to the start of `text`.
+pub fn unescape(text: &str) -> (String, Vec<(CharSeqError, Range<usize>)>) {
+    let ending_delimiter = match text.chars().next() {
+        Some(delimiter @ '"') | Some(delimiter @ '\'') => delimiter,
+        _ => panic!("not a char sequence literal"),
+    };
+
+    let CharSeqExtractor {
+        extracted_text: extracted,
+        mut errors,
+        ..
+    } = CharSeqExtractor::extract(text, ending_delimiter);
+
+    if ending_delimiter == '\'' && text.len() > 1 && extracted.is_empty() && errors.is_empty() {
+        // Zero-length char literals aren't allowed
+        // Unterminated literals are already reported, so don't pile on an extra error
+        errors.push((CharSeqError::EmptySequence, 0..1));
+    }
+
+    (extracted, errors)
+}
+
+/// Finds the ranges of all character escapes in a char sequence literal,
+/// including invalid escapes.
+///
+/// `text` is the full literal text, including the delimiters.
+/// The ranges are relative to the start of `text`.
+pub fn escape_ranges(text: &str) -> Vec<Range<usize>> {
+    let ending_delimiter = match text.chars().next() {
+        Some(delimiter @ '"') | Some(delimiter @ '\'') => delimiter,
+        _ => panic!("not a char sequence literal"),
+    };
+
+    CharSeqExtractor::extract(text, ending_delimiter).escapes
+}
+
+/// Extractor for char sequences
+struct CharSeqExtractor<'a> {
+    /// Source text to extract from
+    text: &'a str,
+    /// Character source
+    char_indices: iter::Peekable<std::str::CharIndices<'a>>,
+    /// Extracted string
+    extracted_text: String,
+    /// Any errors encountered during processing
+    errors: Vec<(CharSeqError, Range<usize>)>,
+    /// Ranges of all character escapes
+    escapes: Vec<Range<usize>>,
+    /// Ending delimiter to stop at
+    ending_delimiter: char,
+
+    /// Current character
+    current: Option<(usize, char)>,
+}
+
+impl<'a> CharSeqExtractor<'a> {
+    /// Extracts the char sequence's text, applying character escapes
+    fn extract(text: &'a str, ending_delimiter: char) -> Self {
+        let mut char_indices = text.char_indices().peekable();
+        // Skip over starting delimiter
+        char_indices.next();
+
+        let mut extractor = Self {
+            text,
+            char_indices,
+            errors: vec![],
+            escapes: vec![],
+            extracted_text: String::with_capacity(256),
+            ending_delimiter,
+            current: None,
+        };
+
+        // Do the extraction
+        extractor.do_extraction();
+        extractor
+    }
+
+    fn do_extraction(&mut self) {
+        // Keep going along the string until there's no more chars, or until the end delimiter is reached
+        // Reaching the end of the inner text without a terminator is already reported by the scanner
+        while let Some(current) = self.bump() {
+            match current {
+                '\\' | '^' => {
+                    let escape_start = self.current_pos();
+
+                    if current == '\\' {
+                        self.eat_slash_escape();
+                    } else {
+                        self.eat_caret_escape();
+                    }
+
+                    let escape_end = self.peek_pos();
+                    self.escapes.push(escape_start..escape_end);
+                }
+                _ => {
+                    if current == self.ending_delimiter {
+                        // At the ending delimiter, stop
+                        return;
+                    } else {
+                        // Append character
+                        self.push(current);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Advances the char cursor by one, returning the current char
+    fn bump(&mut self) -> Option<char> {
+        self.current = self.char_indices.next();
+        Some(self.current?.1)
+    }
+
+    /// Gets the offset of the current char, relative to the start of the source string
+    fn current_pos(&self) -> usize {
+        self.current
+            .map(|(pos, _)| pos)
+            .unwrap_or_else(|| self.text.len())
+    }
+
+    /// Gets the offset of the next char, relative to the start of the source string
+    fn peek_pos(&mut self) -> usize {
+        self.char_indices
+            .peek()
+            .map(|(pos, _)| *pos)
+            .unwrap_or_else(|| self.text.len())
+    }
+
+    /// Peeks at the next char after the cursor
+    fn peek(&mut self) -> Option<char> {
+        self.char_indices.peek().map(|(_, c)| *c)
+    }
+
+    /// Appends a character to the extracted string
+    fn push(&mut self, chr: char) {
+        self.extracted_text.push(chr);
+    }
+
+    /// Appends an error to the error list
+    fn push_error(&mut self, error: CharSeqError, start: usize, end: usize) {
+        self.errors.push((error, start..end));
+    }
+
+    /// Eats all variants of the slash escape
+    fn eat_slash_escape(&mut self) {
+        let escape_start = self.current_pos();
+
+        let escaped = if let Some(chr) = self.bump() {
+            chr
+        } else {
+            // Missing escaped character
+            let escape_end = self.peek_pos();
+            self.push_error(CharSeqError::InvalidSlashEscape, escape_start, escape_end);
+
+            return;
+        };
+
+        match escaped {
+            // Simple escapes
+            '\'' => self.push('\''),
+            '"' => self.push('"'),
+            '\\' => self.push('\\'),
+            // Escaped control characters
+            'b' | 'B' => self.push('\x08'),
+            'd' | 'D' => self.push('\x7F'),
+            'e' | 'E' => self.push('\x1B'),
+            'f' | 'F' => self.push('\x0C'),
+            'r' | 'R' => self.push('\r'),
+            'n' | 'N' => self.push('\n'),
+            't' | 'T' => self.push('\t'),
+            // Escaped caret
+            '^' => self.push('^'),
+            '0'..='7' => {
+                // Octal str, {1-3}, octal 0..400
+                let digits_start = self.current_pos();
+
+                // Eat up to 2 more octal digits
+                for _ in 0..2 {
+                    if !matches!(self.peek(), Some('0'..='7')) {
+                        break;
+                    }
+
+                    // Eat it
+                    self.bump();
+                }
+
+                let digits_end = self.peek_pos();
+                let digits = &self.text[digits_start..digits_end];
+
+                // Convert to character & check if it's in range
+                // Since we're only feeding in chars that are 0..7 and only octal values up to
+                // 777 (decimal 512), parsing should be infallible
+                let chr_value = u16::from_str_radix(digits, 8).unwrap();
+
+                if let Ok(chr) = u8::try_from(chr_value) {
+                    // Successful conversion, push it
+                    self.push(chr as char);
+                } else {
+                    // Out of range
+                    self.push_error(CharSeqError::InvalidOctalChar, escape_start, digits_end);
+
+                    self.push(char::REPLACEMENT_CHARACTER);
+                }
+            }
+            'x' => {
+                // Hex sequence, {1-2} digits
+                let digits_start = self.peek_pos();
+
+                // Eat up to 2 more hex digits
+                for _ in 0..2 {
+                    if !self
+                        .peek()
+                        .map(|c| c.is_ascii_hexdigit())
+                        .unwrap_or_default()
+                    {
+                        break;
+                    }
+
+                    // Eat it
+                    self.bump();
+                }
+
+                let digits_end = self.peek_pos();
+                let digits = &self.text[digits_start..digits_end];
+
+                if digits.is_empty() {
+                    // Missing hex digits after position
+                    self.push_error(CharSeqError::MissingHexDigits, escape_start, digits_end);
+
+                    // Push `escaped`
+                    self.push(escaped);
+
+                    return;
+                }
+
+                // Convert to character value & push it
+                // Since we're only feeding in chars that are '0'..'F' and only hex values up to
+                // `u8::MAX`, parsing should be infallible
+                let chr_value = u8::from_str_radix(digits, 16).unwrap();
+                self.push(chr_value as char);
+            }
+            'u' | 'U' => {
+                // u: unicode character {1-8} `char::REPLACEMENT_CHARACTER` if out of range
+                let digits_start = self.peek_pos();
+
+                // Eat up to 8 more hex digits
+                for _ in 0..8 {
+                    if !self
+                        .peek()
+                        .map(|c| c.is_ascii_hexdigit())
+                        .unwrap_or_default()
+                    {
+                        break;
+                    }
+
+                    // Eat it
+                    self.bump();
+                }
+
+                let digits_end = self.peek_pos();
+                let digits = &self.text[digits_start..digits_end];
+
+                if digits.is_empty() {
+                    // Missing hex digits after position
+                    self.push_error(CharSeqError::MissingHexDigits, escape_start, digits_end);
+
+                    // Push `escaped`
+                    self.push(escaped);
+
+                    return;
+                }
+
+                // Convert to Unicode codepoint
+                // Since we're only feeding in chars that are '0'..'F' and only hex values up to
+                // `u32::MAX`, parsing should be infallible
+                let codepoint = u32::from_str_radix(digits, 16).unwrap();
+
+                // Try to convert codepoint into a `char`
+                if let Ok(chr) = char::try_from(codepoint) {
+                    // Codepoint is okay
+                    self.push(chr);
+                } else {
+                    if codepoint > 0x10FFFF {
+                        // Invalid codepoint
+                        self.push_error(CharSeqError::InvalidUnicodeChar, escape_start, digits_end);
+                    } else if (0xD800..=0xDFFF).contains(&codepoint) {
+                        // Surrogate character, not allowed
+                        self.push_error(CharSeqError::SurrogateChar, escape_start, digits_end);
+                    }
+
+                    // Push replacement character
+                    self.push(char::REPLACEMENT_CHARACTER);
+                }
+            }
+            _ => {
+                // Bad escape character
+                let escape_end = self.peek_pos();
+
+                self.push_error(CharSeqError::InvalidSlashEscape, escape_start, escape_end);
+
+                // Push character unmodified
+                self.push(escaped);
+            }
+        }
+    }
+
+    /// Eats all variants of the caret escape
+    fn eat_caret_escape(&mut self) {
+        let escape_start = self.current_pos();
+
+        let escaped = if let Some(chr) = self.bump() {
+            chr
+        } else {
+            // Missing escaped character
+            let escape_end = self.peek_pos();
+            self.push_error(CharSeqError::InvalidCaretEscape, escape_start, escape_end);
+
+            return;
+        };
+
+        // Parse escaped caret character
+        match escaped {
+            '@'..='_' | 'a'..='z' => {
+                let parsed = (escaped.to_ascii_uppercase() as u8) & 0x1F;
+                self.push(parsed as char);
+            }
+            '?' => {
+                // As the DEL char
+                self.push('\x7F');
+            }
+            _ => {
+                // Unless the user knows what they are doing, they are likely to not intend for the ^ character to be
+                // parsed as the beginning of a caret sequence
+                let escape_end = self.peek_pos();
+
+                self.push_error(CharSeqError::InvalidCaretEscape, escape_start, escape_end);
+
+                // Push escaped
+                self.push(escaped);
+            }
+        }
+    }
+}
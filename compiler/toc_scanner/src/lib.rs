@@ -1,18 +1,28 @@
 //! Scanner for lexing tokens
+pub mod char_seq;
 pub mod token;
 
 use logos::Logos;
 use std::ops::Range;
-use toc_reporting::MessageSink;
-use toc_span::FileId;
+use toc_reporting::{MessageSink, Stage};
+use toc_span::{FileId, Span, TextRange, TextSize};
 use token::{NumberKind, Token, TokenKind};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ErrorFerry {
     file_id: Option<FileId>,
     sink: MessageSink,
 }
 
+impl Default for ErrorFerry {
+    fn default() -> Self {
+        Self {
+            file_id: None,
+            sink: MessageSink::new(Stage::Parse),
+        }
+    }
+}
+
 impl ErrorFerry {
     pub(crate) fn push_error(&mut self, message: &str, span: Range<usize>) {
         let range = token::span_to_text_range(span);
@@ -36,6 +46,8 @@ impl<'s> Scanner<'s> {
         let mut inner = TokenKind::lexer(source);
         // Set up the file id
         inner.extras.file_id = file;
+        inner.extras.sink = MessageSink::new(Stage::Parse)
+            .with_source_bounds(Span::new(file, TextRange::up_to(TextSize::of(source))));
 
         Self { inner }
     }
@@ -49,6 +61,28 @@ impl<'s> Scanner<'s> {
 
         (toks, self.inner.extras.finish())
     }
+
+    /// Unescapes a char sequence literal, reporting any invalid escapes
+    fn unescape_char_seq(&mut self, kind: TokenKind, text: &str) -> String {
+        let (value, errors) = char_seq::unescape(text);
+        let literal_start = self.inner.span().start;
+        let literal_kind = if kind == TokenKind::StringLiteral {
+            "string"
+        } else {
+            "char"
+        };
+
+        // TODO: Add note saying to escape the caret for `InvalidCaretEscape`
+        for (err, range) in errors {
+            let span = (literal_start + range.start)..(literal_start + range.end);
+
+            self.inner
+                .extras
+                .push_error(&format!("invalid {} literal: {}", literal_kind, err), span);
+        }
+
+        value
+    }
 }
 
 impl<'s> std::iter::Iterator for Scanner<'s> {
@@ -68,16 +102,43 @@ impl<'s> std::iter::Iterator for Scanner<'s> {
                     .push_error("invalid character", self.inner.span());
                 TokenKind::Error
             }
+            TokenKind::ControlChars => {
+                // Report the whole run at once, instead of for each character
+                let first = self.inner.slice().chars().next().unwrap();
+                self.inner.extras.push_error(
+                    &format!(
+                        "source contains invalid control characters (U+{:04X})",
+                        u32::from(first)
+                    ),
+                    self.inner.span(),
+                );
+                TokenKind::Error
+            }
             other => other,
         };
 
         let text = self.inner.slice();
         let range = token::span_to_text_range(self.inner.span());
 
-        Some(Token::new(kind, text, range))
+        let value = match kind {
+            TokenKind::CharLiteral | TokenKind::StringLiteral => {
+                Some(self.unescape_char_seq(kind, text))
+            }
+            _ => None,
+        };
+
+        Some(Token::new(kind, text, range, value))
     }
 }
 
+/// Scans `source` into a stream of token kinds and byte ranges.
+///
+/// Invalid characters are produced as [`TokenKind::Error`] tokens.
+/// Any scanning errors are not reported, use [`Scanner::collect_all`] to get those.
+pub fn tokenize(source: &str) -> impl Iterator<Item = (TokenKind, Range<usize>)> + '_ {
+    Scanner::new(None, source).map(|token| (token.kind, token.range.into()))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -159,12 +220,52 @@ mod test {
         expecting.assert_eq(&buf);
     }
 
+    #[test]
+    fn tokenize_source() {
+        assert_eq!(
+            tokenize("a := 1 $").collect::<Vec<_>>(),
+            vec![
+                (TokenKind::Identifier, 0..1),
+                (TokenKind::Whitespace, 1..2),
+                (TokenKind::Assign, 2..4),
+                (TokenKind::Whitespace, 4..5),
+                (TokenKind::IntLiteral, 5..6),
+                (TokenKind::Whitespace, 6..7),
+                (TokenKind::Error, 7..8),
+            ]
+        );
+    }
+
     #[test]
     fn scan_whitespace() {
         expect("     ", &TokenKind::Whitespace);
         expect("     \t", &TokenKind::Whitespace);
         expect("   \n   ", &TokenKind::Whitespace);
         expect("   \r\n   ", &TokenKind::Whitespace);
+        expect("  \x0B\x0C ", &TokenKind::Whitespace);
+    }
+
+    #[test]
+    fn scan_control_chars() {
+        // Only one error for the whole run, and the surrounding tokens are unaffected
+        expect_seq_with_errors(
+            "ab\0\0\x01cd 1",
+            &[
+                (TokenKind::Identifier, "ab"),
+                (TokenKind::Error, "\0\0\x01"),
+                (TokenKind::Identifier, "cd"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::IntLiteral, "1"),
+            ],
+            expect![[r#"
+                error at 2..5: source contains invalid control characters (U+0000)"#]],
+        );
+        expect_with_error(
+            "\x1B\x07\0\x1F",
+            &TokenKind::Error,
+            expect![[r#"
+                error at 0..4: source contains invalid control characters (U+001B)"#]],
+        );
     }
 
     #[test]
@@ -552,6 +653,13 @@ mod test {
         expect("xor", &TokenKind::Xor);
     }
 
+    #[test]
+    fn scan_keyword_table() {
+        for (keyword, kind) in token::KEYWORDS {
+            expect(keyword, kind);
+        }
+    }
+
     #[test]
     fn scan_identifiers() {
         expect("_source_text", &TokenKind::Identifier);
@@ -642,42 +750,69 @@ mod test {
 
         // not an escaped terminator
         expect(r#""abcd\\""#, &TokenKind::StringLiteral);
-        expect(r#""abcd\\\k""#, &TokenKind::StringLiteral);
+        expect_with_error(
+            r#""abcd\\\k""#,
+            &TokenKind::StringLiteral,
+            expect![[r#"error at 7..9: invalid string literal: unknown backslash escape"#]],
+        );
         expect(r#""abcd^^""#, &TokenKind::StringLiteral);
         expect(r#""abcd^^a""#, &TokenKind::StringLiteral);
 
         // Invalid scanning should allow a literal from the successfully parsed characters
-        // Unterminated literals are not reported here, and is deferred to HIR lowering
 
         // Ends at the end of line
-        expect_seq(
+        expect_seq_with_errors(
             "\"abcd\n",
             &[
                 (TokenKind::StringLiteral, "\"abcd"),
                 (TokenKind::Whitespace, "\n"),
             ],
+            expect![[r#"error at 0..5: unterminated string literal"#]],
         );
 
-        expect_seq(
+        expect_seq_with_errors(
             "\"abcd\r\n",
             &[
                 (TokenKind::StringLiteral, "\"abcd"),
                 (TokenKind::Whitespace, "\r\n"),
             ],
+            expect![[r#"error at 0..5: unterminated string literal"#]],
         );
 
         // Ends at the end of file
-        expect("\"abcd", &TokenKind::StringLiteral);
+        expect_with_error(
+            "\"abcd",
+            &TokenKind::StringLiteral,
+            expect![[r#"error at 0..5: unterminated string literal"#]],
+        );
 
         // Escaped terminator
-        expect(r#""abcd\""#, &TokenKind::StringLiteral);
-        expect(r#""abcd^""#, &TokenKind::StringLiteral);
+        expect_with_error(
+            r#""abcd\""#,
+            &TokenKind::StringLiteral,
+            expect![[r#"error at 0..7: unterminated string literal"#]],
+        );
+        expect_with_error(
+            r#""abcd^""#,
+            &TokenKind::StringLiteral,
+            expect![[r#"
+                error at 0..7: unterminated string literal
+                error at 5..7: invalid string literal: unknown caret escape"#]],
+        );
 
         // Mismatched delimiter
-        expect("\"abcd'", &TokenKind::StringLiteral);
+        expect_with_error(
+            "\"abcd'",
+            &TokenKind::StringLiteral,
+            expect![[r#"error at 0..6: unterminated string literal"#]],
+        );
 
         // Empty
-        expect(r#"""#, &TokenKind::StringLiteral);
+        expect_with_error(
+            r#"""#,
+            &TokenKind::StringLiteral,
+            expect![[r#"error at 0..1: unterminated string literal"#]],
+        );
     }
 
     #[test]
@@ -695,36 +830,172 @@ mod test {
         expect(r#"'abcd\\'"#, &TokenKind::CharLiteral);
 
         // Invalid scanning should allow a literal from the successfully parsed characters
-        // Unterminated literals are not reported here, and is deferred to HIR lowering
 
         // Ends at the end of line
-        expect_seq(
+        expect_seq_with_errors(
             "'abcd\n",
             &[
                 (TokenKind::CharLiteral, "'abcd"),
                 (TokenKind::Whitespace, "\n"),
             ],
+            expect![[r#"error at 0..5: unterminated char literal"#]],
         );
 
-        expect_seq(
+        expect_seq_with_errors(
             "'abcd\r\n",
             &[
                 (TokenKind::CharLiteral, "'abcd"),
                 (TokenKind::Whitespace, "\r\n"),
             ],
+            expect![[r#"error at 0..5: unterminated char literal"#]],
         );
 
         // Ends at the end of file
-        expect("'abcd", &TokenKind::CharLiteral);
+        expect_with_error(
+            "'abcd",
+            &TokenKind::CharLiteral,
+            expect![[r#"error at 0..5: unterminated char literal"#]],
+        );
 
         // Escaped terminator
-        expect(r#"'abcd\'"#, &TokenKind::CharLiteral);
+        expect_with_error(
+            r#"'abcd\'"#,
+            &TokenKind::CharLiteral,
+            expect![[r#"error at 0..7: unterminated char literal"#]],
+        );
 
         // Mismatched delimiter
-        expect("'abcd\"", &TokenKind::CharLiteral);
+        expect_with_error(
+            "'abcd\"",
+            &TokenKind::CharLiteral,
+            expect![[r#"error at 0..6: unterminated char literal"#]],
+        );
 
         // Empty
-        expect(r#"'"#, &TokenKind::CharLiteral);
+        expect_with_error(
+            r#"'"#,
+            &TokenKind::CharLiteral,
+            expect![[r#"error at 0..1: unterminated char literal"#]],
+        );
+    }
+
+    #[test]
+    fn unterminated_literal_keeps_next_line() {
+        expect_seq_with_errors(
+            "\"abcd\nvar a",
+            &[
+                (TokenKind::StringLiteral, "\"abcd"),
+                (TokenKind::Whitespace, "\n"),
+                (TokenKind::Var, "var"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Identifier, "a"),
+            ],
+            expect![[r#"error at 0..5: unterminated string literal"#]],
+        );
+
+        expect_seq_with_errors(
+            "'abcd\nvar a",
+            &[
+                (TokenKind::CharLiteral, "'abcd"),
+                (TokenKind::Whitespace, "\n"),
+                (TokenKind::Var, "var"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Identifier, "a"),
+            ],
+            expect![[r#"error at 0..5: unterminated char literal"#]],
+        );
+
+        // Escapes can't continue a literal onto the next line
+        expect_seq_with_errors(
+            "\"abcd\\\n'e'",
+            &[
+                (TokenKind::StringLiteral, "\"abcd\\"),
+                (TokenKind::Whitespace, "\n"),
+                (TokenKind::CharLiteral, "'e'"),
+            ],
+            expect![[r#"
+                error at 0..6: unterminated string literal
+                error at 5..6: invalid string literal: unknown backslash escape"#]],
+        );
+    }
+
+    #[track_caller]
+    fn expect_char_seq_value(source: &str, value: &str) {
+        let mut scanner = Scanner::new(None, source);
+
+        let token = scanner.next().unwrap();
+        assert_eq!(token.lexeme, source);
+        assert_eq!(token.value.as_deref(), Some(value));
+        assert_eq!(build_error_list(scanner.inner.extras.finish()), "");
+    }
+
+    #[test]
+    fn scan_char_seq_escapes() {
+        // Backslash escapes
+        expect_char_seq_value(r#""\n""#, "\n");
+        expect_char_seq_value(r#""\t""#, "\t");
+        expect_char_seq_value(r#""\"""#, "\"");
+        expect_char_seq_value(r#""\'""#, "'");
+        expect_char_seq_value(r#""\\""#, "\\");
+        expect_char_seq_value(r#""\f""#, "\x0C");
+        expect_char_seq_value(r#""\r""#, "\r");
+        expect_char_seq_value(r#""\b""#, "\x08");
+        expect_char_seq_value(r#""\e""#, "\x1B");
+        expect_char_seq_value(r#""\d""#, "\x7F");
+        // Octal escapes
+        expect_char_seq_value(r#""\101""#, "A");
+        expect_char_seq_value(r#""\0o""#, "\0o");
+        expect_char_seq_value(r#""\377""#, "\u{FF}");
+        // Hex escapes
+        expect_char_seq_value(r#""\x41""#, "A");
+        expect_char_seq_value(r#""\x2g""#, "\x02g");
+        // Caret escapes
+        expect_char_seq_value(r#""^J""#, "\n");
+        expect_char_seq_value(r#""^?""#, "\x7F");
+
+        // Also applies to char literals
+        expect_char_seq_value(r#"'\t\''"#, "\t'");
+    }
+
+    #[test]
+    fn scan_invalid_char_seq_escapes() {
+        // Unknown escape keeps the rest of the literal intact
+        let (toks, errors) = {
+            let (toks, errors) = Scanner::new(None, r#""a\qb""#).collect_all();
+            (toks, build_error_list(errors))
+        };
+        assert_eq!(toks[0].kind, TokenKind::StringLiteral);
+        assert_eq!(toks[0].value.as_deref(), Some("aqb"));
+        expect![[r#"error at 2..4: invalid string literal: unknown backslash escape"#]]
+            .assert_eq(&errors);
+
+        expect_with_error(
+            r#"'\777'"#,
+            &TokenKind::CharLiteral,
+            expect![[
+                r#"error at 1..5: invalid char literal: octal character value is greater than \377 (decimal 255)"#
+            ]],
+        );
+        expect_with_error(
+            "''",
+            &TokenKind::CharLiteral,
+            expect![[r#"error at 0..1: invalid char literal: no characters in literal"#]],
+        );
+    }
+
+    #[test]
+    fn char_seq_escape_ranges() {
+        use crate::char_seq::escape_ranges;
+
+        assert_eq!(escape_ranges(r#""a\nb^Jc""#), vec![2..4, 5..7]);
+        assert_eq!(
+            escape_ranges(r#""\101\x41\u0041""#),
+            vec![1..5, 5..9, 9..15]
+        );
+        // Invalid escapes are included too
+        assert_eq!(escape_ranges(r#""a\qb""#), vec![2..4]);
+        assert_eq!(escape_ranges(r#"'\t'"#), vec![1..3]);
+        assert!(escape_ranges(r#""plain""#).is_empty());
     }
 
     #[test]
@@ -14,14 +14,23 @@ pub struct Token<'src> {
     pub kind: TokenKind,
     pub lexeme: &'src str,
     pub range: TokenRange,
+    /// Unescaped text of a char sequence literal.
+    /// Only present for `CharLiteral` and `StringLiteral` tokens.
+    pub value: Option<String>,
 }
 
 impl<'s> Token<'s> {
-    pub(crate) fn new(kind: TokenKind, lexeme: &'s str, range: TokenRange) -> Self {
+    pub(crate) fn new(
+        kind: TokenKind,
+        lexeme: &'s str,
+        range: TokenRange,
+        value: Option<String>,
+    ) -> Self {
         Self {
             kind,
             lexeme,
             range,
+            value,
         }
     }
 }
@@ -349,7 +358,10 @@ pub enum TokenKind {
     RadixLiteral,
 
     /// All whitespace (including line delimiters)
-    #[regex("[ \t\r\n]+")]
+    ///
+    /// Vertical tabs and form feeds are also accepted, since some older editors
+    /// use them as page breaks
+    #[regex("[ \t\r\n\x0B\x0C]+")]
     Whitespace,
 
     /// All comments
@@ -371,6 +383,10 @@ pub enum TokenKind {
     #[token("#endif")]
     PreprocEndIf,
 
+    /// Runs of control characters that can't appear in a source file
+    #[regex("[\x00-\x08\x0E-\x1F]+")]
+    ControlChars,
+
     #[error]
     Error,
 }
@@ -417,16 +433,25 @@ fn lex_block_comment(lexer: &mut logos::Lexer<TokenKind>) {
 }
 
 fn nom_char_literal(lexer: &mut logos::Lexer<TokenKind>) {
-    let bump_count = check_charseq_termination(lexer.remainder(), '\'');
-    lexer.bump(bump_count);
+    nom_char_seq(lexer, '\'', "unterminated char literal");
 }
 
 fn nom_string_literal(lexer: &mut logos::Lexer<TokenKind>) {
-    let bump_count = check_charseq_termination(lexer.remainder(), '"');
+    nom_char_seq(lexer, '"', "unterminated string literal");
+}
+
+fn nom_char_seq(lexer: &mut logos::Lexer<TokenKind>, terminator: char, unterminated_msg: &str) {
+    let (bump_count, terminated) = check_charseq_termination(lexer.remainder(), terminator);
     lexer.bump(bump_count);
+
+    if !terminated {
+        // Span covers the opening delimiter up to (but not including) the end of the line
+        lexer.extras.push_error(unterminated_msg, lexer.span());
+    }
 }
 
-fn check_charseq_termination(remainder: &str, terminator: char) -> usize {
+/// Finds the length of the rest of the char sequence, and if it is properly terminated
+fn check_charseq_termination(remainder: &str, terminator: char) -> (usize, bool) {
     let mut chars = remainder.char_indices().peekable();
 
     while let Some((bump_to, chr)) = chars.next() {
@@ -446,24 +471,27 @@ fn check_charseq_termination(remainder: &str, terminator: char) -> usize {
 
                 if slash_count % 2 == 1 {
                     // odd number of escape characters, nom on the next one
-                    chars.next();
+                    // line endings can't be escaped, so leave those alone
+                    if !matches!(chars.peek(), Some((_, '\r')) | Some((_, '\n'))) {
+                        chars.next();
+                    }
                 }
             }
             '\r' | '\n' => {
                 // also a terminating char, but not the right one
-                return bump_to;
+                return (bump_to, false);
             }
             c if c == terminator => {
                 // is terminated (include terminator)
                 // safe to add by 1 since '\n' is always a 1 byte char in utf-8
-                return bump_to + 1;
+                return (bump_to + 1, true);
             }
             _ => {}
         }
     }
 
     // bump it all, not terminated, maybe escaped
-    remainder.len()
+    (remainder.len(), false)
 }
 
 fn nom_number_literal(lexer: &mut logos::Lexer<TokenKind>) -> NumberKind {
@@ -553,6 +581,134 @@ fn nom_number_literal(lexer: &mut logos::Lexer<TokenKind>) -> NumberKind {
     kind
 }
 
+/// Spellings of all keywords, along with the token kind they're scanned as
+pub const KEYWORDS: &[(&str, TokenKind)] = &[
+    ("addressint", TokenKind::Addressint),
+    ("all", TokenKind::All),
+    ("and", TokenKind::And),
+    ("array", TokenKind::Array),
+    ("asm", TokenKind::Asm),
+    ("assert", TokenKind::Assert),
+    ("begin", TokenKind::Begin),
+    ("bind", TokenKind::Bind),
+    ("bits", TokenKind::Bits),
+    ("body", TokenKind::Body),
+    ("boolean", TokenKind::Boolean),
+    ("break", TokenKind::Break),
+    ("by", TokenKind::By),
+    ("case", TokenKind::Case),
+    ("char", TokenKind::Char),
+    ("cheat", TokenKind::Cheat),
+    ("checked", TokenKind::Checked),
+    ("class", TokenKind::Class),
+    ("close", TokenKind::Close),
+    ("collection", TokenKind::Collection),
+    ("condition", TokenKind::Condition),
+    ("const", TokenKind::Const),
+    ("decreasing", TokenKind::Decreasing),
+    ("def", TokenKind::Def),
+    ("deferred", TokenKind::Deferred),
+    ("div", TokenKind::Div),
+    ("elif", TokenKind::Elif),
+    ("else", TokenKind::Else),
+    ("elseif", TokenKind::Elseif),
+    ("elsif", TokenKind::Elsif),
+    ("end", TokenKind::End),
+    ("endcase", TokenKind::EndCase),
+    ("endfor", TokenKind::EndFor),
+    ("endif", TokenKind::EndIf),
+    ("endloop", TokenKind::EndLoop),
+    ("enum", TokenKind::Enum),
+    ("exit", TokenKind::Exit),
+    ("export", TokenKind::Export),
+    ("external", TokenKind::External),
+    ("false", TokenKind::False),
+    ("fcn", TokenKind::Function),
+    ("flexible", TokenKind::Flexible),
+    ("for", TokenKind::For),
+    ("fork", TokenKind::Fork),
+    ("forward", TokenKind::Forward),
+    ("free", TokenKind::Free),
+    ("function", TokenKind::Function),
+    ("get", TokenKind::Get),
+    ("handler", TokenKind::Handler),
+    ("if", TokenKind::If),
+    ("implement", TokenKind::Implement),
+    ("import", TokenKind::Import),
+    ("in", TokenKind::In),
+    ("include", TokenKind::Include),
+    ("inherit", TokenKind::Inherit),
+    ("init", TokenKind::Init),
+    ("int", TokenKind::Int),
+    ("int1", TokenKind::Int1),
+    ("int2", TokenKind::Int2),
+    ("int4", TokenKind::Int4),
+    ("invariant", TokenKind::Invariant),
+    ("label", TokenKind::Label),
+    ("loop", TokenKind::Loop),
+    ("mod", TokenKind::Mod),
+    ("module", TokenKind::Module),
+    ("monitor", TokenKind::Monitor),
+    ("nat", TokenKind::Nat),
+    ("nat1", TokenKind::Nat1),
+    ("nat2", TokenKind::Nat2),
+    ("nat4", TokenKind::Nat4),
+    ("new", TokenKind::New),
+    ("nil", TokenKind::Nil),
+    ("not", TokenKind::Not),
+    ("objectclass", TokenKind::ObjectClass),
+    ("of", TokenKind::Of),
+    ("opaque", TokenKind::Opaque),
+    ("open", TokenKind::Open),
+    ("or", TokenKind::Or),
+    ("packed", TokenKind::Packed),
+    ("pause", TokenKind::Pause),
+    ("pervasive", TokenKind::Pervasive),
+    ("pointer", TokenKind::Pointer),
+    ("post", TokenKind::Post),
+    ("pre", TokenKind::Pre),
+    ("priority", TokenKind::Priority),
+    ("proc", TokenKind::Procedure),
+    ("procedure", TokenKind::Procedure),
+    ("process", TokenKind::Process),
+    ("put", TokenKind::Put),
+    ("quit", TokenKind::Quit),
+    ("read", TokenKind::Read),
+    ("real", TokenKind::Real),
+    ("real4", TokenKind::Real4),
+    ("real8", TokenKind::Real8),
+    ("record", TokenKind::Record),
+    ("register", TokenKind::Register),
+    ("rem", TokenKind::Rem),
+    ("result", TokenKind::Result_),
+    ("return", TokenKind::Return),
+    ("seek", TokenKind::Seek),
+    ("self", TokenKind::Self_),
+    ("set", TokenKind::Set),
+    ("shl", TokenKind::Shl),
+    ("shr", TokenKind::Shr),
+    ("signal", TokenKind::Signal),
+    ("sizeof", TokenKind::SizeOf),
+    ("skip", TokenKind::Skip),
+    ("string", TokenKind::String_),
+    ("tag", TokenKind::Tag),
+    ("tell", TokenKind::Tell),
+    ("then", TokenKind::Then),
+    ("timeout", TokenKind::Timeout),
+    ("to", TokenKind::To),
+    ("true", TokenKind::True),
+    ("type", TokenKind::Type),
+    ("unchecked", TokenKind::Unchecked),
+    ("union", TokenKind::Union),
+    ("unit", TokenKind::Unit),
+    ("unqualified", TokenKind::Unqualified),
+    ("var", TokenKind::Var),
+    ("wait", TokenKind::Wait),
+    ("when", TokenKind::When),
+    ("write", TokenKind::Write),
+    ("xor", TokenKind::Xor),
+];
+
 impl TokenKind {
     pub fn is_trivia(self) -> bool {
         matches!(self, Self::Whitespace | Self::Comment | Self::Error)
@@ -719,7 +875,9 @@ impl fmt::Display for TokenKind {
             TokenKind::Whitespace => "whitespace",
             TokenKind::Comment => "comment",
             TokenKind::Error => "invalid token",
-            TokenKind::NumberLiteral(_) => unreachable!("never passed through"),
+            TokenKind::NumberLiteral(_) | TokenKind::ControlChars => {
+                unreachable!("never passed through")
+            }
             TokenKind::PreprocIf => "‘#if’",
             TokenKind::PreprocElseIf => "‘#elseif’",
             TokenKind::PreprocElsIf => "‘#elsif’",
@@ -2,11 +2,13 @@
 //! Keeps a common `text_size` version between dependents.
 
 use std::num::NonZeroU32;
+use std::ops::Range;
 
 pub use text_size::{TextRange, TextSize};
 
 /// A unique file id
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(transparent)]
 pub struct FileId(NonZeroU32);
 
@@ -25,6 +27,7 @@ impl FileId {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Span {
     pub file: Option<FileId>,
     pub range: TextRange,
@@ -43,7 +46,8 @@ impl Default for Span {
 }
 
 /// An item with an associated text span
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Spanned<T>(T, Span);
 
 impl<T> Spanned<T> {
@@ -59,3 +63,191 @@ impl<T> Spanned<T> {
         self.1
     }
 }
+
+/// Mapping between byte offsets and line & column positions in a source string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMap {
+    /// Byte ranges of each line, including the line ending
+    line_ranges: Vec<Range<usize>>,
+    /// Byte offset of the line ending of each line, or the end of the source
+    /// for the last line
+    content_ends: Vec<usize>,
+    /// Byte offset and length of every multi-byte char, in source order
+    wide_chars: Vec<(usize, usize)>,
+}
+
+impl LineMap {
+    /// Builds a new line map for `source`
+    pub fn new(source: &str) -> Self {
+        let mut line_ranges = vec![];
+        let mut content_ends = vec![];
+        let mut wide_chars = vec![];
+        let mut line_start = 0;
+
+        for (offset, chr) in source.char_indices() {
+            if chr == '\n' {
+                // CRLF line endings are treated as one line ending
+                let content_end = if source[line_start..offset].ends_with('\r') {
+                    offset - 1
+                } else {
+                    offset
+                };
+
+                let line_end = offset + 1;
+                line_ranges.push(line_start..line_end);
+                content_ends.push(content_end);
+                line_start = line_end;
+            } else if chr.len_utf8() > 1 {
+                wide_chars.push((offset, chr.len_utf8()));
+            }
+        }
+
+        // Use a line span covering the rest of the file
+        // There's always at least one line, even in an empty file
+        if line_start < source.len() || line_ranges.is_empty() {
+            line_ranges.push(line_start..source.len());
+            content_ends.push(source.len());
+        }
+
+        Self {
+            line_ranges,
+            content_ends,
+            wide_chars,
+        }
+    }
+
+    /// Finds the 0-based line and column of `byte`.
+    ///
+    /// Columns are counted in Unicode scalar values from the start of the line,
+    /// with CRLF line endings counted as a single column.
+    /// Offsets past the last line (e.g. at the end of the file) are clamped
+    /// to the last line.
+    pub fn line_col(&self, byte: usize) -> (usize, usize) {
+        let line = self
+            .line_ranges
+            .iter()
+            .position(|range| range.contains(&byte))
+            .unwrap_or(self.line_ranges.len() - 1);
+        let line_start = self.line_ranges[line].start;
+
+        // CRLF line endings only take up one column
+        let is_crlf = self.line_ranges[line].end - self.content_ends[line] == 2;
+        let byte = if is_crlf && byte > self.content_ends[line] {
+            byte - 1
+        } else {
+            byte
+        };
+
+        // Only count each multi-byte char as one column
+        let first_wide = self
+            .wide_chars
+            .partition_point(|(offset, _)| *offset < line_start);
+        let last_wide = self
+            .wide_chars
+            .partition_point(|(offset, _)| *offset < byte);
+        let extra_bytes: usize = self.wide_chars[first_wide..last_wide]
+            .iter()
+            .map(|(_, len)| len - 1)
+            .sum();
+
+        let column = byte.saturating_sub(line_start).saturating_sub(extra_bytes);
+        (line, column)
+    }
+
+    /// Gets the byte range of the 0-based `line`, including the line ending.
+    ///
+    /// ## Panics
+    /// Panics if `line` is not less than [`LineMap::line_count`].
+    pub fn line_range(&self, line: usize) -> Range<usize> {
+        self.line_ranges[line].clone()
+    }
+
+    /// Number of lines in the source.
+    /// This is always at least one, even for an empty source.
+    pub fn line_count(&self) -> usize {
+        self.line_ranges.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_ranges() {
+        let ranges = |source| {
+            let line_map = LineMap::new(source);
+            (0..line_map.line_count())
+                .map(|line| line_map.line_range(line))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ranges(""), vec![0..0]);
+        assert_eq!(ranges("a"), vec![0..1]);
+        assert_eq!(ranges("a\n"), vec![0..2]);
+        assert_eq!(ranges("a\nbc"), vec![0..2, 2..4]);
+        assert_eq!(ranges("a\r\nbc\n\n"), vec![0..3, 3..6, 6..7]);
+    }
+
+    #[test]
+    fn line_col_ascii() {
+        let line_map = LineMap::new("var a\nvar b\n");
+
+        assert_eq!(line_map.line_col(0), (0, 0));
+        assert_eq!(line_map.line_col(4), (0, 4));
+        assert_eq!(line_map.line_col(5), (0, 5));
+        assert_eq!(line_map.line_col(6), (1, 0));
+        assert_eq!(line_map.line_col(10), (1, 4));
+    }
+
+    #[test]
+    fn line_col_multibyte() {
+        // 'ü' is 2 bytes, '€' is 3 bytes, and '𝄞' is 4 bytes
+        let line_map = LineMap::new("ü€𝄞a\nb€c\n");
+
+        assert_eq!(line_map.line_col(0), (0, 0));
+        assert_eq!(line_map.line_col(2), (0, 1));
+        assert_eq!(line_map.line_col(5), (0, 2));
+        assert_eq!(line_map.line_col(9), (0, 3));
+        assert_eq!(line_map.line_col(10), (0, 4));
+        // Multi-byte chars on previous lines don't affect the column
+        assert_eq!(line_map.line_col(11), (1, 0));
+        assert_eq!(line_map.line_col(12), (1, 1));
+        assert_eq!(line_map.line_col(15), (1, 2));
+    }
+
+    #[test]
+    fn line_col_crlf() {
+        let line_map = LineMap::new("var a\r\nvar b\r\n");
+
+        assert_eq!(line_map.line_count(), 2);
+        assert_eq!(line_map.line_col(4), (0, 4));
+        // Both parts of the line ending are at the same column
+        assert_eq!(line_map.line_col(5), (0, 5));
+        assert_eq!(line_map.line_col(6), (0, 5));
+        assert_eq!(line_map.line_col(7), (1, 0));
+        assert_eq!(line_map.line_col(12), (1, 5));
+        assert_eq!(line_map.line_col(13), (1, 5));
+        // Past the end is still clamped to the last line
+        assert_eq!(line_map.line_col(14), (1, 6));
+    }
+
+    #[test]
+    fn line_col_last_line() {
+        // Without a trailing newline
+        let line_map = LineMap::new("a\nbü");
+        assert_eq!(line_map.line_col(2), (1, 0));
+        assert_eq!(line_map.line_col(5), (1, 2));
+        // Past the end is clamped to the last line
+        assert_eq!(line_map.line_col(6), (1, 3));
+
+        // With a trailing newline, the end is still on the last line
+        let line_map = LineMap::new("a\nbü\n");
+        assert_eq!(line_map.line_count(), 2);
+        assert_eq!(line_map.line_col(6), (1, 3));
+
+        // Empty source
+        let line_map = LineMap::new("");
+        assert_eq!(line_map.line_col(0), (0, 0));
+    }
+}
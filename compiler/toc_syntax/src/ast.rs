@@ -16,6 +16,7 @@ macro_rules! match_ast {
 
 #[rustfmt::skip] // formatted during codegen
 mod nodes;
+pub mod edit;
 mod nodes_ext;
 
 use super::SyntaxNode;
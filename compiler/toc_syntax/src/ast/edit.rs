@@ -0,0 +1,335 @@
+//! Editing of syntax trees, for building refactoring tools and quick fixes
+//!
+//! Syntax trees are immutable, so edits are recorded in a [`SyntaxEditor`]
+//! and then applied all at once, giving back both the edited tree and the
+//! equivalent text edits.
+use rowan::{GreenNode, GreenToken, NodeOrToken};
+use toc_span::TextRange;
+
+use crate::{SyntaxElement, SyntaxNode};
+
+type GreenElement = NodeOrToken<GreenNode, GreenToken>;
+
+/// Builders for new nodes and tokens
+pub mod make {
+    use rowan::{GreenNode, GreenToken, NodeOrToken};
+
+    use crate::ast::{self, AstNode};
+    use crate::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+    /// Makes a token of the given `kind`
+    pub fn token(kind: SyntaxKind, text: &str) -> SyntaxToken {
+        // Tokens always need a parent node
+        let token = NodeOrToken::Token(GreenToken::new(kind.into(), text));
+        let node = GreenNode::new(SyntaxKind::Source.into(), vec![token]);
+
+        SyntaxNode::new_root(node).first_token().unwrap()
+    }
+
+    /// Makes a whitespace token
+    pub fn whitespace(text: &str) -> SyntaxToken {
+        token(SyntaxKind::Whitespace, text)
+    }
+
+    /// Makes a simple assignment operator (`:=`)
+    pub fn assign_op() -> ast::AsnOp {
+        let node = GreenNode::new(
+            SyntaxKind::AsnOp.into(),
+            vec![NodeOrToken::Token(GreenToken::new(
+                SyntaxKind::Assign.into(),
+                ":=",
+            ))],
+        );
+
+        ast::AsnOp::cast(SyntaxNode::new_root(node)).unwrap()
+    }
+
+    /// Makes the end of a block group, with `tail` following the `end`
+    /// (e.g. `end_group("if")` makes `end if`)
+    pub fn end_group(tail: &str) -> ast::EndGroup {
+        let tail_kind = match tail {
+            "case" => SyntaxKind::KwCase,
+            "for" => SyntaxKind::KwFor,
+            "if" => SyntaxKind::KwIf,
+            "loop" => SyntaxKind::KwLoop,
+            _ => SyntaxKind::Identifier,
+        };
+
+        let node = GreenNode::new(
+            SyntaxKind::EndGroup.into(),
+            vec![
+                NodeOrToken::Token(GreenToken::new(SyntaxKind::KwEnd.into(), "end")),
+                NodeOrToken::Token(GreenToken::new(SyntaxKind::Whitespace.into(), " ")),
+                NodeOrToken::Token(GreenToken::new(tail_kind.into(), tail)),
+            ],
+        );
+
+        ast::EndGroup::cast(SyntaxNode::new_root(node)).unwrap()
+    }
+}
+
+/// An edit to the text of a file, replacing the text in `range` with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub replacement: String,
+}
+
+/// Applies `edits` to `text`.
+///
+/// Edits must not overlap, and must be sorted by their range, like the ones
+/// given back from [`SyntaxEditor::finish`].
+pub fn apply_edits(text: &str, edits: &[TextEdit]) -> String {
+    let mut edited = String::with_capacity(text.len());
+    let mut offset = 0;
+
+    for edit in edits {
+        let (start, end) = (
+            usize::from(edit.range.start()),
+            usize::from(edit.range.end()),
+        );
+        assert!(offset <= start, "text edits overlap or are out of order");
+
+        edited.push_str(&text[offset..start]);
+        edited.push_str(&edit.replacement);
+        offset = end;
+    }
+
+    edited.push_str(&text[offset..]);
+    edited
+}
+
+/// The result of applying all of the edits in a [`SyntaxEditor`]
+#[derive(Debug, Clone)]
+pub struct EditedTree {
+    /// The edited tree
+    pub green: GreenNode,
+    /// Text edits turning the original text into the edited tree's text
+    pub text_edits: Vec<TextEdit>,
+}
+
+impl EditedTree {
+    pub fn syntax(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green.clone())
+    }
+}
+
+/// Records edits made to elements in a tree
+#[derive(Default)]
+struct ElementEdits {
+    before: Vec<GreenElement>,
+    replacement: Option<Vec<GreenElement>>,
+    after: Vec<GreenElement>,
+}
+
+/// Records replacements, insertions, and deletions of elements in a syntax tree.
+///
+/// Edited elements must not be inside of any replaced or deleted elements.
+pub struct SyntaxEditor {
+    root: SyntaxNode,
+    edits: Vec<(SyntaxElement, ElementEdits)>,
+}
+
+impl SyntaxEditor {
+    /// Creates an editor for the tree starting at `root`
+    pub fn new(root: SyntaxNode) -> Self {
+        Self {
+            root,
+            edits: vec![],
+        }
+    }
+
+    /// Replaces `element` with `with`
+    pub fn replace(&mut self, element: impl Into<SyntaxElement>, with: impl Into<SyntaxElement>) {
+        self.replace_with_many(element, vec![with.into()])
+    }
+
+    /// Replaces `element` with all of the elements in `with`
+    pub fn replace_with_many(
+        &mut self,
+        element: impl Into<SyntaxElement>,
+        with: Vec<SyntaxElement>,
+    ) {
+        let edits = self.edits_for(element.into());
+        assert!(edits.replacement.is_none(), "element is already replaced");
+
+        edits.replacement = Some(with.into_iter().map(to_green).collect());
+    }
+
+    /// Deletes `element` from the tree
+    pub fn delete(&mut self, element: impl Into<SyntaxElement>) {
+        self.replace_with_many(element, vec![])
+    }
+
+    /// Inserts `new` right before `element`
+    pub fn insert_before(
+        &mut self,
+        element: impl Into<SyntaxElement>,
+        new: impl Into<SyntaxElement>,
+    ) {
+        let new = to_green(new.into());
+        self.edits_for(element.into()).before.push(new);
+    }
+
+    /// Inserts `new` right after `element`
+    pub fn insert_after(
+        &mut self,
+        element: impl Into<SyntaxElement>,
+        new: impl Into<SyntaxElement>,
+    ) {
+        let new = to_green(new.into());
+        self.edits_for(element.into()).after.push(new);
+    }
+
+    fn edits_for(&mut self, element: SyntaxElement) -> &mut ElementEdits {
+        assert!(
+            element.ancestors().any(|node| node == self.root),
+            "element is not in the edited tree"
+        );
+        assert!(
+            element.as_node() != Some(&self.root),
+            "the root can't be edited"
+        );
+
+        let idx = match self.edits.iter().position(|(other, _)| *other == element) {
+            Some(idx) => idx,
+            None => {
+                self.edits.push((element, ElementEdits::default()));
+                self.edits.len() - 1
+            }
+        };
+
+        &mut self.edits[idx].1
+    }
+
+    /// Applies all of the recorded edits, giving back the edited tree and
+    /// the text edits making the same changes.
+    ///
+    /// Text edits are sorted by their range, and insertions at the same
+    /// position are in the order that they were recorded.
+    pub fn finish(self) -> EditedTree {
+        for (element, _) in &self.edits {
+            let ancestors = element
+                .parent()
+                .into_iter()
+                .flat_map(|parent| parent.ancestors());
+            let is_inside_replaced = ancestors.into_iter().any(|ancestor| {
+                self.edits.iter().any(|(other, edits)| {
+                    edits.replacement.is_some() && other.as_node() == Some(&ancestor)
+                })
+            });
+            assert!(
+                !is_inside_replaced,
+                "edited element is inside of a replaced element"
+            );
+        }
+
+        let green = self.rebuild_node(&self.root);
+        let text_edits = self.text_edits();
+
+        EditedTree { green, text_edits }
+    }
+
+    fn rebuild_node(&self, node: &SyntaxNode) -> GreenNode {
+        let mut children = vec![];
+
+        for child in node.children_with_tokens() {
+            let edits = self
+                .edits
+                .iter()
+                .find(|(element, _)| *element == child)
+                .map(|(_, edits)| edits);
+
+            if let Some(edits) = edits {
+                children.extend(edits.before.iter().cloned());
+            }
+
+            match edits.and_then(|edits| edits.replacement.as_ref()) {
+                Some(replacement) => children.extend(replacement.iter().cloned()),
+                None => children.push(match &child {
+                    NodeOrToken::Node(child) if self.has_edits_inside(child) => {
+                        NodeOrToken::Node(self.rebuild_node(child))
+                    }
+                    child => to_green(child.clone()),
+                }),
+            }
+
+            if let Some(edits) = edits {
+                children.extend(edits.after.iter().cloned());
+            }
+        }
+
+        GreenNode::new(node.green().kind(), children)
+    }
+
+    fn has_edits_inside(&self, node: &SyntaxNode) -> bool {
+        self.edits
+            .iter()
+            .any(|(element, _)| element.ancestors().any(|ancestor| ancestor == *node))
+    }
+
+    fn text_edits(&self) -> Vec<TextEdit> {
+        let mut text_edits = vec![];
+        let mut push_edit = |range: TextRange, elements: &[GreenElement]| {
+            text_edits.push(TextEdit {
+                range,
+                replacement: elements.iter().map(green_text).collect(),
+            });
+        };
+
+        for (element, edits) in &self.edits {
+            let range = element.text_range();
+
+            match &edits.replacement {
+                Some(replacement) => {
+                    // Surrounding insertions become part of the replacement
+                    let elements: Vec<_> = edits
+                        .before
+                        .iter()
+                        .chain(replacement)
+                        .chain(&edits.after)
+                        .cloned()
+                        .collect();
+                    push_edit(range, &elements);
+                }
+                None => {
+                    if !edits.before.is_empty() {
+                        push_edit(TextRange::empty(range.start()), &edits.before);
+                    }
+                    if !edits.after.is_empty() {
+                        push_edit(TextRange::empty(range.end()), &edits.after);
+                    }
+                }
+            }
+        }
+
+        // Stable sort, so that insertions at the same position keep their order
+        text_edits.sort_by_key(|edit| (edit.range.start(), edit.range.end()));
+
+        // Merge insertions at the same position, so that the order is unambiguous
+        text_edits.dedup_by(|next, prev| {
+            if prev.range == next.range && next.range.is_empty() {
+                prev.replacement.push_str(&next.replacement);
+                true
+            } else {
+                false
+            }
+        });
+
+        text_edits
+    }
+}
+
+fn to_green(element: SyntaxElement) -> GreenElement {
+    match element {
+        NodeOrToken::Node(node) => NodeOrToken::Node(node.green().to_owned()),
+        NodeOrToken::Token(token) => NodeOrToken::Token(token.green().clone()),
+    }
+}
+
+fn green_text(element: &GreenElement) -> String {
+    match element {
+        NodeOrToken::Node(node) => SyntaxNode::new_root(node.clone()).text().to_string(),
+        NodeOrToken::Token(token) => token.text().to_string(),
+    }
+}
@@ -2383,7 +2383,6 @@ impl AstNode for ForBounds {
 impl ForBounds {
     pub fn from(&self) -> Option<Expr> { helper::node(&self.0) }
     pub fn range_token(&self) -> Option<SyntaxToken> { helper::token(&self.0, SyntaxKind::Range) }
-    pub fn to(&self) -> Option<Expr> { helper::node(&self.0) }
 }
 #[derive(Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -3393,7 +3392,7 @@ impl AstNode for EnumType {
 impl EnumType {
     pub fn enum_token(&self) -> Option<SyntaxToken> { helper::token(&self.0, SyntaxKind::KwEnum) }
     pub fn l_paren_token(&self) -> Option<SyntaxToken> { helper::token(&self.0, SyntaxKind::LeftParen) }
-    pub fn fields(&self) -> impl Iterator<Item = Name> + '_ { helper::nodes(&self.0) }
+    pub fn fields(&self) -> Option<NameList> { helper::node(&self.0) }
     pub fn r_paren_token(&self) -> Option<SyntaxToken> { helper::token(&self.0, SyntaxKind::RightParen) }
 }
 #[derive(Debug, PartialEq, Eq, Hash)]
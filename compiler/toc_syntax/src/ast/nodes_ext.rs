@@ -1,11 +1,9 @@
 //! Extensions to the generated nodes
-use std::iter;
-
 use super::nodes::*;
 use crate::ast::{helper, AstNode};
 use crate::{
-    AssignOp, CharSeqParseError, InfixOp, IoKind, LiteralParseError, LiteralValue, PrefixOp,
-    PrimitiveKind, SyntaxElement, SyntaxKind, SyntaxToken,
+    AssignOp, InfixOp, IoKind, LiteralParseError, LiteralValue, PrefixOp, PrimitiveKind,
+    SyntaxElement, SyntaxKind, SyntaxToken,
 };
 
 impl PPBinaryExpr {
@@ -132,12 +130,20 @@ impl LiteralExpr {
     pub fn literal(&self) -> Option<(LiteralValue, Option<Vec<LiteralParseError>>)> {
         let literal = self.syntax().first_token()?;
 
-        match literal.kind() {
-            SyntaxKind::IntLiteral => Some(Self::parse_int_literal(literal.text())),
-            SyntaxKind::RadixLiteral => Some(Self::parse_radix_literal(literal.text())),
-            SyntaxKind::RealLiteral => Some(Self::parse_real_literal(literal.text())),
-            SyntaxKind::CharLiteral => Some(Self::parse_char_seq_literal(literal.text(), false)),
-            SyntaxKind::StringLiteral => Some(Self::parse_char_seq_literal(literal.text(), true)),
+        Self::parse_literal(literal.kind(), literal.text())
+    }
+
+    /// Parses the text of a literal token of the given kind
+    pub fn parse_literal(
+        kind: SyntaxKind,
+        text: &str,
+    ) -> Option<(LiteralValue, Option<Vec<LiteralParseError>>)> {
+        match kind {
+            SyntaxKind::IntLiteral => Some(Self::parse_int_literal(text)),
+            SyntaxKind::RadixLiteral => Some(Self::parse_radix_literal(text)),
+            SyntaxKind::RealLiteral => Some(Self::parse_real_literal(text)),
+            SyntaxKind::CharLiteral => Some(Self::parse_char_seq_literal(text, false)),
+            SyntaxKind::StringLiteral => Some(Self::parse_char_seq_literal(text, true)),
             SyntaxKind::KwTrue => Some((LiteralValue::Boolean(true), None)),
             SyntaxKind::KwFalse => Some((LiteralValue::Boolean(false), None)),
             _ => None,
@@ -230,371 +236,23 @@ impl LiteralExpr {
         text: &str,
         as_str_literal: bool,
     ) -> (LiteralValue, Option<Vec<LiteralParseError>>) {
-        let ending_delimiter = if as_str_literal { '"' } else { '\'' };
-        let (inner_text, errors) = CharSeqExtractor::extract(text, ending_delimiter);
-
-        // Don't need to report a missing terminator, since that's already reported by the scanner
-
-        if as_str_literal {
-            (LiteralValue::String(inner_text), errors)
-        } else if !inner_text.is_empty() {
-            (LiteralValue::Char(inner_text), errors)
-        } else {
-            // Zero-length char-seq string
-            // Shouldn't be any previous errors
-            assert!(errors.is_none());
-
-            // Still pass off the empty inner text
-            let err = LiteralParseError::CharError(CharSeqParseError::EmptySequence, 0, 1);
-            (LiteralValue::Char(inner_text), Some(vec![err]))
-        }
-    }
-}
-
-/// Extractor for char sequences
-struct CharSeqExtractor<'a> {
-    /// Source text to extract from
-    text: &'a str,
-    /// Character source
-    char_indices: iter::Peekable<std::str::CharIndices<'a>>,
-    /// Extracted string
-    extracted_text: String,
-    /// Any errors encountered during processing
-    errors: Vec<LiteralParseError>,
-    /// Ending delimiter to stop at
-    ending_delimiter: char,
-
-    /// Current character
-    current: Option<(usize, char)>,
-}
-
-impl<'a> CharSeqExtractor<'a> {
-    /// Extracts the char sequence's text, applying character escapes
-    fn extract(text: &'a str, ending_delimiter: char) -> (String, Option<Vec<LiteralParseError>>) {
-        let mut char_indices = text.char_indices().peekable();
-        // Skip over starting delimiter
-        char_indices.next();
-
-        let mut extractor = Self {
-            text,
-            char_indices,
-            errors: vec![],
-            extracted_text: String::with_capacity(256),
-            ending_delimiter,
-            current: None,
-        };
-
-        // Do the extraction
-        extractor.do_extraction();
-
-        let Self {
-            extracted_text,
-            errors,
-            ..
-        } = extractor;
-        let has_errors = !errors.is_empty();
-
-        (extracted_text, Some(errors).filter(|_| has_errors))
-    }
-
-    fn do_extraction(&mut self) {
-        // Keep going along the string until there's no more chars, or until the end delimiter is reached
-        while let Some(current) = self.bump() {
-            match current {
-                '\\' => self.eat_slash_escape(),
-                '^' => self.eat_caret_escape(),
-                _ => {
-                    if current == self.ending_delimiter {
-                        // At the ending delimiter, stop
-                        return;
-                    } else {
-                        // Append character
-                        self.push(current);
-                    }
-                }
-            }
-        }
-
-        // Reached the end of the inner text, but no terminator was encountered
-        self.push_error(CharSeqParseError::UnterminatedLiteral, 0, self.text.len());
-    }
-
-    /// Advances the char cursor by one, returning the current char
-    fn bump(&mut self) -> Option<char> {
-        self.current = self.char_indices.next();
-        Some(self.current?.1)
-    }
-
-    /// Gets the offset of the current char, relative to the start of the source string
-    fn current_pos(&self) -> usize {
-        self.current
-            .map(|(pos, _)| pos)
-            .unwrap_or_else(|| self.text.len())
-    }
-
-    /// Gets the offset of the next char, relative to the start of the source string
-    fn peek_pos(&mut self) -> usize {
-        self.char_indices
-            .peek()
-            .map(|(pos, _)| *pos)
-            .unwrap_or_else(|| self.text.len())
-    }
-
-    /// Peeks at the next char after the cursor
-    fn peek(&mut self) -> Option<char> {
-        self.char_indices.peek().map(|(_, c)| *c)
-    }
-
-    /// Appends a character to the extracted string
-    fn push(&mut self, chr: char) {
-        self.extracted_text.push(chr);
-    }
-
-    /// Appends an error to the error list
-    fn push_error(&mut self, error: CharSeqParseError, start: usize, end: usize) {
-        let as_str_literal = self.ending_delimiter == '"';
-
-        if as_str_literal {
-            self.errors
-                .push(LiteralParseError::StringError(error, start, end))
-        } else {
-            self.errors
-                .push(LiteralParseError::CharError(error, start, end))
-        }
-    }
-
-    /// Eats all variants of the slash escape
-    fn eat_slash_escape(&mut self) {
-        use std::char;
-        use std::convert::TryFrom;
-
-        let escape_start = self.current_pos();
-
-        let escaped = if let Some(chr) = self.bump() {
-            chr
-        } else {
-            // Missing escaped character
-            let escape_end = self.peek_pos();
-            self.push_error(
-                CharSeqParseError::InvalidSlashEscape,
-                escape_start,
-                escape_end,
-            );
-
-            return;
-        };
-
-        match escaped {
-            // Simple escapes
-            '\'' => self.push('\''),
-            '"' => self.push('"'),
-            '\\' => self.push('\\'),
-            // Escaped control characters
-            'b' | 'B' => self.push('\x08'),
-            'd' | 'D' => self.push('\x7F'),
-            'e' | 'E' => self.push('\x1B'),
-            'f' | 'F' => self.push('\x0C'),
-            'r' | 'R' => self.push('\r'),
-            'n' | 'N' => self.push('\n'),
-            't' | 'T' => self.push('\t'),
-            // Escaped caret
-            '^' => self.push('^'),
-            '0'..='7' => {
-                // Octal str, {1-3}, octal 0..400
-                let digits_start = self.current_pos();
-
-                // Eat up to 2 more octal digits
-                for _ in 0..2 {
-                    if !matches!(self.peek(), Some('0'..='7')) {
-                        break;
-                    }
-
-                    // Eat it
-                    self.bump();
-                }
-
-                let digits_end = self.peek_pos();
-                let digits = &self.text[digits_start..digits_end];
-
-                // Convert to character & check if it's in range
-                // Since we're only feeding in chars that are 0..7 and only octal values up to
-                // 777 (decimal 512), parsing should be infallible
-                let chr_value = lexical::parse_radix::<u16, _>(digits, 8).unwrap();
-
-                if let Ok(chr) = u8::try_from(chr_value) {
-                    // Successful conversion, push it
-                    self.push(chr as char);
-                } else {
-                    // Out of range
-                    self.push_error(
-                        CharSeqParseError::InvalidOctalChar,
-                        escape_start,
-                        digits_end,
-                    );
-
-                    self.push(char::REPLACEMENT_CHARACTER);
-                }
-            }
-            'x' => {
-                // Hex sequence, {1-2} digits
-                let digits_start = self.peek_pos();
-
-                // Eat up to 2 more hex digits
-                for _ in 0..2 {
-                    if !self
-                        .peek()
-                        .map(|c| c.is_ascii_hexdigit())
-                        .unwrap_or_default()
-                    {
-                        break;
-                    }
-
-                    // Eat it
-                    self.bump();
-                }
-
-                let digits_end = self.peek_pos();
-                let digits = &self.text[digits_start..digits_end];
-
-                if digits.is_empty() {
-                    // Missing hex digits after position
-                    self.push_error(
-                        CharSeqParseError::MissingHexDigits,
-                        escape_start,
-                        digits_end,
-                    );
-
-                    // Push `escaped`
-                    self.push(escaped);
-
-                    return;
-                }
-
-                // Convert to character value & push it
-                // Since we're only feeding in chars that are '0'..'F' and only hex values up to
-                // `u8::MAX`, parsing should be infallible
-                let chr_value = lexical::parse_radix::<u8, _>(digits, 16).unwrap();
-                self.push(chr_value as char);
-            }
-            'u' | 'U' => {
-                // u: unicode character {1-8} `char::REPLACEMENT_CHARACTER` if out of range
-                let digits_start = self.peek_pos();
-
-                // Eat up to 8 more hex digits
-                for _ in 0..8 {
-                    if !self
-                        .peek()
-                        .map(|c| c.is_ascii_hexdigit())
-                        .unwrap_or_default()
-                    {
-                        break;
-                    }
-
-                    // Eat it
-                    self.bump();
-                }
-
-                let digits_end = self.peek_pos();
-                let digits = &self.text[digits_start..digits_end];
-
-                if digits.is_empty() {
-                    // Missing hex digits after position
-                    self.push_error(
-                        CharSeqParseError::MissingHexDigits,
-                        escape_start,
-                        digits_end,
-                    );
-
-                    // Push `escaped`
-                    self.push(escaped);
-
-                    return;
-                }
-
-                // Convert to Unicode codepoint
-                // Since we're only feeding in chars that are '0'..'F' and only hex values up to
-                // `u32::MAX`, parsing should be infallible
-                let codepoint = lexical::parse_radix::<u32, _>(digits, 16).unwrap();
-
-                // Try to convert codepoint into a `char`
-                if let Ok(chr) = char::try_from(codepoint) {
-                    // Codepoint is okay
-                    self.push(chr);
+        let (inner_text, errors) = toc_scanner::char_seq::unescape(text);
+        let errors: Vec<_> = errors
+            .into_iter()
+            .map(|(err, range)| {
+                if as_str_literal {
+                    LiteralParseError::StringError(err, range.start, range.end)
                 } else {
-                    if codepoint > 0x10FFFF {
-                        // Invalid codepoint
-                        self.push_error(
-                            CharSeqParseError::InvalidUnicodeChar,
-                            escape_start,
-                            digits_end,
-                        );
-                    } else if (0xD800..=0xDFFF).contains(&codepoint) {
-                        // Surrogate character, not allowed
-                        self.push_error(CharSeqParseError::SurrogateChar, escape_start, digits_end);
-                    }
-
-                    // Push replacement character
-                    self.push(char::REPLACEMENT_CHARACTER);
+                    LiteralParseError::CharError(err, range.start, range.end)
                 }
-            }
-            _ => {
-                // Bad escape character
-                let escape_end = self.peek_pos();
-
-                self.push_error(
-                    CharSeqParseError::InvalidSlashEscape,
-                    escape_start,
-                    escape_end,
-                );
-
-                // Push character unmodified
-                self.push(escaped);
-            }
-        }
-    }
+            })
+            .collect();
+        let errors = Some(errors).filter(|errors| !errors.is_empty());
 
-    /// Eats all variants of the caret escape
-    fn eat_caret_escape(&mut self) {
-        let escape_start = self.current_pos();
-
-        let escaped = if let Some(chr) = self.bump() {
-            chr
+        if as_str_literal {
+            (LiteralValue::String(inner_text), errors)
         } else {
-            // Missing escaped character
-            let escape_end = self.peek_pos();
-            self.push_error(
-                CharSeqParseError::InvalidCaretEscape,
-                escape_start,
-                escape_end,
-            );
-
-            return;
-        };
-
-        // Parse escaped caret character
-        match escaped {
-            '@'..='_' | 'a'..='z' => {
-                let parsed = (escaped.to_ascii_uppercase() as u8) & 0x1F;
-                self.push(parsed as char);
-            }
-            '?' => {
-                // As the DEL char
-                self.push('\x7F');
-            }
-            _ => {
-                // Unless the user knows what they are doing, they are likely to not intend for the ^ character to be
-                // parsed as the beginning of a caret sequence
-                let escape_end = self.peek_pos();
-
-                self.push_error(
-                    CharSeqParseError::InvalidCaretEscape,
-                    escape_start,
-                    escape_end,
-                );
-
-                // Push escaped
-                self.push(escaped);
-            }
+            (LiteralValue::Char(inner_text), errors)
         }
     }
 }
@@ -674,6 +332,30 @@ impl PutItem {
     }
 }
 
+impl ForStmt {
+    /// Gets the start and optional end bounds of the for-loop
+    pub fn bounds(&self) -> Option<(Expr, Option<Expr>)> {
+        let bounds = self.for_bounds()?;
+        Some((bounds.from()?, bounds.to()))
+    }
+}
+
+impl ForBounds {
+    pub fn to(&self) -> Option<Expr> {
+        helper::nodes(self.syntax()).nth(1)
+    }
+}
+
+impl CaseArm {
+    /// Gets the selector expressions of the arm.
+    /// Empty for the default arm
+    pub fn exprs(&self) -> impl Iterator<Item = Expr> {
+        self.select()
+            .into_iter()
+            .flat_map(|select| select.syntax().children().filter_map(Expr::cast))
+    }
+}
+
 impl TagStmt {
     pub fn tag_ref(&self) -> Option<Expr> {
         helper::nodes(self.syntax()).next()
@@ -741,3 +423,9 @@ impl RangeType {
         helper::nodes(self.syntax()).nth(1)
     }
 }
+
+impl RangeItem {
+    pub fn end(&self) -> Option<RangeBound> {
+        helper::nodes(self.syntax()).nth(1)
+    }
+}
@@ -5,6 +5,7 @@ pub mod ast;
 
 use num_traits::{FromPrimitive, ToPrimitive};
 use rowan::Language;
+pub use toc_scanner::char_seq::CharSeqError;
 
 #[macro_use]
 extern crate num_derive;
@@ -380,6 +381,11 @@ impl From<SyntaxKind> for rowan::SyntaxKind {
 }
 
 impl SyntaxKind {
+    /// If the kind is a keyword, including preprocessor keywords
+    pub fn is_keyword(&self) -> bool {
+        (SyntaxKind::KwAddressint..=SyntaxKind::PPKwEndIf).contains(self)
+    }
+
     fn is_binary_op(&self) -> bool {
         matches!(
             self,
@@ -439,6 +445,25 @@ pub type SyntaxToken = rowan::SyntaxToken<Lang>;
 #[allow(unused)]
 pub type SyntaxElement = rowan::NodeOrToken<SyntaxNode, SyntaxToken>;
 
+/// Prints a green tree back into the source text that it was built from.
+///
+/// Whitespace and comments are kept around as tokens in the tree, so the text of
+/// a parsed tree is always the same as the parsed source, byte for byte.
+pub fn reprint(node: &rowan::GreenNodeData) -> String {
+    fn print_node(node: &rowan::GreenNodeData, out: &mut String) {
+        for child in node.children() {
+            match child {
+                rowan::NodeOrToken::Node(node) => print_node(node, out),
+                rowan::NodeOrToken::Token(token) => out.push_str(token.text()),
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(u32::from(node.text_len()) as usize);
+    print_node(node, &mut out);
+    out
+}
+
 // Operators
 
 pub const MIN_REF_BINDING_POWER: u8 = 19;
@@ -665,9 +690,9 @@ pub enum LiteralParseError {
     IntInvalid,
     // Char Sequences
     #[error("invalid string literal: {0:}")]
-    StringError(CharSeqParseError, usize, usize),
+    StringError(CharSeqError, usize, usize),
     #[error("invalid char literal: {0:}")]
-    CharError(CharSeqParseError, usize, usize),
+    CharError(CharSeqError, usize, usize),
 }
 
 impl LiteralParseError {
@@ -699,26 +724,14 @@ impl LiteralParseError {
 
         (report_span, self)
     }
-}
 
-#[derive(Debug, thiserror::Error)]
-pub enum CharSeqParseError {
-    #[error("octal character value is greater than \\377 (decimal 255)")]
-    InvalidOctalChar,
-    #[error("unicode codepoint value is greater than U+10FFFF")]
-    InvalidUnicodeChar,
-    #[error("surrogate chars are not allowed in char sequences")]
-    SurrogateChar,
-    #[error("missing hex digits after here")]
-    MissingHexDigits,
-    #[error("unknown backslash escape")]
-    InvalidSlashEscape,
-    #[error("unknown caret escape")]
-    InvalidCaretEscape,
-    #[error("missing terminator character")]
-    UnterminatedLiteral,
-    #[error("no characters in literal")]
-    EmptySequence,
+    /// If the error has already been reported while scanning the literal
+    pub fn is_scanner_reported(&self) -> bool {
+        matches!(
+            self,
+            LiteralParseError::StringError(..) | LiteralParseError::CharError(..)
+        )
+    }
 }
 
 #[derive(Debug)]
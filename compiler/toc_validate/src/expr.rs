@@ -0,0 +1,32 @@
+//! Validation for expressions
+#[cfg(test)]
+mod test;
+
+use toc_syntax::ast::{self, AstNode};
+
+use crate::ValidateCtx;
+
+pub(super) fn validate_indirect_expr(expr: ast::IndirectExpr, ctx: &mut ValidateCtx) {
+    // Any primary expression is parsed in place of the type name
+    let lhs = match expr.indirect_ty() {
+        Some(ast::IndirectTy::NameType(ty)) => ty.expr(),
+        _ => return,
+    };
+
+    if let Some(lhs) = lhs {
+        if !is_type_name(&lhs) {
+            ctx.push_detailed_error("invalid indirection type", lhs.syntax().text_range())
+                .with_info("expected a primitive type or a type name", None)
+                .finish();
+        }
+    }
+}
+
+/// Checks if `expr` could refer to a type, either by a plain name or a path into a module
+fn is_type_name(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::NameExpr(_) => true,
+        ast::Expr::FieldExpr(expr) => matches!(expr.expr(), Some(lhs) if is_type_name(&lhs)),
+        _ => false,
+    }
+}
@@ -0,0 +1,32 @@
+//! Expr validation tests
+use crate::check;
+use expect_test::expect;
+
+#[test]
+fn indirect_type_names() {
+    check("var a := int @ (16#A000)", expect![[""]]);
+    check("var a := t @ (16#A000)", expect![[""]]);
+    check("var a := m.t @ (16#A000)", expect![[""]]);
+}
+
+#[test]
+fn report_indirect_non_type() {
+    check(
+        "var a := 1 @ (16#A000)",
+        expect![[r#"
+        error at 9..10: invalid indirection type
+        | info: expected a primitive type or a type name"#]],
+    );
+    check(
+        "var a := (t) @ (16#A000)",
+        expect![[r#"
+        error at 9..12: invalid indirection type
+        | info: expected a primitive type or a type name"#]],
+    );
+    check(
+        "var a := f(1) @ (16#A000)",
+        expect![[r#"
+        error at 9..13: invalid indirection type
+        | info: expected a primitive type or a type name"#]],
+    );
+}
@@ -1,12 +1,13 @@
 //! AST validation
 //! Checking if things hold up to stricter syntax semantics
 // fancy quotes: ‘’
+mod expr;
 mod preproc;
 mod stmt;
 #[cfg(test)]
 mod test;
 
-use toc_reporting::{MessageBuilder, MessageSink, ReportMessage};
+use toc_reporting::{MessageBuilder, MessageSink, ReportMessage, Stage};
 use toc_span::{FileId, Span, TextRange};
 use toc_syntax::{
     ast::{self, AstNode},
@@ -24,9 +25,10 @@ impl ValidateResult {
 }
 
 pub fn validate_ast(file: Option<FileId>, root: SyntaxNode) -> ValidateResult {
+    let bounds = Span::new(file, root.text_range());
     let mut ctx = ValidateCtx {
         file,
-        sink: MessageSink::new(),
+        sink: MessageSink::new(Stage::Validate).with_source_bounds(bounds),
     };
 
     if let Some(source) = ast::Source::cast(root) {
@@ -105,6 +107,7 @@ fn validate_source(src: ast::Source, ctx: &mut ValidateCtx) {
             ast::ProcDecl(decl) =>
                 stmt::validate_in_top_level(decl.syntax(), "‘procedure’ declaration", ctx),
             ast::ProcHeader(node) => stmt::validate_proc_header(node, ctx),
+            ast::ParamSpec(spec) => stmt::validate_param_spec(spec, ctx),
             ast::FcnDecl(decl) =>
                 stmt::validate_in_top_level(decl.syntax(), "‘function’ declaration", ctx),
             ast::ProcessDecl(decl) => stmt::validate_process_decl(decl, ctx),
@@ -114,6 +117,7 @@ fn validate_source(src: ast::Source, ctx: &mut ValidateCtx) {
             ast::DeferredDecl(decl) => stmt::validate_deferred_decl(decl, ctx),
             ast::BodyDecl(decl) =>
                 stmt::validate_in_top_level(decl.syntax(), "‘body’ declaration", ctx),
+            ast::StmtList(list) => stmt::validate_subprog_bodies(list, ctx),
             ast::ModuleDecl(decl) => stmt::validate_module_decl(decl, ctx),
             ast::ClassDecl(decl) => stmt::validate_class_decl(decl, ctx),
             ast::MonitorDecl(decl) => stmt::validate_monitor_decl(decl, ctx),
@@ -123,6 +127,14 @@ fn validate_source(src: ast::Source, ctx: &mut ValidateCtx) {
             ast::ElseifStmt(stmt) => stmt::validate_elseif_stmt(stmt, ctx),
             ast::CaseStmt(stmt) => stmt::validate_case_stmt(stmt, ctx),
             ast::InvariantStmt(stmt) => stmt::validate_invariant_stmt(stmt, ctx),
+            ast::ExportStmt(stmt) => stmt::validate_export_stmt(stmt, ctx),
+            ast::EnumType(ty) => stmt::validate_enum_type(ty, ctx),
+            ast::ConditionType(ty) => stmt::validate_condition_type(ty, ctx),
+            ast::WaitStmt(stmt) => stmt::validate_wait_stmt(stmt, ctx),
+            ast::GetItem(item) => stmt::validate_get_item(item, ctx),
+            ast::ReturnStmt(stmt) => stmt::validate_return_stmt(stmt, ctx),
+            ast::ResultStmt(stmt) => stmt::validate_result_stmt(stmt, ctx),
+            ast::IndirectExpr(expr) => expr::validate_indirect_expr(expr, ctx),
             _ => (),
         })
     }
@@ -2,17 +2,30 @@
 #[cfg(test)]
 mod test;
 
-use toc_span::Span;
+use std::convert::TryFrom;
+
+use toc_span::{Span, TextRange};
 use toc_syntax::{
     ast::{self, AstNode},
-    SyntaxKind,
+    match_ast, SyntaxKind,
 };
-use toc_syntax::{IoKind, SyntaxNode};
+use toc_syntax::{IoKind, SyntaxNode, SyntaxToken};
 
 use crate::{block_containing_node, walk_blocks, without_matching};
 use crate::{BlockKind, ValidateCtx};
 
 pub(super) fn validate_constvar_decl(decl: ast::ConstVarDecl, ctx: &mut ValidateCtx) {
+    if let Some(names) = decl.decl_list() {
+        let what = if decl.const_token().is_some() {
+            "constant"
+        } else {
+            "variable"
+        };
+
+        let names = names.names().filter_map(|name| name.identifier_token());
+        check_duplicate_names(names, what, ctx);
+    }
+
     if let Some(attr) = decl.register_attr() {
         // 'register' attr is not allowed in top-level blocks:
 
@@ -60,6 +73,8 @@ pub(super) fn validate_constvar_decl(decl: ast::ConstVarDecl, ctx: &mut Validate
                     None,
                 )
                 .finish();
+            } else {
+                validate_init_shape(&init_expr, &ty, ctx);
             }
         } else {
             // No type spec, never allowed
@@ -121,6 +136,127 @@ pub(super) fn validate_constvar_decl(decl: ast::ConstVarDecl, ctx: &mut Validate
     }
 }
 
+/// Checks that the elements of an `init` initializer match up with the composite type `ty`,
+/// recursing into any nested `init` groups
+fn validate_init_shape(init_expr: &ast::InitExpr, ty: &ast::Type, ctx: &mut ValidateCtx) {
+    let elems: Vec<_> = init_expr
+        .expr_list()
+        .map(|list| list.exprs().collect())
+        .unwrap_or_default();
+
+    // Type of each element in the initializer, as well as the expected element count
+    let (elem_tys, expected_len, type_kind, elem_kind) = match ty {
+        ast::Type::ArrayType(array_ty) => {
+            let len = array_ty.range_list().and_then(|ranges| {
+                // Multi-dimensional arrays are initialized in a flattened order
+                ranges
+                    .ranges()
+                    .map(|range| known_range_len(&range))
+                    .try_fold(1_usize, |total, len| total.checked_mul(len?))
+            });
+
+            let elem_tys = elems.iter().map(|_| array_ty.elem_ty()).collect();
+
+            (elem_tys, len, "array", "elements")
+        }
+        ast::Type::RecordType(record_ty) => {
+            let field_tys: Vec<_> = record_ty
+                .record_field()
+                .flat_map(|field| {
+                    let names = field
+                        .field_names()
+                        .map(|names| names.names().count())
+                        .unwrap_or_default();
+
+                    (0..names).map(move |_| field.field_ty())
+                })
+                .collect();
+            let len = field_tys.len();
+
+            (field_tys, Some(len), "record", "fields")
+        }
+        // TODO: Check union initializers once the variant can be selected from the tag value
+        _ => return,
+    };
+
+    if let Some(expected_len) = expected_len {
+        if elems.len() != expected_len {
+            let span = Span::new(ctx.file, ty.syntax().text_range());
+
+            ctx.push_detailed_error(
+                &format!(
+                    "expected {} {} in ‘init’ initializer, found {}",
+                    expected_len,
+                    elem_kind,
+                    elems.len()
+                ),
+                init_expr.syntax().text_range(),
+            )
+            .with_note(
+                &format!("this {} type has {} {}", type_kind, expected_len, elem_kind),
+                span,
+            )
+            .finish();
+        }
+    }
+
+    // Match up nested groups with their corresponding element types
+    for (elem, elem_ty) in elems.iter().zip(elem_tys) {
+        let (elem, elem_ty) = match (elem, elem_ty) {
+            (ast::Expr::InitExpr(elem), Some(elem_ty)) => (elem, elem_ty),
+            _ => continue,
+        };
+
+        match &elem_ty {
+            ast::Type::ArrayType(_) | ast::Type::RecordType(_) | ast::Type::UnionType(_) => {
+                validate_init_shape(elem, &elem_ty, ctx)
+            }
+            // Can't see through type aliases yet
+            ast::Type::NameType(_) => {}
+            _ => {
+                let span = Span::new(ctx.file, elem_ty.syntax().text_range());
+
+                ctx.push_detailed_error(
+                    "‘init’ initializer is not allowed here",
+                    elem.syntax().text_range(),
+                )
+                .with_note("cannot use ‘init’ initializer with this type", span)
+                .with_info(
+                    "‘init’ initializer can only be used with array, record, or union types",
+                    None,
+                )
+                .finish();
+            }
+        }
+    }
+}
+
+/// Gets the number of elements in a range type, if both bounds are integer literals
+fn known_range_len(range: &ast::Type) -> Option<usize> {
+    let range_ty = match range {
+        ast::Type::RangeType(range_ty) => range_ty,
+        _ => return None,
+    };
+
+    let int_literal = |expr: ast::Expr| match expr {
+        ast::Expr::LiteralExpr(literal) => match literal.literal()? {
+            (toc_syntax::LiteralValue::Int(value), None) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let start = int_literal(range_ty.begin()?)?;
+    let end = match range_ty.end()? {
+        ast::EndBound::Expr(end) => int_literal(end)?,
+        // Unbounded ranges get their size from the initializer
+        ast::EndBound::UnsizedBound(_) => return None,
+    };
+
+    let len = end.checked_sub(start)?.checked_add(1)?;
+    usize::try_from(len).ok()
+}
+
 pub(super) fn validate_bind_decl(decl: ast::BindDecl, ctx: &mut ValidateCtx) {
     if block_containing_node(decl.syntax()).is_top_level() {
         ctx.push_error(
@@ -405,6 +541,65 @@ pub(super) fn validate_case_stmt(stmt: ast::CaseStmt, ctx: &mut ValidateCtx) {
     }
 }
 
+pub(super) fn validate_get_item(item: ast::GetItem, ctx: &mut ValidateCtx) {
+    if let (Some(_), Some(width)) = (item.skip_token(), item.get_width()) {
+        ctx.push_error("‘skip’ cannot have a width", width.syntax().text_range());
+    }
+}
+
+pub(super) fn validate_return_stmt(stmt: ast::ReturnStmt, ctx: &mut ValidateCtx) {
+    // The result name holds the value to give back, so functions without one can't `return`
+    if let Some(false) = in_fcn_with_result_name(stmt.syntax()) {
+        ctx.push_detailed_error(
+            "‘return’ is not allowed in a function without a named result",
+            stmt.syntax().text_range(),
+        )
+        .with_info("use ‘result’ to give back a value instead", None)
+        .finish();
+    }
+}
+
+pub(super) fn validate_result_stmt(stmt: ast::ResultStmt, ctx: &mut ValidateCtx) {
+    let in_process = walk_blocks(stmt.syntax())
+        .find(|kind| !matches!(kind, BlockKind::Loop | BlockKind::Inner))
+        .is_some_and(|kind| kind == BlockKind::Process);
+
+    if in_process {
+        ctx.push_detailed_error(
+            "‘result’ is not allowed in a process",
+            stmt.syntax().text_range(),
+        )
+        .with_info("processes don't give back a value", None)
+        .finish();
+    }
+}
+
+/// Checks if `node` is inside of a function with a named result,
+/// or `None` if it isn't known to be inside of a function
+fn in_fcn_with_result_name(node: &SyntaxNode) -> Option<bool> {
+    let has_name = |result: Option<ast::FcnResult>| result?.name();
+
+    for node in node.ancestors() {
+        match_ast!(match node {
+            ast::FcnDecl(decl) => return Some(has_name(decl.fcn_header()?.fcn_result()).is_some()),
+            ast::BodyDecl(decl) =>
+                return match decl.body_kind()? {
+                    ast::BodyKind::FcnHeader(header) =>
+                        Some(has_name(header.fcn_result()).is_some()),
+                    // Plain bodies without a result header could be for either kind of subprogram
+                    ast::BodyKind::PlainHeader(header) =>
+                        header.fcn_result().map(|result| result.name().is_some()),
+                    ast::BodyKind::ProcHeader(_) => None,
+                },
+            ast::ProcDecl(_decl) => return None,
+            ast::ProcessDecl(_decl) => return None,
+            _ => (),
+        })
+    }
+
+    None
+}
+
 pub(super) fn validate_invariant_stmt(stmt: ast::InvariantStmt, ctx: &mut ValidateCtx) {
     let kind = block_containing_node(stmt.syntax());
     if kind != BlockKind::Loop && !kind.is_module_kind() {
@@ -433,6 +628,199 @@ pub(super) fn validate_in_top_level(node: &SyntaxNode, kind: &str, ctx: &mut Val
     }
 }
 
+/// Pairs up each `body` declaration in `stmt_list` with a preceding `forward`
+/// or `deferred` declaration of the same name, and checks that their signatures match
+pub(super) fn validate_subprog_bodies(stmt_list: ast::StmtList, ctx: &mut ValidateCtx) {
+    // Misplaced declarations are already reported
+    if !block_containing_node(stmt_list.syntax()).is_top_level() {
+        return;
+    }
+
+    // Bodies may also be for subprograms from other modules or classes,
+    // which are attached to either the declaration or the stmt list
+    let block_nodes = stmt_list
+        .syntax()
+        .parent()
+        .into_iter()
+        .flat_map(|parent| parent.children())
+        .chain(stmt_list.syntax().children());
+    let may_inherit_decls = block_nodes.map(|node| node.kind()).any(|kind| {
+        matches!(
+            kind,
+            SyntaxKind::InheritStmt | SyntaxKind::ImplementStmt | SyntaxKind::ImplementByStmt
+        )
+    });
+
+    // (header, is_forward, existing body)
+    let mut decls: Vec<(SubprogSig, bool, Option<SubprogSig>)> = vec![];
+
+    for stmt in stmt_list.stmts() {
+        match stmt {
+            ast::Stmt::ForwardDecl(decl) => {
+                if let Some(sig) = decl.subprog_header().and_then(SubprogSig::from_header) {
+                    decls.push((sig, true, None));
+                }
+            }
+            ast::Stmt::DeferredDecl(decl) => {
+                if let Some(sig) = decl.subprog_header().and_then(SubprogSig::from_header) {
+                    decls.push((sig, false, None));
+                }
+            }
+            ast::Stmt::BodyDecl(decl) => {
+                let sig = match decl.body_kind().and_then(SubprogSig::from_body_kind) {
+                    Some(sig) => sig,
+                    None => continue,
+                };
+
+                let decl = decls
+                    .iter_mut()
+                    .find(|(decl, ..)| decl.name.text() == sig.name.text());
+
+                match decl {
+                    Some((_, _, Some(first_body))) => {
+                        let first_span = Span::new(ctx.file, first_body.name.text_range());
+
+                        ctx.push_detailed_error(
+                            &format!("duplicate ‘body’ declaration for ‘{}’", sig.name.text()),
+                            sig.name.text_range(),
+                        )
+                        .with_note("first ‘body’ declaration is here", first_span)
+                        .finish();
+                    }
+                    Some((decl, _, body)) => {
+                        check_matching_signatures(decl, &sig, ctx);
+                        *body = Some(sig);
+                    }
+                    None if may_inherit_decls => {}
+                    None => ctx.push_error(
+                        &format!("no forward declaration for ‘{}’", sig.name.text()),
+                        sig.name.text_range(),
+                    ),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Deferred subprograms can be given bodies in other classes
+    for (decl, _, _) in decls
+        .iter()
+        .filter(|(_, is_forward, body)| *is_forward && body.is_none())
+    {
+        ctx.push_error(
+            &format!("missing ‘body’ declaration for ‘{}’", decl.name.text()),
+            decl.name.text_range(),
+        );
+    }
+}
+
+/// The parts of a subprogram header that a `body` declaration must match
+struct SubprogSig {
+    name: SyntaxToken,
+    header: TextRange,
+    params: Option<ast::ParamSpec>,
+    result: Option<ast::Type>,
+}
+
+impl SubprogSig {
+    fn from_header(header: ast::SubprogHeader) -> Option<Self> {
+        match header {
+            ast::SubprogHeader::ProcHeader(header) => {
+                Self::new(header.syntax(), header.name(), header.params(), None)
+            }
+            ast::SubprogHeader::FcnHeader(header) => Self::new(
+                header.syntax(),
+                header.name(),
+                header.param_spec(),
+                header.fcn_result(),
+            ),
+        }
+    }
+
+    fn from_body_kind(header: ast::BodyKind) -> Option<Self> {
+        match header {
+            ast::BodyKind::PlainHeader(header) => Self::new(
+                header.syntax(),
+                header.name(),
+                header.param_spec(),
+                header.fcn_result(),
+            ),
+            ast::BodyKind::ProcHeader(header) => {
+                Self::new(header.syntax(), header.name(), header.params(), None)
+            }
+            ast::BodyKind::FcnHeader(header) => Self::new(
+                header.syntax(),
+                header.name(),
+                header.param_spec(),
+                header.fcn_result(),
+            ),
+        }
+    }
+
+    fn new(
+        header: &SyntaxNode,
+        name: Option<ast::Name>,
+        params: Option<ast::ParamSpec>,
+        result: Option<ast::FcnResult>,
+    ) -> Option<Self> {
+        Some(Self {
+            name: name?.identifier_token()?,
+            header: header.text_range(),
+            params,
+            result: result.and_then(|result| result.ty()),
+        })
+    }
+}
+
+/// Checks that a `body` declaration has the same parameters and result type as
+/// its original declaration.
+/// Bodies may leave out the parameters and result type, as they are taken
+/// from the original declaration.
+fn check_matching_signatures(decl: &SubprogSig, body: &SubprogSig, ctx: &mut ValidateCtx) {
+    /// Non-trivia tokens, for comparing independent of whitespace and comments
+    fn significant_tokens(node: &SyntaxNode) -> Vec<(SyntaxKind, String)> {
+        node.descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .filter(|token| !matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment))
+            .map(|token| (token.kind(), token.text().to_string()))
+            .collect()
+    }
+
+    fn matches<N: AstNode>(decl: &Option<N>, body: &Option<N>) -> bool {
+        match (decl, body) {
+            (_, None) => true,
+            (None, Some(body)) => significant_tokens(body.syntax()).is_empty(),
+            (Some(decl), Some(body)) => {
+                significant_tokens(decl.syntax()) == significant_tokens(body.syntax())
+            }
+        }
+    }
+
+    let (params_match, result_match) = (
+        matches(&decl.params, &body.params),
+        matches(&decl.result, &body.result),
+    );
+
+    let differences = match (params_match, result_match) {
+        (true, true) => return,
+        (false, true) => "parameters are different",
+        (true, false) => "result types are different",
+        (false, false) => "parameters and result types are different",
+    };
+    let decl_span = Span::new(ctx.file, decl.header);
+
+    ctx.push_detailed_error(
+        &format!(
+            "‘body’ declaration for ‘{}’ does not match its original declaration",
+            body.name.text()
+        ),
+        body.header,
+    )
+    .with_note("original declaration is here", decl_span)
+    .with_info(differences, None)
+    .finish();
+}
+
 fn check_matching_names(
     decl_name: Option<ast::Name>,
     end_group: Option<ast::EndGroup>,
@@ -455,3 +843,220 @@ fn check_matching_names(
         }
     }
 }
+
+pub(super) fn validate_param_spec(spec: ast::ParamSpec, ctx: &mut ValidateCtx) {
+    let names = spec.param_decl().filter_map(|param| match param {
+        ast::ParamDecl::ConstVarParam(param) => Some(param.param_names()?.names().collect()),
+        ast::ParamDecl::SubprogType(ast::SubprogType::FcnType(param)) => Some(vec![param.name()?]),
+        ast::ParamDecl::SubprogType(ast::SubprogType::ProcType(param)) => Some(vec![param.name()?]),
+    });
+    let names: Vec<_> = names
+        .flatten()
+        .filter_map(|name| name.identifier_token())
+        .collect();
+
+    check_duplicate_names(names.iter().cloned(), "parameter", ctx);
+
+    // Parameters can't share a name with the subprogram that they belong to
+    let subprog_name = spec.syntax().parent().and_then(|parent| {
+        match_ast!(match parent {
+            ast::ProcHeader(header) => header.name(),
+            ast::FcnHeader(header) => header.name(),
+            ast::ProcessDecl(decl) => decl.name(),
+            _ => None,
+        })
+    });
+    let subprog_name = match subprog_name.and_then(|name| name.identifier_token()) {
+        Some(name) => name,
+        None => return,
+    };
+
+    for name in &names {
+        if name.text() == subprog_name.text() {
+            let subprog_span = Span::new(ctx.file, subprog_name.text_range());
+
+            ctx.push_detailed_error(
+                &format!(
+                    "parameter ‘{}’ has the same name as its subprogram",
+                    name.text()
+                ),
+                name.text_range(),
+            )
+            .with_note(
+                &format!("‘{}’ is declared here", subprog_name.text()),
+                subprog_span,
+            )
+            .finish();
+        }
+    }
+}
+
+pub(super) fn validate_enum_type(ty: ast::EnumType, ctx: &mut ValidateCtx) {
+    if let Some(names) = ty.fields() {
+        let names = names.names().filter_map(|name| name.identifier_token());
+        check_duplicate_names(names, "enum variant", ctx);
+    }
+}
+
+pub(super) fn validate_export_stmt(stmt: ast::ExportStmt, ctx: &mut ValidateCtx) {
+    check_duplicate_names(
+        stmt.exports()
+            .filter_map(|item| item.name()?.identifier_token()),
+        "export",
+        ctx,
+    );
+}
+
+pub(super) fn validate_condition_type(ty: ast::ConditionType, ctx: &mut ValidateCtx) {
+    // Arrays of conditions are also allowed
+    let mut parent = ty.syntax().parent();
+    while let Some(array) = parent.clone().and_then(ast::ArrayType::cast) {
+        parent = array.syntax().parent();
+    }
+
+    let is_allowed = parent
+        .and_then(ast::ConstVarDecl::cast)
+        .filter(|decl| decl.var_token().is_some())
+        .is_some_and(|decl| {
+            matches!(
+                block_containing_node(decl.syntax()),
+                BlockKind::Monitor | BlockKind::MonitorDevice | BlockKind::MonitorClass
+            )
+        });
+
+    if !is_allowed {
+        ctx.push_error(
+            "condition types are only allowed in ‘var’ declarations directly inside of monitors",
+            ty.syntax().text_range(),
+        );
+    }
+}
+
+pub(super) fn validate_wait_stmt(stmt: ast::WaitStmt, ctx: &mut ValidateCtx) {
+    // Only conditions declared directly inside of the enclosing monitor can be
+    // checked, since names aren't resolved yet
+    let (wait_ref, cond_ty) = match (stmt.wait_ref(), find_condition_decl(&stmt)) {
+        (Some(wait_ref), Some(cond_ty)) => (wait_ref, cond_ty),
+        _ => return,
+    };
+
+    let value_kind = cond_ty.condition_kind().and_then(|kind| {
+        if kind.priority_token().is_some() {
+            Some("priority")
+        } else if kind.timeout_token().is_some() {
+            Some("timeout")
+        } else {
+            None
+        }
+    });
+    let cond_span = Span::new(ctx.file, cond_ty.syntax().text_range());
+    let cond_text = cond_ty.syntax().text().to_string();
+
+    match (value_kind, stmt.wait_val()) {
+        (Some(value_kind), None) => {
+            ctx.push_detailed_error(
+                &format!("missing {} value for ‘wait’", value_kind),
+                wait_ref.syntax().text_range(),
+            )
+            .with_note(
+                &format!("condition is declared as a ‘{}’ here", cond_text),
+                cond_span,
+            )
+            .finish();
+        }
+        (None, Some(wait_val)) => {
+            ctx.push_detailed_error(
+                "unexpected value for ‘wait’",
+                wait_val.syntax().text_range(),
+            )
+            .with_note(
+                &format!("condition is declared as a ‘{}’ here", cond_text),
+                cond_span,
+            )
+            .with_info(
+                "only priority and timeout conditions are waited on with a value",
+                None,
+            )
+            .finish();
+        }
+        _ => {}
+    }
+}
+
+/// Finds the condition type that a `wait` statement waits on, out of the
+/// variables declared directly inside of the enclosing monitor
+fn find_condition_decl(stmt: &ast::WaitStmt) -> Option<ast::ConditionType> {
+    let cond_name = match stmt.wait_ref()? {
+        ast::Expr::NameExpr(expr) => expr.name()?,
+        // Element of an array of conditions
+        ast::Expr::CallExpr(expr) => match expr.expr()? {
+            ast::Expr::NameExpr(expr) => expr.name()?,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let cond_name = cond_name.identifier_token()?;
+
+    let stmt_list = stmt.syntax().ancestors().find_map(|node| {
+        match_ast!(match node {
+            ast::MonitorDecl(decl) => decl.stmt_list(),
+            ast::ClassDecl(decl) => decl.monitor_token().and(decl.stmt_list()),
+            _ => None,
+        })
+    })?;
+
+    stmt_list
+        .syntax()
+        .children()
+        .filter_map(ast::ConstVarDecl::cast)
+        .filter(|decl| decl.var_token().is_some())
+        .find_map(|decl| {
+            let is_declared = decl
+                .decl_list()?
+                .names()
+                .filter_map(|name| name.identifier_token())
+                .any(|name| name.text() == cond_name.text());
+
+            if !is_declared {
+                return None;
+            }
+
+            // Look through arrays of conditions
+            let mut ty = decl.type_spec()?;
+            loop {
+                ty = match ty {
+                    ast::Type::ArrayType(array) => array.elem_ty()?,
+                    ast::Type::ConditionType(ty) => return Some(ty),
+                    _ => return None,
+                };
+            }
+        })
+}
+
+/// Reports every name in `names` which is the same as an earlier name,
+/// where `what` describes what the names are declaring
+fn check_duplicate_names(
+    names: impl Iterator<Item = SyntaxToken>,
+    what: &str,
+    ctx: &mut ValidateCtx,
+) {
+    let mut seen: Vec<SyntaxToken> = vec![];
+
+    for name in names {
+        if let Some(first) = seen.iter().find(|first| first.text() == name.text()) {
+            let first_span = Span::new(ctx.file, first.text_range());
+
+            ctx.push_detailed_error(
+                &format!("duplicate {} name ‘{}’", what, name.text()),
+                name.text_range(),
+            )
+            .with_note(
+                &format!("‘{}’ first declared here", name.text()),
+                first_span,
+            )
+            .finish();
+        } else {
+            seen.push(name);
+        }
+    }
+}
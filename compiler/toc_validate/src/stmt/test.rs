@@ -322,6 +322,80 @@ fn init_expr_with_union_ty() {
     );
 }
 
+#[test]
+fn init_expr_with_record_of_array_ty() {
+    check(
+        "var a : record a : int b : array 1 .. 2 of int end record := init(1, init(2, 3))",
+        expect![[]],
+    );
+}
+
+#[test]
+fn init_expr_with_array_of_record_ty() {
+    check(
+        "var a : array 1 .. 2 of record a, b : int end record := init(init(1, 2), init(3, 4))",
+        expect![[]],
+    );
+    // Multi-dimensional arrays are initialized in flattened order
+    check(
+        "var a : array 1 .. 2, 1 .. 2 of int := init(1, 2, 3, 4)",
+        expect![[]],
+    );
+}
+
+#[test]
+fn report_init_expr_wrong_inner_len() {
+    check(
+        "var a : array 1 .. 2 of record a, b : int end record := init(init(1, 2), init(3))",
+        expect![[r#"
+            error at 73..80: expected 2 fields in ‘init’ initializer, found 1
+            | note for 24..52: this record type has 2 fields"#]],
+    );
+    check(
+        "var a : record a : array 1 .. 3 of int end record := init(init(1, 2))",
+        expect![[r#"
+            error at 58..68: expected 3 elements in ‘init’ initializer, found 2
+            | note for 19..38: this array type has 3 elements"#]],
+    );
+}
+
+#[test]
+fn report_init_expr_wrong_outer_len() {
+    check(
+        "var a : record a, b : int end record := init(1)",
+        expect![[r#"
+            error at 40..47: expected 2 fields in ‘init’ initializer, found 1
+            | note for 8..36: this record type has 2 fields"#]],
+    );
+    check(
+        "var a : array 1 .. 3 of int := init(1, 2)",
+        expect![[r#"
+            error at 31..41: expected 3 elements in ‘init’ initializer, found 2
+            | note for 8..27: this array type has 3 elements"#]],
+    );
+}
+
+#[test]
+fn report_nested_init_expr_with_int_ty() {
+    check(
+        "var a : record a : int end record := init(init(1))",
+        expect![[r#"
+            error at 42..49: ‘init’ initializer is not allowed here
+            | note for 19..22: cannot use ‘init’ initializer with this type
+            | info: ‘init’ initializer can only be used with array, record, or union types"#]],
+    );
+}
+
+#[test]
+fn nested_init_expr_unknown_len() {
+    // Named types and non-literal bounds can't be checked yet
+    check("var a : array 1 .. c of int := init(1)", expect![[]]);
+    check(
+        "var a : array 1 .. * of t := init(init(1), init(2, 3))",
+        expect![[]],
+    );
+}
+
 #[test]
 fn report_not_init_expr_with_unbounded_array() {
     check(
@@ -377,7 +451,7 @@ fn report_proc_decl_in_block_stmt() {
 #[test]
 fn report_dev_spec_in_forward_decl() {
     check(
-        "forward proc a : 2",
+        "forward proc a : 2 body a end a",
         expect![[r#"error at 15..18: device specification is not allowed here"#]],
     );
 }
@@ -528,12 +602,12 @@ fn deferred_decl_in_class() {
 
 #[test]
 fn forward_decl_in_main() {
-    check("forward proc a", expect![[]]);
+    check("forward proc a body a end a", expect![[]]);
 }
 
 #[test]
 fn forward_decl_in_module() {
-    check("module q forward proc a end q", expect![[]]);
+    check("module q forward proc a body a end a end q", expect![[]]);
 }
 
 #[test]
@@ -558,12 +632,12 @@ fn report_forward_decl_in_block_stmt() {
 
 #[test]
 fn body_decl_in_main() {
-    check("body a end a", expect![[]]);
+    check("forward proc a body a end a", expect![[]]);
 }
 
 #[test]
 fn body_decl_in_module() {
-    check("module q body a end a end q", expect![[]]);
+    check("module q forward proc a body a end a end q", expect![[]]);
 }
 
 #[test]
@@ -586,6 +660,133 @@ fn report_body_decl_in_block_stmt() {
     );
 }
 
+#[test]
+fn forward_and_body_pair() {
+    check(
+        r#"
+        forward procedure p (x : int, var y : string)
+        forward function f () : int
+        body procedure p (x : int, var y : string) end p
+        body function f () : int end f
+        "#,
+        expect![[]],
+    );
+}
+
+#[test]
+fn forward_and_body_pair_with_import_list() {
+    check(
+        "var q : int forward proc p import q body proc p end p",
+        expect![[]],
+    );
+}
+
+#[test]
+fn body_omits_forward_params() {
+    check(
+        r#"
+        forward procedure p (x : int)
+        forward function f (x : int) : int
+        body p end p
+        body function f end f
+        "#,
+        expect![[]],
+    );
+}
+
+#[test]
+fn body_for_deferred_decl() {
+    check("class q deferred proc a body a end a end q", expect![[]]);
+}
+
+#[test]
+fn body_for_inherited_decl() {
+    check("class q inherit r body a end a end q", expect![[]]);
+    check("module q implement r body a end a end q", expect![[]]);
+}
+
+#[test]
+fn report_body_without_forward() {
+    check(
+        "body proc p end p",
+        expect!["error at 10..11: no forward declaration for ‘p’"],
+    );
+}
+
+#[test]
+fn report_body_before_forward() {
+    check(
+        "body p end p forward proc p",
+        expect![[r#"
+            error at 5..6: no forward declaration for ‘p’
+            error at 26..27: missing ‘body’ declaration for ‘p’"#]],
+    );
+}
+
+#[test]
+fn report_forward_without_body() {
+    check(
+        "forward proc p module q body p end p end q",
+        expect![[r#"
+            error at 13..14: missing ‘body’ declaration for ‘p’
+            error at 29..30: no forward declaration for ‘p’"#]],
+    );
+}
+
+#[test]
+fn report_body_params_mismatch() {
+    check(
+        "forward proc p (x : int) body proc p (x : real) end p",
+        expect![[r#"
+            error at 30..47: ‘body’ declaration for ‘p’ does not match its original declaration
+            | note for 8..24: original declaration is here
+            | info: parameters are different"#]],
+    );
+}
+
+#[test]
+fn report_body_result_mismatch() {
+    check(
+        "forward fcn f (x : int) : int body fcn f (x : int) : real end f",
+        expect![[r#"
+            error at 35..57: ‘body’ declaration for ‘f’ does not match its original declaration
+            | note for 8..29: original declaration is here
+            | info: result types are different"#]],
+    );
+}
+
+#[test]
+fn report_body_params_and_result_mismatch() {
+    check(
+        "forward fcn f () : int body f (x : int) : real end f",
+        expect![[r#"
+            error at 28..46: ‘body’ declaration for ‘f’ does not match its original declaration
+            | note for 8..22: original declaration is here
+            | info: parameters and result types are different"#]],
+    );
+}
+
+#[test]
+fn report_body_extra_params() {
+    check(
+        "forward proc p body proc p (x : int) end p",
+        expect![[r#"
+            error at 20..36: ‘body’ declaration for ‘p’ does not match its original declaration
+            | note for 8..14: original declaration is here
+            | info: parameters are different"#]],
+    );
+}
+
+#[test]
+fn report_duplicate_body() {
+    check(
+        "forward proc p body p end p body p end p",
+        expect![[r#"
+            error at 33..34: duplicate ‘body’ declaration for ‘p’
+            | note for 20..21: first ‘body’ declaration is here"#]],
+    );
+}
+
 #[test]
 fn new_open_text_caps() {
     check("open : _, _, get, put, mod", expect![[]]);
@@ -663,6 +864,75 @@ fn for_stmt_partial_decreasing_no_bounds() {
     );
 }
 
+#[test]
+fn get_skip_item() {
+    check("get skip, a : 4", expect![[]]);
+}
+
+#[test]
+fn report_get_skip_with_width() {
+    check(
+        "get skip : 4",
+        expect![[r#"error at 9..12: ‘skip’ cannot have a width"#]],
+    );
+}
+
+#[test]
+fn fcn_return_with_result_name() {
+    check("function f () r : int return end f", expect![[]]);
+    check(
+        "forward function f () r : int body function f () r : int return end f",
+        expect![[]],
+    );
+    check("procedure p return end p", expect![[]]);
+    // Could be for a function with a named result
+    check(
+        "forward function f () r : int body f return end f",
+        expect![[]],
+    );
+}
+
+#[test]
+fn report_fcn_return_without_result_name() {
+    check(
+        "function f () : int return end f",
+        expect![[r#"
+            error at 20..26: ‘return’ is not allowed in a function without a named result
+            | info: use ‘result’ to give back a value instead"#]],
+    );
+    // Also inside of nested blocks
+    check(
+        "forward function f () : int body function f () : int begin return end end f",
+        expect![[r#"
+            error at 59..65: ‘return’ is not allowed in a function without a named result
+            | info: use ‘result’ to give back a value instead"#]],
+    );
+    check(
+        "forward function f () : int body f () : int return end f",
+        expect![[r#"
+            error at 44..50: ‘return’ is not allowed in a function without a named result
+            | info: use ‘result’ to give back a value instead"#]],
+    );
+}
+
+#[test]
+fn report_result_in_process() {
+    check(
+        "process p result 1 end p",
+        expect![[r#"
+            error at 10..18: ‘result’ is not allowed in a process
+            | info: processes don't give back a value"#]],
+    );
+    // Also inside of nested blocks
+    check(
+        "process p loop begin result 1 end end loop end p",
+        expect![[r#"
+            error at 21..29: ‘result’ is not allowed in a process
+            | info: processes don't give back a value"#]],
+    );
+    check("function f : int result 1 end f", expect![[]]);
+}
+
 #[test]
 fn report_case_stmt_missing_arms() {
     check(
@@ -786,3 +1056,158 @@ fn report_invariant_stmt_in_inner() {
         ]],
     );
 }
+
+#[test]
+fn report_duplicate_param_names() {
+    check(
+        "procedure p (a, b, a : int, function b : int) end p",
+        expect![[r#"
+            error at 19..20: duplicate parameter name ‘a’
+            | note for 13..14: ‘a’ first declared here
+            error at 37..38: duplicate parameter name ‘b’
+            | note for 16..17: ‘b’ first declared here"#]],
+    );
+}
+
+#[test]
+fn report_param_with_subprog_name() {
+    check(
+        "function f (f : int) : int end f",
+        expect![[r#"
+        error at 12..13: parameter ‘f’ has the same name as its subprogram
+        | note for 9..10: ‘f’ is declared here"#]],
+    );
+    check(
+        "process p (p : int) end p",
+        expect![[r#"
+            error at 11..12: parameter ‘p’ has the same name as its subprogram
+            | note for 8..9: ‘p’ is declared here"#]],
+    );
+}
+
+#[test]
+fn report_duplicate_var_names() {
+    check(
+        "var x, y, x, x : int",
+        expect![[r#"
+        error at 10..11: duplicate variable name ‘x’
+        | note for 4..5: ‘x’ first declared here
+        error at 13..14: duplicate variable name ‘x’
+        | note for 4..5: ‘x’ first declared here"#]],
+    );
+    check(
+        "const c, c := 1",
+        expect![[r#"
+        error at 9..10: duplicate constant name ‘c’
+        | note for 6..7: ‘c’ first declared here"#]],
+    );
+}
+
+#[test]
+fn report_duplicate_enum_variants() {
+    check(
+        "type e : enum (a, b, a)",
+        expect![[r#"
+        error at 21..22: duplicate enum variant name ‘a’
+        | note for 15..16: ‘a’ first declared here"#]],
+    );
+}
+
+#[test]
+fn report_duplicate_exports() {
+    check(
+        "module m export a, var a end m",
+        expect![[r#"
+        error at 23..24: duplicate export name ‘a’
+        | note for 16..17: ‘a’ first declared here"#]],
+    );
+}
+
+#[test]
+fn condition_types_in_monitor() {
+    check(
+        r#"
+    monitor m
+        var c : condition
+        var dq : deferred condition
+        var pq : priority condition
+        var tq : timeout condition
+        var ac : array 1 .. 2 of condition
+    end m
+    monitor class mc
+        var c : condition
+    end mc
+    "#,
+        expect![[r#""#]],
+    );
+}
+
+#[test]
+fn report_condition_type_outside_of_monitor() {
+    check(
+        r#"
+    var c : condition
+    type t : condition
+    monitor m
+        const c : timeout condition := d
+        procedure p
+            var c : priority condition
+        end p
+    end m
+    "#,
+        expect![[r#"
+            error at 13..22: condition types are only allowed in ‘var’ declarations directly inside of monitors
+            error at 36..45: condition types are only allowed in ‘var’ declarations directly inside of monitors
+            error at 78..95: condition types are only allowed in ‘var’ declarations directly inside of monitors
+            error at 141..159: condition types are only allowed in ‘var’ declarations directly inside of monitors"#]],
+    );
+}
+
+#[test]
+fn wait_on_condition() {
+    check(
+        r#"
+    monitor m
+        var c : condition
+        var pq : priority condition
+        var tq : timeout condition
+        var ac : array 1 .. 2 of timeout condition
+        wait c
+        wait pq, 1
+        wait tq, 100
+        wait ac(1), 100
+        % Not declared here, so not checked
+        wait other
+    end m
+    "#,
+        expect![[r#""#]],
+    );
+}
+
+#[test]
+fn report_wait_on_condition_value_mismatch() {
+    check(
+        r#"
+    monitor m
+        var c : deferred condition
+        var pq : priority condition
+        var tq : timeout condition
+        var ac : array 1 .. 2 of timeout condition
+        wait c, 1
+        wait pq
+        wait tq
+        wait ac(1)
+    end m
+    "#,
+        expect![[r#"
+            error at 188..189: unexpected value for ‘wait’
+            | note for 31..49: condition is declared as a ‘deferred condition’ here
+            | info: only priority and timeout conditions are waited on with a value
+            error at 203..205: missing priority value for ‘wait’
+            | note for 67..85: condition is declared as a ‘priority condition’ here
+            error at 219..221: missing timeout value for ‘wait’
+            | note for 103..120: condition is declared as a ‘timeout condition’ here
+            error at 235..240: missing timeout value for ‘wait’
+            | note for 154..171: condition is declared as a ‘timeout condition’ here"#]],
+    );
+}
@@ -3,10 +3,37 @@
 use std::num::NonZeroU32;
 use std::{
     convert::TryFrom,
+    fmt,
     sync::{Arc, RwLock},
 };
 
-use toc_span::FileId;
+use toc_span::{FileId, Span, TextRange, TextSize};
+
+/// UTF-8 byte order mark
+const UTF8_BOM: &str = "\u{FEFF}";
+
+/// Errors encountered while loading a file's contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadError {
+    /// The file contents are not valid UTF-8
+    InvalidUtf8 {
+        /// Byte offset in the file of the first invalid sequence
+        byte_offset: usize,
+        /// Span of the replacement character for the invalid sequence,
+        /// relative to the file's source text
+        span: Span,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::InvalidUtf8 { byte_offset, .. } => {
+                write!(f, "file is not valid UTF-8 at byte {}", byte_offset)
+            }
+        }
+    }
+}
 
 /// Information stored about a file
 pub struct FileInfo {
@@ -28,7 +55,16 @@ impl FileDb {
         }
     }
 
+    /// Adds a file with the given source text.
+    ///
+    /// A leading UTF-8 byte order mark is skipped, so all spans are relative to the
+    /// text after the BOM.
     pub fn add_file(&self, path: &str, source: &str) -> FileId {
+        let source = source.strip_prefix(UTF8_BOM).unwrap_or(source);
+        self.insert_file(path, source)
+    }
+
+    fn insert_file(&self, path: &str, source: &str) -> FileId {
         // TODO: Dedup paths
         let mut files = self.files.write().unwrap();
 
@@ -43,10 +79,66 @@ impl FileDb {
         id
     }
 
+    /// Adds a file from its raw bytes.
+    ///
+    /// A leading UTF-8 byte order mark is skipped, so all spans are relative to the
+    /// text after the BOM.
+    ///
+    /// Invalid UTF-8 sequences are always replaced with `U+FFFD REPLACEMENT CHARACTER`
+    /// so that the file still has source text. If `allow_lossy` is false, a
+    /// `LoadError` pointing to the first invalid sequence is also produced.
+    /// Past that point, offsets in the replaced text no longer match the offsets
+    /// in `contents`, so the file should only be reported through the `LoadError`.
+    pub fn add_file_bytes(
+        &self,
+        path: &str,
+        contents: &[u8],
+        allow_lossy: bool,
+    ) -> (FileId, Option<LoadError>) {
+        let (contents, bom_len) = match contents.strip_prefix(UTF8_BOM.as_bytes()) {
+            Some(contents) => (contents, UTF8_BOM.len()),
+            None => (contents, 0),
+        };
+
+        match std::str::from_utf8(contents) {
+            Ok(source) => (self.insert_file(path, source), None),
+            Err(err) => {
+                let source = String::from_utf8_lossy(contents);
+                let id = self.insert_file(path, &source);
+
+                if allow_lossy {
+                    return (id, None);
+                }
+
+                // Everything before the first invalid sequence is untouched by the lossy
+                // conversion, so the replacement character starts at the same offset
+                let valid_up_to = err.valid_up_to();
+                let start = TextSize::try_from(valid_up_to).expect("File too large");
+                let range = TextRange::at(start, TextSize::of('\u{FFFD}'));
+
+                let err = LoadError::InvalidUtf8 {
+                    byte_offset: valid_up_to + bom_len,
+                    span: Span::new(Some(id), range),
+                };
+
+                (id, Some(err))
+            }
+        }
+    }
+
     pub fn get_file(&self, id: FileId) -> Arc<FileInfo> {
         self.files.read().unwrap()[(id.raw_id().get() - 1) as usize].clone()
     }
 
+    /// Finds a previously added file by its path
+    pub fn find_file(&self, path: &str) -> Option<FileId> {
+        let files = self.files.read().unwrap();
+        let index = files.iter().position(|info| info.path == path)?;
+
+        let id = u32::try_from(index + 1).ok();
+        id.and_then(NonZeroU32::new).map(FileId::new)
+    }
+
     pub fn files(&self) -> impl Iterator<Item = FileId> {
         (0..self.files.read().unwrap().len()).map(|i| {
             let id = u32::try_from(i + 1).ok();
@@ -78,3 +170,84 @@ fn test_mut_across_file_add() {
     let _second_file = db.add_file("some/path/to/elsewhere", "other_source");
     let _later_use = first_text;
 }
+
+#[test]
+fn test_find_file() {
+    let db = FileDb::new();
+    let first_file = db.add_file("some/path/to/there", "");
+    let second_file = db.add_file("some/path/to/elsewhere", "");
+
+    assert_eq!(db.find_file("some/path/to/there"), Some(first_file));
+    assert_eq!(db.find_file("some/path/to/elsewhere"), Some(second_file));
+    assert_eq!(db.find_file("some/path/to/nowhere"), None);
+}
+
+#[test]
+fn test_add_file_skip_bom() {
+    let db = FileDb::new();
+    let file = db.add_file("bom", "\u{FEFF}var a := 1");
+
+    assert_eq!(db.get_file(file).source, "var a := 1");
+}
+
+#[test]
+fn test_add_file_keeps_crlf() {
+    let db = FileDb::new();
+    let file = db.add_file("crlf", "var a\r\nvar b\r\n");
+
+    // Line endings are left as-is, so that spans index the original text
+    assert_eq!(db.get_file(file).source, "var a\r\nvar b\r\n");
+}
+
+#[test]
+fn test_add_file_bytes_skip_bom() {
+    let db = FileDb::new();
+    let (file, err) = db.add_file_bytes("bom", b"\xEF\xBB\xBFvar a := 1", false);
+
+    assert_eq!(err, None);
+    assert_eq!(db.get_file(file).source, "var a := 1");
+}
+
+#[test]
+fn test_add_file_bytes_invalid_utf8() {
+    let db = FileDb::new();
+    // "put \"café\"" encoded as latin-1
+    let (file, err) = db.add_file_bytes("latin1", b"put \"caf\xE9\"", false);
+
+    assert_eq!(
+        err,
+        Some(LoadError::InvalidUtf8 {
+            byte_offset: 8,
+            span: Span::new(Some(file), TextRange::new(8.into(), 11.into())),
+        })
+    );
+    assert_eq!(
+        err.unwrap().to_string(),
+        "file is not valid UTF-8 at byte 8"
+    );
+    assert_eq!(db.get_file(file).source, "put \"caf\u{FFFD}\"");
+}
+
+#[test]
+fn test_add_file_bytes_invalid_utf8_after_bom() {
+    let db = FileDb::new();
+    let (file, err) = db.add_file_bytes("bom_latin1", b"\xEF\xBB\xBFcaf\xE9", false);
+
+    // Byte offset is relative to the file, but the span is relative to the source text
+    assert_eq!(
+        err,
+        Some(LoadError::InvalidUtf8 {
+            byte_offset: 6,
+            span: Span::new(Some(file), TextRange::new(3.into(), 6.into())),
+        })
+    );
+}
+
+#[test]
+fn test_add_file_bytes_lossy() {
+    let db = FileDb::new();
+    let (file, err) = db.add_file_bytes("latin1", b"caf\xE9", true);
+
+    assert_eq!(err, None);
+    assert_eq!(db.get_file(file).source, "caf\u{FFFD}");
+}
@@ -5,9 +5,10 @@
 mod inner {
     pub(crate) fn do_fuzz() {
         afl::fuzz!(|data: &[u8]| {
-            if let Ok(s) = std::str::from_utf8(data) {
-                let _ = toc_parser::parse(None, s);
-            }
+            // Arbitrary bytes are mostly caught by the scanner, so also try
+            // sequences of valid tokens to get deeper into the parser
+            super::check_source(&String::from_utf8_lossy(data));
+            super::check_source(&super::token_source(data));
         });
     }
 }
@@ -19,6 +20,46 @@ mod inner {
     }
 }
 
+use toc_span::{Span, TextRange, TextSize};
+
+const PUNCTUATION: &[&str] = &[
+    "(", ")", "[", "]", "{", "}", ",", ".", "..", ":", ":=", ";", "^", "#", "&", "@", "=>", "~",
+    "=", "+", "-", "*", "/", "**", "<", ">", "<=", ">=", "~=", "->", "+=", "|", "1", "1.0",
+    "\"s\"", "'c'", "a", "b", "\n",
+];
+
+/// Builds a source out of tokens, with each byte picking a keyword or punctuation token
+fn token_source(data: &[u8]) -> String {
+    let keywords = toc_scanner::token::KEYWORDS.iter().map(|(text, _)| *text);
+    let tokens: Vec<_> = keywords.chain(PUNCTUATION.iter().copied()).collect();
+
+    data.iter()
+        .map(|byte| tokens[usize::from(*byte) % tokens.len()])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Runs `source` through parsing, validation, and lowering, checking that
+/// every reported span stays within the source
+fn check_source(source: &str) {
+    let bounds = Span::new(None, TextRange::up_to(TextSize::of(source)));
+
+    let parsed = toc_parser::parse(None, source);
+    let validated = toc_validate::validate_ast(None, parsed.syntax());
+    let lowered =
+        toc_hir_lowering::lower_ast(toc_hir::db::HirBuilder::new(), None, parsed.syntax());
+
+    let messages = parsed
+        .messages()
+        .iter()
+        .chain(validated.messages())
+        .chain(lowered.messages());
+
+    for msg in messages {
+        assert!(msg.is_within(bounds), "message out of bounds: {:?}", msg);
+    }
+}
+
 fn main() {
     inner::do_fuzz();
 }
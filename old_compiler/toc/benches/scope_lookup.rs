@@ -0,0 +1,72 @@
+//! Comparing identifier lookups by interned symbols and by strings
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use toc_ast::ast::ident::{IdentId, RefKind};
+use toc_ast::ast::stmt::BlockKind;
+use toc_ast::scope::UnitScope;
+use toc_ast::types::TypeRef;
+use toc_core::sym::{Interner, Symbol};
+use toc_core::Location;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const IDENT_COUNT: usize = 10_000;
+
+fn compare_lookups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Scope Lookup");
+
+    let interner = Arc::new(Interner::new());
+    let mut unit_scope = UnitScope::new(interner.clone());
+    unit_scope.push_block(BlockKind::Main);
+
+    let names: Vec<String> = (0..IDENT_COUNT)
+        .map(|n| format!("some_identifier_{}", n))
+        .collect();
+    let symbols: Vec<Symbol> = names.iter().map(|name| interner.intern(name)).collect();
+
+    for symbol in &symbols {
+        unit_scope.declare_ident(
+            *symbol,
+            Location::new(),
+            TypeRef::Unknown,
+            RefKind::Var,
+            false,
+        );
+    }
+
+    // Scope mappings as they were before names were interned
+    let string_mappings: HashMap<String, IdentId> = names
+        .iter()
+        .zip(&symbols)
+        .map(|(name, symbol)| (name.clone(), unit_scope.get_ident_id(*symbol).unwrap()))
+        .collect();
+
+    group.bench_function("String keys", |b| {
+        b.iter(|| {
+            for name in &names {
+                black_box(string_mappings.get(black_box(name.as_str())));
+            }
+        })
+    });
+    group.bench_function("Symbol keys", |b| {
+        b.iter(|| {
+            for symbol in &symbols {
+                black_box(unit_scope.get_ident_id(black_box(*symbol)));
+            }
+        })
+    });
+    group.bench_function("Intern then Symbol keys", |b| {
+        b.iter(|| {
+            for name in &names {
+                let symbol = interner.intern(black_box(name.as_str()));
+                black_box(unit_scope.get_ident_id(symbol));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, compare_lookups);
+criterion_main!(benches);
@@ -5,27 +5,33 @@ extern crate toc_frontend;
 extern crate toc_ir;
 
 use toc_ast::unit::CodeUnit;
+use toc_core::{Level, MessageCode};
 use toc_frontend::context::{CompileSession, CompileStatus};
 
 fn dump_info(unit: &CodeUnit, dump_out: &[String]) {
-    if dump_out.iter().any(|elem| elem == "ast") {
-        // Pretty-print AST
-        println!("ast: {}", &unit.root_stmt);
-    }
+    // Identifier names are resolved through the unit's interner
+    unit.unit_scope.interner().enter(|| {
+        if dump_out.iter().any(|elem| elem == "ast") {
+            // Pretty-print AST
+            println!("ast: {}", &unit.root_stmt);
+        }
 
-    if dump_out.iter().any(|elem| elem == "scope") {
-        // Pretty-print unit scope
-        println!("scope: {}", &unit.unit_scope);
-    }
+        if dump_out.iter().any(|elem| elem == "scope") {
+            // Pretty-print unit scope
+            println!("scope: {}", &unit.unit_scope);
+        }
 
-    if dump_out.iter().any(|elem| elem == "types") {
-        // Pretty-print types
-        println!("types: {}", &unit.type_table);
-    }
+        if dump_out.iter().any(|elem| elem == "types") {
+            // Pretty-print types
+            println!("types: {}", &unit.type_table);
+        }
+    })
 }
 
 /// Compiles the given file
 ///
+/// `message_levels` are applied in order, so later levels for the same code take priority.
+///
 /// # Returns
 /// Returns whether compilation was successful or not
 pub fn compile_file(
@@ -33,8 +39,14 @@ pub fn compile_file(
     dump_out: Vec<String>,
     mute_warnings: bool,
     only_parser: bool,
+    message_levels: &[(MessageCode, Level)],
 ) -> bool {
     let mut session = CompileSession::new();
+
+    for (code, level) in message_levels {
+        session.set_message_level(*code, *level);
+    }
+
     let (main_unit, status) = session.compile_source_file(path, only_parser, mute_warnings);
 
     // Dump info for the main unit (skip over empty file)
@@ -1,11 +1,39 @@
 extern crate getopts;
+extern crate toc_core;
 
-use getopts::Options;
+use getopts::{Matches, Options};
 use std::env;
+use toc_core::{Level, MessageCode};
 
 fn show_usage(program_name: &str, opts: &Options) {
     let brief = format!("Usage: {} [options] [main file]", program_name);
     print!("{}", opts.usage(&brief));
+
+    let codes: Vec<&str> = MessageCode::ALL.iter().map(|code| code.name()).collect();
+    println!("\nWarning codes: {}", codes.join(", "));
+}
+
+/// Collects the warning levels given on the command line, in the order they were given
+fn collect_message_levels(matches: &Matches) -> Result<Vec<(MessageCode, Level)>, String> {
+    let mut levels = vec![];
+
+    for (opt, level) in &[
+        ("allow", Level::Allow),
+        ("warn", Level::Warn),
+        ("deny", Level::Deny),
+    ] {
+        for (pos, name) in matches.opt_strs_pos(opt) {
+            let code = MessageCode::from_name(&name)
+                .ok_or_else(|| format!("Unknown warning code '{}'", name))?;
+            levels.push((pos, code, *level));
+        }
+    }
+
+    levels.sort_by_key(|(pos, _, _)| *pos);
+    Ok(levels
+        .into_iter()
+        .map(|(_, code, level)| (code, level))
+        .collect())
 }
 
 fn main() {
@@ -38,6 +66,14 @@ fn main() {
         "Only runs the parser stage. Used for testing",
     );
     opts.optflag("M", "mute_warnings", "Mutes all warnings");
+    opts.optmulti("", "allow", "Silences warnings with the given code", "CODE");
+    opts.optmulti("", "warn", "Reports warnings with the given code", "CODE");
+    opts.optmulti(
+        "",
+        "deny",
+        "Reports warnings with the given code as errors",
+        "CODE",
+    );
     opts.optflag("", "help", "Shows this help message");
 
     let matches = match opts.parse(&args[1..]) {
@@ -59,8 +95,22 @@ fn main() {
         let dump_out = matches.opt_strs("dump");
         let mute_warnings = matches.opt_present("mute_warnings");
         let only_parser = matches.opt_present("only_parser");
+        let message_levels = match collect_message_levels(&matches) {
+            Ok(levels) => levels,
+            Err(msg) => {
+                eprintln!("{}", msg);
+                show_usage(&program, &opts);
+                return;
+            }
+        };
 
-        if !toc::compile_file(&source_path, dump_out, mute_warnings, only_parser) {
+        if !toc::compile_file(
+            &source_path,
+            dump_out,
+            mute_warnings,
+            only_parser,
+            &message_levels,
+        ) {
             // Exit with a non-zero status
             std::process::exit(-1);
         }
@@ -1,5 +1,6 @@
 //! Identifier related AST structures
 use crate::types::TypeRef;
+use toc_core::sym::Symbol;
 use toc_core::Location;
 
 /// Identifier id, associated with a unique declaration of an identifier
@@ -40,7 +41,7 @@ pub struct Identifier {
     /// The declaration location of this identifier in the source code.
     pub location: Location,
     /// The name of the identifier.
-    pub name: String,
+    pub name: Symbol,
     /// The type for this identifier.
     pub type_spec: TypeRef,
     /// The reference kind of this identifier
@@ -65,7 +66,7 @@ impl Identifier {
     pub fn new(
         location: Location,
         type_spec: TypeRef,
-        name: String,
+        name: Symbol,
         ref_kind: RefKind,
         is_declared: bool,
         is_pervasive: bool,
@@ -1,9 +1,11 @@
 use crate::ast::ident::{IdentId, Identifier, RefKind};
 use crate::ast::stmt::BlockKind;
 use crate::types::TypeRef;
+use toc_core::sym::{Interner, Symbol};
 use toc_core::Location;
 
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// All import boundary variants
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -23,12 +25,12 @@ pub struct ScopeBlock {
     kind: BlockKind,
     /// Import boundary conditions
     import_boundary: ImportBoundary,
-    /// Mapping of Strings to IdentId's, grouped by block.
+    /// Mapping of names to IdentId's, grouped by block.
     /// Each later mapping in the list builds upon mappings from the previous one.
     ///
     /// If a declaration here shadows another identifier declared before, the `bool`
     /// is marked as true.
-    id_mappings: HashMap<String, (IdentId, bool)>,
+    id_mappings: HashMap<Symbol, (IdentId, bool)>,
     /// List of all identifiers shadowed in this scope.
     /// Also includes local definitions shadowed by other local definitions
     shadowed_by: Vec<(IdentId, IdentId)>,
@@ -74,14 +76,14 @@ impl ScopeBlock {
     /// Gets an identifier id for an identifier declared in this scope block.
     ///
     /// If `None`, then the identifier is either imported, or undeclared beforehand.
-    pub fn get_ident_id(&self, name: &str) -> Option<IdentId> {
-        self.id_mappings.get(name).map(|(id, _)| *id)
+    pub fn get_ident_id(&self, name: Symbol) -> Option<IdentId> {
+        self.id_mappings.get(&name).map(|(id, _)| *id)
     }
 
     /// Gets if a given identifier name is shadowed in this scope block.
-    pub fn is_ident_shadowed(&self, name: &str) -> bool {
+    pub fn is_ident_shadowed(&self, name: Symbol) -> bool {
         self.id_mappings
-            .get(name)
+            .get(&name)
             .map_or(false, |(_, is_shadowed)| *is_shadowed)
     }
 
@@ -115,7 +117,7 @@ impl ScopeBlock {
 
     /// Declares an identifier in the scope, indicating whether it shadows
     /// another identifier.
-    fn declare_ident(&mut self, name: String, old_id: Option<IdentId>, new_id: IdentId) {
+    fn declare_ident(&mut self, name: Symbol, old_id: Option<IdentId>, new_id: IdentId) {
         // Check if an identifer is being shadowed by this declaration
         let is_shadowing = old_id.is_some();
 
@@ -145,6 +147,8 @@ impl ScopeBlock {
 /// A block is a collection of identifiers.
 #[derive(Debug)]
 pub struct UnitScope {
+    /// Interner for all identifier names
+    interner: Arc<Interner>,
     /// Next IdentId spot
     next_ident_id: u32,
     /// Mapping of IdentId's to Identifiers
@@ -156,9 +160,10 @@ pub struct UnitScope {
 }
 
 impl UnitScope {
-    /// Creates a new scope group
-    pub fn new() -> Self {
+    /// Creates a new scope group, with identifier names interned by `interner`
+    pub fn new(interner: Arc<Interner>) -> Self {
         Self {
+            interner,
             next_ident_id: 0,
             ident_ids: HashMap::new(),
             blocks: vec![],
@@ -166,6 +171,11 @@ impl UnitScope {
         }
     }
 
+    /// Gets the interner used for all identifier names
+    pub fn interner(&self) -> &Arc<Interner> {
+        &self.interner
+    }
+
     /// Pushes an identifier scope block.
     ///
     /// # Parameters
@@ -218,22 +228,15 @@ impl UnitScope {
     /// Returns an `IdentId` corresponding to the new definition of the identifier
     pub fn declare_ident(
         &mut self,
-        name: String,
+        name: Symbol,
         decl_location: Location,
         type_spec: TypeRef,
         ref_kind: RefKind,
         is_pervasive: bool,
     ) -> IdentId {
-        let new_id = self.make_ident(
-            name.clone(),
-            decl_location,
-            type_spec,
-            ref_kind,
-            is_pervasive,
-            true,
-        );
+        let new_id = self.make_ident(name, decl_location, type_spec, ref_kind, is_pervasive, true);
 
-        let old_id = self.get_ident_id(&name);
+        let old_id = self.get_ident_id(name);
         let block = self.current_block_mut();
         block.declare_ident(name, old_id, new_id);
 
@@ -252,13 +255,11 @@ impl UnitScope {
     ///
     /// # Returns
     /// Returns the `IdentId` referencing this identifier.
-    pub fn use_ident(&mut self, name: &str, use_location: Location) -> IdentId {
+    pub fn use_ident(&mut self, name: Symbol, use_location: Location) -> IdentId {
         let use_id = self.get_ident_id(name).unwrap_or_else(|| {
             // Declare a new undeclared identifier
-            let name = name.to_string();
-
             let unused_id = self.make_ident(
-                name.clone(),
+                name,
                 use_location,
                 TypeRef::TypeError,
                 RefKind::Var,
@@ -267,7 +268,7 @@ impl UnitScope {
             );
 
             // Declare the unused identifier at the import boundary block
-            let old_id = self.get_ident_id(&name);
+            let old_id = self.get_ident_id(name);
             let boundary = self.import_boundary_mut();
             boundary.declare_ident(name, old_id, unused_id);
 
@@ -298,12 +299,12 @@ impl UnitScope {
     ///
     /// # Returns
     /// Returns `Some(IdentId)` if an identifier has been declared, or `None` otherwise.
-    pub fn get_ident_id(&self, name: &str) -> Option<IdentId> {
+    pub fn get_ident_id(&self, name: Symbol) -> Option<IdentId> {
         // Top-down search through all blocks for an id
         let mut restrict_to_pervasive = false;
 
         for block in self.blocks.iter().rev() {
-            if let Some(id) = block.id_mappings.get(name) {
+            if let Some(id) = block.id_mappings.get(&name) {
                 let id = &id.0;
 
                 // Only allow an identifier to be fetched if we haven't
@@ -402,7 +403,7 @@ impl UnitScope {
     /// Creates a new identifier with the given attributes, giving back the associated IdentId
     fn make_ident(
         &mut self,
-        name: String,
+        name: Symbol,
         decl_location: Location,
         type_spec: TypeRef,
         ref_kind: RefKind,
@@ -426,29 +427,25 @@ impl UnitScope {
     }
 }
 
-impl Default for UnitScope {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 mod pretty_print {
     use super::{IdentId, UnitScope};
     use std::fmt;
 
     impl fmt::Display for UnitScope {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            // Just dump the identifier table
-            f.write_str("[\n")?;
-            for (id, _) in self.ident_ids.keys().enumerate() {
-                let info = self
-                    .ident_ids
-                    .get(&IdentId(id as u32))
-                    .expect("infalliable");
-
-                f.write_fmt(format_args!("{:8} -> {}", id, info))?;
-            }
-            f.write_str("]")
+            // Just dump the identifier table, with names resolved through the interner
+            self.interner.enter(|| {
+                f.write_str("[\n")?;
+                for (id, _) in self.ident_ids.keys().enumerate() {
+                    let info = self
+                        .ident_ids
+                        .get(&IdentId(id as u32))
+                        .expect("infalliable");
+
+                    f.write_fmt(format_args!("{:8} -> {}", id, info))?;
+                }
+                f.write_str("]")
+            })
         }
     }
 }
@@ -460,14 +457,19 @@ mod test {
     use crate::types::PrimitiveType;
     use toc_core::Location;
 
+    fn new_scope() -> (UnitScope, Arc<Interner>) {
+        let interner = Arc::new(Interner::new());
+        (UnitScope::new(interner.clone()), interner)
+    }
+
     #[test]
     fn test_ident_declare_use() {
         // declare | usage
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         let id = unit_scope.declare_ident(
-            String::from("a"),
+            interner.intern("a"),
             Location::new(),
             TypeRef::Primitive(PrimitiveType::Int),
             RefKind::Var,
@@ -476,19 +478,19 @@ mod test {
         let info = unit_scope.get_ident_info(&id);
         assert_eq!(info.type_spec, TypeRef::Primitive(PrimitiveType::Int));
 
-        let id = unit_scope.use_ident("a", Location::new());
+        let id = unit_scope.use_ident(interner.intern("a"), Location::new());
         let info = unit_scope.get_ident_info(&id);
         assert_eq!(info.type_spec, TypeRef::Primitive(PrimitiveType::Int));
     }
 
     #[test]
     fn test_ident_redeclare() {
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         // First decl, pass
         let initial_id = unit_scope.declare_ident(
-            String::from("a"),
+            interner.intern("a"),
             Location::new(),
             TypeRef::Primitive(PrimitiveType::Int),
             RefKind::Var,
@@ -497,7 +499,7 @@ mod test {
 
         // Redecl, have different ids
         let redeclare_id = unit_scope.declare_ident(
-            String::from("a"),
+            interner.intern("a"),
             Location::new(),
             TypeRef::Primitive(PrimitiveType::String_),
             RefKind::Var,
@@ -520,12 +522,12 @@ mod test {
     #[test]
     fn test_ident_declare_shadow() {
         // Identifier shadowing is not allow within inner scopes, but is detected later on
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         // Outer declare
         let declare_id = unit_scope.declare_ident(
-            String::from("a"),
+            interner.intern("a"),
             Location::new(),
             TypeRef::Primitive(PrimitiveType::Int),
             RefKind::Var,
@@ -540,7 +542,7 @@ mod test {
         unit_scope.push_block(BlockKind::InnerBlock);
         {
             let shadow_id = unit_scope.declare_ident(
-                String::from("a"),
+                interner.intern("a"),
                 Location::new(),
                 TypeRef::Primitive(PrimitiveType::Real),
                 RefKind::Var,
@@ -556,28 +558,32 @@ mod test {
             assert_ne!(shadow_id, declare_id);
 
             // Should be marked as shadowing things...
-            assert!(unit_scope.current_block().is_ident_shadowed("a"));
+            assert!(unit_scope
+                .current_block()
+                .is_ident_shadowed(interner.intern("a")));
             // And have a shadowing entry
             assert!(unit_scope.current_block().shadowed_idents().count() > 0);
         }
         unit_scope.pop_block();
 
         // But not in the root scope
-        assert!(!unit_scope.current_block().is_ident_shadowed("a"));
+        assert!(!unit_scope
+            .current_block()
+            .is_ident_shadowed(interner.intern("a")));
         assert!(unit_scope.current_block().shadowed_idents().count() == 0);
     }
 
     #[test]
     fn test_ident_declare_no_shadow() {
         // Declaring outer after inner scopes should not cause issues
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         // Inner declare
         unit_scope.push_block(BlockKind::InnerBlock);
         let shadow_id = {
             let shadow_id = unit_scope.declare_ident(
-                String::from("a"),
+                interner.intern("a"),
                 Location::new(),
                 TypeRef::Primitive(PrimitiveType::Real),
                 RefKind::Var,
@@ -589,14 +595,16 @@ mod test {
             );
 
             // No shadowing should be done
-            assert!(!unit_scope.current_block().is_ident_shadowed("a"));
+            assert!(!unit_scope
+                .current_block()
+                .is_ident_shadowed(interner.intern("a")));
             shadow_id
         };
         unit_scope.pop_block();
 
         // Outer declare
         let declare_id = unit_scope.declare_ident(
-            String::from("a"),
+            interner.intern("a"),
             Location::new(),
             TypeRef::Primitive(PrimitiveType::Int),
             RefKind::Var,
@@ -609,18 +617,20 @@ mod test {
         );
 
         // No shadowing should be done
-        assert!(!unit_scope.current_block().is_ident_shadowed("a"));
+        assert!(!unit_scope
+            .current_block()
+            .is_ident_shadowed(interner.intern("a")));
         // Identifiers should be different
         assert_ne!(shadow_id, declare_id);
     }
 
     #[test]
     fn test_resolve_defined() {
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         let ident = unit_scope.declare_ident(
-            String::from("a"),
+            interner.intern("a"),
             Location::new(),
             TypeRef::Unknown,
             RefKind::Var,
@@ -648,7 +658,7 @@ mod test {
     #[test]
     #[should_panic(expected = "No Identifier for given IdentId")]
     fn test_resolve_undefined() {
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, _interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         // Panics!
@@ -657,10 +667,10 @@ mod test {
 
     #[test]
     fn test_use_undefined() {
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
-        let ident = unit_scope.use_ident("a", Location::new());
+        let ident = unit_scope.use_ident(interner.intern("a"), Location::new());
         // Should be the first identifier
         assert_eq!(ident, IdentId(0));
 
@@ -673,7 +683,7 @@ mod test {
     #[test]
     fn test_use_shared_undefined() {
         // Undeclared identifiers should be hoisted to the top-most import boundary
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         // Don't contaminate root scope
@@ -685,7 +695,7 @@ mod test {
                 unit_scope.push_block(BlockKind::Function);
                 unit_scope.push_block(BlockKind::Procedure);
                 // Hoist through nested inner blocks, functions, and procedures
-                let inner_id = unit_scope.use_ident("undef", Default::default());
+                let inner_id = unit_scope.use_ident(interner.intern("undef"), Default::default());
                 unit_scope.pop_block();
                 unit_scope.pop_block();
                 unit_scope.pop_block();
@@ -694,14 +704,14 @@ mod test {
             };
 
             // Declaration should have been hoisted to module level
-            let top_level = unit_scope.use_ident("undef", Default::default());
+            let top_level = unit_scope.use_ident(interner.intern("undef"), Default::default());
             assert_eq!(inner_id, top_level);
 
             unit_scope.pop_block();
         }
 
         // Identifier should not be hoisted across the import boundary
-        let not_here = unit_scope.get_ident_id("undef");
+        let not_here = unit_scope.get_ident_id(interner.intern("undef"));
         assert!(not_here.is_none());
 
         unit_scope.pop_block();
@@ -710,12 +720,12 @@ mod test {
     #[test]
     fn test_use_import() {
         // External identifiers should be imported into the current scope (i.e share the same IdentId)
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         // Root declare
         let declare_id = unit_scope.declare_ident(
-            String::from("a"),
+            interner.intern("a"),
             Location::new(),
             TypeRef::Primitive(PrimitiveType::Int),
             RefKind::Var,
@@ -729,7 +739,7 @@ mod test {
         // Inner use
         unit_scope.push_block(BlockKind::InnerBlock);
         {
-            let import_id = unit_scope.use_ident("a", Location::new());
+            let import_id = unit_scope.use_ident(interner.intern("a"), Location::new());
 
             assert_eq!(
                 unit_scope.get_ident_info(&import_id).type_spec,
@@ -741,28 +751,46 @@ mod test {
         unit_scope.pop_block();
     }
 
+    #[test]
+    fn test_dump_resolves_names() {
+        let (mut unit_scope, interner) = new_scope();
+        unit_scope.push_block(BlockKind::Main);
+
+        unit_scope.declare_ident(
+            interner.intern("some_name"),
+            Location::new(),
+            TypeRef::Unknown,
+            RefKind::Var,
+            false,
+        );
+
+        // Names should be shown in the dump, instead of the raw symbols
+        let dump = unit_scope.to_string();
+        assert!(dump.contains("-> { some_name ty:"), "got {}", dump);
+    }
+
     #[test]
     #[should_panic(expected = "No scopes to pop off")]
     fn test_pop_all() {
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, _interner) = new_scope();
         unit_scope.pop_block();
     }
 
     #[test]
     fn test_import_boundaries() {
-        let mut unit_scope = UnitScope::new();
+        let (mut unit_scope, interner) = new_scope();
         unit_scope.push_block(BlockKind::Main);
 
         // Declare some external identifiers
         let non_pervasive = unit_scope.declare_ident(
-            "non_pervasive".to_string(),
+            interner.intern("non_pervasive"),
             Default::default(),
             TypeRef::Unknown,
             RefKind::Const,
             false,
         );
         let pervasive = unit_scope.declare_ident(
-            "pervasive".to_string(),
+            interner.intern("pervasive"),
             Default::default(),
             TypeRef::Unknown,
             RefKind::Const,
@@ -774,8 +802,8 @@ mod test {
             unit_scope.push_block(BlockKind::InnerBlock);
 
             // Should be able to access both identifiers
-            let inner_use_a = unit_scope.get_ident_id("non_pervasive");
-            let inner_use_b = unit_scope.get_ident_id("pervasive");
+            let inner_use_a = unit_scope.get_ident_id(interner.intern("non_pervasive"));
+            let inner_use_b = unit_scope.get_ident_id(interner.intern("pervasive"));
             assert_eq!(inner_use_a, Some(non_pervasive));
             assert_eq!(inner_use_b, Some(pervasive));
 
@@ -796,8 +824,8 @@ mod test {
             unit_scope.push_block(BlockKind::InnerBlock);
 
             // Should only be able to access the pervasive identifiers
-            let undecl_use_a = unit_scope.get_ident_id("non_pervasive");
-            let imported_use_b = unit_scope.get_ident_id("pervasive");
+            let undecl_use_a = unit_scope.get_ident_id(interner.intern("non_pervasive"));
+            let imported_use_b = unit_scope.get_ident_id(interner.intern("pervasive"));
 
             assert_ne!(undecl_use_a, Some(non_pervasive));
             assert_eq!(undecl_use_a, None);
@@ -9,6 +9,7 @@
 use crate::value::Value;
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use toc_core::sym::Symbol;
 
 /// Default string size, in bytes
 /// This is the default size for a string if it is not specified
@@ -126,7 +127,7 @@ pub enum PrimitiveType {
 #[derive(Debug, Clone)]
 pub struct ParamInfo {
     /// The name of the parameter
-    pub name: String,
+    pub name: Symbol,
     // Whether to pass the parameter by reference, allowing the function to modify the value (specified by "var")
     pub pass_by_ref: bool,
     // Whether to bind the parameter into a register (specified by "register")
@@ -289,7 +290,7 @@ impl TypeTable {
     /// Checks if the given type is an indirect alias for another type.
     /// This includes both Alias and Reference types.
     pub fn is_indirect_alias(&self, type_id: TypeId) -> bool {
-        matches!(self.get_type(type_id), Type::Alias{ .. })
+        matches!(self.get_type(type_id), Type::Alias { .. })
     }
 }
 
@@ -417,7 +418,10 @@ pub fn is_string_type(type_ref: &TypeRef) -> bool {
 pub fn is_char_seq_type(type_ref: &TypeRef) -> bool {
     match type_ref {
         TypeRef::Primitive(kind) => {
-            matches!(kind, PrimitiveType::String_ | PrimitiveType::StringN(_) | PrimitiveType::CharN(_))
+            matches!(
+                kind,
+                PrimitiveType::String_ | PrimitiveType::StringN(_) | PrimitiveType::CharN(_)
+            )
         }
         _ => false,
     }
@@ -580,7 +584,10 @@ pub fn is_set(type_ref: &TypeRef, type_table: &TypeTable) -> bool {
 /// Requires that `type_ref` is de-aliased (i.e. all aliased references are
 /// forwarded to the base type).
 pub fn is_enum_type(type_ref: &TypeRef, type_table: &TypeTable) -> bool {
-    matches!(type_table.type_from_ref(type_ref), Some(Type::Enum { .. }) | Some(Type::EnumField { .. }))
+    matches!(
+        type_table.type_from_ref(type_ref),
+        Some(Type::Enum { .. }) | Some(Type::EnumField { .. })
+    )
 }
 
 /// Checks if the given `type_ref` references a pointer type (`Type::Pointer`).
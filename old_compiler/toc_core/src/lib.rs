@@ -2,8 +2,10 @@
 //! Includes
 //! - Common location information
 //! - Common status reporting facility
+use std::collections::HashMap;
 use std::fmt::{self, Arguments, Display, Formatter};
 
+pub mod sym;
 pub mod token;
 
 /// Location of a token in a file/text stream
@@ -64,6 +66,48 @@ impl Location {
         }
     }
 
+    /// Creates the smallest location covering both this location and the other location,
+    /// regardless of which location comes first
+    #[must_use = "creates a new merged Location instead of mutating the current one"]
+    pub fn merge(&self, other: &Self) -> Self {
+        let first = if self.start <= other.start {
+            self
+        } else {
+            other
+        };
+        let last = if self.end >= other.end { self } else { other };
+
+        // Find where the last location ends, with the column being exclusive
+        let end_line = last.line.saturating_add(last.line_span);
+        let end_column = if last.line_span > 0 {
+            // Column restarts on a new line
+            last.width.saturating_add(1)
+        } else {
+            last.column.saturating_add(last.width)
+        };
+
+        let line_span = end_line.saturating_sub(first.line);
+        let width = if line_span > 0 {
+            end_column.saturating_sub(1)
+        } else {
+            end_column.saturating_sub(first.column)
+        };
+
+        Self {
+            start: first.start,
+            line: first.line,
+            column: first.column,
+            end: last.end,
+            width,
+            line_span,
+        }
+    }
+
+    /// Checks if the given byte index is inside of this location
+    pub fn contains(&self, byte: usize) -> bool {
+        (self.start..self.end).contains(&byte)
+    }
+
     /// Advances the location to the next lexeme, beginning a new lexeme
     pub fn step(&mut self) {
         self.start = self.end;
@@ -78,6 +122,11 @@ impl Location {
         self.width += steps;
     }
 
+    /// Advances the column location by the number of characters in `text`
+    pub fn columns_in(&mut self, text: &str) {
+        self.columns(text.chars().count());
+    }
+
     /// Advances the line location by the give amount of steps, as well as resetting the column
     pub fn lines(&mut self, steps: usize) {
         self.column = 1;
@@ -100,6 +149,18 @@ impl Location {
     pub fn get_lexeme<'a>(&self, source: &'a str) -> &'a str {
         &source[self.start..self.end]
     }
+
+    /// Gets the starting column of the lexeme, counted in characters from the start of the line
+    ///
+    /// Unlike `column`, this is derived from the byte offsets against `source`,
+    /// so tabs are only counted as a single column
+    #[must_use]
+    pub fn column_char(&self, source: &str) -> usize {
+        let before = &source[..self.start];
+        let line_start = before.rfind('\n').map_or(0, |at| at + 1);
+
+        before[line_start..].chars().count() + 1
+    }
 }
 
 impl Default for Location {
@@ -108,8 +169,264 @@ impl Default for Location {
     }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn make_loc(start: usize, end: usize, line: usize, column: usize) -> Location {
+        Location {
+            start,
+            end,
+            line,
+            column,
+            width: end - start,
+            line_span: 0,
+        }
+    }
+
+    #[test]
+    fn test_merge_same_line() {
+        // `a + bc`
+        let a = make_loc(0, 1, 1, 1);
+        let bc = make_loc(4, 6, 1, 5);
+
+        let merged = a.merge(&bc);
+        assert_eq!(merged, make_loc(0, 6, 1, 1));
+        assert_eq!(merged, a.span_to(&bc));
+    }
+
+    #[test]
+    fn test_merge_out_of_order() {
+        let a = make_loc(0, 1, 1, 1);
+        let bc = make_loc(4, 6, 1, 5);
+
+        assert_eq!(bc.merge(&a), a.merge(&bc));
+    }
+
+    #[test]
+    fn test_merge_multiple_lines() {
+        // `a +\n  bc`
+        let a = make_loc(0, 1, 1, 1);
+        let bc = make_loc(6, 8, 2, 3);
+
+        let expected = Location {
+            start: 0,
+            end: 8,
+            line: 1,
+            column: 1,
+            width: 4,
+            line_span: 1,
+        };
+
+        assert_eq!(a.merge(&bc), expected);
+        assert_eq!(bc.merge(&a), expected);
+        assert_eq!(a.span_to(&bc), expected);
+
+        // Merging with a location inside of the multi-line location keeps the same ends
+        let plus = make_loc(2, 3, 1, 3);
+        assert_eq!(expected.merge(&plus), expected);
+        assert_eq!(plus.merge(&expected), expected);
+    }
+
+    #[test]
+    fn test_merge_overlapping() {
+        let ab = make_loc(0, 2, 1, 1);
+        let bc = make_loc(1, 3, 1, 2);
+
+        assert_eq!(ab.merge(&bc), make_loc(0, 3, 1, 1));
+        assert_eq!(bc.merge(&ab), make_loc(0, 3, 1, 1));
+    }
+
+    #[test]
+    fn test_column_char() {
+        let source = "aé b\nüx";
+
+        // Byte offsets are converted into character columns
+        assert_eq!(make_loc(0, 1, 1, 1).column_char(source), 1);
+        assert_eq!(make_loc(4, 5, 1, 4).column_char(source), 4);
+        assert_eq!(make_loc(6, 8, 2, 1).column_char(source), 1);
+        assert_eq!(make_loc(8, 9, 2, 2).column_char(source), 2);
+    }
+
+    #[test]
+    fn test_contains() {
+        let loc = make_loc(2, 5, 1, 3);
+
+        assert!(!loc.contains(1));
+        assert!(loc.contains(2));
+        assert!(loc.contains(4));
+        assert!(!loc.contains(5));
+
+        // Empty locations contain nothing
+        assert!(!make_loc(2, 2, 1, 3).contains(2));
+    }
+
+    fn sorted_output(reporter: &StatusReporter, options: ReportOptions) -> Vec<String> {
+        let messages = StatusReporter::sort_messages(
+            reporter.messages.iter().map(|msg| (None, msg)),
+            options.min_severity,
+        );
+        StatusReporter::format_messages(&messages, options.max_errors)
+    }
+
+    #[test]
+    fn test_report_annotations() {
+        let mut reporter = StatusReporter::new();
+
+        reporter
+            .report_warning(&make_loc(0, 1, 1, 1), format_args!("unused variable"))
+            .with_info("prefix with `_` to silence this warning");
+        reporter
+            .report_error(&make_loc(8, 9, 2, 3), format_args!("mismatched types"))
+            .with_note("this is of type `int`", &make_loc(4, 5, 1, 5))
+            .with_note("this is of type `string`", &make_loc(8, 9, 2, 3))
+            .with_info("operands must both be the same type");
+        reporter.report_error(&make_loc(2, 3, 1, 3), format_args!("missing expression"));
+
+        assert_eq!(
+            sorted_output(&reporter, ReportOptions::default()),
+            vec![
+                "error line:1 column:3-4 missing expression",
+                "error line:2 column:3-4 mismatched types\n\
+                 | note line:1 column:5-6: this is of type `int`\n\
+                 | note line:2 column:3-4: this is of type `string`\n\
+                 | info: operands must both be the same type",
+                "warn line:1 column:1-2 unused variable\n\
+                 | info: prefix with `_` to silence this warning",
+            ]
+        );
+        assert!(reporter.has_error());
+    }
+
+    #[test]
+    fn test_report_mute_warnings() {
+        let mut reporter = StatusReporter::new();
+
+        reporter
+            .report_warning(&make_loc(0, 1, 1, 1), format_args!("unused variable"))
+            .with_note("declared here", &make_loc(0, 1, 1, 1));
+        assert!(sorted_output(&reporter, ReportOptions::with_muted_warnings(true)).is_empty());
+        assert!(!reporter.has_error());
+    }
+
+    #[test]
+    fn test_report_code_levels() {
+        let at = make_loc(0, 1, 1, 1);
+        let mut reporter = StatusReporter::new();
+        reporter.set_level(MessageCode::UnusedVar, Level::Allow);
+        reporter.set_level(MessageCode::EquAsAssign, Level::Deny);
+
+        assert!(reporter
+            .report_warning_with_code(MessageCode::UnusedVar, &at, format_args!("unused"))
+            .is_none());
+        reporter.report_warning_with_code(MessageCode::EquAsAssign, &at, format_args!("equ"));
+        reporter.report_warning_with_code(MessageCode::AltKeyword, &at, format_args!("alt"));
+
+        assert_eq!(
+            sorted_output(&reporter, ReportOptions::default()),
+            vec!["error line:1 column:1-2 equ", "warn line:1 column:1-2 alt"]
+        );
+        assert_eq!(reporter.messages[0].code(), Some(MessageCode::EquAsAssign));
+        assert!(reporter.has_error());
+    }
+
+    #[test]
+    fn test_report_min_severity() {
+        let mut reporter = StatusReporter::new();
+
+        reporter.report_warning(&make_loc(0, 1, 1, 1), format_args!("unused variable"));
+        reporter.report_error(&make_loc(2, 3, 1, 3), format_args!("missing expression"));
+
+        let options = ReportOptions {
+            min_severity: ReportKind::Error,
+            max_errors: None,
+        };
+        assert_eq!(
+            sorted_output(&reporter, options),
+            vec!["error line:1 column:3-4 missing expression"]
+        );
+    }
+
+    #[test]
+    fn test_report_error_cap() {
+        let mut reporter = StatusReporter::new();
+
+        for start in (0..5).rev() {
+            reporter.report_error(
+                &make_loc(start, start + 1, 1, start + 1),
+                format_args!("error {}", start),
+            );
+        }
+        reporter.report_warning(&make_loc(0, 1, 1, 1), format_args!("unused variable"));
+
+        let options = ReportOptions {
+            max_errors: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted_output(&reporter, options),
+            vec![
+                "error line:1 column:1-2 error 0",
+                "error line:1 column:2-3 error 1",
+                "... and 3 more errors",
+            ]
+        );
+
+        // Only one error left out
+        let options = ReportOptions {
+            max_errors: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted_output(&reporter, options).last().unwrap(),
+            "... and 1 more error"
+        );
+
+        // Warnings are still reported if the cap isn't reached
+        let options = ReportOptions {
+            max_errors: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted_output(&reporter, options).last().unwrap(),
+            "warn line:1 column:1-2 unused variable"
+        );
+    }
+
+    #[test]
+    fn test_report_file_groups() {
+        let mut reporter = StatusReporter::new();
+
+        reporter.report_warning(&make_loc(0, 1, 1, 1), format_args!("in b"));
+        reporter.report_error(&make_loc(4, 5, 1, 5), format_args!("in a"));
+        reporter.report_warning(&make_loc(0, 1, 1, 1), format_args!("in a too"));
+        reporter.report_error(&make_loc(2, 3, 1, 3), format_args!("in b too"));
+        reporter.report_error(&make_loc(0, 1, 1, 1), format_args!("not in a file"));
+
+        let files = [Some("b.t"), Some("a.t"), Some("a.t"), Some("b.t"), None];
+        let messages = StatusReporter::sort_messages(
+            files.iter().copied().zip(reporter.messages.iter()),
+            ReportKind::Warning,
+        );
+
+        assert_eq!(
+            StatusReporter::format_messages(&messages, None),
+            vec![
+                "error line:1 column:1-2 not in a file",
+                "--> b.t",
+                "error line:1 column:3-4 in b too",
+                "warn line:1 column:1-2 in b",
+                "--> a.t",
+                "error line:1 column:5-6 in a",
+                "warn line:1 column:1-2 in a too",
+            ]
+        );
+    }
+}
+
 // Status reporter
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+/// Kind of reported message, ordered from most to least severe
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub enum ReportKind {
     Error,
     Warning,
@@ -124,11 +441,224 @@ impl Display for ReportKind {
     }
 }
 
-#[derive(Debug)]
+/// Compilation stage that a message was reported from
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Stage {
+    Scanner,
+    Parser,
+    Validator,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Stage::Scanner => f.write_str("scanner"),
+            Stage::Parser => f.write_str("parser"),
+            Stage::Validator => f.write_str("validator"),
+        }
+    }
+}
+
+/// Category of a warning, used to configure how the warning is reported
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MessageCode {
+    /// `=` used in place of `:=`
+    EquAsAssign,
+    /// Alternate spelling of a keyword (e.g. `elif` instead of `elsif`)
+    AltKeyword,
+    /// Function type declaration without `()` after the name
+    MissingFcnParens,
+    /// Declared identifier which is never used
+    UnusedVar,
+    /// Range type whose size can't be represented
+    RangeSizeOverflow,
+    /// Compile-time division by zero
+    ConstDivByZero,
+}
+
+impl MessageCode {
+    /// All of the message codes
+    pub const ALL: &'static [MessageCode] = &[
+        MessageCode::EquAsAssign,
+        MessageCode::AltKeyword,
+        MessageCode::MissingFcnParens,
+        MessageCode::UnusedVar,
+        MessageCode::RangeSizeOverflow,
+        MessageCode::ConstDivByZero,
+    ];
+
+    /// Gets the name of the code, as given on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageCode::EquAsAssign => "equ_as_assign",
+            MessageCode::AltKeyword => "alt_keyword",
+            MessageCode::MissingFcnParens => "missing_fcn_parens",
+            MessageCode::UnusedVar => "unused_var",
+            MessageCode::RangeSizeOverflow => "range_size_overflow",
+            MessageCode::ConstDivByZero => "const_div_by_zero",
+        }
+    }
+
+    /// Looks up a code from its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|code| code.name() == name)
+    }
+}
+
+impl Display for MessageCode {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str(self.name())
+    }
+}
+
+/// How a category of warnings is reported
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Level {
+    /// Warnings are not reported
+    Allow,
+    /// Warnings are reported as warnings
+    Warn,
+    /// Warnings are reported as errors
+    Deny,
+}
+
+#[derive(Debug, Clone)]
 pub struct ReportMessage {
     kind: ReportKind,
+    /// Category of the message, if it has one
+    code: Option<MessageCode>,
     at: Location,
     message: String,
+    /// Secondary locations related to the message
+    annotations: Vec<ReportAnnotation>,
+    /// Extra notes shown after everything else
+    footer: Vec<String>,
+}
+
+impl ReportMessage {
+    fn new(kind: ReportKind, at: Location, message: String) -> Self {
+        Self {
+            kind,
+            code: None,
+            at,
+            message,
+            annotations: vec![],
+            footer: vec![],
+        }
+    }
+
+    /// Gets the kind of message reported
+    pub fn kind(&self) -> ReportKind {
+        self.kind
+    }
+
+    /// Gets the location the message was reported at
+    pub fn at(&self) -> &Location {
+        &self.at
+    }
+
+    /// Gets the category of the message, if it has one
+    pub fn code(&self) -> Option<MessageCode> {
+        self.code
+    }
+
+    /// Attaches a note at a secondary location
+    pub fn with_note(&mut self, label: &str, at: &Location) -> &mut Self {
+        self.annotations.push(ReportAnnotation {
+            at: *at,
+            label: label.to_string(),
+        });
+        self
+    }
+
+    /// Attaches a note without a location, shown after all other notes
+    pub fn with_info(&mut self, note: &str) -> &mut Self {
+        self.footer.push(note.to_string());
+        self
+    }
+}
+
+impl Display for ReportMessage {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{} {} {}",
+            self.kind,
+            DisplayLocation(&self.at),
+            self.message
+        )?;
+
+        for annotation in &self.annotations {
+            write!(
+                f,
+                "\n| note {}: {}",
+                DisplayLocation(&annotation.at),
+                annotation.label
+            )?;
+        }
+
+        for note in &self.footer {
+            write!(f, "\n| info: {}", note)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A note attached to a secondary location
+#[derive(Debug, Clone)]
+struct ReportAnnotation {
+    at: Location,
+    label: String,
+}
+
+/// Displays the line and column range of a location
+struct DisplayLocation<'a>(&'a Location);
+
+impl Display for DisplayLocation<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        let at = self.0;
+        let end_column = at.column + at.width;
+
+        write!(f, "line:{} column:{}-{}", at.line, at.column, end_column)
+    }
+}
+
+/// Default number of errors to report before giving up
+pub const DEFAULT_MAX_ERRORS: usize = 100;
+
+/// Options for [`StatusReporter::report_messages`]
+#[derive(Debug, Clone, Copy)]
+pub struct ReportOptions {
+    /// Least severe kind of message to report
+    pub min_severity: ReportKind,
+    /// Maximum number of errors to report, or `None` to report all of them
+    pub max_errors: Option<usize>,
+}
+
+impl ReportOptions {
+    /// Reports all messages, except for warnings if `mute_warnings` is true
+    pub fn with_muted_warnings(mute_warnings: bool) -> Self {
+        let min_severity = if mute_warnings {
+            ReportKind::Error
+        } else {
+            ReportKind::Warning
+        };
+
+        Self {
+            min_severity,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            min_severity: ReportKind::Warning,
+            max_errors: Some(DEFAULT_MAX_ERRORS),
+        }
+    }
 }
 
 /// Common status reporter
@@ -138,6 +668,8 @@ pub struct StatusReporter {
     has_error: bool,
     /// Messages to report
     messages: Vec<ReportMessage>,
+    /// Reporting levels of warning categories, with unlisted ones being `Level::Warn`
+    levels: HashMap<MessageCode, Level>,
 }
 
 impl StatusReporter {
@@ -145,54 +677,116 @@ impl StatusReporter {
         Self {
             has_error: false,
             messages: vec![],
+            levels: HashMap::new(),
         }
     }
 
-    /// Reports messages given by an iterator
+    /// Sets how warnings with the given `code` are reported
+    pub fn set_level(&mut self, code: MessageCode, level: Level) {
+        self.levels.insert(code, level);
+    }
+
+    /// Reports messages given by an iterator, along with the path of the file
+    /// each message is from.
+    ///
+    /// Messages are grouped by file, with each group headed by a `--> path` line.
+    /// Files are reported in the order they first appear in, and messages
+    /// without a file are reported before all of the files.
     ///
     /// # Returns
     /// Returns true if any errors were reported
     pub fn report_messages<'m>(
-        messages: impl Iterator<Item = &'m ReportMessage>,
-        mute_warnings: bool,
+        messages: impl Iterator<Item = (Option<&'m str>, &'m ReportMessage)>,
+        options: &ReportOptions,
     ) -> bool {
-        use std::cmp::Ordering;
+        let messages = Self::sort_messages(messages, options.min_severity);
+        let reported_errors = messages
+            .iter()
+            .any(|(_, reporting)| reporting.kind == ReportKind::Error);
 
-        let mut messages: Vec<&ReportMessage> = messages.collect();
+        for line in Self::format_messages(&messages, options.max_errors) {
+            eprintln!("{}", line);
+        }
 
-        messages.sort_by(|a, b| {
-            // All errors before warnings
-            let ordering = a.kind.cmp(&b.kind);
+        reported_errors
+    }
 
-            if ordering == Ordering::Equal {
-                a.at.start.cmp(&b.at.start)
-            } else {
-                ordering
+    /// Formats sorted messages for reporting, starting each file's group of
+    /// messages with a `--> path` header.
+    ///
+    /// Stops after `max_errors` errors, noting how many errors were left out.
+    fn format_messages(
+        messages: &[(Option<&str>, &ReportMessage)],
+        max_errors: Option<usize>,
+    ) -> Vec<String> {
+        let total_errors = messages
+            .iter()
+            .filter(|(_, reporting)| reporting.kind == ReportKind::Error)
+            .count();
+        let mut shown_errors = 0;
+        let mut current_file = None;
+        let mut lines = vec![];
+
+        for (file, reporting) in messages {
+            if reporting.kind == ReportKind::Error {
+                if Some(shown_errors) == max_errors {
+                    let remaining = total_errors - shown_errors;
+                    let plural = if remaining == 1 { "" } else { "s" };
+
+                    lines.push(format!("... and {} more error{}", remaining, plural));
+                    break;
+                }
+
+                shown_errors += 1;
             }
-        });
 
-        let mut reported_errors = false;
+            if *file != current_file {
+                current_file = *file;
 
-        for reporting in messages {
-            if mute_warnings && reporting.kind == ReportKind::Warning {
-                continue;
+                if let Some(path) = file {
+                    lines.push(format!("--> {}", path));
+                }
             }
 
-            reported_errors |= reporting.kind == ReportKind::Error;
+            lines.push(reporting.to_string());
+        }
 
-            let end_column = reporting.at.column + reporting.at.width;
+        lines
+    }
 
-            eprintln!(
-                "{} line:{} column:{}-{} {}",
-                reporting.kind,
-                reporting.at.line,
-                reporting.at.column,
-                end_column,
-                reporting.message
-            );
-        }
+    /// Sorts messages into reporting order, grouped by file.
+    /// Within each file, all errors come before warnings.
+    /// Messages less severe than `min_severity` are excluded.
+    fn sort_messages<'m>(
+        messages: impl Iterator<Item = (Option<&'m str>, &'m ReportMessage)>,
+        min_severity: ReportKind,
+    ) -> Vec<(Option<&'m str>, &'m ReportMessage)> {
+        let mut files: Vec<Option<&str>> = vec![None];
+        let mut messages: Vec<_> = messages
+            .filter(|(_, reporting)| reporting.kind <= min_severity)
+            .map(|(file, reporting)| {
+                // Files are ordered by first appearance
+                let file_order = match files.iter().position(|other| *other == file) {
+                    Some(order) => order,
+                    None => {
+                        files.push(file);
+                        files.len() - 1
+                    }
+                };
+
+                (file_order, file, reporting)
+            })
+            .collect();
+
+        messages.sort_by_key(|(file_order, _, reporting)| {
+            // All errors before warnings
+            (*file_order, reporting.kind, reporting.at.start)
+        });
 
-        reported_errors
+        messages
+            .into_iter()
+            .map(|(_, file, reporting)| (file, reporting))
+            .collect()
     }
 
     /// Takes all stored messages
@@ -200,26 +794,67 @@ impl StatusReporter {
         std::mem::take(&mut self.messages)
     }
 
-    fn report_at(&mut self, reporting: ReportMessage) {
+    fn report_at(&mut self, reporting: ReportMessage) -> &mut ReportMessage {
         self.messages.push(reporting);
+        self.messages.last_mut().unwrap()
     }
 
-    pub fn report_error<'k>(&'k mut self, at: &Location, message: Arguments<'k>) {
+    /// Reports an error at the given location.
+    ///
+    /// Notes can be attached to the returned message.
+    pub fn report_error<'k>(
+        &'k mut self,
+        at: &Location,
+        message: Arguments<'k>,
+    ) -> &'k mut ReportMessage {
         self.has_error = true;
 
-        self.report_at(ReportMessage {
-            kind: ReportKind::Error,
-            at: *at,
-            message: message.to_string(),
-        });
+        self.report_at(ReportMessage::new(
+            ReportKind::Error,
+            *at,
+            message.to_string(),
+        ))
     }
 
-    pub fn report_warning<'k>(&'k mut self, at: &Location, message: Arguments<'k>) {
-        self.report_at(ReportMessage {
-            kind: ReportKind::Warning,
-            at: *at,
-            message: message.to_string(),
-        });
+    /// Reports a warning at the given location.
+    ///
+    /// Notes can be attached to the returned message.
+    pub fn report_warning<'k>(
+        &'k mut self,
+        at: &Location,
+        message: Arguments<'k>,
+    ) -> &'k mut ReportMessage {
+        self.report_at(ReportMessage::new(
+            ReportKind::Warning,
+            *at,
+            message.to_string(),
+        ))
+    }
+
+    /// Reports a warning in the given category at the given location.
+    ///
+    /// Depending on the category's level, the warning may be reported as an error,
+    /// or not reported at all.
+    ///
+    /// # Returns
+    /// Returns the reported message, which notes can be attached to,
+    /// or `None` if the category is allowed
+    pub fn report_warning_with_code<'k>(
+        &'k mut self,
+        code: MessageCode,
+        at: &Location,
+        message: Arguments<'k>,
+    ) -> Option<&'k mut ReportMessage> {
+        let level = self.levels.get(&code).copied().unwrap_or(Level::Warn);
+
+        let reporting = match level {
+            Level::Allow => return None,
+            Level::Warn => self.report_warning(at, message),
+            Level::Deny => self.report_error(at, message),
+        };
+
+        reporting.code = Some(code);
+        Some(reporting)
     }
 
     pub fn has_error(&self) -> bool {
@@ -235,6 +870,15 @@ impl Default for StatusReporter {
 
 /// Anything which reports messages
 pub trait MessageSource {
+    /// Takes reported messages from an integrated reporter, along with the
+    /// stage each message was reported from
+    fn take_staged_messages(&mut self) -> Vec<(Stage, ReportMessage)>;
+
     /// Takes reported messages from an integrated reporter
-    fn take_reported_messages(&mut self) -> Vec<ReportMessage>;
+    fn take_reported_messages(&mut self) -> Vec<ReportMessage> {
+        self.take_staged_messages()
+            .into_iter()
+            .map(|(_, reporting)| reporting)
+            .collect()
+    }
 }
@@ -0,0 +1,196 @@
+//! Identifier interning
+//!
+//! Identifier names are interned into `Symbol`s, which are cheap to copy, hash, and compare.
+//! The `Interner` holding the actual names is shared through the `CompileContext`.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// A handle to an interned name
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Gets the raw index of the symbol
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// Interns names into `Symbol`s, and resolves `Symbol`s back into names
+///
+/// Interning can be done from multiple threads at once.
+#[derive(Debug, Default)]
+pub struct Interner {
+    inner: RwLock<InternerInner>,
+}
+
+#[derive(Debug, Default)]
+struct InternerInner {
+    /// Mapping of names to the corresponding symbols
+    symbols: HashMap<Arc<str>, Symbol>,
+    /// All interned names, indexed by `Symbol`
+    names: Vec<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns the given name, giving back the associated `Symbol`.
+    ///
+    /// Interning the same name always gives back the same `Symbol`.
+    pub fn intern(&self, name: &str) -> Symbol {
+        if let Some(symbol) = self.get(name) {
+            return symbol;
+        }
+
+        let mut inner = self.inner.write().unwrap();
+
+        // Might have been interned between dropping the read lock and acquiring the write lock
+        if let Some(symbol) = inner.symbols.get(name) {
+            return *symbol;
+        }
+
+        // Can't have more than 4 billion unique names
+        let index = u32::try_from(inner.names.len()).expect("Too many interned names");
+        let symbol = Symbol(index);
+        let name: Arc<str> = Arc::from(name);
+
+        inner.names.push(name.clone());
+        inner.symbols.insert(name, symbol);
+
+        symbol
+    }
+
+    /// Gets the `Symbol` for an already interned name, or `None` if the name hasn't been interned yet
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.inner.read().unwrap().symbols.get(name).copied()
+    }
+
+    /// Resolves a `Symbol` back into the interned name
+    ///
+    /// # Panics
+    /// Will panic with "Symbol not from this interner" if the symbol was made by a different interner.
+    pub fn resolve(&self, symbol: Symbol) -> Arc<str> {
+        self.inner
+            .read()
+            .unwrap()
+            .names
+            .get(symbol.0 as usize)
+            .cloned()
+            .expect("Symbol not from this interner")
+    }
+
+    /// Gets the number of interned names
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().names.len()
+    }
+
+    /// Checks if there are no interned names
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Runs `f` with this interner being used to display `Symbol`s on the current thread.
+    ///
+    /// Outside of `enter`, `Symbol`s are displayed by their raw index.
+    pub fn enter<R>(self: &Arc<Self>, f: impl FnOnce() -> R) -> R {
+        // Restore the previous interner afterwards, even if `f` panics
+        struct Restore(Option<Arc<Interner>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT_INTERNER.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let previous = CURRENT_INTERNER.with(|current| current.replace(Some(self.clone())));
+        let _restore = Restore(previous);
+
+        f()
+    }
+}
+
+thread_local! {
+    /// Interner used for displaying symbols on this thread
+    static CURRENT_INTERNER: RefCell<Option<Arc<Interner>>> = RefCell::new(None);
+}
+
+/// Resolves the symbol through the current interner, if there is one
+fn with_current_name<R>(symbol: Symbol, f: impl FnOnce(Option<&str>) -> R) -> R {
+    CURRENT_INTERNER.with(|current| {
+        let name = current.borrow().as_ref().and_then(|interner| {
+            interner
+                .inner
+                .read()
+                .unwrap()
+                .names
+                .get(symbol.0 as usize)
+                .cloned()
+        });
+
+        f(name.as_deref())
+    })
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        with_current_name(*self, |name| match name {
+            Some(name) => f.write_str(name),
+            None => f.write_fmt(format_args!("#{}", self.0)),
+        })
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        with_current_name(*self, |name| match name {
+            Some(name) => f.write_fmt(format_args!("Symbol({:?})", name)),
+            None => f.write_fmt(format_args!("Symbol(#{})", self.0)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intern_same_name() {
+        let interner = Interner::new();
+
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+
+        assert_eq!(a, interner.intern("a"));
+        assert_ne!(a, b);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get("b"), Some(b));
+        assert_eq!(interner.get("c"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let interner = Interner::new();
+
+        let sym = interner.intern("some_name");
+        assert_eq!(&*interner.resolve(sym), "some_name");
+    }
+
+    #[test]
+    fn test_display_through_interner() {
+        let interner = Arc::new(Interner::new());
+        let sym = interner.intern("a");
+
+        assert_eq!(interner.enter(|| sym.to_string()), "a");
+        assert_eq!(interner.enter(|| format!("{:?}", sym)), "Symbol(\"a\")");
+
+        // Not resolvable outside of the interner
+        assert_eq!(sym.to_string(), "#0");
+    }
+}
@@ -1,9 +1,12 @@
 //! Compile context things
 use toc_ast::unit::{CodeUnit, UnitId};
-use toc_core::{MessageSource, ReportMessage};
+use toc_core::sym::Interner;
+use toc_core::{
+    Level, MessageCode, MessageSource, ReportMessage, ReportOptions, Stage, StatusReporter,
+};
 
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{Arc, Mutex};
 
 /// Status of compilation
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -81,6 +84,8 @@ impl SourceMap {
 pub struct CompileSession {
     compiled_units: HashMap<UnitId, CodeUnit>,
     compile_ctx: Option<Arc<CompileContext>>,
+    /// Reporting levels of warning categories
+    message_levels: Vec<(MessageCode, Level)>,
 }
 
 impl CompileSession {
@@ -88,9 +93,16 @@ impl CompileSession {
         Self {
             compiled_units: HashMap::new(),
             compile_ctx: None,
+            message_levels: vec![],
         }
     }
 
+    /// Sets how warnings with the given `code` are reported.
+    /// Must be called before compilation.
+    pub fn set_message_level(&mut self, code: MessageCode, level: Level) {
+        self.message_levels.push((code, level));
+    }
+
     /// Gets an iterator over all of the code units
     pub fn units(&self) -> impl Iterator<Item = (&UnitId, &CodeUnit)> {
         self.compiled_units.iter()
@@ -114,7 +126,6 @@ impl CompileSession {
     ) -> (Option<UnitId>, CompileStatus) {
         use crate::{parser::Parser, scanner::Scanner, validator::Validator};
         use toc_ast::ast::VisitorMut;
-        use toc_core::StatusReporter;
 
         // TODO: Replace below with a preprocessing stage
         let mut sources = SourceMap::new();
@@ -128,13 +139,18 @@ impl CompileSession {
         let pending_compiles = vec![main_unit];
 
         // Make a new ctx
-        let ctx = CompileContext::new(sources);
+        let mut ctx = CompileContext::new(sources);
+        for (code, level) in &self.message_levels {
+            ctx.set_message_level(*code, *level);
+        }
         let ctx = Arc::new(ctx);
         self.compile_ctx = Some(ctx.clone());
 
         // Spin up scanners & parsers to any pending paths
 
         for unit_id in pending_compiles {
+            ctx.attach_source(unit_id);
+
             let source = &ctx
                 .source_map()
                 .get_unit_info(unit_id)
@@ -152,7 +168,8 @@ impl CompileSession {
         if only_parser {
             // Only the parser stage is to be run
             // Report status messages
-            let has_errors = StatusReporter::report_messages(ctx.messages().iter(), mute_warnings);
+            let has_errors =
+                ctx.report_messages(&ReportOptions::with_muted_warnings(mute_warnings));
 
             let status = match !has_errors {
                 true => CompileStatus::Success,
@@ -165,7 +182,9 @@ impl CompileSession {
         // TODO: Provide inter-unit type resolution stage
 
         // Run AST validator stage
-        for (_, code_unit) in &mut self.compiled_units {
+        for (unit_id, code_unit) in &mut self.compiled_units {
+            ctx.attach_source(*unit_id);
+
             let mut validator = Validator::new(
                 &mut code_unit.unit_scope,
                 &mut code_unit.type_table,
@@ -177,7 +196,7 @@ impl CompileSession {
         }
 
         // Report status messages
-        let has_errors = StatusReporter::report_messages(ctx.messages().iter(), mute_warnings);
+        let has_errors = ctx.report_messages(&ReportOptions::with_muted_warnings(mute_warnings));
 
         let status = match !has_errors {
             true => CompileStatus::Success,
@@ -202,39 +221,254 @@ impl CompileSession {
     }
 }
 
+/// A message aggregated into a [`CompileContext`], along with where it came from
+#[derive(Debug, Clone)]
+pub struct ContextMessage {
+    /// Unit that the message was reported in, or `None` if no source was attached
+    pub unit_id: Option<UnitId>,
+    /// Stage that the message was reported from
+    pub stage: Stage,
+    pub message: ReportMessage,
+}
+
 /// Context for the current compilation session
 #[derive(Debug)]
 pub struct CompileContext {
     // message aggregator, unit source map, etc.
-    /// All aggregated messages
-    messages: Mutex<Vec<ReportMessage>>,
+    /// All aggregated messages, in aggregation order
+    messages: Mutex<Vec<ContextMessage>>,
+    /// Unit that newly aggregated messages are attached to
+    current_source: Mutex<Option<UnitId>>,
     /// Source map for all units
     source_map: SourceMap,
+    /// Interner for all identifier names
+    interner: Arc<Interner>,
+    /// Reporting levels of warning categories
+    message_levels: HashMap<MessageCode, Level>,
 }
 
 impl CompileContext {
     pub fn new(source_map: SourceMap) -> Self {
         Self {
             messages: Mutex::new(vec![]),
+            current_source: Mutex::new(None),
             source_map,
+            interner: Arc::new(Interner::new()),
+            message_levels: HashMap::new(),
         }
     }
 
-    /// Takes reported messages from the given message source
+    /// Sets how warnings with the given `code` are reported
+    pub fn set_message_level(&mut self, code: MessageCode, level: Level) {
+        self.message_levels.insert(code, level);
+    }
+
+    /// Makes a new status reporter, using the context's warning category levels
+    pub fn make_reporter(&self) -> StatusReporter {
+        let mut reporter = StatusReporter::new();
+
+        for (code, level) in &self.message_levels {
+            reporter.set_level(*code, *level);
+        }
+
+        reporter
+    }
+
+    /// Attaches all messages aggregated after this to the given unit
+    pub fn attach_source(&self, unit_id: UnitId) {
+        *self.current_source.lock().unwrap() = Some(unit_id);
+    }
+
+    /// Takes reported messages from the given message source,
+    /// attaching them to the current source
     pub fn aggregate_messages(&self, message_source: &mut impl MessageSource) {
+        let unit_id = *self.current_source.lock().unwrap();
+        let messages = message_source.take_staged_messages();
+
         self.messages
             .lock()
             .unwrap()
-            .append(&mut message_source.take_reported_messages())
+            .extend(messages.into_iter().map(|(stage, message)| ContextMessage {
+                unit_id,
+                stage,
+                message,
+            }));
+    }
+
+    /// Gets all of the aggregated messages, grouped by unit in the order the
+    /// units were first attached.
+    ///
+    /// Within each unit, messages are sorted by location and then by stage,
+    /// with messages from the same stage keeping their aggregation order.
+    pub fn all_messages(&self) -> Vec<ContextMessage> {
+        let mut messages = self.messages.lock().unwrap().clone();
+        let mut units = vec![];
+
+        for msg in &messages {
+            if !units.contains(&msg.unit_id) {
+                units.push(msg.unit_id);
+            }
+        }
+
+        messages.sort_by_key(|msg| {
+            let unit_order = units.iter().position(|unit| *unit == msg.unit_id);
+            (unit_order, msg.message.at().start, msg.stage)
+        });
+
+        messages
     }
 
-    /// Gives a reference to all of the aggregated messages
-    pub fn messages(&self) -> MutexGuard<Vec<ReportMessage>> {
-        self.messages.lock().unwrap()
+    /// Gets the aggregated messages for the given unit, sorted by location
+    pub fn messages_for(&self, unit_id: UnitId) -> Vec<ReportMessage> {
+        self.all_messages()
+            .into_iter()
+            .filter(|msg| msg.unit_id == Some(unit_id))
+            .map(|msg| msg.message)
+            .collect()
+    }
+
+    /// Reports all of the aggregated messages, grouped by the path of the
+    /// unit they're from
+    ///
+    /// # Returns
+    /// Returns true if any errors were reported
+    pub fn report_messages(&self, options: &ReportOptions) -> bool {
+        let messages = self.all_messages();
+
+        StatusReporter::report_messages(
+            messages.iter().map(|msg| {
+                let path = msg
+                    .unit_id
+                    .and_then(|unit_id| self.source_map.get_unit_info(unit_id))
+                    .map(|info| info.path.as_str());
+
+                (path, &msg.message)
+            }),
+            options,
+        )
     }
 
     /// Gives a reference to the unit source maps
     pub fn source_map(&self) -> &SourceMap {
         &self.source_map
     }
+
+    /// Gives a reference to the identifier name interner
+    pub fn interner(&self) -> &Arc<Interner> {
+        &self.interner
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parser::Parser, scanner::Scanner, validator::Validator};
+    use toc_ast::ast::VisitorMut;
+
+    /// Compiles all of the units in `sources`, in order
+    fn compile_sources(sources: &[(&str, &str)]) -> (Arc<CompileContext>, Vec<UnitId>) {
+        let mut source_map = SourceMap::new();
+        let units: Vec<UnitId> = sources
+            .iter()
+            .map(|(path, source)| source_map.add_unit(path, source.to_string()))
+            .collect();
+        let ctx = Arc::new(CompileContext::new(source_map));
+
+        for unit_id in &units {
+            ctx.attach_source(*unit_id);
+
+            let source = &ctx.source_map().get_unit_info(*unit_id).unwrap().source;
+            let mut parser = Parser::new(Scanner::scan_source(source), true, ctx.clone());
+            parser.parse();
+            ctx.aggregate_messages(&mut parser);
+
+            let mut code_unit = parser.take_unit();
+            let mut validator = Validator::new(
+                &mut code_unit.unit_scope,
+                &mut code_unit.type_table,
+                ctx.clone(),
+            );
+            validator.visit_stmt(&mut code_unit.root_stmt);
+            ctx.aggregate_messages(&mut validator);
+        }
+
+        (ctx, units)
+    }
+
+    #[test]
+    fn test_messages_by_source() {
+        let (ctx, units) = compile_sources(&[
+            ("a.t", "var a : int := 1 + \"s\"\nvar b : int = 2 $"),
+            ("b.t", "var c : int = 3"),
+        ]);
+
+        let all_messages: Vec<_> = ctx
+            .all_messages()
+            .into_iter()
+            .map(|msg| (msg.unit_id, msg.stage, msg.message.to_string()))
+            .collect();
+
+        let (a, b) = (Some(units[0]), Some(units[1]));
+
+        // Grouped by unit, then ordered by location and stage
+        assert_eq!(
+            all_messages,
+            vec![
+                (
+                    a,
+                    Stage::Validator,
+                    "warn line:1 column:5-6 This declaration of 'a' is never used".to_string()
+                ),
+                (
+                    a,
+                    Stage::Validator,
+                    "error line:1 column:18-19 Operands of '+' must both be scalars (int, real, or nat), strings, or compatible sets".to_string()
+                ),
+                (
+                    a,
+                    Stage::Validator,
+                    "warn line:2 column:5-6 This declaration of 'b' is never used".to_string()
+                ),
+                (
+                    a,
+                    Stage::Parser,
+                    "warn line:2 column:13-14 '=' found, assumed it to be ':='".to_string()
+                ),
+                (
+                    a,
+                    Stage::Scanner,
+                    "error line:2 column:17-17 Unrecognized character '$'".to_string()
+                ),
+                (
+                    a,
+                    Stage::Parser,
+                    "error line:2 column:17-18 '$' does not begin a statement or declaration"
+                        .to_string()
+                ),
+                (
+                    b,
+                    Stage::Validator,
+                    "warn line:1 column:5-6 This declaration of 'c' is never used".to_string()
+                ),
+                (
+                    b,
+                    Stage::Parser,
+                    "warn line:1 column:13-14 '=' found, assumed it to be ':='".to_string()
+                ),
+            ]
+        );
+
+        let b_messages: Vec<_> = ctx
+            .messages_for(units[1])
+            .iter()
+            .map(|msg| msg.to_string())
+            .collect();
+        assert_eq!(
+            b_messages,
+            vec![
+                "warn line:1 column:5-6 This declaration of 'c' is never used",
+                "warn line:1 column:13-14 '=' found, assumed it to be ':='"
+            ]
+        );
+    }
 }
@@ -13,4 +13,3 @@ extern crate lazy_static;
 
 #[cfg(test)]
 extern crate rand;
-extern crate unicode_segmentation;
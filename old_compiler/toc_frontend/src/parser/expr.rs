@@ -307,7 +307,14 @@ impl<'s> Parser<'s> {
 
     fn expr_binary(&mut self, lhs: Expr) -> ParseResult<Expr> {
         let op = self.previous().clone();
-        let precedence = Parser::get_rule(&op.token_type).precedence.up();
+        let precedence = Parser::get_rule(&op.token_type).precedence;
+
+        // Exponents are right associative, so the rhs can contain another exponent
+        let precedence = if op.token_type == TokenType::Exp {
+            precedence
+        } else {
+            precedence.up()
+        };
         let rhs = self.expr_precedence(precedence);
 
         let span = lhs.get_span().span_to(&self.previous().location);
@@ -12,8 +12,9 @@ use toc_ast::ast::stmt::{Block, BlockKind, Stmt, StmtKind};
 use toc_ast::scope;
 use toc_ast::types::{Type, TypeRef, TypeTable};
 use toc_ast::unit::CodeUnit;
+use toc_core::sym::Symbol;
 use toc_core::token::{Token, TokenType};
-use toc_core::{Location, StatusReporter};
+use toc_core::{Location, MessageCode, StatusReporter};
 
 use std::cell::RefCell;
 use std::collections::HashSet;
@@ -66,9 +67,12 @@ pub struct Parser<'s> {
 
 impl<'s> Parser<'s> {
     pub fn new(mut scanner: Scanner<'s>, is_main: bool, context: Arc<CompileContext>) -> Self {
+        let unit_scope = scope::UnitScope::new(context.interner().clone());
+        let reporter = RefCell::new(context.make_reporter());
+
         Self {
             context,
-            reporter: RefCell::new(StatusReporter::new()),
+            reporter,
             previous: Token::new(TokenType::Error, Location::new(), ""),
             current: scanner.next().unwrap_or_else(|| scanner.make_eof_here()),
             peek: scanner.next().unwrap_or_else(|| scanner.make_eof_here()),
@@ -81,7 +85,7 @@ impl<'s> Parser<'s> {
             forward_refs: HashSet::new(),
 
             is_main,
-            unit_scope: scope::UnitScope::new(),
+            unit_scope,
             root_stmt: None,
             type_table: TypeTable::new(),
         }
@@ -109,8 +113,8 @@ impl<'s> Parser<'s> {
                 // Don't push No-ops
                 stmts.push(stmt);
             } else if let StmtKind::Error = stmt.kind {
-                // Skip to safe point
-                self.skip_to_safe_point(|_| false);
+                // Skip to the next statement
+                self.synchronize();
             }
         }
 
@@ -283,8 +287,15 @@ impl<'s> Parser<'s> {
         }
     }
 
-    fn warn_found_as_something_else(&self, found: &str, as_something: &str, at: &Location) {
-        self.reporter.borrow_mut().report_warning(
+    fn warn_found_as_something_else(
+        &self,
+        code: MessageCode,
+        found: &str,
+        as_something: &str,
+        at: &Location,
+    ) {
+        self.reporter.borrow_mut().report_warning_with_code(
+            code,
             at,
             format_args!("'{}' found, assumed it to be '{}'", found, as_something),
         );
@@ -316,8 +327,23 @@ impl<'s> Parser<'s> {
         F: Fn(&TokenType) -> bool,
     {
         loop {
-            match self.current().token_type {
-                TokenType::Var
+            let token_type = &self.current().token_type;
+
+            if Self::is_safe_point(token_type) && !exclude(token_type) {
+                // Not in the exclusion list, continue parsing
+                break;
+            }
+
+            let _ = self.next_token();
+        }
+    }
+
+    /// Checks if `token_type` is a safe point to resume parsing at, which is
+    /// any token that can begin or end a statement
+    fn is_safe_point(token_type: &TokenType) -> bool {
+        matches!(
+            token_type,
+            TokenType::Var
                 | TokenType::Const
                 | TokenType::Type
                 | TokenType::Bind
@@ -362,17 +388,47 @@ impl<'s> Parser<'s> {
                 | TokenType::Pause
                 | TokenType::Quit
                 | TokenType::Semicolon
-                | TokenType::Eof => {
-                    if exclude(&self.current().token_type) {
-                        // In the exclusion list, skip over
-                        self.next_token();
-                    } else {
-                        // Not in the exclusion list, continue parsing
+                | TokenType::Eof
+        )
+    }
+
+    /// Skips all tokens until the start of the next statement is reached.
+    ///
+    /// The start of a statement is either a statement-beginning keyword, an
+    /// identifier at the start of a line, or just after a semicolon. The end
+    /// of an enclosing block is also a stopping point, if there is one. This
+    /// ensures that a chunk of invalid tokens only produces one error, instead
+    /// of an error for every token that does not begin a statement.
+    fn synchronize(&mut self) {
+        loop {
+            match self.current().token_type {
+                TokenType::Semicolon => {
+                    // Statement ends here, nom it
+                    self.next_token();
+                    break;
+                }
+                TokenType::Identifier
+                    if self.current().location.line != self.previous().location.line =>
+                {
+                    // Identifiers at the start of a line likely begin a new statement
+                    break;
+                }
+                TokenType::End
+                | TokenType::EndIf
+                | TokenType::EndFor
+                | TokenType::EndLoop
+                | TokenType::EndCase => {
+                    if !matches!(self.unit_scope.current_block().kind(), BlockKind::Main) {
+                        // Don't skip over the end of the enclosing block
                         break;
                     }
+
+                    // Nothing to end at the top level, so it's also junk
+                    self.next_token();
                 }
+                _ if Self::is_safe_point(&self.current().token_type) => break,
                 _ => {
-                    let _ = self.next_token();
+                    self.next_token();
                 }
             }
         }
@@ -399,7 +455,7 @@ impl<'s> Parser<'s> {
         is_pervasive: bool,
     ) -> IdentId {
         let decl_location = ident.location;
-        let name = ident.get_lexeme().to_string();
+        let name = self.intern_ident(ident);
 
         self.unit_scope
             .declare_ident(name, decl_location, type_spec, ref_kind, is_pervasive)
@@ -409,17 +465,22 @@ impl<'s> Parser<'s> {
     ///
     /// If an identifier is not declared, a new identifier is made.
     fn use_ident(&mut self, ident: &Token) -> IdentId {
-        let name = ident.get_lexeme();
+        let name = self.intern_ident(ident);
         self.unit_scope.use_ident(name, ident.location)
     }
 
     /// Gets the identifier id from the current scope by name.
     ///
     /// Note: The latest identifier declaration is fetched, instead of a specific declaration
-    fn get_ident(&self, name: &str) -> Option<IdentId> {
+    fn get_ident(&self, name: Symbol) -> Option<IdentId> {
         self.unit_scope.get_ident_id(name)
     }
 
+    /// Interns the name of an identifier token
+    fn intern_ident(&self, ident: &Token) -> Symbol {
+        self.context.interner().intern(ident.get_lexeme())
+    }
+
     /// Gets the identifier info for the given id
     fn get_ident_info(&self, id: &IdentId) -> &Identifier {
         self.unit_scope.get_ident_info(id)
@@ -455,13 +516,15 @@ impl<'s> Parser<'s> {
 }
 
 impl toc_core::MessageSource for Parser<'_> {
-    fn take_reported_messages(&mut self) -> Vec<toc_core::ReportMessage> {
-        let mut scanner_messages = self.scanner.take_reported_messages();
-        let mut parser_messages = self.reporter.borrow_mut().take_messages();
-
-        parser_messages.append(&mut scanner_messages);
+    fn take_staged_messages(&mut self) -> Vec<(toc_core::Stage, toc_core::ReportMessage)> {
+        let scanner_messages = self.scanner.take_staged_messages();
+        let parser_messages = self.reporter.borrow_mut().take_messages();
 
         parser_messages
+            .into_iter()
+            .map(|reporting| (toc_core::Stage::Parser, reporting))
+            .chain(scanner_messages)
+            .collect()
     }
 }
 
@@ -517,3 +580,127 @@ fn make_error_expr(error_at: Location) -> Expr {
         span: error_at,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::context::SourceMap;
+    use toc_core::MessageSource;
+
+    fn parse_messages(source: &str) -> Vec<toc_core::ReportMessage> {
+        parse_with_levels(source, &[]).take_reported_messages()
+    }
+
+    fn parse_with_levels<'s>(
+        source: &'s str,
+        levels: &[(MessageCode, toc_core::Level)],
+    ) -> Parser<'s> {
+        let mut context = CompileContext::new(SourceMap::new());
+        for (code, level) in levels {
+            context.set_message_level(*code, *level);
+        }
+
+        let scanner = Scanner::scan_source(source);
+        let mut parser = Parser::new(scanner, true, Arc::new(context));
+        parser.parse();
+
+        parser
+    }
+
+    /// Parses an expression, fully parenthesizing it to show the tree's shape
+    fn parse_expr_shape(source: &str) -> String {
+        fn parenthesize(expr: &Expr) -> String {
+            match &expr.kind {
+                ExprKind::BinaryOp {
+                    left, op, right, ..
+                } => format!("({} {} {})", parenthesize(left), op.0, parenthesize(right)),
+                ExprKind::UnaryOp { op, right, .. } => format!("({}{})", op.0, parenthesize(right)),
+                _ => expr.to_string(),
+            }
+        }
+
+        let context = Arc::new(CompileContext::new(SourceMap::new()));
+        let scanner = Scanner::scan_source(source);
+        let mut parser = Parser::new(scanner, true, context);
+
+        parenthesize(&parser.expr())
+    }
+
+    #[test]
+    fn test_exponent_associativity() {
+        // Exponents are right associative
+        assert_eq!(
+            parse_expr_shape("2 ** 3 ** 2"),
+            "(nat(2) ** (nat(3) ** nat(2)))"
+        );
+        assert_eq!(
+            parse_expr_shape("2 ** 3 ** 2 ** 1"),
+            "(nat(2) ** (nat(3) ** (nat(2) ** nat(1))))"
+        );
+        // Unary minus binds looser than exponents, but `#` binds tighter
+        assert_eq!(parse_expr_shape("-2 ** 2"), "(-(nat(2) ** nat(2)))");
+        assert_eq!(parse_expr_shape("#2 ** 2"), "((#nat(2)) ** nat(2))");
+        // Still binds tighter than other binary operators
+        assert_eq!(
+            parse_expr_shape("2 * 3 ** 2 * 4"),
+            "((nat(2) * (nat(3) ** nat(2))) * nat(4))"
+        );
+        assert_eq!(parse_expr_shape("2 ** -3"), "(nat(2) ** (-nat(3)))");
+    }
+
+    #[test]
+    fn test_equ_as_assign_levels() {
+        use toc_core::Level;
+        let source = "var a : int\na = 1";
+
+        // Warns by default
+        let mut parser = parse_with_levels(source, &[]);
+        let messages = parser.take_reported_messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].code(), Some(MessageCode::EquAsAssign));
+        assert!(!parser.reporter.borrow().has_error());
+
+        // Denying it fails compilation
+        let mut parser = parse_with_levels(source, &[(MessageCode::EquAsAssign, Level::Deny)]);
+        let messages = parser.take_reported_messages();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].to_string().starts_with("error"));
+        assert!(parser.reporter.borrow().has_error());
+
+        // Allowing it silences the message
+        let mut parser = parse_with_levels(source, &[(MessageCode::EquAsAssign, Level::Allow)]);
+        assert!(parser.take_reported_messages().is_empty());
+        assert!(!parser.reporter.borrow().has_error());
+    }
+
+    #[test]
+    fn test_synchronize_after_junk() {
+        // A block of junk should only produce one error, and parsing should resume afterwards
+        let messages = parse_messages(
+            "var a : int\n) end , endif => endloop := end + endfor\nvar b : int := 1 +",
+        );
+        // One for the junk, one for the missing operand
+        assert_eq!(messages.len(), 2, "{:#?}", messages);
+
+        // Resumes at the start of the next statement
+        let messages = parse_messages(") end , endif => endloop := end + endfor; var b : int");
+        assert_eq!(messages.len(), 1, "{:#?}", messages);
+
+        // Resumes at any statement keyword, even in the middle of a line
+        let messages = parse_messages("var a : int\n) , => := if a = 1 then end if");
+        assert_eq!(messages.len(), 1, "{:#?}", messages);
+        let messages = parse_messages(") , => := begin var b : int end");
+        assert_eq!(messages.len(), 1, "{:#?}", messages);
+
+        // Doesn't skip past the end of the enclosing block
+        let messages = parse_messages("begin var a : ) , := end var b : int");
+        assert_eq!(messages.len(), 2, "{:#?}", messages);
+    }
+
+    #[test]
+    fn test_synchronize_after_bad_type_spec() {
+        // Rest of the declaration is skipped over
+        let messages = parse_messages("var a : ) , := end 1 + 2\nvar b : int");
+        assert_eq!(messages.len(), 2, "{:#?}", messages);
+    }
+}
@@ -6,6 +6,7 @@ use toc_ast::ast::stmt::{self, BlockKind, Stmt, StmtKind};
 use toc_ast::ast::types::{Type, TypeKind};
 use toc_ast::types::{Type as TypeInfo, TypeRef};
 use toc_core::token::TokenType;
+use toc_core::MessageCode;
 
 impl<'s> Parser<'s> {
     // --- Decl Parsing --- //
@@ -127,7 +128,14 @@ impl<'s> Parser<'s> {
         // Grab typespec
         let mut type_spec = if self.optional(&TokenType::Colon) {
             // Parse the type spec
-            Some(Box::new(self.parse_type(&decl_tok.token_type)))
+            let type_spec = self.parse_type(&decl_tok.token_type);
+
+            if let TypeKind::Error = &type_spec.kind {
+                // Skip the rest of the declaration, as the type spec is unrecoverable
+                self.synchronize();
+            }
+
+            Some(Box::new(type_spec))
         } else {
             // Will be resolved in the validation stage
             None
@@ -137,7 +145,12 @@ impl<'s> Parser<'s> {
         let assign_expr = if self.is_simple_assignment() {
             if self.current().token_type == TokenType::Equ {
                 // Warn of mistake
-                self.warn_found_as_something_else("=", ":=", &self.current().location);
+                self.warn_found_as_something_else(
+                    MessageCode::EquAsAssign,
+                    "=",
+                    ":=",
+                    &self.current().location,
+                );
             }
 
             // Consume assign
@@ -311,7 +324,7 @@ impl<'s> Parser<'s> {
 
         let (ident, is_new_def) = {
             let is_new_forward_ref = matches!(type_spec.kind, TypeKind::Forward);
-            let name = self.get_token_lexeme(&ident_tok);
+            let name = self.intern_ident(&ident_tok);
             let old_id = self.get_ident(name);
             let mut is_resolved = false;
 
@@ -588,7 +601,7 @@ impl<'s> Parser<'s> {
                     // Current assignment op is '=', not ':='
                     // Warn of mistake, convert into ':='
                     let locate = self.previous().location;
-                    self.warn_found_as_something_else("=", ":=", &locate);
+                    self.warn_found_as_something_else(MessageCode::EquAsAssign, "=", ":=", &locate);
                 }
 
                 None
@@ -694,12 +707,18 @@ impl<'s> Parser<'s> {
 
             // Canonical version is `elsif`, warn about the alternates
             match &self.previous().token_type {
-                TokenType::Elseif => {
-                    self.warn_found_as_something_else("elseif", "elsif", &self.current().location)
-                }
-                TokenType::Elif => {
-                    self.warn_found_as_something_else("elif", "elsif", &self.current().location)
-                }
+                TokenType::Elseif => self.warn_found_as_something_else(
+                    MessageCode::AltKeyword,
+                    "elseif",
+                    "elsif",
+                    &self.current().location,
+                ),
+                TokenType::Elif => self.warn_found_as_something_else(
+                    MessageCode::AltKeyword,
+                    "elif",
+                    "elsif",
+                    &self.current().location,
+                ),
                 _ => {}
             }
         } else {
@@ -821,7 +840,12 @@ impl<'s> Parser<'s> {
     fn nom_endif_or_end_if(&mut self) {
         if self.optional(&TokenType::EndIf) {
             // report warning!
-            self.warn_found_as_something_else("endif", "end if", &self.previous().location);
+            self.warn_found_as_something_else(
+                MessageCode::AltKeyword,
+                "endif",
+                "end if",
+                &self.previous().location,
+            );
         } else {
             // Nom `end`
             let end_tok = self.expects(
@@ -4,6 +4,7 @@ use toc_ast::ast::expr::{Expr, ExprKind, Literal};
 use toc_ast::ast::types::{SeqSize, Type, TypeKind};
 use toc_ast::types::{self, ParamInfo, PrimitiveType, TypeRef};
 use toc_core::token::TokenType;
+use toc_core::MessageCode;
 
 impl<'s> Parser<'s> {
     // --- Type Parsing --- //
@@ -417,7 +418,8 @@ impl<'s> Parser<'s> {
         {
             // In the context of a function type declaration, which requires the '()'
             if matches!(self.previous().token_type, TokenType::Identifier) {
-                self.reporter.borrow_mut().report_warning(
+                self.reporter.borrow_mut().report_warning_with_code(
+                    MessageCode::MissingFcnParens,
                     &self.previous().location,
                     format_args!(
                         "Function type declarations must specifiy '()' after the identifier"
@@ -426,7 +428,8 @@ impl<'s> Parser<'s> {
             } else {
                 // Identifier is not really needed in these situations, though
                 // we still do so for compatibility
-                self.reporter.borrow_mut().report_warning(
+                self.reporter.borrow_mut().report_warning_with_code(
+                    MessageCode::MissingFcnParens,
                     &self.previous().location,
                     format_args!(
                         "Function type declarations must specifiy '()' after '{}'",
@@ -483,7 +486,9 @@ impl<'s> Parser<'s> {
                     TokenType::Identifier,
                     format_args!("Expected identifier for parameter name"),
                 )
-                .map_or_else(|_| String::new(), |ident| ident.get_lexeme().to_string());
+                .ok();
+            let ident = ident.as_ref().map_or("", |ident| ident.get_lexeme());
+            let ident = self.context.interner().intern(ident);
 
             idents.push(ident);
 
@@ -528,7 +533,9 @@ impl<'s> Parser<'s> {
                 TokenType::Identifier,
                 format_args!("Expected identifier for parameter name"),
             )
-            .map_or_else(|_| String::new(), |ident| ident.get_lexeme().to_string());
+            .ok();
+        let name = name.as_ref().map_or("", |ident| ident.get_lexeme());
+        let name = self.context.interner().intern(name);
 
         let type_spec = Box::new(self.type_function(parse_context, has_result));
 
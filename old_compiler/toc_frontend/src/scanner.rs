@@ -7,8 +7,6 @@ use std::char;
 use std::collections::HashMap;
 use std::num::ParseIntError;
 
-use unicode_segmentation::UnicodeSegmentation;
-
 extern crate strtod;
 
 lazy_static! {
@@ -403,8 +401,7 @@ impl<'s> Scanner<'s> {
 
         // Handle column stuff
         let remaining_comment = self.cursor.get_lexeme(self.source);
-        let end_at_column = UnicodeSegmentation::graphemes(remaining_comment, true).count();
-        self.cursor.columns(end_at_column);
+        self.cursor.columns_in(remaining_comment);
     }
 
     /// Skips over a line comment
@@ -453,18 +450,24 @@ impl<'s> Scanner<'s> {
             Ok(num) => self.make_token(TokenType::NatLiteral(num), numerals_len),
             Err(e) => {
                 match e {
-                    IntErrKind::Overflow(_) => self
-                        .reporter
-                        .borrow_mut()
-                        .report_error(&self.cursor, format_args!("Integer literal is too large")),
-                    IntErrKind::InvalidDigit(_) => self.reporter.borrow_mut().report_error(
-                        &self.cursor,
-                        format_args!("Invalid digit found for a base 10 number"),
-                    ),
-                    IntErrKind::Other(e) => self.reporter.borrow_mut().report_error(
-                        &self.cursor,
-                        format_args!("Failed to parse integer literal ({})", e),
-                    ),
+                    IntErrKind::Overflow(_) => {
+                        self.reporter.borrow_mut().report_error(
+                            &self.cursor,
+                            format_args!("Integer literal is too large"),
+                        );
+                    }
+                    IntErrKind::InvalidDigit(_) => {
+                        self.reporter.borrow_mut().report_error(
+                            &self.cursor,
+                            format_args!("Invalid digit found for a base 10 number"),
+                        );
+                    }
+                    IntErrKind::Other(e) => {
+                        self.reporter.borrow_mut().report_error(
+                            &self.cursor,
+                            format_args!("Failed to parse integer literal ({})", e),
+                        );
+                    }
                 }
 
                 // Produce a 0 value token (exact value doesn't matter, as the output will not be compiled)
@@ -529,10 +532,12 @@ impl<'s> Scanner<'s> {
                             format_args!("Invalid digit found in the base specifier"),
                         );
                     } // Notify!
-                    IntErrKind::Other(e) => self.reporter.borrow_mut().report_error(
-                        &self.cursor,
-                        format_args!("Failed to parse base for integer literal ({})", e),
-                    ),
+                    IntErrKind::Other(e) => {
+                        self.reporter.borrow_mut().report_error(
+                            &self.cursor,
+                            format_args!("Failed to parse base for integer literal ({})", e),
+                        );
+                    }
                 }
 
                 None
@@ -580,10 +585,12 @@ impl<'s> Scanner<'s> {
                         format_args!("Digit in integer literal is outside of the specified base's allowed digits"),
                     );
                     }
-                    IntErrKind::Other(e) => self.reporter.borrow_mut().report_error(
-                        &self.cursor,
-                        format_args!("Failed to parse base for integer literal ({})", e),
-                    ),
+                    IntErrKind::Other(e) => {
+                        self.reporter.borrow_mut().report_error(
+                            &self.cursor,
+                            format_args!("Failed to parse base for integer literal ({})", e),
+                        );
+                    }
                 }
                 // Produce a 0 value token (exact value doesn't matter, as the output will not be compiled)
                 self.make_token(TokenType::NatLiteral(0), base_numerals.len())
@@ -681,12 +688,10 @@ impl<'s> Scanner<'s> {
         let ending_delimiter = if is_str_literal { '"' } else { '\'' };
         let literal_text = self.extract_char_sequence(ending_delimiter);
 
-        // Get lexeme (the entire text including the starting delimiter) and lexeme width
+        // Get lexeme (the entire text including the starting delimiter),
+        // and advance column width to the correct location
         let lexeme = self.cursor.get_lexeme(self.source);
-        let lexeme_partial_width = UnicodeSegmentation::graphemes(lexeme, true).count();
-
-        // Advance column width to the correct location
-        self.cursor.columns(lexeme_partial_width);
+        self.cursor.columns_in(lexeme);
 
         // Get width adjustment based on ending delimeter
         let width_adjust = match self.current {
@@ -992,7 +997,7 @@ impl<'s> Scanner<'s> {
 
         // Produce the identifier
         let ident_slice = ident_cursor.get_lexeme(self.source);
-        let len = UnicodeSegmentation::graphemes(ident_slice, true).count();
+        let len = ident_slice.chars().count();
 
         let token_type = KEYWORD_MAP
             .get(&ident_slice)
@@ -1013,8 +1018,13 @@ impl<'s> std::iter::Iterator for Scanner<'s> {
 }
 
 impl toc_core::MessageSource for Scanner<'_> {
-    fn take_reported_messages(&mut self) -> Vec<toc_core::ReportMessage> {
-        self.reporter.borrow_mut().take_messages()
+    fn take_staged_messages(&mut self) -> Vec<(toc_core::Stage, toc_core::ReportMessage)> {
+        let messages = self.reporter.borrow_mut().take_messages();
+
+        messages
+            .into_iter()
+            .map(|reporting| (toc_core::Stage::Scanner, reporting))
+            .collect()
     }
 }
 
@@ -1125,6 +1135,34 @@ mod test {
         assert!(scanner.reporter.borrow().has_error());
     }
 
+    #[test]
+    fn test_multibyte_column() {
+        // Columns are counted in characters, not bytes
+        let source = "var çà := \"é\" % ü\n\tné";
+        let mut scanner = make_scanner(source);
+
+        assert_eq!(scanner.next().unwrap().token_type, TokenType::Var);
+        let tok = scanner.next().unwrap();
+        assert_eq!(tok.location.get_lexeme(source), "çà");
+        assert_eq!(tok.location.column, 5);
+
+        let tok = scanner.next().unwrap();
+        assert_eq!(tok.token_type, TokenType::Assign);
+        assert_eq!(tok.location.column, 8);
+        assert_eq!(tok.location.column_char(source), 8);
+
+        // After a multi-byte string literal
+        assert_eq!(scanner.next().unwrap().location.column, 11);
+
+        // Tabs are wider than a single character
+        let tok = scanner.next().unwrap();
+        assert_eq!(tok.location.get_lexeme(source), "né");
+        assert_eq!(tok.location.line, 2);
+        assert_eq!(tok.location.column, 5);
+        assert_eq!(tok.location.column_char(source), 2);
+        assert!(!scanner.reporter.borrow().has_error());
+    }
+
     #[test]
     fn test_int_literal_basic() {
         // Basic integer literal
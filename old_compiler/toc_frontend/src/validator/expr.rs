@@ -1,6 +1,7 @@
 //! Validator fragment, resolves all expressions
 use super::Validator;
 
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use toc_ast::ast::expr::{BinaryOp, Expr, ExprKind, FieldDef, Literal, UnaryOp};
 use toc_ast::ast::ident::{IdentRef, RefKind};
@@ -167,21 +168,26 @@ impl Validator<'_> {
                 *is_compile_eval = false;
 
                 match op {
-                    BinaryOp::Add =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat), strings, or compatible sets", op), ),
-                    BinaryOp::Sub | BinaryOp::Mul =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat), or compatible sets", op)),
-                    BinaryOp::RealDiv | BinaryOp::Div | BinaryOp::Mod | BinaryOp::Rem | BinaryOp::Exp =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat)", op)),
-                    BinaryOp::And | BinaryOp::Or | BinaryOp::Xor =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat) or booleans", op)),
-                    BinaryOp::Shl | BinaryOp::Shr =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be integers (int, or nat)", op)),
+                    BinaryOp::Add => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat), strings, or compatible sets", op));
+                    }
+                    BinaryOp::Sub | BinaryOp::Mul => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat), or compatible sets", op));
+                    }
+                    BinaryOp::RealDiv | BinaryOp::Div | BinaryOp::Mod | BinaryOp::Rem | BinaryOp::Exp => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat)", op));
+                    }
+                    BinaryOp::And | BinaryOp::Or | BinaryOp::Xor => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat) or booleans", op));
+                    }
+                    BinaryOp::Shl | BinaryOp::Shr => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be integers (int, or nat)", op));
+                    }
                     BinaryOp::Less | BinaryOp::LessEq | BinaryOp::Greater | BinaryOp::GreaterEq => {
                         if types::is_equivalent_to(left_type, right_type, &self.type_table) {
-                            self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat), sets, enumerations, strings, or object classes", op))
+                            self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be scalars (int, real, or nat), sets, enumerations, strings, or object classes", op));
                         } else {
-                            self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must be the same type", op))
+                            self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must be the same type", op));
                         }
                     },
                     BinaryOp::NotEqual | BinaryOp::Equal => {
@@ -198,8 +204,9 @@ impl Validator<'_> {
                             self.reporter.borrow_mut().report_error(&location, format_args!("Right operand of '{}' must be a set type", op));
                         }
                     },
-                    BinaryOp::Imply =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be booleans", op)),
+                    BinaryOp::Imply => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operands of '{}' must both be booleans", op));
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -262,16 +269,21 @@ impl Validator<'_> {
                 *is_compile_eval = false;
 
                 match op {
-                    UnaryOp::Not =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of 'not' must be an integer (int or nat) or a boolean")),
-                    UnaryOp::Identity =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of prefix '+' must be a scalar (int, real, or nat)")),
-                    UnaryOp::Negate =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of unary negation must be a scalar (int, real, or nat)")),
-                    UnaryOp::Deref =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of pointer dereference must be a pointer")),
-                    UnaryOp::NatCheat =>
-                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of nat cheat must be a literal, or a reference to a variable or constant")),
+                    UnaryOp::Not => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of 'not' must be an integer (int or nat) or a boolean"));
+                    }
+                    UnaryOp::Identity => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of prefix '+' must be a scalar (int, real, or nat)"));
+                    }
+                    UnaryOp::Negate => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of unary negation must be a scalar (int, real, or nat)"));
+                    }
+                    UnaryOp::Deref => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of pointer dereference must be a pointer"));
+                    }
+                    UnaryOp::NatCheat => {
+                        self.reporter.borrow_mut().report_error(&location, format_args!("Operand of nat cheat must be a literal, or a reference to a variable or constant"));
+                    }
                 }
             }
         }
@@ -368,13 +380,13 @@ impl Validator<'_> {
                             format_args!(
                                 "Too many arguments for pointer specialization (expected 1, found {})", args.len()
                             ),
-                        )
+                        );
                     } else if args.is_empty() {
                         // Not enough args
                         self.reporter.borrow_mut().report_error(
                             paren_at,
                             format_args!("Pointer specialization requires 1 argument"),
-                        )
+                        );
                     }
 
                     if let Some(expr) = args.first() {
@@ -386,7 +398,7 @@ impl Validator<'_> {
                                 format_args!(
                                     "Expression refers to a type, and is not allowed here"
                                 ),
-                            )
+                            );
                         }
                     }
 
@@ -705,7 +717,7 @@ impl Validator<'_> {
                                 "'{}' is not a field of the enum type '{}'",
                                 field.name,
                                 self.get_reference_ident(left)
-                                    .map_or("<unknown>", |(name, ..)| name.as_str())
+                                    .map_or(Cow::Borrowed("<unknown>"), |(name, ..)| name)
                             ),
                         );
 
@@ -777,7 +789,10 @@ impl Validator<'_> {
             // Only reported once everytime something is not declared
             self.reporter.borrow_mut().report_error(
                 &ident.location,
-                format_args!("'{}' has not been declared yet", info.name),
+                format_args!(
+                    "'{}' has not been declared yet",
+                    self.context.interner().resolve(info.name)
+                ),
             );
         }
 
@@ -23,8 +23,9 @@ use toc_ast::scope::{ScopeBlock, UnitScope};
 use toc_ast::types as ty; // Validator submodule is named `types`, but not used here
 use toc_ast::types::{PrimitiveType, Type, TypeRef, TypeTable};
 use toc_ast::value::{self, Value, ValueApplyError};
-use toc_core::{Location, StatusReporter};
+use toc_core::{Location, MessageCode, StatusReporter};
 
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -34,7 +35,7 @@ use std::sync::Arc;
 /// `'unit` is attatched to the lifetime of the visited code unit
 pub struct Validator<'unit> {
     /// Compile Context
-    _context: Arc<CompileContext>,
+    context: Arc<CompileContext>,
     /// Local status reporter
     reporter: RefCell<StatusReporter>,
     /// Type table to use
@@ -52,8 +53,8 @@ impl<'unit> Validator<'unit> {
         context: Arc<CompileContext>,
     ) -> Self {
         Self {
-            _context: context,
-            reporter: RefCell::new(StatusReporter::new()),
+            reporter: RefCell::new(context.make_reporter()),
+            context,
             type_table,
             unit_scope,
             compile_values: HashMap::new(),
@@ -80,9 +81,13 @@ impl<'unit> Validator<'unit> {
         // Report all undeclared identifiers
         for id in unique_undeclared {
             let ident = self.unit_scope.get_ident_info(&id);
-            self.reporter.borrow_mut().report_warning(
+            self.reporter.borrow_mut().report_warning_with_code(
+                MessageCode::UnusedVar,
                 &ident.location,
-                format_args!("This declaration of '{}' is never used", ident.name),
+                format_args!(
+                    "This declaration of '{}' is never used",
+                    self.context.interner().resolve(ident.name)
+                ),
             );
         }
     }
@@ -101,7 +106,10 @@ impl<'unit> Validator<'unit> {
 
             self.reporter.borrow_mut().report_error(
                 &new_info.location,
-                format_args!("'{}' has already been declared", &new_info.name),
+                format_args!(
+                    "'{}' has already been declared",
+                    self.context.interner().resolve(new_info.name)
+                ),
             );
         }
     }
@@ -118,17 +126,29 @@ impl<'unit> Validator<'unit> {
     fn get_reference_ident<'a, 'b: 'a>(
         &'a self,
         ref_expr: &'b Expr,
-    ) -> Option<(&'a String, &'a TypeRef, &'a RefKind, &'a Location)> {
+    ) -> Option<(Cow<'a, str>, &'a TypeRef, &'a RefKind, &'a Location)> {
         match &ref_expr.kind {
             ExprKind::Parens { inner } => self.get_reference_ident(inner),
             ExprKind::Reference { ident, .. } => {
                 let info = self.unit_scope.get_ident_info(&ident.id);
-                Some((&info.name, &info.type_spec, &info.ref_kind, &ident.location))
+                let name = self.context.interner().resolve(info.name);
+
+                Some((
+                    Cow::Owned(name.to_string()),
+                    &info.type_spec,
+                    &info.ref_kind,
+                    &ident.location,
+                ))
             }
             ExprKind::Dot {
                 field: (field, location),
                 ..
-            } => Some((&field.name, &field.type_spec, &field.ref_kind, &location)),
+            } => Some((
+                Cow::Borrowed(field.name.as_str()),
+                &field.type_spec,
+                &field.ref_kind,
+                &location,
+            )),
             _ => None,
         }
     }
@@ -237,7 +257,8 @@ impl<'unit> Validator<'unit> {
                         }
                         ValueApplyError::DivisionByZero => {
                             // Recoverable
-                            self.reporter.borrow_mut().report_warning(
+                            self.reporter.borrow_mut().report_warning_with_code(
+                                MessageCode::ConstDivByZero,
                                 &location,
                                 format_args!("Compile-time '{}' by zero", op),
                             );
@@ -534,8 +555,13 @@ impl<'u> VisitorMut<(), (), ()> for Validator<'u> {
 }
 
 impl toc_core::MessageSource for Validator<'_> {
-    fn take_reported_messages(&mut self) -> Vec<toc_core::ReportMessage> {
-        self.reporter.borrow_mut().take_messages()
+    fn take_staged_messages(&mut self) -> Vec<(toc_core::Stage, toc_core::ReportMessage)> {
+        let messages = self.reporter.borrow_mut().take_messages();
+
+        messages
+            .into_iter()
+            .map(|reporting| (toc_core::Stage::Validator, reporting))
+            .collect()
     }
 }
 
@@ -561,6 +587,7 @@ mod test {
     use crate::scanner::Scanner;
     use rand::prelude::*;
     use toc_ast::unit::CodeUnit;
+    use toc_core::ReportOptions;
 
     /// Makes and runs a validator
     /// Parsing & scanning must complete successfully
@@ -588,7 +615,11 @@ mod test {
         context.aggregate_messages(&mut validator);
 
         // Emit all pending validator errors & warnings
-        let has_errors = StatusReporter::report_messages(context.messages().iter(), false);
+        let messages = context.all_messages();
+        let has_errors = StatusReporter::report_messages(
+            messages.iter().map(|msg| (None, &msg.message)),
+            &ReportOptions::default(),
+        );
 
         (!has_errors && successful_parse, code_unit)
     }
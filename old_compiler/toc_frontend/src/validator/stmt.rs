@@ -343,7 +343,10 @@ impl Validator<'_> {
                 // Not resolved in the current unit
                 self.reporter.borrow_mut().report_error(
                     &ident.location,
-                    format_args!("'{}' is not resolved in the current unit", info.name),
+                    format_args!(
+                        "'{}' is not resolved in the current unit",
+                        self.context.interner().resolve(info.name)
+                    ),
                 );
             }
         } else {
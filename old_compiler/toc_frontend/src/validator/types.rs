@@ -7,6 +7,7 @@ use toc_ast::ast::types::{SeqSize, Type as TypeNode};
 use toc_ast::ast::VisitorMut;
 use toc_ast::types::{self, ParamInfo, PrimitiveType, SequenceSize, Type, TypeRef, TypeTable};
 use toc_ast::value;
+use toc_core::MessageCode;
 
 impl Validator<'_> {
     // --- Type Resolvers --- //
@@ -330,7 +331,8 @@ impl Validator<'_> {
                     match size_err {
                         RangeSizeError::Overflow => {
                             // Cap the size to usize max
-                            self.reporter.borrow_mut().report_warning(
+                            self.reporter.borrow_mut().report_warning_with_code(
+                                MessageCode::RangeSizeOverflow,
                                 &range_span,
                                 format_args!(
                                     "Range bound size exceeds the maximum representable size"
@@ -460,7 +462,10 @@ impl Validator<'_> {
                 if info.ref_kind != RefKind::Type {
                     self.reporter.borrow_mut().report_error(
                         &ident.location,
-                        format_args!("'{}' does not refer to a type", info.name),
+                        format_args!(
+                            "'{}' does not refer to a type",
+                            self.context.interner().resolve(info.name)
+                        ),
                     );
 
                     // Produce a type error
@@ -128,7 +128,8 @@ impl<'unit> IrVisitor<'unit> {
 
     /// Uses an existsing reference from any reference scope
     fn make_use_ref(&self, ident: &ident::Identifier) -> Reference {
-        self.reference_scope.use_ref(&ident.name)
+        let name = self.unit_scope.interner().resolve(ident.name);
+        self.reference_scope.use_ref(&name)
     }
 
     /// Makes a new temporary reference with the given `type_ref`
@@ -172,7 +173,8 @@ impl ast::Visitor<(), Reference, ()> for IrVisitor<'_> {
                 idents.iter().for_each(|ident| {
                     // TODO: If something is a global var, allocate space for it
                     let info = self.unit_scope.get_ident_info(&ident.id);
-                    let ident_ref = self.make_assign_ref(&info.name, &info.type_spec, alloc_space);
+                    let name = self.unit_scope.interner().resolve(info.name);
+                    let ident_ref = self.make_assign_ref(&name, &info.type_spec, alloc_space);
 
                     // If there is an assignment value, assign it to everyone
                     if let Some(ref value_ref) = value_ref {
@@ -374,8 +376,11 @@ mod test {
         validator.visit_stmt(&mut code_unit.root_stmt);
         context.aggregate_messages(&mut validator);
 
-        let has_errors =
-            toc_core::StatusReporter::report_messages(context.messages().iter(), false);
+        let messages = context.all_messages();
+        let has_errors = toc_core::StatusReporter::report_messages(
+            messages.iter().map(|msg| (None, &msg.message)),
+            &toc_core::ReportOptions::default(),
+        );
 
         assert!(!has_errors, "Validator failed to validate the AST");
 
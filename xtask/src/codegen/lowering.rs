@@ -313,6 +313,7 @@ fn visit_terminal_rule(rule: &Rule, g: &Grammar, children: &mut Vec<Child>) -> O
                 "width",
                 "fraction",
                 "exp_width",
+                "to",
             ];
 
             if manual_impl.contains(&label.as_str()) {